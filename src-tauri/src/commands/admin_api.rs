@@ -0,0 +1,101 @@
+//! @file admin_api.rs
+//! @description Anthropic Admin API 官方用量相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::{AdminApiSettings, OfficialUsage, UsageDriftReport};
+use crate::services::{admin_api, admin_api_poller, keychain};
+
+/// 获取官方用量拉取设置
+#[tauri::command]
+pub async fn get_admin_api_settings(db: State<'_, Repository>) -> Result<AdminApiSettings, String> {
+    tracing::debug!("IPC 调用: get_admin_api_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存官方用量拉取设置
+#[tauri::command]
+pub async fn set_admin_api_settings(
+    db: State<'_, Repository>,
+    settings: AdminApiSettings,
+) -> Result<AdminApiSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_admin_api_settings, enabled={}, interval_minutes={}, lookback_days={}",
+        settings.enabled,
+        settings.interval_minutes,
+        settings.lookback_days
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 保存 Admin API Key 到系统钥匙串，传入空值时删除
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_admin_api_key(api_key: Option<String>) -> Result<(), String> {
+    tracing::debug!("IPC 调用: set_admin_api_key");
+    match api_key.filter(|api_key| !api_key.trim().is_empty()) {
+        Some(api_key) => keychain::store_admin_api_key(api_key.trim()),
+        None => keychain::delete_admin_api_key().map(|_| ()),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// 钥匙串中是否保存了 Admin API Key
+#[tauri::command]
+pub async fn has_admin_api_key() -> Result<bool, String> {
+    tracing::debug!("IPC 调用: has_admin_api_key");
+    keychain::load_admin_api_key()
+        .map(|api_key| api_key.is_some())
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内（UTC 日期）已拉取的官方用量
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_official_usage(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<OfficialUsage>, String> {
+    tracing::debug!(
+        "IPC 调用: get_official_usage, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_official_usage(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取官方用量与本地解析用量的偏差报告
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_usage_drift_report(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<UsageDriftReport, String> {
+    tracing::debug!(
+        "IPC 调用: get_usage_drift_report, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    admin_api::build_drift_report(&db, &start_date, &end_date).map_err(|e| e.to_string())
+}
+
+/// 立即按当前设置拉取一次官方用量
+///
+/// # 返回
+/// 写入的天数
+#[tauri::command]
+pub async fn poll_official_usage_now(
+    app: AppHandle,
+    db: State<'_, Repository>,
+) -> Result<usize, String> {
+    tracing::debug!("IPC 调用: poll_official_usage_now");
+    let settings: AdminApiSettings = db.get_setting().map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || admin_api_poller::run_poll(&app, &settings))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,108 @@
+//! @file alert.rs
+//! @description 告警规则与触发历史相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::DateTime;
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{AlertRule, AlertRuleInput, TriggeredAlert};
+
+/// 触发历史默认返回条数
+const DEFAULT_TRIGGERED_ALERT_LIMIT: usize = 100;
+
+/// 获取全部告警规则
+#[tauri::command]
+pub async fn get_alert_rules(db: State<'_, Repository>) -> Result<Vec<AlertRule>, String> {
+    tracing::debug!("IPC 调用: get_alert_rules");
+    db.get_alert_rules().map_err(|e| e.to_string())
+}
+
+/// 新建告警规则
+#[tauri::command]
+pub async fn create_alert_rule(
+    db: State<'_, Repository>,
+    rule: AlertRuleInput,
+) -> Result<AlertRule, String> {
+    tracing::debug!("IPC 调用: create_alert_rule, rule={:?}", rule);
+    let rule = validate_rule(rule)?;
+    db.create_alert_rule(&rule).map_err(|e| e.to_string())
+}
+
+/// 更新告警规则，规则不存在时返回 None
+#[tauri::command]
+pub async fn update_alert_rule(
+    db: State<'_, Repository>,
+    id: i64,
+    rule: AlertRuleInput,
+) -> Result<Option<AlertRule>, String> {
+    tracing::debug!("IPC 调用: update_alert_rule, id={}, rule={:?}", id, rule);
+    let rule = validate_rule(rule)?;
+    db.update_alert_rule(id, &rule).map_err(|e| e.to_string())
+}
+
+/// 删除告警规则，已触发的历史记录保留
+#[tauri::command]
+pub async fn delete_alert_rule(db: State<'_, Repository>, id: i64) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: delete_alert_rule, id={}", id);
+    db.delete_alert_rule(id).map_err(|e| e.to_string())
+}
+
+/// 获取最近触发的告警（从新到旧），active_only 为 true 时只返回未确认的告警
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_triggered_alerts(
+    db: State<'_, Repository>,
+    limit: Option<usize>,
+    active_only: Option<bool>,
+) -> Result<Vec<TriggeredAlert>, String> {
+    let limit = limit.unwrap_or(DEFAULT_TRIGGERED_ALERT_LIMIT);
+    let active_only = active_only.unwrap_or(false);
+    tracing::debug!(
+        "IPC 调用: get_triggered_alerts, limit={}, active_only={}",
+        limit,
+        active_only
+    );
+    db.get_triggered_alerts(limit, active_only)
+        .map_err(|e| e.to_string())
+}
+
+/// 确认告警，确认后规则持续越线时不再重复通知，告警仍保留在历史中
+#[tauri::command]
+pub async fn acknowledge_alert(
+    db: State<'_, Repository>,
+    id: i64,
+) -> Result<Option<TriggeredAlert>, String> {
+    tracing::debug!("IPC 调用: acknowledge_alert, id={}", id);
+    db.acknowledge_alert(id).map_err(|e| e.to_string())
+}
+
+/// 暂停规则通知直到指定时间（ISO 8601 格式），until 为空时取消暂停
+#[tauri::command]
+pub async fn snooze_rule(
+    db: State<'_, Repository>,
+    id: i64,
+    until: Option<String>,
+) -> Result<Option<AlertRule>, String> {
+    tracing::debug!("IPC 调用: snooze_rule, id={}, until={:?}", id, until);
+    let until = until
+        .map(|until| {
+            DateTime::parse_from_rfc3339(until.trim())
+                .map(|until| until.to_rfc3339())
+                .map_err(|e| format!("暂停截止时间格式无效: {}", e))
+        })
+        .transpose()?;
+    db.snooze_alert_rule(id, until.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// 校验规则参数，名称去除首尾空白
+fn validate_rule(mut rule: AlertRuleInput) -> Result<AlertRuleInput, String> {
+    rule.name = rule.name.trim().to_string();
+    if rule.name.is_empty() {
+        return Err("规则名称不能为空".to_string());
+    }
+    if !rule.threshold.is_finite() || rule.threshold < 0.0 {
+        return Err("阈值必须为非负数".to_string());
+    }
+    Ok(rule)
+}
@@ -0,0 +1,45 @@
+//! @file api_server.rs
+//! @description 本地 REST API 设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::sync::Mutex;
+
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::ApiServerSettings;
+use crate::services::api_server::{generate_token, restart_api_server, ApiServer};
+
+/// 获取本地 REST API 设置
+#[tauri::command]
+pub async fn get_api_server_settings(
+    db: State<'_, Repository>,
+) -> Result<ApiServerSettings, String> {
+    tracing::debug!("IPC 调用: get_api_server_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存本地 REST API 设置并按新设置重启服务
+///
+/// 启用服务且 Token 为空时自动生成 Token
+#[tauri::command]
+pub async fn set_api_server_settings(
+    db: State<'_, Repository>,
+    server: State<'_, Mutex<Option<ApiServer>>>,
+    mut settings: ApiServerSettings,
+) -> Result<ApiServerSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_api_server_settings, enabled={}, port={}, allow_remote={}",
+        settings.enabled,
+        settings.port,
+        settings.allow_remote
+    );
+    if settings.enabled && settings.token.trim().is_empty() {
+        settings.token = generate_token();
+    }
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+
+    let mut server = server.lock().map_err(|e| e.to_string())?;
+    restart_api_server(&mut server, &db, &settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
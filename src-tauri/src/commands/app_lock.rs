@@ -0,0 +1,75 @@
+//! @file app_lock.rs
+//! @description 应用锁相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::Instant;
+
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::AppLockStatus;
+use crate::services::app_lock::{self, AppLock};
+
+/// 获取应用锁状态
+#[tauri::command]
+pub async fn get_app_lock_status(lock: State<'_, AppLock>) -> Result<AppLockStatus, String> {
+    tracing::debug!("IPC 调用: get_app_lock_status");
+    Ok(lock.status(Instant::now()))
+}
+
+/// 修改应用锁设置；已启用时需提供当前口令
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_app_lock_settings(
+    db: State<'_, Repository>,
+    lock: State<'_, AppLock>,
+    enabled: bool,
+    idle_timeout_minutes: u32,
+    passcode: Option<String>,
+    current_passcode: Option<String>,
+) -> Result<AppLockStatus, String> {
+    tracing::debug!(
+        "IPC 调用: set_app_lock_settings, enabled={}, idle_timeout_minutes={}",
+        enabled,
+        idle_timeout_minutes
+    );
+    app_lock::set_app_lock(
+        &db,
+        &lock,
+        enabled,
+        idle_timeout_minutes,
+        passcode.as_deref(),
+        current_passcode.as_deref(),
+        Instant::now(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 用口令解锁
+#[tauri::command]
+pub async fn unlock_app(
+    db: State<'_, Repository>,
+    lock: State<'_, AppLock>,
+    passcode: String,
+) -> Result<AppLockStatus, String> {
+    tracing::debug!("IPC 调用: unlock_app");
+    app_lock::unlock(&db, &lock, &passcode, Instant::now()).map_err(|e| e.to_string())
+}
+
+/// 立即锁定，锁定后通知各窗口
+#[tauri::command]
+pub async fn lock_app(app: AppHandle, lock: State<'_, AppLock>) -> Result<AppLockStatus, String> {
+    tracing::debug!("IPC 调用: lock_app");
+    if lock.lock() {
+        if let Err(e) = app.emit("app-locked", ()) {
+            tracing::error!("发送 app-locked 事件失败: {}", e);
+        }
+    }
+    Ok(lock.status(Instant::now()))
+}
+
+/// 前端上报用户操作（键盘、鼠标），用于计算空闲时间
+#[tauri::command]
+pub async fn report_activity(lock: State<'_, AppLock>) -> Result<(), String> {
+    lock.record_activity(Instant::now());
+    Ok(())
+}
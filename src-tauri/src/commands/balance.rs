@@ -0,0 +1,96 @@
+//! @file balance.rs
+//! @description 中转站余额查询相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::balance::DEFAULT_BALANCE_REFRESH_MINUTES;
+use crate::models::ProviderBalance;
+use crate::services::{keychain, provider_balance};
+
+/// 获取所有已配置余额查询的供应商及最近一次余额
+#[tauri::command]
+pub async fn get_provider_balances(
+    db: State<'_, Repository>,
+) -> Result<Vec<ProviderBalance>, String> {
+    tracing::debug!("IPC 调用: get_provider_balances");
+    db.get_provider_balances().map_err(|e| e.to_string())
+}
+
+/// 获取供应商余额
+///
+/// refresh 为 true 时立即查询一次，否则返回最近一次结果；未配置余额查询时返回 None
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_provider_balance(
+    db: State<'_, Repository>,
+    provider_id: i64,
+    refresh: Option<bool>,
+) -> Result<Option<ProviderBalance>, String> {
+    tracing::debug!(
+        "IPC 调用: get_provider_balance, provider_id={}, refresh={:?}",
+        provider_id,
+        refresh
+    );
+    if !refresh.unwrap_or(false) {
+        return db
+            .get_provider_balance(provider_id)
+            .map_err(|e| e.to_string());
+    }
+
+    let repository = db.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        provider_balance::refresh_provider_balance(&repository, provider_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// 设置供应商余额查询地址与凭证
+///
+/// quota_url 为空时删除配置与钥匙串中的凭证；credential 为 None 时保留已保存的凭证，
+/// 为空字符串时删除凭证
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_provider_quota(
+    db: State<'_, Repository>,
+    provider_id: i64,
+    quota_url: Option<String>,
+    credential: Option<String>,
+    refresh_minutes: Option<u32>,
+) -> Result<Option<ProviderBalance>, String> {
+    tracing::debug!(
+        "IPC 调用: set_provider_quota, provider_id={}, quota_url={:?}, refresh_minutes={:?}",
+        provider_id,
+        quota_url,
+        refresh_minutes
+    );
+    let namespace = db.keychain_namespace().map_err(|e| e.to_string())?;
+    let Some(quota_url) = quota_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+    else {
+        db.delete_provider_quota(provider_id)
+            .map_err(|e| e.to_string())?;
+        keychain::delete_quota_credential(&namespace, provider_id).map_err(|e| e.to_string())?;
+        return Ok(None);
+    };
+    if !quota_url.starts_with("http://") && !quota_url.starts_with("https://") {
+        return Err("余额查询地址必须以 http:// 或 https:// 开头".to_string());
+    }
+
+    match credential.as_deref().map(str::trim) {
+        Some("") => keychain::delete_quota_credential(&namespace, provider_id).map(|_| ()),
+        Some(credential) => keychain::store_quota_credential(&namespace, provider_id, credential),
+        None => Ok(()),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let refresh_minutes = refresh_minutes
+        .unwrap_or(DEFAULT_BALANCE_REFRESH_MINUTES)
+        .max(1);
+    db.set_provider_quota(provider_id, quota_url, refresh_minutes)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,29 @@
+//! @file benchmark.rs
+//! @description 解析器基准测试相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use crate::models::ParserBenchmarkReport;
+
+/// 对 path（JSONL 文件或目录）运行解析器基准测试
+///
+/// 需以 parser-benchmark 特性构建，否则直接返回错误
+#[tauri::command]
+pub async fn benchmark_parser(path: String) -> Result<ParserBenchmarkReport, String> {
+    tracing::debug!("IPC 调用: benchmark_parser, path={}", path);
+
+    #[cfg(feature = "parser-benchmark")]
+    {
+        // 大语料解析耗时较长，放到阻塞线程池执行
+        tauri::async_runtime::spawn_blocking(move || {
+            crate::services::parser_benchmark::benchmark_parser(&std::path::PathBuf::from(path))
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "parser-benchmark"))]
+    {
+        Err("未启用解析器基准测试，需以 parser-benchmark 特性构建".to_string())
+    }
+}
@@ -0,0 +1,61 @@
+//! @file block_warning.rs
+//! @description 5 小时区块用量预警设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::Utc;
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::BlockWarningSettings;
+use crate::services::block_warning;
+
+/// 获取区块用量预警设置
+#[tauri::command]
+pub async fn get_block_warning_settings(
+    db: State<'_, Repository>,
+) -> Result<BlockWarningSettings, String> {
+    tracing::debug!("IPC 调用: get_block_warning_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存区块用量预警设置，预警百分比去重并升序保存
+#[tauri::command]
+pub async fn set_block_warning_settings(
+    db: State<'_, Repository>,
+    mut settings: BlockWarningSettings,
+) -> Result<BlockWarningSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_block_warning_settings, enabled={}, thresholds_percent={:?}",
+        settings.enabled,
+        settings.thresholds_percent
+    );
+    if settings
+        .thresholds_percent
+        .iter()
+        .any(|threshold| *threshold == 0 || *threshold > 100)
+    {
+        return Err("预警百分比必须在 1 到 100 之间".to_string());
+    }
+    if settings
+        .capacity_tokens
+        .is_some_and(|capacity| capacity <= 0)
+    {
+        return Err("区块容量必须大于 0".to_string());
+    }
+    settings.thresholds_percent.sort_unstable();
+    settings.thresholds_percent.dedup();
+
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 获取当前使用的区块容量（Token 总数）
+///
+/// # 返回
+/// (容量, 是否来自历史学习)，未配置且历史区块不足时返回 None
+#[tauri::command]
+pub async fn get_block_capacity(db: State<'_, Repository>) -> Result<Option<(i64, bool)>, String> {
+    tracing::debug!("IPC 调用: get_block_capacity");
+    let settings: BlockWarningSettings = db.get_setting().map_err(|e| e.to_string())?;
+    block_warning::get_block_capacity(&db, &settings, Utc::now()).map_err(|e| e.to_string())
+}
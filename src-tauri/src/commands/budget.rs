@@ -0,0 +1,57 @@
+//! @file budget.rs
+//! @description 每日预算设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::BudgetSettings;
+use crate::services::budget::{budget_progress, BudgetProgress};
+use crate::services::dock_badge::update_dock_badge;
+use crate::services::taskbar::update_taskbar_progress;
+
+/// 获取每日预算设置
+#[tauri::command]
+pub async fn get_budget_settings(db: State<'_, Repository>) -> Result<BudgetSettings, String> {
+    tracing::debug!("IPC 调用: get_budget_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存每日预算设置并立即刷新任务栏进度与 Dock 角标
+#[tauri::command]
+pub async fn set_budget_settings(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    settings: BudgetSettings,
+) -> Result<BudgetSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_budget_settings, daily_budget_usd={:?}, warning_percent={}",
+        settings.daily_budget_usd,
+        settings.warning_percent
+    );
+    if settings
+        .daily_budget_usd
+        .is_some_and(|budget| !budget.is_finite() || budget <= 0.0)
+    {
+        return Err("每日预算必须大于 0".to_string());
+    }
+    if settings.warning_percent == 0 || settings.warning_percent > 100 {
+        return Err("预警百分比必须在 1 到 100 之间".to_string());
+    }
+
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    update_taskbar_progress(&app);
+    update_dock_badge(&app);
+    Ok(settings)
+}
+
+/// 获取今日预算消耗进度，未配置预算时返回 None
+#[tauri::command]
+pub async fn get_budget_progress(
+    db: State<'_, Repository>,
+) -> Result<Option<BudgetProgress>, String> {
+    tracing::debug!("IPC 调用: get_budget_progress");
+    let settings: BudgetSettings = db.get_setting().map_err(|e| e.to_string())?;
+    let today = db.get_today_stats().map_err(|e| e.to_string())?;
+    Ok(budget_progress(&settings, today.cost_usd))
+}
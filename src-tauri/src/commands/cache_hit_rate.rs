@@ -0,0 +1,42 @@
+//! @file cache_hit_rate.rs
+//! @description 缓存命中率计算公式设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::CacheHitRateSettings;
+
+/// 获取缓存命中率计算公式设置
+#[tauri::command]
+pub async fn get_cache_hit_rate_settings(
+    db: State<'_, Repository>,
+) -> Result<CacheHitRateSettings, String> {
+    tracing::debug!("IPC 调用: get_cache_hit_rate_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存缓存命中率计算公式设置，并通知前端按新公式刷新统计
+#[tauri::command]
+pub async fn set_cache_hit_rate_settings(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    settings: CacheHitRateSettings,
+) -> Result<CacheHitRateSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_cache_hit_rate_settings, formula={:?}",
+        settings.formula
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+
+    match db.get_current_stats() {
+        Ok(stats) => {
+            if let Err(e) = app.emit("stats-updated", stats) {
+                tracing::error!("发送 stats-updated 事件失败: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("获取统计数据失败: {}", e),
+    }
+
+    Ok(settings)
+}
@@ -0,0 +1,43 @@
+//! @file csv_import.rs
+//! @description 通用 CSV 历史数据导入相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::{CsvColumnMapping, CsvImportSummary};
+use crate::services::csv_import;
+
+/// 按列映射从 path 导入 CSV 历史数据，完成后通知前端刷新统计
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_csv(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    path: String,
+    column_mapping: CsvColumnMapping,
+    provider_name: Option<String>,
+) -> Result<CsvImportSummary, String> {
+    tracing::debug!("IPC 调用: import_csv, path={}", path);
+    let summary = csv_import::import_csv(
+        &db,
+        &PathBuf::from(path),
+        &column_mapping,
+        provider_name.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    if summary.messages_added > 0 {
+        match db.get_current_stats() {
+            Ok(stats) => {
+                if let Err(e) = app.emit("stats-updated", stats) {
+                    tracing::error!("发送 stats-updated 事件失败: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("获取统计数据失败: {}", e),
+        }
+    }
+
+    Ok(summary)
+}
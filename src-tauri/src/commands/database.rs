@@ -0,0 +1,47 @@
+//! @file database.rs
+//! @description 数据库位置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::{self, location, Repository};
+use crate::models::{DatabaseLocation, DEFAULT_PROFILE_NAME};
+use crate::services::profile::ActiveProfile;
+
+/// 获取当前数据库位置
+#[tauri::command]
+pub async fn get_database_location(
+    app: AppHandle,
+    db: State<'_, Repository>,
+) -> Result<DatabaseLocation, String> {
+    tracing::debug!("IPC 调用: get_database_location");
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(location::database_location(&app_data_dir, &db.path()))
+}
+
+/// 将数据库迁移到新位置（文件路径或目录），复制校验通过后立即切换，无需重启
+#[tauri::command(rename_all = "camelCase")]
+pub async fn move_database(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    profile: State<'_, ActiveProfile>,
+    new_path: String,
+) -> Result<DatabaseLocation, String> {
+    tracing::debug!("IPC 调用: move_database, new_path={}", new_path);
+    if db::is_demo_mode() {
+        return Err("演示模式下无法迁移数据库".to_string());
+    }
+    if profile.name() != DEFAULT_PROFILE_NAME {
+        return Err("只有默认档案支持迁移数据库".to_string());
+    }
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let repository = db.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        location::move_database(&repository, &app_data_dir, &PathBuf::from(new_path))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,29 @@
+//! @file dedupe.rs
+//! @description 消息去重设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::DedupeSettings;
+
+/// 获取消息去重设置
+#[tauri::command]
+pub async fn get_dedupe_settings(db: State<'_, Repository>) -> Result<DedupeSettings, String> {
+    tracing::debug!("IPC 调用: get_dedupe_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存消息去重设置，对之后采集的消息生效
+#[tauri::command]
+pub async fn set_dedupe_settings(
+    db: State<'_, Repository>,
+    settings: DedupeSettings,
+) -> Result<DedupeSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_dedupe_settings, policy={:?}",
+        settings.policy
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
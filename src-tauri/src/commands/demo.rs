@@ -0,0 +1,45 @@
+//! @file demo.rs
+//! @description 演示数据生成相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::{self, Repository};
+use crate::models::{DemoDataSummary, DemoIntensity};
+use crate::services::demo_data;
+
+/// 重新生成演示数据库
+///
+/// 演示数据写入独立的数据库文件，不影响真实数据；
+/// 以环境变量 CLAUDE_TOKEN_MONITOR_DEMO=1 启动应用即可查看
+#[tauri::command]
+pub async fn generate_demo_data(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    days: u32,
+    intensity: Option<DemoIntensity>,
+) -> Result<DemoDataSummary, String> {
+    tracing::debug!(
+        "IPC 调用: generate_demo_data, days={}, intensity={:?}",
+        days,
+        intensity
+    );
+    let path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(db::DEMO_DB_FILE_NAME);
+    if db.path() == path {
+        return Err(
+            "演示模式下无法覆盖正在使用的演示数据库，请以普通模式启动后重新生成".to_string(),
+        );
+    }
+
+    let intensity = intensity.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        demo_data::create_demo_database(&path, days, intensity)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
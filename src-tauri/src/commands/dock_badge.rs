@@ -0,0 +1,35 @@
+//! @file dock_badge.rs
+//! @description macOS Dock 角标设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::DockBadgeSettings;
+use crate::services::dock_badge::update_dock_badge;
+
+/// 获取 Dock 角标设置
+#[tauri::command]
+pub async fn get_dock_badge_settings(
+    db: State<'_, Repository>,
+) -> Result<DockBadgeSettings, String> {
+    tracing::debug!("IPC 调用: get_dock_badge_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存 Dock 角标设置并立即更新角标
+#[tauri::command]
+pub async fn set_dock_badge_settings(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    settings: DockBadgeSettings,
+) -> Result<DockBadgeSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_dock_badge_settings, enabled={}, content={:?}",
+        settings.enabled,
+        settings.content
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    update_dock_badge(&app);
+    Ok(settings)
+}
@@ -0,0 +1,46 @@
+//! @file event_stream.rs
+//! @description 本地 WebSocket 事件流设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::sync::Mutex;
+
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::EventStreamSettings;
+use crate::services::api_server::generate_token;
+use crate::services::event_stream::{restart_event_stream, EventBroadcaster, EventStream};
+
+/// 获取 WebSocket 事件流设置
+#[tauri::command]
+pub async fn get_event_stream_settings(
+    db: State<'_, Repository>,
+) -> Result<EventStreamSettings, String> {
+    tracing::debug!("IPC 调用: get_event_stream_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存 WebSocket 事件流设置并按新设置重启服务
+///
+/// 启用服务且 Token 为空时自动生成 Token
+#[tauri::command]
+pub async fn set_event_stream_settings(
+    db: State<'_, Repository>,
+    broadcaster: State<'_, EventBroadcaster>,
+    stream: State<'_, Mutex<Option<EventStream>>>,
+    mut settings: EventStreamSettings,
+) -> Result<EventStreamSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_event_stream_settings, enabled={}, port={}",
+        settings.enabled,
+        settings.port
+    );
+    if settings.enabled && settings.token.trim().is_empty() {
+        settings.token = generate_token();
+    }
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+
+    let mut stream = stream.lock().map_err(|e| e.to_string())?;
+    restart_event_stream(&mut stream, &broadcaster, &settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
@@ -0,0 +1,66 @@
+//! @file goal.rs
+//! @description 使用目标相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::Local;
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{Goal, GoalInput, GoalMetric, GoalProgress};
+use crate::services::goals;
+
+/// 获取全部目标
+#[tauri::command]
+pub async fn get_goals(db: State<'_, Repository>) -> Result<Vec<Goal>, String> {
+    tracing::debug!("IPC 调用: get_goals");
+    db.get_goals().map_err(|e| e.to_string())
+}
+
+/// 新建目标
+#[tauri::command]
+pub async fn create_goal(db: State<'_, Repository>, goal: GoalInput) -> Result<Goal, String> {
+    tracing::debug!("IPC 调用: create_goal, goal={:?}", goal);
+    let goal = validate_goal(goal)?;
+    db.create_goal(&goal).map_err(|e| e.to_string())
+}
+
+/// 更新目标，目标不存在时返回 None
+#[tauri::command]
+pub async fn update_goal(
+    db: State<'_, Repository>,
+    id: i64,
+    goal: GoalInput,
+) -> Result<Option<Goal>, String> {
+    tracing::debug!("IPC 调用: update_goal, id={}, goal={:?}", id, goal);
+    let goal = validate_goal(goal)?;
+    db.update_goal(id, &goal).map_err(|e| e.to_string())
+}
+
+/// 删除目标及其评估记录
+#[tauri::command]
+pub async fn delete_goal(db: State<'_, Repository>, id: i64) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: delete_goal, id={}", id);
+    db.delete_goal(id).map_err(|e| e.to_string())
+}
+
+/// 获取全部启用目标的当前周期进度与已结束周期的评估历史
+#[tauri::command]
+pub async fn get_goal_progress(db: State<'_, Repository>) -> Result<Vec<GoalProgress>, String> {
+    tracing::debug!("IPC 调用: get_goal_progress");
+    goals::get_goal_progress(&db, Local::now()).map_err(|e| e.to_string())
+}
+
+/// 校验目标参数，名称去除首尾空白
+fn validate_goal(mut goal: GoalInput) -> Result<GoalInput, String> {
+    goal.name = goal.name.trim().to_string();
+    if goal.name.is_empty() {
+        return Err("目标名称不能为空".to_string());
+    }
+    if !goal.target.is_finite() || goal.target < 0.0 {
+        return Err("目标值必须为非负数".to_string());
+    }
+    if goal.metric == GoalMetric::CacheHitRate && goal.target > 100.0 {
+        return Err("缓存命中率目标不能超过 100%".to_string());
+    }
+    Ok(goal)
+}
@@ -0,0 +1,52 @@
+//! @file health.rs
+//! @description 运行状态诊断与监控错误相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::migrations::latest_version;
+use crate::db::Repository;
+use crate::models::{AppInfo, HealthReport, MonitorError, SchemaVersion};
+use crate::services::health::{self, build_health_report, WatcherHealth};
+use crate::services::monitor_errors::MonitorErrorLog;
+
+/// 最近错误默认返回条数
+const DEFAULT_ERROR_LIMIT: usize = 50;
+
+/// 获取运行状态诊断报告（监控状态、数据库信息、最近错误等）
+#[tauri::command]
+pub async fn get_health(
+    db: State<'_, Repository>,
+    watcher: State<'_, WatcherHealth>,
+) -> Result<HealthReport, String> {
+    tracing::debug!("IPC 调用: get_health");
+    build_health_report(&db, &watcher).map_err(|e| e.to_string())
+}
+
+/// 获取应用信息（版本、git 提交、构建时间、Schema 版本与启用的 features），用于问题反馈
+#[tauri::command]
+pub async fn get_app_info(db: State<'_, Repository>) -> Result<AppInfo, String> {
+    tracing::debug!("IPC 调用: get_app_info");
+    health::get_app_info(&db).map_err(|e| e.to_string())
+}
+
+/// 获取数据库 Schema 版本
+#[tauri::command]
+pub async fn get_schema_version(db: State<'_, Repository>) -> Result<SchemaVersion, String> {
+    tracing::debug!("IPC 调用: get_schema_version");
+    Ok(SchemaVersion {
+        current_version: db.schema_version().map_err(|e| e.to_string())?,
+        latest_version: latest_version(),
+    })
+}
+
+/// 获取最近的监控错误（从新到旧）
+#[tauri::command]
+pub async fn get_recent_errors(
+    errors: State<'_, MonitorErrorLog>,
+    limit: Option<usize>,
+) -> Result<Vec<MonitorError>, String> {
+    let limit = limit.unwrap_or(DEFAULT_ERROR_LIMIT);
+    tracing::debug!("IPC 调用: get_recent_errors, limit={}", limit);
+    Ok(errors.recent(limit))
+}
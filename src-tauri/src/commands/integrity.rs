@@ -0,0 +1,65 @@
+//! @file integrity.rs
+//! @description 数据一致性检查与修复相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::{DuplicateAuditReport, IntegrityReport};
+use crate::services::integrity;
+
+/// 检查 daily_stats 与 message_usage 重新汇总结果是否一致
+#[tauri::command]
+pub async fn verify_data_integrity(db: State<'_, Repository>) -> Result<IntegrityReport, String> {
+    tracing::debug!("IPC 调用: verify_data_integrity");
+    integrity::verify_data_integrity(&db).map_err(|e| e.to_string())
+}
+
+/// 根据 message_usage 重建每日汇总，完成后通知前端刷新统计
+#[tauri::command]
+pub async fn rebuild_daily_stats(
+    app: AppHandle,
+    db: State<'_, Repository>,
+) -> Result<IntegrityReport, String> {
+    tracing::debug!("IPC 调用: rebuild_daily_stats");
+    let report = integrity::rebuild_daily_stats(&db).map_err(|e| e.to_string())?;
+
+    match db.get_current_stats() {
+        Ok(stats) => {
+            if let Err(e) = app.emit("stats-updated", stats) {
+                tracing::error!("发送 stats-updated 事件失败: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("获取统计数据失败: {}", e),
+    }
+
+    Ok(report)
+}
+
+/// 审计重复写入的消息，只读取不修改
+#[tauri::command]
+pub async fn audit_duplicates(db: State<'_, Repository>) -> Result<DuplicateAuditReport, String> {
+    tracing::debug!("IPC 调用: audit_duplicates");
+    integrity::audit_duplicates(&db).map_err(|e| e.to_string())
+}
+
+/// 删除重复消息并重建每日汇总，完成后通知前端刷新统计
+#[tauri::command]
+pub async fn cleanup_duplicates(
+    app: AppHandle,
+    db: State<'_, Repository>,
+) -> Result<DuplicateAuditReport, String> {
+    tracing::debug!("IPC 调用: cleanup_duplicates");
+    let report = integrity::cleanup_duplicates(&db).map_err(|e| e.to_string())?;
+
+    match db.get_current_stats() {
+        Ok(stats) => {
+            if let Err(e) = app.emit("stats-updated", stats) {
+                tracing::error!("发送 stats-updated 事件失败: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("获取统计数据失败: {}", e),
+    }
+
+    Ok(report)
+}
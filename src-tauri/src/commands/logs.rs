@@ -0,0 +1,35 @@
+//! @file logs.rs
+//! @description 日志查看相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::AppHandle;
+
+use crate::models::{LogEntry, LogLevel};
+use crate::services::log_viewer::read_recent_logs;
+use crate::services::logging::logs_dir;
+
+/// 默认返回条数
+const DEFAULT_LOG_LIMIT: usize = 200;
+
+/// 单次最多返回条数
+const MAX_LOG_LIMIT: usize = 2000;
+
+/// 获取最近的日志记录（从新到旧）
+///
+/// level 为最低级别，未指定时返回 info 及以上级别
+#[tauri::command]
+pub async fn get_recent_logs(
+    app: AppHandle,
+    level: Option<LogLevel>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let level = level.unwrap_or(LogLevel::Info);
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT);
+    tracing::debug!(
+        "IPC 调用: get_recent_logs, level={:?}, limit={}",
+        level,
+        limit
+    );
+    let dir = logs_dir(&app).map_err(|e| e.to_string())?;
+    read_recent_logs(&dir, level, limit).map_err(|e| e.to_string())
+}
@@ -0,0 +1,55 @@
+//! @file menu_bar.rs
+//! @description 菜单栏模式与托盘图标设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::{MenuBarSettings, TrayIconSettings};
+use crate::services::tray::{apply_menu_bar_mode, refresh_tray_icon};
+
+/// 获取菜单栏模式设置
+#[tauri::command]
+pub async fn get_menu_bar_settings(db: State<'_, Repository>) -> Result<MenuBarSettings, String> {
+    tracing::debug!("IPC 调用: get_menu_bar_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存菜单栏模式设置并立即生效
+#[tauri::command]
+pub async fn set_menu_bar_settings(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    settings: MenuBarSettings,
+) -> Result<MenuBarSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_menu_bar_settings, menu_bar_only={}",
+        settings.menu_bar_only
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    apply_menu_bar_mode(&app, settings.menu_bar_only).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 获取托盘图标设置
+#[tauri::command]
+pub async fn get_tray_icon_settings(db: State<'_, Repository>) -> Result<TrayIconSettings, String> {
+    tracing::debug!("IPC 调用: get_tray_icon_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存托盘图标设置并立即重新绘制
+#[tauri::command]
+pub async fn set_tray_icon_settings(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    settings: TrayIconSettings,
+) -> Result<TrayIconSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_tray_icon_settings, metric={:?}",
+        settings.metric
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    refresh_tray_icon(&app);
+    Ok(settings)
+}
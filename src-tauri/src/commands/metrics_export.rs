@@ -0,0 +1,58 @@
+//! @file metrics_export.rs
+//! @description 行协议与 OTLP 指标推送相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{MetricsExportSettings, OtlpExportSettings};
+
+/// 获取指标推送设置
+#[tauri::command]
+pub async fn get_metrics_export_settings(
+    db: State<'_, Repository>,
+) -> Result<MetricsExportSettings, String> {
+    tracing::debug!("IPC 调用: get_metrics_export_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存指标推送设置，推送线程在下一次检查时生效
+#[tauri::command]
+pub async fn set_metrics_export_settings(
+    db: State<'_, Repository>,
+    settings: MetricsExportSettings,
+) -> Result<MetricsExportSettings, String> {
+    // Authorization 中包含访问凭据，日志只记录非敏感字段
+    tracing::debug!(
+        "IPC 调用: set_metrics_export_settings, enabled={}, interval_seconds={}",
+        settings.enabled,
+        settings.interval_seconds
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 获取 OTLP 指标推送设置
+#[tauri::command]
+pub async fn get_otlp_export_settings(
+    db: State<'_, Repository>,
+) -> Result<OtlpExportSettings, String> {
+    tracing::debug!("IPC 调用: get_otlp_export_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存 OTLP 指标推送设置，推送线程在下一次检查时生效
+#[tauri::command]
+pub async fn set_otlp_export_settings(
+    db: State<'_, Repository>,
+    settings: OtlpExportSettings,
+) -> Result<OtlpExportSettings, String> {
+    // 请求头中可能包含访问凭据，日志只记录非敏感字段
+    tracing::debug!(
+        "IPC 调用: set_otlp_export_settings, enabled={}, interval_seconds={}",
+        settings.enabled,
+        settings.interval_seconds
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
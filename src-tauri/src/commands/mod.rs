@@ -2,5 +2,45 @@
 //! @description Tauri Commands 模块入口
 //! @author Atlas.oi
 //! @date 2026-01-08
+pub mod admin_api;
+pub mod alert;
+pub mod api_server;
+pub mod app_lock;
+pub mod balance;
+pub mod benchmark;
+pub mod block_warning;
+pub mod budget;
+pub mod cache_hit_rate;
+pub mod csv_import;
+pub mod database;
+pub mod dedupe;
+pub mod demo;
+pub mod dock_badge;
+pub mod event_stream;
+pub mod goal;
+pub mod health;
+pub mod integrity;
+pub mod logs;
+pub mod menu_bar;
+pub mod metrics_export;
+pub mod model_alias;
+pub mod note;
+pub mod notification;
+pub mod onboarding;
+pub mod outlier;
+pub mod overlay;
+pub mod permissions;
+pub mod plan;
+pub mod pricing;
+pub mod privacy;
+pub mod profile;
 pub mod provider;
+pub mod report;
+pub mod saved_query;
+pub mod snapshot;
 pub mod stats;
+pub mod sync;
+pub mod tag;
+pub mod trash;
+pub mod updater;
+pub mod webhook;
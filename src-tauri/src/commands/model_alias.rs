@@ -0,0 +1,77 @@
+//! @file model_alias.rs
+//! @description 模型别名映射相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::ModelAlias;
+use crate::services::pricing::{pricing_date, PricingService};
+
+/// 获取全部模型别名
+#[tauri::command]
+pub async fn get_model_aliases(db: State<'_, Repository>) -> Result<Vec<ModelAlias>, String> {
+    tracing::debug!("IPC 调用: get_model_aliases");
+    db.get_model_aliases().map_err(|e| e.to_string())
+}
+
+/// 设置模型别名，已有记录改写为标准模型名后通知前端刷新统计
+#[tauri::command]
+pub async fn set_model_alias(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    alias: String,
+    canonical: String,
+) -> Result<ModelAlias, String> {
+    tracing::debug!(
+        "IPC 调用: set_model_alias, alias={}, canonical={}",
+        alias,
+        canonical
+    );
+    let alias = alias.trim();
+    let canonical = canonical.trim();
+    if alias.is_empty() || canonical.is_empty() {
+        return Err("别名和标准模型名不能为空".to_string());
+    }
+    if alias == canonical {
+        return Err("别名不能与标准模型名相同".to_string());
+    }
+
+    let pricing = PricingService::new();
+    let (saved, renamed) = db
+        .set_model_alias(
+            alias,
+            canonical,
+            |created_at, input, output, cache_read, cache_creation| {
+                pricing.calculate_cost_at(
+                    canonical,
+                    pricing_date(created_at),
+                    input,
+                    output,
+                    cache_read,
+                    cache_creation,
+                )
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    if renamed > 0 {
+        match db.get_current_stats() {
+            Ok(stats) => {
+                if let Err(e) = app.emit("stats-updated", stats) {
+                    tracing::error!("发送 stats-updated 事件失败: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("获取统计数据失败: {}", e),
+        }
+    }
+
+    Ok(saved)
+}
+
+/// 删除模型别名，已改写的历史记录不会恢复
+#[tauri::command]
+pub async fn delete_model_alias(db: State<'_, Repository>, alias: String) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: delete_model_alias, alias={}", alias);
+    db.delete_model_alias(&alias).map_err(|e| e.to_string())
+}
@@ -0,0 +1,48 @@
+//! @file note.rs
+//! @description 日期备注相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::NaiveDate;
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::DateNote;
+
+/// 设置某一天的备注
+///
+/// 备注为空时删除该日期的备注并返回 None
+#[tauri::command]
+pub async fn set_date_note(
+    db: State<'_, Repository>,
+    date: String,
+    note: Option<String>,
+) -> Result<Option<DateNote>, String> {
+    tracing::debug!("IPC 调用: set_date_note, date={}", date);
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    match note
+        .as_deref()
+        .map(str::trim)
+        .filter(|note| !note.is_empty())
+    {
+        Some(note) => db.set_date_note(&date, note).map(Some),
+        None => db.delete_date_note(&date).map(|_| None),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内的备注
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_date_notes(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<DateNote>, String> {
+    tracing::debug!(
+        "IPC 调用: get_date_notes, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_date_notes(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
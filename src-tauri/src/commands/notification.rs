@@ -0,0 +1,27 @@
+//! @file notification.rs
+//! @description 通知历史相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::NotificationRecord;
+
+/// 获取日期范围内（本地日期 YYYY-MM-DD，含首尾）发送过的通知，按时间倒序
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_notification_history(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<NotificationRecord>, String> {
+    tracing::debug!(
+        "IPC 调用: get_notification_history, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    if start_date > end_date {
+        return Err("开始日期不能晚于结束日期".to_string());
+    }
+    db.get_notification_history(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,24 @@
+//! @file onboarding.rs
+//! @description 首次运行引导相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::OnboardingState;
+use crate::services::onboarding;
+
+/// 获取首次运行引导状态，前端据此引导新用户而不是显示空仪表盘
+#[tauri::command]
+pub async fn get_onboarding_state(db: State<'_, Repository>) -> Result<OnboardingState, String> {
+    tracing::debug!("IPC 调用: get_onboarding_state");
+    let home_dir = dirs::home_dir().ok_or_else(|| "无法获取用户主目录".to_string())?;
+    onboarding::get_onboarding_state(&db, &home_dir).map_err(|e| e.to_string())
+}
+
+/// 跳过引导，之后不再显示
+#[tauri::command]
+pub async fn complete_onboarding(db: State<'_, Repository>) -> Result<(), String> {
+    tracing::debug!("IPC 调用: complete_onboarding");
+    onboarding::complete_onboarding(&db).map_err(|e| e.to_string())
+}
@@ -0,0 +1,52 @@
+//! @file outlier.rs
+//! @description 异常大输入消息检测相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{OutlierReport, OutlierSettings};
+use crate::services::outliers;
+
+/// 获取日期范围内新输入 Token 远超中位数的异常消息
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_outliers(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<OutlierReport, String> {
+    tracing::debug!(
+        "IPC 调用: get_outliers, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    if start_date > end_date {
+        return Err("开始日期不能晚于结束日期".to_string());
+    }
+    let settings: OutlierSettings = db.get_setting().map_err(|e| e.to_string())?;
+    outliers::get_outliers(&db, &settings, &start_date, &end_date).map_err(|e| e.to_string())
+}
+
+/// 获取异常输入检测设置
+#[tauri::command]
+pub async fn get_outlier_settings(db: State<'_, Repository>) -> Result<OutlierSettings, String> {
+    tracing::debug!("IPC 调用: get_outlier_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存异常输入检测设置
+#[tauri::command]
+pub async fn set_outlier_settings(
+    db: State<'_, Repository>,
+    settings: OutlierSettings,
+) -> Result<OutlierSettings, String> {
+    tracing::debug!("IPC 调用: set_outlier_settings, settings={:?}", settings);
+    if !settings.multiple.is_finite() || settings.multiple <= 1.0 {
+        return Err("倍数必须大于 1".to_string());
+    }
+    if settings.min_prompt_tokens < 0 {
+        return Err("最小输入 Token 不能为负数".to_string());
+    }
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
@@ -0,0 +1,50 @@
+//! @file overlay.rs
+//! @description 迷你悬浮窗相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::OverlaySettings;
+use crate::services::overlay::{register_overlay_shortcut, toggle_overlay};
+
+/// 获取悬浮窗设置
+#[tauri::command]
+pub async fn get_overlay_settings(db: State<'_, Repository>) -> Result<OverlaySettings, String> {
+    tracing::debug!("IPC 调用: get_overlay_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存悬浮窗设置并重新注册全局快捷键
+///
+/// 快捷键无效或已被占用时恢复原快捷键并返回错误，设置不会被保存
+#[tauri::command]
+pub async fn set_overlay_settings(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    settings: OverlaySettings,
+) -> Result<OverlaySettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_overlay_settings, enabled={}, shortcut={}",
+        settings.enabled,
+        settings.shortcut
+    );
+    let previous: OverlaySettings = db.get_setting().map_err(|e| e.to_string())?;
+
+    if let Err(e) = register_overlay_shortcut(&app, &settings) {
+        if let Err(restore_error) = register_overlay_shortcut(&app, &previous) {
+            tracing::error!("恢复悬浮窗快捷键失败: {}", restore_error);
+        }
+        return Err(format!("快捷键注册失败: {}", e));
+    }
+
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 切换悬浮窗显示状态
+#[tauri::command]
+pub async fn toggle_overlay_window(app: AppHandle) -> Result<(), String> {
+    tracing::debug!("IPC 调用: toggle_overlay_window");
+    toggle_overlay(&app).map_err(|e| e.to_string())
+}
@@ -0,0 +1,36 @@
+//! @file permissions.rs
+//! @description 权限与访问诊断相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::plugin::PermissionState;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::models::{PermissionReport, PermissionStatus};
+use crate::services::permissions;
+
+/// 检查监控目录读取权限、通知权限与 macOS 完全磁盘访问
+///
+/// 每项结果附带系统设置深链接，前端可引导用户直接打开对应设置页
+#[tauri::command]
+pub async fn check_permissions(app: AppHandle) -> Result<PermissionReport, String> {
+    tracing::debug!("IPC 调用: check_permissions");
+    let home_dir = dirs::home_dir().ok_or_else(|| "无法获取用户主目录".to_string())?;
+    let claude_dir = home_dir.join(".claude");
+    let watch_dirs = vec![claude_dir.clone(), claude_dir.join("projects")];
+
+    let mut checks = permissions::check_directories(&watch_dirs, &home_dir);
+
+    let notification_status = match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => PermissionStatus::Granted,
+        Ok(PermissionState::Denied) => PermissionStatus::Denied,
+        Ok(_) => PermissionStatus::NotDetermined,
+        Err(e) => {
+            tracing::warn!("读取通知权限失败: {}", e);
+            PermissionStatus::NotDetermined
+        }
+    };
+    checks.push(permissions::notification_check(notification_status));
+
+    Ok(permissions::build_permission_report(checks))
+}
@@ -0,0 +1,125 @@
+//! @file plan.rs
+//! @description 订阅套餐相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::Local;
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{PlanLimitSettings, PlanLimitStatus, PlanType, PlanValue, ProviderPlan};
+use crate::services::oauth_account::detect_oauth_account;
+use crate::services::plan_limits::get_plan_limit_statuses;
+use crate::services::plan_value::calculate_plan_value;
+use crate::services::pricing::PricingService;
+
+/// 获取所有供应商套餐配置
+#[tauri::command]
+pub async fn get_provider_plans(db: State<'_, Repository>) -> Result<Vec<ProviderPlan>, String> {
+    tracing::debug!("IPC 调用: get_provider_plans");
+    db.get_provider_plans().map_err(|e| e.to_string())
+}
+
+/// 设置供应商套餐（Pro / Max 订阅或按量计费）
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_provider_plan(
+    db: State<'_, Repository>,
+    provider_id: i64,
+    plan_type: PlanType,
+    monthly_fee_usd: f64,
+) -> Result<ProviderPlan, String> {
+    tracing::debug!(
+        "IPC 调用: set_provider_plan, provider_id={}, plan_type={}, monthly_fee_usd={}",
+        provider_id,
+        plan_type.as_str(),
+        monthly_fee_usd
+    );
+    if monthly_fee_usd < 0.0 {
+        return Err("月费不能为负数".to_string());
+    }
+    db.set_provider_plan(provider_id, plan_type, monthly_fee_usd)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取本月订阅价值对比
+///
+/// 未指定 provider_id 时返回所有订阅供应商的对比结果
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_plan_value(
+    db: State<'_, Repository>,
+    provider_id: Option<i64>,
+) -> Result<Vec<PlanValue>, String> {
+    tracing::debug!("IPC 调用: get_plan_value, provider_id={:?}", provider_id);
+    let pricing = PricingService::new();
+    let today = Local::now().date_naive();
+
+    let plans = db.get_provider_plans().map_err(|e| e.to_string())?;
+    plans
+        .iter()
+        .filter(|plan| match provider_id {
+            Some(id) => plan.provider_id == id,
+            None => plan.plan_type.is_subscription(),
+        })
+        .map(|plan| calculate_plan_value(&db, &pricing, plan, today).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// 获取订阅每周额度设置
+#[tauri::command]
+pub async fn get_plan_limit_settings(
+    db: State<'_, Repository>,
+) -> Result<PlanLimitSettings, String> {
+    tracing::debug!("IPC 调用: get_plan_limit_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存订阅每周额度设置
+#[tauri::command]
+pub async fn set_plan_limit_settings(
+    db: State<'_, Repository>,
+    settings: PlanLimitSettings,
+) -> Result<(), String> {
+    tracing::debug!(
+        "IPC 调用: set_plan_limit_settings, reset_weekday={}, reset_hour={}",
+        settings.reset_weekday,
+        settings.reset_hour
+    );
+    if settings.reset_weekday > 6 {
+        return Err("重置星期必须在 0（周一）到 6（周日）之间".to_string());
+    }
+    if settings.reset_hour > 23 {
+        return Err("重置时刻必须在 0 到 23 之间".to_string());
+    }
+    if [
+        settings.pro_weekly_token_limit,
+        settings.max_weekly_token_limit,
+    ]
+    .iter()
+    .flatten()
+    .any(|limit| *limit <= 0)
+    {
+        return Err("每周额度必须大于 0".to_string());
+    }
+    db.set_setting(&settings).map_err(|e| e.to_string())
+}
+
+/// 获取订阅每周额度使用情况与距重置时间
+///
+/// 未配置套餐的订阅账号按 OAuth 登录信息中的订阅类型识别；未指定 provider_id 时返回所有订阅供应商
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_plan_limit_status(
+    db: State<'_, Repository>,
+    provider_id: Option<i64>,
+) -> Result<Vec<PlanLimitStatus>, String> {
+    tracing::debug!(
+        "IPC 调用: get_plan_limit_status, provider_id={:?}",
+        provider_id
+    );
+    let settings: PlanLimitSettings = db.get_setting().map_err(|e| e.to_string())?;
+    let detected_plan = dirs::home_dir()
+        .and_then(|home| detect_oauth_account(&home))
+        .and_then(|account| account.subscription_type)
+        .map(|subscription_type| PlanType::from_db(&subscription_type.to_lowercase()));
+
+    get_plan_limit_statuses(&db, &settings, detected_plan, provider_id, Local::now())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,73 @@
+//! @file pricing.rs
+//! @description 费用重算与审计记录相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::CostRecalculation;
+use crate::services::pricing::{pricing_date, PricingService, PRICING_VERSION};
+
+/// 默认返回的审计记录条数
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// 按当前价格表重算订阅消息的 API 等价费用，费用有变化时记录审计并通知前端刷新统计
+///
+/// # 返回
+/// 有费用变化时返回本次审计记录，否则返回 None
+#[tauri::command]
+pub async fn recalculate_costs(
+    app: AppHandle,
+    db: State<'_, Repository>,
+) -> Result<Option<CostRecalculation>, String> {
+    tracing::debug!("IPC 调用: recalculate_costs");
+    let pricing =
+        PricingService::new().with_aliases(&db.get_model_aliases().map_err(|e| e.to_string())?);
+    let recalculation = db
+        .recalculate_costs(
+            PRICING_VERSION,
+            |model, created_at, input, output, cache_read, cache_creation| {
+                pricing.has_pricing(model).then(|| {
+                    pricing.calculate_cost_at(
+                        model,
+                        pricing_date(created_at),
+                        input,
+                        output,
+                        cache_read,
+                        cache_creation,
+                    )
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    if let Some(recalculation) = &recalculation {
+        tracing::info!(
+            "费用重算完成: {} 条消息，合计 ${:.4} → ${:.4}",
+            recalculation.changed_messages,
+            recalculation.old_total_usd,
+            recalculation.new_total_usd
+        );
+        match db.get_current_stats() {
+            Ok(stats) => {
+                if let Err(e) = app.emit("stats-updated", stats) {
+                    tracing::error!("发送 stats-updated 事件失败: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("获取统计数据失败: {}", e),
+        }
+    }
+
+    Ok(recalculation)
+}
+
+/// 获取费用重算审计记录，按时间倒序
+#[tauri::command]
+pub async fn get_recalculation_history(
+    db: State<'_, Repository>,
+    limit: Option<usize>,
+) -> Result<Vec<CostRecalculation>, String> {
+    tracing::debug!("IPC 调用: get_recalculation_history, limit={:?}", limit);
+    db.get_recalculation_history(limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,35 @@
+//! @file privacy.rs
+//! @description 隐私设置相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::PrivacySettings;
+
+/// 获取隐私设置
+#[tauri::command]
+pub async fn get_privacy_settings(db: State<'_, Repository>) -> Result<PrivacySettings, String> {
+    tracing::debug!("IPC 调用: get_privacy_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存隐私设置，并立即截短已有供应商的 API Key 前缀
+#[tauri::command]
+pub async fn set_privacy_settings(
+    db: State<'_, Repository>,
+    settings: PrivacySettings,
+) -> Result<PrivacySettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_privacy_settings, api_key_prefix_length={}",
+        settings.api_key_prefix_length
+    );
+    let remasked = db
+        .set_privacy_settings(&settings)
+        .map_err(|e| e.to_string())?;
+
+    if remasked > 0 {
+        tracing::info!("已按隐私设置截短 {} 个供应商的 API Key 前缀", remasked);
+    }
+    Ok(settings)
+}
@@ -0,0 +1,84 @@
+//! @file profile.rs
+//! @description 配置档案相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::profile::{profile_infos, ProfilesConfig};
+use crate::models::{Profile, ProfileInfo};
+use crate::services::profile::{self, ActiveProfile};
+use crate::services::read_only::ReadOnlyMode;
+
+/// 获取全部档案
+#[tauri::command]
+pub async fn get_profiles(
+    app: AppHandle,
+    active: State<'_, ActiveProfile>,
+) -> Result<Vec<ProfileInfo>, String> {
+    tracing::debug!("IPC 调用: get_profiles");
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let config = ProfilesConfig::load(&app_data_dir);
+    Ok(profile_infos(&app_data_dir, &config, &active.name()))
+}
+
+/// 新增或更新档案（名称、监控目录与是否只读），修改当前档案的设置需重新切换或重启后生效
+#[tauri::command(rename_all = "camelCase")]
+pub async fn save_profile(
+    app: AppHandle,
+    active: State<'_, ActiveProfile>,
+    name: String,
+    watch_paths: Vec<String>,
+    read_only: Option<bool>,
+) -> Result<Vec<ProfileInfo>, String> {
+    tracing::debug!("IPC 调用: save_profile, name={}", name);
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let mut config = ProfilesConfig::load(&app_data_dir);
+    config
+        .upsert(Profile {
+            name,
+            watch_paths,
+            read_only: read_only.unwrap_or(false),
+        })
+        .map_err(|e| e.to_string())?;
+    config.save(&app_data_dir).map_err(|e| e.to_string())?;
+    Ok(profile_infos(&app_data_dir, &config, &active.name()))
+}
+
+/// 删除档案配置，档案的数据库文件保留
+#[tauri::command]
+pub async fn delete_profile(
+    app: AppHandle,
+    active: State<'_, ActiveProfile>,
+    name: String,
+) -> Result<Vec<ProfileInfo>, String> {
+    tracing::debug!("IPC 调用: delete_profile, name={}", name);
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let mut config = ProfilesConfig::load(&app_data_dir);
+    let active_name = active.name();
+    if name == active_name {
+        return Err(format!("不能删除正在使用的档案: {}", name));
+    }
+    config.remove(&name).map_err(|e| e.to_string())?;
+    config.save(&app_data_dir).map_err(|e| e.to_string())?;
+    Ok(profile_infos(&app_data_dir, &config, &active_name))
+}
+
+/// 切换档案：重新打开数据库并按档案的目录重新监控
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<ProfileInfo, String> {
+    tracing::debug!("IPC 调用: switch_profile, name={}", name);
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        profile::switch_profile(&app, &app_data_dir, &name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// 当前是否为只读查看模式
+#[tauri::command]
+pub async fn get_read_only_mode(mode: State<'_, ReadOnlyMode>) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: get_read_only_mode");
+    Ok(mode.is_enabled())
+}
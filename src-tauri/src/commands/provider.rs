@@ -5,7 +5,8 @@
 use tauri::State;
 
 use crate::db::Repository;
-use crate::models::Provider;
+use crate::models::{DeletedItem, Provider, ProviderTestResult};
+use crate::services::{keychain, provider_test, trash};
 
 /// 获取供应商列表
 #[tauri::command(rename_all = "camelCase")]
@@ -13,7 +14,7 @@ pub async fn get_providers(
     db: State<'_, Repository>,
     active_only: Option<bool>,
 ) -> Result<Vec<Provider>, String> {
-    println!(
+    tracing::debug!(
         "IPC 调用: get_providers, active_only={}",
         active_only.unwrap_or(false)
     );
@@ -22,24 +23,82 @@ pub async fn get_providers(
 }
 
 /// 添加新供应商（手动）
+///
+/// 数据库中只保存 API Key 的哈希与前缀，完整 Key 保存到系统钥匙串供后续连通性检查使用；
+/// 钥匙串不可用时仍完成添加，只记录错误
 #[tauri::command(rename_all = "camelCase")]
 pub async fn add_provider(
     db: State<'_, Repository>,
     api_key: String,
     display_name: Option<String>,
 ) -> Result<Provider, String> {
-    println!("IPC 调用: add_provider, display_name={:?}", display_name);
-    db.create_provider(&api_key, display_name)
+    tracing::debug!("IPC 调用: add_provider, display_name={:?}", display_name);
+    let provider = db
+        .create_provider(&api_key, display_name)
+        .map_err(|e| e.to_string())?;
+
+    let stored = db
+        .keychain_namespace()
         .map_err(|e| e.to_string())
+        .and_then(|namespace| {
+            keychain::store_api_key(&namespace, provider.id, &api_key).map_err(|e| e.to_string())
+        });
+    if let Err(e) = stored {
+        tracing::error!("API Key 写入钥匙串失败 [{}]: {}", provider.id, e);
+    }
+    Ok(provider)
 }
 
-/// 删除供应商
+/// 删除供应商并移入回收站，钥匙串中的凭证保留到回收站条目被清除为止
 #[tauri::command(rename_all = "camelCase")]
-pub async fn delete_provider(db: State<'_, Repository>, provider_id: i64) -> Result<(), String> {
-    println!("IPC 调用: delete_provider, provider_id={}", provider_id);
+pub async fn delete_provider(
+    db: State<'_, Repository>,
+    provider_id: i64,
+) -> Result<Option<DeletedItem>, String> {
+    tracing::debug!("IPC 调用: delete_provider, provider_id={}", provider_id);
     db.delete_provider(provider_id).map_err(|e| e.to_string())
 }
 
+/// 删除供应商，消息与每日汇总归并到"未归属"供应商，同时删除钥匙串中保存的凭证
+///
+/// # 返回
+/// 归并的消息条数；供应商不存在或本身就是未归属供应商时返回 None
+#[tauri::command(rename_all = "camelCase")]
+pub async fn delete_provider_reassigning(
+    db: State<'_, Repository>,
+    provider_id: i64,
+) -> Result<Option<usize>, String> {
+    tracing::debug!(
+        "IPC 调用: delete_provider_reassigning, provider_id={}",
+        provider_id
+    );
+    let reassigned = db
+        .delete_provider_reassigning(provider_id)
+        .map_err(|e| e.to_string())?;
+    if reassigned.is_none() {
+        return Ok(None);
+    }
+
+    trash::delete_provider_credentials(&db, provider_id);
+    Ok(reassigned)
+}
+
+/// 钥匙串中是否保存了供应商的完整 API Key
+#[tauri::command(rename_all = "camelCase")]
+pub async fn has_provider_api_key(
+    db: State<'_, Repository>,
+    provider_id: i64,
+) -> Result<bool, String> {
+    tracing::debug!(
+        "IPC 调用: has_provider_api_key, provider_id={}",
+        provider_id
+    );
+    let namespace = db.keychain_namespace().map_err(|e| e.to_string())?;
+    keychain::load_api_key(&namespace, provider_id)
+        .map(|api_key| api_key.is_some())
+        .map_err(|e| e.to_string())
+}
+
 /// 更新供应商显示名称
 #[tauri::command(rename_all = "camelCase")]
 pub async fn update_provider_name(
@@ -47,10 +106,28 @@ pub async fn update_provider_name(
     provider_id: i64,
     display_name: String,
 ) -> Result<(), String> {
-    println!(
+    tracing::debug!(
         "IPC 调用: update_provider_name, provider_id={}, display_name={}",
-        provider_id, display_name
+        provider_id,
+        display_name
     );
     db.update_provider_display_name(provider_id, &display_name)
         .map_err(|e| e.to_string())
 }
+
+/// 检测供应商连通性，返回状态码与延迟
+#[tauri::command(rename_all = "camelCase")]
+pub async fn test_provider(
+    db: State<'_, Repository>,
+    provider_id: i64,
+) -> Result<ProviderTestResult, String> {
+    tracing::debug!("IPC 调用: test_provider, provider_id={}", provider_id);
+    let repository = db.inner().clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        provider_test::test_provider(&repository, provider_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,141 @@
+//! @file report.rs
+//! @description 使用报告相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::{
+    AllocationGroupBy, CostAllocationExport, ExportFormat, GeneratedReport, ParquetExport,
+    ReportFile, ReportScheduleSettings, SummaryLocale,
+};
+use crate::services::report_scheduler::reports_dir;
+use crate::services::{cost_allocation, parquet_export, report, summary_text};
+
+/// 生成指定日期范围的 HTML 使用报告并写入 path
+#[tauri::command(rename_all = "camelCase")]
+pub async fn generate_report(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+    path: String,
+) -> Result<GeneratedReport, String> {
+    tracing::debug!(
+        "IPC 调用: generate_report, start_date={}, end_date={}, path={}",
+        start_date,
+        end_date,
+        path
+    );
+    if start_date > end_date {
+        return Err("开始日期不能晚于结束日期".to_string());
+    }
+    report::generate_report(&db, &start_date, &end_date, &PathBuf::from(path))
+        .map_err(|e| e.to_string())
+}
+
+/// 生成指定日期的一句话用量摘要
+///
+/// date 默认为今天；locale 为语言标签（如 en、zh-CN），默认中文
+#[tauri::command]
+pub async fn get_daily_summary_text(
+    db: State<'_, Repository>,
+    date: Option<String>,
+    locale: Option<String>,
+) -> Result<String, String> {
+    tracing::debug!(
+        "IPC 调用: get_daily_summary_text, date={:?}, locale={:?}",
+        date,
+        locale
+    );
+    let date = date.unwrap_or_else(|| Local::now().date_naive().to_string());
+    let locale = locale
+        .as_deref()
+        .map(SummaryLocale::from_tag)
+        .unwrap_or_default();
+    summary_text::daily_summary_text(&db, &date, locale).map_err(|e| e.to_string())
+}
+
+/// 按项目或标签导出日期范围内的费用分摊（CSV / JSON）并写入 path
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_cost_allocation(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+    group_by: AllocationGroupBy,
+    format: ExportFormat,
+    path: String,
+) -> Result<CostAllocationExport, String> {
+    tracing::debug!(
+        "IPC 调用: export_cost_allocation, start_date={}, end_date={}, group_by={:?}, format={:?}, path={}",
+        start_date,
+        end_date,
+        group_by,
+        format,
+        path
+    );
+    if start_date > end_date {
+        return Err("开始日期不能晚于结束日期".to_string());
+    }
+    cost_allocation::export_cost_allocation(
+        &db,
+        &start_date,
+        &end_date,
+        group_by,
+        format,
+        &PathBuf::from(path),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 导出日期范围内的消息级用量为 Parquet 文件并写入 path
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_parquet(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+    path: String,
+) -> Result<ParquetExport, String> {
+    tracing::debug!(
+        "IPC 调用: export_parquet, start_date={}, end_date={}, path={}",
+        start_date,
+        end_date,
+        path
+    );
+    if start_date > end_date {
+        return Err("开始日期不能晚于结束日期".to_string());
+    }
+    parquet_export::export_parquet(&db, &start_date, &end_date, &PathBuf::from(path))
+        .map_err(|e| e.to_string())
+}
+
+/// 获取定时报告设置
+#[tauri::command]
+pub async fn get_report_schedule(
+    db: State<'_, Repository>,
+) -> Result<ReportScheduleSettings, String> {
+    tracing::debug!("IPC 调用: get_report_schedule");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存定时报告设置
+#[tauri::command]
+pub async fn set_report_schedule(
+    db: State<'_, Repository>,
+    settings: ReportScheduleSettings,
+) -> Result<ReportScheduleSettings, String> {
+    tracing::debug!("IPC 调用: set_report_schedule, settings={:?}", settings);
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 列出报告目录中已生成的定时报告
+#[tauri::command]
+pub async fn list_reports(app: AppHandle) -> Result<Vec<ReportFile>, String> {
+    tracing::debug!("IPC 调用: list_reports");
+    let dir = reports_dir(&app).map_err(|e| e.to_string())?;
+    report::list_reports(&dir).map_err(|e| e.to_string())
+}
@@ -0,0 +1,86 @@
+//! @file saved_query.rs
+//! @description 自定义查询与仪表板卡片相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{QueryResult, QueryVisualization, SavedQuery};
+
+/// 获取全部自定义查询
+#[tauri::command]
+pub async fn get_saved_queries(db: State<'_, Repository>) -> Result<Vec<SavedQuery>, String> {
+    tracing::debug!("IPC 调用: get_saved_queries");
+    db.get_saved_queries().map_err(|e| e.to_string())
+}
+
+/// 保存新的自定义查询
+#[tauri::command]
+pub async fn create_saved_query(
+    db: State<'_, Repository>,
+    name: String,
+    sql: String,
+    visualization: Option<QueryVisualization>,
+) -> Result<SavedQuery, String> {
+    tracing::debug!("IPC 调用: create_saved_query, name={}", name);
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("查询名称不能为空".to_string());
+    }
+    db.create_saved_query(name, &sql, visualization.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// 更新自定义查询
+#[tauri::command(rename_all = "camelCase")]
+pub async fn update_saved_query(
+    db: State<'_, Repository>,
+    query_id: i64,
+    name: String,
+    sql: String,
+    visualization: Option<QueryVisualization>,
+) -> Result<SavedQuery, String> {
+    tracing::debug!(
+        "IPC 调用: update_saved_query, query_id={}, name={}",
+        query_id,
+        name
+    );
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("查询名称不能为空".to_string());
+    }
+    db.update_saved_query(query_id, name, &sql, visualization.unwrap_or_default())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("自定义查询不存在: {}", query_id))
+}
+
+/// 删除自定义查询
+#[tauri::command(rename_all = "camelCase")]
+pub async fn delete_saved_query(db: State<'_, Repository>, query_id: i64) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: delete_saved_query, query_id={}", query_id);
+    db.delete_saved_query(query_id).map_err(|e| e.to_string())
+}
+
+/// 通过只读查询路径执行已保存的查询，供自定义卡片渲染
+#[tauri::command(rename_all = "camelCase")]
+pub async fn run_saved_query(
+    db: State<'_, Repository>,
+    query_id: i64,
+) -> Result<QueryResult, String> {
+    tracing::debug!("IPC 调用: run_saved_query, query_id={}", query_id);
+    let query = db
+        .get_saved_query(query_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("自定义查询不存在: {}", query_id))?;
+    db.run_readonly_query(&query.sql).map_err(|e| e.to_string())
+}
+
+/// 执行未保存的只读查询，用于保存前预览结果
+#[tauri::command]
+pub async fn run_readonly_query(
+    db: State<'_, Repository>,
+    sql: String,
+) -> Result<QueryResult, String> {
+    tracing::debug!("IPC 调用: run_readonly_query");
+    db.run_readonly_query(&sql).map_err(|e| e.to_string())
+}
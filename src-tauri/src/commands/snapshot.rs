@@ -0,0 +1,44 @@
+//! @file snapshot.rs
+//! @description 数据快照导出 / 导入相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::{SnapshotExport, SnapshotImportSummary};
+use crate::services::snapshot;
+
+/// 导出数据快照到 path
+#[tauri::command]
+pub async fn export_snapshot(
+    db: State<'_, Repository>,
+    path: String,
+) -> Result<SnapshotExport, String> {
+    tracing::debug!("IPC 调用: export_snapshot, path={}", path);
+    snapshot::export_snapshot(&db, &PathBuf::from(path)).map_err(|e| e.to_string())
+}
+
+/// 从 path 导入数据快照并与本地数据合并，完成后通知前端刷新统计
+#[tauri::command]
+pub async fn import_snapshot(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    path: String,
+) -> Result<SnapshotImportSummary, String> {
+    tracing::debug!("IPC 调用: import_snapshot, path={}", path);
+    let summary =
+        snapshot::import_snapshot(&db, &PathBuf::from(path)).map_err(|e| e.to_string())?;
+
+    match db.get_current_stats() {
+        Ok(stats) => {
+            if let Err(e) = app.emit("stats-updated", stats) {
+                tracing::error!("发送 stats-updated 事件失败: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("获取统计数据失败: {}", e),
+    }
+
+    Ok(summary)
+}
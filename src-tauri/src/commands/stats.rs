@@ -2,16 +2,47 @@
 //! @description 统计相关 Tauri Commands
 //! @author Atlas.oi
 //! @date 2026-01-08
+use std::collections::BTreeMap;
+
+use chrono::{Local, NaiveDate, Utc};
 use tauri::State;
 
 use crate::db::Repository;
-use crate::models::{DailyActivity, ProviderStats, StatsCache, TodayStats};
+use crate::models::trend::build_model_trends;
+use crate::models::{
+    ApiErrorStats, BurnRate, CompactionStats, CostAnomaly, CostSimulation, DailyActivity, Insight,
+    InterruptStats, LatencyStats, LiveRate, MessageDistribution, MessageSearchFilters,
+    MessageSearchPage, ModelTrend, ProviderComparison, ProviderStats, QuickStats, RateLimitStats,
+    RollingAveragePoint, ServiceTierUsage, SessionContextUsage, SessionDetail, SessionDistribution,
+    SessionOrder, SessionSummary, StatsCache, StatsFilters, TodayStats, ToolCallStats, UsageBlock,
+    UsageHeatmap, UsageStreaks, YearSummary, DEFAULT_TOP_SESSIONS_LIMIT,
+};
+use crate::services::active_sessions::{self, DEFAULT_ACTIVE_MINUTES};
+use crate::services::burn_rate::{burn_rate_start_date, calculate_burn_rate};
+use crate::services::context_usage;
+use crate::services::cost_simulation;
+use crate::services::distribution::{
+    calculate_message_distribution, calculate_session_distribution,
+};
+use crate::services::insights;
+use crate::services::latency::calculate_latency_stats;
+use crate::services::live_stats::LiveStats;
+use crate::services::pricing::PricingService;
+use crate::services::session_detail;
+use crate::services::streaks::calculate_streaks;
+use crate::services::usage_block::{get_cached_current_block, get_current_block};
+use crate::services::year_summary::build_year_summary;
 
-/// 获取当前统计数据
+/// 获取当前统计数据，可按供应商 / 模型 / 项目过滤
 #[tauri::command]
-pub async fn get_current_stats(db: State<'_, Repository>) -> Result<StatsCache, String> {
-    println!("IPC 调用: get_current_stats");
-    db.get_current_stats().map_err(|e| e.to_string())
+pub async fn get_current_stats(
+    db: State<'_, Repository>,
+    filters: Option<StatsFilters>,
+) -> Result<StatsCache, String> {
+    let filters = filters.unwrap_or_default();
+    tracing::debug!("IPC 调用: get_current_stats, filters={:?}", filters);
+    db.get_current_stats_filtered(&filters)
+        .map_err(|e| e.to_string())
 }
 
 /// 获取今日各供应商统计
@@ -19,28 +50,473 @@ pub async fn get_current_stats(db: State<'_, Repository>) -> Result<StatsCache,
 pub async fn get_today_provider_stats(
     db: State<'_, Repository>,
 ) -> Result<Vec<ProviderStats>, String> {
-    println!("IPC 调用: get_today_provider_stats");
+    tracing::debug!("IPC 调用: get_today_provider_stats");
     db.get_today_provider_stats().map_err(|e| e.to_string())
 }
 
-/// 获取今日汇总统计
+/// 获取今日汇总统计，可按供应商 / 模型 / 项目过滤
 #[tauri::command]
-pub async fn get_today_stats(db: State<'_, Repository>) -> Result<TodayStats, String> {
-    println!("IPC 调用: get_today_stats");
-    db.get_today_stats().map_err(|e| e.to_string())
+pub async fn get_today_stats(
+    db: State<'_, Repository>,
+    filters: Option<StatsFilters>,
+) -> Result<TodayStats, String> {
+    let filters = filters.unwrap_or_default();
+    tracing::debug!("IPC 调用: get_today_stats, filters={:?}", filters);
+    db.get_today_stats_filtered(&filters)
+        .map_err(|e| e.to_string())
 }
 
-/// 获取每日活动记录
+/// 获取每日活动记录，可按供应商 / 模型 / 项目过滤
 #[tauri::command(rename_all = "camelCase")]
 pub async fn get_daily_activities(
     db: State<'_, Repository>,
     start_date: String,
     end_date: String,
+    filters: Option<StatsFilters>,
 ) -> Result<Vec<DailyActivity>, String> {
-    println!(
-        "IPC 调用: get_daily_activities, start_date={}, end_date={}",
-        start_date, end_date
+    let filters = filters.unwrap_or_default();
+    tracing::debug!(
+        "IPC 调用: get_daily_activities, start_date={}, end_date={}, filters={:?}",
+        start_date,
+        end_date,
+        filters
+    );
+    db.get_daily_activities_filtered(&start_date, &end_date, &filters)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取任意日期范围内的汇总统计，用于"最近 7 天"、"本计费周期"等卡片
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_range_stats(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+    filters: Option<StatsFilters>,
+) -> Result<StatsCache, String> {
+    let filters = filters.unwrap_or_default();
+    tracing::debug!(
+        "IPC 调用: get_range_stats, start_date={}, end_date={}, filters={:?}",
+        start_date,
+        end_date,
+        filters
+    );
+    db.get_range_stats(&start_date, &end_date, &filters)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取指定年份的年度回顾汇总
+#[tauri::command]
+pub async fn get_year_summary(db: State<'_, Repository>, year: i32) -> Result<YearSummary, String> {
+    tracing::debug!("IPC 调用: get_year_summary, year={}", year);
+    build_year_summary(&db, &PricingService::new(), year).map_err(|e| e.to_string())
+}
+
+/// 获取消耗速率与月末预测
+#[tauri::command]
+pub async fn get_burn_rate(db: State<'_, Repository>) -> Result<BurnRate, String> {
+    tracing::debug!("IPC 调用: get_burn_rate");
+    let now = Local::now().naive_local();
+    let today = now.date();
+    let start_date = burn_rate_start_date(today).to_string();
+
+    let activities = db
+        .get_daily_activities(&start_date, &today.to_string())
+        .map_err(|e| e.to_string())?;
+    Ok(calculate_burn_rate(&activities, now))
+}
+
+/// 获取指定日期范围内检测到的费用异常
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_cost_anomalies(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<CostAnomaly>, String> {
+    tracing::debug!(
+        "IPC 调用: get_cost_anomalies, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_cost_anomalies(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取连续活跃天数与累计 Token 里程碑
+#[tauri::command]
+pub async fn get_streaks(db: State<'_, Repository>) -> Result<UsageStreaks, String> {
+    tracing::debug!("IPC 调用: get_streaks");
+    let today = Local::now().date_naive();
+
+    let activities = match db.get_first_activity_date().map_err(|e| e.to_string())? {
+        Some(first_date) => db
+            .get_daily_activities(&first_date, &today.to_string())
+            .map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+    Ok(calculate_streaks(&activities, today))
+}
+
+/// 获取星期 × 小时使用热力图
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_usage_heatmap(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<UsageHeatmap, String> {
+    tracing::debug!(
+        "IPC 调用: get_usage_heatmap, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    let cells = db
+        .get_usage_heatmap(&start_date, &end_date)
+        .map_err(|e| e.to_string())?;
+    Ok(UsageHeatmap::from_cells(start_date, end_date, cells))
+}
+
+/// 获取日期范围内各模型的每日趋势
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_model_trends(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<ModelTrend>, String> {
+    tracing::debug!(
+        "IPC 调用: get_model_trends, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    let rows = db
+        .get_model_daily_usage(&start_date, &end_date)
+        .map_err(|e| e.to_string())?;
+    Ok(build_model_trends(start, end, rows))
+}
+
+/// 获取日期范围内每日费用 / Token 的滚动平均序列，用于平滑趋势图
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_rolling_average(
+    db: State<'_, Repository>,
+    window_days: u32,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<RollingAveragePoint>, String> {
+    tracing::debug!(
+        "IPC 调用: get_rolling_average, window_days={}, start_date={}, end_date={}",
+        window_days,
+        start_date,
+        end_date
+    );
+    db.get_rolling_average(window_days, &start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内每条消息输入 / 输出 Token 数的直方图与分位数
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_message_distribution(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<MessageDistribution, String> {
+    tracing::debug!(
+        "IPC 调用: get_message_distribution, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    let samples = db
+        .get_message_token_samples(&start_date, &end_date)
+        .map_err(|e| e.to_string())?;
+    Ok(calculate_message_distribution(&samples))
+}
+
+/// 获取日期范围内会话时长、消息数与费用的分布
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_session_distribution(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<SessionDistribution, String> {
+    tracing::debug!(
+        "IPC 调用: get_session_distribution, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    let samples = db
+        .get_session_samples(&start_date, &end_date)
+        .map_err(|e| e.to_string())?;
+    Ok(calculate_session_distribution(&samples))
+}
+
+/// 获取日期范围内费用或 Token 最高的会话排行
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_top_sessions(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+    limit: Option<i64>,
+    order_by: Option<SessionOrder>,
+) -> Result<Vec<SessionSummary>, String> {
+    let limit = limit.unwrap_or(DEFAULT_TOP_SESSIONS_LIMIT);
+    let order_by = order_by.unwrap_or_default();
+    tracing::debug!(
+        "IPC 调用: get_top_sessions, start_date={}, end_date={}, limit={}, order_by={:?}",
+        start_date,
+        end_date,
+        limit,
+        order_by
     );
-    db.get_daily_activities(&start_date, &end_date)
+    db.get_top_sessions(&start_date, &end_date, limit, order_by)
         .map_err(|e| e.to_string())
 }
+
+/// 获取最近 minutes 分钟内有消息的活跃会话（默认 5 分钟）
+#[tauri::command]
+pub async fn get_active_sessions(
+    db: State<'_, Repository>,
+    minutes: Option<i64>,
+) -> Result<Vec<SessionSummary>, String> {
+    let minutes = minutes.unwrap_or(DEFAULT_ACTIVE_MINUTES);
+    tracing::debug!("IPC 调用: get_active_sessions, minutes={}", minutes);
+    active_sessions::get_active_sessions(&db, Utc::now(), minutes).map_err(|e| e.to_string())
+}
+
+/// 获取会话当前的上下文窗口占用，会话不存在时返回 None
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_session_context_usage(
+    db: State<'_, Repository>,
+    session_id: String,
+) -> Result<Option<SessionContextUsage>, String> {
+    tracing::debug!(
+        "IPC 调用: get_session_context_usage, session_id={}",
+        session_id
+    );
+    context_usage::get_session_context_usage(&db, &PricingService::new(), &session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取会话明细：逐条消息及其缓存经济性（有效输入单价、缓存写入溢价与净节省），会话不存在时返回 None
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_session_detail(
+    db: State<'_, Repository>,
+    session_id: String,
+) -> Result<Option<SessionDetail>, String> {
+    tracing::debug!("IPC 调用: get_session_detail, session_id={}", session_id);
+    let pricing =
+        PricingService::new().with_aliases(&db.get_model_aliases().map_err(|e| e.to_string())?);
+    session_detail::get_session_detail(&db, &pricing, &session_id).map_err(|e| e.to_string())
+}
+
+/// 模拟日期范围内的用量换用其他模型后的费用差异
+///
+/// modelMapping 为 实际模型（名称或前缀）→ 目标模型，键 `*` 匹配所有模型
+#[tauri::command(rename_all = "camelCase")]
+pub async fn simulate_cost(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+    model_mapping: BTreeMap<String, String>,
+) -> Result<CostSimulation, String> {
+    tracing::debug!(
+        "IPC 调用: simulate_cost, start_date={}, end_date={}, model_mapping={:?}",
+        start_date,
+        end_date,
+        model_mapping
+    );
+    if start_date > end_date {
+        return Err("开始日期不能晚于结束日期".to_string());
+    }
+    let pricing =
+        PricingService::new().with_aliases(&db.get_model_aliases().map_err(|e| e.to_string())?);
+    cost_simulation::simulate_cost(&db, &pricing, &start_date, &end_date, &model_mapping)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内的用量洞察（如可换用更便宜模型的短输出消息）
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_insights(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<Insight>, String> {
+    tracing::debug!(
+        "IPC 调用: get_insights, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    let pricing =
+        PricingService::new().with_aliases(&db.get_model_aliases().map_err(|e| e.to_string())?);
+    insights::get_insights(&db, &pricing, &start_date, &end_date).map_err(|e| e.to_string())
+}
+
+/// 按过滤条件分页搜索消息记录
+#[tauri::command]
+pub async fn search_messages(
+    db: State<'_, Repository>,
+    filters: MessageSearchFilters,
+) -> Result<MessageSearchPage, String> {
+    tracing::debug!("IPC 调用: search_messages, filters={:?}", filters);
+    db.search_messages(&filters).map_err(|e| e.to_string())
+}
+
+/// 获取小组件轮询用的精简统计（今日费用与 Token、区块剩余时间、活跃供应商）
+#[tauri::command]
+pub async fn get_quick_stats(db: State<'_, Repository>) -> Result<QuickStats, String> {
+    tracing::debug!("IPC 调用: get_quick_stats");
+    let today = Local::now().date_naive().to_string();
+    let mut stats = db.get_quick_stats(&today).map_err(|e| e.to_string())?;
+    stats.block_remaining_minutes = get_cached_current_block(&db, Utc::now())
+        .map_err(|e| e.to_string())?
+        .map(|block| block.remaining_minutes);
+    Ok(stats)
+}
+
+/// 获取当前 5 小时使用区块，没有活跃区块时返回 None
+#[tauri::command]
+pub async fn get_usage_block(db: State<'_, Repository>) -> Result<Option<UsageBlock>, String> {
+    tracing::debug!("IPC 调用: get_usage_block");
+    get_current_block(&db, Utc::now()).map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内各供应商的限流与过载次数
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_rate_limit_stats(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<RateLimitStats>, String> {
+    tracing::debug!(
+        "IPC 调用: get_rate_limit_stats, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_rate_limit_stats(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内各供应商的 API 错误次数与错误率
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_api_error_stats(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<ApiErrorStats>, String> {
+    tracing::debug!(
+        "IPC 调用: get_api_error_stats, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_api_error_stats(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内的上下文压缩次数与各会话压缩情况
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_compaction_stats(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<CompactionStats, String> {
+    tracing::debug!(
+        "IPC 调用: get_compaction_stats, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_compaction_stats(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内按服务层级（标准 / Priority / Batch）汇总的用量与费用
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_service_tier_usage(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<ServiceTierUsage>, String> {
+    tracing::debug!(
+        "IPC 调用: get_service_tier_usage, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_service_tier_usage(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内用户中断丢弃的 Token 与费用，按日期与会话汇总
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_interrupt_stats(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<InterruptStats, String> {
+    tracing::debug!(
+        "IPC 调用: get_interrupt_stats, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_interrupt_stats(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取日期范围内各工具（Bash、Edit、Read 等）的调用次数
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_tool_call_stats(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<ToolCallStats>, String> {
+    tracing::debug!(
+        "IPC 调用: get_tool_call_stats, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    db.get_tool_call_stats(&start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 按日期对比所选供应商的费用、Token、缓存命中率、耗时与错误率
+#[tauri::command(rename_all = "camelCase")]
+pub async fn compare_providers(
+    db: State<'_, Repository>,
+    provider_ids: Vec<i64>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<ProviderComparison>, String> {
+    tracing::debug!(
+        "IPC 调用: compare_providers, provider_ids={:?}, start_date={}, end_date={}",
+        provider_ids,
+        start_date,
+        end_date
+    );
+    NaiveDate::parse_from_str(&start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    NaiveDate::parse_from_str(&end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    db.compare_providers(&provider_ids, &start_date, &end_date)
+        .map_err(|e| e.to_string())
+}
+
+/// 获取最近一段时间的实时 Token 速率
+#[tauri::command]
+pub async fn get_live_rate(live: State<'_, LiveStats>) -> Result<LiveRate, String> {
+    tracing::debug!("IPC 调用: get_live_rate");
+    Ok(live.rate(Utc::now()))
+}
+
+/// 获取日期范围内各供应商、模型的请求耗时统计（平均值与 P95）
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_latency_stats(
+    db: State<'_, Repository>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<LatencyStats>, String> {
+    tracing::debug!(
+        "IPC 调用: get_latency_stats, start_date={}, end_date={}",
+        start_date,
+        end_date
+    );
+    let samples = db
+        .get_latency_samples(&start_date, &end_date)
+        .map_err(|e| e.to_string())?;
+    Ok(calculate_latency_stats(&samples))
+}
@@ -0,0 +1,68 @@
+//! @file sync.rs
+//! @description 云同步相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::{SyncResult, SyncSecret, SyncSecretStatus, SyncSettings};
+use crate::services::{cloud_sync, sync_scheduler};
+
+/// 获取云同步设置，不含密码、访问密钥与加密口令
+#[tauri::command]
+pub async fn get_sync_settings(db: State<'_, Repository>) -> Result<SyncSettings, String> {
+    tracing::debug!("IPC 调用: get_sync_settings");
+    cloud_sync::migrate_legacy_secrets(&db).map_err(|e| e.to_string())?;
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存云同步设置，密钥通过 set_sync_secret 单独保存到钥匙串
+#[tauri::command]
+pub async fn set_sync_settings(
+    db: State<'_, Repository>,
+    settings: SyncSettings,
+) -> Result<SyncSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_sync_settings, enabled={}, backend={:?}, interval_minutes={}",
+        settings.enabled,
+        settings.backend,
+        settings.interval_minutes
+    );
+    cloud_sync::migrate_legacy_secrets(&db).map_err(|e| e.to_string())?;
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 保存云同步密钥到钥匙串，传入空值时删除
+#[tauri::command]
+pub async fn set_sync_secret(
+    db: State<'_, Repository>,
+    secret: SyncSecret,
+    value: Option<String>,
+) -> Result<(), String> {
+    tracing::debug!(
+        "IPC 调用: set_sync_secret, secret={}, clear={}",
+        secret.as_str(),
+        value.as_deref().is_none_or(str::is_empty)
+    );
+    cloud_sync::set_sync_secret(&db, secret, value.as_deref()).map_err(|e| e.to_string())
+}
+
+/// 各云同步密钥是否已保存
+#[tauri::command]
+pub async fn get_sync_secret_status(db: State<'_, Repository>) -> Result<SyncSecretStatus, String> {
+    tracing::debug!("IPC 调用: get_sync_secret_status");
+    cloud_sync::sync_secret_status(&db).map_err(|e| e.to_string())
+}
+
+/// 立即按当前设置同步一次
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncResult, String> {
+    tracing::debug!("IPC 调用: sync_now");
+
+    // 网络请求与 PBKDF2 密钥派生较慢，放到阻塞线程池执行
+    tauri::async_runtime::spawn_blocking(move || sync_scheduler::run_sync(&app))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,58 @@
+//! @file tag.rs
+//! @description 会话与日期标签相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::NaiveDate;
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{TagStats, TagTarget};
+
+/// 为会话或日期添加标签
+///
+/// # 返回
+/// 新建关联返回 true，关联已存在返回 false
+#[tauri::command]
+pub async fn add_tag(
+    db: State<'_, Repository>,
+    name: String,
+    target: TagTarget,
+) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: add_tag, name={}, target={:?}", name, target);
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("标签名称不能为空".to_string());
+    }
+    if let TagTarget::Date(date) = &target {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    }
+    db.add_tag(name, &target).map_err(|e| e.to_string())
+}
+
+/// 移除会话或日期上的标签
+#[tauri::command]
+pub async fn remove_tag(
+    db: State<'_, Repository>,
+    name: String,
+    target: TagTarget,
+) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: remove_tag, name={}, target={:?}", name, target);
+    db.remove_tag(name.trim(), &target)
+        .map_err(|e| e.to_string())
+}
+
+/// 按标签统计用量，用于费用分摊
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_stats_by_tag(
+    db: State<'_, Repository>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<TagStats>, String> {
+    tracing::debug!(
+        "IPC 调用: get_stats_by_tag, start_date={:?}, end_date={:?}",
+        start_date,
+        end_date
+    );
+    db.get_stats_by_tag(start_date.as_deref(), end_date.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,64 @@
+//! @file trash.rs
+//! @description 回收站相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::Repository;
+use crate::models::{DeletedItem, DeletedItemKind, RestoredItem, TrashSettings};
+use crate::services::trash;
+
+/// 获取回收站条目
+#[tauri::command]
+pub async fn get_deleted_items(db: State<'_, Repository>) -> Result<Vec<DeletedItem>, String> {
+    tracing::debug!("IPC 调用: get_deleted_items");
+    db.get_deleted_items().map_err(|e| e.to_string())
+}
+
+/// 从回收站恢复条目，恢复后通知前端刷新统计
+#[tauri::command]
+pub async fn restore_deleted(
+    app: AppHandle,
+    db: State<'_, Repository>,
+    id: i64,
+) -> Result<Option<RestoredItem>, String> {
+    tracing::debug!("IPC 调用: restore_deleted, id={}", id);
+    let Some(restored) = db.restore_deleted(id).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+
+    if restored.item.kind == DeletedItemKind::Provider {
+        trash::move_provider_credentials(&db, restored.item.source_id, restored.restored_id);
+    }
+    match db.get_current_stats() {
+        Ok(stats) => {
+            if let Err(e) = app.emit("stats-updated", stats) {
+                tracing::error!("发送 stats-updated 事件失败: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("获取统计数据失败: {}", e),
+    }
+
+    Ok(Some(restored))
+}
+
+/// 获取回收站设置
+#[tauri::command]
+pub async fn get_trash_settings(db: State<'_, Repository>) -> Result<TrashSettings, String> {
+    tracing::debug!("IPC 调用: get_trash_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存回收站设置
+#[tauri::command]
+pub async fn set_trash_settings(
+    db: State<'_, Repository>,
+    settings: TrashSettings,
+) -> Result<TrashSettings, String> {
+    tracing::debug!("IPC 调用: set_trash_settings, settings={:?}", settings);
+    if settings.retention_days == 0 {
+        return Err("保留天数必须大于 0".to_string());
+    }
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
@@ -0,0 +1,60 @@
+//! @file updater.rs
+//! @description 自动更新相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, State};
+
+use crate::db::Repository;
+use crate::models::{UpdateInfo, UpdateSettings};
+use crate::services::updater;
+
+/// 获取自动更新设置
+#[tauri::command]
+pub async fn get_update_settings(db: State<'_, Repository>) -> Result<UpdateSettings, String> {
+    tracing::debug!("IPC 调用: get_update_settings");
+    db.get_setting().map_err(|e| e.to_string())
+}
+
+/// 保存自动更新设置
+#[tauri::command]
+pub async fn set_update_settings(
+    db: State<'_, Repository>,
+    settings: UpdateSettings,
+) -> Result<UpdateSettings, String> {
+    tracing::debug!(
+        "IPC 调用: set_update_settings, channel={}, auto_check={}",
+        settings.channel.as_str(),
+        settings.auto_check
+    );
+    db.set_setting(&settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// 按当前渠道检查更新，没有新版本时返回 None
+#[tauri::command]
+pub async fn check_for_updates(
+    app: AppHandle,
+    db: State<'_, Repository>,
+) -> Result<Option<UpdateInfo>, String> {
+    let settings: UpdateSettings = db.get_setting().map_err(|e| e.to_string())?;
+    tracing::debug!(
+        "IPC 调用: check_for_updates, channel={}",
+        settings.channel.as_str()
+    );
+    updater::check_for_updates(&app, settings.channel)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 下载并安装当前渠道的最新版本，成功后应用会重启
+#[tauri::command]
+pub async fn install_update(app: AppHandle, db: State<'_, Repository>) -> Result<(), String> {
+    let settings: UpdateSettings = db.get_setting().map_err(|e| e.to_string())?;
+    tracing::debug!(
+        "IPC 调用: install_update, channel={}",
+        settings.channel.as_str()
+    );
+    updater::install_update(&app, settings.channel)
+        .await
+        .map_err(|e| e.to_string())
+}
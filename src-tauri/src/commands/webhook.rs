@@ -0,0 +1,97 @@
+//! @file webhook.rs
+//! @description Webhook 告警相关 Tauri Commands
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::State;
+
+use crate::db::Repository;
+use crate::models::{WebhookDelivery, WebhookTarget, WebhookTargetInput};
+use crate::services::webhook;
+
+/// 投递记录默认返回条数
+const DEFAULT_DELIVERY_LIMIT: usize = 100;
+
+/// 获取全部 Webhook 目标
+#[tauri::command]
+pub async fn get_webhook_targets(db: State<'_, Repository>) -> Result<Vec<WebhookTarget>, String> {
+    tracing::debug!("IPC 调用: get_webhook_targets");
+    db.get_webhook_targets().map_err(|e| e.to_string())
+}
+
+/// 新建 Webhook 目标，完整地址保存到钥匙串，返回的目标只含脱敏地址
+#[tauri::command]
+pub async fn create_webhook_target(
+    db: State<'_, Repository>,
+    target: WebhookTargetInput,
+) -> Result<WebhookTarget, String> {
+    tracing::debug!("IPC 调用: create_webhook_target, name={}", target.name);
+    let target = validate_target(target)?;
+    if target.url.is_empty() {
+        return Err("Webhook 地址不能为空".to_string());
+    }
+    webhook::create_target(&db, &target).map_err(|e| e.to_string())
+}
+
+/// 更新 Webhook 目标，地址留空时保留原地址；目标不存在时返回 None
+#[tauri::command]
+pub async fn update_webhook_target(
+    db: State<'_, Repository>,
+    id: i64,
+    target: WebhookTargetInput,
+) -> Result<Option<WebhookTarget>, String> {
+    tracing::debug!("IPC 调用: update_webhook_target, id={}", id);
+    let target = validate_target(target)?;
+    webhook::update_target(&db, id, &target).map_err(|e| e.to_string())
+}
+
+/// 删除 Webhook 目标及其投递记录，同时删除钥匙串中的完整地址
+#[tauri::command]
+pub async fn delete_webhook_target(db: State<'_, Repository>, id: i64) -> Result<bool, String> {
+    tracing::debug!("IPC 调用: delete_webhook_target, id={}", id);
+    webhook::delete_target(&db, id).map_err(|e| e.to_string())
+}
+
+/// 获取最近的投递记录（从新到旧）
+#[tauri::command]
+pub async fn get_webhook_deliveries(
+    db: State<'_, Repository>,
+    limit: Option<usize>,
+) -> Result<Vec<WebhookDelivery>, String> {
+    tracing::debug!("IPC 调用: get_webhook_deliveries, limit={:?}", limit);
+    db.get_webhook_deliveries(limit.unwrap_or(DEFAULT_DELIVERY_LIMIT))
+        .map_err(|e| e.to_string())
+}
+
+/// 向目标发送一条测试消息（含重试），不写入投递记录
+#[tauri::command]
+pub async fn test_webhook_target(db: State<'_, Repository>, id: i64) -> Result<(), String> {
+    tracing::debug!("IPC 调用: test_webhook_target, id={}", id);
+    let target = db
+        .get_webhook_targets()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|target| target.id == id)
+        .ok_or_else(|| format!("Webhook 目标不存在: {}", id))?;
+    let url = webhook::target_url(&db, &target).map_err(|e| e.to_string())?;
+
+    // 网络请求与重试等待较慢，放到阻塞线程池执行
+    tauri::async_runtime::spawn_blocking(move || webhook::send_test(&target, &url))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 校验目标参数，名称与地址去除首尾空白，填写的地址必须为 http / https
+fn validate_target(mut target: WebhookTargetInput) -> Result<WebhookTargetInput, String> {
+    target.name = target.name.trim().to_string();
+    target.url = target.url.trim().to_string();
+    if target.name.is_empty() {
+        return Err("Webhook 名称不能为空".to_string());
+    }
+    if !target.url.is_empty()
+        && !target.url.starts_with("https://")
+        && !target.url.starts_with("http://")
+    {
+        return Err("Webhook 地址必须以 http:// 或 https:// 开头".to_string());
+    }
+    Ok(target)
+}
@@ -0,0 +1,290 @@
+//! @file location.rs
+//! @description 数据库位置解析与迁移，支持自定义数据库路径和便携模式
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{Repository, RepositoryError, DB_FILE_NAME};
+use crate::models::DatabaseLocation;
+
+/// 数据库位置配置文件名，位于应用数据目录
+///
+/// 数据库位置不能保存在数据库自身的设置表里，因此单独存放
+pub const DATABASE_CONFIG_FILE: &str = "database.json";
+
+/// 便携模式标记文件名：程序目录下存在该文件时，数据库固定存放在程序目录的 data 子目录
+pub const PORTABLE_MARKER_FILE: &str = "portable";
+
+/// 便携模式下的数据目录名
+pub const PORTABLE_DATA_DIR: &str = "data";
+
+/// 数据库位置配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    /// 自定义数据库文件路径，None 表示使用默认位置
+    pub path: Option<PathBuf>,
+}
+
+impl DatabaseConfig {
+    /// 读取配置；文件不存在或内容损坏时回退到默认位置
+    pub fn load(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join(DATABASE_CONFIG_FILE);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!(
+                "数据库位置配置无效，使用默认位置 [{}]: {}",
+                path.display(),
+                e
+            );
+            Self::default()
+        })
+    }
+
+    /// 保存配置
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), RepositoryError> {
+        write_config(&app_data_dir.join(DATABASE_CONFIG_FILE), self)
+    }
+}
+
+/// 保存 JSON 配置文件，先写临时文件再重命名，避免写入中断留下半个文件
+pub(crate) fn write_config<T: Serialize>(path: &Path, value: &T) -> Result<(), RepositoryError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, serde_json::to_string_pretty(value)?)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// 便携模式数据目录；程序目录下没有标记文件时返回 None
+pub fn portable_data_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    portable_data_dir_in(exe.parent()?)
+}
+
+fn portable_data_dir_in(exe_dir: &Path) -> Option<PathBuf> {
+    exe_dir
+        .join(PORTABLE_MARKER_FILE)
+        .exists()
+        .then(|| exe_dir.join(PORTABLE_DATA_DIR))
+}
+
+/// 解析实际使用的数据库文件路径
+///
+/// 优先级：便携模式 > 自定义位置 > 应用数据目录下的默认位置
+pub fn resolve_db_path(app_data_dir: &Path) -> PathBuf {
+    resolve_db_path_with(app_data_dir, portable_data_dir())
+}
+
+fn resolve_db_path_with(app_data_dir: &Path, portable_dir: Option<PathBuf>) -> PathBuf {
+    if let Some(dir) = portable_dir {
+        return dir.join(DB_FILE_NAME);
+    }
+    DatabaseConfig::load(app_data_dir)
+        .path
+        .unwrap_or_else(|| app_data_dir.join(DB_FILE_NAME))
+}
+
+/// 当前数据库位置信息
+pub fn database_location(app_data_dir: &Path, current: &Path) -> DatabaseLocation {
+    let default_path = app_data_dir.join(DB_FILE_NAME);
+    DatabaseLocation {
+        path: current.display().to_string(),
+        default_path: default_path.display().to_string(),
+        custom: current != default_path,
+        portable: portable_data_dir().is_some(),
+    }
+}
+
+/// 将数据库迁移到 new_path 并记住新位置
+///
+/// 业务逻辑说明：
+/// 1. 便携模式下数据库固定在程序目录，拒绝迁移
+/// 2. new_path 为已存在的目录时，在该目录下使用默认文件名
+/// 3. 复制、校验并切换连接（见 Repository::relocate），然后保存新位置
+/// 4. 最后删除原数据库文件；删除失败只记录日志，不影响迁移结果
+pub fn move_database(
+    repository: &Repository,
+    app_data_dir: &Path,
+    new_path: &Path,
+) -> Result<DatabaseLocation, RepositoryError> {
+    if portable_data_dir().is_some() {
+        return Err(RepositoryError::Relocate(
+            "便携模式下数据库固定存放在程序目录".to_string(),
+        ));
+    }
+    let new_path = if new_path.is_dir() {
+        new_path.join(DB_FILE_NAME)
+    } else {
+        new_path.to_path_buf()
+    };
+
+    let old_path = repository.relocate(&new_path)?;
+    let default_path = app_data_dir.join(DB_FILE_NAME);
+    DatabaseConfig {
+        path: (new_path != default_path).then(|| new_path.clone()),
+    }
+    .save(app_data_dir)?;
+    remove_database_files(&old_path);
+
+    Ok(database_location(app_data_dir, &new_path))
+}
+
+/// 生成数据库副本到 dest，覆盖已有副本
+///
+/// 以只读方式打开原库并用 VACUUM INTO 复制，原库不会被迁移或写入；
+/// 原库不存在时只清理 dest，由调用方打开时创建空库
+pub fn copy_database(source: &Path, dest: &Path) -> Result<(), RepositoryError> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    remove_database_files(dest);
+    if !source.exists() {
+        return Ok(());
+    }
+    let conn = Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.execute("VACUUM INTO ?1", [dest.to_string_lossy()])?;
+    Ok(())
+}
+
+/// 删除数据库文件及其 WAL / 共享内存文件
+fn remove_database_files(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = path.as_os_str().to_owned();
+        file.push(suffix);
+        let file = PathBuf::from(file);
+        if file.exists() {
+            if let Err(e) = std::fs::remove_file(&file) {
+                tracing::warn!("删除原数据库文件失败 [{}]: {}", file.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("db-loc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("dir");
+        dir
+    }
+
+    #[test]
+    fn test_resolve_db_path() {
+        let dir = temp_dir("resolve");
+        let app_data_dir = dir.join("app");
+        assert_eq!(
+            resolve_db_path_with(&app_data_dir, None),
+            app_data_dir.join(DB_FILE_NAME)
+        );
+
+        let custom = dir.join("custom/monitor.db");
+        DatabaseConfig {
+            path: Some(custom.clone()),
+        }
+        .save(&app_data_dir)
+        .expect("save");
+        assert_eq!(resolve_db_path_with(&app_data_dir, None), custom);
+
+        let exe_dir = dir.join("usb");
+        std::fs::create_dir_all(&exe_dir).expect("dir");
+        assert_eq!(portable_data_dir_in(&exe_dir), None);
+        std::fs::write(exe_dir.join(PORTABLE_MARKER_FILE), "").expect("marker");
+        let portable = portable_data_dir_in(&exe_dir);
+        assert_eq!(portable, Some(exe_dir.join(PORTABLE_DATA_DIR)));
+        assert_eq!(
+            resolve_db_path_with(&app_data_dir, portable),
+            exe_dir.join(PORTABLE_DATA_DIR).join(DB_FILE_NAME)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_move_database() {
+        let dir = temp_dir("move");
+        let app_data_dir = dir.join("app");
+        let old_path = app_data_dir.join(DB_FILE_NAME);
+        let repository = Repository::new(&old_path).expect("repo");
+        let shared = repository.clone();
+        repository
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+
+        let target_dir = dir.join("elsewhere");
+        std::fs::create_dir_all(&target_dir).expect("dir");
+        let location = move_database(&repository, &app_data_dir, &target_dir).expect("move");
+        let new_path = target_dir.join(DB_FILE_NAME);
+        assert_eq!(location.path, new_path.display().to_string());
+        assert!(location.custom);
+        assert!(!old_path.exists());
+        assert_eq!(shared.path(), new_path);
+        assert_eq!(shared.get_all_providers(false).expect("providers").len(), 1);
+        assert_eq!(resolve_db_path_with(&app_data_dir, None), new_path);
+
+        // 目标已存在时拒绝覆盖，当前连接保持不变
+        let err = move_database(&repository, &app_data_dir, &new_path);
+        assert!(err.is_err());
+        assert_eq!(repository.path(), new_path);
+
+        // 迁回默认位置后清除自定义配置
+        move_database(&repository, &app_data_dir, &old_path).expect("move back");
+        assert_eq!(
+            DatabaseConfig::load(&app_data_dir),
+            DatabaseConfig::default()
+        );
+        assert!(!new_path.exists());
+
+        drop(repository);
+        drop(shared);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_copy_database() {
+        let dir = temp_dir("copy");
+        let source = dir.join("source.db");
+        let dest = dir.join("copy/monitor.db");
+        Repository::new(&source)
+            .expect("repo")
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+
+        copy_database(&source, &dest).expect("copy");
+        let copy = Repository::new(&dest).expect("copy repo");
+        copy.upsert_provider("sk-other", None).expect("provider");
+        assert_eq!(copy.get_all_providers(false).expect("providers").len(), 2);
+        let original = Repository::new(&source).expect("repo");
+        assert_eq!(
+            original.get_all_providers(false).expect("providers").len(),
+            1
+        );
+
+        // 再次复制覆盖旧副本；原库不存在时副本被清空
+        drop(copy);
+        copy_database(&source, &dest).expect("copy again");
+        assert_eq!(
+            Repository::new(&dest)
+                .expect("copy repo")
+                .get_all_providers(false)
+                .expect("providers")
+                .len(),
+            1
+        );
+        copy_database(&dir.join("missing.db"), &dest).expect("copy missing");
+        assert!(!dest.exists());
+
+        drop(original);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
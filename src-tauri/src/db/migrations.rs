@@ -4,58 +4,348 @@
 //! @date 2026-01-08
 use chrono::Utc;
 use rusqlite::{params, Connection};
+use thiserror::Error;
 
 use crate::db::schema::{
-    CREATE_DAILY_STATS_TABLE, CREATE_INDEXES, CREATE_MESSAGE_USAGE_TABLE, CREATE_PROVIDERS_TABLE,
-    CREATE_PROVIDER_SWITCH_LOGS_TABLE, CREATE_SCHEMA_MIGRATIONS_TABLE,
+    ADD_ALERT_STATE, ADD_API_EQUIVALENT_COST, ADD_FOREIGN_KEY_CASCADE, ADD_MESSAGE_USAGE_DURATION,
+    ADD_MESSAGE_USAGE_PROJECT, ADD_PROVIDER_KIND, ADD_SERVICE_TIER, ADD_THINKING_TOKENS,
+    BACKFILL_MODEL_DAILY_STATS, CREATE_ALERT_TABLES, CREATE_API_ERRORS_TABLE,
+    CREATE_APP_SETTINGS_TABLE, CREATE_COMPACTION_EVENTS_TABLE, CREATE_CORE_TABLES,
+    CREATE_COST_ANOMALIES_TABLE, CREATE_COST_RECALCULATIONS_TABLE, CREATE_DATE_NOTES_TABLE,
+    CREATE_DELETED_ITEMS_TABLE, CREATE_GOALS_TABLES, CREATE_INTERRUPTS_TABLE,
+    CREATE_MESSAGE_ID_INDEX, CREATE_MESSAGE_SEARCH_INDEXES, CREATE_MESSAGE_USAGE_UNIQUE_INDEX,
+    CREATE_MODEL_ALIASES_TABLE, CREATE_MODEL_DAILY_STATS_TABLE, CREATE_NOTIFICATION_HISTORY_TABLE,
+    CREATE_OFFICIAL_USAGE_TABLE, CREATE_PROVIDER_PLANS_TABLE, CREATE_PROVIDER_QUOTAS_TABLE,
+    CREATE_RATE_LIMIT_EVENTS_TABLE, CREATE_SAVED_QUERIES_TABLE, CREATE_SCHEMA_MIGRATIONS_TABLE,
+    CREATE_SESSIONS_TABLE, CREATE_TAGS_TABLES, CREATE_TOOL_CALLS_TABLE, CREATE_WEBHOOK_TABLES,
+    DROP_ALERT_STATE, DROP_ALERT_TABLES, DROP_API_EQUIVALENT_COST, DROP_API_ERRORS_TABLE,
+    DROP_APP_SETTINGS_TABLE, DROP_COMPACTION_EVENTS_TABLE, DROP_CORE_TABLES,
+    DROP_COST_ANOMALIES_TABLE, DROP_COST_RECALCULATIONS_TABLE, DROP_DATE_NOTES_TABLE,
+    DROP_DELETED_ITEMS_TABLE, DROP_FOREIGN_KEY_CASCADE, DROP_GOALS_TABLES, DROP_INTERRUPTS_TABLE,
+    DROP_MESSAGE_ID_INDEX, DROP_MESSAGE_SEARCH_INDEXES, DROP_MESSAGE_USAGE_DURATION,
+    DROP_MESSAGE_USAGE_PROJECT, DROP_MESSAGE_USAGE_UNIQUE_INDEX, DROP_MODEL_ALIASES_TABLE,
+    DROP_MODEL_DAILY_STATS_TABLE, DROP_NOTIFICATION_HISTORY_TABLE, DROP_OFFICIAL_USAGE_TABLE,
+    DROP_PROVIDER_KIND, DROP_PROVIDER_PLANS_TABLE, DROP_PROVIDER_QUOTAS_TABLE,
+    DROP_RATE_LIMIT_EVENTS_TABLE, DROP_SAVED_QUERIES_TABLE, DROP_SERVICE_TIER, DROP_SESSIONS_TABLE,
+    DROP_TAGS_TABLES, DROP_THINKING_TOKENS, DROP_TOOL_CALLS_TABLE, DROP_WEBHOOK_TABLES,
+    REMASK_API_KEY_PREFIXES,
 };
 
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Migration {0} cannot be rolled back")]
+    Irreversible(i64),
+}
+
+/// 单个版本的迁移
+///
+/// up 为升级 SQL，down 为可选的回滚 SQL；每个版本的 SQL 自包含，不依赖其他版本之外的建表语句
 #[derive(Debug, Clone)]
 pub struct Migration {
     pub version: i64,
     pub description: &'static str,
-    pub sql: &'static str,
+    pub up: &'static str,
+    pub down: Option<&'static str>,
 }
 
 pub fn all_migrations() -> Vec<Migration> {
-    vec![Migration {
-        version: 1,
-        description: "init core tables",
-        sql: r#"
-                -- 核心表
-                "#,
-    }]
+    vec![
+        Migration {
+            version: 1,
+            description: "init core tables",
+            up: CREATE_CORE_TABLES,
+            down: Some(DROP_CORE_TABLES),
+        },
+        Migration {
+            version: 2,
+            description: "add provider plans",
+            up: CREATE_PROVIDER_PLANS_TABLE,
+            down: Some(DROP_PROVIDER_PLANS_TABLE),
+        },
+        Migration {
+            version: 3,
+            description: "add cost anomalies",
+            up: CREATE_COST_ANOMALIES_TABLE,
+            down: Some(DROP_COST_ANOMALIES_TABLE),
+        },
+        Migration {
+            version: 4,
+            description: "add model daily stats",
+            up: CREATE_MODEL_DAILY_STATS_TABLE,
+            down: Some(DROP_MODEL_DAILY_STATS_TABLE),
+        },
+        Migration {
+            version: 5,
+            description: "backfill model daily stats",
+            up: BACKFILL_MODEL_DAILY_STATS,
+            down: None,
+        },
+        Migration {
+            version: 6,
+            description: "add message usage project",
+            up: ADD_MESSAGE_USAGE_PROJECT,
+            down: Some(DROP_MESSAGE_USAGE_PROJECT),
+        },
+        Migration {
+            version: 7,
+            description: "add message search indexes",
+            up: CREATE_MESSAGE_SEARCH_INDEXES,
+            down: Some(DROP_MESSAGE_SEARCH_INDEXES),
+        },
+        Migration {
+            version: 8,
+            description: "add sessions table",
+            up: CREATE_SESSIONS_TABLE,
+            down: Some(DROP_SESSIONS_TABLE),
+        },
+        Migration {
+            version: 9,
+            description: "add app settings table",
+            up: CREATE_APP_SETTINGS_TABLE,
+            down: Some(DROP_APP_SETTINGS_TABLE),
+        },
+        Migration {
+            version: 10,
+            description: "add webhooks and budget alerts",
+            up: CREATE_WEBHOOK_TABLES,
+            down: Some(DROP_WEBHOOK_TABLES),
+        },
+        Migration {
+            version: 11,
+            description: "add message usage unique index",
+            up: CREATE_MESSAGE_USAGE_UNIQUE_INDEX,
+            down: Some(DROP_MESSAGE_USAGE_UNIQUE_INDEX),
+        },
+        Migration {
+            version: 12,
+            description: "add message id index",
+            up: CREATE_MESSAGE_ID_INDEX,
+            down: Some(DROP_MESSAGE_ID_INDEX),
+        },
+        Migration {
+            version: 13,
+            description: "add rate limit events",
+            up: CREATE_RATE_LIMIT_EVENTS_TABLE,
+            down: Some(DROP_RATE_LIMIT_EVENTS_TABLE),
+        },
+        Migration {
+            version: 14,
+            description: "add message usage duration",
+            up: ADD_MESSAGE_USAGE_DURATION,
+            down: Some(DROP_MESSAGE_USAGE_DURATION),
+        },
+        Migration {
+            version: 15,
+            description: "add api errors",
+            up: CREATE_API_ERRORS_TABLE,
+            down: Some(DROP_API_ERRORS_TABLE),
+        },
+        Migration {
+            version: 16,
+            description: "add tags",
+            up: CREATE_TAGS_TABLES,
+            down: Some(DROP_TAGS_TABLES),
+        },
+        Migration {
+            version: 17,
+            description: "add date notes",
+            up: CREATE_DATE_NOTES_TABLE,
+            down: Some(DROP_DATE_NOTES_TABLE),
+        },
+        Migration {
+            version: 18,
+            description: "re-mask api key prefixes",
+            up: REMASK_API_KEY_PREFIXES,
+            // 截短的前缀无法恢复，表结构未变化，回滚时无需操作
+            down: Some(""),
+        },
+        Migration {
+            version: 19,
+            description: "add official usage",
+            up: CREATE_OFFICIAL_USAGE_TABLE,
+            down: Some(DROP_OFFICIAL_USAGE_TABLE),
+        },
+        Migration {
+            version: 20,
+            description: "add provider kind",
+            up: ADD_PROVIDER_KIND,
+            down: Some(DROP_PROVIDER_KIND),
+        },
+        Migration {
+            version: 21,
+            description: "add api equivalent cost",
+            up: ADD_API_EQUIVALENT_COST,
+            down: Some(DROP_API_EQUIVALENT_COST),
+        },
+        Migration {
+            version: 22,
+            description: "add saved queries",
+            up: CREATE_SAVED_QUERIES_TABLE,
+            down: Some(DROP_SAVED_QUERIES_TABLE),
+        },
+        Migration {
+            version: 23,
+            description: "add model aliases",
+            up: CREATE_MODEL_ALIASES_TABLE,
+            down: Some(DROP_MODEL_ALIASES_TABLE),
+        },
+        Migration {
+            version: 24,
+            description: "add provider quotas",
+            up: CREATE_PROVIDER_QUOTAS_TABLE,
+            down: Some(DROP_PROVIDER_QUOTAS_TABLE),
+        },
+        Migration {
+            version: 25,
+            description: "add alert rules",
+            up: CREATE_ALERT_TABLES,
+            down: Some(DROP_ALERT_TABLES),
+        },
+        Migration {
+            version: 26,
+            description: "add alert state",
+            up: ADD_ALERT_STATE,
+            down: Some(DROP_ALERT_STATE),
+        },
+        Migration {
+            version: 27,
+            description: "add notification history",
+            up: CREATE_NOTIFICATION_HISTORY_TABLE,
+            down: Some(DROP_NOTIFICATION_HISTORY_TABLE),
+        },
+        Migration {
+            version: 28,
+            description: "add compaction events",
+            up: CREATE_COMPACTION_EVENTS_TABLE,
+            down: Some(DROP_COMPACTION_EVENTS_TABLE),
+        },
+        Migration {
+            version: 29,
+            description: "add tool calls",
+            up: CREATE_TOOL_CALLS_TABLE,
+            down: Some(DROP_TOOL_CALLS_TABLE),
+        },
+        Migration {
+            version: 30,
+            description: "add interrupts",
+            up: CREATE_INTERRUPTS_TABLE,
+            down: Some(DROP_INTERRUPTS_TABLE),
+        },
+        Migration {
+            version: 31,
+            description: "add thinking tokens",
+            up: ADD_THINKING_TOKENS,
+            down: Some(DROP_THINKING_TOKENS),
+        },
+        Migration {
+            version: 32,
+            description: "add service tier",
+            up: ADD_SERVICE_TIER,
+            down: Some(DROP_SERVICE_TIER),
+        },
+        Migration {
+            version: 33,
+            description: "add cost recalculations",
+            up: CREATE_COST_RECALCULATIONS_TABLE,
+            down: Some(DROP_COST_RECALCULATIONS_TABLE),
+        },
+        Migration {
+            version: 34,
+            description: "add deleted items",
+            up: CREATE_DELETED_ITEMS_TABLE,
+            down: Some(DROP_DELETED_ITEMS_TABLE),
+        },
+        Migration {
+            version: 35,
+            description: "add foreign key cascade",
+            up: ADD_FOREIGN_KEY_CASCADE,
+            down: Some(DROP_FOREIGN_KEY_CASCADE),
+        },
+        Migration {
+            version: 36,
+            description: "add goals",
+            up: CREATE_GOALS_TABLES,
+            down: Some(DROP_GOALS_TABLES),
+        },
+    ]
+}
+
+/// 最新的迁移版本
+pub fn latest_version() -> i64 {
+    all_migrations()
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
 }
 
+/// 当前已应用的最高迁移版本
+pub fn current_version(conn: &Connection) -> Result<i64, rusqlite::Error> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// 依次应用尚未执行的迁移
+///
+/// 每个版本的 SQL 与版本记录在同一事务中提交，失败时该版本整体回滚
 pub fn apply_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(CREATE_SCHEMA_MIGRATIONS_TABLE)?;
 
-    let mut stmt = conn.prepare("SELECT COALESCE(MAX(version), 0) FROM schema_migrations")?;
-    let current_version: i64 = stmt.query_row([], |row| row.get(0))?;
+    let current_version = current_version(conn)?;
 
     for migration in all_migrations() {
         if migration.version <= current_version {
             continue;
         }
 
-        conn.execute_batch(migration.sql)?;
-        conn.execute_batch(CREATE_PROVIDERS_TABLE)?;
-        conn.execute_batch(CREATE_MESSAGE_USAGE_TABLE)?;
-        conn.execute_batch(CREATE_DAILY_STATS_TABLE)?;
-        conn.execute_batch(CREATE_PROVIDER_SWITCH_LOGS_TABLE)?;
+        apply_migration(conn, &migration)?;
+    }
+
+    Ok(())
+}
 
-        for index_sql in CREATE_INDEXES {
-            conn.execute_batch(index_sql)?;
+/// 在事务中执行单个迁移并记录版本
+fn apply_migration(conn: &Connection, migration: &Migration) -> Result<(), rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(migration.up)?;
+    tx.execute(
+        "INSERT INTO schema_migrations (version, description, applied_at) VALUES (?1, ?2, ?3)",
+        params![
+            migration.version,
+            migration.description,
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    tx.commit()
+}
+
+/// 回滚到指定版本（保留 target_version 及以下的迁移）
+///
+/// 按版本从高到低执行 down SQL，每个版本单独一个事务；
+/// 遇到没有 down SQL 的版本时停止并返回错误，已回滚的版本不受影响
+pub fn rollback_migrations(conn: &Connection, target_version: i64) -> Result<(), MigrationError> {
+    let current_version = current_version(conn)?;
+
+    for migration in all_migrations().iter().rev() {
+        if migration.version > current_version || migration.version <= target_version {
+            continue;
         }
+        let down = migration
+            .down
+            .ok_or(MigrationError::Irreversible(migration.version))?;
 
-        conn.execute(
-            "INSERT INTO schema_migrations (version, description, applied_at) VALUES (?1, ?2, ?3)",
-            params![
-                migration.version,
-                migration.description,
-                Utc::now().to_rfc3339()
-            ],
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(down)?;
+        tx.execute(
+            "DELETE FROM schema_migrations WHERE version = ?1",
+            params![migration.version],
         )?;
+        tx.commit()?;
     }
 
     Ok(())
@@ -75,6 +365,178 @@ mod tests {
             .expect("prepare");
         let count: i64 = stmt.query_row([], |row| row.get(0)).expect("count");
 
-        assert_eq!(count, 1);
+        assert_eq!(count, all_migrations().len() as i64);
+    }
+
+    #[test]
+    fn test_backfill_model_daily_stats() {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        conn.execute_batch(CREATE_SCHEMA_MIGRATIONS_TABLE)
+            .expect("migrations table");
+        conn.execute_batch(CREATE_CORE_TABLES).expect("core tables");
+        conn.execute(
+            "INSERT INTO schema_migrations (version, description, applied_at) VALUES (3, 'legacy', '2026-01-08T00:00:00Z')",
+            [],
+        )
+        .expect("legacy version");
+        conn.execute_batch(
+            "INSERT INTO providers (id, api_key_hash, api_key_prefix, first_seen_at, last_seen_at)
+             VALUES (1, 'hash', 'sk-test', '2026-01-08T00:00:00Z', '2026-01-08T00:00:00Z');
+             INSERT INTO message_usage (provider_id, session_id, message_id, model, input_tokens, output_tokens, cost_usd, created_at)
+             VALUES (1, 's1', 'm1', 'claude-3-opus', 10, 5, 0.5, '2026-01-08T12:00:00Z'),
+                    (1, 's1', 'm2', 'claude-3-opus', 20, 5, 0.5, '2026-01-08T12:05:00Z');",
+        )
+        .expect("legacy rows");
+
+        apply_migrations(&conn).expect("migrations should succeed");
+
+        let (input_tokens, message_count): (i64, i64) = conn
+            .query_row(
+                "SELECT SUM(total_input_tokens), SUM(message_count) FROM model_daily_stats",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("backfilled");
+
+        assert_eq!(input_tokens, 30);
+        assert_eq!(message_count, 2);
+    }
+
+    #[test]
+    fn test_unique_index_rebuilds_duplicated_stats() {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        apply_migrations(&conn).expect("migrations should succeed");
+        rollback_migrations(&conn, 10).expect("rollback");
+
+        // 早期版本重复写入 m1，daily_stats 与 model_daily_stats 都按重复后的数值累计
+        conn.execute_batch(
+            "INSERT INTO providers (id, api_key_hash, api_key_prefix, first_seen_at, last_seen_at)
+             VALUES (1, 'hash', 'sk-test', '2026-01-08T00:00:00Z', '2026-01-08T00:00:00Z');
+             INSERT INTO message_usage (provider_id, session_id, message_id, model, input_tokens, output_tokens, cost_usd, created_at)
+             VALUES (1, 's1', 'm1', 'claude-3-opus', 10, 5, 0.5, '2026-01-08T12:00:00Z'),
+                    (1, 's1', 'm1', 'claude-3-opus', 10, 5, 0.5, '2026-01-08T12:00:00Z'),
+                    (1, 's2', 'm2', 'claude-3-opus', 20, 5, 0.25, '2026-01-08T12:05:00Z');
+             INSERT INTO daily_stats (provider_id, date, total_input_tokens, total_output_tokens, total_cost_usd, session_count, message_count)
+             SELECT 1, date('2026-01-08T12:00:00Z', 'localtime'), 40, 15, 1.25, 2, 3;
+             INSERT INTO model_daily_stats (provider_id, date, model, total_input_tokens, total_output_tokens, total_cost_usd, message_count)
+             SELECT 1, date('2026-01-08T12:00:00Z', 'localtime'), 'claude-3-opus', 40, 15, 1.25, 3;",
+        )
+        .expect("legacy rows");
+
+        apply_migrations(&conn).expect("reapply");
+
+        let daily: (i64, f64, i64, i64) = conn
+            .query_row(
+                "SELECT total_input_tokens, total_cost_usd, session_count, message_count FROM daily_stats",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .expect("daily stats");
+        assert_eq!(daily, (30, 0.75, 2, 2));
+
+        let model: (i64, f64, i64) = conn
+            .query_row(
+                "SELECT total_input_tokens, total_cost_usd, message_count FROM model_daily_stats",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("model daily stats");
+        assert_eq!(model, (30, 0.75, 2));
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        apply_migrations(&conn).expect("migrations should succeed");
+
+        let broken = Migration {
+            version: latest_version() + 1,
+            description: "broken",
+            up: "CREATE TABLE half_done (id INTEGER); INSERT INTO missing_table VALUES (1);",
+            down: None,
+        };
+        assert!(apply_migration(&conn, &broken).is_err());
+
+        let half_done: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'half_done'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("sqlite_master");
+        assert_eq!(half_done, 0);
+        assert_eq!(current_version(&conn).expect("version"), latest_version());
+    }
+
+    #[test]
+    fn test_foreign_key_cascade() {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        apply_migrations(&conn).expect("migrations should succeed");
+        rollback_migrations(&conn, 34).expect("rollback");
+        // 模拟未开启外键检查时写入的孤立记录
+        conn.pragma_update(None, "foreign_keys", false)
+            .expect("foreign keys");
+        conn.execute_batch(
+            "INSERT INTO providers (id, api_key_hash, api_key_prefix, first_seen_at, last_seen_at)
+             VALUES (1, 'hash', 'sk-test', '2026-10-01T00:00:00Z', '2026-10-01T00:00:00Z');
+             INSERT INTO message_usage (provider_id, session_id, message_id, model, created_at)
+             VALUES (1, 's1', 'm1', 'claude-3-opus', '2026-10-01T12:00:00Z'),
+                    (2, 's1', 'm2', 'claude-3-opus', '2026-10-01T12:05:00Z');
+             INSERT INTO provider_plans (provider_id, plan_type, updated_at)
+             VALUES (1, 'pro', '2026-10-01T00:00:00Z');",
+        )
+        .expect("legacy rows");
+
+        apply_migrations(&conn).expect("reapply");
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .expect("count")
+        };
+        // 孤立记录在重建时被清除
+        assert_eq!(count("message_usage"), 1);
+
+        conn.pragma_update(None, "foreign_keys", true)
+            .expect("foreign keys");
+        assert!(conn
+            .execute(
+                "INSERT INTO message_usage (provider_id, session_id, message_id, model, created_at)
+                 VALUES (2, 's1', 'm3', 'claude-3-opus', '2026-10-01T12:10:00Z')",
+                [],
+            )
+            .is_err());
+        conn.execute("DELETE FROM providers WHERE id = 1", [])
+            .expect("delete provider");
+        assert_eq!(count("message_usage"), 0);
+        assert_eq!(count("provider_plans"), 0);
+    }
+
+    #[test]
+    fn test_rollback_and_reapply() {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        apply_migrations(&conn).expect("migrations should succeed");
+        assert_eq!(current_version(&conn).expect("version"), latest_version());
+
+        rollback_migrations(&conn, 5).expect("rollback");
+        assert_eq!(current_version(&conn).expect("version"), 5);
+        let sessions_exists: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'sessions'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("sqlite_master");
+        assert_eq!(sessions_exists, 0);
+
+        // v5 是数据回填，没有回滚 SQL
+        assert!(matches!(
+            rollback_migrations(&conn, 0),
+            Err(MigrationError::Irreversible(5))
+        ));
+        assert_eq!(current_version(&conn).expect("version"), 5);
+
+        apply_migrations(&conn).expect("reapply");
+        assert_eq!(current_version(&conn).expect("version"), latest_version());
     }
 }
@@ -2,8 +2,41 @@
 //! @description 数据库模块入口，包含 Schema、迁移与仓储层
 //! @author Atlas.oi
 //! @date 2026-01-08
+pub mod location;
 pub mod migrations;
+pub mod profile;
 pub mod repository;
 pub mod schema;
 
+use std::path::PathBuf;
+
 pub use repository::{Repository, RepositoryError};
+
+/// 数据库文件名
+pub const DB_FILE_NAME: &str = "claude-token-monitor.db";
+
+/// 演示数据库文件名，与真实数据库位于同一目录
+pub const DEMO_DB_FILE_NAME: &str = "claude-token-monitor-demo.db";
+
+/// 设置为 1 时以演示模式启动：使用演示数据库且不监控 Claude 日志
+pub const DEMO_MODE_ENV: &str = "CLAUDE_TOKEN_MONITOR_DEMO";
+
+/// 应用标识，需与 tauri.conf.json 中的 identifier 保持一致
+pub const APP_IDENTIFIER: &str = "com.claude-token-monitor.app";
+
+/// 不经过 Tauri 运行时（如 MCP 模式）使用的数据库路径
+///
+/// 与 Tauri 的 app_data_dir 规则一致：系统数据目录 / 应用标识，
+/// 再按启动档案（支持 `--profile`）、便携模式与自定义位置解析出实际数据库文件
+pub fn default_db_path() -> Option<PathBuf> {
+    let app_data_dir = dirs::data_dir()?.join(APP_IDENTIFIER);
+    let config = profile::ProfilesConfig::load(&app_data_dir);
+    let args: Vec<String> = std::env::args().collect();
+    let name = profile::startup_profile(&config, &args);
+    Some(profile::profile_db_path(&app_data_dir, &name))
+}
+
+/// 是否以演示模式启动
+pub fn is_demo_mode() -> bool {
+    std::env::var(DEMO_MODE_ENV).is_ok_and(|value| value == "1")
+}
@@ -0,0 +1,291 @@
+//! @file profile.rs
+//! @description 配置档案的读写与数据库路径解析，每个档案使用独立的数据库文件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::db::location::{portable_data_dir, resolve_db_path, write_config};
+use crate::db::{RepositoryError, DB_FILE_NAME};
+use crate::models::{Profile, ProfileInfo, DEFAULT_PROFILE_NAME, MAX_PROFILE_NAME_LEN};
+
+/// 档案配置文件名，位于应用数据目录
+pub const PROFILES_CONFIG_FILE: &str = "profiles.json";
+
+/// 非默认档案的数据库目录，位于应用数据目录（便携模式下为程序目录的 data 子目录）
+pub const PROFILES_DIR: &str = "profiles";
+
+/// 启动参数 `--profile <名称>`：本次启动使用指定档案，不改变保存的当前档案
+pub const PROFILE_ARG: &str = "--profile";
+
+/// 启动档案环境变量，优先级低于启动参数
+pub const PROFILE_ENV: &str = "CLAUDE_TOKEN_MONITOR_PROFILE";
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error("档案名称无效（1-{MAX_PROFILE_NAME_LEN} 个字母、数字、- 或 _）: {0}")]
+    InvalidName(String),
+    #[error("档案不存在: {0}")]
+    NotFound(String),
+    #[error("默认档案不能删除")]
+    DefaultProfile,
+    #[error("不能删除正在使用的档案: {0}")]
+    ActiveProfile(String),
+    #[error("演示模式下无法切换档案")]
+    DemoMode,
+    #[error("只读档案只能在启动时选择: {0}")]
+    ReadOnlyProfile(String),
+    #[error("文件监控启动失败: {0}")]
+    Watcher(String),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// 档案配置
+///
+/// 默认档案始终存在，未保存时视为没有自定义监控目录
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfilesConfig {
+    /// 当前档案，None 表示默认档案
+    pub active: Option<String>,
+
+    pub profiles: Vec<Profile>,
+}
+
+impl ProfilesConfig {
+    /// 读取配置；文件不存在或内容损坏时只有默认档案
+    pub fn load(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join(PROFILES_CONFIG_FILE);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("档案配置无效，使用默认档案 [{}]: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// 保存配置
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), RepositoryError> {
+        write_config(&app_data_dir.join(PROFILES_CONFIG_FILE), self)
+    }
+
+    /// 当前档案名称
+    pub fn active_name(&self) -> &str {
+        self.active.as_deref().unwrap_or(DEFAULT_PROFILE_NAME)
+    }
+
+    /// 按名称查找档案
+    pub fn get(&self, name: &str) -> Option<Profile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .cloned()
+            .or_else(|| {
+                (name == DEFAULT_PROFILE_NAME).then(|| Profile {
+                    name: DEFAULT_PROFILE_NAME.to_string(),
+                    watch_paths: Vec::new(),
+                    read_only: false,
+                })
+            })
+    }
+
+    /// 全部档案，默认档案排在最前
+    pub fn all(&self) -> Vec<Profile> {
+        let mut profiles = self.profiles.clone();
+        if !profiles.iter().any(|p| p.name == DEFAULT_PROFILE_NAME) {
+            profiles.extend(self.get(DEFAULT_PROFILE_NAME));
+        }
+        profiles.sort_by_key(|p| (p.name != DEFAULT_PROFILE_NAME, p.name.clone()));
+        profiles
+    }
+
+    /// 新增档案，同名档案存在时更新其监控目录
+    pub fn upsert(&mut self, profile: Profile) -> Result<(), ProfileError> {
+        validate_profile_name(&profile.name)?;
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+        Ok(())
+    }
+
+    /// 删除档案配置；档案数据库文件保留在磁盘上，重新添加同名档案即可恢复
+    pub fn remove(&mut self, name: &str) -> Result<(), ProfileError> {
+        if name == DEFAULT_PROFILE_NAME {
+            return Err(ProfileError::DefaultProfile);
+        }
+        if name == self.active_name() {
+            return Err(ProfileError::ActiveProfile(name.to_string()));
+        }
+        let before = self.profiles.len();
+        self.profiles.retain(|p| p.name != name);
+        if self.profiles.len() == before {
+            return Err(ProfileError::NotFound(name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// 校验档案名称；名称会用作目录名，只允许字母、数字、- 与 _
+pub fn validate_profile_name(name: &str) -> Result<(), ProfileError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidName(name.to_string()))
+    }
+}
+
+/// 档案使用的数据库文件路径
+///
+/// 默认档案沿用原有规则（便携模式 / 自定义位置 / 默认位置）；
+/// 其他档案位于数据目录下的 profiles/<名称>/
+pub fn profile_db_path(app_data_dir: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE_NAME {
+        return resolve_db_path(app_data_dir);
+    }
+    portable_data_dir()
+        .unwrap_or_else(|| app_data_dir.to_path_buf())
+        .join(PROFILES_DIR)
+        .join(name)
+        .join(DB_FILE_NAME)
+}
+
+/// 全部档案的展示信息
+pub fn profile_infos(
+    app_data_dir: &Path,
+    config: &ProfilesConfig,
+    active: &str,
+) -> Vec<ProfileInfo> {
+    config
+        .all()
+        .into_iter()
+        .map(|profile| ProfileInfo {
+            db_path: profile_db_path(app_data_dir, &profile.name)
+                .display()
+                .to_string(),
+            active: profile.name == active,
+            name: profile.name,
+            watch_paths: profile.watch_paths,
+            read_only: profile.read_only,
+        })
+        .collect()
+}
+
+/// 确定启动时使用的档案
+///
+/// 优先级：启动参数 `--profile <名称>` / `--profile=<名称>` > 环境变量 > 保存的当前档案；
+/// 指定的档案不存在时回退到保存的当前档案，当前档案也不存在时使用默认档案
+pub fn startup_profile(config: &ProfilesConfig, args: &[String]) -> String {
+    let from_args = args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == PROFILE_ARG {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(PROFILE_ARG)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(str::to_string)
+        }
+    });
+    let requested = from_args.or_else(|| std::env::var(PROFILE_ENV).ok());
+    if let Some(name) = requested {
+        if config.get(&name).is_some() {
+            return name;
+        }
+        tracing::warn!("启动档案不存在，使用当前档案: {}", name);
+    }
+    // 保存的当前档案已被手动移除时回退到默认档案
+    let active = config.active_name();
+    if config.get(active).is_some() {
+        active.to_string()
+    } else {
+        DEFAULT_PROFILE_NAME.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str) -> Profile {
+        Profile {
+            name: name.to_string(),
+            watch_paths: vec![format!("/home/user/{}/.claude", name)],
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn test_profiles_config_upsert_and_remove() {
+        let mut config = ProfilesConfig::default();
+        assert_eq!(config.active_name(), DEFAULT_PROFILE_NAME);
+        assert!(config.get(DEFAULT_PROFILE_NAME).is_some());
+        assert!(config.get("work").is_none());
+
+        config.upsert(profile("work")).expect("work");
+        config.upsert(profile("personal")).expect("personal");
+        assert!(matches!(
+            config.upsert(profile("../etc")),
+            Err(ProfileError::InvalidName(_))
+        ));
+        let names: Vec<_> = config.all().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, [DEFAULT_PROFILE_NAME, "personal", "work"]);
+
+        config.active = Some("work".to_string());
+        assert!(matches!(
+            config.remove("work"),
+            Err(ProfileError::ActiveProfile(_))
+        ));
+        assert!(matches!(
+            config.remove(DEFAULT_PROFILE_NAME),
+            Err(ProfileError::DefaultProfile)
+        ));
+        config.remove("personal").expect("remove");
+        assert!(matches!(
+            config.remove("personal"),
+            Err(ProfileError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_profile_paths_and_startup_selection() {
+        let dir = std::env::temp_dir().join(format!("db-profiles-{}", std::process::id()));
+        let mut config = ProfilesConfig::default();
+        config.upsert(profile("work")).expect("work");
+        config.save(&dir).expect("save");
+        let config = ProfilesConfig::load(&dir);
+        assert_eq!(config.get("work"), Some(profile("work")));
+
+        assert_eq!(
+            profile_db_path(&dir, "work"),
+            dir.join(PROFILES_DIR).join("work").join(DB_FILE_NAME)
+        );
+        let infos = profile_infos(&dir, &config, "work");
+        assert_eq!(infos.len(), 2);
+        assert!(!infos[0].active);
+        assert!(infos[1].active);
+
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            startup_profile(&config, &args(&["app", "--profile", "work"])),
+            "work"
+        );
+        assert_eq!(
+            startup_profile(&config, &args(&["app", "--profile=work"])),
+            "work"
+        );
+        assert_eq!(
+            startup_profile(&config, &args(&["app", "--profile", "missing"])),
+            DEFAULT_PROFILE_NAME
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -2,15 +2,44 @@
 //! @description 数据仓库层，封装 SQLite 操作
 //! @author Atlas.oi
 //! @date 2026-01-08
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::{Mutex, MutexGuard};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
-use chrono::{Local, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use chrono::{DateTime, Local, Utc};
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::{params, Batch, Connection, OptionalExtension};
 use thiserror::Error;
 
-use crate::db::migrations::apply_migrations;
-use crate::models::{DailyActivity, ModelUsage, Provider, ProviderStats, StatsCache, TodayStats};
+use crate::db::migrations::{apply_migrations, current_version};
+use crate::db::schema::{REMASK_API_KEY_PREFIXES, SYNC_MODEL_THINKING_TOKENS};
+use crate::models::saved_query::{MAX_QUERY_ROWS, MAX_QUERY_SECONDS};
+use crate::models::settings::AppSetting;
+use crate::models::trend::MAX_ROLLING_WINDOW_DAYS;
+use crate::models::webhook::MAX_WEBHOOK_DELIVERIES;
+use crate::models::{
+    AlertChannel, AlertComparator, AlertMetric, AlertRule, AlertRuleInput, AlertWindow,
+    AllocationGroupBy, ApiErrorEvent, ApiErrorStats, BalanceAmounts, BlockEntry,
+    CacheHitRateSettings, CompactionEvent, CompactionStats, CostAllocationRow, CostAnomaly,
+    CostRecalculation, DailyActivity, DailyDiscardedUsage, DailyStatsTotals, DateNote,
+    DedupePolicy, DedupeSettings, DeletedItem, DeletedItemKind, DeliveryChannel, DiscardedUsage,
+    DuplicateGroup, DuplicateKind, Goal, GoalDirection, GoalEvaluation, GoalInput, GoalMetric,
+    GoalPeriod, GoalUsageTotals, HeatmapCell, InterruptEvent, InterruptStats, IntervalUsage,
+    KeychainSettings, LatencySample, MessageSearchFilters, MessageSearchPage, MessageTokenSample,
+    ModelAlias, ModelDailyUsage, ModelOutputProfile, ModelUsage, NotificationKind,
+    NotificationRecord, OfficialUsage, PlanType, PrivacySettings, Provider, ProviderBalance,
+    ProviderComparison, ProviderComparisonPoint, ProviderKind, ProviderPlan, ProviderStats,
+    QueryResult, QueryVisualization, QuickStats, RateLimitEvent, RateLimitStats, RestoredItem,
+    RollingAveragePoint, SavedQuery, ServiceTier, ServiceTierUsage, SessionCompaction,
+    SessionDiscardedUsage, SessionOrder, SessionSample, SessionSummary, SessionTitleSource,
+    Snapshot, SnapshotApiError, SnapshotImportSummary, SnapshotMessage, SnapshotProvider,
+    SnapshotProviderPlan, SnapshotProviderSwitch, SnapshotRateLimitEvent, SnapshotSession,
+    SnapshotTag, StatsCache, StatsFilters, StoredMessage, TagStats, TagTarget, TodayStats,
+    ToolCall, ToolCallStats, TriggeredAlert, UsageDriftPoint, UsageWindowTotals, WebhookDelivery,
+    WebhookDeliveryStatus, WebhookEvent, WebhookFormat, WebhookTarget, WebhookTargetInput,
+    UNATTRIBUTED_PROVIDER_KEY, UNATTRIBUTED_PROVIDER_NAME,
+};
 
 #[derive(Error, Debug)]
 pub enum RepositoryError {
@@ -18,37 +47,125 @@ pub enum RepositoryError {
     Database(#[from] rusqlite::Error),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] serde_json::Error),
     #[error("Database lock poisoned")]
     LockPoisoned,
+    #[error("只允许执行单条只读查询（SELECT / WITH）")]
+    NotReadOnly,
+    #[error("只读查询不能访问应用设置与 Webhook 目标表")]
+    RestrictedTable,
+    #[error("只读查询执行超过 {0} 秒，已中断")]
+    QueryTimeout(u64),
+    #[error("数据库迁移失败: {0}")]
+    Relocate(String),
 }
 
+/// 数据仓库
+///
+/// 克隆开销很小，克隆后的实例共享同一数据库连接与总体统计缓存，可交给后台线程使用
+#[derive(Clone)]
 pub struct Repository {
-    conn: Mutex<Connection>,
-    _path: PathBuf,
+    conn: Arc<Mutex<Connection>>,
+    path: Arc<Mutex<PathBuf>>,
+    stats_cache: Arc<Mutex<Option<CurrentStatsCache>>>,
 }
 
-impl Repository {
-    pub fn new(db_path: &Path) -> Result<Self, RepositoryError> {
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent)?;
+/// 总体统计的内存缓存
+///
+/// 首次读取时从数据库汇总，之后每写入一条消息增量累加；
+/// 重算费用、删除数据、重建汇总等批量修改后失效，下次读取时重新汇总。
+/// 缓存只在持有连接锁时读写（先连接锁后缓存锁），与数据库内容保持一致
+struct CurrentStatsCache {
+    stats: StatsCache,
+
+    /// 已出现过的会话 ID，用于增量统计去重会话数
+    sessions: HashSet<String>,
+
+    /// 小组件轮询用的单日汇总（日期, 费用, Token 总数），首次查询该日期时从 daily_stats 读取
+    day_totals: Option<(String, f64, i64)>,
+
+    /// 区块计算用的近期消息（起始时间, 按时间升序的消息），首次查询时从 message_usage 读取
+    block_entries: Option<(DateTime<Utc>, Vec<BlockEntry>)>,
+}
+
+impl CurrentStatsCache {
+    /// 累加一条新写入的消息，cost_usd 为实际计入的费用（订阅供应商为 0）
+    fn record(&mut self, record: &crate::models::MessageRecord, cost_usd: f64) {
+        let stats = &mut self.stats;
+        stats.total_input_tokens += record.usage.input_tokens;
+        stats.total_output_tokens += record.usage.output_tokens;
+        stats.total_cache_read_tokens += record.usage.cache_read_tokens;
+        stats.total_cache_creation_tokens += record.usage.cache_creation_tokens;
+        stats.total_cost_usd += cost_usd;
+        stats.total_api_equivalent_cost_usd += record.usage.cost_usd;
+        stats.total_messages += 1;
+        if self.sessions.insert(record.session_id.clone()) {
+            stats.total_sessions += 1;
         }
+        // 与 model_daily_stats 一致，按模型的费用同样使用实际计入的费用
+        stats.add_or_update_model(ModelUsage {
+            model: record.model.clone(),
+            input_tokens: record.usage.input_tokens,
+            output_tokens: record.usage.output_tokens,
+            cache_read_tokens: record.usage.cache_read_tokens,
+            cache_creation_tokens: record.usage.cache_creation_tokens,
+            cost_usd,
+            message_count: 1,
+            thinking_tokens: record.usage.thinking_tokens,
+        });
 
-        let conn = Connection::open(db_path)?;
-        apply_migrations(&conn)?;
+        let total_tokens = record.usage.input_tokens
+            + record.usage.output_tokens
+            + record.usage.cache_read_tokens
+            + record.usage.cache_creation_tokens;
+        if let Some((date, day_cost_usd, day_tokens)) = self.day_totals.as_mut() {
+            if *date == extract_date(&record.created_at) {
+                *day_cost_usd += cost_usd;
+                *day_tokens += total_tokens;
+            }
+        }
+        if let (Some((since, entries)), Some(created_at)) = (
+            self.block_entries.as_mut(),
+            parse_timestamp(&record.created_at),
+        ) {
+            if created_at >= *since {
+                let index = entries.partition_point(|entry| {
+                    parse_timestamp(&entry.created_at).is_some_and(|at| at <= created_at)
+                });
+                entries.insert(
+                    index,
+                    BlockEntry {
+                        created_at: record.created_at.clone(),
+                        total_tokens,
+                        cost_usd,
+                    },
+                );
+            }
+        }
+    }
+}
+
+impl Repository {
+    pub fn new(db_path: &Path) -> Result<Self, RepositoryError> {
+        let conn = open_connection(db_path)?;
 
         Ok(Self {
-            conn: Mutex::new(conn),
-            _path: db_path.to_path_buf(),
+            conn: Arc::new(Mutex::new(conn)),
+            path: Arc::new(Mutex::new(db_path.to_path_buf())),
+            stats_cache: Arc::new(Mutex::new(None)),
         })
     }
 
     pub fn new_in_memory() -> Result<Self, RepositoryError> {
         let conn = Connection::open_in_memory()?;
         apply_migrations(&conn)?;
+        enable_foreign_keys(&conn)?;
 
         Ok(Self {
-            conn: Mutex::new(conn),
-            _path: PathBuf::from(":memory:"),
+            conn: Arc::new(Mutex::new(conn)),
+            path: Arc::new(Mutex::new(PathBuf::from(":memory:"))),
+            stats_cache: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -56,10 +173,150 @@ impl Repository {
         self.conn.lock().map_err(|_| RepositoryError::LockPoisoned)
     }
 
+    /// 总体统计缓存，调用方必须已持有连接锁
+    fn stats_cache(&self) -> Result<MutexGuard<'_, Option<CurrentStatsCache>>, RepositoryError> {
+        self.stats_cache
+            .lock()
+            .map_err(|_| RepositoryError::LockPoisoned)
+    }
+
+    /// 使总体统计缓存失效，在持有连接锁、修改数据之前调用
+    fn invalidate_stats_cache(&self) -> Result<(), RepositoryError> {
+        *self.stats_cache()? = None;
+        Ok(())
+    }
+
+    /// 数据库文件路径（内存数据库为 `:memory:`）
+    pub fn path(&self) -> PathBuf {
+        self.path
+            .lock()
+            .map(|path| path.clone())
+            .unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    /// 切换到另一个数据库文件（不存在时创建），返回原数据库路径
+    ///
+    /// 新库先完成打开与迁移再替换共享连接，失败时保持原连接不变；
+    /// 所有克隆的仓库实例同时切换
+    pub fn reopen(&self, db_path: &Path) -> Result<PathBuf, RepositoryError> {
+        let opened = open_connection(db_path)?;
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        *conn = opened;
+        let mut path = self
+            .path
+            .lock()
+            .map_err(|_| RepositoryError::LockPoisoned)?;
+        Ok(std::mem::replace(&mut *path, db_path.to_path_buf()))
+    }
+
+    /// 将数据库迁移到 new_path 并切换连接，返回原数据库路径
+    ///
+    /// 业务逻辑说明：
+    /// 1. 持有连接锁期间用 VACUUM INTO 生成一致的副本，期间不会有新的写入
+    /// 2. 校验副本：完整性检查通过，且迁移版本与消息数和原库一致；失败时删除副本
+    /// 3. 校验通过后替换共享连接与路径，所有克隆的仓库实例同时切换到新库
+    ///
+    /// 原数据库文件保持不动，由调用方在保存新位置后删除
+    pub fn relocate(&self, new_path: &Path) -> Result<PathBuf, RepositoryError> {
+        let old_path = self.path();
+        if new_path == old_path {
+            return Err(RepositoryError::Relocate(
+                "新位置与当前位置相同".to_string(),
+            ));
+        }
+        if new_path.exists() {
+            return Err(RepositoryError::Relocate(format!(
+                "目标文件已存在: {}",
+                new_path.display()
+            )));
+        }
+        if let Some(parent) = new_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut conn = self.connection()?;
+        conn.execute("VACUUM INTO ?1", [new_path.to_string_lossy()])?;
+        let copied = match verify_copy(&conn, new_path) {
+            Ok(copied) => copied,
+            Err(e) => {
+                let _ = std::fs::remove_file(new_path);
+                return Err(e);
+            }
+        };
+        *conn = copied;
+        *self
+            .path
+            .lock()
+            .map_err(|_| RepositoryError::LockPoisoned)? = new_path.to_path_buf();
+        tracing::info!(
+            "数据库已迁移: {} -> {}",
+            old_path.display(),
+            new_path.display()
+        );
+        Ok(old_path)
+    }
+
+    /// 退出前关闭数据库
+    ///
+    /// 连接由多个服务共享，无法在此真正释放；改为等待当前写入结束（获取连接锁）后
+    /// 执行 WAL 检查点并整理查询统计，使退出后的数据库文件无需恢复即可直接打开
+    pub fn close(&self) -> Result<(), RepositoryError> {
+        let conn = self.connection()?;
+        // 非 WAL 模式下检查点为空操作，返回值无需关心
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        conn.execute_batch("PRAGMA optimize")?;
+        Ok(())
+    }
+
+    /// 当前已应用的最高迁移版本
+    pub fn schema_version(&self) -> Result<i64, RepositoryError> {
+        let conn = self.connection()?;
+        current_version(&conn).map_err(RepositoryError::from)
+    }
+
     pub fn upsert_provider(
         &self,
         api_key: &str,
         base_url: Option<String>,
+    ) -> Result<Provider, RepositoryError> {
+        self.activate_provider(api_key, base_url, ProviderKind::ApiKey, None)
+    }
+
+    /// 记录 API Key 供应商并设为当前供应商，default_name 作为尚未命名时的默认显示名称
+    pub fn upsert_provider_with_default_name(
+        &self,
+        api_key: &str,
+        base_url: Option<String>,
+        default_name: Option<String>,
+    ) -> Result<Provider, RepositoryError> {
+        self.activate_provider(api_key, base_url, ProviderKind::ApiKey, default_name)
+    }
+
+    /// 记录通过 claude.ai OAuth 登录的订阅账号并设为当前供应商
+    ///
+    /// 订阅账号没有 API Key，以 `oauth:<账号 UUID>` 作为标识计算哈希与前缀
+    pub fn upsert_subscription_provider(
+        &self,
+        account_id: &str,
+        display_name: &str,
+    ) -> Result<Provider, RepositoryError> {
+        self.activate_provider(
+            &format!("oauth:{}", account_id),
+            None,
+            ProviderKind::Subscription,
+            Some(display_name.to_string()),
+        )
+    }
+
+    /// 将标识对应的供应商设为唯一的活跃供应商，不存在时创建；
+    /// display_name 只在创建或尚未命名时使用，不覆盖用户修改过的名称
+    fn activate_provider(
+        &self,
+        api_key: &str,
+        base_url: Option<String>,
+        kind: ProviderKind,
+        display_name: Option<String>,
     ) -> Result<Provider, RepositoryError> {
         let now = Utc::now().to_rfc3339();
         let conn = self.connection()?;
@@ -70,12 +327,15 @@ impl Repository {
 
         if let Some(existing) = provider.as_mut() {
             conn.execute(
-                "UPDATE providers SET last_seen_at = ?1, base_url = COALESCE(?2, base_url), is_active = 1 WHERE id = ?3",
-                params![now, base_url.clone(), existing.id],
+                "UPDATE providers SET last_seen_at = ?1, base_url = COALESCE(?2, base_url), display_name = COALESCE(display_name, ?3), is_active = 1 WHERE id = ?4",
+                params![now, base_url.clone(), display_name.clone(), existing.id],
             )?;
 
             existing.last_seen_at = now;
             existing.is_active = true;
+            if existing.display_name.is_none() {
+                existing.display_name = display_name;
+            }
             if let Some(url) = base_url {
                 existing.base_url = Some(url);
             }
@@ -83,13 +343,16 @@ impl Repository {
             return Ok(existing.clone());
         }
 
-        let mut new_provider = Provider::new(api_key, None, base_url.clone());
+        let mut new_provider = Provider::new(api_key, display_name, base_url.clone());
+        new_provider.kind = kind;
+        let privacy: PrivacySettings = read_setting(&conn)?;
+        new_provider.api_key_prefix = privacy.mask_api_key_prefix(&new_provider.api_key_prefix);
         new_provider.first_seen_at = now.clone();
         new_provider.last_seen_at = now;
 
         conn.execute(
-            "INSERT INTO providers (api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO providers (api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 new_provider.api_key_hash,
                 new_provider.api_key_prefix,
@@ -97,7 +360,8 @@ impl Repository {
                 new_provider.base_url,
                 1,
                 new_provider.first_seen_at,
-                new_provider.last_seen_at
+                new_provider.last_seen_at,
+                new_provider.kind.as_str()
             ],
         )?;
 
@@ -127,6 +391,8 @@ impl Repository {
         }
 
         let mut new_provider = Provider::new(api_key, display_name, None);
+        let privacy: PrivacySettings = read_setting(&conn)?;
+        new_provider.api_key_prefix = privacy.mask_api_key_prefix(&new_provider.api_key_prefix);
         // 手动添加的默认为非活跃，避免干扰当前 CLI 状态
         new_provider.is_active = false;
         new_provider.first_seen_at = now.clone();
@@ -156,12 +422,12 @@ impl Repository {
 
         let mut stmt = if active_only {
             conn.prepare(
-                "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at
+                "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind
                  FROM providers WHERE is_active = 1 ORDER BY last_seen_at DESC",
             )?
         } else {
             conn.prepare(
-                "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at
+                "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind
                  FROM providers ORDER BY last_seen_at DESC",
             )?
         };
@@ -176,6 +442,7 @@ impl Repository {
                 is_active: row.get::<_, i64>(5)? == 1,
                 first_seen_at: row.get(6)?,
                 last_seen_at: row.get(7)?,
+                kind: ProviderKind::from_db(&row.get::<_, String>(8)?),
             })
         })?;
 
@@ -186,6 +453,31 @@ impl Repository {
         Ok(providers)
     }
 
+    /// 按 ID 获取供应商，不存在时返回 None
+    pub fn get_provider(&self, provider_id: i64) -> Result<Option<Provider>, RepositoryError> {
+        let conn = self.connection()?;
+        conn.query_row(
+            "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind
+             FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| {
+                Ok(Provider {
+                    id: row.get(0)?,
+                    api_key_hash: row.get(1)?,
+                    api_key_prefix: row.get(2)?,
+                    display_name: row.get(3)?,
+                    base_url: row.get(4)?,
+                    is_active: row.get::<_, i64>(5)? == 1,
+                    first_seen_at: row.get(6)?,
+                    last_seen_at: row.get(7)?,
+                    kind: ProviderKind::from_db(&row.get::<_, String>(8)?),
+                })
+            },
+        )
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+
     pub fn update_provider_display_name(
         &self,
         provider_id: i64,
@@ -199,44 +491,261 @@ impl Repository {
         Ok(())
     }
 
-    pub fn delete_provider(&self, provider_id: i64) -> Result<(), RepositoryError> {
-        let conn = self.connection()?;
-        conn.execute(
-            "DELETE FROM message_usage WHERE provider_id = ?1",
-            params![provider_id],
+    /// 删除供应商，连同其消息与汇总移入回收站
+    ///
+    /// 业务逻辑说明：
+    /// 1. 先将供应商及其消息、套餐、切换记录与事件导出为快照保存到回收站
+    /// 2. 再删除供应商，其全部关联记录由外键级联删除；余额配置与工具调用等记录不进入回收站
+    /// 3. 保留期内可通过 restore_deleted 恢复，过期后由 purge_deleted_items 彻底清除
+    ///
+    /// # 返回
+    /// 供应商存在时返回回收站条目
+    pub fn delete_provider(
+        &self,
+        provider_id: i64,
+    ) -> Result<Option<DeletedItem>, RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+        let snapshot = export_provider_data(&tx, Some(provider_id))?;
+        let Some(provider) = snapshot.providers.first() else {
+            return Ok(None);
+        };
+        let label = provider
+            .display_name
+            .clone()
+            .unwrap_or_else(|| provider.api_key_prefix.clone());
+        let message_count = snapshot.messages.len() as i64;
+        let deleted_at = Utc::now().to_rfc3339();
+        tx.execute(
+            "INSERT INTO deleted_items (kind, source_id, label, message_count, payload, deleted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                DeletedItemKind::Provider.as_str(),
+                provider_id,
+                label,
+                message_count,
+                serde_json::to_string(&snapshot)?,
+                deleted_at
+            ],
         )?;
-        conn.execute(
-            "DELETE FROM daily_stats WHERE provider_id = ?1",
-            params![provider_id],
+        let item = DeletedItem {
+            id: tx.last_insert_rowid(),
+            kind: DeletedItemKind::Provider,
+            source_id: provider_id,
+            label,
+            message_count,
+            deleted_at,
+        };
+
+        // 消息、汇总、套餐、事件等关联记录由外键级联删除
+        tx.execute("DELETE FROM providers WHERE id = ?1", params![provider_id])?;
+        tx.commit()?;
+        Ok(Some(item))
+    }
+
+    /// 删除供应商，消息归并到"未归属"供应商，全局总量保持不变
+    ///
+    /// 业务逻辑说明：
+    /// 1. 未归属供应商不存在时创建，不设为当前供应商
+    /// 2. 消息改挂到未归属供应商；与未归属供应商已有记录重复的消息无法归并，随供应商一起删除
+    /// 3. 删除供应商本身，其余关联记录由外键级联删除（不进入回收站），再从消息重建每日汇总
+    ///
+    /// # 返回
+    /// 归并的消息条数；供应商不存在或本身就是未归属供应商时返回 None
+    pub fn delete_provider_reassigning(
+        &self,
+        provider_id: i64,
+    ) -> Result<Option<usize>, RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+        let unattributed = Provider::new(
+            UNATTRIBUTED_PROVIDER_KEY,
+            Some(UNATTRIBUTED_PROVIDER_NAME.to_string()),
+            None,
+        );
+        let hash: Option<String> = tx
+            .query_row(
+                "SELECT api_key_hash FROM providers WHERE id = ?1",
+                params![provider_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if hash.is_none_or(|hash| hash == unattributed.api_key_hash) {
+            return Ok(None);
+        }
+
+        tx.execute(
+            "INSERT INTO providers (api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind)
+             VALUES (?1, ?2, ?3, NULL, 0, ?4, ?4, ?5)
+             ON CONFLICT(api_key_hash) DO NOTHING",
+            params![
+                unattributed.api_key_hash,
+                unattributed.api_key_prefix,
+                unattributed.display_name,
+                unattributed.first_seen_at,
+                unattributed.kind.as_str()
+            ],
         )?;
-        conn.execute(
-            "DELETE FROM provider_switch_logs WHERE provider_id = ?1",
-            params![provider_id],
+        let unattributed_id: i64 = tx.query_row(
+            "SELECT id FROM providers WHERE api_key_hash = ?1",
+            params![unattributed.api_key_hash],
+            |row| row.get(0),
         )?;
-        conn.execute("DELETE FROM providers WHERE id = ?1", params![provider_id])?;
-        Ok(())
+
+        let reassigned = tx.execute(
+            "UPDATE OR IGNORE message_usage SET provider_id = ?2 WHERE provider_id = ?1",
+            params![provider_id, unattributed_id],
+        )?;
+        // 消息、汇总、套餐、事件等关联记录由外键级联删除
+        tx.execute("DELETE FROM providers WHERE id = ?1", params![provider_id])?;
+        rebuild_daily_stats_in(&tx)?;
+        tx.commit()?;
+        Ok(Some(reassigned))
+    }
+
+    /// 获取回收站条目，按删除时间倒序
+    pub fn get_deleted_items(&self) -> Result<Vec<DeletedItem>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all(
+            &conn,
+            "SELECT id, kind, source_id, label, message_count, deleted_at
+             FROM deleted_items
+             ORDER BY deleted_at DESC, id DESC",
+            deleted_item_from_row,
+        )
+    }
+
+    /// 从回收站恢复条目
+    ///
+    /// 快照按当前去重策略合并回数据库，已存在的消息不会重复写入；恢复后从回收站移除
+    ///
+    /// # 返回
+    /// 条目存在时返回恢复结果
+    pub fn restore_deleted(&self, id: i64) -> Result<Option<RestoredItem>, RepositoryError> {
+        let policy = self.get_setting::<DedupeSettings>()?.policy;
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+        let deleted = tx
+            .query_row(
+                "SELECT id, kind, source_id, label, message_count, deleted_at, payload
+                 FROM deleted_items WHERE id = ?1",
+                params![id],
+                |row| Ok((deleted_item_from_row(row)?, row.get::<_, String>(6)?)),
+            )
+            .optional()?;
+        let Some((item, payload)) = deleted else {
+            return Ok(None);
+        };
+
+        let snapshot: Snapshot = serde_json::from_str(&payload)?;
+        // 重复消息本身就与其他供应商的记录重复，按全局去重恢复会被全部跳过
+        let policy = match item.kind {
+            DeletedItemKind::Provider => policy,
+            DeletedItemKind::DuplicateMessages => DedupePolicy::PerProvider,
+        };
+        let summary = import_snapshot_in(&tx, &snapshot, policy)?;
+        if summary.messages_added > 0 {
+            rebuild_daily_stats_in(&tx)?;
+        }
+        let restored_id = match (item.kind, snapshot.providers.first()) {
+            (DeletedItemKind::Provider, Some(provider)) => tx.query_row(
+                "SELECT id FROM providers WHERE api_key_hash = ?1",
+                params![provider.api_key_hash],
+                |row| row.get(0),
+            )?,
+            _ => item.source_id,
+        };
+        tx.execute("DELETE FROM deleted_items WHERE id = ?1", params![id])?;
+        tx.commit()?;
+
+        Ok(Some(RestoredItem {
+            item,
+            restored_id,
+            messages_restored: summary.messages_added,
+        }))
+    }
+
+    /// 彻底清除删除时间早于 before（RFC 3339）的回收站条目
+    ///
+    /// # 返回
+    /// 被清除的条目
+    pub fn purge_deleted_items(&self, before: &str) -> Result<Vec<DeletedItem>, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        let purged = query_all_with(
+            &tx,
+            "SELECT id, kind, source_id, label, message_count, deleted_at
+             FROM deleted_items
+             WHERE deleted_at < ?1
+             ORDER BY id",
+            params![before],
+            deleted_item_from_row,
+        )?;
+        tx.execute(
+            "DELETE FROM deleted_items WHERE deleted_at < ?1",
+            params![before],
+        )?;
+        tx.commit()?;
+        Ok(purged)
     }
 
+    /// 写入一条消息用量并累加每日汇总（使用默认去重策略）
     pub fn insert_message_usage(
         &self,
         provider_id: i64,
         record: &crate::models::MessageRecord,
     ) -> Result<(), RepositoryError> {
-        let conn = self.connection()?;
+        self.insert_message_usage_with_policy(provider_id, record, DedupePolicy::default())
+    }
+
+    /// 按指定去重策略写入一条消息用量并累加每日汇总
+    ///
+    /// 同一供应商下的重复消息由 message_usage 唯一索引忽略；
+    /// Global 策略下，消息已记录在其他供应商名下时同样跳过。
+    /// 重复消息不会写入，也不会累加汇总；消息写入与汇总更新在同一事务中完成
+    pub fn insert_message_usage_with_policy(
+        &self,
+        provider_id: i64,
+        record: &crate::models::MessageRecord,
+        policy: DedupePolicy,
+    ) -> Result<(), RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        if policy == DedupePolicy::Global {
+            let recorded_elsewhere: Option<i64> = tx
+                .query_row(
+                    "SELECT 1 FROM message_usage
+                     WHERE message_id = ?1 AND substr(created_at, 1, 10) = substr(?2, 1, 10) AND provider_id != ?3
+                     LIMIT 1",
+                    params![record.message_id, record.created_at, provider_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if recorded_elsewhere.is_some() {
+                return Ok(());
+            }
+        }
 
-        let message_exists: Option<i64> = conn
+        // 订阅供应商的实际费用为 0，消息费用只计入 API 等价费用
+        let kind: Option<String> = tx
             .query_row(
-                "SELECT 1 FROM message_usage WHERE provider_id = ?1 AND message_id = ?2 LIMIT 1",
-                params![provider_id, record.message_id],
+                "SELECT kind FROM providers WHERE id = ?1",
+                params![provider_id],
                 |row| row.get(0),
             )
             .optional()?;
-        if message_exists.is_some() {
-            return Ok(());
-        }
+        let api_equivalent_cost_usd = record.usage.cost_usd;
+        let cost_usd = match kind.as_deref().map(ProviderKind::from_db) {
+            Some(ProviderKind::Subscription) => 0.0,
+            _ => record.usage.cost_usd,
+        };
 
         let date = extract_date(&record.created_at);
-        let session_exists: Option<i64> = conn
+        let session_exists: Option<i64> = tx
             .query_row(
                 "SELECT 1 FROM message_usage WHERE provider_id = ?1 AND session_id = ?2 AND date(created_at) = ?3 LIMIT 1",
                 params![provider_id, record.session_id, date],
@@ -245,9 +754,10 @@ impl Repository {
             .optional()?;
         let session_increment = if session_exists.is_some() { 0 } else { 1 };
 
-        conn.execute(
-            "INSERT INTO message_usage (provider_id, session_id, message_id, model, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        let inserted = tx.execute(
+            "INSERT INTO message_usage (provider_id, session_id, message_id, model, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at, project, duration_ms, api_equivalent_cost_usd, thinking_tokens, service_tier)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+             ON CONFLICT DO NOTHING",
             params![
                 provider_id,
                 record.session_id,
@@ -257,14 +767,22 @@ impl Repository {
                 record.usage.output_tokens,
                 record.usage.cache_read_tokens,
                 record.usage.cache_creation_tokens,
-                record.usage.cost_usd,
-                record.created_at
+                cost_usd,
+                record.created_at,
+                record.project,
+                record.duration_ms,
+                api_equivalent_cost_usd,
+                record.usage.thinking_tokens,
+                record.service_tier.as_str()
             ],
         )?;
+        if inserted == 0 {
+            return Ok(());
+        }
 
-        conn.execute(
-            "INSERT INTO daily_stats (provider_id, date, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, session_count, message_count)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        tx.execute(
+            "INSERT INTO daily_stats (provider_id, date, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, session_count, message_count, total_api_equivalent_cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
              ON CONFLICT(provider_id, date) DO UPDATE SET
                 total_input_tokens = total_input_tokens + excluded.total_input_tokens,
                 total_output_tokens = total_output_tokens + excluded.total_output_tokens,
@@ -272,7 +790,8 @@ impl Repository {
                 total_cache_creation_tokens = total_cache_creation_tokens + excluded.total_cache_creation_tokens,
                 total_cost_usd = total_cost_usd + excluded.total_cost_usd,
                 session_count = session_count + excluded.session_count,
-                message_count = message_count + excluded.message_count",
+                message_count = message_count + excluded.message_count,
+                total_api_equivalent_cost_usd = total_api_equivalent_cost_usd + excluded.total_api_equivalent_cost_usd",
             params![
                 provider_id,
                 date,
@@ -280,12 +799,42 @@ impl Repository {
                 record.usage.output_tokens,
                 record.usage.cache_read_tokens,
                 record.usage.cache_creation_tokens,
-                record.usage.cost_usd,
+                cost_usd,
                 session_increment,
                 1,
+                api_equivalent_cost_usd,
+            ],
+        )?;
+
+        tx.execute(
+            "INSERT INTO model_daily_stats (provider_id, date, model, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, message_count, total_thinking_tokens)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(provider_id, date, model) DO UPDATE SET
+                total_input_tokens = total_input_tokens + excluded.total_input_tokens,
+                total_output_tokens = total_output_tokens + excluded.total_output_tokens,
+                total_cache_read_tokens = total_cache_read_tokens + excluded.total_cache_read_tokens,
+                total_cache_creation_tokens = total_cache_creation_tokens + excluded.total_cache_creation_tokens,
+                total_cost_usd = total_cost_usd + excluded.total_cost_usd,
+                message_count = message_count + excluded.message_count,
+                total_thinking_tokens = total_thinking_tokens + excluded.total_thinking_tokens",
+            params![
+                provider_id,
+                date,
+                record.model,
+                record.usage.input_tokens,
+                record.usage.output_tokens,
+                record.usage.cache_read_tokens,
+                record.usage.cache_creation_tokens,
+                cost_usd,
+                1,
+                record.usage.thinking_tokens,
             ],
         )?;
 
+        tx.commit()?;
+        if let Some(cache) = self.stats_cache()?.as_mut() {
+            cache.record(record, cost_usd);
+        }
         Ok(())
     }
 
@@ -293,7 +842,7 @@ impl Repository {
         let conn = self.connection()?;
 
         conn.query_row(
-            "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at
+            "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind
              FROM providers WHERE is_active = 1 ORDER BY last_seen_at DESC LIMIT 1",
             [],
             |row| {
@@ -306,6 +855,7 @@ impl Repository {
                     is_active: row.get::<_, i64>(5)? == 1,
                     first_seen_at: row.get(6)?,
                     last_seen_at: row.get(7)?,
+                    kind: ProviderKind::from_db(&row.get::<_, String>(8)?),
                 })
             },
         )
@@ -313,76 +863,177 @@ impl Repository {
         .map_err(RepositoryError::from)
     }
 
+    /// 总体统计，优先读取内存缓存，缓存失效时重新汇总
     pub fn get_current_stats(&self) -> Result<StatsCache, RepositoryError> {
         let conn = self.connection()?;
+        let mut cached = self.stats_cache()?;
+        let cache = match cached.as_mut() {
+            Some(cache) => cache,
+            None => cached.insert(load_current_stats(&conn)?),
+        };
 
-        let mut cache = StatsCache::default();
+        let mut stats = cache.stats.clone();
+        let cache_hit_rate: CacheHitRateSettings = read_setting(&conn)?;
+        stats.update_cache_hit_rate(cache_hit_rate.formula);
+        stats.sort_models_by_cost();
+        Ok(stats)
+    }
 
-        let mut stmt = conn.prepare(
-            "SELECT
-                COALESCE(SUM(input_tokens), 0),
-                COALESCE(SUM(output_tokens), 0),
-                COALESCE(SUM(cache_read_tokens), 0),
-                COALESCE(SUM(cache_creation_tokens), 0),
-                COALESCE(SUM(cost_usd), 0),
-                COALESCE(COUNT(DISTINCT session_id), 0),
-                COALESCE(COUNT(*), 0)
-             FROM message_usage",
-        )?;
+    /// 按供应商 / 模型 / 项目过滤的总体统计
+    ///
+    /// 未设置过滤条件时与 get_current_stats 相同，否则直接从 message_usage 汇总
+    pub fn get_current_stats_filtered(
+        &self,
+        filters: &StatsFilters,
+    ) -> Result<StatsCache, RepositoryError> {
+        if filters.is_empty() {
+            return self.get_current_stats();
+        }
 
-        let totals: (i64, i64, i64, i64, f64, i64, i64) = stmt.query_row([], |row| {
-            Ok((
-                row.get(0)?,
-                row.get(1)?,
-                row.get(2)?,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-                row.get(6)?,
-            ))
-        })?;
+        let conn = self.connection()?;
+        let mut cache = aggregate_filtered_stats(&conn, filters, None, None)?;
 
-        cache.total_input_tokens = totals.0;
-        cache.total_output_tokens = totals.1;
-        cache.total_cache_read_tokens = totals.2;
-        cache.total_cache_creation_tokens = totals.3;
-        cache.total_cost_usd = totals.4;
-        cache.total_sessions = totals.5;
-        cache.total_messages = totals.6;
+        let cache_hit_rate: CacheHitRateSettings = read_setting(&conn)?;
+        cache.update_cache_hit_rate(cache_hit_rate.formula);
+        cache.sort_models_by_cost();
+        Ok(cache)
+    }
 
-        let mut stmt = conn.prepare(
-            "SELECT model, SUM(input_tokens), SUM(output_tokens), SUM(cache_read_tokens), SUM(cache_creation_tokens), SUM(cost_usd), COUNT(*)
-             FROM message_usage GROUP BY model",
-        )?;
+    /// 获取任意日期范围（本地日期，包含两端）内的汇总统计
+    ///
+    /// 仅按供应商过滤时从 daily_stats / model_daily_stats 汇总；
+    /// 汇总表不区分项目，且按模型汇总的表没有 API 等价费用，因此按模型或项目过滤时回退到 message_usage
+    pub fn get_range_stats(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        filters: &StatsFilters,
+    ) -> Result<StatsCache, RepositoryError> {
+        let conn = self.connection()?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok(ModelUsage {
-                model: row.get(0)?,
-                input_tokens: row.get(1)?,
-                output_tokens: row.get(2)?,
-                cache_read_tokens: row.get(3)?,
-                cache_creation_tokens: row.get(4)?,
-                cost_usd: row.get(5)?,
-                message_count: row.get(6)?,
-            })
-        })?;
+        let mut cache = if filters.model.is_some() || filters.project.is_some() {
+            aggregate_filtered_stats(&conn, filters, Some(start_date), Some(end_date))?
+        } else {
+            let mut cache = StatsCache::default();
 
-        for row in rows {
-            cache.models.push(row?);
-        }
+            let totals: (i64, i64, i64, i64, f64, i64, f64) = conn.query_row(
+                "SELECT
+                    COALESCE(SUM(total_input_tokens), 0),
+                    COALESCE(SUM(total_output_tokens), 0),
+                    COALESCE(SUM(total_cache_read_tokens), 0),
+                    COALESCE(SUM(total_cache_creation_tokens), 0),
+                    COALESCE(SUM(total_cost_usd), 0),
+                    COALESCE(SUM(message_count), 0),
+                    COALESCE(SUM(total_api_equivalent_cost_usd), 0)
+                 FROM daily_stats
+                 WHERE date BETWEEN ?1 AND ?2 AND (?3 IS NULL OR provider_id = ?3)",
+                params![start_date, end_date, filters.provider_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                        row.get(6)?,
+                    ))
+                },
+            )?;
+
+            cache.total_input_tokens = totals.0;
+            cache.total_output_tokens = totals.1;
+            cache.total_cache_read_tokens = totals.2;
+            cache.total_cache_creation_tokens = totals.3;
+            cache.total_cost_usd = totals.4;
+            cache.total_messages = totals.5;
+            cache.total_api_equivalent_cost_usd = totals.6;
 
-        cache.update_cache_hit_rate();
+            // daily_stats 按天记录会话数，跨天会话会被重复计数，因此会话数单独去重统计
+            cache.total_sessions = conn.query_row(
+                "SELECT COUNT(DISTINCT session_id)
+                 FROM message_usage
+                 WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+                   AND (?3 IS NULL OR provider_id = ?3)",
+                params![start_date, end_date, filters.provider_id],
+                |row| row.get(0),
+            )?;
+
+            let mut stmt = conn.prepare(
+                "SELECT model, SUM(total_input_tokens), SUM(total_output_tokens), SUM(total_cache_read_tokens), SUM(total_cache_creation_tokens), SUM(total_cost_usd), SUM(message_count), SUM(total_thinking_tokens)
+                 FROM model_daily_stats
+                 WHERE date BETWEEN ?1 AND ?2 AND (?3 IS NULL OR provider_id = ?3)
+                 GROUP BY model",
+            )?;
+
+            let rows = stmt.query_map(
+                params![start_date, end_date, filters.provider_id],
+                model_usage_from_row,
+            )?;
+
+            for row in rows {
+                cache.models.push(row?);
+            }
+
+            cache
+        };
+
+        let cache_hit_rate: CacheHitRateSettings = read_setting(&conn)?;
+        cache.update_cache_hit_rate(cache_hit_rate.formula);
         cache.sort_models_by_cost();
         Ok(cache)
     }
 
+    /// 小组件轮询用的精简统计，当日汇总来自总体统计缓存，写入消息时增量累加
+    ///
+    /// 区块剩余时间需要逐条消息计算，由调用方填充
+    pub fn get_quick_stats(&self, date: &str) -> Result<QuickStats, RepositoryError> {
+        let conn = self.connection()?;
+        let mut cached = self.stats_cache()?;
+        let cache = match cached.as_mut() {
+            Some(cache) => cache,
+            None => cached.insert(load_current_stats(&conn)?),
+        };
+        let (today_cost_usd, today_tokens) = match &cache.day_totals {
+            Some((cached_date, cost_usd, tokens)) if cached_date == date => (*cost_usd, *tokens),
+            _ => {
+                let (cost_usd, tokens) = conn.query_row(
+                    "SELECT COALESCE(SUM(total_cost_usd), 0),
+                            COALESCE(SUM(total_input_tokens + total_output_tokens + total_cache_read_tokens + total_cache_creation_tokens), 0)
+                     FROM daily_stats
+                     WHERE date = ?1",
+                    params![date],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                cache.day_totals = Some((date.to_string(), cost_usd, tokens));
+                (cost_usd, tokens)
+            }
+        };
+        let active_provider = conn
+            .query_row(
+                "SELECT COALESCE(display_name, api_key_prefix)
+                 FROM providers WHERE is_active = 1 ORDER BY last_seen_at DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(QuickStats {
+            today_cost_usd,
+            today_tokens,
+            block_remaining_minutes: None,
+            active_provider,
+        })
+    }
+
     pub fn get_today_provider_stats(&self) -> Result<Vec<ProviderStats>, RepositoryError> {
         let conn = self.connection()?;
         let today = Local::now().date_naive().to_string();
+        let cache_hit_rate: CacheHitRateSettings = read_setting(&conn)?;
 
         let mut stmt = conn.prepare(
             "SELECT
-                p.id, p.api_key_hash, p.api_key_prefix, p.display_name, p.base_url, p.is_active, p.first_seen_at, p.last_seen_at,
+                p.id, p.api_key_hash, p.api_key_prefix, p.display_name, p.base_url, p.is_active, p.first_seen_at, p.last_seen_at, p.kind,
                 COALESCE(d.total_input_tokens, 0),
                 COALESCE(d.total_output_tokens, 0),
                 COALESCE(d.total_cache_read_tokens, 0),
@@ -403,15 +1054,16 @@ impl Repository {
                 is_active: row.get::<_, i64>(5)? == 1,
                 first_seen_at: row.get(6)?,
                 last_seen_at: row.get(7)?,
+                kind: ProviderKind::from_db(&row.get::<_, String>(8)?),
             };
 
             let mut stats = ProviderStats::new(provider);
-            stats.today_input_tokens = row.get(8)?;
-            stats.today_output_tokens = row.get(9)?;
-            stats.today_cache_read_tokens = row.get(10)?;
-            stats.today_cache_creation_tokens = row.get(11)?;
-            stats.today_cost_usd = row.get(12)?;
-            stats.update_cache_hit_rate();
+            stats.today_input_tokens = row.get(9)?;
+            stats.today_output_tokens = row.get(10)?;
+            stats.today_cache_read_tokens = row.get(11)?;
+            stats.today_cache_creation_tokens = row.get(12)?;
+            stats.today_cost_usd = row.get(13)?;
+            stats.update_cache_hit_rate(cache_hit_rate.formula);
 
             Ok(stats)
         })?;
@@ -425,10 +1077,18 @@ impl Repository {
     }
 
     pub fn get_today_stats(&self) -> Result<TodayStats, RepositoryError> {
+        self.get_today_stats_filtered(&StatsFilters::default())
+    }
+
+    /// 按供应商 / 模型 / 项目过滤的今日统计
+    pub fn get_today_stats_filtered(
+        &self,
+        filters: &StatsFilters,
+    ) -> Result<TodayStats, RepositoryError> {
         let conn = self.connection()?;
         let today = Local::now().date_naive().to_string();
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT
                 COALESCE(SUM(input_tokens), 0),
                 COALESCE(SUM(output_tokens), 0),
@@ -436,13 +1096,16 @@ impl Repository {
                 COALESCE(SUM(cache_creation_tokens), 0),
                 COALESCE(SUM(cost_usd), 0),
                 COALESCE(COUNT(DISTINCT session_id), 0),
-                COALESCE(COUNT(*), 0)
+                COALESCE(COUNT(*), 0),
+                COALESCE(SUM(api_equivalent_cost_usd), 0)
              FROM message_usage
-             WHERE date(created_at, 'localtime') = ?1",
-        )?;
+             WHERE date(created_at, 'localtime') = ?4 AND {}",
+            STATS_FILTER_CONDITIONS
+        ))?;
 
-        let totals: (i64, i64, i64, i64, f64, i64, i64) =
-            stmt.query_row(params![today], |row| {
+        let totals: (i64, i64, i64, i64, f64, i64, i64, f64) = stmt.query_row(
+            params![filters.provider_id, filters.model, filters.project, today],
+            |row| {
                 Ok((
                     row.get(0)?,
                     row.get(1)?,
@@ -451,9 +1114,11 @@ impl Repository {
                     row.get(4)?,
                     row.get(5)?,
                     row.get(6)?,
+                    row.get(7)?,
                 ))
-            })?;
-
+            },
+        )?;
+
         let mut stats = TodayStats {
             input_tokens: totals.0,
             output_tokens: totals.1,
@@ -463,8 +1128,10 @@ impl Repository {
             session_count: totals.5,
             message_count: totals.6,
             cache_hit_rate: 0.0,
+            api_equivalent_cost_usd: totals.7,
         };
-        stats.update_cache_hit_rate();
+        let cache_hit_rate: CacheHitRateSettings = read_setting(&conn)?;
+        stats.update_cache_hit_rate(cache_hit_rate.formula);
         Ok(stats)
     }
 
@@ -482,7 +1149,9 @@ impl Repository {
                 COALESCE(SUM(total_output_tokens), 0),
                 COALESCE(SUM(total_cost_usd), 0),
                 COALESCE(SUM(session_count), 0),
-                COALESCE(SUM(message_count), 0)
+                COALESCE(SUM(message_count), 0),
+                (SELECT note FROM date_notes WHERE date_notes.date = daily_stats.date),
+                COALESCE(SUM(total_api_equivalent_cost_usd), 0)
              FROM daily_stats
              WHERE date BETWEEN ?1 AND ?2
              GROUP BY date
@@ -497,6 +1166,8 @@ impl Repository {
                 cost_usd: row.get(3)?,
                 session_count: row.get(4)?,
                 message_count: row.get(5)?,
+                note: row.get(6)?,
+                api_equivalent_cost_usd: row.get(7)?,
             })
         })?;
 
@@ -508,102 +1179,6615 @@ impl Repository {
         Ok(activities)
     }
 
-    fn get_provider_by_hash(
+    /// 按供应商 / 模型 / 项目过滤的每日活动
+    ///
+    /// 未设置过滤条件时与 get_daily_activities 相同，否则按本地日期从 message_usage 汇总
+    pub fn get_daily_activities_filtered(
         &self,
-        conn: &Connection,
-        api_key: &str,
-    ) -> Result<Option<Provider>, RepositoryError> {
-        let temp_provider = Provider::new(api_key, None, None);
+        start_date: &str,
+        end_date: &str,
+        filters: &StatsFilters,
+    ) -> Result<Vec<DailyActivity>, RepositoryError> {
+        if filters.is_empty() {
+            return self.get_daily_activities(start_date, end_date);
+        }
 
-        conn.query_row(
-            "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at
-             FROM providers WHERE api_key_hash = ?1",
-            params![temp_provider.api_key_hash],
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT
+                date(created_at, 'localtime') AS day,
+                COALESCE(SUM(input_tokens), 0),
+                COALESCE(SUM(output_tokens), 0),
+                COALESCE(SUM(cost_usd), 0),
+                COUNT(DISTINCT session_id),
+                COUNT(*),
+                (SELECT note FROM date_notes WHERE date_notes.date = date(message_usage.created_at, 'localtime')),
+                COALESCE(SUM(api_equivalent_cost_usd), 0)
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?4 AND ?5 AND {}
+             GROUP BY day
+             ORDER BY day ASC",
+            STATS_FILTER_CONDITIONS
+        ))?;
+
+        let rows = stmt.query_map(
+            params![
+                filters.provider_id,
+                filters.model,
+                filters.project,
+                start_date,
+                end_date
+            ],
             |row| {
-                Ok(Provider {
-                    id: row.get(0)?,
-                    api_key_hash: row.get(1)?,
-                    api_key_prefix: row.get(2)?,
-                    display_name: row.get(3)?,
-                    base_url: row.get(4)?,
-                    is_active: row.get::<_, i64>(5)? == 1,
-                    first_seen_at: row.get(6)?,
-                    last_seen_at: row.get(7)?,
+                Ok(DailyActivity {
+                    date: row.get(0)?,
+                    input_tokens: row.get(1)?,
+                    output_tokens: row.get(2)?,
+                    cost_usd: row.get(3)?,
+                    session_count: row.get(4)?,
+                    message_count: row.get(5)?,
+                    note: row.get(6)?,
+                    api_equivalent_cost_usd: row.get(7)?,
                 })
             },
-        )
-        .optional()
-        .map_err(RepositoryError::from)
+        )?;
+
+        let mut activities = Vec::new();
+        for row in rows {
+            activities.push(row?);
+        }
+
+        Ok(activities)
     }
-}
 
-fn extract_date(iso: &str) -> String {
-    if let Ok(parsed) = chrono::DateTime::parse_from_rfc3339(iso) {
-        return parsed.with_timezone(&Local).date_naive().to_string();
+    /// 设置某一天的备注，已存在时覆盖
+    pub fn set_date_note(&self, date: &str, note: &str) -> Result<DateNote, RepositoryError> {
+        let conn = self.connection()?;
+        let updated_at = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO date_notes (date, note, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(date) DO UPDATE SET note = excluded.note, updated_at = excluded.updated_at",
+            params![date, note, updated_at],
+        )?;
+
+        Ok(DateNote {
+            date: date.to_string(),
+            note: note.to_string(),
+            updated_at,
+        })
     }
-    Local::now().date_naive().to_string()
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::{MessageRecord, MessageUsage};
+    /// 删除某一天的备注
+    ///
+    /// # 返回
+    /// 备注存在并被删除返回 true
+    pub fn delete_date_note(&self, date: &str) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let deleted = conn.execute("DELETE FROM date_notes WHERE date = ?1", params![date])?;
+        Ok(deleted > 0)
+    }
 
-    #[test]
-    fn test_repository_insert_and_stats() {
-        let repo = Repository::new_in_memory().expect("repo");
-        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+    /// 获取日期范围内的备注，包括没有用量记录的日期
+    pub fn get_date_notes(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<DateNote>, RepositoryError> {
+        let conn = self.connection()?;
 
-        let record = MessageRecord::new(
-            "session-1".to_string(),
-            "message-1".to_string(),
-            "claude-3-opus".to_string(),
-            Utc::now().to_rfc3339(),
-            MessageUsage {
-                input_tokens: 100,
-                output_tokens: 50,
-                cache_read_tokens: 0,
-                cache_creation_tokens: 0,
-                cost_usd: 1.0,
-            },
-        );
+        let mut stmt = conn.prepare(
+            "SELECT date, note, updated_at
+             FROM date_notes
+             WHERE date BETWEEN ?1 AND ?2
+             ORDER BY date ASC",
+        )?;
 
-        repo.insert_message_usage(provider.id, &record)
-            .expect("insert");
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(DateNote {
+                date: row.get(0)?,
+                note: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        })?;
 
-        let stats = repo.get_current_stats().expect("stats");
-        assert_eq!(stats.total_input_tokens, 100);
-        assert_eq!(stats.total_messages, 1);
+        let mut notes = Vec::new();
+        for row in rows {
+            notes.push(row?);
+        }
+
+        Ok(notes)
     }
 
-    #[test]
-    fn test_get_daily_activities() {
-        let repo = Repository::new_in_memory().expect("repo");
-        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+    /// 写入官方用量，同一日期以最新拉取的数据为准
+    ///
+    /// # 返回
+    /// 写入的天数
+    pub fn upsert_official_usage(&self, usage: &[OfficialUsage]) -> Result<usize, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
 
-        let record = MessageRecord::new(
-            "session-1".to_string(),
-            "message-1".to_string(),
-            "claude-3-opus".to_string(),
-            Local::now().to_rfc3339(),
-            MessageUsage {
-                input_tokens: 10,
-                output_tokens: 5,
-                cache_read_tokens: 0,
-                cache_creation_tokens: 0,
-                cost_usd: 0.1,
-            },
-        );
+        for day in usage {
+            tx.execute(
+                "INSERT INTO official_usage (date, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(date) DO UPDATE SET
+                    input_tokens = excluded.input_tokens,
+                    output_tokens = excluded.output_tokens,
+                    cache_read_tokens = excluded.cache_read_tokens,
+                    cache_creation_tokens = excluded.cache_creation_tokens,
+                    cost_usd = excluded.cost_usd,
+                    fetched_at = excluded.fetched_at",
+                params![
+                    day.date,
+                    day.input_tokens,
+                    day.output_tokens,
+                    day.cache_read_tokens,
+                    day.cache_creation_tokens,
+                    day.cost_usd,
+                    day.fetched_at
+                ],
+            )?;
+        }
 
-        repo.insert_message_usage(provider.id, &record)
-            .expect("insert");
+        tx.commit()?;
+        Ok(usage.len())
+    }
 
-        let today = Local::now().date_naive().to_string();
-        let activities = repo
-            .get_daily_activities(&today, &today)
-            .expect("activities");
+    /// 获取日期范围内（UTC 日期）已拉取的官方用量
+    pub fn get_official_usage(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<OfficialUsage>, RepositoryError> {
+        let conn = self.connection()?;
 
-        assert_eq!(activities.len(), 1);
-        assert_eq!(activities[0].message_count, 1);
+        let mut stmt = conn.prepare(
+            "SELECT date, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, fetched_at
+             FROM official_usage
+             WHERE date BETWEEN ?1 AND ?2
+             ORDER BY date ASC",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(OfficialUsage {
+                date: row.get(0)?,
+                input_tokens: row.get(1)?,
+                output_tokens: row.get(2)?,
+                cache_read_tokens: row.get(3)?,
+                cache_creation_tokens: row.get(4)?,
+                cost_usd: row.get(5)?,
+                fetched_at: row.get(6)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 按 UTC 日期对比官方用量与本地消息记录，只返回已拉取官方数据的日期
+    pub fn get_usage_drift(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<UsageDriftPoint>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "WITH local AS (
+                SELECT date(created_at) AS date,
+                       SUM(input_tokens + output_tokens + cache_read_tokens + cache_creation_tokens) AS tokens,
+                       SUM(cost_usd) AS cost_usd
+                FROM message_usage
+                WHERE date(created_at) BETWEEN ?1 AND ?2
+                GROUP BY date(created_at)
+             )
+             SELECT o.date,
+                    o.input_tokens + o.output_tokens + o.cache_read_tokens + o.cache_creation_tokens,
+                    COALESCE(l.tokens, 0),
+                    o.cost_usd,
+                    COALESCE(l.cost_usd, 0)
+             FROM official_usage o
+             LEFT JOIN local l ON l.date = o.date
+             WHERE o.date BETWEEN ?1 AND ?2
+             ORDER BY o.date ASC",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            let official_tokens: i64 = row.get(1)?;
+            let local_tokens: i64 = row.get(2)?;
+            let official_cost_usd: f64 = row.get(3)?;
+            let local_cost_usd: f64 = row.get(4)?;
+            Ok(UsageDriftPoint {
+                date: row.get(0)?,
+                official_tokens,
+                local_tokens,
+                official_cost_usd,
+                local_cost_usd,
+                token_drift: local_tokens - official_tokens,
+                cost_drift_usd: local_cost_usd - official_cost_usd,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    pub fn set_provider_plan(
+        &self,
+        provider_id: i64,
+        plan_type: PlanType,
+        monthly_fee_usd: f64,
+    ) -> Result<ProviderPlan, RepositoryError> {
+        let now = Utc::now().to_rfc3339();
+        let conn = self.connection()?;
+
+        conn.execute(
+            "INSERT INTO provider_plans (provider_id, plan_type, monthly_fee_usd, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(provider_id) DO UPDATE SET
+                plan_type = excluded.plan_type,
+                monthly_fee_usd = excluded.monthly_fee_usd,
+                updated_at = excluded.updated_at",
+            params![provider_id, plan_type.as_str(), monthly_fee_usd, now],
+        )?;
+
+        Ok(ProviderPlan {
+            provider_id,
+            plan_type,
+            monthly_fee_usd,
+            updated_at: now,
+        })
+    }
+
+    pub fn get_provider_plans(&self) -> Result<Vec<ProviderPlan>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT provider_id, plan_type, monthly_fee_usd, updated_at
+             FROM provider_plans ORDER BY provider_id ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(ProviderPlan {
+                provider_id: row.get(0)?,
+                plan_type: PlanType::from_db(&row.get::<_, String>(1)?),
+                monthly_fee_usd: row.get(2)?,
+                updated_at: row.get(3)?,
+            })
+        })?;
+
+        let mut plans = Vec::new();
+        for row in rows {
+            plans.push(row?);
+        }
+
+        Ok(plans)
+    }
+
+    /// 设置供应商余额查询地址与刷新间隔
+    ///
+    /// 查询地址变化时清空上一次的查询结果
+    pub fn set_provider_quota(
+        &self,
+        provider_id: i64,
+        quota_url: &str,
+        refresh_minutes: u32,
+    ) -> Result<ProviderBalance, RepositoryError> {
+        let now = Utc::now().to_rfc3339();
+        let conn = self.connection()?;
+
+        conn.execute(
+            "INSERT INTO provider_quotas (provider_id, quota_url, refresh_minutes, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(provider_id) DO UPDATE SET
+                remaining_usd = CASE WHEN quota_url = excluded.quota_url THEN remaining_usd END,
+                used_usd = CASE WHEN quota_url = excluded.quota_url THEN used_usd END,
+                total_usd = CASE WHEN quota_url = excluded.quota_url THEN total_usd END,
+                fetched_at = CASE WHEN quota_url = excluded.quota_url THEN fetched_at END,
+                error = CASE WHEN quota_url = excluded.quota_url THEN error END,
+                quota_url = excluded.quota_url,
+                refresh_minutes = excluded.refresh_minutes,
+                updated_at = excluded.updated_at",
+            params![provider_id, quota_url, refresh_minutes, now],
+        )?;
+
+        read_provider_balance(&conn, provider_id)?.ok_or(RepositoryError::Database(
+            rusqlite::Error::QueryReturnedNoRows,
+        ))
+    }
+
+    /// 删除供应商余额查询配置
+    ///
+    /// # 返回
+    /// 配置存在并被删除返回 true
+    pub fn delete_provider_quota(&self, provider_id: i64) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let deleted = conn.execute(
+            "DELETE FROM provider_quotas WHERE provider_id = ?1",
+            params![provider_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// 获取供应商余额查询配置与最近一次结果，未配置时返回 None
+    pub fn get_provider_balance(
+        &self,
+        provider_id: i64,
+    ) -> Result<Option<ProviderBalance>, RepositoryError> {
+        let conn = self.connection()?;
+        read_provider_balance(&conn, provider_id)
+    }
+
+    /// 获取所有已配置余额查询的供应商
+    pub fn get_provider_balances(&self) -> Result<Vec<ProviderBalance>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all(
+            &conn,
+            &format!("{} ORDER BY provider_id ASC", PROVIDER_BALANCE_SELECT),
+            provider_balance_from_row,
+        )
+    }
+
+    /// 记录一次余额查询结果
+    ///
+    /// 查询失败时只记录错误与时间，保留上一次成功的额度
+    pub fn record_provider_balance(
+        &self,
+        provider_id: i64,
+        result: &Result<BalanceAmounts, String>,
+    ) -> Result<Option<ProviderBalance>, RepositoryError> {
+        let now = Utc::now().to_rfc3339();
+        let conn = self.connection()?;
+
+        match result {
+            Ok(amounts) => conn.execute(
+                "UPDATE provider_quotas
+                 SET remaining_usd = ?2, used_usd = ?3, total_usd = ?4, fetched_at = ?5, error = NULL
+                 WHERE provider_id = ?1",
+                params![
+                    provider_id,
+                    amounts.remaining_usd,
+                    amounts.used_usd,
+                    amounts.total_usd,
+                    now
+                ],
+            )?,
+            Err(error) => conn.execute(
+                "UPDATE provider_quotas SET fetched_at = ?2, error = ?3 WHERE provider_id = ?1",
+                params![provider_id, now, error],
+            )?,
+        };
+
+        read_provider_balance(&conn, provider_id)
+    }
+
+    /// 读取设置项，未保存过时返回默认值
+    pub fn get_setting<T: AppSetting>(&self) -> Result<T, RepositoryError> {
+        let conn = self.connection()?;
+        read_setting(&conn)
+    }
+
+    /// 保存设置项
+    pub fn set_setting<T: AppSetting>(&self, setting: &T) -> Result<(), RepositoryError> {
+        let conn = self.connection()?;
+        write_setting(&conn, setting)
+    }
+
+    /// 当前数据库的钥匙串命名空间，首次使用时随机生成并保存
+    ///
+    /// 档案与演示数据库各自独立生成，供应商 ID 相同也不会读取或删除其他数据库的凭证；
+    /// 只读模式的数据库副本与源数据库使用同一命名空间
+    pub fn keychain_namespace(&self) -> Result<String, RepositoryError> {
+        let conn = self.connection()?;
+        let mut settings: KeychainSettings = read_setting(&conn)?;
+        if let Some(namespace) = settings.namespace {
+            return Ok(namespace);
+        }
+
+        let namespace = hex::encode(rand::random::<[u8; 8]>());
+        settings.namespace = Some(namespace.clone());
+        write_setting(&conn, &settings)?;
+        Ok(namespace)
+    }
+
+    /// 保存隐私设置，并按新设置截短已有供应商的 API Key 前缀
+    ///
+    /// # 返回
+    /// 前缀被截短的供应商数
+    pub fn set_privacy_settings(
+        &self,
+        settings: &PrivacySettings,
+    ) -> Result<usize, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        write_setting(&tx, settings)?;
+        let remasked = tx.execute(REMASK_API_KEY_PREFIXES, [])?;
+
+        tx.commit()?;
+        Ok(remasked)
+    }
+
+    pub fn get_provider_model_usage(
+        &self,
+        provider_id: i64,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<ModelUsage>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT model, SUM(total_input_tokens), SUM(total_output_tokens), SUM(total_cache_read_tokens), SUM(total_cache_creation_tokens), SUM(total_cost_usd), SUM(message_count), SUM(total_thinking_tokens)
+             FROM model_daily_stats
+             WHERE provider_id = ?1 AND date BETWEEN ?2 AND ?3
+             GROUP BY model",
+        )?;
+
+        let rows = stmt.query_map(params![provider_id, start_date, end_date], |row| {
+            Ok(ModelUsage {
+                model: row.get(0)?,
+                input_tokens: row.get(1)?,
+                output_tokens: row.get(2)?,
+                cache_read_tokens: row.get(3)?,
+                cache_creation_tokens: row.get(4)?,
+                cost_usd: row.get(5)?,
+                message_count: row.get(6)?,
+                thinking_tokens: row.get(7)?,
+            })
+        })?;
+
+        let mut usages = Vec::new();
+        for row in rows {
+            usages.push(row?);
+        }
+
+        Ok(usages)
+    }
+
+    /// 按日期与模型聚合使用量（本地日期，基于 model_daily_stats）
+    /// 获取日期范围内每日费用 / Token 及其滚动平均
+    ///
+    /// 业务逻辑说明：
+    /// 1. 用递归 CTE 生成从 start_date 往前 window_days - 1 天到 end_date 的完整日期序列，无数据的日期按 0 计
+    /// 2. 使用窗口函数对最近 window_days 天求平均，范围开头的数据点也使用完整窗口
+    /// 3. 只返回 start_date 到 end_date 之间的数据点
+    pub fn get_rolling_average(
+        &self,
+        window_days: u32,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<RollingAveragePoint>, RepositoryError> {
+        let conn = self.connection()?;
+        // 窗口帧边界需为常量表达式，window_days 已限制范围后直接拼接
+        let preceding = window_days.clamp(1, MAX_ROLLING_WINDOW_DAYS) - 1;
+
+        let mut stmt = conn.prepare(&format!(
+            "WITH RECURSIVE days(date) AS (
+                SELECT date(?1, '-{preceding} days')
+                UNION ALL
+                SELECT date(date, '+1 day') FROM days WHERE date < ?2
+            ),
+            daily AS (
+                SELECT
+                    d.date,
+                    COALESCE(SUM(s.total_cost_usd), 0) AS cost_usd,
+                    COALESCE(SUM(s.total_input_tokens + s.total_output_tokens), 0) AS total_tokens
+                FROM days d
+                LEFT JOIN daily_stats s ON s.date = d.date
+                GROUP BY d.date
+            ),
+            smoothed AS (
+                SELECT
+                    date,
+                    cost_usd,
+                    total_tokens,
+                    AVG(cost_usd) OVER (ORDER BY date ROWS BETWEEN {preceding} PRECEDING AND CURRENT ROW) AS avg_cost_usd,
+                    AVG(total_tokens) OVER (ORDER BY date ROWS BETWEEN {preceding} PRECEDING AND CURRENT ROW) AS avg_total_tokens
+                FROM daily
+            )
+            SELECT date, cost_usd, total_tokens, avg_cost_usd, avg_total_tokens
+            FROM smoothed
+            WHERE date BETWEEN ?1 AND ?2
+            ORDER BY date ASC"
+        ))?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(RollingAveragePoint {
+                date: row.get(0)?,
+                cost_usd: row.get(1)?,
+                total_tokens: row.get(2)?,
+                avg_cost_usd: row.get(3)?,
+                avg_total_tokens: row.get(4)?,
+            })
+        })?;
+
+        let mut points = Vec::new();
+        for row in rows {
+            points.push(row?);
+        }
+
+        Ok(points)
+    }
+
+    pub fn get_model_daily_usage(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<ModelDailyUsage>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                date,
+                model,
+                COALESCE(SUM(total_input_tokens), 0),
+                COALESCE(SUM(total_output_tokens), 0),
+                COALESCE(SUM(total_cache_read_tokens), 0),
+                COALESCE(SUM(total_cache_creation_tokens), 0),
+                COALESCE(SUM(total_cost_usd), 0),
+                COALESCE(SUM(message_count), 0)
+             FROM model_daily_stats
+             WHERE date BETWEEN ?1 AND ?2
+             GROUP BY date, model
+             ORDER BY date ASC",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(ModelDailyUsage {
+                date: row.get(0)?,
+                model: row.get(1)?,
+                input_tokens: row.get(2)?,
+                output_tokens: row.get(3)?,
+                cache_read_tokens: row.get(4)?,
+                cache_creation_tokens: row.get(5)?,
+                cost_usd: row.get(6)?,
+                message_count: row.get(7)?,
+            })
+        })?;
+
+        let mut usages = Vec::new();
+        for row in rows {
+            usages.push(row?);
+        }
+
+        Ok(usages)
+    }
+
+    /// 写入会话标题
+    ///
+    /// summary 条目总是覆盖已有标题；首条用户输入仅在会话尚无标题时写入，
+    /// 避免后续输入或重复扫描覆盖更准确的标题
+    pub fn upsert_session_title(
+        &self,
+        session_id: &str,
+        title: &str,
+        source: SessionTitleSource,
+    ) -> Result<(), RepositoryError> {
+        let conn = self.connection()?;
+        let sql = match source {
+            SessionTitleSource::Summary => {
+                "INSERT INTO sessions (session_id, title, title_source, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(session_id) DO UPDATE SET
+                    title = excluded.title,
+                    title_source = excluded.title_source,
+                    updated_at = excluded.updated_at"
+            }
+            SessionTitleSource::Prompt => {
+                "INSERT INTO sessions (session_id, title, title_source, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(session_id) DO NOTHING"
+            }
+        };
+
+        conn.execute(
+            sql,
+            params![session_id, title, source.as_str(), Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 获取会话标题及其来源
+    pub fn get_session_title(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<(String, SessionTitleSource)>, RepositoryError> {
+        let conn = self.connection()?;
+        let title = conn
+            .query_row(
+                "SELECT title, title_source FROM sessions WHERE session_id = ?1",
+                params![session_id],
+                |row| {
+                    let source: String = row.get(1)?;
+                    Ok((row.get(0)?, SessionTitleSource::from_db(&source)))
+                },
+            )
+            .optional()?;
+        Ok(title)
+    }
+
+    /// 获取日期范围内费用或 Token 最高的会话
+    pub fn get_top_sessions(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        limit: i64,
+        order_by: SessionOrder,
+    ) -> Result<Vec<SessionSummary>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let order_clause = match order_by {
+            SessionOrder::Cost => "cost_usd DESC",
+            SessionOrder::Tokens => "total_tokens DESC",
+        };
+
+        let sql = format!(
+            "SELECT
+                m.session_id,
+                m.provider_id,
+                MAX(m.project),
+                GROUP_CONCAT(DISTINCT m.model),
+                COALESCE(SUM(m.input_tokens), 0),
+                COALESCE(SUM(m.output_tokens), 0),
+                COALESCE(SUM(m.cache_read_tokens), 0),
+                COALESCE(SUM(m.cache_creation_tokens), 0),
+                COALESCE(SUM(m.cost_usd), 0) AS cost_usd,
+                COUNT(*),
+                MIN(m.created_at),
+                MAX(m.created_at),
+                COALESCE(SUM(m.input_tokens + m.output_tokens), 0) AS total_tokens,
+                s.title
+             FROM message_usage m
+             LEFT JOIN sessions s ON s.session_id = m.session_id
+             WHERE date(m.created_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY m.provider_id, m.session_id
+             ORDER BY {}
+             LIMIT ?3",
+            order_clause
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params![start_date, end_date, limit],
+            session_summary_from_row,
+        )?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row?);
+        }
+
+        Ok(sessions)
+    }
+
+    /// 获取最近有消息的会话（按末条消息时间从新到旧）
+    ///
+    /// # 参数
+    /// * `since` - ISO 8601 时间，末条消息不早于该时间的会话视为活跃
+    pub fn get_active_sessions(&self, since: &str) -> Result<Vec<SessionSummary>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                m.session_id,
+                m.provider_id,
+                MAX(m.project),
+                GROUP_CONCAT(DISTINCT m.model),
+                COALESCE(SUM(m.input_tokens), 0),
+                COALESCE(SUM(m.output_tokens), 0),
+                COALESCE(SUM(m.cache_read_tokens), 0),
+                COALESCE(SUM(m.cache_creation_tokens), 0),
+                COALESCE(SUM(m.cost_usd), 0),
+                COUNT(*),
+                MIN(m.created_at),
+                MAX(m.created_at),
+                COALESCE(SUM(m.input_tokens + m.output_tokens), 0),
+                s.title
+             FROM message_usage m
+             LEFT JOIN sessions s ON s.session_id = m.session_id
+             WHERE m.session_id IN (
+                SELECT DISTINCT session_id FROM message_usage
+                WHERE julianday(created_at) >= julianday(?1)
+             )
+             GROUP BY m.provider_id, m.session_id
+             ORDER BY MAX(julianday(m.created_at)) DESC",
+        )?;
+
+        let rows = stmt.query_map(params![since], session_summary_from_row)?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row?);
+        }
+
+        Ok(sessions)
+    }
+
+    /// 按过滤条件分页搜索消息记录
+    ///
+    /// 未设置的过滤条件以 NULL 传入，SQL 中通过 `?N IS NULL OR ...` 跳过
+    pub fn search_messages(
+        &self,
+        filters: &MessageSearchFilters,
+    ) -> Result<MessageSearchPage, RepositoryError> {
+        const SEARCH_CONDITIONS: &str = "(?1 IS NULL OR model = ?1)
+               AND (?2 IS NULL OR provider_id = ?2)
+               AND (?3 IS NULL OR project = ?3)
+               AND (?4 IS NULL OR date(created_at, 'localtime') >= ?4)
+               AND (?5 IS NULL OR date(created_at, 'localtime') <= ?5)
+               AND (?6 IS NULL OR input_tokens + output_tokens >= ?6)";
+
+        let conn = self.connection()?;
+        let page = filters.page();
+        let page_size = filters.page_size();
+
+        let total: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM message_usage WHERE {}",
+                SEARCH_CONDITIONS
+            ),
+            params![
+                filters.model,
+                filters.provider_id,
+                filters.project,
+                filters.start_date,
+                filters.end_date,
+                filters.min_tokens
+            ],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, provider_id, session_id, message_id, model, project, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at
+             FROM message_usage
+             WHERE {}
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?7 OFFSET ?8",
+            SEARCH_CONDITIONS
+        ))?;
+
+        let rows = stmt.query_map(
+            params![
+                filters.model,
+                filters.provider_id,
+                filters.project,
+                filters.start_date,
+                filters.end_date,
+                filters.min_tokens,
+                page_size,
+                filters.offset()
+            ],
+            stored_message_from_row,
+        )?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            items.push(row?);
+        }
+
+        Ok(MessageSearchPage {
+            items,
+            total,
+            page,
+            page_size,
+        })
+    }
+
+    /// 获取日期范围内的全部消息（本地日期），按创建时间升序
+    pub fn get_messages_in_range(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<StoredMessage>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, provider_id, session_id, message_id, model, project, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+             ORDER BY created_at ASC, id ASC",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], stored_message_from_row)?;
+
+        let mut messages = Vec::new();
+        for row in rows {
+            messages.push(row?);
+        }
+
+        Ok(messages)
+    }
+
+    /// 执行单条只读 SQL 查询，最多返回 MAX_QUERY_ROWS 行
+    ///
+    /// 仅接受 SQLite 判定为只读的 SELECT / WITH 语句，写入、ATTACH、PRAGMA 等一律拒绝
+    pub fn run_readonly_query(&self, sql: &str) -> Result<QueryResult, RepositoryError> {
+        self.run_readonly_query_within(sql, Duration::from_secs(MAX_QUERY_SECONDS))
+    }
+
+    /// 执行只读查询，超过 timeout 时中断并返回 QueryTimeout
+    ///
+    /// 查询期间持有连接锁，超时中断避免失控的查询阻塞采集与其他命令
+    fn run_readonly_query_within(
+        &self,
+        sql: &str,
+        timeout: Duration,
+    ) -> Result<QueryResult, RepositoryError> {
+        let conn = self.connection()?;
+        let deadline = Instant::now() + timeout;
+        conn.progress_handler(
+            READONLY_QUERY_PROGRESS_OPS,
+            Some(move || Instant::now() >= deadline),
+        );
+        let result = collect_readonly_query(&conn, sql);
+        conn.progress_handler(READONLY_QUERY_PROGRESS_OPS, None::<fn() -> bool>);
+
+        result.map_err(|e| match e {
+            RepositoryError::Database(ref error)
+                if error.sqlite_error_code() == Some(rusqlite::ErrorCode::OperationInterrupted) =>
+            {
+                RepositoryError::QueryTimeout(timeout.as_secs())
+            }
+            e => e,
+        })
+    }
+
+    /// 保存新的自定义查询，SQL 需通过只读校验
+    pub fn create_saved_query(
+        &self,
+        name: &str,
+        sql: &str,
+        visualization: QueryVisualization,
+    ) -> Result<SavedQuery, RepositoryError> {
+        let conn = self.connection()?;
+        prepare_readonly_query(&conn, sql)?;
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO saved_queries (name, sql, visualization, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![name, sql, visualization.as_str(), now],
+        )?;
+
+        Ok(SavedQuery {
+            id: conn.last_insert_rowid(),
+            name: name.to_string(),
+            sql: sql.to_string(),
+            visualization,
+            created_at: now.clone(),
+            updated_at: now,
+        })
+    }
+
+    /// 更新自定义查询，SQL 需通过只读校验
+    ///
+    /// # 返回
+    /// 查询不存在时返回 None
+    pub fn update_saved_query(
+        &self,
+        id: i64,
+        name: &str,
+        sql: &str,
+        visualization: QueryVisualization,
+    ) -> Result<Option<SavedQuery>, RepositoryError> {
+        let conn = self.connection()?;
+        prepare_readonly_query(&conn, sql)?;
+
+        conn.execute(
+            "UPDATE saved_queries
+             SET name = ?2, sql = ?3, visualization = ?4, updated_at = ?5
+             WHERE id = ?1",
+            params![
+                id,
+                name,
+                sql,
+                visualization.as_str(),
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        read_saved_query(&conn, id)
+    }
+
+    /// 删除自定义查询
+    ///
+    /// # 返回
+    /// 查询存在并被删除返回 true
+    pub fn delete_saved_query(&self, id: i64) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let deleted = conn.execute("DELETE FROM saved_queries WHERE id = ?1", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// 获取单个自定义查询
+    pub fn get_saved_query(&self, id: i64) -> Result<Option<SavedQuery>, RepositoryError> {
+        let conn = self.connection()?;
+        read_saved_query(&conn, id)
+    }
+
+    /// 获取全部自定义查询（按创建顺序）
+    pub fn get_saved_queries(&self) -> Result<Vec<SavedQuery>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all(
+            &conn,
+            "SELECT id, name, sql, visualization, created_at, updated_at
+             FROM saved_queries ORDER BY id ASC",
+            saved_query_from_row,
+        )
+    }
+
+    /// 获取会话中最新的一条消息
+    pub fn get_latest_session_message(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<StoredMessage>, RepositoryError> {
+        let conn = self.connection()?;
+
+        conn.query_row(
+            "SELECT id, provider_id, session_id, message_id, model, project, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at
+             FROM message_usage
+             WHERE session_id = ?1
+             ORDER BY julianday(created_at) DESC, id DESC
+             LIMIT 1",
+            params![session_id],
+            stored_message_from_row,
+        )
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+
+    /// 获取会话的全部消息，按时间升序
+    pub fn get_session_messages(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<StoredMessage>, RepositoryError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, provider_id, session_id, message_id, model, project, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at
+             FROM message_usage
+             WHERE session_id = ?1
+             ORDER BY julianday(created_at), id",
+        )?;
+        let rows = stmt.query_map(params![session_id], stored_message_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 获取记录 ID 大于 after_id 的消息（即之后写入的消息），按 ID 升序
+    pub fn get_messages_after_id(
+        &self,
+        after_id: i64,
+    ) -> Result<Vec<StoredMessage>, RepositoryError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, provider_id, session_id, message_id, model, project, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at
+             FROM message_usage
+             WHERE id > ?1
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![after_id], stored_message_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 当前最大的消息记录 ID，没有消息时为 0
+    pub fn get_max_message_id(&self) -> Result<i64, RepositoryError> {
+        let conn = self.connection()?;
+        let id = conn.query_row(
+            "SELECT COALESCE(MAX(id), 0) FROM message_usage",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// 按星期几与小时聚合使用量（本地时间）
+    ///
+    /// SQLite 的 %w 以周日为 0，这里转换为周一为 0
+    pub fn get_usage_heatmap(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<HeatmapCell>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                (CAST(strftime('%w', created_at, 'localtime') AS INTEGER) + 6) % 7 AS weekday,
+                CAST(strftime('%H', created_at, 'localtime') AS INTEGER) AS hour,
+                COALESCE(SUM(input_tokens + output_tokens), 0),
+                COALESCE(SUM(cost_usd), 0),
+                COUNT(*)
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY weekday, hour",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(HeatmapCell {
+                weekday: row.get(0)?,
+                hour: row.get(1)?,
+                tokens: row.get(2)?,
+                cost_usd: row.get(3)?,
+                message_count: row.get(4)?,
+            })
+        })?;
+
+        let mut cells = Vec::new();
+        for row in rows {
+            cells.push(row?);
+        }
+
+        Ok(cells)
+    }
+
+    /// 获取指定时间之后的消息用量（按时间升序），用于计算 5 小时使用区块
+    pub fn get_block_entries(&self, since: &str) -> Result<Vec<BlockEntry>, RepositoryError> {
+        let conn = self.connection()?;
+        block_entries_since(&conn, since)
+    }
+
+    /// 与 get_block_entries 相同，结果来自总体统计缓存，供小组件高频轮询
+    ///
+    /// 首次查询或 since 早于已缓存的起始时间时从数据库读取，之后随写入的消息增量更新
+    pub fn get_cached_block_entries(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<BlockEntry>, RepositoryError> {
+        let conn = self.connection()?;
+        let mut cached = self.stats_cache()?;
+        let cache = match cached.as_mut() {
+            Some(cache) => cache,
+            None => cached.insert(load_current_stats(&conn)?),
+        };
+        match cache.block_entries.as_mut() {
+            Some((cached_since, entries)) if *cached_since <= since => {
+                entries.retain(|entry| {
+                    parse_timestamp(&entry.created_at).is_some_and(|at| at >= since)
+                });
+                *cached_since = since;
+                Ok(entries.clone())
+            }
+            _ => {
+                let entries = block_entries_since(&conn, &since.to_rfc3339())?;
+                cache.block_entries = Some((since, entries.clone()));
+                Ok(entries)
+            }
+        }
+    }
+
+    /// 统计供应商在 [start, end) 时间段内的用量
+    ///
+    /// # 返回
+    /// (token 总数, API 等价费用, 消息数)
+    pub fn get_provider_window_usage(
+        &self,
+        provider_id: i64,
+        start: &str,
+        end: &str,
+    ) -> Result<(i64, f64, i64), RepositoryError> {
+        let conn = self.connection()?;
+        conn.query_row(
+            "SELECT COALESCE(SUM(input_tokens + output_tokens + cache_read_tokens + cache_creation_tokens), 0),
+                    COALESCE(SUM(api_equivalent_cost_usd), 0),
+                    COUNT(*)
+             FROM message_usage
+             WHERE provider_id = ?1
+               AND julianday(created_at) >= julianday(?2)
+               AND julianday(created_at) < julianday(?3)",
+            params![provider_id, start, end],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(RepositoryError::from)
+    }
+
+    /// 按供应商与模型统计 [start, end) 时间段内的用量，用于指标推送
+    pub fn get_interval_usage(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<IntervalUsage>, RepositoryError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(p.display_name, p.api_key_prefix), m.model,
+                    SUM(m.input_tokens), SUM(m.output_tokens),
+                    SUM(m.cache_read_tokens), SUM(m.cache_creation_tokens),
+                    SUM(m.cost_usd), COUNT(*)
+             FROM message_usage m
+             JOIN providers p ON p.id = m.provider_id
+             WHERE julianday(m.created_at) >= julianday(?1)
+               AND julianday(m.created_at) < julianday(?2)
+             GROUP BY m.provider_id, m.model
+             ORDER BY m.provider_id, m.model",
+        )?;
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok(IntervalUsage {
+                provider: row.get(0)?,
+                model: row.get(1)?,
+                input_tokens: row.get(2)?,
+                output_tokens: row.get(3)?,
+                cache_read_tokens: row.get(4)?,
+                cache_creation_tokens: row.get(5)?,
+                cost_usd: row.get(6)?,
+                message_count: row.get(7)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 统计所有供应商自 start 起的用量，用于评估告警规则
+    pub fn get_usage_window_totals(
+        &self,
+        start: &str,
+    ) -> Result<UsageWindowTotals, RepositoryError> {
+        let conn = self.connection()?;
+        conn.query_row(
+            "SELECT COALESCE(SUM(cost_usd), 0),
+                    COALESCE(SUM(input_tokens + output_tokens + cache_read_tokens + cache_creation_tokens), 0),
+                    COUNT(*),
+                    COUNT(DISTINCT session_id)
+             FROM message_usage
+             WHERE julianday(created_at) >= julianday(?1)",
+            params![start],
+            |row| {
+                Ok(UsageWindowTotals {
+                    cost_usd: row.get(0)?,
+                    total_tokens: row.get(1)?,
+                    message_count: row.get(2)?,
+                    session_count: row.get(3)?,
+                })
+            },
+        )
+        .map_err(RepositoryError::from)
+    }
+
+    /// 新建告警规则
+    pub fn create_alert_rule(&self, input: &AlertRuleInput) -> Result<AlertRule, RepositoryError> {
+        let conn = self.connection()?;
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO alert_rules
+                (name, metric, comparator, threshold, time_window, channel, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+            params![
+                input.name,
+                input.metric.as_str(),
+                input.comparator.as_str(),
+                input.threshold,
+                input.window.as_str(),
+                input.channel.as_str(),
+                input.enabled,
+                now
+            ],
+        )?;
+
+        read_alert_rule(&conn, conn.last_insert_rowid())?.ok_or(RepositoryError::Database(
+            rusqlite::Error::QueryReturnedNoRows,
+        ))
+    }
+
+    /// 更新告警规则，越线状态重置，按新条件重新评估
+    ///
+    /// # 返回
+    /// 规则不存在时返回 None
+    pub fn update_alert_rule(
+        &self,
+        id: i64,
+        input: &AlertRuleInput,
+    ) -> Result<Option<AlertRule>, RepositoryError> {
+        let conn = self.connection()?;
+
+        conn.execute(
+            "UPDATE alert_rules
+             SET name = ?2, metric = ?3, comparator = ?4, threshold = ?5, time_window = ?6,
+                 channel = ?7, enabled = ?8, breached_since = NULL, updated_at = ?9
+             WHERE id = ?1",
+            params![
+                id,
+                input.name,
+                input.metric.as_str(),
+                input.comparator.as_str(),
+                input.threshold,
+                input.window.as_str(),
+                input.channel.as_str(),
+                input.enabled,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        read_alert_rule(&conn, id)
+    }
+
+    /// 删除告警规则，已触发的历史记录保留
+    ///
+    /// # 返回
+    /// 规则存在并被删除返回 true
+    pub fn delete_alert_rule(&self, id: i64) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let deleted = conn.execute("DELETE FROM alert_rules WHERE id = ?1", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// 获取全部告警规则（按创建顺序）
+    pub fn get_alert_rules(&self) -> Result<Vec<AlertRule>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all(
+            &conn,
+            &format!("{} ORDER BY id ASC", ALERT_RULE_SELECT),
+            alert_rule_from_row,
+        )
+    }
+
+    /// 规则自 since 起是否已触发过
+    pub fn has_triggered_alert_since(
+        &self,
+        rule_id: i64,
+        since: &str,
+    ) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        conn.query_row(
+            "SELECT EXISTS(
+                SELECT 1 FROM triggered_alerts
+                WHERE rule_id = ?1 AND julianday(triggered_at) >= julianday(?2)
+             )",
+            params![rule_id, since],
+            |row| row.get(0),
+        )
+        .map_err(RepositoryError::from)
+    }
+
+    /// 记录一次告警触发
+    pub fn insert_triggered_alert(
+        &self,
+        rule: &AlertRule,
+        value: f64,
+        message: &str,
+        triggered_at: &str,
+    ) -> Result<TriggeredAlert, RepositoryError> {
+        let conn = self.connection()?;
+
+        conn.execute(
+            "INSERT INTO triggered_alerts
+                (rule_id, rule_name, metric, value, threshold, channel, message, triggered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                rule.id,
+                rule.name,
+                rule.metric.as_str(),
+                value,
+                rule.threshold,
+                rule.channel.as_str(),
+                message,
+                triggered_at
+            ],
+        )?;
+
+        Ok(TriggeredAlert {
+            id: conn.last_insert_rowid(),
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            metric: rule.metric,
+            value,
+            threshold: rule.threshold,
+            channel: rule.channel,
+            message: message.to_string(),
+            triggered_at: triggered_at.to_string(),
+            acknowledged_at: None,
+        })
+    }
+
+    /// 获取最近触发的告警（按时间倒序）
+    ///
+    /// active_only 为 true 时只返回未确认的告警
+    pub fn get_triggered_alerts(
+        &self,
+        limit: usize,
+        active_only: bool,
+    ) -> Result<Vec<TriggeredAlert>, RepositoryError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE ?2 = 0 OR acknowledged_at IS NULL
+             ORDER BY julianday(triggered_at) DESC, id DESC
+             LIMIT ?1",
+            TRIGGERED_ALERT_SELECT
+        ))?;
+        let rows = stmt.query_map(params![limit as i64, active_only], triggered_alert_from_row)?;
+
+        let mut alerts = Vec::new();
+        for row in rows {
+            alerts.push(row?);
+        }
+
+        Ok(alerts)
+    }
+
+    /// 确认告警，已确认的告警保留原确认时间
+    ///
+    /// # 返回
+    /// 告警不存在时返回 None
+    pub fn acknowledge_alert(&self, id: i64) -> Result<Option<TriggeredAlert>, RepositoryError> {
+        let conn = self.connection()?;
+        conn.execute(
+            "UPDATE triggered_alerts SET acknowledged_at = COALESCE(acknowledged_at, ?2) WHERE id = ?1",
+            params![id, Utc::now().to_rfc3339()],
+        )?;
+
+        conn.query_row(
+            &format!("{} WHERE id = ?1", TRIGGERED_ALERT_SELECT),
+            params![id],
+            triggered_alert_from_row,
+        )
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+
+    /// 规则自 since 起最近一次触发的告警是否已确认，期间未触发过返回 false
+    pub fn is_latest_alert_acknowledged(
+        &self,
+        rule_id: i64,
+        since: &str,
+    ) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let acknowledged: Option<bool> = conn
+            .query_row(
+                "SELECT acknowledged_at IS NOT NULL FROM triggered_alerts
+                 WHERE rule_id = ?1 AND julianday(triggered_at) >= julianday(?2)
+                 ORDER BY julianday(triggered_at) DESC, id DESC
+                 LIMIT 1",
+                params![rule_id, since],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(acknowledged.unwrap_or(false))
+    }
+
+    /// 设置规则暂停通知的截止时间，until 为 None 时取消暂停
+    ///
+    /// # 返回
+    /// 规则不存在时返回 None
+    pub fn snooze_alert_rule(
+        &self,
+        id: i64,
+        until: Option<&str>,
+    ) -> Result<Option<AlertRule>, RepositoryError> {
+        let conn = self.connection()?;
+        conn.execute(
+            "UPDATE alert_rules SET snoozed_until = ?2, updated_at = ?3 WHERE id = ?1",
+            params![id, until, Utc::now().to_rfc3339()],
+        )?;
+
+        read_alert_rule(&conn, id)
+    }
+
+    /// 记录规则本次越线的开始时间，since 为 None 表示指标已回落
+    pub fn set_alert_rule_breached_since(
+        &self,
+        id: i64,
+        since: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        let conn = self.connection()?;
+        conn.execute(
+            "UPDATE alert_rules SET breached_since = ?2 WHERE id = ?1",
+            params![id, since],
+        )?;
+        Ok(())
+    }
+
+    /// 汇总 [start, end) 内用于目标评估的用量
+    pub fn get_goal_usage_totals(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<GoalUsageTotals, RepositoryError> {
+        let conn = self.connection()?;
+        conn.query_row(
+            "SELECT COALESCE(SUM(cost_usd), 0),
+                    COALESCE(SUM(input_tokens), 0),
+                    COALESCE(SUM(output_tokens), 0),
+                    COALESCE(SUM(cache_read_tokens), 0),
+                    COALESCE(SUM(cache_creation_tokens), 0),
+                    COUNT(*)
+             FROM message_usage
+             WHERE julianday(created_at) >= julianday(?1)
+               AND julianday(created_at) < julianday(?2)",
+            params![start, end],
+            |row| {
+                Ok(GoalUsageTotals {
+                    cost_usd: row.get(0)?,
+                    input_tokens: row.get(1)?,
+                    output_tokens: row.get(2)?,
+                    cache_read_tokens: row.get(3)?,
+                    cache_creation_tokens: row.get(4)?,
+                    message_count: row.get(5)?,
+                })
+            },
+        )
+        .map_err(RepositoryError::from)
+    }
+
+    /// 新建目标
+    pub fn create_goal(&self, input: &GoalInput) -> Result<Goal, RepositoryError> {
+        let conn = self.connection()?;
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO goals
+                (name, metric, direction, target, period, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            params![
+                input.name,
+                input.metric.as_str(),
+                input.direction.as_str(),
+                input.target,
+                input.period.as_str(),
+                input.enabled,
+                now
+            ],
+        )?;
+
+        read_goal(&conn, conn.last_insert_rowid())?.ok_or(RepositoryError::Database(
+            rusqlite::Error::QueryReturnedNoRows,
+        ))
+    }
+
+    /// 更新目标；指标、方向或周期变化后旧评估记录不再可比，一并清除
+    ///
+    /// # 返回
+    /// 目标不存在时返回 None
+    pub fn update_goal(&self, id: i64, input: &GoalInput) -> Result<Option<Goal>, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM goal_evaluations
+             WHERE goal_id = ?1
+               AND EXISTS (
+                   SELECT 1 FROM goals
+                   WHERE id = ?1 AND (metric != ?2 OR direction != ?3 OR period != ?4)
+               )",
+            params![
+                id,
+                input.metric.as_str(),
+                input.direction.as_str(),
+                input.period.as_str()
+            ],
+        )?;
+        tx.execute(
+            "UPDATE goals
+             SET name = ?2, metric = ?3, direction = ?4, target = ?5, period = ?6,
+                 enabled = ?7, updated_at = ?8
+             WHERE id = ?1",
+            params![
+                id,
+                input.name,
+                input.metric.as_str(),
+                input.direction.as_str(),
+                input.target,
+                input.period.as_str(),
+                input.enabled,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        let goal = read_goal(&tx, id)?;
+        tx.commit()?;
+        Ok(goal)
+    }
+
+    /// 删除目标及其评估记录
+    ///
+    /// # 返回
+    /// 目标存在并被删除返回 true
+    pub fn delete_goal(&self, id: i64) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let deleted = conn.execute("DELETE FROM goals WHERE id = ?1", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// 获取全部目标（按创建顺序）
+    pub fn get_goals(&self) -> Result<Vec<Goal>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all(
+            &conn,
+            &format!("{} ORDER BY id ASC", GOAL_SELECT),
+            goal_from_row,
+        )
+    }
+
+    /// 写入目标在一个周期内的评估结果，同一周期已有记录时覆盖
+    pub fn upsert_goal_evaluation(
+        &self,
+        evaluation: &GoalEvaluation,
+    ) -> Result<(), RepositoryError> {
+        let conn = self.connection()?;
+        conn.execute(
+            "INSERT INTO goal_evaluations (goal_id, period_start, value, target, met, evaluated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(goal_id, period_start) DO UPDATE SET
+                 value = excluded.value,
+                 target = excluded.target,
+                 met = excluded.met,
+                 evaluated_at = excluded.evaluated_at",
+            params![
+                evaluation.goal_id,
+                evaluation.period_start,
+                evaluation.value,
+                evaluation.target,
+                evaluation.met,
+                evaluation.evaluated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 获取目标在 before 之前开始的周期的评估记录（从新到旧，最多 limit 条）
+    pub fn get_goal_evaluations(
+        &self,
+        goal_id: i64,
+        before: &str,
+        limit: usize,
+    ) -> Result<Vec<GoalEvaluation>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all_with(
+            &conn,
+            "SELECT goal_id, period_start, value, target, met, evaluated_at
+             FROM goal_evaluations
+             WHERE goal_id = ?1 AND period_start < ?2
+             ORDER BY period_start DESC
+             LIMIT ?3",
+            params![goal_id, before, limit as i64],
+            |row| {
+                Ok(GoalEvaluation {
+                    goal_id: row.get(0)?,
+                    period_start: row.get(1)?,
+                    value: row.get(2)?,
+                    target: row.get(3)?,
+                    met: row.get(4)?,
+                    evaluated_at: row.get(5)?,
+                })
+            },
+        )
+    }
+
+    /// 记录一条已发送的通知
+    pub fn insert_notification(
+        &self,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+        payload: &serde_json::Value,
+        channel: DeliveryChannel,
+    ) -> Result<NotificationRecord, RepositoryError> {
+        let conn = self.connection()?;
+        let sent_at = Local::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO notification_history (kind, title, body, payload, channel, sent_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                kind.as_str(),
+                title,
+                body,
+                serde_json::to_string(payload)?,
+                channel.as_str(),
+                sent_at
+            ],
+        )?;
+
+        Ok(NotificationRecord {
+            id: conn.last_insert_rowid(),
+            kind,
+            title: title.to_string(),
+            body: body.to_string(),
+            payload: payload.clone(),
+            channel,
+            sent_at,
+        })
+    }
+
+    /// 获取日期范围内（本地日期，含首尾）发送的通知，按时间倒序
+    pub fn get_notification_history(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<NotificationRecord>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, title, body, payload, channel, sent_at
+             FROM notification_history
+             WHERE substr(sent_at, 1, 10) BETWEEN ?1 AND ?2
+             ORDER BY sent_at DESC, id DESC",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (id, kind, title, body, payload, channel, sent_at) = row?;
+            records.push(NotificationRecord {
+                id,
+                kind: NotificationKind::from_db(&kind),
+                title,
+                body,
+                payload: serde_json::from_str(&payload)?,
+                channel: DeliveryChannel::from_db(&channel),
+                sent_at,
+            });
+        }
+
+        Ok(records)
+    }
+
+    /// 获取最早有记录的日期（YYYY-MM-DD 格式）
+    pub fn get_first_activity_date(&self) -> Result<Option<String>, RepositoryError> {
+        let conn = self.connection()?;
+        conn.query_row("SELECT MIN(date) FROM daily_stats", [], |row| row.get(0))
+            .map_err(RepositoryError::from)
+    }
+
+    /// 记录费用异常，同一天只保留首次检测结果
+    ///
+    /// # 返回
+    /// 新插入返回 true，当天已有记录返回 false
+    pub fn insert_cost_anomaly(&self, anomaly: &CostAnomaly) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+
+        let inserted = conn.execute(
+            "INSERT INTO cost_anomalies (date, cost_usd, baseline_mean_usd, baseline_stddev_usd, weekday_mean_usd, ratio, detected_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(date) DO NOTHING",
+            params![
+                anomaly.date,
+                anomaly.cost_usd,
+                anomaly.baseline_mean_usd,
+                anomaly.baseline_stddev_usd,
+                anomaly.weekday_mean_usd,
+                anomaly.ratio,
+                anomaly.detected_at
+            ],
+        )?;
+
+        Ok(inserted > 0)
+    }
+
+    pub fn get_cost_anomalies(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<CostAnomaly>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, date, cost_usd, baseline_mean_usd, baseline_stddev_usd, weekday_mean_usd, ratio, detected_at
+             FROM cost_anomalies
+             WHERE date BETWEEN ?1 AND ?2
+             ORDER BY date DESC",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(CostAnomaly {
+                id: row.get(0)?,
+                date: row.get(1)?,
+                cost_usd: row.get(2)?,
+                baseline_mean_usd: row.get(3)?,
+                baseline_stddev_usd: row.get(4)?,
+                weekday_mean_usd: row.get(5)?,
+                ratio: row.get(6)?,
+                detected_at: row.get(7)?,
+            })
+        })?;
+
+        let mut anomalies = Vec::new();
+        for row in rows {
+            anomalies.push(row?);
+        }
+
+        Ok(anomalies)
+    }
+
+    /// 记录预算告警，同一天同一级别只保留首次记录
+    ///
+    /// # 返回
+    /// 新插入返回 true，当天该级别已有记录返回 false
+    pub fn insert_budget_alert(
+        &self,
+        date: &str,
+        level: &str,
+        spent_usd: f64,
+        budget_usd: f64,
+    ) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+
+        let inserted = conn.execute(
+            "INSERT INTO budget_alerts (date, level, spent_usd, budget_usd, triggered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(date, level) DO NOTHING",
+            params![
+                date,
+                level,
+                spent_usd,
+                budget_usd,
+                Local::now().to_rfc3339()
+            ],
+        )?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 新建 Webhook 目标
+    pub fn create_webhook_target(
+        &self,
+        input: &WebhookTargetInput,
+    ) -> Result<WebhookTarget, RepositoryError> {
+        let conn = self.connection()?;
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO webhook_targets (name, url, format, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            params![
+                input.name,
+                input.url,
+                input.format.as_str(),
+                input.enabled,
+                now
+            ],
+        )?;
+
+        read_webhook_target(&conn, conn.last_insert_rowid())?.ok_or(RepositoryError::Database(
+            rusqlite::Error::QueryReturnedNoRows,
+        ))
+    }
+
+    /// 更新 Webhook 目标
+    ///
+    /// # 返回
+    /// 目标不存在时返回 None
+    pub fn update_webhook_target(
+        &self,
+        id: i64,
+        input: &WebhookTargetInput,
+    ) -> Result<Option<WebhookTarget>, RepositoryError> {
+        let conn = self.connection()?;
+        conn.execute(
+            "UPDATE webhook_targets
+             SET name = ?2, url = ?3, format = ?4, enabled = ?5, updated_at = ?6
+             WHERE id = ?1",
+            params![
+                id,
+                input.name,
+                input.url,
+                input.format.as_str(),
+                input.enabled,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        read_webhook_target(&conn, id)
+    }
+
+    /// 删除 Webhook 目标及其投递记录
+    ///
+    /// # 返回
+    /// 目标存在并被删除返回 true
+    pub fn delete_webhook_target(&self, id: i64) -> Result<bool, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM webhook_deliveries WHERE target_id = ?1",
+            params![id],
+        )?;
+        let deleted = tx.execute("DELETE FROM webhook_targets WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    /// 获取全部 Webhook 目标（按创建顺序）
+    pub fn get_webhook_targets(&self) -> Result<Vec<WebhookTarget>, RepositoryError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&format!("{} ORDER BY id ASC", WEBHOOK_TARGET_SELECT))?;
+        let rows = stmt.query_map([], webhook_target_from_row)?;
+
+        let mut targets = Vec::new();
+        for row in rows {
+            targets.push(row?);
+        }
+
+        Ok(targets)
+    }
+
+    /// 记录一次 Webhook 投递，只保留最近 MAX_WEBHOOK_DELIVERIES 条
+    pub fn insert_webhook_delivery(
+        &self,
+        target: &WebhookTarget,
+        event: WebhookEvent,
+        title: &str,
+        status: WebhookDeliveryStatus,
+        attempts: u32,
+        error: Option<&str>,
+    ) -> Result<WebhookDelivery, RepositoryError> {
+        let conn = self.connection()?;
+        let delivered_at = Local::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO webhook_deliveries
+                (target_id, target_name, event, title, status, attempts, error, delivered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                target.id,
+                target.name,
+                event.as_str(),
+                title,
+                status.as_str(),
+                attempts,
+                error,
+                delivered_at
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "DELETE FROM webhook_deliveries
+             WHERE id NOT IN (SELECT id FROM webhook_deliveries ORDER BY id DESC LIMIT ?1)",
+            params![MAX_WEBHOOK_DELIVERIES as i64],
+        )?;
+
+        Ok(WebhookDelivery {
+            id,
+            target_id: target.id,
+            target_name: target.name.clone(),
+            event,
+            title: title.to_string(),
+            status,
+            attempts,
+            error: error.map(str::to_string),
+            delivered_at,
+        })
+    }
+
+    /// 获取最近的 Webhook 投递记录（从新到旧，最多 limit 条）
+    pub fn get_webhook_deliveries(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, target_id, target_name, event, title, status, attempts, error, delivered_at
+             FROM webhook_deliveries
+             ORDER BY id DESC
+             LIMIT ?1",
+        )?;
+
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok(WebhookDelivery {
+                id: row.get(0)?,
+                target_id: row.get(1)?,
+                target_name: row.get(2)?,
+                event: WebhookEvent::from_db(&row.get::<_, String>(3)?),
+                title: row.get(4)?,
+                status: WebhookDeliveryStatus::from_db(&row.get::<_, String>(5)?),
+                attempts: row.get(6)?,
+                error: row.get(7)?,
+                delivered_at: row.get(8)?,
+            })
+        })?;
+
+        let mut deliveries = Vec::new();
+        for row in rows {
+            deliveries.push(row?);
+        }
+
+        Ok(deliveries)
+    }
+
+    /// 获取日期范围内有耗时记录的请求样本（本地日期）
+    pub fn get_latency_samples(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<LatencySample>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT provider_id, model, duration_ms
+             FROM message_usage
+             WHERE duration_ms IS NOT NULL
+               AND date(created_at, 'localtime') BETWEEN ?1 AND ?2",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(LatencySample {
+                provider_id: row.get(0)?,
+                model: row.get(1)?,
+                duration_ms: row.get(2)?,
+            })
+        })?;
+
+        let mut samples = Vec::new();
+        for row in rows {
+            samples.push(row?);
+        }
+
+        Ok(samples)
+    }
+
+    /// 获取日期范围内每条消息的输入 / 输出 Token 数（本地日期）
+    ///
+    /// 输入 Token 包含缓存读取与缓存创建，反映每次请求的完整提示词大小
+    pub fn get_message_token_samples(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<MessageTokenSample>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT input_tokens + cache_read_tokens + cache_creation_tokens, output_tokens
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(MessageTokenSample {
+                input_tokens: row.get(0)?,
+                output_tokens: row.get(1)?,
+            })
+        })?;
+
+        let mut samples = Vec::new();
+        for row in rows {
+            samples.push(row?);
+        }
+
+        Ok(samples)
+    }
+
+    /// 获取日期范围内各模型的消息数与短输出消息（输出 Token 低于阈值）的 Token 合计（本地日期）
+    pub fn get_model_output_profiles(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        short_output_tokens: i64,
+    ) -> Result<Vec<ModelOutputProfile>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT model,
+                    COUNT(*),
+                    SUM(CASE WHEN output_tokens < ?3 THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN output_tokens < ?3 THEN input_tokens ELSE 0 END),
+                    SUM(CASE WHEN output_tokens < ?3 THEN output_tokens ELSE 0 END),
+                    SUM(CASE WHEN output_tokens < ?3 THEN cache_read_tokens ELSE 0 END),
+                    SUM(CASE WHEN output_tokens < ?3 THEN cache_creation_tokens ELSE 0 END)
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY model
+             ORDER BY model",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date, short_output_tokens], |row| {
+            Ok(ModelOutputProfile {
+                model: row.get(0)?,
+                message_count: row.get(1)?,
+                short_output_count: row.get(2)?,
+                short_input_tokens: row.get(3)?,
+                short_output_tokens: row.get(4)?,
+                short_cache_read_tokens: row.get(5)?,
+                short_cache_creation_tokens: row.get(6)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 获取日期范围内各会话的时长、消息数与费用（本地日期）
+    pub fn get_session_samples(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<SessionSample>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                (julianday(MAX(created_at)) - julianday(MIN(created_at))) * 86400.0,
+                COUNT(*),
+                COALESCE(SUM(cost_usd), 0)
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY provider_id, session_id",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(SessionSample {
+                duration_seconds: row.get::<_, Option<f64>>(0)?.unwrap_or(0.0),
+                message_count: row.get(1)?,
+                cost_usd: row.get(2)?,
+            })
+        })?;
+
+        let mut samples = Vec::new();
+        for row in rows {
+            samples.push(row?);
+        }
+
+        Ok(samples)
+    }
+
+    /// 记录限流事件，重复扫描到的同一事件只保留一条
+    ///
+    /// # 返回
+    /// 新插入返回 true，已存在返回 false
+    pub fn insert_rate_limit_event(
+        &self,
+        provider_id: i64,
+        event: &RateLimitEvent,
+    ) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+
+        let inserted = conn.execute(
+            "INSERT INTO rate_limit_events (provider_id, session_id, kind, status_code, message, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(provider_id, session_id, occurred_at, kind) DO NOTHING",
+            params![
+                provider_id,
+                event.session_id,
+                event.kind.as_str(),
+                event.status_code,
+                event.message,
+                event.occurred_at
+            ],
+        )?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 按供应商统计日期范围内的限流事件（本地日期）
+    pub fn get_rate_limit_stats(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<RateLimitStats>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                e.provider_id,
+                p.display_name,
+                SUM(CASE WHEN e.kind = 'rate_limited' THEN 1 ELSE 0 END),
+                SUM(CASE WHEN e.kind = 'overloaded' THEN 1 ELSE 0 END),
+                MAX(e.occurred_at)
+             FROM rate_limit_events e
+             LEFT JOIN providers p ON p.id = e.provider_id
+             WHERE date(e.occurred_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY e.provider_id
+             ORDER BY COUNT(*) DESC",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(RateLimitStats {
+                provider_id: row.get(0)?,
+                display_name: row.get(1)?,
+                rate_limited_count: row.get(2)?,
+                overloaded_count: row.get(3)?,
+                last_occurred_at: row.get(4)?,
+            })
+        })?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row?);
+        }
+
+        Ok(stats)
+    }
+
+    /// 记录上下文压缩事件，重复扫描到的同一事件只保留一条
+    ///
+    /// # 返回
+    /// 新插入返回 true，已存在返回 false
+    pub fn insert_compaction_event(
+        &self,
+        provider_id: i64,
+        event: &CompactionEvent,
+    ) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+
+        let inserted = conn.execute(
+            "INSERT INTO compaction_events (provider_id, session_id, trigger, pre_tokens, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(session_id, occurred_at) DO NOTHING",
+            params![
+                provider_id,
+                event.session_id,
+                event.trigger.as_str(),
+                event.pre_tokens,
+                event.occurred_at
+            ],
+        )?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 统计日期范围内的上下文压缩次数与各会话压缩情况（本地日期）
+    ///
+    /// 会话总数取自同一范围内有用量记录的会话，用于计算发生压缩的会话占比
+    pub fn get_compaction_stats(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<CompactionStats, RepositoryError> {
+        let conn = self.connection()?;
+
+        let (total_count, auto_count, avg_pre_tokens): (i64, i64, Option<f64>) = conn.query_row(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(CASE WHEN trigger = 'auto' THEN 1 ELSE 0 END), 0),
+                AVG(pre_tokens)
+             FROM compaction_events
+             WHERE date(occurred_at, 'localtime') BETWEEN ?1 AND ?2",
+            params![start_date, end_date],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let total_sessions: i64 = conn.query_row(
+            "SELECT COUNT(DISTINCT session_id)
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2",
+            params![start_date, end_date],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                e.session_id,
+                s.title,
+                COUNT(*),
+                SUM(CASE WHEN e.trigger = 'auto' THEN 1 ELSE 0 END),
+                MAX(e.occurred_at)
+             FROM compaction_events e
+             LEFT JOIN sessions s ON s.session_id = e.session_id
+             WHERE date(e.occurred_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY e.session_id
+             ORDER BY COUNT(*) DESC, MAX(e.occurred_at) DESC",
+        )?;
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(SessionCompaction {
+                session_id: row.get(0)?,
+                title: row.get(1)?,
+                compaction_count: row.get(2)?,
+                auto_count: row.get(3)?,
+                last_compacted_at: row.get(4)?,
+            })
+        })?;
+        let sessions = rows.collect::<Result<Vec<_>, _>>()?;
+
+        // 压缩事件可能来自没有用量记录的会话，总数至少为发生压缩的会话数
+        let compacted_sessions = sessions.len() as i64;
+        let total_sessions = total_sessions.max(compacted_sessions);
+
+        Ok(CompactionStats {
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            total_count,
+            auto_count,
+            manual_count: total_count - auto_count,
+            avg_pre_tokens,
+            compacted_sessions,
+            total_sessions,
+            compacted_session_rate: if total_sessions > 0 {
+                compacted_sessions as f64 / total_sessions as f64
+            } else {
+                0.0
+            },
+            sessions,
+        })
+    }
+
+    /// 按服务层级汇总日期范围内的用量（本地日期），按费用降序
+    pub fn get_service_tier_usage(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<ServiceTierUsage>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT service_tier, SUM(input_tokens), SUM(output_tokens), SUM(cache_read_tokens), SUM(cache_creation_tokens), SUM(cost_usd), COUNT(*)
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY service_tier
+             ORDER BY SUM(cost_usd) DESC, service_tier",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(ServiceTierUsage {
+                service_tier: ServiceTier::from_db(&row.get::<_, String>(0)?),
+                input_tokens: row.get(1)?,
+                output_tokens: row.get(2)?,
+                cache_read_tokens: row.get(3)?,
+                cache_creation_tokens: row.get(4)?,
+                cost_usd: row.get(5)?,
+                message_count: row.get(6)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 记录工具调用，重复扫描到的同一 tool_use 块只保留一条
+    ///
+    /// # 返回
+    /// 新插入的条数
+    pub fn insert_tool_calls(
+        &self,
+        provider_id: i64,
+        calls: &[ToolCall],
+    ) -> Result<usize, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO tool_calls (provider_id, session_id, message_id, tool_use_id, tool_name, called_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(tool_use_id) DO NOTHING",
+            )?;
+            for call in calls {
+                inserted += stmt.execute(params![
+                    provider_id,
+                    call.session_id,
+                    call.message_id,
+                    call.tool_use_id,
+                    call.tool_name,
+                    call.called_at
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(inserted)
+    }
+
+    /// 按工具统计日期范围内的调用次数（本地日期），按调用次数降序
+    pub fn get_tool_call_stats(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<ToolCallStats>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                tool_name,
+                COUNT(*),
+                COUNT(DISTINCT session_id),
+                MAX(called_at),
+                CAST(COUNT(*) AS REAL) / SUM(COUNT(*)) OVER ()
+             FROM tool_calls
+             WHERE date(called_at, 'localtime') BETWEEN ?1 AND ?2
+             GROUP BY tool_name
+             ORDER BY COUNT(*) DESC, tool_name",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(ToolCallStats {
+                tool_name: row.get(0)?,
+                call_count: row.get(1)?,
+                session_count: row.get(2)?,
+                last_called_at: row.get(3)?,
+                share: row.get(4)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 记录用户中断，重复扫描到的同一中断只保留一条
+    ///
+    /// # 返回
+    /// 新插入返回 true，已存在返回 false
+    pub fn insert_interrupt(
+        &self,
+        provider_id: i64,
+        event: &InterruptEvent,
+    ) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+
+        let inserted = conn.execute(
+            "INSERT INTO interrupts (provider_id, session_id, kind, occurred_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(session_id, occurred_at) DO NOTHING",
+            params![
+                provider_id,
+                event.session_id,
+                event.kind.as_str(),
+                event.occurred_at
+            ],
+        )?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 统计日期范围内用户中断丢弃的用量（本地日期），分别按日期与会话汇总
+    ///
+    /// 被丢弃的生成取中断时间之前同一会话的最后一条消息；
+    /// 多次中断指向同一条消息时只计一次 Token 与费用
+    pub fn get_interrupt_stats(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<InterruptStats, RepositoryError> {
+        // linked：每条被中断的消息一行，附带中断次数；找不到消息时 message_row_id 为 NULL
+        const LINKED: &str = "WITH interrupted AS (
+                SELECT
+                    i.session_id,
+                    date(i.occurred_at, 'localtime') AS date,
+                    (SELECT m.id FROM message_usage m
+                     WHERE m.session_id = i.session_id
+                       AND julianday(m.created_at) <= julianday(i.occurred_at)
+                     ORDER BY julianday(m.created_at) DESC, m.id DESC
+                     LIMIT 1) AS message_row_id
+                FROM interrupts i
+                WHERE date(i.occurred_at, 'localtime') BETWEEN ?1 AND ?2
+             ),
+             linked AS (
+                SELECT date, session_id, message_row_id, COUNT(*) AS interrupt_count
+                FROM interrupted
+                GROUP BY date, session_id, message_row_id
+             )";
+        const USAGE_COLUMNS: &str = "COALESCE(SUM(l.interrupt_count), 0),
+                COALESCE(SUM(m.input_tokens + m.output_tokens + m.cache_read_tokens + m.cache_creation_tokens), 0),
+                COALESCE(SUM(m.output_tokens), 0),
+                COALESCE(SUM(m.cost_usd), 0.0)";
+
+        let conn = self.connection()?;
+
+        let total = conn.query_row(
+            &format!(
+                "{LINKED}
+                 SELECT {USAGE_COLUMNS}
+                 FROM linked l
+                 LEFT JOIN message_usage m ON m.id = l.message_row_id"
+            ),
+            params![start_date, end_date],
+            |row| discarded_usage_from_row(row, 0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "{LINKED}
+             SELECT l.date, {USAGE_COLUMNS}
+             FROM linked l
+             LEFT JOIN message_usage m ON m.id = l.message_row_id
+             GROUP BY l.date
+             ORDER BY l.date"
+        ))?;
+        let daily = stmt
+            .query_map(params![start_date, end_date], |row| {
+                Ok(DailyDiscardedUsage {
+                    date: row.get(0)?,
+                    usage: discarded_usage_from_row(row, 1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "{LINKED}
+             SELECT l.session_id, s.title, {USAGE_COLUMNS}
+             FROM linked l
+             LEFT JOIN message_usage m ON m.id = l.message_row_id
+             LEFT JOIN sessions s ON s.session_id = l.session_id
+             GROUP BY l.session_id
+             ORDER BY 4 DESC, l.session_id"
+        ))?;
+        let sessions = stmt
+            .query_map(params![start_date, end_date], |row| {
+                Ok(SessionDiscardedUsage {
+                    session_id: row.get(0)?,
+                    title: row.get(1)?,
+                    usage: discarded_usage_from_row(row, 2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(InterruptStats {
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            total,
+            daily,
+            sessions,
+        })
+    }
+
+    /// 记录 API 错误，重复扫描到的同一错误只保留一条
+    ///
+    /// # 返回
+    /// 新插入返回 true，已存在返回 false
+    pub fn insert_api_error(
+        &self,
+        provider_id: i64,
+        error: &ApiErrorEvent,
+    ) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+
+        let inserted = conn.execute(
+            "INSERT INTO api_errors (provider_id, session_id, kind, status_code, message, is_retry, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(provider_id, session_id, occurred_at, kind) DO NOTHING",
+            params![
+                provider_id,
+                error.session_id,
+                error.kind.as_str(),
+                error.status_code,
+                error.message,
+                error.is_retry,
+                error.occurred_at
+            ],
+        )?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 按供应商统计日期范围内的 API 错误与错误率（本地日期）
+    ///
+    /// 成功请求数取自同一范围内的 message_usage 记录，
+    /// 只有成功请求或只有错误的供应商都会出现在结果中，按错误率从高到低排序
+    pub fn get_api_error_stats(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<ApiErrorStats>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "WITH errors AS (
+                SELECT
+                    provider_id,
+                    COUNT(*) AS error_count,
+                    SUM(is_retry) AS retry_count,
+                    SUM(CASE WHEN kind = 'rate_limited' THEN 1 ELSE 0 END) AS rate_limited_count,
+                    SUM(CASE WHEN kind = 'overloaded' THEN 1 ELSE 0 END) AS overloaded_count,
+                    SUM(CASE WHEN kind = 'timeout' THEN 1 ELSE 0 END) AS timeout_count,
+                    SUM(CASE WHEN kind = 'server' THEN 1 ELSE 0 END) AS server_error_count,
+                    SUM(CASE WHEN kind = 'client' THEN 1 ELSE 0 END) AS client_error_count
+                FROM api_errors
+                WHERE date(occurred_at, 'localtime') BETWEEN ?1 AND ?2
+                GROUP BY provider_id
+             ),
+             requests AS (
+                SELECT provider_id, COUNT(*) AS request_count
+                FROM message_usage
+                WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+                GROUP BY provider_id
+             )
+             SELECT
+                p.id,
+                p.display_name,
+                COALESCE(r.request_count, 0),
+                COALESCE(e.error_count, 0),
+                COALESCE(e.retry_count, 0),
+                COALESCE(e.rate_limited_count, 0),
+                COALESCE(e.overloaded_count, 0),
+                COALESCE(e.timeout_count, 0),
+                COALESCE(e.server_error_count, 0),
+                COALESCE(e.client_error_count, 0)
+             FROM providers p
+             LEFT JOIN errors e ON e.provider_id = p.id
+             LEFT JOIN requests r ON r.provider_id = p.id
+             WHERE e.provider_id IS NOT NULL OR r.provider_id IS NOT NULL
+             ORDER BY
+                CAST(COALESCE(e.error_count, 0) AS REAL)
+                    / (COALESCE(r.request_count, 0) + COALESCE(e.error_count, 0)) DESC,
+                p.id",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            let request_count: i64 = row.get(2)?;
+            let error_count: i64 = row.get(3)?;
+            let total = request_count + error_count;
+            Ok(ApiErrorStats {
+                provider_id: row.get(0)?,
+                display_name: row.get(1)?,
+                request_count,
+                error_count,
+                error_rate: if total > 0 {
+                    error_count as f64 / total as f64
+                } else {
+                    0.0
+                },
+                retry_count: row.get(4)?,
+                rate_limited_count: row.get(5)?,
+                overloaded_count: row.get(6)?,
+                timeout_count: row.get(7)?,
+                server_error_count: row.get(8)?,
+                client_error_count: row.get(9)?,
+            })
+        })?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row?);
+        }
+
+        Ok(stats)
+    }
+
+    /// 按日期对比多个供应商的费用、Token、缓存命中率、耗时与错误率（本地日期）
+    ///
+    /// 业务逻辑：
+    /// 1. 递归 CTE 生成 start_date 至 end_date 的完整日期序列
+    /// 2. 分别汇总 message_usage 与 api_errors 后按日期左连接，无数据的日期补零
+    /// 3. 不存在的供应商 ID 被忽略，结果顺序与传入的 ID 顺序一致
+    pub fn compare_providers(
+        &self,
+        provider_ids: &[i64],
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<ProviderComparison>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let cache_hit_rate: CacheHitRateSettings = read_setting(&conn)?;
+        let mut provider_stmt = conn.prepare("SELECT display_name FROM providers WHERE id = ?1")?;
+        let mut series_stmt = conn.prepare(
+            "WITH RECURSIVE dates(date) AS (
+                SELECT date(?2)
+                UNION ALL
+                SELECT date(date, '+1 day') FROM dates WHERE date < date(?3)
+             ),
+             usage AS (
+                SELECT
+                    date(created_at, 'localtime') AS date,
+                    SUM(cost_usd) AS cost_usd,
+                    SUM(input_tokens + output_tokens) AS total_tokens,
+                    SUM(input_tokens) AS input_tokens,
+                    SUM(cache_read_tokens) AS cache_read_tokens,
+                    SUM(cache_creation_tokens) AS cache_creation_tokens,
+                    AVG(duration_ms) AS avg_latency_ms,
+                    COUNT(*) AS request_count
+                FROM message_usage
+                WHERE provider_id = ?1 AND date(created_at, 'localtime') BETWEEN ?2 AND ?3
+                GROUP BY date(created_at, 'localtime')
+             ),
+             errors AS (
+                SELECT date(occurred_at, 'localtime') AS date, COUNT(*) AS error_count
+                FROM api_errors
+                WHERE provider_id = ?1 AND date(occurred_at, 'localtime') BETWEEN ?2 AND ?3
+                GROUP BY date(occurred_at, 'localtime')
+             )
+             SELECT
+                d.date,
+                COALESCE(u.cost_usd, 0),
+                COALESCE(u.total_tokens, 0),
+                COALESCE(u.cache_read_tokens, 0),
+                COALESCE(u.input_tokens, 0),
+                COALESCE(u.cache_creation_tokens, 0),
+                u.avg_latency_ms,
+                COALESCE(u.request_count, 0),
+                COALESCE(e.error_count, 0),
+                CASE WHEN COALESCE(u.request_count, 0) + COALESCE(e.error_count, 0) > 0
+                    THEN CAST(COALESCE(e.error_count, 0) AS REAL)
+                        / (COALESCE(u.request_count, 0) + COALESCE(e.error_count, 0))
+                    ELSE 0.0 END
+             FROM dates d
+             LEFT JOIN usage u ON u.date = d.date
+             LEFT JOIN errors e ON e.date = d.date
+             WHERE d.date <= date(?3)
+             ORDER BY d.date",
+        )?;
+
+        let mut comparisons = Vec::with_capacity(provider_ids.len());
+        for &provider_id in provider_ids {
+            let display_name: Option<Option<String>> = provider_stmt
+                .query_row(params![provider_id], |row| row.get(0))
+                .optional()?;
+            let Some(display_name) = display_name else {
+                continue;
+            };
+
+            let rows =
+                series_stmt.query_map(params![provider_id, start_date, end_date], |row| {
+                    Ok(ProviderComparisonPoint {
+                        date: row.get(0)?,
+                        cost_usd: row.get(1)?,
+                        total_tokens: row.get(2)?,
+                        cache_hit_rate: cache_hit_rate.formula.rate(
+                            row.get(3)?,
+                            row.get(4)?,
+                            row.get(5)?,
+                        ),
+                        avg_latency_ms: row.get(6)?,
+                        request_count: row.get(7)?,
+                        error_count: row.get(8)?,
+                        error_rate: row.get(9)?,
+                    })
+                })?;
+
+            let mut points = Vec::new();
+            for row in rows {
+                points.push(row?);
+            }
+
+            comparisons.push(ProviderComparison {
+                provider_id,
+                display_name,
+                points,
+            });
+        }
+
+        Ok(comparisons)
+    }
+
+    /// 为会话或日期添加标签，标签不存在时自动创建
+    ///
+    /// # 返回
+    /// 新建关联返回 true，关联已存在返回 false
+    pub fn add_tag(&self, name: &str, target: &TagTarget) -> Result<bool, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO tags (name, created_at) VALUES (?1, ?2)
+             ON CONFLICT(name) DO NOTHING",
+            params![name, Utc::now().to_rfc3339()],
+        )?;
+        let tag_id: i64 = tx.query_row(
+            "SELECT id FROM tags WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )?;
+
+        let (table, column, value) = tag_target_columns(target);
+        let inserted = tx.execute(
+            &format!(
+                "INSERT INTO {table} (tag_id, {column}) VALUES (?1, ?2)
+                 ON CONFLICT(tag_id, {column}) DO NOTHING"
+            ),
+            params![tag_id, value],
+        )?;
+
+        tx.commit()?;
+        Ok(inserted > 0)
+    }
+
+    /// 移除会话或日期上的标签，标签不再关联任何对象时一并删除
+    ///
+    /// # 返回
+    /// 关联存在并被移除返回 true
+    pub fn remove_tag(&self, name: &str, target: &TagTarget) -> Result<bool, RepositoryError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        let (table, column, value) = tag_target_columns(target);
+        let removed = tx.execute(
+            &format!(
+                "DELETE FROM {table}
+                 WHERE {column} = ?2 AND tag_id = (SELECT id FROM tags WHERE name = ?1)"
+            ),
+            params![name, value],
+        )?;
+        tx.execute(
+            "DELETE FROM tags
+             WHERE name = ?1
+               AND NOT EXISTS (SELECT 1 FROM session_tags WHERE tag_id = tags.id)
+               AND NOT EXISTS (SELECT 1 FROM date_tags WHERE tag_id = tags.id)",
+            params![name],
+        )?;
+
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    /// 按标签统计用量，可限定日期范围（本地日期）
+    ///
+    /// 消息属于标签关联的会话，或发生在标签关联的日期当天，即计入该标签；
+    /// 同一条消息同时满足两者时只计一次。结果按费用从高到低排序
+    pub fn get_stats_by_tag(
+        &self,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<Vec<TagStats>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                t.id,
+                t.name,
+                (SELECT COUNT(*) FROM session_tags WHERE tag_id = t.id),
+                (SELECT COUNT(*) FROM date_tags WHERE tag_id = t.id),
+                COUNT(m.id),
+                COALESCE(SUM(m.input_tokens + m.output_tokens), 0),
+                COALESCE(SUM(m.cost_usd), 0)
+             FROM tags t
+             LEFT JOIN message_usage m
+                ON (m.session_id IN (SELECT session_id FROM session_tags WHERE tag_id = t.id)
+                    OR date(m.created_at, 'localtime') IN (SELECT date FROM date_tags WHERE tag_id = t.id))
+                AND (?1 IS NULL OR date(m.created_at, 'localtime') >= ?1)
+                AND (?2 IS NULL OR date(m.created_at, 'localtime') <= ?2)
+             GROUP BY t.id
+             ORDER BY COALESCE(SUM(m.cost_usd), 0) DESC, t.name",
+        )?;
+
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(TagStats {
+                tag_id: row.get(0)?,
+                name: row.get(1)?,
+                tagged_session_count: row.get(2)?,
+                tagged_day_count: row.get(3)?,
+                message_count: row.get(4)?,
+                total_tokens: row.get(5)?,
+                cost_usd: row.get(6)?,
+            })
+        })?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row?);
+        }
+
+        Ok(stats)
+    }
+
+    /// 日期范围内的总费用（本地日期）
+    pub fn get_range_cost(&self, start_date: &str, end_date: &str) -> Result<f64, RepositoryError> {
+        let conn = self.connection()?;
+        let cost = conn.query_row(
+            "SELECT COALESCE(SUM(cost_usd), 0)
+             FROM message_usage
+             WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2",
+            params![start_date, end_date],
+            |row| row.get(0),
+        )?;
+        Ok(cost)
+    }
+
+    /// 按项目或标签拆分日期范围内的费用（本地日期），按费用从高到低排序
+    ///
+    /// 按标签分组时只包含带标签的消息，规则与 get_stats_by_tag 一致
+    pub fn get_cost_allocation(
+        &self,
+        group_by: AllocationGroupBy,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<CostAllocationRow>, RepositoryError> {
+        const COLUMNS: &str = "COUNT(DISTINCT m.session_id),
+                COUNT(m.id),
+                COALESCE(SUM(m.input_tokens), 0),
+                COALESCE(SUM(m.output_tokens), 0),
+                COALESCE(SUM(m.cache_read_tokens), 0),
+                COALESCE(SUM(m.cache_creation_tokens), 0),
+                COALESCE(SUM(m.cost_usd), 0),
+                COALESCE(SUM(m.cost_usd) / NULLIF((
+                    SELECT SUM(cost_usd) FROM message_usage
+                    WHERE date(created_at, 'localtime') BETWEEN ?1 AND ?2
+                ), 0), 0)";
+
+        let sql = match group_by {
+            AllocationGroupBy::Project => format!(
+                "SELECT m.project, {COLUMNS}
+                 FROM message_usage m
+                 WHERE date(m.created_at, 'localtime') BETWEEN ?1 AND ?2
+                 GROUP BY m.project
+                 ORDER BY SUM(m.cost_usd) DESC"
+            ),
+            AllocationGroupBy::Tag => format!(
+                "SELECT t.name, {COLUMNS}
+                 FROM tags t
+                 JOIN message_usage m
+                    ON m.session_id IN (SELECT session_id FROM session_tags WHERE tag_id = t.id)
+                    OR date(m.created_at, 'localtime') IN (SELECT date FROM date_tags WHERE tag_id = t.id)
+                 WHERE date(m.created_at, 'localtime') BETWEEN ?1 AND ?2
+                 GROUP BY t.id
+                 ORDER BY SUM(m.cost_usd) DESC, t.name"
+            ),
+        };
+
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(CostAllocationRow {
+                group: row.get(0)?,
+                session_count: row.get(1)?,
+                message_count: row.get(2)?,
+                input_tokens: row.get(3)?,
+                output_tokens: row.get(4)?,
+                cache_read_tokens: row.get(5)?,
+                cache_creation_tokens: row.get(6)?,
+                cost_usd: row.get(7)?,
+                cost_share: row.get(8)?,
+            })
+        })?;
+
+        let mut allocation = Vec::new();
+        for row in rows {
+            allocation.push(row?);
+        }
+
+        Ok(allocation)
+    }
+
+    /// 导出所有可合并的数据为快照（format_version 与 exported_at 由调用方填写）
+    pub fn export_snapshot(&self) -> Result<Snapshot, RepositoryError> {
+        let conn = self.connection()?;
+        let provider_data = export_provider_data(&conn, None)?;
+
+        let sessions = query_all(
+            &conn,
+            "SELECT session_id, title, title_source, updated_at FROM sessions ORDER BY session_id",
+            |row| {
+                Ok(SnapshotSession {
+                    session_id: row.get(0)?,
+                    title: row.get(1)?,
+                    title_source: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            },
+        )?;
+
+        let mut tags: BTreeMap<i64, SnapshotTag> =
+            query_all(&conn, "SELECT id, name, created_at FROM tags", |row| {
+                Ok((
+                    row.get(0)?,
+                    SnapshotTag {
+                        name: row.get(1)?,
+                        created_at: row.get(2)?,
+                        session_ids: Vec::new(),
+                        dates: Vec::new(),
+                    },
+                ))
+            })?
+            .into_iter()
+            .collect();
+        for (tag_id, session_id) in query_all(
+            &conn,
+            "SELECT tag_id, session_id FROM session_tags ORDER BY session_id",
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+        )? {
+            if let Some(tag) = tags.get_mut(&tag_id) {
+                tag.session_ids.push(session_id);
+            }
+        }
+        for (tag_id, date) in query_all(
+            &conn,
+            "SELECT tag_id, date FROM date_tags ORDER BY date",
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+        )? {
+            if let Some(tag) = tags.get_mut(&tag_id) {
+                tag.dates.push(date);
+            }
+        }
+
+        let date_notes = query_all(
+            &conn,
+            "SELECT date, note, updated_at FROM date_notes ORDER BY date",
+            |row| {
+                Ok(DateNote {
+                    date: row.get(0)?,
+                    note: row.get(1)?,
+                    updated_at: row.get(2)?,
+                })
+            },
+        )?;
+
+        Ok(Snapshot {
+            sessions,
+            tags: tags.into_values().collect(),
+            date_notes,
+            ..provider_data
+        })
+    }
+
+    /// 将快照合并到本地数据库，完成后根据消息记录重建每日汇总
+    ///
+    /// 合并规则：
+    /// 1. 供应商按 api_key_hash 匹配，本地缺失的名称、地址由快照补全
+    /// 2. 消息按与实时采集相同的去重规则合并，已存在的消息跳过
+    /// 3. 会话标题中 summary 优先于首条用户输入，同来源的 summary 以较新的为准
+    /// 4. 套餐与日期备注以 updated_at 较新的一方为准
+    /// 5. 事件、切换记录与标签关联只补充本地缺失的记录
+    pub fn import_snapshot(
+        &self,
+        snapshot: &Snapshot,
+        policy: DedupePolicy,
+    ) -> Result<SnapshotImportSummary, RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+        let summary = import_snapshot_in(&tx, snapshot, policy)?;
+        tx.commit()?;
+        drop(conn);
+
+        if summary.messages_added > 0 {
+            self.rebuild_daily_stats()?;
+        }
+        Ok(summary)
+    }
+
+    /// 在同一事务中批量写入外部导入的消息并重建每日汇总
+    ///
+    /// 已存在的消息由 message_usage 唯一索引忽略；与实时采集一致，
+    /// 订阅供应商的实际费用记为 0，消息费用只计入 API 等价费用
+    ///
+    /// # 返回
+    /// (新增消息数, 跳过消息数)
+    pub fn import_message_records(
+        &self,
+        provider_id: i64,
+        records: &[crate::models::MessageRecord],
+    ) -> Result<(usize, usize), RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+
+        let kind: Option<String> = tx
+            .query_row(
+                "SELECT kind FROM providers WHERE id = ?1",
+                params![provider_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let is_subscription =
+            kind.as_deref().map(ProviderKind::from_db) == Some(ProviderKind::Subscription);
+
+        let mut added = 0;
+        for record in records {
+            let cost_usd = if is_subscription {
+                0.0
+            } else {
+                record.usage.cost_usd
+            };
+            added += tx.execute(
+                "INSERT INTO message_usage (provider_id, session_id, message_id, model, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at, project, duration_ms, api_equivalent_cost_usd, thinking_tokens, service_tier)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                 ON CONFLICT DO NOTHING",
+                params![
+                    provider_id,
+                    record.session_id,
+                    record.message_id,
+                    record.model,
+                    record.usage.input_tokens,
+                    record.usage.output_tokens,
+                    record.usage.cache_read_tokens,
+                    record.usage.cache_creation_tokens,
+                    cost_usd,
+                    record.created_at,
+                    record.project,
+                    record.duration_ms,
+                    record.usage.cost_usd,
+                    record.usage.thinking_tokens,
+                    record.service_tier.as_str()
+                ],
+            )?;
+        }
+        if added > 0 {
+            rebuild_daily_stats_in(&tx)?;
+        }
+        tx.commit()?;
+
+        Ok((added, records.len() - added))
+    }
+
+    /// 是否已有消息用量记录
+    pub fn has_message_usage(&self) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let exists: bool =
+            conn.query_row("SELECT EXISTS(SELECT 1 FROM message_usage)", [], |row| {
+                row.get(0)
+            })?;
+        Ok(exists)
+    }
+
+    /// 查找 message_id 重复的消息记录，按多计费用降序
+    ///
+    /// 解析时缺少 ID 的消息（message_id 为 unknown）不参与检查
+    pub fn get_duplicate_messages(&self) -> Result<Vec<DuplicateGroup>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all(
+            &conn,
+            "WITH groups AS (
+                SELECT message_id,
+                       MIN(id) AS kept_id,
+                       COUNT(*) AS row_count,
+                       COUNT(DISTINCT provider_id) AS provider_count,
+                       GROUP_CONCAT(DISTINCT provider_id) AS provider_ids,
+                       SUM(input_tokens + output_tokens + cache_read_tokens + cache_creation_tokens) AS tokens,
+                       SUM(cost_usd) AS cost_usd,
+                       MIN(created_at) AS first_created_at,
+                       MAX(created_at) AS last_created_at
+                FROM message_usage
+                WHERE message_id != 'unknown'
+                GROUP BY message_id
+                HAVING COUNT(*) > 1
+             )
+             SELECT g.message_id, k.session_id, g.provider_count, g.provider_ids, g.row_count, g.kept_id,
+                    g.tokens - (k.input_tokens + k.output_tokens + k.cache_read_tokens + k.cache_creation_tokens),
+                    g.cost_usd - k.cost_usd,
+                    g.first_created_at, g.last_created_at
+             FROM groups g
+             JOIN message_usage k ON k.id = g.kept_id
+             ORDER BY g.cost_usd - k.cost_usd DESC, g.message_id",
+            |row| {
+                let provider_count: i64 = row.get(2)?;
+                let provider_ids: String = row.get(3)?;
+                Ok(DuplicateGroup {
+                    kind: if provider_count > 1 {
+                        DuplicateKind::CrossProvider
+                    } else {
+                        DuplicateKind::RepeatedSessionMessage
+                    },
+                    message_id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    provider_ids: provider_ids
+                        .split(',')
+                        .filter_map(|id| id.parse().ok())
+                        .collect(),
+                    row_count: row.get(4)?,
+                    kept_id: row.get(5)?,
+                    duplicate_tokens: row.get(6)?,
+                    duplicate_cost_usd: row.get(7)?,
+                    first_created_at: row.get(8)?,
+                    last_created_at: row.get(9)?,
+                })
+            },
+        )
+    }
+
+    /// 删除重复消息，每个 message_id 只保留最早写入的一条，并重建每日汇总
+    ///
+    /// 被删除的消息连同所属供应商移入回收站，保留期内可恢复
+    ///
+    /// # 返回
+    /// 回收站条目，没有重复消息时返回 None
+    pub fn delete_duplicate_messages(&self) -> Result<Option<DeletedItem>, RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+        let messages = query_all_with(
+            &tx,
+            &format!(
+                "{} WHERE {} ORDER BY m.id",
+                SNAPSHOT_MESSAGE_SELECT, DUPLICATE_MESSAGE_CONDITION
+            ),
+            [],
+            snapshot_message_from_row,
+        )?;
+        if messages.is_empty() {
+            return Ok(None);
+        }
+        let providers = query_all_with(
+            &tx,
+            &format!(
+                "{} WHERE id IN (SELECT m.provider_id FROM message_usage m WHERE {}) ORDER BY id",
+                SNAPSHOT_PROVIDER_SELECT, DUPLICATE_MESSAGE_CONDITION
+            ),
+            [],
+            snapshot_provider_from_row,
+        )?;
+        let snapshot = Snapshot {
+            schema_version: current_version(&tx)?,
+            providers,
+            messages,
+            ..Default::default()
+        };
+
+        let label = DUPLICATE_MESSAGES_LABEL.to_string();
+        let message_count = snapshot.messages.len() as i64;
+        let deleted_at = Utc::now().to_rfc3339();
+        tx.execute(
+            "INSERT INTO deleted_items (kind, source_id, label, message_count, payload, deleted_at)
+             VALUES (?1, 0, ?2, ?3, ?4, ?5)",
+            params![
+                DeletedItemKind::DuplicateMessages.as_str(),
+                label,
+                message_count,
+                serde_json::to_string(&snapshot)?,
+                deleted_at
+            ],
+        )?;
+        let item = DeletedItem {
+            id: tx.last_insert_rowid(),
+            kind: DeletedItemKind::DuplicateMessages,
+            source_id: 0,
+            label,
+            message_count,
+            deleted_at,
+        };
+
+        tx.execute(
+            &format!(
+                "DELETE FROM message_usage AS m WHERE {}",
+                DUPLICATE_MESSAGE_CONDITION
+            ),
+            [],
+        )?;
+        rebuild_daily_stats_in(&tx)?;
+        tx.commit()?;
+        Ok(Some(item))
+    }
+
+    /// 读取 daily_stats 中存储的每日汇总，按 (供应商 ID, 日期) 索引
+    pub fn get_stored_daily_totals(
+        &self,
+    ) -> Result<BTreeMap<DailyKey, DailyStatsTotals>, RepositoryError> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT provider_id, date, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, session_count, message_count, total_api_equivalent_cost_usd
+             FROM daily_stats",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                (row.get::<_, i64>(0)?, row.get::<_, String>(1)?),
+                DailyStatsTotals {
+                    input_tokens: row.get(2)?,
+                    output_tokens: row.get(3)?,
+                    cache_read_tokens: row.get(4)?,
+                    cache_creation_tokens: row.get(5)?,
+                    cost_usd: row.get(6)?,
+                    session_count: row.get(7)?,
+                    message_count: row.get(8)?,
+                    api_equivalent_cost_usd: row.get(9)?,
+                },
+            ))
+        })?;
+
+        let mut totals = BTreeMap::new();
+        for row in rows {
+            let (key, value) = row?;
+            totals.insert(key, value);
+        }
+
+        Ok(totals)
+    }
+
+    /// 从 message_usage 重新汇总每日数据，按 (供应商 ID, 日期) 索引
+    pub fn recompute_daily_totals(
+        &self,
+    ) -> Result<BTreeMap<DailyKey, DailyStatsTotals>, RepositoryError> {
+        let conn = self.connection()?;
+        Ok(aggregate_message_usage(&conn)?.0)
+    }
+
+    /// 根据 message_usage 重建 daily_stats 与 model_daily_stats
+    ///
+    /// 在事务中清空两张汇总表后重新写入，失败时不影响原有数据
+    ///
+    /// # 返回
+    /// 重建后 daily_stats 的记录数
+    pub fn rebuild_daily_stats(&self) -> Result<usize, RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+        let rebuilt = rebuild_daily_stats_in(&tx)?;
+        tx.commit()?;
+        Ok(rebuilt)
+    }
+
+    /// 获取全部模型别名
+    pub fn get_model_aliases(&self) -> Result<Vec<ModelAlias>, RepositoryError> {
+        let conn = self.connection()?;
+        query_all(
+            &conn,
+            "SELECT alias, canonical, updated_at FROM model_aliases ORDER BY alias ASC",
+            |row| {
+                Ok(ModelAlias {
+                    alias: row.get(0)?,
+                    canonical: row.get(1)?,
+                    updated_at: row.get(2)?,
+                })
+            },
+        )
+    }
+
+    /// 设置模型别名，已存在时覆盖
+    ///
+    /// 同一事务内将已有记录中的别名改写为标准模型名，并重建每日汇总，保证按模型统计合并到一起。
+    /// 订阅供应商的记录若因别名无定价导致等价费用为 0，改写时用 estimate_cost 按标准模型重新估算
+    ///
+    /// # 参数
+    /// - `estimate_cost`: 按 (created_at, input, output, cache_read, cache_creation) 估算标准模型在消息时间的标准层级费用，
+    ///   写入时再乘以消息服务层级的倍率
+    ///
+    /// # 返回
+    /// 保存后的别名与被改写的消息条数
+    pub fn set_model_alias(
+        &self,
+        alias: &str,
+        canonical: &str,
+        estimate_cost: impl Fn(&str, i64, i64, i64, i64) -> f64,
+    ) -> Result<(ModelAlias, usize), RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+        let updated_at = Utc::now().to_rfc3339();
+
+        tx.execute(
+            "INSERT INTO model_aliases (alias, canonical, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(alias) DO UPDATE SET canonical = excluded.canonical, updated_at = excluded.updated_at",
+            params![alias, canonical, updated_at],
+        )?;
+
+        let unpriced = {
+            let mut stmt = tx.prepare(
+                "SELECT id, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, service_tier, created_at
+                 FROM message_usage
+                 WHERE model = ?1 AND api_equivalent_cost_usd = 0
+                   AND provider_id IN (SELECT id FROM providers WHERE kind = 'subscription')",
+            )?;
+            let rows = stmt.query_map(params![alias], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    ServiceTier::from_db(&row.get::<_, String>(5)?),
+                    row.get::<_, String>(6)?,
+                ))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        for (id, input, output, cache_read, cache_creation, service_tier, created_at) in unpriced {
+            let cost = estimate_cost(&created_at, input, output, cache_read, cache_creation)
+                * service_tier.cost_multiplier();
+            tx.execute(
+                "UPDATE message_usage SET api_equivalent_cost_usd = ?2 WHERE id = ?1",
+                params![id, cost],
+            )?;
+        }
+
+        let renamed = tx.execute(
+            "UPDATE message_usage SET model = ?2 WHERE model = ?1",
+            params![alias, canonical],
+        )?;
+        if renamed > 0 {
+            rebuild_daily_stats_in(&tx)?;
+        }
+
+        tx.commit()?;
+        Ok((
+            ModelAlias {
+                alias: alias.to_string(),
+                canonical: canonical.to_string(),
+                updated_at,
+            },
+            renamed,
+        ))
+    }
+
+    /// 按当前价格重算订阅供应商消息的 API 等价费用
+    ///
+    /// 业务逻辑说明：
+    /// 1. 订阅消息的等价费用由价格表估算，价格更新后逐条用 estimate_cost 重新估算并乘以服务层级倍率
+    /// 2. estimate_cost 返回 None（如模型无定价）的消息保持原值
+    /// 3. 有费用变化时重建每日汇总，并写入一条包含重算前后合计与价格表版本的审计记录
+    ///
+    /// # 参数
+    /// - `estimate_cost`: 按 (model, created_at, input, output, cache_read, cache_creation) 估算标准层级费用
+    ///
+    /// # 返回
+    /// 有费用变化时返回审计记录，否则返回 None
+    pub fn recalculate_costs(
+        &self,
+        pricing_version: &str,
+        estimate_cost: impl Fn(&str, &str, i64, i64, i64, i64) -> Option<f64>,
+    ) -> Result<Option<CostRecalculation>, RepositoryError> {
+        let mut conn = self.connection()?;
+        self.invalidate_stats_cache()?;
+        let tx = conn.transaction()?;
+
+        let messages = {
+            let mut stmt = tx.prepare(
+                "SELECT id, model, created_at, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, service_tier, api_equivalent_cost_usd
+                 FROM message_usage
+                 WHERE provider_id IN (SELECT id FROM providers WHERE kind = 'subscription')",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, i64>(6)?,
+                    ServiceTier::from_db(&row.get::<_, String>(7)?),
+                    row.get::<_, f64>(8)?,
+                ))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut old_total_usd = 0.0;
+        let mut new_total_usd = 0.0;
+        let mut changed_messages = 0;
+        for (id, model, created_at, input, output, cache_read, cache_creation, tier, old_cost) in
+            messages
+        {
+            old_total_usd += old_cost;
+            let new_cost = estimate_cost(
+                &model,
+                &created_at,
+                input,
+                output,
+                cache_read,
+                cache_creation,
+            )
+            .map(|cost| cost * tier.cost_multiplier())
+            .unwrap_or(old_cost);
+            new_total_usd += new_cost;
+            if (new_cost - old_cost).abs() > 1e-9 {
+                tx.execute(
+                    "UPDATE message_usage SET api_equivalent_cost_usd = ?2 WHERE id = ?1",
+                    params![id, new_cost],
+                )?;
+                changed_messages += 1;
+            }
+        }
+        if changed_messages == 0 {
+            return Ok(None);
+        }
+
+        rebuild_daily_stats_in(&tx)?;
+        let recalculated_at = Utc::now().to_rfc3339();
+        tx.execute(
+            "INSERT INTO cost_recalculations (pricing_version, old_total_usd, new_total_usd, changed_messages, recalculated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                pricing_version,
+                old_total_usd,
+                new_total_usd,
+                changed_messages,
+                recalculated_at
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
+
+        Ok(Some(CostRecalculation {
+            id,
+            pricing_version: pricing_version.to_string(),
+            old_total_usd,
+            new_total_usd,
+            changed_messages,
+            recalculated_at,
+        }))
+    }
+
+    /// 获取最近的费用重算审计记录，按时间倒序
+    pub fn get_recalculation_history(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<CostRecalculation>, RepositoryError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, pricing_version, old_total_usd, new_total_usd, changed_messages, recalculated_at
+             FROM cost_recalculations
+             ORDER BY id DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok(CostRecalculation {
+                id: row.get(0)?,
+                pricing_version: row.get(1)?,
+                old_total_usd: row.get(2)?,
+                new_total_usd: row.get(3)?,
+                changed_messages: row.get(4)?,
+                recalculated_at: row.get(5)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    /// 删除模型别名，已改写的历史记录保持标准模型名
+    ///
+    /// # 返回
+    /// 别名存在并被删除返回 true
+    pub fn delete_model_alias(&self, alias: &str) -> Result<bool, RepositoryError> {
+        let conn = self.connection()?;
+        let deleted = conn.execute("DELETE FROM model_aliases WHERE alias = ?1", params![alias])?;
+        Ok(deleted > 0)
+    }
+
+    fn get_provider_by_hash(
+        &self,
+        conn: &Connection,
+        api_key: &str,
+    ) -> Result<Option<Provider>, RepositoryError> {
+        let temp_provider = Provider::new(api_key, None, None);
+
+        conn.query_row(
+            "SELECT id, api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind
+             FROM providers WHERE api_key_hash = ?1",
+            params![temp_provider.api_key_hash],
+            |row| {
+                Ok(Provider {
+                    id: row.get(0)?,
+                    api_key_hash: row.get(1)?,
+                    api_key_prefix: row.get(2)?,
+                    display_name: row.get(3)?,
+                    base_url: row.get(4)?,
+                    is_active: row.get::<_, i64>(5)? == 1,
+                    first_seen_at: row.get(6)?,
+                    last_seen_at: row.get(7)?,
+                    kind: ProviderKind::from_db(&row.get::<_, String>(8)?),
+                })
+            },
+        )
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+}
+
+/// 核心统计的过滤条件，参数依次为 provider_id、model、project
+const STATS_FILTER_CONDITIONS: &str = "(?1 IS NULL OR provider_id = ?1)
+               AND (?2 IS NULL OR model = ?2)
+               AND (?3 IS NULL OR project = ?3)";
+
+/// 直接从 message_usage 汇总统计，日期为本地日期且包含两端，未设置时不限制
+///
+/// 返回结果尚未计算缓存命中率，也未排序模型
+fn aggregate_filtered_stats(
+    conn: &Connection,
+    filters: &StatsFilters,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<StatsCache, RepositoryError> {
+    const RANGE_CONDITIONS: &str = "(?4 IS NULL OR date(created_at, 'localtime') >= ?4)
+               AND (?5 IS NULL OR date(created_at, 'localtime') <= ?5)";
+
+    let mut cache = StatsCache::default();
+
+    let totals: (i64, i64, i64, i64, f64, i64, i64, f64) = conn.query_row(
+        &format!(
+            "SELECT
+                COALESCE(SUM(input_tokens), 0),
+                COALESCE(SUM(output_tokens), 0),
+                COALESCE(SUM(cache_read_tokens), 0),
+                COALESCE(SUM(cache_creation_tokens), 0),
+                COALESCE(SUM(cost_usd), 0),
+                COALESCE(COUNT(DISTINCT session_id), 0),
+                COALESCE(COUNT(*), 0),
+                COALESCE(SUM(api_equivalent_cost_usd), 0)
+             FROM message_usage
+             WHERE {} AND {}",
+            STATS_FILTER_CONDITIONS, RANGE_CONDITIONS
+        ),
+        params![
+            filters.provider_id,
+            filters.model,
+            filters.project,
+            start_date,
+            end_date
+        ],
+        |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+            ))
+        },
+    )?;
+
+    cache.total_input_tokens = totals.0;
+    cache.total_output_tokens = totals.1;
+    cache.total_cache_read_tokens = totals.2;
+    cache.total_cache_creation_tokens = totals.3;
+    cache.total_cost_usd = totals.4;
+    cache.total_sessions = totals.5;
+    cache.total_messages = totals.6;
+    cache.total_api_equivalent_cost_usd = totals.7;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT model, SUM(input_tokens), SUM(output_tokens), SUM(cache_read_tokens), SUM(cache_creation_tokens), SUM(cost_usd), COUNT(*), SUM(thinking_tokens)
+         FROM message_usage
+         WHERE {} AND {}
+         GROUP BY model",
+        STATS_FILTER_CONDITIONS, RANGE_CONDITIONS
+    ))?;
+
+    let rows = stmt.query_map(
+        params![
+            filters.provider_id,
+            filters.model,
+            filters.project,
+            start_date,
+            end_date
+        ],
+        model_usage_from_row,
+    )?;
+
+    for row in rows {
+        cache.models.push(row?);
+    }
+
+    Ok(cache)
+}
+
+/// 从 message_usage 与 model_daily_stats 汇总总体统计，缓存命中率与模型排序由读取方计算
+fn load_current_stats(conn: &Connection) -> Result<CurrentStatsCache, RepositoryError> {
+    let mut cache = StatsCache::default();
+
+    let totals: (i64, i64, i64, i64, f64, i64, f64) = conn.query_row(
+        "SELECT
+            COALESCE(SUM(input_tokens), 0),
+            COALESCE(SUM(output_tokens), 0),
+            COALESCE(SUM(cache_read_tokens), 0),
+            COALESCE(SUM(cache_creation_tokens), 0),
+            COALESCE(SUM(cost_usd), 0),
+            COALESCE(COUNT(*), 0),
+            COALESCE(SUM(api_equivalent_cost_usd), 0)
+         FROM message_usage",
+        [],
+        |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+            ))
+        },
+    )?;
+    let sessions: HashSet<String> = query_all(
+        conn,
+        "SELECT DISTINCT session_id FROM message_usage",
+        |row| row.get(0),
+    )?
+    .into_iter()
+    .collect();
+
+    cache.total_input_tokens = totals.0;
+    cache.total_output_tokens = totals.1;
+    cache.total_cache_read_tokens = totals.2;
+    cache.total_cache_creation_tokens = totals.3;
+    cache.total_cost_usd = totals.4;
+    cache.total_sessions = sessions.len() as i64;
+    cache.total_messages = totals.5;
+    cache.total_api_equivalent_cost_usd = totals.6;
+    cache.models = query_all(
+        conn,
+        "SELECT model, SUM(total_input_tokens), SUM(total_output_tokens), SUM(total_cache_read_tokens), SUM(total_cache_creation_tokens), SUM(total_cost_usd), SUM(message_count), SUM(total_thinking_tokens)
+         FROM model_daily_stats GROUP BY model",
+        model_usage_from_row,
+    )?;
+
+    Ok(CurrentStatsCache {
+        stats: cache,
+        sessions,
+        day_totals: None,
+        block_entries: None,
+    })
+}
+
+/// 按 model, input, output, cache_read, cache_creation, cost, message_count, thinking 列顺序读取模型用量
+fn model_usage_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ModelUsage> {
+    Ok(ModelUsage {
+        model: row.get(0)?,
+        input_tokens: row.get(1)?,
+        output_tokens: row.get(2)?,
+        cache_read_tokens: row.get(3)?,
+        cache_creation_tokens: row.get(4)?,
+        cost_usd: row.get(5)?,
+        message_count: row.get(6)?,
+        thinking_tokens: row.get(7)?,
+    })
+}
+
+/// 只读查询禁止读取的表：应用设置中含有访问令牌等敏感配置，Webhook 目标含有推送地址
+const READONLY_QUERY_DENIED_TABLES: &[&str] = &["app_settings", "webhook_targets"];
+
+/// 只读查询每执行多少条虚拟机指令检查一次是否超时
+const READONLY_QUERY_PROGRESS_OPS: i32 = 1000;
+
+/// 预编译只读查询
+///
+/// 语句需以 SELECT / WITH 开头、只有一条语句，且被 SQLite 判定为只读；
+/// 预编译期间通过授权回调拒绝读取 READONLY_QUERY_DENIED_TABLES 中的表
+fn prepare_readonly_query<'conn>(
+    conn: &'conn Connection,
+    sql: &str,
+) -> Result<rusqlite::Statement<'conn>, RepositoryError> {
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    if !matches!(keyword.as_str(), "SELECT" | "WITH") {
+        return Err(RepositoryError::NotReadOnly);
+    }
+
+    conn.authorizer(Some(|context: AuthContext<'_>| match context.action {
+        AuthAction::Read { table_name, .. }
+            if READONLY_QUERY_DENIED_TABLES
+                .iter()
+                .any(|denied| table_name.eq_ignore_ascii_case(denied)) =>
+        {
+            Authorization::Deny
+        }
+        _ => Authorization::Allow,
+    }));
+    // 逐条预编译：第一条之后只允许空白、注释与结尾分号，字符串中的分号不受影响
+    let mut batch = Batch::new(conn, sql);
+    let prepared = batch.next();
+    let single = matches!(batch.next(), Ok(None));
+    conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+
+    let stmt = prepared
+        .map_err(|e| match e.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::AuthorizationForStatementDenied) => {
+                RepositoryError::RestrictedTable
+            }
+            _ => e.into(),
+        })?
+        .ok_or(RepositoryError::NotReadOnly)?;
+    if !single || !stmt.readonly() {
+        return Err(RepositoryError::NotReadOnly);
+    }
+    Ok(stmt)
+}
+
+/// 预编译并执行只读查询，最多收集 MAX_QUERY_ROWS 行
+fn collect_readonly_query(conn: &Connection, sql: &str) -> Result<QueryResult, RepositoryError> {
+    let mut stmt = prepare_readonly_query(conn, sql)?;
+    let columns: Vec<String> = stmt
+        .column_names()
+        .into_iter()
+        .map(|name| name.to_string())
+        .collect();
+
+    let mut result = QueryResult {
+        columns,
+        ..Default::default()
+    };
+
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        if result.rows.len() == MAX_QUERY_ROWS {
+            result.truncated = true;
+            break;
+        }
+
+        let mut values = Vec::with_capacity(result.columns.len());
+        for index in 0..result.columns.len() {
+            values.push(json_value(row.get_ref(index)?));
+        }
+        result.rows.push(values);
+    }
+
+    Ok(result)
+}
+
+/// 将 SQLite 值转换为 JSON，BLOB 以长度描述代替
+fn json_value(value: rusqlite::types::ValueRef<'_>) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(value) => value.into(),
+        ValueRef::Real(value) => value.into(),
+        ValueRef::Text(value) => String::from_utf8_lossy(value).into_owned().into(),
+        ValueRef::Blob(value) => format!("<blob {} bytes>", value.len()).into(),
+    }
+}
+
+/// 在给定连接上读取单个自定义查询
+fn read_saved_query(conn: &Connection, id: i64) -> Result<Option<SavedQuery>, RepositoryError> {
+    conn.query_row(
+        "SELECT id, name, sql, visualization, created_at, updated_at
+         FROM saved_queries WHERE id = ?1",
+        params![id],
+        saved_query_from_row,
+    )
+    .optional()
+    .map_err(RepositoryError::from)
+}
+
+/// 列顺序：id, name, sql, visualization, created_at, updated_at
+fn saved_query_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SavedQuery> {
+    Ok(SavedQuery {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        sql: row.get(2)?,
+        visualization: QueryVisualization::from_db(&row.get::<_, String>(3)?),
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// 供应商余额查询列，列顺序与 provider_balance_from_row 一致
+const PROVIDER_BALANCE_SELECT: &str = "SELECT provider_id, quota_url, refresh_minutes, remaining_usd, used_usd, total_usd, fetched_at, error, updated_at
+     FROM provider_quotas";
+
+fn read_provider_balance(
+    conn: &Connection,
+    provider_id: i64,
+) -> Result<Option<ProviderBalance>, RepositoryError> {
+    conn.query_row(
+        &format!("{} WHERE provider_id = ?1", PROVIDER_BALANCE_SELECT),
+        params![provider_id],
+        provider_balance_from_row,
+    )
+    .optional()
+    .map_err(RepositoryError::from)
+}
+
+fn provider_balance_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ProviderBalance> {
+    Ok(ProviderBalance {
+        provider_id: row.get(0)?,
+        quota_url: row.get(1)?,
+        refresh_minutes: row.get(2)?,
+        remaining_usd: row.get(3)?,
+        used_usd: row.get(4)?,
+        total_usd: row.get(5)?,
+        fetched_at: row.get(6)?,
+        error: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// 告警规则查询列，列顺序与 alert_rule_from_row 一致
+const ALERT_RULE_SELECT: &str = "SELECT id, name, metric, comparator, threshold, time_window, channel, enabled, snoozed_until, breached_since, created_at, updated_at
+     FROM alert_rules";
+
+fn read_alert_rule(conn: &Connection, id: i64) -> Result<Option<AlertRule>, RepositoryError> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", ALERT_RULE_SELECT),
+        params![id],
+        alert_rule_from_row,
+    )
+    .optional()
+    .map_err(RepositoryError::from)
+}
+
+fn alert_rule_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<AlertRule> {
+    Ok(AlertRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        metric: AlertMetric::from_db(&row.get::<_, String>(2)?),
+        comparator: AlertComparator::from_db(&row.get::<_, String>(3)?),
+        threshold: row.get(4)?,
+        window: AlertWindow::from_db(&row.get::<_, String>(5)?),
+        channel: AlertChannel::from_db(&row.get::<_, String>(6)?),
+        enabled: row.get(7)?,
+        snoozed_until: row.get(8)?,
+        breached_since: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+/// 目标查询列，列顺序与 goal_from_row 一致
+const GOAL_SELECT: &str =
+    "SELECT id, name, metric, direction, target, period, enabled, created_at, updated_at FROM goals";
+
+fn read_goal(conn: &Connection, id: i64) -> Result<Option<Goal>, RepositoryError> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", GOAL_SELECT),
+        params![id],
+        goal_from_row,
+    )
+    .optional()
+    .map_err(RepositoryError::from)
+}
+
+fn goal_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Goal> {
+    Ok(Goal {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        metric: GoalMetric::from_db(&row.get::<_, String>(2)?),
+        direction: GoalDirection::from_db(&row.get::<_, String>(3)?),
+        target: row.get(4)?,
+        period: GoalPeriod::from_db(&row.get::<_, String>(5)?),
+        enabled: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// 告警记录查询列，列顺序与 triggered_alert_from_row 一致
+const TRIGGERED_ALERT_SELECT: &str = "SELECT id, rule_id, rule_name, metric, value, threshold, channel, message, triggered_at, acknowledged_at
+     FROM triggered_alerts";
+
+fn triggered_alert_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TriggeredAlert> {
+    Ok(TriggeredAlert {
+        id: row.get(0)?,
+        rule_id: row.get(1)?,
+        rule_name: row.get(2)?,
+        metric: AlertMetric::from_db(&row.get::<_, String>(3)?),
+        value: row.get(4)?,
+        threshold: row.get(5)?,
+        channel: AlertChannel::from_db(&row.get::<_, String>(6)?),
+        message: row.get(7)?,
+        triggered_at: row.get(8)?,
+        acknowledged_at: row.get(9)?,
+    })
+}
+
+/// 将消息查询的一行转换为 StoredMessage
+///
+/// 列顺序：id, provider_id, session_id, message_id, model, project, 四类 Token, cost_usd, created_at
+fn stored_message_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<StoredMessage> {
+    Ok(StoredMessage {
+        id: row.get(0)?,
+        provider_id: row.get(1)?,
+        session_id: row.get(2)?,
+        message_id: row.get(3)?,
+        model: row.get(4)?,
+        project: row.get(5)?,
+        input_tokens: row.get(6)?,
+        output_tokens: row.get(7)?,
+        cache_read_tokens: row.get(8)?,
+        cache_creation_tokens: row.get(9)?,
+        cost_usd: row.get(10)?,
+        created_at: row.get(11)?,
+    })
+}
+
+/// 在给定连接上读取设置项，未保存过时返回默认值
+fn read_setting<T: AppSetting>(conn: &Connection) -> Result<T, RepositoryError> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![T::KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match value {
+        Some(value) => Ok(serde_json::from_str(&value)?),
+        None => Ok(T::default()),
+    }
+}
+
+/// 在给定连接上保存设置项
+fn write_setting<T: AppSetting>(conn: &Connection, setting: &T) -> Result<(), RepositoryError> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value, updated_at)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET
+            value = excluded.value,
+            updated_at = excluded.updated_at",
+        params![
+            T::KEY,
+            serde_json::to_string(setting)?,
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// 打开数据库文件并完成迁移，父目录不存在时创建
+fn open_connection(db_path: &Path) -> Result<Connection, RepositoryError> {
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(db_path)?;
+    apply_migrations(&conn)?;
+    enable_foreign_keys(&conn)?;
+    Ok(conn)
+}
+
+/// 打开 VACUUM INTO 生成的数据库副本并与原库比对，通过后返回可直接使用的连接
+fn verify_copy(source: &Connection, copy_path: &Path) -> Result<Connection, RepositoryError> {
+    let copy = Connection::open(copy_path)?;
+    let integrity: String = copy.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(RepositoryError::Relocate(format!(
+            "副本完整性检查失败: {}",
+            integrity
+        )));
+    }
+    let count_sql = "SELECT COUNT(*) FROM message_usage";
+    let source_state: (i64, i64) = (
+        current_version(source)?,
+        source.query_row(count_sql, [], |row| row.get(0))?,
+    );
+    let copy_state: (i64, i64) = (
+        current_version(&copy)?,
+        copy.query_row(count_sql, [], |row| row.get(0))?,
+    );
+    if source_state != copy_state {
+        return Err(RepositoryError::Relocate(format!(
+            "副本与原库不一致: 版本/消息数 {:?} != {:?}",
+            copy_state, source_state
+        )));
+    }
+    enable_foreign_keys(&copy)?;
+    Ok(copy)
+}
+
+/// 开启外键约束
+///
+/// SQLite 是否默认检查外键取决于编译选项，这里显式开启；孤立记录已由迁移清除
+fn enable_foreign_keys(conn: &Connection) -> Result<(), RepositoryError> {
+    conn.pragma_update(None, "foreign_keys", true)?;
+    Ok(())
+}
+
+fn deleted_item_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DeletedItem> {
+    Ok(DeletedItem {
+        id: row.get(0)?,
+        kind: DeletedItemKind::from_db(&row.get::<_, String>(1)?),
+        source_id: row.get(2)?,
+        label: row.get(3)?,
+        message_count: row.get(4)?,
+        deleted_at: row.get(5)?,
+    })
+}
+
+/// 快照中的供应商查询，后接 WHERE 条件（列名不带表别名）
+const SNAPSHOT_PROVIDER_SELECT: &str =
+    "SELECT api_key_hash, api_key_prefix, display_name, base_url, first_seen_at, last_seen_at, kind
+     FROM providers";
+
+fn snapshot_provider_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SnapshotProvider> {
+    Ok(SnapshotProvider {
+        api_key_hash: row.get(0)?,
+        api_key_prefix: row.get(1)?,
+        display_name: row.get(2)?,
+        base_url: row.get(3)?,
+        first_seen_at: row.get(4)?,
+        last_seen_at: row.get(5)?,
+        kind: ProviderKind::from_db(&row.get::<_, String>(6)?),
+    })
+}
+
+/// 快照中的消息查询，后接 WHERE 条件（消息表别名 m，供应商表别名 p）
+const SNAPSHOT_MESSAGE_SELECT: &str =
+    "SELECT p.api_key_hash, m.session_id, m.message_id, m.model, m.project, m.input_tokens, m.output_tokens, m.cache_read_tokens, m.cache_creation_tokens, m.cost_usd, m.duration_ms, m.created_at, m.api_equivalent_cost_usd, m.thinking_tokens, m.service_tier
+     FROM message_usage m
+     JOIN providers p ON p.id = m.provider_id";
+
+/// 重复消息条件：每个 message_id 只保留最早写入的一条（消息表别名 m）
+const DUPLICATE_MESSAGE_CONDITION: &str = "m.message_id != 'unknown'
+     AND m.id NOT IN (SELECT MIN(id) FROM message_usage GROUP BY message_id)";
+
+/// 清理重复消息时回收站条目的展示名称
+const DUPLICATE_MESSAGES_LABEL: &str = "重复消息";
+
+fn snapshot_message_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SnapshotMessage> {
+    Ok(SnapshotMessage {
+        provider: row.get(0)?,
+        session_id: row.get(1)?,
+        message_id: row.get(2)?,
+        model: row.get(3)?,
+        project: row.get(4)?,
+        input_tokens: row.get(5)?,
+        output_tokens: row.get(6)?,
+        cache_read_tokens: row.get(7)?,
+        cache_creation_tokens: row.get(8)?,
+        cost_usd: row.get(9)?,
+        duration_ms: row.get(10)?,
+        created_at: row.get(11)?,
+        api_equivalent_cost_usd: row.get(12)?,
+        thinking_tokens: row.get(13)?,
+        service_tier: ServiceTier::from_db(&row.get::<_, String>(14)?),
+    })
+}
+
+/// 在 conn 所在事务中合并快照，规则见 Repository::import_snapshot；不重建每日汇总
+fn import_snapshot_in(
+    conn: &Connection,
+    snapshot: &Snapshot,
+    policy: DedupePolicy,
+) -> Result<SnapshotImportSummary, RepositoryError> {
+    let mut summary = SnapshotImportSummary::default();
+
+    let mut provider_ids = HashMap::new();
+    for provider in &snapshot.providers {
+        let exists: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM providers WHERE api_key_hash = ?1",
+                params![provider.api_key_hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if exists.is_none() {
+            summary.providers_added += 1;
+        }
+
+        conn.execute(
+            "INSERT INTO providers (api_key_hash, api_key_prefix, display_name, base_url, is_active, first_seen_at, last_seen_at, kind)
+             VALUES (?1, ?2, ?3, ?4, 0, ?5, ?6, ?7)
+             ON CONFLICT(api_key_hash) DO UPDATE SET
+                display_name = COALESCE(providers.display_name, excluded.display_name),
+                base_url = COALESCE(providers.base_url, excluded.base_url),
+                first_seen_at = MIN(providers.first_seen_at, excluded.first_seen_at),
+                last_seen_at = MAX(providers.last_seen_at, excluded.last_seen_at)",
+            params![
+                provider.api_key_hash,
+                provider.api_key_prefix,
+                provider.display_name,
+                provider.base_url,
+                provider.first_seen_at,
+                provider.last_seen_at,
+                provider.kind.as_str()
+            ],
+        )?;
+        let id: i64 = conn.query_row(
+            "SELECT id FROM providers WHERE api_key_hash = ?1",
+            params![provider.api_key_hash],
+            |row| row.get(0),
+        )?;
+        provider_ids.insert(provider.api_key_hash.as_str(), id);
+    }
+
+    for message in &snapshot.messages {
+        let Some(&provider_id) = provider_ids.get(message.provider.as_str()) else {
+            summary.messages_skipped += 1;
+            continue;
+        };
+
+        if policy == DedupePolicy::Global {
+            let recorded_elsewhere: Option<i64> = conn
+                .query_row(
+                    "SELECT 1 FROM message_usage
+                     WHERE message_id = ?1 AND substr(created_at, 1, 10) = substr(?2, 1, 10) AND provider_id != ?3
+                     LIMIT 1",
+                    params![message.message_id, message.created_at, provider_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if recorded_elsewhere.is_some() {
+                summary.messages_skipped += 1;
+                continue;
+            }
+        }
+
+        let inserted = conn.execute(
+            "INSERT INTO message_usage (provider_id, session_id, message_id, model, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at, project, duration_ms, api_equivalent_cost_usd, thinking_tokens, service_tier)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+             ON CONFLICT DO NOTHING",
+            params![
+                provider_id,
+                message.session_id,
+                message.message_id,
+                message.model,
+                message.input_tokens,
+                message.output_tokens,
+                message.cache_read_tokens,
+                message.cache_creation_tokens,
+                message.cost_usd,
+                message.created_at,
+                message.project,
+                message.duration_ms,
+                message.api_equivalent_cost_usd.unwrap_or(message.cost_usd),
+                message.thinking_tokens,
+                message.service_tier.as_str()
+            ],
+        )?;
+        if inserted > 0 {
+            summary.messages_added += 1;
+        } else {
+            summary.messages_skipped += 1;
+        }
+    }
+
+    for session in &snapshot.sessions {
+        summary.other_rows_merged += conn.execute(
+            "INSERT INTO sessions (session_id, title, title_source, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(session_id) DO UPDATE SET
+                title = excluded.title,
+                title_source = excluded.title_source,
+                updated_at = excluded.updated_at
+             WHERE excluded.title_source = 'summary'
+               AND (sessions.title_source != 'summary' OR excluded.updated_at > sessions.updated_at)",
+            params![
+                session.session_id,
+                session.title,
+                session.title_source,
+                session.updated_at
+            ],
+        )?;
+    }
+
+    for plan in &snapshot.provider_plans {
+        let Some(&provider_id) = provider_ids.get(plan.provider.as_str()) else {
+            continue;
+        };
+        summary.other_rows_merged += conn.execute(
+            "INSERT INTO provider_plans (provider_id, plan_type, monthly_fee_usd, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(provider_id) DO UPDATE SET
+                plan_type = excluded.plan_type,
+                monthly_fee_usd = excluded.monthly_fee_usd,
+                updated_at = excluded.updated_at
+             WHERE excluded.updated_at > provider_plans.updated_at",
+            params![
+                provider_id,
+                plan.plan_type,
+                plan.monthly_fee_usd,
+                plan.updated_at
+            ],
+        )?;
+    }
+
+    for switch in &snapshot.provider_switches {
+        let Some(&provider_id) = provider_ids.get(switch.provider.as_str()) else {
+            continue;
+        };
+        summary.other_rows_merged += conn.execute(
+            "INSERT INTO provider_switch_logs (provider_id, switched_at)
+             SELECT ?1, ?2
+             WHERE NOT EXISTS (
+                SELECT 1 FROM provider_switch_logs WHERE provider_id = ?1 AND switched_at = ?2
+             )",
+            params![provider_id, switch.switched_at],
+        )?;
+    }
+
+    for event in &snapshot.rate_limit_events {
+        let Some(&provider_id) = provider_ids.get(event.provider.as_str()) else {
+            continue;
+        };
+        summary.other_rows_merged += conn.execute(
+            "INSERT INTO rate_limit_events (provider_id, session_id, kind, status_code, message, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(provider_id, session_id, occurred_at, kind) DO NOTHING",
+            params![
+                provider_id,
+                event.session_id,
+                event.kind,
+                event.status_code,
+                event.message,
+                event.occurred_at
+            ],
+        )?;
+    }
+
+    for error in &snapshot.api_errors {
+        let Some(&provider_id) = provider_ids.get(error.provider.as_str()) else {
+            continue;
+        };
+        summary.other_rows_merged += conn.execute(
+            "INSERT INTO api_errors (provider_id, session_id, kind, status_code, message, is_retry, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(provider_id, session_id, occurred_at, kind) DO NOTHING",
+            params![
+                provider_id,
+                error.session_id,
+                error.kind,
+                error.status_code,
+                error.message,
+                error.is_retry,
+                error.occurred_at
+            ],
+        )?;
+    }
+
+    for tag in &snapshot.tags {
+        summary.other_rows_merged += conn.execute(
+            "INSERT INTO tags (name, created_at) VALUES (?1, ?2)
+             ON CONFLICT(name) DO NOTHING",
+            params![tag.name, tag.created_at],
+        )?;
+        let tag_id: i64 = conn.query_row(
+            "SELECT id FROM tags WHERE name = ?1",
+            params![tag.name],
+            |row| row.get(0),
+        )?;
+        for session_id in &tag.session_ids {
+            summary.other_rows_merged += conn.execute(
+                "INSERT INTO session_tags (tag_id, session_id) VALUES (?1, ?2)
+                 ON CONFLICT(tag_id, session_id) DO NOTHING",
+                params![tag_id, session_id],
+            )?;
+        }
+        for date in &tag.dates {
+            summary.other_rows_merged += conn.execute(
+                "INSERT INTO date_tags (tag_id, date) VALUES (?1, ?2)
+                 ON CONFLICT(tag_id, date) DO NOTHING",
+                params![tag_id, date],
+            )?;
+        }
+    }
+
+    for note in &snapshot.date_notes {
+        summary.other_rows_merged += conn.execute(
+            "INSERT INTO date_notes (date, note, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(date) DO UPDATE SET note = excluded.note, updated_at = excluded.updated_at
+             WHERE excluded.updated_at > date_notes.updated_at",
+            params![note.date, note.note, note.updated_at],
+        )?;
+    }
+
+    // 快照来自其他设备，其前缀长度可能超出本机隐私设置
+    conn.execute(REMASK_API_KEY_PREFIXES, [])?;
+
+    Ok(summary)
+}
+
+/// 导出供应商及其消息、套餐、切换记录与事件，provider_id 为 None 时导出全部供应商
+fn export_provider_data(
+    conn: &Connection,
+    provider_id: Option<i64>,
+) -> Result<Snapshot, RepositoryError> {
+    let providers = query_all_with(
+        conn,
+        &format!(
+            "{} WHERE ?1 IS NULL OR id = ?1 ORDER BY id",
+            SNAPSHOT_PROVIDER_SELECT
+        ),
+        params![provider_id],
+        snapshot_provider_from_row,
+    )?;
+
+    let messages = query_all_with(
+        conn,
+        &format!(
+            "{} WHERE ?1 IS NULL OR p.id = ?1 ORDER BY m.id",
+            SNAPSHOT_MESSAGE_SELECT
+        ),
+        params![provider_id],
+        snapshot_message_from_row,
+    )?;
+
+    let provider_plans = query_all_with(
+        conn,
+        "SELECT p.api_key_hash, pp.plan_type, pp.monthly_fee_usd, pp.updated_at
+         FROM provider_plans pp
+         JOIN providers p ON p.id = pp.provider_id
+         WHERE ?1 IS NULL OR p.id = ?1",
+        params![provider_id],
+        |row| {
+            Ok(SnapshotProviderPlan {
+                provider: row.get(0)?,
+                plan_type: row.get(1)?,
+                monthly_fee_usd: row.get(2)?,
+                updated_at: row.get(3)?,
+            })
+        },
+    )?;
+
+    let provider_switches = query_all_with(
+        conn,
+        "SELECT p.api_key_hash, s.switched_at
+         FROM provider_switch_logs s
+         JOIN providers p ON p.id = s.provider_id
+         WHERE ?1 IS NULL OR p.id = ?1
+         ORDER BY s.id",
+        params![provider_id],
+        |row| {
+            Ok(SnapshotProviderSwitch {
+                provider: row.get(0)?,
+                switched_at: row.get(1)?,
+            })
+        },
+    )?;
+
+    let rate_limit_events = query_all_with(
+        conn,
+        "SELECT p.api_key_hash, e.session_id, e.kind, e.status_code, e.message, e.occurred_at
+         FROM rate_limit_events e
+         JOIN providers p ON p.id = e.provider_id
+         WHERE ?1 IS NULL OR p.id = ?1
+         ORDER BY e.id",
+        params![provider_id],
+        |row| {
+            Ok(SnapshotRateLimitEvent {
+                provider: row.get(0)?,
+                session_id: row.get(1)?,
+                kind: row.get(2)?,
+                status_code: row.get(3)?,
+                message: row.get(4)?,
+                occurred_at: row.get(5)?,
+            })
+        },
+    )?;
+
+    let api_errors = query_all_with(
+        conn,
+        "SELECT p.api_key_hash, e.session_id, e.kind, e.status_code, e.message, e.is_retry, e.occurred_at
+         FROM api_errors e
+         JOIN providers p ON p.id = e.provider_id
+         WHERE ?1 IS NULL OR p.id = ?1
+         ORDER BY e.id",
+        params![provider_id],
+        |row| {
+            Ok(SnapshotApiError {
+                provider: row.get(0)?,
+                session_id: row.get(1)?,
+                kind: row.get(2)?,
+                status_code: row.get(3)?,
+                message: row.get(4)?,
+                is_retry: row.get(5)?,
+                occurred_at: row.get(6)?,
+            })
+        },
+    )?;
+
+    Ok(Snapshot {
+        schema_version: current_version(conn)?,
+        providers,
+        messages,
+        provider_plans,
+        provider_switches,
+        rate_limit_events,
+        api_errors,
+        ..Default::default()
+    })
+}
+
+/// 执行查询并收集所有行
+fn query_all<T>(
+    conn: &Connection,
+    sql: &str,
+    map: impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, RepositoryError> {
+    query_all_with(conn, sql, [], map)
+}
+
+fn query_all_with<T>(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+    map: impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, RepositoryError> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params, map)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(RepositoryError::from)
+}
+
+/// 从 offset 开始读取中断次数、丢弃 Token、丢弃输出 Token 与丢弃费用四列
+fn discarded_usage_from_row(
+    row: &rusqlite::Row<'_>,
+    offset: usize,
+) -> rusqlite::Result<DiscardedUsage> {
+    Ok(DiscardedUsage {
+        interrupt_count: row.get(offset)?,
+        discarded_tokens: row.get(offset + 1)?,
+        discarded_output_tokens: row.get(offset + 2)?,
+        discarded_cost_usd: row.get(offset + 3)?,
+    })
+}
+
+/// 标签关联对象对应的关联表、列名与值
+fn tag_target_columns(target: &TagTarget) -> (&'static str, &'static str, &str) {
+    match target {
+        TagTarget::Session(session_id) => ("session_tags", "session_id", session_id),
+        TagTarget::Date(date) => ("date_tags", "date", date),
+    }
+}
+
+/// 将会话汇总查询的一行转换为 SessionSummary
+///
+/// 列顺序：session_id, provider_id, project, models, 四类 Token, cost_usd, message_count,
+/// first_message_at, last_message_at, total_tokens, title
+fn session_summary_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SessionSummary> {
+    let models: Option<String> = row.get(3)?;
+    Ok(SessionSummary {
+        session_id: row.get(0)?,
+        provider_id: row.get(1)?,
+        title: row.get(13)?,
+        project: row.get(2)?,
+        models: models
+            .map(|models| models.split(',').map(|m| m.to_string()).collect())
+            .unwrap_or_default(),
+        input_tokens: row.get(4)?,
+        output_tokens: row.get(5)?,
+        cache_read_tokens: row.get(6)?,
+        cache_creation_tokens: row.get(7)?,
+        cost_usd: row.get(8)?,
+        message_count: row.get(9)?,
+        first_message_at: row.get(10)?,
+        last_message_at: row.get(11)?,
+    })
+}
+
+/// 供应商、日期维度的汇总键
+type DailyKey = (i64, String);
+
+/// 供应商、日期、模型维度的汇总键
+type ModelDailyKey = (i64, String, String);
+
+/// message_usage 汇总结果：(按供应商和日期, 按供应商、日期和模型)
+type UsageAggregates = (
+    BTreeMap<DailyKey, DailyStatsTotals>,
+    BTreeMap<ModelDailyKey, DailyStatsTotals>,
+);
+
+/// 在给定连接（通常为事务）上根据 message_usage 重建 daily_stats 与 model_daily_stats
+///
+/// # 返回
+/// 重建后的 daily_stats 行数
+fn rebuild_daily_stats_in(conn: &Connection) -> Result<usize, RepositoryError> {
+    let (daily, model_daily) = aggregate_message_usage(conn)?;
+
+    conn.execute("DELETE FROM daily_stats", [])?;
+    conn.execute("DELETE FROM model_daily_stats", [])?;
+
+    for ((provider_id, date), totals) in &daily {
+        conn.execute(
+            "INSERT INTO daily_stats (provider_id, date, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, session_count, message_count, total_api_equivalent_cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                provider_id,
+                date,
+                totals.input_tokens,
+                totals.output_tokens,
+                totals.cache_read_tokens,
+                totals.cache_creation_tokens,
+                totals.cost_usd,
+                totals.session_count,
+                totals.message_count,
+                totals.api_equivalent_cost_usd
+            ],
+        )?;
+    }
+
+    for ((provider_id, date, model), totals) in &model_daily {
+        conn.execute(
+            "INSERT INTO model_daily_stats (provider_id, date, model, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, message_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                provider_id,
+                date,
+                model,
+                totals.input_tokens,
+                totals.output_tokens,
+                totals.cache_read_tokens,
+                totals.cache_creation_tokens,
+                totals.cost_usd,
+                totals.message_count
+            ],
+        )?;
+    }
+    // 思考 Token 不参与 daily_stats 校验，按模型汇总直接由 SQL 回填
+    conn.execute_batch(SYNC_MODEL_THINKING_TOKENS)?;
+
+    Ok(daily.len())
+}
+
+/// 按本地日期汇总 message_usage
+///
+/// 日期与 insert_message_usage 一致使用 extract_date 计算，会话数按当日不同 session_id 计
+fn aggregate_message_usage(conn: &Connection) -> Result<UsageAggregates, RepositoryError> {
+    let mut stmt = conn.prepare(
+        "SELECT provider_id, session_id, model, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, cost_usd, created_at, api_equivalent_cost_usd
+         FROM message_usage",
+    )?;
+
+    let mut daily: BTreeMap<DailyKey, DailyStatsTotals> = BTreeMap::new();
+    let mut model_daily: BTreeMap<ModelDailyKey, DailyStatsTotals> = BTreeMap::new();
+    let mut sessions: HashSet<(i64, String, String)> = HashSet::new();
+
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let provider_id: i64 = row.get(0)?;
+        let session_id: String = row.get(1)?;
+        let model: String = row.get(2)?;
+        let usage = DailyStatsTotals {
+            input_tokens: row.get(3)?,
+            output_tokens: row.get(4)?,
+            cache_read_tokens: row.get(5)?,
+            cache_creation_tokens: row.get(6)?,
+            cost_usd: row.get(7)?,
+            api_equivalent_cost_usd: row.get(9)?,
+            session_count: 0,
+            message_count: 1,
+        };
+        let date = extract_date(&row.get::<_, String>(8)?);
+
+        let is_new_session = sessions.insert((provider_id, date.clone(), session_id));
+
+        let day = daily.entry((provider_id, date.clone())).or_default();
+        add_totals(day, &usage);
+        if is_new_session {
+            day.session_count += 1;
+        }
+
+        add_totals(
+            model_daily.entry((provider_id, date, model)).or_default(),
+            &usage,
+        );
+    }
+
+    Ok((daily, model_daily))
+}
+
+fn add_totals(target: &mut DailyStatsTotals, usage: &DailyStatsTotals) {
+    target.input_tokens += usage.input_tokens;
+    target.output_tokens += usage.output_tokens;
+    target.cache_read_tokens += usage.cache_read_tokens;
+    target.cache_creation_tokens += usage.cache_creation_tokens;
+    target.cost_usd += usage.cost_usd;
+    target.api_equivalent_cost_usd += usage.api_equivalent_cost_usd;
+    target.message_count += usage.message_count;
+}
+
+const WEBHOOK_TARGET_SELECT: &str =
+    "SELECT id, name, url, format, enabled, created_at, updated_at FROM webhook_targets";
+
+fn read_webhook_target(
+    conn: &Connection,
+    id: i64,
+) -> Result<Option<WebhookTarget>, RepositoryError> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", WEBHOOK_TARGET_SELECT),
+        params![id],
+        webhook_target_from_row,
+    )
+    .optional()
+    .map_err(RepositoryError::from)
+}
+
+fn webhook_target_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<WebhookTarget> {
+    Ok(WebhookTarget {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        format: WebhookFormat::from_db(&row.get::<_, String>(3)?),
+        enabled: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+/// 查询指定时间之后的消息用量（按时间升序），用于计算 5 小时使用区块
+fn block_entries_since(conn: &Connection, since: &str) -> Result<Vec<BlockEntry>, RepositoryError> {
+    query_all_with(
+        conn,
+        "SELECT created_at,
+                input_tokens + output_tokens + cache_read_tokens + cache_creation_tokens,
+                cost_usd
+         FROM message_usage
+         WHERE julianday(created_at) >= julianday(?1)
+         ORDER BY julianday(created_at) ASC",
+        params![since],
+        |row| {
+            Ok(BlockEntry {
+                created_at: row.get(0)?,
+                total_tokens: row.get(1)?,
+                cost_usd: row.get(2)?,
+            })
+        },
+    )
+}
+
+/// 解析 ISO 8601 时间戳，无法解析时返回 None（与 SQLite julianday 返回 NULL 时一致，不参与时间比较）
+fn parse_timestamp(iso: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(iso)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+fn extract_date(iso: &str) -> String {
+    if let Ok(parsed) = chrono::DateTime::parse_from_rfc3339(iso) {
+        return parsed.with_timezone(&Local).date_naive().to_string();
+    }
+    Local::now().date_naive().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        ApiErrorKind, CacheHitRateFormula, CompactionTrigger, InterruptKind, MessageRecord,
+        MessageUsage, RateLimitKind, ReportScheduleSettings,
+    };
+    use chrono::{Datelike, Timelike};
+
+    #[test]
+    fn test_repository_insert_and_stats() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            Utc::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 100,
+                output_tokens: 50,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 1.0,
+            },
+        );
+
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        let stats = repo.get_current_stats().expect("stats");
+        assert_eq!(stats.total_input_tokens, 100);
+        assert_eq!(stats.total_messages, 1);
+    }
+
+    #[test]
+    fn test_close_checkpoints_wal() {
+        let dir = std::env::temp_dir().join(format!("repo-close-{}", std::process::id()));
+        let db_path = dir.join("monitor.db");
+        let repo = Repository::new(&db_path).expect("repo");
+        repo.connection()
+            .expect("conn")
+            .query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))
+            .expect("wal");
+        repo.upsert_provider("sk-test", None).expect("provider");
+        let wal_path = dir.join("monitor.db-wal");
+        assert!(std::fs::metadata(&wal_path).expect("wal file").len() > 0);
+
+        repo.close().expect("close");
+        assert_eq!(std::fs::metadata(&wal_path).expect("wal file").len(), 0);
+
+        drop(repo);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_insert_message_usage_ignores_duplicates() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            Utc::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 100,
+                output_tokens: 50,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 1.0,
+            },
+        );
+
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+        repo.insert_message_usage(provider.id, &record)
+            .expect("duplicate insert");
+
+        let stats = repo.get_current_stats().expect("stats");
+        assert_eq!(stats.total_input_tokens, 100);
+        assert_eq!(stats.total_messages, 1);
+
+        let conn = repo.connection().expect("conn");
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM message_usage", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(rows, 1);
+    }
+
+    #[test]
+    fn test_current_stats_cache_tracks_ingest_and_invalidation() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let api = repo.upsert_provider("sk-test", None).expect("provider");
+        let subscription = repo
+            .upsert_subscription_provider("uuid-1", "Subscription")
+            .expect("subscription");
+        let record = |session: &str, message: &str, model: &str| {
+            MessageRecord::new(
+                session.to_string(),
+                message.to_string(),
+                model.to_string(),
+                Utc::now().to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    output_tokens: 50,
+                    cache_read_tokens: 30,
+                    cache_creation_tokens: 10,
+                    thinking_tokens: 5,
+                    cost_usd: 1.0,
+                },
+            )
+        };
+        let fresh = |repo: &Repository| {
+            let conn = repo.connection().expect("conn");
+            let mut stats = load_current_stats(&conn).expect("load").stats;
+            stats.update_cache_hit_rate(CacheHitRateFormula::Strict);
+            stats.sort_models_by_cost();
+            stats
+        };
+        let assert_matches = |repo: &Repository| {
+            let cached = repo.get_current_stats().expect("stats");
+            let expected = fresh(repo);
+            assert_eq!(cached.total_input_tokens, expected.total_input_tokens);
+            assert_eq!(cached.total_output_tokens, expected.total_output_tokens);
+            assert_eq!(
+                cached.total_cache_read_tokens,
+                expected.total_cache_read_tokens
+            );
+            assert!((cached.total_cost_usd - expected.total_cost_usd).abs() < 1e-9);
+            assert!(
+                (cached.total_api_equivalent_cost_usd - expected.total_api_equivalent_cost_usd)
+                    .abs()
+                    < 1e-9
+            );
+            assert_eq!(cached.total_sessions, expected.total_sessions);
+            assert_eq!(cached.total_messages, expected.total_messages);
+            assert!((cached.cache_hit_rate - expected.cache_hit_rate).abs() < 1e-9);
+            assert_eq!(cached.models.len(), expected.models.len());
+            for (cached, expected) in cached.models.iter().zip(&expected.models) {
+                assert_eq!(cached.model, expected.model);
+                assert_eq!(cached.message_count, expected.message_count);
+                assert_eq!(cached.thinking_tokens, expected.thinking_tokens);
+                assert!((cached.cost_usd - expected.cost_usd).abs() < 1e-9);
+            }
+        };
+
+        assert_eq!(repo.get_current_stats().expect("stats").total_messages, 0);
+        assert!(repo.stats_cache().expect("cache").is_some());
+
+        // 缓存已加载后写入：重复消息不累加，订阅供应商不计实际费用，会话去重
+        repo.insert_message_usage(api.id, &record("s1", "m1", "claude-3-opus"))
+            .expect("insert");
+        repo.insert_message_usage(api.id, &record("s1", "m1", "claude-3-opus"))
+            .expect("duplicate");
+        repo.insert_message_usage(api.id, &record("s1", "m2", "claude-3-opus"))
+            .expect("insert");
+        repo.insert_message_usage(subscription.id, &record("s2", "m3", "claude-3-haiku"))
+            .expect("insert");
+        let stats = repo.get_current_stats().expect("stats");
+        assert_eq!(stats.total_messages, 3);
+        assert_eq!(stats.total_sessions, 2);
+        assert!((stats.total_cost_usd - 2.0).abs() < 1e-9);
+        assert!((stats.total_api_equivalent_cost_usd - 3.0).abs() < 1e-9);
+        assert_matches(&repo);
+
+        // 批量修改后缓存失效，下次读取重新汇总
+        repo.rebuild_daily_stats().expect("rebuild");
+        assert!(repo.stats_cache().expect("cache").is_none());
+        assert_matches(&repo);
+        repo.delete_provider(subscription.id).expect("delete");
+        assert!(repo.stats_cache().expect("cache").is_none());
+        assert_eq!(repo.get_current_stats().expect("stats").total_messages, 2);
+        assert_matches(&repo);
+    }
+
+    #[test]
+    fn test_cross_provider_dedupe_policy() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let first = repo.upsert_provider("sk-first", None).expect("provider");
+        let second = repo.upsert_provider("sk-second", None).expect("provider");
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            Utc::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 100,
+                output_tokens: 50,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 1.0,
+            },
+        );
+
+        repo.insert_message_usage(first.id, &record)
+            .expect("insert");
+        repo.insert_message_usage_with_policy(second.id, &record, DedupePolicy::Global)
+            .expect("global dedupe");
+        assert_eq!(repo.get_current_stats().expect("stats").total_messages, 1);
+
+        repo.insert_message_usage_with_policy(second.id, &record, DedupePolicy::PerProvider)
+            .expect("per provider");
+        assert_eq!(repo.get_current_stats().expect("stats").total_messages, 2);
+    }
+
+    #[test]
+    fn test_model_daily_stats_maintained_on_insert() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        for (message_id, model) in [
+            ("message-1", "claude-3-opus"),
+            ("message-2", "claude-3-opus"),
+            ("message-3", "claude-3-haiku"),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                model.to_string(),
+                Local::now().to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cache_read_tokens: 0,
+                    cache_creation_tokens: 0,
+                    thinking_tokens: 2,
+                    cost_usd: 0.5,
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let stats = repo.get_current_stats().expect("stats");
+        assert_eq!(stats.models.len(), 2);
+        assert_eq!(stats.models[0].model, "claude-3-opus");
+        assert_eq!(stats.models[0].input_tokens, 20);
+        assert_eq!(stats.models[0].message_count, 2);
+        assert_eq!(stats.models[0].thinking_tokens, 4);
+
+        repo.rebuild_daily_stats().expect("rebuild");
+        let stats = repo.get_current_stats().expect("stats");
+        assert_eq!(stats.models[0].thinking_tokens, 4);
+        assert_eq!(stats.models[1].thinking_tokens, 2);
+
+        let today = Local::now().date_naive().to_string();
+        let daily = repo
+            .get_model_daily_usage(&today, &today)
+            .expect("model daily usage");
+        assert_eq!(daily.len(), 2);
+    }
+
+    #[test]
+    fn test_get_daily_activities() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            Local::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 10,
+                output_tokens: 5,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 0.1,
+            },
+        );
+
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        let today = Local::now().date_naive().to_string();
+        let activities = repo
+            .get_daily_activities(&today, &today)
+            .expect("activities");
+
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].message_count, 1);
+    }
+
+    #[test]
+    fn test_get_usage_heatmap() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        let now = Local::now();
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            now.to_rfc3339(),
+            MessageUsage {
+                input_tokens: 10,
+                output_tokens: 5,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 0.1,
+            },
+        );
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        let today = now.date_naive().to_string();
+        let cells = repo.get_usage_heatmap(&today, &today).expect("heatmap");
+
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].weekday, now.weekday().num_days_from_monday());
+        assert_eq!(cells[0].hour, now.hour());
+        assert_eq!(cells[0].tokens, 15);
+    }
+
+    #[test]
+    fn test_get_top_sessions() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        for (session_id, message_id, input_tokens, cost_usd) in [
+            ("session-1", "message-1", 1000, 0.1),
+            ("session-2", "message-2", 10, 2.0),
+            ("session-2", "message-3", 10, 1.0),
+        ] {
+            let record = MessageRecord::new(
+                session_id.to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                Local::now().to_rfc3339(),
+                MessageUsage {
+                    input_tokens,
+                    output_tokens: 5,
+                    cache_read_tokens: 0,
+                    cache_creation_tokens: 0,
+                    thinking_tokens: 0,
+                    cost_usd,
+                },
+            )
+            .with_project(Some("/work/app".to_string()));
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        repo.upsert_session_title("session-2", "Refactor parser", SessionTitleSource::Summary)
+            .expect("session title");
+
+        let today = Local::now().date_naive().to_string();
+        let by_cost = repo
+            .get_top_sessions(&today, &today, 10, SessionOrder::Cost)
+            .expect("top sessions");
+        assert_eq!(by_cost.len(), 2);
+        assert_eq!(by_cost[0].session_id, "session-2");
+        assert_eq!(by_cost[0].message_count, 2);
+        assert_eq!(by_cost[0].models, vec!["claude-3-opus".to_string()]);
+        assert_eq!(by_cost[0].project, Some("/work/app".to_string()));
+        assert_eq!(by_cost[0].title, Some("Refactor parser".to_string()));
+        assert_eq!(by_cost[1].title, None);
+
+        let by_tokens = repo
+            .get_top_sessions(&today, &today, 1, SessionOrder::Tokens)
+            .expect("top sessions");
+        assert_eq!(by_tokens.len(), 1);
+        assert_eq!(by_tokens[0].session_id, "session-1");
+    }
+
+    #[test]
+    fn test_get_block_entries() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        for (message_id, created_at) in [
+            ("message-1", "2026-10-17T08:59:00.000Z"),
+            ("message-2", "2026-10-17T10:30:00.000Z"),
+            ("message-3", "2026-10-17T09:15:00.000Z"),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                created_at.to_string(),
+                MessageUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cache_read_tokens: 2,
+                    cache_creation_tokens: 1,
+                    thinking_tokens: 0,
+                    cost_usd: 0.5,
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let entries = repo
+            .get_block_entries("2026-10-17T09:00:00+00:00")
+            .expect("entries");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].created_at, "2026-10-17T09:15:00.000Z");
+        assert_eq!(entries[0].total_tokens, 18);
+    }
+
+    #[test]
+    fn test_get_active_sessions() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let now = Utc::now();
+
+        for (session_id, message_id, minutes_ago) in [
+            ("active", "m1", 60),
+            ("active", "m2", 2),
+            ("idle", "m3", 30),
+        ] {
+            let record = MessageRecord::new(
+                session_id.to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                (now - chrono::Duration::minutes(minutes_ago)).to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cache_read_tokens: 0,
+                    cache_creation_tokens: 0,
+                    thinking_tokens: 0,
+                    cost_usd: 0.1,
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let since = (now - chrono::Duration::minutes(5)).to_rfc3339();
+        let sessions = repo.get_active_sessions(&since).expect("active sessions");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, "active");
+        // 汇总包含会话的全部消息，而不只是窗口内的消息
+        assert_eq!(sessions[0].message_count, 2);
+    }
+
+    #[test]
+    fn test_search_messages() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+
+        for (message_id, model, input_tokens) in [
+            ("message-1", "claude-3-opus", 100),
+            ("message-2", "claude-3-opus", 5),
+            ("message-3", "claude-3-haiku", 100),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                model.to_string(),
+                Local::now().to_rfc3339(),
+                MessageUsage {
+                    input_tokens,
+                    output_tokens: 5,
+                    cache_read_tokens: 0,
+                    cache_creation_tokens: 0,
+                    thinking_tokens: 0,
+                    cost_usd: 0.1,
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let today = Local::now().date_naive().to_string();
+        let result = repo
+            .search_messages(&MessageSearchFilters {
+                model: Some("claude-3-opus".to_string()),
+                provider_id: Some(provider.id),
+                start_date: Some(today.clone()),
+                end_date: Some(today),
+                min_tokens: Some(50),
+                ..Default::default()
+            })
+            .expect("search");
+        assert_eq!(result.total, 1);
+        assert_eq!(result.items[0].message_id, "message-1");
+
+        let result = repo
+            .search_messages(&MessageSearchFilters {
+                page: Some(2),
+                page_size: Some(2),
+                ..Default::default()
+            })
+            .expect("search");
+        assert_eq!(result.total, 3);
+        assert_eq!(result.items.len(), 1);
+    }
+
+    #[test]
+    fn test_upsert_session_title_prefers_summary() {
+        let repo = Repository::new_in_memory().expect("repo");
+
+        repo.upsert_session_title("session-1", "first prompt", SessionTitleSource::Prompt)
+            .expect("prompt title");
+        repo.upsert_session_title("session-1", "later prompt", SessionTitleSource::Prompt)
+            .expect("prompt title");
+        assert_eq!(
+            repo.get_session_title("session-1").expect("title"),
+            Some(("first prompt".to_string(), SessionTitleSource::Prompt))
+        );
+
+        repo.upsert_session_title("session-1", "Summary title", SessionTitleSource::Summary)
+            .expect("summary title");
+        repo.upsert_session_title("session-1", "late prompt", SessionTitleSource::Prompt)
+            .expect("prompt title");
+        assert_eq!(
+            repo.get_session_title("session-1").expect("title"),
+            Some(("Summary title".to_string(), SessionTitleSource::Summary))
+        );
+    }
+
+    #[test]
+    fn test_get_and_set_setting() {
+        let repo = Repository::new_in_memory().expect("repo");
+
+        let schedule: ReportScheduleSettings = repo.get_setting().expect("default setting");
+        assert_eq!(schedule, ReportScheduleSettings::default());
+
+        let schedule = ReportScheduleSettings {
+            weekly: true,
+            monthly: false,
+            notify: true,
+        };
+        repo.set_setting(&schedule).expect("save setting");
+        assert_eq!(
+            repo.get_setting::<ReportScheduleSettings>()
+                .expect("setting"),
+            schedule
+        );
+    }
+
+    #[test]
+    fn test_keychain_namespace_per_database() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let other = Repository::new_in_memory().expect("repo");
+
+        let namespace = repo.keychain_namespace().expect("namespace");
+        assert_eq!(namespace.len(), 16);
+        assert_eq!(repo.keychain_namespace().expect("namespace"), namespace);
+        assert_ne!(other.keychain_namespace().expect("namespace"), namespace);
+    }
+
+    #[test]
+    fn test_upsert_subscription_provider() {
+        let repo = Repository::new_in_memory().expect("repo");
+        repo.upsert_provider("sk-test", None).expect("provider");
+
+        let subscription = repo
+            .upsert_subscription_provider("uuid-1", "Subscription (max)")
+            .expect("subscription");
+        assert_eq!(subscription.kind, ProviderKind::Subscription);
+        assert_eq!(
+            subscription.display_name.as_deref(),
+            Some("Subscription (max)")
+        );
+
+        let active = repo
+            .get_active_provider()
+            .expect("active")
+            .expect("provider");
+        assert_eq!(active.id, subscription.id);
+        assert_eq!(active.kind, ProviderKind::Subscription);
+
+        let again = repo
+            .upsert_subscription_provider("uuid-1", "Subscription")
+            .expect("subscription");
+        assert_eq!(again.id, subscription.id);
+        assert_eq!(again.display_name.as_deref(), Some("Subscription (max)"));
+    }
+
+    #[test]
+    fn test_get_quick_stats() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let today = Local::now().date_naive().to_string();
+        assert_eq!(
+            repo.get_quick_stats(&today).expect("quick stats"),
+            QuickStats::default()
+        );
+
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let yesterday = (Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+        for (message_id, created_at) in [
+            ("m1", Utc::now().to_rfc3339()),
+            ("m2", Utc::now().to_rfc3339()),
+            ("m3", yesterday),
+        ] {
+            let record = MessageRecord::new(
+                "s1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                created_at,
+                MessageUsage {
+                    input_tokens: 100,
+                    output_tokens: 50,
+                    cache_read_tokens: 10,
+                    cost_usd: 0.25,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let stats = repo.get_quick_stats(&today).expect("quick stats");
+        assert_eq!(stats.today_cost_usd, 0.5);
+        assert_eq!(stats.today_tokens, 320);
+        assert_eq!(stats.block_remaining_minutes, None);
+        assert_eq!(stats.active_provider.as_deref(), Some("sk-test"));
+
+        repo.update_provider_display_name(provider.id, "Work")
+            .expect("rename");
+        let stats = repo.get_quick_stats(&today).expect("quick stats");
+        assert_eq!(stats.active_provider.as_deref(), Some("Work"));
+
+        // 缓存建立后写入的消息增量计入当日汇总与区块消息；起点与昨天的消息留出余量，避免边界比较不稳定
+        let since = Utc::now() - chrono::Duration::hours(12);
+        assert_eq!(
+            repo.get_cached_block_entries(since).expect("entries").len(),
+            2
+        );
+        let record = MessageRecord::new(
+            "s2".to_string(),
+            "m4".to_string(),
+            "claude-3-opus".to_string(),
+            Utc::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 40,
+                cost_usd: 0.5,
+                ..Default::default()
+            },
+        );
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+        assert!(repo.stats_cache().expect("cache").is_some());
+
+        let stats = repo.get_quick_stats(&today).expect("quick stats");
+        assert_eq!(stats.today_cost_usd, 1.0);
+        assert_eq!(stats.today_tokens, 360);
+        let cached = repo.get_cached_block_entries(since).expect("entries");
+        let stored = repo
+            .get_block_entries(&since.to_rfc3339())
+            .expect("entries");
+        assert_eq!(cached.len(), 3);
+        assert_eq!(
+            cached.iter().map(|e| &e.created_at).collect::<Vec<_>>(),
+            stored.iter().map(|e| &e.created_at).collect::<Vec<_>>()
+        );
+        assert_eq!(cached[2].total_tokens, 40);
+    }
+
+    #[test]
+    fn test_subscription_usage_records_api_equivalent_cost() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let api = repo.upsert_provider("sk-test", None).expect("provider");
+        let subscription = repo
+            .upsert_subscription_provider("uuid-1", "Subscription")
+            .expect("subscription");
+        let now = Utc::now().to_rfc3339();
+
+        for (provider_id, message_id) in [(api.id, "m1"), (subscription.id, "m2")] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                now.clone(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.5,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+
+        let today = repo.get_today_stats().expect("today");
+        assert!((today.cost_usd - 1.5).abs() < 1e-9);
+        assert!((today.api_equivalent_cost_usd - 3.0).abs() < 1e-9);
+
+        let stats = repo.get_current_stats().expect("stats");
+        assert!((stats.total_cost_usd - 1.5).abs() < 1e-9);
+        assert!((stats.total_api_equivalent_cost_usd - 3.0).abs() < 1e-9);
+
+        let date = Local::now().date_naive().to_string();
+        let activities = repo.get_daily_activities(&date, &date).expect("daily");
+        assert!((activities[0].api_equivalent_cost_usd - 3.0).abs() < 1e-9);
+
+        assert!(
+            crate::services::integrity::verify_data_integrity(&repo)
+                .expect("integrity")
+                .is_consistent
+        );
+    }
+
+    #[test]
+    fn test_subscription_model_cost_survives_rebuild() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let api = repo.upsert_provider("sk-test", None).expect("provider");
+        let subscription = repo
+            .upsert_subscription_provider("uuid-1", "Subscription")
+            .expect("subscription");
+        let now = Utc::now().to_rfc3339();
+        // 先读取一次，让后续写入走缓存增量累加
+        repo.get_current_stats().expect("stats");
+
+        for (provider_id, message_id) in [(api.id, "m1"), (subscription.id, "m2")] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                now.clone(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.5,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+
+        let model_cost = |stats: &StatsCache| {
+            stats
+                .models
+                .iter()
+                .find(|model| model.model == "claude-3-opus")
+                .map(|model| model.cost_usd)
+                .expect("model")
+        };
+        let cached = repo.get_current_stats().expect("stats");
+        assert!((model_cost(&cached) - 1.5).abs() < 1e-9);
+
+        repo.rebuild_daily_stats().expect("rebuild");
+        let rebuilt = repo.get_current_stats().expect("stats");
+        assert!((model_cost(&rebuilt) - 1.5).abs() < 1e-9);
+        assert!((rebuilt.total_cost_usd - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_filters() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let first = repo.upsert_provider("sk-first", None).expect("provider");
+        let second = repo.upsert_provider("sk-second", None).expect("provider");
+        let now = Utc::now().to_rfc3339();
+
+        for (provider_id, message_id, model, project) in [
+            (first.id, "m1", "claude-3-opus", "/work/a"),
+            (first.id, "m2", "claude-3-sonnet", "/work/b"),
+            (second.id, "m3", "claude-3-opus", "/work/a"),
+        ] {
+            let record = MessageRecord::new(
+                format!("session-{}", message_id),
+                message_id.to_string(),
+                model.to_string(),
+                now.clone(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.0,
+                    ..Default::default()
+                },
+            )
+            .with_project(Some(project.to_string()));
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+
+        let all = repo
+            .get_current_stats_filtered(&StatsFilters::default())
+            .expect("stats");
+        assert_eq!(all.total_messages, 3);
+
+        let by_provider = StatsFilters {
+            provider_id: Some(first.id),
+            ..Default::default()
+        };
+        let stats = repo
+            .get_current_stats_filtered(&by_provider)
+            .expect("stats");
+        assert_eq!(stats.total_messages, 2);
+        assert_eq!(stats.models.len(), 2);
+
+        let by_model_and_project = StatsFilters {
+            model: Some("claude-3-opus".to_string()),
+            project: Some("/work/a".to_string()),
+            ..Default::default()
+        };
+        let today = repo
+            .get_today_stats_filtered(&by_model_and_project)
+            .expect("today");
+        assert_eq!(today.message_count, 2);
+        assert_eq!(today.session_count, 2);
+        assert!((today.cost_usd - 2.0).abs() < 1e-9);
+
+        let date = Local::now().date_naive().to_string();
+        let activities = repo
+            .get_daily_activities_filtered(&date, &date, &by_provider)
+            .expect("daily");
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].message_count, 2);
+        assert_eq!(activities[0].input_tokens, 200);
+    }
+
+    #[test]
+    fn test_get_range_stats() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let first = repo.upsert_provider("sk-first", None).expect("provider");
+        let second = repo.upsert_provider("sk-second", None).expect("provider");
+        let today = Local::now();
+        let yesterday = today - chrono::Duration::days(1);
+        let old = today - chrono::Duration::days(30);
+
+        for (provider_id, message_id, model, created_at) in [
+            (first.id, "m1", "claude-3-opus", yesterday),
+            (first.id, "m2", "claude-3-sonnet", today),
+            (second.id, "m3", "claude-3-opus", today),
+            (first.id, "m4", "claude-3-opus", old),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                model.to_string(),
+                created_at.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.0,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+
+        let start = yesterday.date_naive().to_string();
+        let end = today.date_naive().to_string();
+
+        let stats = repo
+            .get_range_stats(&start, &end, &StatsFilters::default())
+            .expect("range");
+        assert_eq!(stats.total_messages, 3);
+        assert_eq!(stats.total_sessions, 1);
+        assert_eq!(stats.total_input_tokens, 300);
+        assert_eq!(stats.models.len(), 2);
+        assert_eq!(stats.models[0].model, "claude-3-opus");
+
+        let by_provider = StatsFilters {
+            provider_id: Some(first.id),
+            ..Default::default()
+        };
+        let stats = repo
+            .get_range_stats(&start, &end, &by_provider)
+            .expect("range");
+        assert_eq!(stats.total_messages, 2);
+
+        let by_model = StatsFilters {
+            model: Some("claude-3-opus".to_string()),
+            ..Default::default()
+        };
+        let stats = repo
+            .get_range_stats(&start, &end, &by_model)
+            .expect("range");
+        assert_eq!(stats.total_messages, 2);
+        assert!((stats.total_cost_usd - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_rolling_average() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let today = Local::now();
+
+        for (days_ago, message_id, cost_usd) in [(3, "m1", 3.0), (1, "m2", 6.0)] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                (today - chrono::Duration::days(days_ago)).to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    output_tokens: 50,
+                    cost_usd,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let start = (today - chrono::Duration::days(1)).date_naive().to_string();
+        let end = today.date_naive().to_string();
+        let points = repo.get_rolling_average(3, &start, &end).expect("rolling");
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].date, start);
+        assert!((points[0].cost_usd - 6.0).abs() < 1e-9);
+        assert_eq!(points[0].total_tokens, 150);
+        assert!((points[0].avg_cost_usd - 3.0).abs() < 1e-9);
+        assert!((points[0].avg_total_tokens - 100.0).abs() < 1e-9);
+        assert!((points[1].cost_usd - 0.0).abs() < 1e-9);
+        assert!((points[1].avg_cost_usd - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_session_samples() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let start = Utc::now() - chrono::Duration::minutes(10);
+
+        for (message_id, created_at) in
+            [("m1", start), ("m2", start + chrono::Duration::minutes(10))]
+        {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                created_at.to_rfc3339(),
+                MessageUsage {
+                    cost_usd: 0.5,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let date = Local::now().date_naive();
+        let samples = repo
+            .get_session_samples(
+                &(date - chrono::Duration::days(1)).to_string(),
+                &date.to_string(),
+            )
+            .expect("samples");
+        assert_eq!(samples.len(), 1);
+        assert!((samples[0].duration_seconds - 600.0).abs() < 1e-3);
+        assert_eq!(samples[0].message_count, 2);
+        assert!((samples[0].cost_usd - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_saved_queries_run_readonly() {
+        let repo = Repository::new_in_memory().expect("repo");
+        repo.upsert_provider("sk-test", None).expect("provider");
+
+        let query = repo
+            .create_saved_query(
+                "providers",
+                "SELECT id, api_key_prefix, NULL AS empty, 1.5 AS ratio FROM providers;",
+                QueryVisualization::Table,
+            )
+            .expect("create");
+        let result = repo.run_readonly_query(&query.sql).expect("run");
+        assert_eq!(
+            result.columns,
+            vec!["id", "api_key_prefix", "empty", "ratio"]
+        );
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0][2], serde_json::Value::Null);
+        assert_eq!(result.rows[0][3], serde_json::json!(1.5));
+        assert!(!result.truncated);
+
+        for sql in [
+            "DELETE FROM providers",
+            "SELECT 1; DELETE FROM providers",
+            "WITH x AS (SELECT 1) DELETE FROM providers",
+            "ATTACH DATABASE ':memory:' AS other",
+            "PRAGMA user_version",
+        ] {
+            assert!(
+                matches!(
+                    repo.run_readonly_query(sql),
+                    Err(RepositoryError::NotReadOnly)
+                ),
+                "{}",
+                sql
+            );
+        }
+        for sql in [
+            "SELECT * FROM app_settings",
+            "SELECT url FROM webhook_targets",
+            "WITH s AS (SELECT value FROM APP_SETTINGS) SELECT * FROM s",
+            "SELECT id FROM providers WHERE id IN (SELECT id FROM webhook_targets)",
+        ] {
+            assert!(
+                matches!(
+                    repo.run_readonly_query(sql),
+                    Err(RepositoryError::RestrictedTable)
+                ),
+                "{}",
+                sql
+            );
+        }
+        assert!(repo.run_readonly_query("SELECT id FROM providers").is_ok());
+        // 字符串中的分号、结尾分号与注释不视为多条语句
+        for sql in [
+            "SELECT COUNT(*) FROM message_usage WHERE project = 'a;b'",
+            "SELECT id FROM providers; -- 全部供应商",
+        ] {
+            assert!(repo.run_readonly_query(sql).is_ok(), "{}", sql);
+        }
+
+        // 失控的递归查询超时后被中断，连接恢复可用
+        let endless = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT count(*) FROM c";
+        assert!(matches!(
+            repo.run_readonly_query_within(endless, Duration::from_millis(100)),
+            Err(RepositoryError::QueryTimeout(_))
+        ));
+        assert_eq!(repo.get_all_providers(false).expect("providers").len(), 1);
+        assert!(repo
+            .create_saved_query(
+                "bad",
+                "UPDATE providers SET is_active = 0",
+                Default::default()
+            )
+            .is_err());
+        assert_eq!(repo.get_all_providers(false).expect("providers").len(), 1);
+
+        let truncated = repo
+            .run_readonly_query(
+                "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000) SELECT i FROM n",
+            )
+            .expect("run");
+        assert_eq!(truncated.rows.len(), MAX_QUERY_ROWS);
+        assert!(truncated.truncated);
+
+        let updated = repo
+            .update_saved_query(
+                query.id,
+                "count",
+                "SELECT COUNT(*) FROM providers",
+                QueryVisualization::Number,
+            )
+            .expect("update")
+            .expect("exists");
+        assert_eq!(updated.visualization, QueryVisualization::Number);
+        assert_eq!(repo.get_saved_queries().expect("list"), vec![updated]);
+
+        assert!(repo.delete_saved_query(query.id).expect("delete"));
+        assert!(repo.get_saved_query(query.id).expect("get").is_none());
+    }
+
+    #[test]
+    fn test_set_model_alias_merges_and_reprices() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let api = repo.upsert_provider("sk-test", None).expect("provider");
+        let subscription = repo
+            .upsert_subscription_provider("uuid-1", "Subscription")
+            .expect("subscription");
+        let now = Utc::now().to_rfc3339();
+
+        for (provider_id, message_id, model) in [
+            (api.id, "m1", "claude-sonnet-4-5"),
+            (api.id, "m2", "relay/sonnet-latest"),
+            (subscription.id, "m3", "relay/sonnet-latest"),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                model.to_string(),
+                now.clone(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: if provider_id == api.id { 1.0 } else { 0.0 },
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+
+        let (alias, renamed) = repo
+            .set_model_alias(
+                "relay/sonnet-latest",
+                "claude-sonnet-4-5",
+                |_, input, _, _, _| input as f64 / 100.0,
+            )
+            .expect("set alias");
+        assert_eq!(alias.canonical, "claude-sonnet-4-5");
+        assert_eq!(renamed, 2);
+        assert_eq!(repo.get_model_aliases().expect("aliases"), vec![alias]);
+
+        let stats = repo.get_current_stats().expect("stats");
+        assert_eq!(stats.models.len(), 1);
+        assert_eq!(stats.models[0].model, "claude-sonnet-4-5");
+        assert_eq!(stats.models[0].message_count, 3);
+        assert!((stats.total_cost_usd - 2.0).abs() < 1e-9);
+        assert!((stats.total_api_equivalent_cost_usd - 3.0).abs() < 1e-9);
+        assert!(
+            crate::services::integrity::verify_data_integrity(&repo)
+                .expect("integrity")
+                .is_consistent
+        );
+
+        assert!(repo
+            .delete_model_alias("relay/sonnet-latest")
+            .expect("delete"));
+        assert!(!repo
+            .delete_model_alias("relay/sonnet-latest")
+            .expect("delete"));
+    }
+
+    #[test]
+    fn test_delete_and_restore_provider() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let other = repo.upsert_provider("sk-other", None).expect("provider");
+        for (provider_id, message_id) in
+            [(provider.id, "m1"), (provider.id, "m2"), (other.id, "m3")]
+        {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                Utc::now().to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.0,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+        repo.set_provider_plan(provider.id, PlanType::Pro, 20.0)
+            .expect("plan");
+
+        let item = repo
+            .delete_provider(provider.id)
+            .expect("delete")
+            .expect("item");
+        assert_eq!(item.kind, DeletedItemKind::Provider);
+        assert_eq!(item.source_id, provider.id);
+        assert_eq!(item.message_count, 2);
+        assert!(repo.delete_provider(provider.id).expect("delete").is_none());
+        assert_eq!(repo.get_deleted_items().expect("items"), vec![item.clone()]);
+        assert!((repo.get_current_stats().expect("stats").total_cost_usd - 1.0).abs() < 1e-9);
+
+        let restored = repo
+            .restore_deleted(item.id)
+            .expect("restore")
+            .expect("restored");
+        assert_eq!(restored.item, item);
+        assert_eq!(restored.messages_restored, 2);
+        assert!((repo.get_current_stats().expect("stats").total_cost_usd - 3.0).abs() < 1e-9);
+        let plans = repo.get_provider_plans().expect("plans");
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].provider_id, restored.restored_id);
+        assert!(repo.get_deleted_items().expect("items").is_empty());
+        assert!(repo.restore_deleted(item.id).expect("restore").is_none());
+    }
+
+    #[test]
+    fn test_delete_provider_reassigning() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let first = repo.upsert_provider("sk-first", None).expect("provider");
+        let second = repo.upsert_provider("sk-second", None).expect("provider");
+        let kept = repo.upsert_provider("sk-kept", None).expect("provider");
+        for (provider_id, message_id) in [
+            (first.id, "m1"),
+            (first.id, "m2"),
+            (second.id, "m3"),
+            (kept.id, "m4"),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                Utc::now().to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.0,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+
+        assert_eq!(
+            repo.delete_provider_reassigning(first.id).expect("delete"),
+            Some(2)
+        );
+        assert_eq!(
+            repo.delete_provider_reassigning(second.id).expect("delete"),
+            Some(1)
+        );
+        assert!(repo
+            .delete_provider_reassigning(first.id)
+            .expect("delete")
+            .is_none());
+
+        let providers = repo.get_all_providers(false).expect("providers");
+        assert_eq!(providers.len(), 2);
+        let unattributed = providers
+            .iter()
+            .find(|provider| provider.display_name.as_deref() == Some(UNATTRIBUTED_PROVIDER_NAME))
+            .expect("unattributed");
+        assert!(!unattributed.is_active);
+        assert!(repo
+            .delete_provider_reassigning(unattributed.id)
+            .expect("delete")
+            .is_none());
+
+        let stats = repo.get_current_stats().expect("stats");
+        assert!((stats.total_cost_usd - 4.0).abs() < 1e-9);
+        assert_eq!(stats.total_input_tokens, 400);
+        assert!(
+            crate::services::integrity::verify_data_integrity(&repo)
+                .expect("integrity")
+                .is_consistent
+        );
+    }
+
+    #[test]
+    fn test_purge_deleted_items() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let item = repo
+            .delete_provider(provider.id)
+            .expect("delete")
+            .expect("item");
+
+        assert!(repo
+            .purge_deleted_items("2000-01-01T00:00:00+00:00")
+            .expect("purge")
+            .is_empty());
+        let future = (Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+        assert_eq!(
+            repo.purge_deleted_items(&future).expect("purge"),
+            vec![item]
+        );
+        assert!(repo.get_deleted_items().expect("items").is_empty());
+    }
+
+    #[test]
+    fn test_recalculate_costs() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let api = repo.upsert_provider("sk-test", None).expect("provider");
+        let subscription = repo
+            .upsert_subscription_provider("uuid-1", "Subscription")
+            .expect("subscription");
+        let now = Utc::now().to_rfc3339();
+
+        for (provider_id, message_id, model, cost_usd) in [
+            (api.id, "m1", "claude-3-opus", 1.0),
+            (subscription.id, "m2", "claude-3-opus", 1.0),
+            (subscription.id, "m3", "unknown-model", 0.5),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                model.to_string(),
+                now.clone(),
+                MessageUsage {
+                    input_tokens: 300,
+                    cost_usd,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider_id, &record)
+                .expect("insert");
+        }
+
+        let estimate = |model: &str, _: &str, input: i64, _, _, _| {
+            (model == "claude-3-opus").then_some(input as f64 / 100.0)
+        };
+        let audit = repo
+            .recalculate_costs("v2", estimate)
+            .expect("recalculate")
+            .expect("audit");
+        assert_eq!(audit.pricing_version, "v2");
+        assert_eq!(audit.changed_messages, 1);
+        assert!((audit.old_total_usd - 1.5).abs() < 1e-9);
+        assert!((audit.new_total_usd - 3.5).abs() < 1e-9);
+
+        let stats = repo.get_current_stats().expect("stats");
+        assert!((stats.total_cost_usd - 1.0).abs() < 1e-9);
+        assert!((stats.total_api_equivalent_cost_usd - 4.5).abs() < 1e-9);
+
+        // 价格未变化时不重复记录
+        assert!(repo
+            .recalculate_costs("v2", estimate)
+            .expect("recalculate")
+            .is_none());
+        assert_eq!(
+            repo.get_recalculation_history(10).expect("history"),
+            vec![audit]
+        );
+    }
+
+    #[test]
+    fn test_cache_hit_rate_formula_setting() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "m1".to_string(),
+            "claude-3-opus".to_string(),
+            Utc::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 100,
+                cache_read_tokens: 300,
+                cache_creation_tokens: 200,
+                ..Default::default()
+            },
+        );
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        assert!((repo.get_today_stats().expect("today").cache_hit_rate - 0.75).abs() < 1e-9);
+
+        repo.set_setting(&CacheHitRateSettings {
+            formula: CacheHitRateFormula::Inclusive,
+        })
+        .expect("settings");
+
+        assert!((repo.get_today_stats().expect("today").cache_hit_rate - 0.5).abs() < 1e-9);
+        assert!((repo.get_current_stats().expect("stats").cache_hit_rate - 0.5).abs() < 1e-9);
+        let provider_stats = repo.get_today_provider_stats().expect("providers");
+        assert!((provider_stats[0].cache_hit_rate - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_set_privacy_settings_remasks_prefixes() {
+        let repo = Repository::new_in_memory().expect("repo");
+        repo.upsert_provider("sk-ant-api03-first", None)
+            .expect("provider");
+        repo.create_provider("sk-ant-api03-second", None)
+            .expect("provider");
+
+        let remasked = repo
+            .set_privacy_settings(&PrivacySettings {
+                api_key_prefix_length: 3,
+            })
+            .expect("privacy");
+        assert_eq!(remasked, 2);
+
+        let provider = repo
+            .upsert_provider("sk-ant-api03-third", None)
+            .expect("provider");
+        assert_eq!(provider.api_key_prefix, "sk-");
+        assert!(repo
+            .get_all_providers(false)
+            .expect("providers")
+            .iter()
+            .all(|provider| provider.api_key_prefix == "sk-"));
+
+        // 放宽设置不会恢复已截短的前缀
+        let remasked = repo
+            .set_privacy_settings(&PrivacySettings::default())
+            .expect("privacy");
+        assert_eq!(remasked, 0);
+    }
+
+    #[test]
+    fn test_get_latency_samples() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let today = Local::now().date_naive().to_string();
+
+        for (message_id, duration_ms) in [("m1", Some(1200)), ("m2", None)] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                Utc::now().to_rfc3339(),
+                MessageUsage::default(),
+            )
+            .with_duration_ms(duration_ms);
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let samples = repo.get_latency_samples(&today, &today).expect("samples");
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].duration_ms, 1200);
+        assert_eq!(samples[0].provider_id, provider.id);
+    }
+
+    #[test]
+    fn test_rate_limit_events() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let today = Local::now().date_naive().to_string();
+        let now = Utc::now();
+
+        let event = |kind: RateLimitKind, offset_secs: i64| RateLimitEvent {
+            session_id: "session-1".to_string(),
+            kind,
+            status_code: None,
+            message: "error".to_string(),
+            occurred_at: (now - chrono::Duration::seconds(offset_secs)).to_rfc3339(),
+        };
+
+        assert!(repo
+            .insert_rate_limit_event(provider.id, &event(RateLimitKind::RateLimited, 0))
+            .expect("insert"));
+        assert!(!repo
+            .insert_rate_limit_event(provider.id, &event(RateLimitKind::RateLimited, 0))
+            .expect("duplicate"));
+        repo.insert_rate_limit_event(provider.id, &event(RateLimitKind::RateLimited, 1))
+            .expect("insert");
+        repo.insert_rate_limit_event(provider.id, &event(RateLimitKind::Overloaded, 2))
+            .expect("insert");
+
+        let stats = repo.get_rate_limit_stats(&today, &today).expect("stats");
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].rate_limited_count, 2);
+        assert_eq!(stats[0].overloaded_count, 1);
+        assert!(repo
+            .get_rate_limit_stats("2000-01-01", "2000-01-02")
+            .expect("stats")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_compaction_stats() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let today = Local::now().date_naive().to_string();
+        let now = Utc::now();
+
+        for session_id in ["session-1", "session-2"] {
+            let record = MessageRecord::new(
+                session_id.to_string(),
+                format!("message-{}", session_id),
+                "claude-3-sonnet".to_string(),
+                now.to_rfc3339(),
+                MessageUsage::default(),
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+        repo.upsert_session_title("session-1", "Refactor", SessionTitleSource::Summary)
+            .expect("title");
+
+        let event =
+            |trigger: CompactionTrigger, pre_tokens: i64, offset_secs: i64| CompactionEvent {
+                session_id: "session-1".to_string(),
+                trigger,
+                pre_tokens: Some(pre_tokens),
+                occurred_at: (now - chrono::Duration::seconds(offset_secs)).to_rfc3339(),
+            };
+        assert!(repo
+            .insert_compaction_event(provider.id, &event(CompactionTrigger::Auto, 150_000, 0))
+            .expect("insert"));
+        assert!(!repo
+            .insert_compaction_event(provider.id, &event(CompactionTrigger::Auto, 150_000, 0))
+            .expect("duplicate"));
+        repo.insert_compaction_event(provider.id, &event(CompactionTrigger::Manual, 50_000, 60))
+            .expect("insert");
+
+        let stats = repo.get_compaction_stats(&today, &today).expect("stats");
+        assert_eq!(stats.total_count, 2);
+        assert_eq!(stats.auto_count, 1);
+        assert_eq!(stats.manual_count, 1);
+        assert_eq!(stats.avg_pre_tokens, Some(100_000.0));
+        assert_eq!(stats.compacted_sessions, 1);
+        assert_eq!(stats.total_sessions, 2);
+        assert!((stats.compacted_session_rate - 0.5).abs() < 1e-9);
+        assert_eq!(stats.sessions[0].title.as_deref(), Some("Refactor"));
+        assert_eq!(stats.sessions[0].compaction_count, 2);
+
+        let empty = repo
+            .get_compaction_stats("2000-01-01", "2000-01-02")
+            .expect("stats");
+        assert_eq!(empty.total_count, 0);
+        assert!(empty.sessions.is_empty());
+    }
+
+    #[test]
+    fn test_service_tier_usage() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let today = Local::now().date_naive().to_string();
+
+        for (message_id, service_tier) in [
+            ("message-1", ServiceTier::Standard),
+            ("message-2", ServiceTier::Batch),
+            ("message-3", ServiceTier::Batch),
+        ] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-sonnet".to_string(),
+                Local::now().to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.0,
+                    ..Default::default()
+                },
+            )
+            .with_service_tier(service_tier);
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let usage = repo.get_service_tier_usage(&today, &today).expect("usage");
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].service_tier, ServiceTier::Batch);
+        assert_eq!(usage[0].message_count, 2);
+        assert_eq!(usage[0].input_tokens, 200);
+        assert_eq!(usage[1].service_tier, ServiceTier::Standard);
+    }
+
+    #[test]
+    fn test_tool_call_stats() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let today = Local::now().date_naive().to_string();
+        let now = Utc::now().to_rfc3339();
+
+        let call = |session_id: &str, tool_use_id: &str, tool_name: &str| ToolCall {
+            session_id: session_id.to_string(),
+            message_id: "msg-1".to_string(),
+            tool_use_id: tool_use_id.to_string(),
+            tool_name: tool_name.to_string(),
+            called_at: now.clone(),
+        };
+        let calls = vec![
+            call("session-1", "toolu_1", "Bash"),
+            call("session-1", "toolu_2", "Bash"),
+            call("session-2", "toolu_3", "Bash"),
+            call("session-2", "toolu_4", "Read"),
+        ];
+        assert_eq!(
+            repo.insert_tool_calls(provider.id, &calls).expect("insert"),
+            4
+        );
+        assert_eq!(
+            repo.insert_tool_calls(provider.id, &calls)
+                .expect("duplicate"),
+            0
+        );
+
+        let stats = repo.get_tool_call_stats(&today, &today).expect("stats");
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].tool_name, "Bash");
+        assert_eq!(stats[0].call_count, 3);
+        assert_eq!(stats[0].session_count, 2);
+        assert!((stats[0].share - 0.75).abs() < 1e-9);
+        assert_eq!(stats[1].tool_name, "Read");
+        assert!(repo
+            .get_tool_call_stats("2000-01-01", "2000-01-02")
+            .expect("stats")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_interrupt_stats() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let today = Local::now().date_naive().to_string();
+        let now = Utc::now();
+        let at = |offset_secs: i64| (now - chrono::Duration::seconds(offset_secs)).to_rfc3339();
+
+        for (message_id, output_tokens, offset_secs) in [("m1", 100, 60), ("m2", 400, 30)] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-sonnet".to_string(),
+                at(offset_secs),
+                MessageUsage {
+                    input_tokens: 1_000,
+                    output_tokens,
+                    cost_usd: 0.5,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let interrupt = |kind: InterruptKind, offset_secs: i64| InterruptEvent {
+            session_id: "session-1".to_string(),
+            kind,
+            occurred_at: at(offset_secs),
+        };
+        // 两次中断都发生在 m2 之后，只计一次 m2 的用量
+        assert!(repo
+            .insert_interrupt(provider.id, &interrupt(InterruptKind::User, 20))
+            .expect("insert"));
+        assert!(!repo
+            .insert_interrupt(provider.id, &interrupt(InterruptKind::User, 20))
+            .expect("duplicate"));
+        repo.insert_interrupt(provider.id, &interrupt(InterruptKind::ToolUse, 10))
+            .expect("insert");
+        // 找不到之前消息的中断只计次数
+        repo.insert_interrupt(
+            provider.id,
+            &InterruptEvent {
+                session_id: "session-2".to_string(),
+                kind: InterruptKind::User,
+                occurred_at: at(5),
+            },
+        )
+        .expect("insert");
+
+        let stats = repo.get_interrupt_stats(&today, &today).expect("stats");
+        assert_eq!(stats.total.interrupt_count, 3);
+        assert_eq!(stats.total.discarded_tokens, 1_400);
+        assert_eq!(stats.total.discarded_output_tokens, 400);
+        assert!((stats.total.discarded_cost_usd - 0.5).abs() < 1e-9);
+        assert_eq!(stats.daily.len(), 1);
+        assert_eq!(stats.daily[0].usage, stats.total);
+        assert_eq!(stats.sessions.len(), 2);
+        assert_eq!(stats.sessions[0].session_id, "session-1");
+        assert_eq!(stats.sessions[0].usage.interrupt_count, 2);
+        assert_eq!(stats.sessions[1].usage.discarded_tokens, 0);
+
+        let empty = repo
+            .get_interrupt_stats("2000-01-01", "2000-01-02")
+            .expect("stats");
+        assert_eq!(empty.total, DiscardedUsage::default());
+        assert!(empty.daily.is_empty());
+    }
+
+    #[test]
+    fn test_api_error_stats() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let relay = repo.upsert_provider("sk-relay", None).expect("provider");
+        let official = repo.upsert_provider("sk-official", None).expect("provider");
+        let today = Local::now().date_naive().to_string();
+        let now = Utc::now();
+
+        for message_id in ["message-1", "message-2", "message-3"] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                now.to_rfc3339(),
+                MessageUsage::default(),
+            );
+            repo.insert_message_usage(official.id, &record)
+                .expect("insert");
+        }
+
+        let error = |kind: ApiErrorKind, is_retry: bool, offset_secs: i64| ApiErrorEvent {
+            session_id: "session-1".to_string(),
+            kind,
+            status_code: None,
+            message: "error".to_string(),
+            is_retry,
+            occurred_at: (now - chrono::Duration::seconds(offset_secs)).to_rfc3339(),
+        };
+
+        assert!(repo
+            .insert_api_error(official.id, &error(ApiErrorKind::Timeout, true, 0))
+            .expect("insert"));
+        assert!(!repo
+            .insert_api_error(official.id, &error(ApiErrorKind::Timeout, true, 0))
+            .expect("duplicate"));
+        repo.insert_api_error(relay.id, &error(ApiErrorKind::Client, false, 0))
+            .expect("insert");
+        repo.insert_api_error(relay.id, &error(ApiErrorKind::Server, true, 1))
+            .expect("insert");
+
+        let stats = repo.get_api_error_stats(&today, &today).expect("stats");
+        assert_eq!(stats.len(), 2);
+
+        assert_eq!(stats[0].provider_id, relay.id);
+        assert_eq!(stats[0].request_count, 0);
+        assert_eq!(stats[0].error_count, 2);
+        assert!((stats[0].error_rate - 1.0).abs() < 1e-9);
+        assert_eq!(stats[0].client_error_count, 1);
+        assert_eq!(stats[0].server_error_count, 1);
+        assert_eq!(stats[0].retry_count, 1);
+
+        assert_eq!(stats[1].provider_id, official.id);
+        assert_eq!(stats[1].request_count, 3);
+        assert_eq!(stats[1].timeout_count, 1);
+        assert!((stats[1].error_rate - 0.25).abs() < 1e-9);
+
+        assert!(repo
+            .get_api_error_stats("2000-01-01", "2000-01-02")
+            .expect("stats")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_compare_providers() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let relay = repo.upsert_provider("sk-relay", None).expect("provider");
+        let official = repo.upsert_provider("sk-official", None).expect("provider");
+        let today = Local::now().date_naive();
+        let start = (today - chrono::Duration::days(2)).to_string();
+        let now = Utc::now();
+
+        for (message_id, duration_ms) in [("message-1", 1000), ("message-2", 3000)] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                now.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    output_tokens: 50,
+                    cache_read_tokens: 300,
+                    cache_creation_tokens: 0,
+                    thinking_tokens: 0,
+                    cost_usd: 0.5,
+                },
+            )
+            .with_duration_ms(Some(duration_ms));
+            repo.insert_message_usage(relay.id, &record)
+                .expect("insert");
+        }
+        repo.insert_api_error(
+            relay.id,
+            &ApiErrorEvent {
+                session_id: "session-1".to_string(),
+                kind: ApiErrorKind::Overloaded,
+                status_code: Some(529),
+                message: "overloaded".to_string(),
+                is_retry: true,
+                occurred_at: now.to_rfc3339(),
+            },
+        )
+        .expect("insert");
+
+        let comparisons = repo
+            .compare_providers(&[official.id, relay.id, 999], &start, &today.to_string())
+            .expect("compare");
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!(comparisons[0].provider_id, official.id);
+        assert_eq!(comparisons[1].provider_id, relay.id);
+
+        for comparison in &comparisons {
+            let dates: Vec<_> = comparison.points.iter().map(|p| p.date.clone()).collect();
+            assert_eq!(dates.len(), 3);
+            assert_eq!(dates[0], start);
+            assert_eq!(dates[2], today.to_string());
+        }
+
+        assert!(comparisons[0]
+            .points
+            .iter()
+            .all(|p| p.request_count == 0 && p.avg_latency_ms.is_none()));
+
+        let point = &comparisons[1].points[2];
+        assert!((point.cost_usd - 1.0).abs() < 1e-9);
+        assert_eq!(point.total_tokens, 300);
+        assert!((point.cache_hit_rate - 0.75).abs() < 1e-9);
+        assert_eq!(point.avg_latency_ms, Some(2000.0));
+        assert_eq!(point.error_count, 1);
+        assert!((point.error_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(comparisons[1].points[0].request_count, 0);
+
+        assert!(repo
+            .compare_providers(&[relay.id], &today.to_string(), &start)
+            .expect("compare")[0]
+            .points
+            .is_empty());
+    }
+
+    #[test]
+    fn test_tags() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let now = Local::now();
+        let today = now.date_naive().to_string();
+
+        for (session_id, message_id, cost_usd) in [
+            ("session-1", "message-1", 1.0),
+            ("session-1", "message-2", 2.0),
+            ("session-2", "message-3", 4.0),
+        ] {
+            let record = MessageRecord::new(
+                session_id.to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                now.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 10,
+                    cost_usd,
+                    ..Default::default()
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let session = TagTarget::Session("session-1".to_string());
+        let day = TagTarget::Date(today.clone());
+        assert!(repo.add_tag("client-A", &session).expect("add"));
+        assert!(!repo.add_tag("client-A", &session).expect("duplicate"));
+        assert!(repo.add_tag("client-A", &day).expect("add"));
+        assert!(repo
+            .add_tag("experiment", &TagTarget::Session("session-2".to_string()))
+            .expect("add"));
+
+        let stats = repo.get_stats_by_tag(None, None).expect("stats");
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].name, "client-A");
+        assert_eq!(stats[0].tagged_session_count, 1);
+        assert_eq!(stats[0].tagged_day_count, 1);
+        assert_eq!(stats[0].message_count, 3);
+        assert!((stats[0].cost_usd - 7.0).abs() < 1e-9);
+        assert_eq!(stats[1].name, "experiment");
+        assert_eq!(stats[1].message_count, 1);
+
+        let stats = repo
+            .get_stats_by_tag(Some("2000-01-01"), Some("2000-01-02"))
+            .expect("stats");
+        assert!(stats.iter().all(|tag| tag.message_count == 0));
+
+        assert!(repo.remove_tag("client-A", &day).expect("remove"));
+        assert!(!repo.remove_tag("client-A", &day).expect("remove"));
+        let stats = repo.get_stats_by_tag(None, None).expect("stats");
+        assert_eq!(stats[0].name, "experiment");
+        assert_eq!(stats[1].message_count, 2);
+
+        repo.remove_tag("client-A", &session).expect("remove");
+        let stats = repo.get_stats_by_tag(None, None).expect("stats");
+        assert_eq!(stats.len(), 1);
+    }
+
+    #[test]
+    fn test_notification_history() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let today = Local::now().date_naive().to_string();
+
+        let first = repo
+            .insert_notification(
+                NotificationKind::BlockWarning,
+                "区块用量提醒",
+                "当前 5 小时区块已使用约 85%",
+                &serde_json::json!({ "threshold_percent": 80 }),
+                DeliveryChannel::System,
+            )
+            .expect("insert");
+        let second = repo
+            .insert_notification(
+                NotificationKind::ContextWarning,
+                "上下文提醒",
+                "session-1",
+                &serde_json::json!(null),
+                DeliveryChannel::InApp,
+            )
+            .expect("insert");
+
+        let history = repo
+            .get_notification_history(&today, &today)
+            .expect("history");
+        assert_eq!(history, vec![second, first]);
+        assert_eq!(history[1].payload["threshold_percent"], 80);
+        assert!(repo
+            .get_notification_history("2000-01-01", "2000-01-31")
+            .expect("history")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_date_notes() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let now = Local::now();
+        let today = now.date_naive().to_string();
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            now.to_rfc3339(),
+            MessageUsage::default(),
+        );
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        repo.set_date_note(&today, "huge refactor").expect("note");
+        repo.set_date_note(&today, "migrated repo, huge refactor")
+            .expect("note");
+        repo.set_date_note("2000-01-01", "vacation").expect("note");
+
+        let activities = repo.get_daily_activities(&today, &today).expect("daily");
+        assert_eq!(
+            activities[0].note.as_deref(),
+            Some("migrated repo, huge refactor")
+        );
+
+        let notes = repo.get_date_notes("2000-01-01", &today).expect("notes");
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].note, "vacation");
+
+        assert!(repo.delete_date_note(&today).expect("delete"));
+        assert!(!repo.delete_date_note(&today).expect("delete"));
+        let activities = repo.get_daily_activities(&today, &today).expect("daily");
+        assert_eq!(activities[0].note, None);
+    }
+
+    #[test]
+    fn test_cost_allocation() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let now = Local::now();
+        let today = now.date_naive().to_string();
+
+        for (session_id, message_id, project, cost_usd) in [
+            ("session-1", "message-1", Some("client-a"), 3.0),
+            ("session-2", "message-2", Some("client-b"), 1.0),
+            ("session-3", "message-3", None, 4.0),
+        ] {
+            let mut record = MessageRecord::new(
+                session_id.to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                now.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 10,
+                    cost_usd,
+                    ..Default::default()
+                },
+            );
+            record.project = project.map(|project| project.to_string());
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+        assert!((repo.get_range_cost(&today, &today).expect("cost") - 8.0).abs() < 1e-9);
+
+        let rows = repo
+            .get_cost_allocation(AllocationGroupBy::Project, &today, &today)
+            .expect("allocation");
+        let groups: Vec<_> = rows.iter().map(|row| row.group.as_deref()).collect();
+        assert_eq!(groups, vec![None, Some("client-a"), Some("client-b")]);
+        assert!((rows[1].cost_share - 0.375).abs() < 1e-9);
+
+        repo.add_tag("billable", &TagTarget::Session("session-1".to_string()))
+            .expect("tag");
+        repo.add_tag("billable", &TagTarget::Session("session-2".to_string()))
+            .expect("tag");
+        let rows = repo
+            .get_cost_allocation(AllocationGroupBy::Tag, &today, &today)
+            .expect("allocation");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].group.as_deref(), Some("billable"));
+        assert_eq!(rows[0].session_count, 2);
+        assert!((rows[0].cost_share - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rebuild_daily_stats_repairs_drift() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let created_at = Utc::now().to_rfc3339();
+
+        for (session_id, message_id) in [("s1", "m1"), ("s1", "m2"), ("s2", "m3")] {
+            let record = MessageRecord::new(
+                session_id.to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                created_at.clone(),
+                MessageUsage {
+                    input_tokens: 100,
+                    output_tokens: 10,
+                    cache_read_tokens: 5,
+                    cache_creation_tokens: 1,
+                    thinking_tokens: 0,
+                    cost_usd: 0.5,
+                },
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let key = (provider.id, extract_date(&created_at));
+        let recomputed = repo.recompute_daily_totals().expect("recompute");
+        assert_eq!(recomputed[&key].input_tokens, 300);
+        assert_eq!(recomputed[&key].session_count, 2);
+        assert_eq!(recomputed[&key].message_count, 3);
+        assert!(repo.get_stored_daily_totals().expect("stored")[&key].matches(&recomputed[&key]));
+
+        {
+            let conn = repo.connection().expect("conn");
+            conn.execute("UPDATE daily_stats SET total_input_tokens = 999", [])
+                .expect("corrupt");
+            conn.execute(
+                "INSERT INTO daily_stats (provider_id, date) VALUES (?1, '2000-01-01')",
+                params![provider.id],
+            )
+            .expect("orphan");
+        }
+        let stored = repo.get_stored_daily_totals().expect("stored");
+        assert_eq!(stored.len(), 2);
+        assert!(!stored[&key].matches(&recomputed[&key]));
+
+        assert_eq!(repo.rebuild_daily_stats().expect("rebuild"), 1);
+        let stored = repo.get_stored_daily_totals().expect("stored");
+        assert_eq!(stored.len(), 1);
+        assert!(stored[&key].matches(&recomputed[&key]));
+
+        let models = repo
+            .get_provider_model_usage(provider.id, &key.1, &key.1)
+            .expect("model usage");
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].message_count, 3);
     }
 }
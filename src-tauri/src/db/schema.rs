@@ -10,7 +10,8 @@ CREATE TABLE IF NOT EXISTS schema_migrations (
 );
 "#;
 
-pub const CREATE_PROVIDERS_TABLE: &str = r#"
+/// 核心表：供应商、消息记录、每日汇总与供应商切换日志
+pub const CREATE_CORE_TABLES: &str = r#"
 CREATE TABLE IF NOT EXISTS providers (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     api_key_hash TEXT NOT NULL UNIQUE,
@@ -21,9 +22,6 @@ CREATE TABLE IF NOT EXISTS providers (
     first_seen_at TEXT NOT NULL,
     last_seen_at TEXT NOT NULL
 );
-"#;
-
-pub const CREATE_MESSAGE_USAGE_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS message_usage (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     provider_id INTEGER NOT NULL,
@@ -38,9 +36,6 @@ CREATE TABLE IF NOT EXISTS message_usage (
     created_at TEXT NOT NULL,
     FOREIGN KEY (provider_id) REFERENCES providers(id)
 );
-"#;
-
-pub const CREATE_DAILY_STATS_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS daily_stats (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     provider_id INTEGER NOT NULL,
@@ -55,20 +50,1151 @@ CREATE TABLE IF NOT EXISTS daily_stats (
     UNIQUE(provider_id, date),
     FOREIGN KEY (provider_id) REFERENCES providers(id)
 );
+CREATE TABLE IF NOT EXISTS provider_switch_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    switched_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_message_usage_provider ON message_usage(provider_id);
+CREATE INDEX IF NOT EXISTS idx_message_usage_created ON message_usage(created_at);
+CREATE INDEX IF NOT EXISTS idx_daily_stats_date ON daily_stats(date);
+CREATE INDEX IF NOT EXISTS idx_daily_stats_provider ON daily_stats(provider_id);
+"#;
+
+pub const DROP_CORE_TABLES: &str = r#"
+DROP TABLE IF EXISTS provider_switch_logs;
+DROP TABLE IF EXISTS daily_stats;
+DROP TABLE IF EXISTS message_usage;
+DROP TABLE IF EXISTS providers;
+"#;
+
+pub const CREATE_PROVIDER_PLANS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS provider_plans (
+    provider_id INTEGER PRIMARY KEY,
+    plan_type TEXT NOT NULL,
+    monthly_fee_usd REAL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+"#;
+
+pub const DROP_PROVIDER_PLANS_TABLE: &str = "DROP TABLE IF EXISTS provider_plans;";
+
+pub const CREATE_COST_ANOMALIES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS cost_anomalies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL UNIQUE,
+    cost_usd REAL NOT NULL,
+    baseline_mean_usd REAL NOT NULL,
+    baseline_stddev_usd REAL NOT NULL,
+    weekday_mean_usd REAL NOT NULL,
+    ratio REAL NOT NULL,
+    detected_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_COST_ANOMALIES_TABLE: &str = "DROP TABLE IF EXISTS cost_anomalies;";
+
+pub const CREATE_MODEL_DAILY_STATS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS model_daily_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    model TEXT NOT NULL,
+    total_input_tokens INTEGER DEFAULT 0,
+    total_output_tokens INTEGER DEFAULT 0,
+    total_cache_read_tokens INTEGER DEFAULT 0,
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    total_cost_usd REAL DEFAULT 0,
+    message_count INTEGER DEFAULT 0,
+    UNIQUE(provider_id, date, model),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_model_daily_stats_date ON model_daily_stats(date);
+"#;
+
+pub const DROP_MODEL_DAILY_STATS_TABLE: &str = "DROP TABLE IF EXISTS model_daily_stats;";
+
+/// 从已有 message_usage 回填 model_daily_stats
+pub const BACKFILL_MODEL_DAILY_STATS: &str = r#"
+INSERT OR IGNORE INTO model_daily_stats (provider_id, date, model, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, message_count)
+SELECT
+    provider_id,
+    COALESCE(date(created_at, 'localtime'), date('now', 'localtime')) AS day,
+    model,
+    SUM(input_tokens),
+    SUM(output_tokens),
+    SUM(cache_read_tokens),
+    SUM(cache_creation_tokens),
+    SUM(cost_usd),
+    COUNT(*)
+FROM message_usage
+GROUP BY provider_id, day, model;
+"#;
+
+pub const ADD_MESSAGE_USAGE_PROJECT: &str = r#"
+ALTER TABLE message_usage ADD COLUMN project TEXT;
+CREATE INDEX IF NOT EXISTS idx_message_usage_session ON message_usage(session_id);
+"#;
+
+pub const DROP_MESSAGE_USAGE_PROJECT: &str = r#"
+DROP INDEX IF EXISTS idx_message_usage_project;
+DROP INDEX IF EXISTS idx_message_usage_session;
+ALTER TABLE message_usage DROP COLUMN project;
+"#;
+
+pub const CREATE_MESSAGE_SEARCH_INDEXES: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_message_usage_model ON message_usage(model);
+CREATE INDEX IF NOT EXISTS idx_message_usage_project ON message_usage(project);
+CREATE INDEX IF NOT EXISTS idx_message_usage_provider_created ON message_usage(provider_id, created_at);
+"#;
+
+pub const DROP_MESSAGE_SEARCH_INDEXES: &str = r#"
+DROP INDEX IF EXISTS idx_message_usage_model;
+DROP INDEX IF EXISTS idx_message_usage_project;
+DROP INDEX IF EXISTS idx_message_usage_provider_created;
+"#;
+
+pub const CREATE_SESSIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS sessions (
+    session_id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    title_source TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_SESSIONS_TABLE: &str = "DROP TABLE IF EXISTS sessions;";
+
+pub const CREATE_APP_SETTINGS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS app_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_APP_SETTINGS_TABLE: &str = "DROP TABLE IF EXISTS app_settings;";
+
+/// Webhook 目标、投递记录与预算告警记录
+///
+/// budget_alerts 以 (date, level) 为主键，保证每天每个预算级别只推送一次
+pub const CREATE_WEBHOOK_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS webhook_targets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    format TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    target_id INTEGER NOT NULL,
+    target_name TEXT NOT NULL,
+    event TEXT NOT NULL,
+    title TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    error TEXT,
+    delivered_at TEXT NOT NULL,
+    FOREIGN KEY (target_id) REFERENCES webhook_targets(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS budget_alerts (
+    date TEXT NOT NULL,
+    level TEXT NOT NULL,
+    spent_usd REAL NOT NULL,
+    budget_usd REAL NOT NULL,
+    triggered_at TEXT NOT NULL,
+    PRIMARY KEY (date, level)
+);
+"#;
+
+pub const DROP_WEBHOOK_TABLES: &str = r#"
+DROP TABLE IF EXISTS budget_alerts;
+DROP TABLE IF EXISTS webhook_deliveries;
+DROP TABLE IF EXISTS webhook_targets;
+"#;
+
+/// message_usage 唯一索引，同一供应商同一消息在同一天（按 created_at 的 UTC 日期）只记录一次
+///
+/// 创建前先清理历史重复记录（保留最早插入的一条），并按本地日期重新汇总受影响的
+/// daily_stats 与 model_daily_stats，避免被删除的重复记录仍计入汇总
+pub const CREATE_MESSAGE_USAGE_UNIQUE_INDEX: &str = r#"
+CREATE TEMP TABLE duplicate_usage_days AS
+SELECT DISTINCT provider_id, COALESCE(date(created_at, 'localtime'), date('now', 'localtime')) AS day
+FROM message_usage
+WHERE id NOT IN (
+    SELECT MIN(id) FROM message_usage
+    GROUP BY provider_id, message_id, substr(created_at, 1, 10)
+);
+DELETE FROM message_usage
+WHERE id NOT IN (
+    SELECT MIN(id) FROM message_usage
+    GROUP BY provider_id, message_id, substr(created_at, 1, 10)
+);
+DELETE FROM daily_stats
+WHERE (provider_id, date) IN (SELECT provider_id, day FROM duplicate_usage_days);
+INSERT INTO daily_stats (provider_id, date, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, session_count, message_count)
+SELECT
+    provider_id,
+    COALESCE(date(created_at, 'localtime'), date('now', 'localtime')) AS day,
+    SUM(input_tokens),
+    SUM(output_tokens),
+    SUM(cache_read_tokens),
+    SUM(cache_creation_tokens),
+    SUM(cost_usd),
+    COUNT(DISTINCT session_id),
+    COUNT(*)
+FROM message_usage
+WHERE (provider_id, COALESCE(date(created_at, 'localtime'), date('now', 'localtime')))
+    IN (SELECT provider_id, day FROM duplicate_usage_days)
+GROUP BY provider_id, day;
+DELETE FROM model_daily_stats
+WHERE (provider_id, date) IN (SELECT provider_id, day FROM duplicate_usage_days);
+INSERT INTO model_daily_stats (provider_id, date, model, total_input_tokens, total_output_tokens, total_cache_read_tokens, total_cache_creation_tokens, total_cost_usd, message_count)
+SELECT
+    provider_id,
+    COALESCE(date(created_at, 'localtime'), date('now', 'localtime')) AS day,
+    model,
+    SUM(input_tokens),
+    SUM(output_tokens),
+    SUM(cache_read_tokens),
+    SUM(cache_creation_tokens),
+    SUM(cost_usd),
+    COUNT(*)
+FROM message_usage
+WHERE (provider_id, COALESCE(date(created_at, 'localtime'), date('now', 'localtime')))
+    IN (SELECT provider_id, day FROM duplicate_usage_days)
+GROUP BY provider_id, day, model;
+DROP TABLE duplicate_usage_days;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_message_usage_unique
+ON message_usage(provider_id, message_id, substr(created_at, 1, 10));
+"#;
+
+pub const DROP_MESSAGE_USAGE_UNIQUE_INDEX: &str = "DROP INDEX IF EXISTS idx_message_usage_unique;";
+
+/// 全局 message_id 索引，用于跨供应商去重
+pub const CREATE_MESSAGE_ID_INDEX: &str =
+    "CREATE INDEX IF NOT EXISTS idx_message_usage_message ON message_usage(message_id);";
+
+pub const DROP_MESSAGE_ID_INDEX: &str = "DROP INDEX IF EXISTS idx_message_usage_message;";
+
+pub const CREATE_RATE_LIMIT_EVENTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS rate_limit_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_rate_limit_events_occurred ON rate_limit_events(occurred_at);
+"#;
+
+pub const DROP_RATE_LIMIT_EVENTS_TABLE: &str = "DROP TABLE IF EXISTS rate_limit_events;";
+
+pub const ADD_MESSAGE_USAGE_DURATION: &str =
+    "ALTER TABLE message_usage ADD COLUMN duration_ms INTEGER;";
+
+pub const DROP_MESSAGE_USAGE_DURATION: &str = "ALTER TABLE message_usage DROP COLUMN duration_ms;";
+
+pub const CREATE_API_ERRORS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS api_errors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    is_retry INTEGER NOT NULL DEFAULT 0,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_api_errors_occurred ON api_errors(occurred_at);
+"#;
+
+pub const DROP_API_ERRORS_TABLE: &str = "DROP TABLE IF EXISTS api_errors;";
+
+/// 上下文压缩事件，同一会话同一时间的压缩只记录一次
+pub const CREATE_COMPACTION_EVENTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS compaction_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    trigger TEXT NOT NULL,
+    pre_tokens INTEGER,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_compaction_events_occurred ON compaction_events(occurred_at);
+"#;
+
+pub const DROP_COMPACTION_EVENTS_TABLE: &str = "DROP TABLE IF EXISTS compaction_events;";
+
+/// 用户中断记录，同一会话同一时间的中断只记录一次
+pub const CREATE_INTERRUPTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS interrupts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_interrupts_occurred ON interrupts(occurred_at);
+"#;
+
+pub const DROP_INTERRUPTS_TABLE: &str = "DROP TABLE IF EXISTS interrupts;";
+
+/// 工具调用记录，以 tool_use 块 ID 去重
+pub const CREATE_TOOL_CALLS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS tool_calls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    tool_use_id TEXT NOT NULL UNIQUE,
+    tool_name TEXT NOT NULL,
+    called_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_tool_calls_called ON tool_calls(called_at);
 "#;
 
-pub const CREATE_PROVIDER_SWITCH_LOGS_TABLE: &str = r#"
+pub const DROP_TOOL_CALLS_TABLE: &str = "DROP TABLE IF EXISTS tool_calls;";
+
+pub const CREATE_TAGS_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS session_tags (
+    tag_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    PRIMARY KEY (tag_id, session_id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+);
+
+CREATE TABLE IF NOT EXISTS date_tags (
+    tag_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    PRIMARY KEY (tag_id, date),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+);
+"#;
+
+pub const DROP_TAGS_TABLES: &str = r#"
+DROP TABLE IF EXISTS date_tags;
+DROP TABLE IF EXISTS session_tags;
+DROP TABLE IF EXISTS tags;
+"#;
+
+pub const CREATE_DATE_NOTES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS date_notes (
+    date TEXT PRIMARY KEY,
+    note TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_DATE_NOTES_TABLE: &str = "DROP TABLE IF EXISTS date_notes;";
+
+pub const CREATE_OFFICIAL_USAGE_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS official_usage (
+    date TEXT PRIMARY KEY,
+    input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+    cost_usd REAL NOT NULL DEFAULT 0,
+    fetched_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_OFFICIAL_USAGE_TABLE: &str = "DROP TABLE IF EXISTS official_usage;";
+
+pub const ADD_PROVIDER_KIND: &str =
+    "ALTER TABLE providers ADD COLUMN kind TEXT NOT NULL DEFAULT 'api_key';";
+
+pub const DROP_PROVIDER_KIND: &str = "ALTER TABLE providers DROP COLUMN kind;";
+
+/// 新增 API 等价费用列：已有记录的等价费用取原费用，订阅供应商的实际费用清零
+pub const ADD_API_EQUIVALENT_COST: &str = r#"
+ALTER TABLE message_usage ADD COLUMN api_equivalent_cost_usd REAL NOT NULL DEFAULT 0;
+ALTER TABLE daily_stats ADD COLUMN total_api_equivalent_cost_usd REAL NOT NULL DEFAULT 0;
+
+UPDATE message_usage SET api_equivalent_cost_usd = cost_usd;
+UPDATE daily_stats SET total_api_equivalent_cost_usd = total_cost_usd;
+
+UPDATE message_usage SET cost_usd = 0
+WHERE provider_id IN (SELECT id FROM providers WHERE kind = 'subscription');
+UPDATE daily_stats SET total_cost_usd = 0
+WHERE provider_id IN (SELECT id FROM providers WHERE kind = 'subscription');
+UPDATE model_daily_stats SET total_cost_usd = 0
+WHERE provider_id IN (SELECT id FROM providers WHERE kind = 'subscription');
+"#;
+
+pub const DROP_API_EQUIVALENT_COST: &str = r#"
+ALTER TABLE daily_stats DROP COLUMN total_api_equivalent_cost_usd;
+ALTER TABLE message_usage DROP COLUMN api_equivalent_cost_usd;
+"#;
+
+/// 思考 Token 列：消息明细与按模型每日汇总
+pub const ADD_THINKING_TOKENS: &str = r#"
+ALTER TABLE message_usage ADD COLUMN thinking_tokens INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE model_daily_stats ADD COLUMN total_thinking_tokens INTEGER NOT NULL DEFAULT 0;
+"#;
+
+pub const DROP_THINKING_TOKENS: &str = r#"
+ALTER TABLE model_daily_stats DROP COLUMN total_thinking_tokens;
+ALTER TABLE message_usage DROP COLUMN thinking_tokens;
+"#;
+
+/// 根据 message_usage 重新汇总 model_daily_stats 的思考 Token
+pub const SYNC_MODEL_THINKING_TOKENS: &str = r#"
+UPDATE model_daily_stats
+SET total_thinking_tokens = (
+    SELECT COALESCE(SUM(m.thinking_tokens), 0)
+    FROM message_usage m
+    WHERE m.provider_id = model_daily_stats.provider_id
+      AND m.model = model_daily_stats.model
+      AND date(m.created_at, 'localtime') = model_daily_stats.date
+);
+"#;
+
+/// 服务层级列，历史消息按标准层级处理
+pub const ADD_SERVICE_TIER: &str = r#"
+ALTER TABLE message_usage ADD COLUMN service_tier TEXT NOT NULL DEFAULT 'standard';
+"#;
+
+pub const DROP_SERVICE_TIER: &str = "ALTER TABLE message_usage DROP COLUMN service_tier;";
+
+/// 费用重算审计记录，仅记录改变了已存储费用的重算
+pub const CREATE_COST_RECALCULATIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS cost_recalculations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pricing_version TEXT NOT NULL,
+    old_total_usd REAL NOT NULL,
+    new_total_usd REAL NOT NULL,
+    changed_messages INTEGER NOT NULL,
+    recalculated_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_COST_RECALCULATIONS_TABLE: &str = "DROP TABLE IF EXISTS cost_recalculations;";
+
+/// 回收站，payload 为被删除数据的 JSON 快照
+pub const CREATE_DELETED_ITEMS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS deleted_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    source_id INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    message_count INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    deleted_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_deleted_items_deleted ON deleted_items(deleted_at);
+"#;
+
+pub const DROP_DELETED_ITEMS_TABLE: &str = "DROP TABLE IF EXISTS deleted_items;";
+
+/// 重建引用供应商与标签的子表，外键改为 ON DELETE CASCADE，并清除已存在的孤立记录
+///
+/// SQLite 不支持修改已有外键，只能按"新建表 → 复制数据 → 删除旧表 → 重命名"重建，之后补回索引；
+/// 旧库缺少某张表时先按新定义补建空表，保证迁移可以执行
+pub const ADD_FOREIGN_KEY_CASCADE: &str = r#"
+CREATE TABLE IF NOT EXISTS message_usage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    input_tokens INTEGER DEFAULT 0,
+    output_tokens INTEGER DEFAULT 0,
+    cache_read_tokens INTEGER DEFAULT 0,
+    cache_creation_tokens INTEGER DEFAULT 0,
+    cost_usd REAL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    project TEXT,
+    duration_ms INTEGER,
+    api_equivalent_cost_usd REAL NOT NULL DEFAULT 0,
+    thinking_tokens INTEGER NOT NULL DEFAULT 0,
+    service_tier TEXT NOT NULL DEFAULT 'standard',
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE message_usage_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    input_tokens INTEGER DEFAULT 0,
+    output_tokens INTEGER DEFAULT 0,
+    cache_read_tokens INTEGER DEFAULT 0,
+    cache_creation_tokens INTEGER DEFAULT 0,
+    cost_usd REAL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    project TEXT,
+    duration_ms INTEGER,
+    api_equivalent_cost_usd REAL NOT NULL DEFAULT 0,
+    thinking_tokens INTEGER NOT NULL DEFAULT 0,
+    service_tier TEXT NOT NULL DEFAULT 'standard',
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO message_usage_new SELECT * FROM message_usage WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE message_usage;
+ALTER TABLE message_usage_new RENAME TO message_usage;
+CREATE INDEX IF NOT EXISTS idx_message_usage_provider ON message_usage(provider_id);
+CREATE INDEX IF NOT EXISTS idx_message_usage_created ON message_usage(created_at);
+CREATE INDEX IF NOT EXISTS idx_message_usage_session ON message_usage(session_id);
+CREATE INDEX IF NOT EXISTS idx_message_usage_model ON message_usage(model);
+CREATE INDEX IF NOT EXISTS idx_message_usage_project ON message_usage(project);
+CREATE INDEX IF NOT EXISTS idx_message_usage_provider_created ON message_usage(provider_id, created_at);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_message_usage_unique
+ON message_usage(provider_id, message_id, substr(created_at, 1, 10));
+CREATE INDEX IF NOT EXISTS idx_message_usage_message ON message_usage(message_id);
+CREATE TABLE IF NOT EXISTS daily_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    total_input_tokens INTEGER DEFAULT 0,
+    total_output_tokens INTEGER DEFAULT 0,
+    total_cache_read_tokens INTEGER DEFAULT 0,
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    total_cost_usd REAL DEFAULT 0,
+    session_count INTEGER DEFAULT 0,
+    message_count INTEGER DEFAULT 0,
+    total_api_equivalent_cost_usd REAL NOT NULL DEFAULT 0,
+    UNIQUE(provider_id, date),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE daily_stats_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    total_input_tokens INTEGER DEFAULT 0,
+    total_output_tokens INTEGER DEFAULT 0,
+    total_cache_read_tokens INTEGER DEFAULT 0,
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    total_cost_usd REAL DEFAULT 0,
+    session_count INTEGER DEFAULT 0,
+    message_count INTEGER DEFAULT 0,
+    total_api_equivalent_cost_usd REAL NOT NULL DEFAULT 0,
+    UNIQUE(provider_id, date),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO daily_stats_new SELECT * FROM daily_stats WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE daily_stats;
+ALTER TABLE daily_stats_new RENAME TO daily_stats;
+CREATE INDEX IF NOT EXISTS idx_daily_stats_date ON daily_stats(date);
+CREATE INDEX IF NOT EXISTS idx_daily_stats_provider ON daily_stats(provider_id);
+CREATE TABLE IF NOT EXISTS model_daily_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    model TEXT NOT NULL,
+    total_input_tokens INTEGER DEFAULT 0,
+    total_output_tokens INTEGER DEFAULT 0,
+    total_cache_read_tokens INTEGER DEFAULT 0,
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    total_cost_usd REAL DEFAULT 0,
+    message_count INTEGER DEFAULT 0,
+    total_thinking_tokens INTEGER NOT NULL DEFAULT 0,
+    UNIQUE(provider_id, date, model),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE model_daily_stats_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    model TEXT NOT NULL,
+    total_input_tokens INTEGER DEFAULT 0,
+    total_output_tokens INTEGER DEFAULT 0,
+    total_cache_read_tokens INTEGER DEFAULT 0,
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    total_cost_usd REAL DEFAULT 0,
+    message_count INTEGER DEFAULT 0,
+    total_thinking_tokens INTEGER NOT NULL DEFAULT 0,
+    UNIQUE(provider_id, date, model),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO model_daily_stats_new SELECT * FROM model_daily_stats WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE model_daily_stats;
+ALTER TABLE model_daily_stats_new RENAME TO model_daily_stats;
+CREATE INDEX IF NOT EXISTS idx_model_daily_stats_date ON model_daily_stats(date);
 CREATE TABLE IF NOT EXISTS provider_switch_logs (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     provider_id INTEGER NOT NULL,
     switched_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE provider_switch_logs_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    switched_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO provider_switch_logs_new SELECT * FROM provider_switch_logs WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE provider_switch_logs;
+ALTER TABLE provider_switch_logs_new RENAME TO provider_switch_logs;
+CREATE TABLE IF NOT EXISTS provider_plans (
+    provider_id INTEGER PRIMARY KEY,
+    plan_type TEXT NOT NULL,
+    monthly_fee_usd REAL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE provider_plans_new (
+    provider_id INTEGER PRIMARY KEY,
+    plan_type TEXT NOT NULL,
+    monthly_fee_usd REAL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO provider_plans_new SELECT * FROM provider_plans WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE provider_plans;
+ALTER TABLE provider_plans_new RENAME TO provider_plans;
+CREATE TABLE IF NOT EXISTS provider_quotas (
+    provider_id INTEGER PRIMARY KEY,
+    quota_url TEXT NOT NULL,
+    refresh_minutes INTEGER NOT NULL,
+    remaining_usd REAL,
+    used_usd REAL,
+    total_usd REAL,
+    fetched_at TEXT,
+    error TEXT,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE provider_quotas_new (
+    provider_id INTEGER PRIMARY KEY,
+    quota_url TEXT NOT NULL,
+    refresh_minutes INTEGER NOT NULL,
+    remaining_usd REAL,
+    used_usd REAL,
+    total_usd REAL,
+    fetched_at TEXT,
+    error TEXT,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO provider_quotas_new SELECT * FROM provider_quotas WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE provider_quotas;
+ALTER TABLE provider_quotas_new RENAME TO provider_quotas;
+CREATE TABLE IF NOT EXISTS rate_limit_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE rate_limit_events_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO rate_limit_events_new SELECT * FROM rate_limit_events WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE rate_limit_events;
+ALTER TABLE rate_limit_events_new RENAME TO rate_limit_events;
+CREATE INDEX IF NOT EXISTS idx_rate_limit_events_occurred ON rate_limit_events(occurred_at);
+CREATE TABLE IF NOT EXISTS api_errors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    is_retry INTEGER NOT NULL DEFAULT 0,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE api_errors_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    is_retry INTEGER NOT NULL DEFAULT 0,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO api_errors_new SELECT * FROM api_errors WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE api_errors;
+ALTER TABLE api_errors_new RENAME TO api_errors;
+CREATE INDEX IF NOT EXISTS idx_api_errors_occurred ON api_errors(occurred_at);
+CREATE TABLE IF NOT EXISTS compaction_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    trigger TEXT NOT NULL,
+    pre_tokens INTEGER,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE compaction_events_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    trigger TEXT NOT NULL,
+    pre_tokens INTEGER,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO compaction_events_new SELECT * FROM compaction_events WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE compaction_events;
+ALTER TABLE compaction_events_new RENAME TO compaction_events;
+CREATE INDEX IF NOT EXISTS idx_compaction_events_occurred ON compaction_events(occurred_at);
+CREATE TABLE IF NOT EXISTS interrupts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE interrupts_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO interrupts_new SELECT * FROM interrupts WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE interrupts;
+ALTER TABLE interrupts_new RENAME TO interrupts;
+CREATE INDEX IF NOT EXISTS idx_interrupts_occurred ON interrupts(occurred_at);
+CREATE TABLE IF NOT EXISTS tool_calls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    tool_use_id TEXT NOT NULL UNIQUE,
+    tool_name TEXT NOT NULL,
+    called_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+CREATE TABLE tool_calls_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    tool_use_id TEXT NOT NULL UNIQUE,
+    tool_name TEXT NOT NULL,
+    called_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO tool_calls_new SELECT * FROM tool_calls WHERE provider_id IN (SELECT id FROM providers);
+DROP TABLE tool_calls;
+ALTER TABLE tool_calls_new RENAME TO tool_calls;
+CREATE INDEX IF NOT EXISTS idx_tool_calls_called ON tool_calls(called_at);
+CREATE TABLE IF NOT EXISTS session_tags (
+    tag_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    PRIMARY KEY (tag_id, session_id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+CREATE TABLE session_tags_new (
+    tag_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    PRIMARY KEY (tag_id, session_id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+INSERT INTO session_tags_new SELECT * FROM session_tags WHERE tag_id IN (SELECT id FROM tags);
+DROP TABLE session_tags;
+ALTER TABLE session_tags_new RENAME TO session_tags;
+CREATE TABLE IF NOT EXISTS date_tags (
+    tag_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    PRIMARY KEY (tag_id, date),
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+CREATE TABLE date_tags_new (
+    tag_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    PRIMARY KEY (tag_id, date),
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+INSERT INTO date_tags_new SELECT * FROM date_tags WHERE tag_id IN (SELECT id FROM tags);
+DROP TABLE date_tags;
+ALTER TABLE date_tags_new RENAME TO date_tags;
+"#;
+
+/// 回滚为不带 ON DELETE 行为的外键定义（已清除的孤立记录不会恢复）
+pub const DROP_FOREIGN_KEY_CASCADE: &str = r#"
+CREATE TABLE message_usage_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    input_tokens INTEGER DEFAULT 0,
+    output_tokens INTEGER DEFAULT 0,
+    cache_read_tokens INTEGER DEFAULT 0,
+    cache_creation_tokens INTEGER DEFAULT 0,
+    cost_usd REAL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    project TEXT,
+    duration_ms INTEGER,
+    api_equivalent_cost_usd REAL NOT NULL DEFAULT 0,
+    thinking_tokens INTEGER NOT NULL DEFAULT 0,
+    service_tier TEXT NOT NULL DEFAULT 'standard',
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO message_usage_new SELECT * FROM message_usage;
+DROP TABLE message_usage;
+ALTER TABLE message_usage_new RENAME TO message_usage;
+CREATE INDEX IF NOT EXISTS idx_message_usage_provider ON message_usage(provider_id);
+CREATE INDEX IF NOT EXISTS idx_message_usage_created ON message_usage(created_at);
+CREATE INDEX IF NOT EXISTS idx_message_usage_session ON message_usage(session_id);
+CREATE INDEX IF NOT EXISTS idx_message_usage_model ON message_usage(model);
+CREATE INDEX IF NOT EXISTS idx_message_usage_project ON message_usage(project);
+CREATE INDEX IF NOT EXISTS idx_message_usage_provider_created ON message_usage(provider_id, created_at);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_message_usage_unique
+ON message_usage(provider_id, message_id, substr(created_at, 1, 10));
+CREATE INDEX IF NOT EXISTS idx_message_usage_message ON message_usage(message_id);
+CREATE TABLE daily_stats_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    total_input_tokens INTEGER DEFAULT 0,
+    total_output_tokens INTEGER DEFAULT 0,
+    total_cache_read_tokens INTEGER DEFAULT 0,
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    total_cost_usd REAL DEFAULT 0,
+    session_count INTEGER DEFAULT 0,
+    message_count INTEGER DEFAULT 0,
+    total_api_equivalent_cost_usd REAL NOT NULL DEFAULT 0,
+    UNIQUE(provider_id, date),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO daily_stats_new SELECT * FROM daily_stats;
+DROP TABLE daily_stats;
+ALTER TABLE daily_stats_new RENAME TO daily_stats;
+CREATE INDEX IF NOT EXISTS idx_daily_stats_date ON daily_stats(date);
+CREATE INDEX IF NOT EXISTS idx_daily_stats_provider ON daily_stats(provider_id);
+CREATE TABLE model_daily_stats_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    model TEXT NOT NULL,
+    total_input_tokens INTEGER DEFAULT 0,
+    total_output_tokens INTEGER DEFAULT 0,
+    total_cache_read_tokens INTEGER DEFAULT 0,
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    total_cost_usd REAL DEFAULT 0,
+    message_count INTEGER DEFAULT 0,
+    total_thinking_tokens INTEGER NOT NULL DEFAULT 0,
+    UNIQUE(provider_id, date, model),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO model_daily_stats_new SELECT * FROM model_daily_stats;
+DROP TABLE model_daily_stats;
+ALTER TABLE model_daily_stats_new RENAME TO model_daily_stats;
+CREATE INDEX IF NOT EXISTS idx_model_daily_stats_date ON model_daily_stats(date);
+CREATE TABLE provider_switch_logs_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    switched_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO provider_switch_logs_new SELECT * FROM provider_switch_logs;
+DROP TABLE provider_switch_logs;
+ALTER TABLE provider_switch_logs_new RENAME TO provider_switch_logs;
+CREATE TABLE provider_plans_new (
+    provider_id INTEGER PRIMARY KEY,
+    plan_type TEXT NOT NULL,
+    monthly_fee_usd REAL DEFAULT 0,
+    updated_at TEXT NOT NULL,
     FOREIGN KEY (provider_id) REFERENCES providers(id)
 );
+INSERT INTO provider_plans_new SELECT * FROM provider_plans;
+DROP TABLE provider_plans;
+ALTER TABLE provider_plans_new RENAME TO provider_plans;
+CREATE TABLE provider_quotas_new (
+    provider_id INTEGER PRIMARY KEY,
+    quota_url TEXT NOT NULL,
+    refresh_minutes INTEGER NOT NULL,
+    remaining_usd REAL,
+    used_usd REAL,
+    total_usd REAL,
+    fetched_at TEXT,
+    error TEXT,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO provider_quotas_new SELECT * FROM provider_quotas;
+DROP TABLE provider_quotas;
+ALTER TABLE provider_quotas_new RENAME TO provider_quotas;
+CREATE TABLE rate_limit_events_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO rate_limit_events_new SELECT * FROM rate_limit_events;
+DROP TABLE rate_limit_events;
+ALTER TABLE rate_limit_events_new RENAME TO rate_limit_events;
+CREATE INDEX IF NOT EXISTS idx_rate_limit_events_occurred ON rate_limit_events(occurred_at);
+CREATE TABLE api_errors_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status_code INTEGER,
+    message TEXT NOT NULL,
+    is_retry INTEGER NOT NULL DEFAULT 0,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(provider_id, session_id, occurred_at, kind),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO api_errors_new SELECT * FROM api_errors;
+DROP TABLE api_errors;
+ALTER TABLE api_errors_new RENAME TO api_errors;
+CREATE INDEX IF NOT EXISTS idx_api_errors_occurred ON api_errors(occurred_at);
+CREATE TABLE compaction_events_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    trigger TEXT NOT NULL,
+    pre_tokens INTEGER,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO compaction_events_new SELECT * FROM compaction_events;
+DROP TABLE compaction_events;
+ALTER TABLE compaction_events_new RENAME TO compaction_events;
+CREATE INDEX IF NOT EXISTS idx_compaction_events_occurred ON compaction_events(occurred_at);
+CREATE TABLE interrupts_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    UNIQUE(session_id, occurred_at),
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO interrupts_new SELECT * FROM interrupts;
+DROP TABLE interrupts;
+ALTER TABLE interrupts_new RENAME TO interrupts;
+CREATE INDEX IF NOT EXISTS idx_interrupts_occurred ON interrupts(occurred_at);
+CREATE TABLE tool_calls_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    tool_use_id TEXT NOT NULL UNIQUE,
+    tool_name TEXT NOT NULL,
+    called_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+INSERT INTO tool_calls_new SELECT * FROM tool_calls;
+DROP TABLE tool_calls;
+ALTER TABLE tool_calls_new RENAME TO tool_calls;
+CREATE INDEX IF NOT EXISTS idx_tool_calls_called ON tool_calls(called_at);
+CREATE TABLE session_tags_new (
+    tag_id INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    PRIMARY KEY (tag_id, session_id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+);
+INSERT INTO session_tags_new SELECT * FROM session_tags;
+DROP TABLE session_tags;
+ALTER TABLE session_tags_new RENAME TO session_tags;
+CREATE TABLE date_tags_new (
+    tag_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    PRIMARY KEY (tag_id, date),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+);
+INSERT INTO date_tags_new SELECT * FROM date_tags;
+DROP TABLE date_tags;
+ALTER TABLE date_tags_new RENAME TO date_tags;
+"#;
+
+/// 使用目标与每个周期的评估结果，删除目标时级联删除评估记录
+pub const CREATE_GOALS_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS goals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    direction TEXT NOT NULL,
+    target REAL NOT NULL,
+    period TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS goal_evaluations (
+    goal_id INTEGER NOT NULL,
+    period_start TEXT NOT NULL,
+    value REAL NOT NULL,
+    target REAL NOT NULL,
+    met INTEGER NOT NULL,
+    evaluated_at TEXT NOT NULL,
+    PRIMARY KEY (goal_id, period_start),
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE
+);
+"#;
+
+pub const DROP_GOALS_TABLES: &str = r#"
+DROP TABLE IF EXISTS goal_evaluations;
+DROP TABLE IF EXISTS goals;
+"#;
+
+pub const CREATE_SAVED_QUERIES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS saved_queries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    sql TEXT NOT NULL,
+    visualization TEXT NOT NULL DEFAULT 'table',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_SAVED_QUERIES_TABLE: &str = "DROP TABLE IF EXISTS saved_queries;";
+
+pub const CREATE_MODEL_ALIASES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS model_aliases (
+    alias TEXT PRIMARY KEY,
+    canonical TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+pub const DROP_MODEL_ALIASES_TABLE: &str = "DROP TABLE IF EXISTS model_aliases;";
+
+/// 供应商余额查询配置与最近一次查询结果，凭证保存在系统钥匙串
+pub const CREATE_PROVIDER_QUOTAS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS provider_quotas (
+    provider_id INTEGER PRIMARY KEY,
+    quota_url TEXT NOT NULL,
+    refresh_minutes INTEGER NOT NULL,
+    remaining_usd REAL,
+    used_usd REAL,
+    total_usd REAL,
+    fetched_at TEXT,
+    error TEXT,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (provider_id) REFERENCES providers(id)
+);
+"#;
+
+pub const DROP_PROVIDER_QUOTAS_TABLE: &str = "DROP TABLE IF EXISTS provider_quotas;";
+
+/// 告警规则与触发历史，历史记录保留规则名称，删除规则后仍可查看
+pub const CREATE_ALERT_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS alert_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    comparator TEXT NOT NULL,
+    threshold REAL NOT NULL,
+    time_window TEXT NOT NULL,
+    channel TEXT NOT NULL DEFAULT 'notification',
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS triggered_alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    rule_name TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    value REAL NOT NULL,
+    threshold REAL NOT NULL,
+    channel TEXT NOT NULL,
+    message TEXT NOT NULL,
+    triggered_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_triggered_alerts_rule ON triggered_alerts(rule_id, triggered_at);
 "#;
 
-pub const CREATE_INDEXES: &[&str] = &[
-    "CREATE INDEX IF NOT EXISTS idx_message_usage_provider ON message_usage(provider_id);",
-    "CREATE INDEX IF NOT EXISTS idx_message_usage_created ON message_usage(created_at);",
-    "CREATE INDEX IF NOT EXISTS idx_daily_stats_date ON daily_stats(date);",
-    "CREATE INDEX IF NOT EXISTS idx_daily_stats_provider ON daily_stats(provider_id);",
-];
+pub const DROP_ALERT_TABLES: &str = r#"
+DROP TABLE IF EXISTS triggered_alerts;
+DROP TABLE IF EXISTS alert_rules;
+"#;
+
+/// 告警状态列：规则的暂停截止时间与本次越线开始时间，告警的确认时间
+pub const ADD_ALERT_STATE: &str = r#"
+ALTER TABLE alert_rules ADD COLUMN snoozed_until TEXT;
+ALTER TABLE alert_rules ADD COLUMN breached_since TEXT;
+ALTER TABLE triggered_alerts ADD COLUMN acknowledged_at TEXT;
+"#;
+
+pub const DROP_ALERT_STATE: &str = r#"
+ALTER TABLE triggered_alerts DROP COLUMN acknowledged_at;
+ALTER TABLE alert_rules DROP COLUMN breached_since;
+ALTER TABLE alert_rules DROP COLUMN snoozed_until;
+"#;
+
+/// 通知历史，sent_at 为本地时间，按日期查询时直接截取前 10 位
+pub const CREATE_NOTIFICATION_HISTORY_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS notification_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    sent_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_history_sent_at ON notification_history(sent_at);
+"#;
+
+pub const DROP_NOTIFICATION_HISTORY_TABLE: &str = "DROP TABLE IF EXISTS notification_history;";
+
+/// 按隐私设置截短已有供应商的 API Key 前缀，未保存隐私设置时保留 8 个字符
+pub const REMASK_API_KEY_PREFIXES: &str = r#"
+WITH privacy(prefix_length) AS (
+    SELECT MIN(8, COALESCE(
+        (SELECT json_extract(value, '$.api_key_prefix_length') FROM app_settings WHERE key = 'privacy'),
+        8
+    ))
+)
+UPDATE providers
+SET api_key_prefix = substr(api_key_prefix, 1, (SELECT prefix_length FROM privacy))
+WHERE length(api_key_prefix) > (SELECT prefix_length FROM privacy);
+"#;
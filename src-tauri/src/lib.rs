@@ -4,7 +4,7 @@
 /// @date 2026-01-08
 use std::sync::Mutex;
 
-use tauri::Manager;
+use tauri::{Listener, Manager};
 
 pub mod commands;
 pub mod db;
@@ -29,6 +29,8 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_updater::Builder::new().build())
         // autostart 插件将在 Phase 7 启用
         // .plugin(tauri_plugin_autostart::init(
         //     tauri_plugin_autostart::MacosLauncher::LaunchAgent,
@@ -38,41 +40,460 @@ pub fn run() {
         // 应用初始化
         // ============================================
         .setup(|app| {
+            // 日志最先初始化，后续启动过程的输出才能写入日志文件
+            match services::logging::logs_dir(app.handle()) {
+                Ok(dir) => {
+                    if let Err(e) = services::logging::init_logging(&dir) {
+                        eprintln!("日志初始化失败: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("获取日志目录失败: {}", e),
+            }
+
             // 获取主窗口（用于后续操作）
             // 使用 if let 避免 panic，提高生产环境稳定性
             if let Some(_main_window) = app.get_webview_window("main") {
-                println!("Claude Token Monitor 启动成功！");
+                tracing::info!("Claude Token Monitor 启动成功！");
             } else {
-                eprintln!("警告: 无法获取主窗口，应用将继续运行");
+                tracing::warn!("无法获取主窗口，应用将继续运行");
             }
 
             let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-            let db_path = app_data_dir.join("claude-token-monitor.db");
+            // 演示模式使用独立的演示数据库，不读写真实数据；
+            // 否则使用启动档案的数据库，只读模式下改用其副本
+            let demo_mode = db::is_demo_mode();
+            let profiles = db::profile::ProfilesConfig::load(&app_data_dir);
+            let args: Vec<String> = std::env::args().collect();
+            let profile_name = db::profile::startup_profile(&profiles, &args);
+            let profile = profiles
+                .get(&profile_name)
+                .ok_or_else(|| format!("档案不存在: {}", profile_name))?;
+            let db_path = if demo_mode {
+                app_data_dir.join(db::DEMO_DB_FILE_NAME)
+            } else {
+                db::profile::profile_db_path(&app_data_dir, &profile_name)
+            };
+            let read_only = !demo_mode && services::read_only::read_only_requested(&profile, &args);
+            let db_path = if read_only {
+                services::read_only::prepare_read_only_copy(&app_data_dir, &profile_name, &db_path)
+                    .map_err(|e| e.to_string())?
+            } else {
+                db_path
+            };
             let repository = db::Repository::new(&db_path).map_err(|e| e.to_string())?;
-            println!("数据库已初始化: {}", db_path.display());
-            app.manage(repository);
+            tracing::info!("数据库已初始化 [{}]: {}", profile_name, db_path.display());
+            // 默认档案沿用旧版本的唯一数据库，接管旧版本未区分数据库保存的钥匙串凭证
+            if !demo_mode && !read_only && profile_name == models::DEFAULT_PROFILE_NAME {
+                if let Err(e) = services::trash::adopt_legacy_credentials(&repository) {
+                    tracing::error!("迁移旧版本钥匙串凭证失败: {}", e);
+                }
+            }
+            // 旧版本明文保存在数据库中的 Webhook 地址迁移到钥匙串；只读副本不迁移
+            if !read_only {
+                if let Err(e) = services::webhook::migrate_legacy_urls(&repository) {
+                    tracing::error!("迁移 Webhook 地址到钥匙串失败: {}", e);
+                }
+            }
+            app.manage(repository.clone());
+            app.manage(services::profile::ActiveProfile::new(profile_name));
+            app.manage(services::read_only::ReadOnlyMode::new(read_only));
+            // 启用应用锁时以锁定状态启动，解锁前拒绝数据命令
+            let app_lock = match repository.get_setting::<models::AppLockSettings>() {
+                Ok(settings) => settings,
+                Err(e) => {
+                    tracing::error!("读取应用锁设置失败: {}", e);
+                    models::AppLockSettings::default()
+                }
+            };
+            app.manage(services::app_lock::AppLock::new(
+                &app_lock,
+                std::time::Instant::now(),
+            ));
+
+            // 启动时彻底清除超过保留期的回收站条目；只读模式不清除（会同时删除钥匙串中的凭据）
+            if read_only {
+                tracing::info!("只读模式：修改类命令将被拒绝");
+            } else if let Err(e) =
+                services::trash::purge_expired_items(&repository, chrono::Utc::now())
+            {
+                tracing::error!("清除过期回收站条目失败: {}", e);
+            }
 
-            let mut watcher = services::file_watcher::FileWatcher::new(app.handle().clone())
-                .map_err(|e| e.to_string())?;
-            watcher.start().map_err(|e| e.to_string())?;
+            // 本地 REST API 按设置启动，启动失败不影响主程序
+            let mut api_server = None;
+            match repository.get_setting::<models::ApiServerSettings>() {
+                Ok(settings) => {
+                    if let Err(e) = services::api_server::restart_api_server(
+                        &mut api_server,
+                        &repository,
+                        &settings,
+                    ) {
+                        tracing::error!("REST API 启动失败: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("读取 REST API 设置失败: {}", e),
+            }
+            app.manage(Mutex::new(api_server));
+
+            // 应用事件统一转发到 WebSocket 事件流，服务未启用时广播器没有订阅者
+            let broadcaster = services::event_stream::EventBroadcaster::new();
+            for event_name in services::event_stream::FORWARDED_EVENTS {
+                let broadcaster = broadcaster.clone();
+                app.listen_any(*event_name, move |event| {
+                    broadcaster.publish(event_name, event.payload());
+                });
+            }
+            let mut event_stream = None;
+            match repository.get_setting::<models::EventStreamSettings>() {
+                Ok(settings) => {
+                    if let Err(e) = services::event_stream::restart_event_stream(
+                        &mut event_stream,
+                        &broadcaster,
+                        &settings,
+                    ) {
+                        tracing::error!("WebSocket 事件流启动失败: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("读取 WebSocket 事件流设置失败: {}", e),
+            }
+            app.manage(broadcaster);
+            app.manage(Mutex::new(event_stream));
+
+            app.manage(services::health::WatcherHealth::new());
+            app.manage(services::monitor_errors::MonitorErrorLog::new());
+            app.manage(services::live_stats::LiveStats::new());
+            app.manage(services::block_warning::BlockWarningState::new());
+            app.manage(services::outliers::OutlierState::new());
+            app.manage(services::shutdown::IngestTracker::new());
+            // 全部采集写库与汇总由唯一的工作线程按序执行
+            let ingest_handle = app.handle().clone();
+            let ingest_worker =
+                services::ingest_worker::IngestWorker::spawn(move |generation, jobs| {
+                    services::file_watcher::process_ingest_batch(&ingest_handle, generation, jobs)
+                })?;
+            app.manage(ingest_worker);
+            let watch_dirs = services::profile::watch_dirs(&profile).map_err(|e| e.to_string())?;
+            let mut watcher =
+                services::file_watcher::FileWatcher::with_dirs(app.handle().clone(), watch_dirs)
+                    .map_err(|e| e.to_string())?;
+            if demo_mode {
+                tracing::info!("演示模式：不监控 Claude 日志");
+            } else {
+                watcher.start().map_err(|e| e.to_string())?;
+            }
             app.manage(Mutex::new(watcher));
 
+            services::report_scheduler::start_report_scheduler(app.handle().clone());
+            services::goal_scheduler::start_goal_evaluator(app.handle().clone());
+            services::updater::start_update_checker(app.handle().clone());
+            services::live_rate::start_live_rate_emitter(app.handle().clone());
+            services::app_lock::start_auto_lock_checker(app.handle().clone());
+            services::session_tracker::start_session_tracker(app.handle().clone());
+            services::sync_scheduler::start_sync_scheduler(app.handle().clone());
+            services::admin_api_poller::start_admin_api_poller(app.handle().clone());
+            services::balance_poller::start_balance_poller(app.handle().clone());
+            services::metrics_scheduler::start_metrics_exporter(app.handle().clone());
+            services::metrics_scheduler::start_otlp_exporter(app.handle().clone());
+
+            // 托盘图标常驻；菜单栏模式下主窗口从托盘弹出
+            app.manage(services::tray::MenuBarMode::default());
+            services::tray::setup_tray(app.handle())?;
+            services::tray::refresh_tray_icon(app.handle());
+            services::taskbar::update_taskbar_progress(app.handle());
+            services::dock_badge::update_dock_badge(app.handle());
+            let tray_handle = app.handle().clone();
+            app.listen_any("stats-updated", move |_| {
+                services::tray::refresh_tray_icon(&tray_handle);
+                services::dock_badge::update_dock_badge(&tray_handle);
+            });
+            match repository.get_setting::<models::MenuBarSettings>() {
+                Ok(settings) if settings.menu_bar_only => {
+                    services::tray::apply_menu_bar_mode(app.handle(), true)?;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("读取菜单栏模式设置失败: {}", e),
+            }
+
+            // 悬浮窗快捷键注册失败（如被其他应用占用）不影响主程序
+            match repository.get_setting::<models::OverlaySettings>() {
+                Ok(settings) => {
+                    if let Err(e) =
+                        services::overlay::register_overlay_shortcut(app.handle(), &settings)
+                    {
+                        tracing::error!("悬浮窗快捷键注册失败: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("读取悬浮窗设置失败: {}", e),
+            }
+
             Ok(())
         })
+        .on_window_event(services::tray::handle_window_event)
         // ============================================
         // 命令注册
         // ============================================
-        .invoke_handler(tauri::generate_handler![
-            greet,
-            commands::stats::get_current_stats,
-            commands::stats::get_today_provider_stats,
-            commands::stats::get_today_stats,
-            commands::stats::get_daily_activities,
-            commands::provider::get_providers,
-            commands::provider::update_provider_name,
-            commands::provider::add_provider,
-            commands::provider::delete_provider,
-        ])
-        .run(tauri::generate_context!())
-        .expect("Error while running Tauri application");
+        // 应用锁定时的数据命令与只读模式下的修改类命令在进入处理器前即被拒绝
+        .invoke_handler(services::command_guard::guard_invoke_handler(
+            tauri::generate_handler![
+                greet,
+                commands::stats::get_current_stats,
+                commands::stats::get_today_provider_stats,
+                commands::stats::get_today_stats,
+                commands::stats::get_daily_activities,
+                commands::stats::get_range_stats,
+                commands::stats::get_year_summary,
+                commands::stats::get_burn_rate,
+                commands::stats::get_cost_anomalies,
+                commands::stats::get_streaks,
+                commands::stats::get_usage_heatmap,
+                commands::stats::get_model_trends,
+                commands::stats::get_rolling_average,
+                commands::stats::get_message_distribution,
+                commands::stats::get_session_distribution,
+                commands::stats::get_top_sessions,
+                commands::stats::get_active_sessions,
+                commands::stats::get_session_context_usage,
+                commands::stats::get_session_detail,
+                commands::stats::simulate_cost,
+                commands::stats::get_insights,
+                commands::stats::search_messages,
+                commands::stats::get_usage_block,
+                commands::stats::get_quick_stats,
+                commands::stats::get_rate_limit_stats,
+                commands::stats::get_api_error_stats,
+                commands::stats::get_compaction_stats,
+                commands::stats::get_tool_call_stats,
+                commands::stats::get_interrupt_stats,
+                commands::stats::get_service_tier_usage,
+                commands::stats::compare_providers,
+                commands::stats::get_latency_stats,
+                commands::stats::get_live_rate,
+                commands::note::set_date_note,
+                commands::note::get_date_notes,
+                commands::tag::add_tag,
+                commands::tag::remove_tag,
+                commands::tag::get_stats_by_tag,
+                commands::privacy::get_privacy_settings,
+                commands::privacy::set_privacy_settings,
+                commands::cache_hit_rate::get_cache_hit_rate_settings,
+                commands::cache_hit_rate::set_cache_hit_rate_settings,
+                commands::provider::get_providers,
+                commands::provider::update_provider_name,
+                commands::provider::test_provider,
+                commands::balance::get_provider_balances,
+                commands::balance::get_provider_balance,
+                commands::balance::set_provider_quota,
+                commands::provider::add_provider,
+                commands::provider::delete_provider,
+                commands::provider::delete_provider_reassigning,
+                commands::trash::get_deleted_items,
+                commands::trash::restore_deleted,
+                commands::trash::get_trash_settings,
+                commands::trash::set_trash_settings,
+                commands::database::get_database_location,
+                commands::database::move_database,
+                commands::profile::get_profiles,
+                commands::profile::save_profile,
+                commands::profile::delete_profile,
+                commands::profile::switch_profile,
+                commands::profile::get_read_only_mode,
+                commands::app_lock::get_app_lock_status,
+                commands::app_lock::set_app_lock_settings,
+                commands::app_lock::unlock_app,
+                commands::app_lock::lock_app,
+                commands::app_lock::report_activity,
+                commands::provider::has_provider_api_key,
+                commands::plan::get_provider_plans,
+                commands::plan::set_provider_plan,
+                commands::plan::get_plan_value,
+                commands::webhook::get_webhook_targets,
+                commands::webhook::create_webhook_target,
+                commands::webhook::update_webhook_target,
+                commands::webhook::delete_webhook_target,
+                commands::webhook::get_webhook_deliveries,
+                commands::webhook::test_webhook_target,
+                commands::plan::get_plan_limit_settings,
+                commands::plan::set_plan_limit_settings,
+                commands::plan::get_plan_limit_status,
+                commands::block_warning::get_block_warning_settings,
+                commands::block_warning::set_block_warning_settings,
+                commands::block_warning::get_block_capacity,
+                commands::outlier::get_outliers,
+                commands::outlier::get_outlier_settings,
+                commands::outlier::set_outlier_settings,
+                commands::budget::get_budget_settings,
+                commands::budget::set_budget_settings,
+                commands::budget::get_budget_progress,
+                commands::dock_badge::get_dock_badge_settings,
+                commands::dock_badge::set_dock_badge_settings,
+                commands::alert::get_alert_rules,
+                commands::alert::create_alert_rule,
+                commands::alert::update_alert_rule,
+                commands::alert::delete_alert_rule,
+                commands::alert::get_triggered_alerts,
+                commands::alert::acknowledge_alert,
+                commands::alert::snooze_rule,
+                commands::goal::get_goals,
+                commands::goal::create_goal,
+                commands::goal::update_goal,
+                commands::goal::delete_goal,
+                commands::goal::get_goal_progress,
+                commands::notification::get_notification_history,
+                commands::report::generate_report,
+                commands::report::get_daily_summary_text,
+                commands::report::export_cost_allocation,
+                commands::report::export_parquet,
+                commands::saved_query::get_saved_queries,
+                commands::saved_query::create_saved_query,
+                commands::saved_query::update_saved_query,
+                commands::saved_query::delete_saved_query,
+                commands::saved_query::run_saved_query,
+                commands::saved_query::run_readonly_query,
+                commands::model_alias::get_model_aliases,
+                commands::model_alias::set_model_alias,
+                commands::model_alias::delete_model_alias,
+                commands::pricing::recalculate_costs,
+                commands::pricing::get_recalculation_history,
+                commands::snapshot::export_snapshot,
+                commands::snapshot::import_snapshot,
+                commands::csv_import::import_csv,
+                commands::demo::generate_demo_data,
+                commands::benchmark::benchmark_parser,
+                commands::sync::get_sync_settings,
+                commands::sync::set_sync_settings,
+                commands::sync::set_sync_secret,
+                commands::sync::get_sync_secret_status,
+                commands::sync::sync_now,
+                commands::metrics_export::get_metrics_export_settings,
+                commands::metrics_export::set_metrics_export_settings,
+                commands::metrics_export::get_otlp_export_settings,
+                commands::metrics_export::set_otlp_export_settings,
+                commands::admin_api::get_admin_api_settings,
+                commands::admin_api::set_admin_api_settings,
+                commands::admin_api::set_admin_api_key,
+                commands::admin_api::has_admin_api_key,
+                commands::admin_api::get_official_usage,
+                commands::admin_api::get_usage_drift_report,
+                commands::admin_api::poll_official_usage_now,
+                commands::report::get_report_schedule,
+                commands::report::set_report_schedule,
+                commands::report::list_reports,
+                commands::api_server::get_api_server_settings,
+                commands::api_server::set_api_server_settings,
+                commands::event_stream::get_event_stream_settings,
+                commands::event_stream::set_event_stream_settings,
+                commands::health::get_health,
+                commands::health::get_recent_errors,
+                commands::health::get_schema_version,
+                commands::health::get_app_info,
+                commands::integrity::verify_data_integrity,
+                commands::integrity::rebuild_daily_stats,
+                commands::integrity::audit_duplicates,
+                commands::integrity::cleanup_duplicates,
+                commands::dedupe::get_dedupe_settings,
+                commands::dedupe::set_dedupe_settings,
+                commands::logs::get_recent_logs,
+                commands::menu_bar::get_menu_bar_settings,
+                commands::menu_bar::set_menu_bar_settings,
+                commands::menu_bar::get_tray_icon_settings,
+                commands::menu_bar::set_tray_icon_settings,
+                commands::onboarding::get_onboarding_state,
+                commands::onboarding::complete_onboarding,
+                commands::overlay::get_overlay_settings,
+                commands::overlay::set_overlay_settings,
+                commands::overlay::toggle_overlay_window,
+                commands::permissions::check_permissions,
+                commands::updater::get_update_settings,
+                commands::updater::set_update_settings,
+                commands::updater::check_for_updates,
+                commands::updater::install_update,
+            ],
+        ))
+        .build(tauri::generate_context!())
+        .expect("Error while building Tauri application")
+        .run(|app, event| {
+            // 退出前停止监控并等待进行中的写入，避免采集工作线程在插入中途被终止
+            if let tauri::RunEvent::Exit = event {
+                services::shutdown::shutdown(app);
+            }
+        });
+}
+
+/// 以 MCP stdio 服务模式运行（`--mcp`）
+///
+/// 不启动窗口与文件监控，直接读取桌面应用的数据库；返回进程退出码
+pub fn run_mcp_server() -> i32 {
+    let repository = match open_default_repository() {
+        Some(repository) => repository,
+        None => return 1,
+    };
+
+    eprintln!("MCP 服务已启动");
+    match services::mcp_server::run_stdio(&repository) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("MCP 服务异常退出: {}", e);
+            1
+        }
+    }
+}
+
+/// 以 JSON-RPC 模式运行：通过标准输入输出提供仓储查询
+///
+/// # 返回
+/// 进程退出码
+pub fn run_rpc_server() -> i32 {
+    let repository = match open_default_repository() {
+        Some(repository) => repository,
+        None => return 1,
+    };
+
+    eprintln!("JSON-RPC 服务已启动");
+    match services::rpc_server::run_stdio(&repository) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("JSON-RPC 服务异常退出: {}", e);
+            1
+        }
+    }
+}
+
+/// 以 statusline 模式运行：输出单行使用量摘要供 Claude Code 的 statusLine 使用
+///
+/// # 返回
+/// 进程退出码
+pub fn run_statusline() -> i32 {
+    let repository = match open_default_repository() {
+        Some(repository) => repository,
+        None => return 1,
+    };
+
+    match services::statusline::build_statusline(&repository) {
+        Ok(line) => {
+            println!("{}", line);
+            0
+        }
+        Err(e) => {
+            eprintln!("状态行生成失败: {}", e);
+            1
+        }
+    }
+}
+
+/// 不经过 Tauri 运行时打开应用数据库，失败时输出原因到标准错误
+fn open_default_repository() -> Option<db::Repository> {
+    let db_path = match db::default_db_path() {
+        Some(path) => path,
+        None => {
+            eprintln!("无法确定数据目录");
+            return None;
+        }
+    };
+    match db::Repository::new(&db_path) {
+        Ok(repository) => Some(repository),
+        Err(e) => {
+            eprintln!("数据库打开失败 [{}]: {}", db_path.display(), e);
+            None
+        }
+    }
 }
@@ -7,5 +7,20 @@
 //! @date 2026-01-08
 
 fn main() {
+    // --mcp：作为 MCP stdio 服务运行，供 Claude 查询使用量
+    if std::env::args().any(|arg| arg == "--mcp") {
+        std::process::exit(claude_token_monitor_lib::run_mcp_server());
+    }
+
+    // --rpc：作为行分隔 JSON-RPC stdio 服务运行，供编辑器与脚本查询仓储
+    if std::env::args().any(|arg| arg == "--rpc") {
+        std::process::exit(claude_token_monitor_lib::run_rpc_server());
+    }
+
+    // statusline：输出单行摘要，供 Claude Code 的 statusLine 配置调用
+    if std::env::args().nth(1).as_deref() == Some("statusline") {
+        std::process::exit(claude_token_monitor_lib::run_statusline());
+    }
+
     claude_token_monitor_lib::run()
 }
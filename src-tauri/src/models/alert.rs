@@ -0,0 +1,312 @@
+//! @file alert.rs
+//! @description 告警规则数据模型，包含规则定义与触发历史
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 告警指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// 费用（美元）
+    CostUsd,
+
+    /// Token 总数（输入 + 输出 + 缓存读取 + 缓存创建）
+    TotalTokens,
+
+    /// 消息数
+    MessageCount,
+
+    /// 会话数
+    SessionCount,
+}
+
+impl AlertMetric {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertMetric::CostUsd => "cost_usd",
+            AlertMetric::TotalTokens => "total_tokens",
+            AlertMetric::MessageCount => "message_count",
+            AlertMetric::SessionCount => "session_count",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 CostUsd 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "total_tokens" => AlertMetric::TotalTokens,
+            "message_count" => AlertMetric::MessageCount,
+            "session_count" => AlertMetric::SessionCount,
+            _ => AlertMetric::CostUsd,
+        }
+    }
+
+    /// 从窗口用量中取出该指标的值
+    pub fn value(&self, totals: &UsageWindowTotals) -> f64 {
+        match self {
+            AlertMetric::CostUsd => totals.cost_usd,
+            AlertMetric::TotalTokens => totals.total_tokens as f64,
+            AlertMetric::MessageCount => totals.message_count as f64,
+            AlertMetric::SessionCount => totals.session_count as f64,
+        }
+    }
+}
+
+/// 比较方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertComparator {
+    /// 大于
+    Gt,
+
+    /// 大于等于
+    Gte,
+
+    /// 小于
+    Lt,
+
+    /// 小于等于
+    Lte,
+}
+
+impl AlertComparator {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertComparator::Gt => "gt",
+            AlertComparator::Gte => "gte",
+            AlertComparator::Lt => "lt",
+            AlertComparator::Lte => "lte",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Gt 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "gte" => AlertComparator::Gte,
+            "lt" => AlertComparator::Lt,
+            "lte" => AlertComparator::Lte,
+            _ => AlertComparator::Gt,
+        }
+    }
+
+    /// 比较指标值与阈值
+    pub fn matches(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertComparator::Gt => value > threshold,
+            AlertComparator::Gte => value >= threshold,
+            AlertComparator::Lt => value < threshold,
+            AlertComparator::Lte => value <= threshold,
+        }
+    }
+
+    /// 展示用符号
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            AlertComparator::Gt => ">",
+            AlertComparator::Gte => "≥",
+            AlertComparator::Lt => "<",
+            AlertComparator::Lte => "≤",
+        }
+    }
+}
+
+/// 统计窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertWindow {
+    /// 最近 60 分钟
+    Hour,
+
+    /// 今天（本地日期）
+    Day,
+
+    /// 最近 7 天（含今天）
+    Week,
+
+    /// 本月（本地日期）
+    Month,
+}
+
+impl AlertWindow {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertWindow::Hour => "hour",
+            AlertWindow::Day => "day",
+            AlertWindow::Week => "week",
+            AlertWindow::Month => "month",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Day 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "hour" => AlertWindow::Hour,
+            "week" => AlertWindow::Week,
+            "month" => AlertWindow::Month,
+            _ => AlertWindow::Day,
+        }
+    }
+}
+
+/// 通知渠道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertChannel {
+    /// 系统通知 + 应用内提示（默认）
+    #[default]
+    Notification,
+
+    /// 仅应用内提示
+    InApp,
+}
+
+impl AlertChannel {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertChannel::Notification => "notification",
+            AlertChannel::InApp => "in_app",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Notification 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "in_app" => AlertChannel::InApp,
+            _ => AlertChannel::Notification,
+        }
+    }
+}
+
+/// 新建或更新告警规则的参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRuleInput {
+    /// 规则名称
+    pub name: String,
+
+    pub metric: AlertMetric,
+
+    pub comparator: AlertComparator,
+
+    /// 阈值，单位与指标一致
+    pub threshold: f64,
+
+    pub window: AlertWindow,
+
+    #[serde(default)]
+    pub channel: AlertChannel,
+
+    /// 是否启用，默认启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 告警规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// 规则 ID
+    pub id: i64,
+
+    pub name: String,
+
+    pub metric: AlertMetric,
+
+    pub comparator: AlertComparator,
+
+    pub threshold: f64,
+
+    pub window: AlertWindow,
+
+    pub channel: AlertChannel,
+
+    pub enabled: bool,
+
+    /// 暂停通知截止时间（ISO 8601 格式），到期前不再触发
+    pub snoozed_until: Option<String>,
+
+    /// 本次越线开始时间（ISO 8601 格式），上次评估未越线时为 None
+    pub breached_since: Option<String>,
+
+    /// 创建时间（ISO 8601 格式）
+    pub created_at: String,
+
+    /// 更新时间（ISO 8601 格式）
+    pub updated_at: String,
+}
+
+/// 已触发的告警记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggeredAlert {
+    /// 记录 ID
+    pub id: i64,
+
+    /// 触发的规则 ID（规则删除后仍保留历史）
+    pub rule_id: i64,
+
+    /// 触发时的规则名称
+    pub rule_name: String,
+
+    pub metric: AlertMetric,
+
+    /// 触发时的指标值
+    pub value: f64,
+
+    /// 触发时的阈值
+    pub threshold: f64,
+
+    pub channel: AlertChannel,
+
+    /// 通知文本
+    pub message: String,
+
+    /// 触发时间（ISO 8601 格式）
+    pub triggered_at: String,
+
+    /// 确认时间（ISO 8601 格式），未确认的告警在告警面板中保持活跃
+    pub acknowledged_at: Option<String>,
+}
+
+/// 时间窗口内的用量汇总（所有供应商）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageWindowTotals {
+    pub cost_usd: f64,
+    pub total_tokens: i64,
+    pub message_count: i64,
+    pub session_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_enums_round_trip() {
+        for metric in [
+            AlertMetric::CostUsd,
+            AlertMetric::TotalTokens,
+            AlertMetric::MessageCount,
+            AlertMetric::SessionCount,
+        ] {
+            assert_eq!(AlertMetric::from_db(metric.as_str()), metric);
+        }
+        for window in [
+            AlertWindow::Hour,
+            AlertWindow::Day,
+            AlertWindow::Week,
+            AlertWindow::Month,
+        ] {
+            assert_eq!(AlertWindow::from_db(window.as_str()), window);
+        }
+        assert_eq!(AlertComparator::from_db("lte"), AlertComparator::Lte);
+        assert_eq!(AlertChannel::from_db("in_app"), AlertChannel::InApp);
+        assert!(AlertComparator::Gte.matches(5.0, 5.0));
+        assert!(!AlertComparator::Gt.matches(5.0, 5.0));
+    }
+}
@@ -0,0 +1,76 @@
+//! @file allocation.rs
+//! @description 费用分摊导出数据模型，按项目或标签拆分日期范围内的费用
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 费用分摊的分组维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AllocationGroupBy {
+    /// 按项目目录分组
+    Project,
+
+    /// 按会话 / 日期标签分组
+    Tag,
+}
+
+/// 导出文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// 单个项目或标签的费用分摊
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostAllocationRow {
+    /// 项目名或标签名，无项目信息的消息为 None
+    pub group: Option<String>,
+
+    /// 会话数
+    pub session_count: i64,
+
+    /// 消息数
+    pub message_count: i64,
+
+    /// 输入 Token 数
+    pub input_tokens: i64,
+
+    /// 输出 Token 数
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token 数
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 数
+    pub cache_creation_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 占日期范围内总费用的比例（0.0 - 1.0）
+    ///
+    /// 按标签分组时同一条消息可能属于多个标签，各行比例之和可能超过 1
+    pub cost_share: f64,
+}
+
+/// 费用分摊导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostAllocationExport {
+    /// 导出文件路径
+    pub path: String,
+
+    /// 分组维度
+    pub group_by: AllocationGroupBy,
+
+    /// 文件格式
+    pub format: ExportFormat,
+
+    /// 导出的行数
+    pub row_count: usize,
+
+    /// 日期范围内的总费用（美元）
+    pub total_cost_usd: f64,
+}
@@ -0,0 +1,35 @@
+//! @file anomaly.rs
+//! @description 费用异常数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 费用异常记录
+///
+/// 当日费用显著高于近期基线（均值 + k·标准差）时生成，每天最多一条
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostAnomaly {
+    /// 数据库主键 ID
+    pub id: i64,
+
+    /// 异常日期（YYYY-MM-DD 格式）
+    pub date: String,
+
+    /// 当日费用（美元）
+    pub cost_usd: f64,
+
+    /// 基线窗口日均费用（美元）
+    pub baseline_mean_usd: f64,
+
+    /// 基线窗口费用标准差（美元）
+    pub baseline_stddev_usd: f64,
+
+    /// 基线窗口内同一星期几的日均费用（美元），无同星期数据时等于 baseline_mean_usd
+    pub weekday_mean_usd: f64,
+
+    /// 当日费用相对同星期日均费用的倍数
+    pub ratio: f64,
+
+    /// 检测时间（ISO 8601 格式）
+    pub detected_at: String,
+}
@@ -0,0 +1,124 @@
+//! @file api_error.rs
+//! @description API 错误事件与供应商错误率统计数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::{RateLimitEvent, RateLimitKind};
+
+/// API 错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorKind {
+    /// 触发速率限制（429）
+    RateLimited,
+
+    /// 服务过载（529）
+    Overloaded,
+
+    /// 请求超时
+    Timeout,
+
+    /// 其他服务端错误（5xx）
+    Server,
+
+    /// 其他客户端错误（4xx，如鉴权失败）
+    Client,
+
+    /// 无法归类的错误
+    Other,
+}
+
+impl ApiErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiErrorKind::RateLimited => "rate_limited",
+            ApiErrorKind::Overloaded => "overloaded",
+            ApiErrorKind::Timeout => "timeout",
+            ApiErrorKind::Server => "server",
+            ApiErrorKind::Client => "client",
+            ApiErrorKind::Other => "other",
+        }
+    }
+
+    /// 对应的限流事件类型，非限流 / 过载错误返回 None
+    pub fn rate_limit_kind(&self) -> Option<RateLimitKind> {
+        match self {
+            ApiErrorKind::RateLimited => Some(RateLimitKind::RateLimited),
+            ApiErrorKind::Overloaded => Some(RateLimitKind::Overloaded),
+            _ => None,
+        }
+    }
+}
+
+/// JSONL 中解析出的单次 API 错误
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiErrorEvent {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 错误类别
+    pub kind: ApiErrorKind,
+
+    /// HTTP 状态码，日志中未给出时为 None
+    pub status_code: Option<i64>,
+
+    /// 错误信息
+    pub message: String,
+
+    /// 是否为自动重试过程中的错误
+    pub is_retry: bool,
+
+    /// 发生时间（ISO 8601 格式）
+    pub occurred_at: String,
+}
+
+impl ApiErrorEvent {
+    /// 限流 / 过载错误转换为限流事件，其他错误返回 None
+    pub fn rate_limit_event(&self) -> Option<RateLimitEvent> {
+        self.kind.rate_limit_kind().map(|kind| RateLimitEvent {
+            session_id: self.session_id.clone(),
+            kind,
+            status_code: self.status_code,
+            message: self.message.clone(),
+            occurred_at: self.occurred_at.clone(),
+        })
+    }
+}
+
+/// 单个供应商的 API 错误统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiErrorStats {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 供应商显示名称
+    pub display_name: Option<String>,
+
+    /// 成功请求数（有用量记录的消息数）
+    pub request_count: i64,
+
+    /// 错误总数
+    pub error_count: i64,
+
+    /// 错误率：错误数 / (成功请求数 + 错误数)
+    pub error_rate: f64,
+
+    /// 自动重试过程中的错误数
+    pub retry_count: i64,
+
+    /// 速率限制次数
+    pub rate_limited_count: i64,
+
+    /// 服务过载次数
+    pub overloaded_count: i64,
+
+    /// 超时次数
+    pub timeout_count: i64,
+
+    /// 其他服务端错误次数
+    pub server_error_count: i64,
+
+    /// 其他客户端错误次数
+    pub client_error_count: i64,
+}
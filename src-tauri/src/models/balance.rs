@@ -0,0 +1,49 @@
+//! @file balance.rs
+//! @description 中转站余额查询相关数据模型，包含供应商余额查询配置与查询结果
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 默认余额刷新间隔（分钟）
+pub const DEFAULT_BALANCE_REFRESH_MINUTES: u32 = 30;
+
+/// 供应商余额查询配置与最近一次查询结果
+///
+/// 凭证保存在系统钥匙串，不随配置返回
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderBalance {
+    /// 关联的供应商 ID
+    pub provider_id: i64,
+
+    /// 余额查询地址（如 one-api 的 /api/user/self）
+    pub quota_url: String,
+
+    /// 定时刷新间隔（分钟）
+    pub refresh_minutes: u32,
+
+    /// 剩余额度（美元）
+    pub remaining_usd: Option<f64>,
+
+    /// 已用额度（美元）
+    pub used_usd: Option<f64>,
+
+    /// 总额度（美元）
+    pub total_usd: Option<f64>,
+
+    /// 最近一次查询时间（ISO 8601 格式），从未查询时为 None
+    pub fetched_at: Option<String>,
+
+    /// 最近一次查询失败的原因，成功时为 None
+    pub error: Option<String>,
+
+    /// 配置更新时间（ISO 8601 格式）
+    pub updated_at: String,
+}
+
+/// 从余额接口响应中解析出的额度（美元）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BalanceAmounts {
+    pub remaining_usd: Option<f64>,
+    pub used_usd: Option<f64>,
+    pub total_usd: Option<f64>,
+}
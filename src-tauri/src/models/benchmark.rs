@@ -0,0 +1,66 @@
+//! @file benchmark.rs
+//! @description 解析器基准测试报告模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 单个解析阶段的耗时与分配统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTiming {
+    /// 阶段名称
+    pub name: String,
+
+    /// 该阶段处理全部行的总耗时（毫秒）
+    pub total_ms: f64,
+
+    /// 平均每行耗时（微秒）
+    pub avg_us_per_line: f64,
+
+    /// 该阶段的分配次数
+    pub allocations: u64,
+
+    /// 该阶段分配的字节数
+    pub allocated_bytes: u64,
+}
+
+/// 解析器基准测试报告
+///
+/// 分配计数为进程级统计，测试期间其他线程的分配也会计入，结果为近似值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParserBenchmarkReport {
+    /// 基准测试的文件或目录
+    pub path: String,
+
+    /// 读取的 JSONL 文件数
+    pub files: usize,
+
+    /// 读取的字节数
+    pub bytes: u64,
+
+    /// 非空行数
+    pub lines: usize,
+
+    /// 解析出的消息数
+    pub messages: usize,
+
+    /// JSON 解析失败的行数
+    pub invalid_lines: usize,
+
+    /// 全部阶段的总耗时（毫秒）
+    pub total_ms: f64,
+
+    /// 每秒处理行数（按 ingest 阶段耗时计算）
+    pub lines_per_sec: f64,
+
+    /// 每秒处理的数据量（MB，按 ingest 阶段耗时计算）
+    pub mb_per_sec: f64,
+
+    /// 全部阶段的分配次数
+    pub allocations: u64,
+
+    /// 全部阶段分配的字节数
+    pub allocated_bytes: u64,
+
+    /// 各阶段统计，按执行顺序排列
+    pub stages: Vec<StageTiming>,
+}
@@ -0,0 +1,66 @@
+//! @file block.rs
+//! @description 5 小时计费区块数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 区块计算所需的单条消息用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockEntry {
+    /// 消息时间（ISO 8601 格式）
+    pub created_at: String,
+
+    /// Token 总数（输入 + 输出 + 缓存读取 + 缓存创建）
+    pub total_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+}
+
+/// 使用区块
+///
+/// 与 Claude 订阅的 5 小时用量窗口对应：区块从首条消息所在整点开始，持续 5 小时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageBlock {
+    /// 区块开始时间（ISO 8601 格式，UTC）
+    pub start_at: String,
+
+    /// 区块结束时间（ISO 8601 格式，UTC）
+    pub end_at: String,
+
+    /// 区块内最后一条消息时间（ISO 8601 格式）
+    pub last_message_at: String,
+
+    /// 距区块结束的剩余分钟数
+    pub remaining_minutes: i64,
+
+    /// 区块内 Token 总数
+    pub total_tokens: i64,
+
+    /// 区块内费用（美元）
+    pub cost_usd: f64,
+
+    /// 区块内消息数
+    pub message_count: i64,
+}
+
+/// 区块用量预警
+///
+/// 当前区块用量达到容量的某个百分比时生成，容量为用户配置值或从历史区块学习的上限
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockWarning {
+    /// 触发的预警百分比（如 80）
+    pub threshold_percent: u32,
+
+    /// 当前用量占容量的比例
+    pub usage_ratio: f64,
+
+    /// 区块容量（Token 总数）
+    pub capacity_tokens: i64,
+
+    /// 容量是否来自历史学习（false 表示用户配置）
+    pub capacity_learned: bool,
+
+    /// 当前区块
+    pub block: UsageBlock,
+}
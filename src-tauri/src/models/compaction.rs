@@ -0,0 +1,102 @@
+//! @file compaction.rs
+//! @description 上下文压缩事件数据模型，压缩频率反映上下文窗口的紧张程度
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 压缩触发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionTrigger {
+    /// 上下文接近上限时自动压缩
+    Auto,
+
+    /// 用户执行 /compact 手动压缩
+    Manual,
+}
+
+impl CompactionTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompactionTrigger::Auto => "auto",
+            CompactionTrigger::Manual => "manual",
+        }
+    }
+
+    /// 从数据库或日志中的字符串解析，未知值按自动压缩处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "manual" => CompactionTrigger::Manual,
+            _ => CompactionTrigger::Auto,
+        }
+    }
+}
+
+/// JSONL 中解析出的单次压缩事件（compact_boundary 条目）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompactionEvent {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 触发方式
+    pub trigger: CompactionTrigger,
+
+    /// 压缩前的上下文 Token 数，日志中未给出时为 None
+    pub pre_tokens: Option<i64>,
+
+    /// 发生时间（ISO 8601 格式）
+    pub occurred_at: String,
+}
+
+/// 单个会话的压缩统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCompaction {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 会话标题
+    pub title: Option<String>,
+
+    /// 压缩次数
+    pub compaction_count: i64,
+
+    /// 其中自动压缩次数
+    pub auto_count: i64,
+
+    /// 最近一次压缩时间（ISO 8601 格式）
+    pub last_compacted_at: String,
+}
+
+/// 日期范围内的压缩统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// 开始日期（YYYY-MM-DD 格式，包含）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式，包含）
+    pub end_date: String,
+
+    /// 压缩总次数
+    pub total_count: i64,
+
+    /// 自动压缩次数
+    pub auto_count: i64,
+
+    /// 手动压缩次数
+    pub manual_count: i64,
+
+    /// 压缩前上下文 Token 的平均值
+    pub avg_pre_tokens: Option<f64>,
+
+    /// 发生过压缩的会话数
+    pub compacted_sessions: i64,
+
+    /// 范围内有用量记录的会话总数
+    pub total_sessions: i64,
+
+    /// 发生过压缩的会话占比
+    pub compacted_session_rate: f64,
+
+    /// 各会话的压缩统计，按压缩次数降序
+    pub sessions: Vec<SessionCompaction>,
+}
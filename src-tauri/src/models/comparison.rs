@@ -0,0 +1,48 @@
+//! @file comparison.rs
+//! @description 供应商横向对比数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 对比序列中单个供应商单日的数据点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderComparisonPoint {
+    /// 日期（YYYY-MM-DD 格式，本地日期）
+    pub date: String,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// Token 总数（输入 + 输出）
+    pub total_tokens: i64,
+
+    /// 缓存命中率（0.0 - 1.0）
+    pub cache_hit_rate: f64,
+
+    /// 平均请求耗时（毫秒），当天无耗时记录时为 None
+    pub avg_latency_ms: Option<f64>,
+
+    /// 成功请求数
+    pub request_count: i64,
+
+    /// API 错误数
+    pub error_count: i64,
+
+    /// 错误率：错误数 / (成功请求数 + 错误数)
+    pub error_rate: f64,
+}
+
+/// 单个供应商的对比序列
+///
+/// 所有供应商的 points 覆盖相同的日期范围且顺序一致，无数据的日期补零
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderComparison {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 供应商显示名称
+    pub display_name: Option<String>,
+
+    /// 按日期升序排列的数据点
+    pub points: Vec<ProviderComparisonPoint>,
+}
@@ -0,0 +1,26 @@
+//! @file cost_recalculation.rs
+//! @description 费用重算审计记录数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 一次改变了已存储费用的重算记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostRecalculation {
+    pub id: i64,
+
+    /// 重算使用的价格表版本
+    pub pricing_version: String,
+
+    /// 重算前订阅消息的 API 等价费用合计（美元）
+    pub old_total_usd: f64,
+
+    /// 重算后订阅消息的 API 等价费用合计（美元）
+    pub new_total_usd: f64,
+
+    /// 费用发生变化的消息条数
+    pub changed_messages: i64,
+
+    /// 重算时间（ISO 8601 格式）
+    pub recalculated_at: String,
+}
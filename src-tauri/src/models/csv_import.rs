@@ -0,0 +1,54 @@
+//! @file csv_import.rs
+//! @description 通用 CSV 历史数据导入模型，描述列映射与导入结果
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// CSV 列映射，值为 CSV 表头中的列名（不区分大小写）
+///
+/// 只有 date 必填；未映射的 token 列按 0 处理，未映射费用时按模型价格计算
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvColumnMapping {
+    /// 日期或时间列，支持 RFC 3339、`YYYY-MM-DD HH:MM:SS`、`YYYY-MM-DD`、`YYYY/MM/DD` 与 `MM/DD/YYYY`
+    pub date: String,
+
+    /// 模型列，未映射或为空时使用 `unknown`
+    pub model: Option<String>,
+
+    pub input_tokens: Option<String>,
+
+    pub output_tokens: Option<String>,
+
+    pub cache_read_tokens: Option<String>,
+
+    pub cache_creation_tokens: Option<String>,
+
+    /// Token 总数列，仅在未映射输入与输出列时使用，计入输入 Token
+    pub total_tokens: Option<String>,
+
+    /// 费用（美元）列
+    pub cost_usd: Option<String>,
+
+    /// 会话列，未映射时同一天的行归为一个会话
+    pub session: Option<String>,
+}
+
+/// CSV 导入结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvImportSummary {
+    /// 读取的数据行数（不含表头）
+    pub rows_read: usize,
+
+    /// 新增的消息数
+    pub messages_added: usize,
+
+    /// 已导入过而跳过的行数
+    pub messages_skipped: usize,
+
+    /// 无法解析的行数
+    pub invalid_rows: usize,
+
+    /// 前若干条解析错误，格式为 `第 N 行: 原因`
+    pub errors: Vec<String>,
+}
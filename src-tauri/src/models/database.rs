@@ -0,0 +1,21 @@
+//! @file database.rs
+//! @description 数据库位置数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 数据库位置信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseLocation {
+    /// 当前使用的数据库文件路径
+    pub path: String,
+
+    /// 未自定义位置时使用的默认路径
+    pub default_path: String,
+
+    /// 是否为自定义位置
+    pub custom: bool,
+
+    /// 是否为便携模式（数据库固定在程序目录下，不可迁移）
+    pub portable: bool,
+}
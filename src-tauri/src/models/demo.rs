@@ -0,0 +1,49 @@
+//! @file demo.rs
+//! @description 演示数据模型，描述生成强度与生成结果
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 演示数据的使用强度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DemoIntensity {
+    /// 偶尔使用，每个工作日约 2 个会话
+    Light,
+
+    /// 日常使用，每个工作日约 6 个会话
+    #[default]
+    Normal,
+
+    /// 重度使用，每个工作日约 15 个会话
+    Heavy,
+}
+
+impl DemoIntensity {
+    /// 工作日平均会话数，周末减半
+    pub fn sessions_per_weekday(&self) -> u32 {
+        match self {
+            DemoIntensity::Light => 2,
+            DemoIntensity::Normal => 6,
+            DemoIntensity::Heavy => 15,
+        }
+    }
+}
+
+/// 演示数据生成结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DemoDataSummary {
+    /// 演示数据库文件路径
+    pub path: String,
+
+    pub days: u32,
+
+    pub providers: usize,
+
+    pub sessions: usize,
+
+    pub messages: usize,
+
+    /// 生成数据的总费用（美元）
+    pub total_cost_usd: f64,
+}
@@ -0,0 +1,92 @@
+//! @file distribution.rs
+//! @description 分布统计数据模型（直方图分桶与分位数）
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 直方图中的单个分桶，区间为 [lower, upper)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DistributionBucket {
+    /// 下界（包含）
+    pub lower: f64,
+
+    /// 上界（不包含），最后一个分桶为 None 表示无上限
+    pub upper: Option<f64>,
+
+    /// 落入该分桶的样本数
+    pub count: i64,
+}
+
+/// 单个指标的分布统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    /// 直方图分桶
+    pub buckets: Vec<DistributionBucket>,
+
+    /// 中位数
+    pub p50: f64,
+
+    /// 90 分位数
+    pub p90: f64,
+
+    /// 99 分位数
+    pub p99: f64,
+
+    /// 最大值
+    pub max: f64,
+}
+
+/// 单条消息的 Token 数样本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageTokenSample {
+    /// 输入 Token 数（输入 + 缓存读取 + 缓存创建，即完整提示词大小）
+    pub input_tokens: i64,
+
+    /// 输出 Token 数
+    pub output_tokens: i64,
+}
+
+/// 日期范围内每条消息的 Token 数分布
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageDistribution {
+    /// 参与统计的消息数
+    pub message_count: i64,
+
+    /// 输入 Token 数分布
+    pub input_tokens: Distribution,
+
+    /// 输出 Token 数分布
+    pub output_tokens: Distribution,
+}
+
+/// 单个会话的时长、消息数与费用样本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSample {
+    /// 会话时长（秒，首条到末条消息）
+    pub duration_seconds: f64,
+
+    /// 消息数
+    pub message_count: i64,
+
+    /// 累计费用（美元）
+    pub cost_usd: f64,
+}
+
+/// 日期范围内会话的时长、消息数与费用分布
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionDistribution {
+    /// 参与统计的会话数
+    pub session_count: i64,
+
+    /// 会话时长分布（秒）
+    pub duration_seconds: Distribution,
+
+    /// 每个会话消息数分布
+    pub message_count: Distribution,
+
+    /// 每个会话费用分布（美元）
+    pub cost_usd: Distribution,
+
+    /// 费用最高的 10% 会话占总费用的比例（0.0 - 1.0），接近 1 说明少数长会话主导费用
+    pub top_decile_cost_share: f64,
+}
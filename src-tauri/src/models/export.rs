@@ -0,0 +1,21 @@
+//! @file export.rs
+//! @description 消息级用量导出（Parquet）结果数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// Parquet 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParquetExport {
+    /// 导出文件路径
+    pub path: String,
+
+    /// 开始日期（YYYY-MM-DD 格式，本地时间，包含）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式，本地时间，包含）
+    pub end_date: String,
+
+    /// 导出的消息行数
+    pub row_count: usize,
+}
@@ -0,0 +1,260 @@
+//! @file goal.rs
+//! @description 使用目标数据模型，与预算不同，目标可以要求指标保持在上限以下或下限以上
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 目标指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalMetric {
+    /// 缓存命中率（百分比 0 - 100，按缓存命中率设置中的公式计算）
+    CacheHitRate,
+
+    /// 费用（美元）
+    CostUsd,
+
+    /// Token 总数（输入 + 输出 + 缓存读取 + 缓存创建）
+    TotalTokens,
+
+    /// 输入 Token 数
+    InputTokens,
+
+    /// 输出 Token 数
+    OutputTokens,
+
+    /// 消息数
+    MessageCount,
+}
+
+impl GoalMetric {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalMetric::CacheHitRate => "cache_hit_rate",
+            GoalMetric::CostUsd => "cost_usd",
+            GoalMetric::TotalTokens => "total_tokens",
+            GoalMetric::InputTokens => "input_tokens",
+            GoalMetric::OutputTokens => "output_tokens",
+            GoalMetric::MessageCount => "message_count",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 CostUsd 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "cache_hit_rate" => GoalMetric::CacheHitRate,
+            "total_tokens" => GoalMetric::TotalTokens,
+            "input_tokens" => GoalMetric::InputTokens,
+            "output_tokens" => GoalMetric::OutputTokens,
+            "message_count" => GoalMetric::MessageCount,
+            _ => GoalMetric::CostUsd,
+        }
+    }
+}
+
+/// 目标方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalDirection {
+    /// 不超过目标值（如每周输出 Token 不超过 200 万）
+    AtMost,
+
+    /// 不低于目标值（如缓存命中率保持在 60% 以上）
+    AtLeast,
+}
+
+impl GoalDirection {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalDirection::AtMost => "at_most",
+            GoalDirection::AtLeast => "at_least",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 AtMost 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "at_least" => GoalDirection::AtLeast,
+            _ => GoalDirection::AtMost,
+        }
+    }
+
+    /// 指标值是否达成目标
+    pub fn is_met(&self, value: f64, target: f64) -> bool {
+        match self {
+            GoalDirection::AtMost => value <= target,
+            GoalDirection::AtLeast => value >= target,
+        }
+    }
+}
+
+/// 目标周期（按本地日历划分）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalPeriod {
+    /// 自然日
+    Day,
+
+    /// 自然周（周一开始）
+    Week,
+
+    /// 自然月
+    Month,
+}
+
+impl GoalPeriod {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalPeriod::Day => "day",
+            GoalPeriod::Week => "week",
+            GoalPeriod::Month => "month",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Week 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "day" => GoalPeriod::Day,
+            "month" => GoalPeriod::Month,
+            _ => GoalPeriod::Week,
+        }
+    }
+}
+
+/// 新建或更新目标的参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalInput {
+    /// 目标名称
+    pub name: String,
+
+    pub metric: GoalMetric,
+
+    pub direction: GoalDirection,
+
+    /// 目标值，单位与指标一致（缓存命中率为百分比）
+    pub target: f64,
+
+    pub period: GoalPeriod,
+
+    /// 是否启用，默认启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 使用目标
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    /// 目标 ID
+    pub id: i64,
+
+    pub name: String,
+
+    pub metric: GoalMetric,
+
+    pub direction: GoalDirection,
+
+    pub target: f64,
+
+    pub period: GoalPeriod,
+
+    pub enabled: bool,
+
+    /// 创建时间（ISO 8601 格式）
+    pub created_at: String,
+
+    /// 更新时间（ISO 8601 格式）
+    pub updated_at: String,
+}
+
+/// 目标在一个周期内的评估结果，每个周期一条，周期内每天评估时覆盖
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalEvaluation {
+    pub goal_id: i64,
+
+    /// 周期开始日期（YYYY-MM-DD）
+    pub period_start: String,
+
+    /// 评估时的指标值
+    pub value: f64,
+
+    /// 评估时的目标值
+    pub target: f64,
+
+    /// 是否达成
+    pub met: bool,
+
+    /// 评估时间（ISO 8601 格式）
+    pub evaluated_at: String,
+}
+
+/// 目标进度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub goal: Goal,
+
+    /// 当前周期开始日期（YYYY-MM-DD）
+    pub period_start: String,
+
+    /// 当前周期结束日期（YYYY-MM-DD，不含）
+    pub period_end: String,
+
+    /// 当前周期的指标值
+    pub value: f64,
+
+    /// 指标值占目标值的百分比，目标值为 0 时按是否达成取 100 或 0
+    pub percent: f64,
+
+    /// 当前周期是否达成（上限目标在周期结束前可能变为未达成）
+    pub met: bool,
+
+    /// 连续达成的已结束周期数
+    pub streak: u32,
+
+    /// 已结束周期的评估记录（从新到旧）
+    pub history: Vec<GoalEvaluation>,
+}
+
+/// 时间范围内用于目标评估的用量汇总（所有供应商）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GoalUsageTotals {
+    pub cost_usd: f64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub message_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goal_enums_round_trip() {
+        for metric in [
+            GoalMetric::CacheHitRate,
+            GoalMetric::CostUsd,
+            GoalMetric::TotalTokens,
+            GoalMetric::InputTokens,
+            GoalMetric::OutputTokens,
+            GoalMetric::MessageCount,
+        ] {
+            assert_eq!(GoalMetric::from_db(metric.as_str()), metric);
+        }
+        for period in [GoalPeriod::Day, GoalPeriod::Week, GoalPeriod::Month] {
+            assert_eq!(GoalPeriod::from_db(period.as_str()), period);
+        }
+        assert_eq!(GoalDirection::from_db("at_least"), GoalDirection::AtLeast);
+        assert!(GoalDirection::AtMost.is_met(5.0, 5.0));
+        assert!(!GoalDirection::AtMost.is_met(5.1, 5.0));
+        assert!(GoalDirection::AtLeast.is_met(60.0, 60.0));
+        assert!(!GoalDirection::AtLeast.is_met(59.9, 60.0));
+    }
+}
@@ -0,0 +1,90 @@
+//! @file health.rs
+//! @description 运行状态诊断数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 文件监控状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatcherStatus {
+    /// 正在监控
+    Running,
+
+    /// 未在监控（尚未启动或已停止）
+    #[default]
+    Paused,
+
+    /// 监控出错，文件变更可能未被采集
+    Errored,
+}
+
+/// 运行状态诊断报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// 应用版本
+    pub app_version: String,
+
+    /// 文件监控状态
+    pub watcher_status: WatcherStatus,
+
+    /// 正在监控的路径
+    pub watched_paths: Vec<String>,
+
+    /// 数据库文件路径
+    pub db_path: String,
+
+    /// 数据库文件大小（字节，包含 WAL 文件），无法读取时为 None
+    pub db_size_bytes: Option<u64>,
+
+    /// 最近一次采集日志数据的时间（ISO 8601 格式）
+    pub last_ingest_at: Option<String>,
+
+    /// 最近一次错误信息
+    pub last_error: Option<String>,
+
+    /// 最近一次错误时间（ISO 8601 格式）
+    pub last_error_at: Option<String>,
+
+    /// 数据库 Schema 版本
+    pub schema_version: i64,
+}
+
+/// 数据库 Schema 版本信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaVersion {
+    /// 当前已应用的迁移版本
+    pub current_version: i64,
+
+    /// 应用内置的最新迁移版本
+    pub latest_version: i64,
+}
+
+/// 构建信息，编译时确定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// 应用版本（语义化版本）
+    pub version: String,
+
+    /// 构建时的 git 提交（短哈希），非 git 环境构建时为 None
+    pub git_commit: Option<String>,
+
+    /// 构建时间（ISO 8601 格式）
+    pub build_date: Option<String>,
+
+    /// 启用的 Cargo features
+    pub features: Vec<String>,
+
+    /// debug 或 release
+    pub profile: String,
+}
+
+/// 应用信息，用于问题反馈与报告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppInfo {
+    #[serde(flatten)]
+    pub build: BuildInfo,
+
+    /// 当前数据库 Schema 版本
+    pub schema_version: i64,
+}
@@ -0,0 +1,129 @@
+//! @file heatmap.rs
+//! @description 星期 × 小时使用热力图数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 热力图单元格
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    /// 星期几（0 = 周一，6 = 周日）
+    pub weekday: u32,
+
+    /// 小时（0 - 23，本地时间）
+    pub hour: u32,
+
+    /// Token 总数（输入 + 输出）
+    pub tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 消息数
+    pub message_count: i64,
+}
+
+impl HeatmapCell {
+    /// 创建空单元格
+    pub fn new(weekday: u32, hour: u32) -> Self {
+        Self {
+            weekday,
+            hour,
+            tokens: 0,
+            cost_usd: 0.0,
+            message_count: 0,
+        }
+    }
+}
+
+/// 星期 × 小时使用热力图
+///
+/// 7 × 24 矩阵，cells[weekday][hour]，用于展示 "什么时候最消耗 Token"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageHeatmap {
+    /// 统计开始日期（YYYY-MM-DD 格式）
+    pub start_date: String,
+
+    /// 统计结束日期（YYYY-MM-DD 格式）
+    pub end_date: String,
+
+    /// 7 × 24 单元格矩阵
+    pub cells: Vec<Vec<HeatmapCell>>,
+
+    /// 单元格最大 Token 数，便于前端归一化颜色
+    pub max_tokens: i64,
+
+    /// 单元格最大费用（美元）
+    pub max_cost_usd: f64,
+}
+
+impl UsageHeatmap {
+    /// 将稀疏的聚合结果填充为完整的 7 × 24 矩阵
+    ///
+    /// 业务逻辑说明：
+    /// 1. 初始化全部为 0 的矩阵
+    /// 2. 按 weekday / hour 写入已有数据，越界数据忽略
+    /// 3. 统计最大值供前端渲染色阶
+    pub fn from_cells(start_date: String, end_date: String, sparse: Vec<HeatmapCell>) -> Self {
+        let mut cells: Vec<Vec<HeatmapCell>> = (0..7)
+            .map(|weekday| {
+                (0..24)
+                    .map(|hour| HeatmapCell::new(weekday, hour))
+                    .collect()
+            })
+            .collect();
+
+        for cell in sparse {
+            if let Some(slot) = cells
+                .get_mut(cell.weekday as usize)
+                .and_then(|row| row.get_mut(cell.hour as usize))
+            {
+                *slot = cell;
+            }
+        }
+
+        let max_tokens = cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.tokens)
+            .max()
+            .unwrap_or(0);
+        let max_cost_usd = cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.cost_usd)
+            .fold(0.0, f64::max);
+
+        Self {
+            start_date,
+            end_date,
+            cells,
+            max_tokens,
+            max_cost_usd,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap_from_cells() {
+        let mut cell = HeatmapCell::new(2, 14);
+        cell.tokens = 500;
+        cell.cost_usd = 1.5;
+
+        let heatmap = UsageHeatmap::from_cells(
+            "2026-10-01".to_string(),
+            "2026-10-31".to_string(),
+            vec![cell, HeatmapCell::new(9, 30)],
+        );
+
+        assert_eq!(heatmap.cells.len(), 7);
+        assert!(heatmap.cells.iter().all(|row| row.len() == 24));
+        assert_eq!(heatmap.cells[2][14].tokens, 500);
+        assert_eq!(heatmap.max_tokens, 500);
+        assert_eq!(heatmap.max_cost_usd, 1.5);
+    }
+}
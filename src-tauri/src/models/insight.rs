@@ -0,0 +1,57 @@
+//! @file insight.rs
+//! @description 用量洞察数据模型，描述从消息级统计中发现的可优化模式
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 洞察类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsightKind {
+    /// 较贵模型的大量消息输出很短，可考虑换用更便宜的模型
+    ModelDowngrade,
+}
+
+/// 单条洞察
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Insight {
+    pub kind: InsightKind,
+
+    /// 面向用户的说明
+    pub message: String,
+
+    /// 涉及的模型
+    pub model: String,
+
+    /// 建议换用的模型
+    pub suggested_model: Option<String>,
+
+    /// 符合条件的消息数
+    pub affected_messages: i64,
+
+    /// 符合条件的消息占该模型消息数的比例（0 - 1）
+    pub share: f64,
+
+    /// 这些消息换用建议模型后预计节省的费用（美元），建议模型没有价格配置时为 None
+    pub potential_savings_usd: Option<f64>,
+}
+
+/// 单个模型在日期范围内的输出长度分布
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelOutputProfile {
+    pub model: String,
+
+    pub message_count: i64,
+
+    /// 输出 Token 低于阈值的消息数
+    pub short_output_count: i64,
+
+    /// 短输出消息的 Token 合计
+    pub short_input_tokens: i64,
+
+    pub short_output_tokens: i64,
+
+    pub short_cache_read_tokens: i64,
+
+    pub short_cache_creation_tokens: i64,
+}
@@ -0,0 +1,143 @@
+//! @file integrity.rs
+//! @description 数据一致性检查数据模型，对比 daily_stats 与 message_usage 重新汇总的结果
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 单个供应商单日的汇总数据
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyStatsTotals {
+    /// 输入 Token 总数
+    pub input_tokens: i64,
+
+    /// 输出 Token 总数
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token 总数
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 总数
+    pub cache_creation_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 按 API 价格计算的等价费用（美元）
+    pub api_equivalent_cost_usd: f64,
+
+    /// 会话数
+    pub session_count: i64,
+
+    /// 消息数
+    pub message_count: i64,
+}
+
+impl DailyStatsTotals {
+    /// 两份汇总是否一致（费用允许浮点误差）
+    pub fn matches(&self, other: &Self) -> bool {
+        self.input_tokens == other.input_tokens
+            && self.output_tokens == other.output_tokens
+            && self.cache_read_tokens == other.cache_read_tokens
+            && self.cache_creation_tokens == other.cache_creation_tokens
+            && (self.cost_usd - other.cost_usd).abs() < 1e-6
+            && (self.api_equivalent_cost_usd - other.api_equivalent_cost_usd).abs() < 1e-6
+            && self.session_count == other.session_count
+            && self.message_count == other.message_count
+    }
+}
+
+/// daily_stats 与重新汇总结果不一致的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyStatsDiscrepancy {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 日期（YYYY-MM-DD，本地日期）
+    pub date: String,
+
+    /// daily_stats 中存储的数据，缺失时为 None
+    pub stored: Option<DailyStatsTotals>,
+
+    /// 从 message_usage 重新汇总的数据，无对应消息时为 None
+    pub recomputed: Option<DailyStatsTotals>,
+}
+
+/// 数据一致性检查报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// 检查的供应商日期组合数量
+    pub checked_days: usize,
+
+    /// 是否完全一致
+    pub is_consistent: bool,
+
+    /// 不一致的记录
+    pub discrepancies: Vec<DailyStatsDiscrepancy>,
+
+    /// 检查时间（ISO 8601 格式）
+    pub checked_at: String,
+}
+
+/// 重复消息类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    /// 同一消息记录在多个供应商名下（切换供应商后重新扫描）
+    CrossProvider,
+
+    /// 同一供应商下同一会话消息记录了多次（早期版本按日期去重时跨天重复写入）
+    RepeatedSessionMessage,
+}
+
+/// 同一 message_id 的一组重复记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+
+    pub message_id: String,
+
+    /// 保留记录的会话 ID
+    pub session_id: String,
+
+    /// 涉及的供应商 ID
+    pub provider_ids: Vec<i64>,
+
+    /// 该 message_id 的记录数
+    pub row_count: i64,
+
+    /// 清理时保留的记录 ID（最早写入的一条）
+    pub kept_id: i64,
+
+    /// 多计的 Token 数（不含保留记录）
+    pub duplicate_tokens: i64,
+
+    /// 多计的费用（美元，不含保留记录）
+    pub duplicate_cost_usd: f64,
+
+    /// 最早与最晚的记录时间（ISO 8601 格式）
+    pub first_created_at: String,
+
+    pub last_created_at: String,
+}
+
+/// 重复数据审计报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateAuditReport {
+    /// 重复的 message_id 数量
+    pub group_count: usize,
+
+    /// 可删除的重复记录数
+    pub duplicate_rows: i64,
+
+    /// 多计的 Token 总数
+    pub duplicate_tokens: i64,
+
+    /// 多计的费用总额（美元）
+    pub duplicate_cost_usd: f64,
+
+    /// 重复记录组，按多计费用降序
+    pub groups: Vec<DuplicateGroup>,
+
+    /// 检查时间（ISO 8601 格式）
+    pub checked_at: String,
+}
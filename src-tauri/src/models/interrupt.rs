@@ -0,0 +1,98 @@
+//! @file interrupt.rs
+//! @description 请求中断数据模型，用于统计被用户中断而丢弃的生成所消耗的 Token
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 中断类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptKind {
+    /// 生成过程中被用户中断（Esc）
+    User,
+
+    /// 等待工具调用确认时被用户中断
+    ToolUse,
+}
+
+impl InterruptKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterruptKind::User => "user",
+            InterruptKind::ToolUse => "tool_use",
+        }
+    }
+}
+
+/// JSONL 中解析出的单次中断
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterruptEvent {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 中断类型
+    pub kind: InterruptKind,
+
+    /// 发生时间（ISO 8601 格式）
+    pub occurred_at: String,
+}
+
+/// 中断丢弃的用量汇总
+///
+/// 被丢弃的生成取中断前同一会话的最后一条消息，多次中断同一条消息只计一次
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiscardedUsage {
+    /// 中断次数
+    pub interrupt_count: i64,
+
+    /// 被丢弃消息的 Token 总数（输入 + 输出 + 缓存读取 + 缓存创建）
+    pub discarded_tokens: i64,
+
+    /// 被丢弃消息的输出 Token
+    pub discarded_output_tokens: i64,
+
+    /// 被丢弃消息的费用（美元）
+    pub discarded_cost_usd: f64,
+}
+
+/// 单日中断统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyDiscardedUsage {
+    /// 日期（YYYY-MM-DD 格式）
+    pub date: String,
+
+    #[serde(flatten)]
+    pub usage: DiscardedUsage,
+}
+
+/// 单个会话的中断统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDiscardedUsage {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 会话标题
+    pub title: Option<String>,
+
+    #[serde(flatten)]
+    pub usage: DiscardedUsage,
+}
+
+/// 日期范围内的中断统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterruptStats {
+    /// 开始日期（YYYY-MM-DD 格式，包含）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式，包含）
+    pub end_date: String,
+
+    /// 范围内合计
+    pub total: DiscardedUsage,
+
+    /// 按日期统计，只包含有中断的日期
+    pub daily: Vec<DailyDiscardedUsage>,
+
+    /// 按会话统计，按丢弃 Token 降序
+    pub sessions: Vec<SessionDiscardedUsage>,
+}
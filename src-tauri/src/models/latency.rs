@@ -0,0 +1,40 @@
+//! @file latency.rs
+//! @description 请求耗时统计数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 单次请求耗时样本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencySample {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 模型名称
+    pub model: String,
+
+    /// 请求耗时（毫秒）
+    pub duration_ms: i64,
+}
+
+/// 单个供应商 + 模型的耗时统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 模型名称
+    pub model: String,
+
+    /// 有耗时记录的请求数
+    pub request_count: i64,
+
+    /// 平均耗时（毫秒）
+    pub avg_ms: f64,
+
+    /// P95 耗时（毫秒）
+    pub p95_ms: i64,
+
+    /// 最大耗时（毫秒）
+    pub max_ms: i64,
+}
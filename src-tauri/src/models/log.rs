@@ -0,0 +1,51 @@
+//! @file log.rs
+//! @description 应用日志数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 日志级别（按严重程度从高到低排列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// 解析日志文件中的级别文本（不区分大小写）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "error" => Some(LogLevel::Error),
+            "warn" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "trace" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+
+    /// 是否达到 min_level 指定的严重程度
+    pub fn is_at_least(&self, min_level: LogLevel) -> bool {
+        *self <= min_level
+    }
+}
+
+/// 单条日志记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// 记录时间（ISO 8601 格式，UTC）
+    pub timestamp: String,
+
+    /// 日志级别
+    pub level: LogLevel,
+
+    /// 日志来源模块
+    pub target: String,
+
+    /// 日志内容
+    pub message: String,
+}
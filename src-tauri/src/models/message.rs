@@ -4,6 +4,8 @@
 //! @date 2026-01-08
 use serde::{Deserialize, Serialize};
 
+use crate::models::ServiceTier;
+
 /// 单条消息的 Token 使用记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageUsage {
@@ -19,6 +21,10 @@ pub struct MessageUsage {
     /// 缓存创建 Token
     pub cache_creation_tokens: i64,
 
+    /// 思考 Token（扩展思考，已包含在输出 Token 中，按输出价格计费）
+    #[serde(default)]
+    pub thinking_tokens: i64,
+
     /// 费用（美元）
     pub cost_usd: f64,
 }
@@ -30,6 +36,7 @@ impl Default for MessageUsage {
             output_tokens: 0,
             cache_read_tokens: 0,
             cache_creation_tokens: 0,
+            thinking_tokens: 0,
             cost_usd: 0.0,
         }
     }
@@ -52,6 +59,18 @@ pub struct MessageRecord {
 
     /// Token 使用统计
     pub usage: MessageUsage,
+
+    /// 所属项目路径（Claude Code 记录中的 cwd），未知时为 None
+    #[serde(default)]
+    pub project: Option<String>,
+
+    /// 请求耗时（毫秒），日志中未记录时为 None
+    #[serde(default)]
+    pub duration_ms: Option<i64>,
+
+    /// 服务层级，日志中未记录时为标准层级
+    #[serde(default)]
+    pub service_tier: ServiceTier,
 }
 
 impl MessageRecord {
@@ -69,8 +88,29 @@ impl MessageRecord {
             model,
             created_at,
             usage,
+            project: None,
+            duration_ms: None,
+            service_tier: ServiceTier::Standard,
         }
     }
+
+    /// 设置所属项目路径
+    pub fn with_project(mut self, project: Option<String>) -> Self {
+        self.project = project;
+        self
+    }
+
+    /// 设置请求耗时
+    pub fn with_duration_ms(mut self, duration_ms: Option<i64>) -> Self {
+        self.duration_ms = duration_ms;
+        self
+    }
+
+    /// 设置服务层级
+    pub fn with_service_tier(mut self, service_tier: ServiceTier) -> Self {
+        self.service_tier = service_tier;
+        self
+    }
 }
 
 #[cfg(test)]
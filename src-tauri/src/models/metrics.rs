@@ -0,0 +1,26 @@
+//! @file metrics.rs
+//! @description 指标推送数据模型，按时间段、供应商与模型汇总的用量
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 一个推送周期内某供应商某模型的用量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntervalUsage {
+    /// 供应商名称（未命名时为 API Key 前缀）
+    pub provider: String,
+
+    pub model: String,
+
+    pub input_tokens: i64,
+
+    pub output_tokens: i64,
+
+    pub cache_read_tokens: i64,
+
+    pub cache_creation_tokens: i64,
+
+    pub cost_usd: f64,
+
+    pub message_count: i64,
+}
@@ -2,11 +2,154 @@
 //! @description 数据模型模块，包含供应商、统计、消息等核心数据结构
 //! @author Atlas.oi
 //! @date 2026-01-08
+pub mod alert;
+pub mod allocation;
+pub mod anomaly;
+pub mod api_error;
+pub mod balance;
+pub mod benchmark;
+pub mod block;
+pub mod compaction;
+pub mod comparison;
+pub mod cost_recalculation;
+pub mod csv_import;
+pub mod database;
+pub mod demo;
+pub mod distribution;
+pub mod export;
+pub mod goal;
+pub mod health;
+pub mod heatmap;
+pub mod insight;
+pub mod integrity;
+pub mod interrupt;
+pub mod latency;
+pub mod log;
 pub mod message;
+pub mod metrics;
+pub mod model_alias;
+pub mod monitor_error;
+pub mod note;
+pub mod notification;
+pub mod official_usage;
+pub mod onboarding;
+pub mod outlier;
+pub mod permissions;
+pub mod plan;
+pub mod profile;
 pub mod provider;
+pub mod rate_limit;
+pub mod report;
+pub mod saved_query;
+pub mod search;
+pub mod service_tier;
+pub mod session;
+pub mod settings;
+pub mod simulation;
+pub mod snapshot;
 pub mod stats;
+pub mod streak;
+pub mod sync;
+pub mod tag;
+pub mod tool_call;
+pub mod trash;
+pub mod trend;
+pub mod update;
+pub mod webhook;
+pub mod year_summary;
 
 // 重新导出所有公共类型
+pub use alert::{
+    AlertChannel, AlertComparator, AlertMetric, AlertRule, AlertRuleInput, AlertWindow,
+    TriggeredAlert, UsageWindowTotals,
+};
+pub use allocation::{AllocationGroupBy, CostAllocationExport, CostAllocationRow, ExportFormat};
+pub use anomaly::CostAnomaly;
+pub use api_error::{ApiErrorEvent, ApiErrorKind, ApiErrorStats};
+pub use balance::{BalanceAmounts, ProviderBalance};
+pub use benchmark::{ParserBenchmarkReport, StageTiming};
+pub use block::{BlockEntry, BlockWarning, UsageBlock};
+pub use compaction::{CompactionEvent, CompactionStats, CompactionTrigger, SessionCompaction};
+pub use comparison::{ProviderComparison, ProviderComparisonPoint};
+pub use cost_recalculation::CostRecalculation;
+pub use csv_import::{CsvColumnMapping, CsvImportSummary};
+pub use database::DatabaseLocation;
+pub use demo::{DemoDataSummary, DemoIntensity};
+pub use distribution::{
+    Distribution, DistributionBucket, MessageDistribution, MessageTokenSample, SessionDistribution,
+    SessionSample,
+};
+pub use export::ParquetExport;
+pub use goal::{
+    Goal, GoalDirection, GoalEvaluation, GoalInput, GoalMetric, GoalPeriod, GoalProgress,
+    GoalUsageTotals,
+};
+pub use health::{AppInfo, BuildInfo, HealthReport, SchemaVersion, WatcherStatus};
+pub use heatmap::{HeatmapCell, UsageHeatmap};
+pub use insight::{Insight, InsightKind, ModelOutputProfile};
+pub use integrity::{
+    DailyStatsDiscrepancy, DailyStatsTotals, DuplicateAuditReport, DuplicateGroup, DuplicateKind,
+    IntegrityReport,
+};
+pub use interrupt::{
+    DailyDiscardedUsage, DiscardedUsage, InterruptEvent, InterruptKind, InterruptStats,
+    SessionDiscardedUsage,
+};
+pub use latency::{LatencySample, LatencyStats};
+pub use log::{LogEntry, LogLevel};
 pub use message::{MessageRecord, MessageUsage};
-pub use provider::{Provider, ProviderStats};
-pub use stats::{DailyActivity, ModelUsage, StatsCache, TodayStats};
+pub use metrics::IntervalUsage;
+pub use model_alias::ModelAlias;
+pub use monitor_error::{MonitorError, MonitorErrorCategory};
+pub use note::DateNote;
+pub use notification::{DeliveryChannel, NotificationKind, NotificationRecord};
+pub use official_usage::{OfficialUsage, UsageDriftPoint, UsageDriftReport};
+pub use onboarding::{OnboardingState, OnboardingStep};
+pub use outlier::{OutlierMessage, OutlierReport};
+pub use permissions::{PermissionCheck, PermissionKind, PermissionReport, PermissionStatus};
+pub use plan::{PlanLimitStatus, PlanType, PlanValue, ProviderPlan};
+pub use profile::{Profile, ProfileInfo, DEFAULT_PROFILE_NAME, MAX_PROFILE_NAME_LEN};
+pub use provider::{
+    Provider, ProviderKind, ProviderStats, ProviderTestResult, UNATTRIBUTED_PROVIDER_KEY,
+    UNATTRIBUTED_PROVIDER_NAME,
+};
+pub use rate_limit::{RateLimitEvent, RateLimitKind, RateLimitStats};
+pub use report::{GeneratedReport, ReportFile, ReportKind, SummaryLocale, UsageReport};
+pub use saved_query::{QueryResult, QueryVisualization, SavedQuery};
+pub use search::{MessageSearchFilters, MessageSearchPage, StatsFilters, StoredMessage};
+pub use service_tier::{ServiceTier, ServiceTierUsage};
+pub use session::{
+    CacheEconomics, SessionContextUsage, SessionDetail, SessionMessageDetail, SessionOrder,
+    SessionSummary, SessionTitleSource, DEFAULT_TOP_SESSIONS_LIMIT,
+};
+pub use settings::{
+    AdminApiSettings, ApiServerSettings, AppLockSettings, AppLockStatus, BlockWarningSettings,
+    BudgetSettings, CacheHitRateFormula, CacheHitRateSettings, DedupePolicy, DedupeSettings,
+    DockBadgeContent, DockBadgeSettings, EventStreamSettings, KeychainSettings, MenuBarSettings,
+    MetricsExportSettings, OnboardingSettings, OtlpExportSettings, OutlierSettings,
+    OverlaySettings, PlanLimitSettings, PrivacySettings, ReportScheduleSettings, S3Config,
+    SyncBackend, SyncSecret, SyncSecretStatus, SyncSettings, TrashSettings, TrayIconMetric,
+    TrayIconSettings, UpdateChannel, UpdateSettings, WebDavConfig,
+};
+pub use simulation::{CostSimulation, ModelCostSimulation};
+pub use snapshot::{
+    Snapshot, SnapshotApiError, SnapshotExport, SnapshotImportSummary, SnapshotMessage,
+    SnapshotProvider, SnapshotProviderPlan, SnapshotProviderSwitch, SnapshotRateLimitEvent,
+    SnapshotSession, SnapshotTag,
+};
+pub use stats::{
+    BurnRate, BurnRateWindow, DailyActivity, LiveRate, ModelUsage, QuickStats, StatsCache,
+    TodayStats,
+};
+pub use streak::{TokenMilestone, UsageStreaks};
+pub use sync::SyncResult;
+pub use tag::{TagStats, TagTarget};
+pub use tool_call::{ToolCall, ToolCallStats};
+pub use trash::{DeletedItem, DeletedItemKind, RestoredItem};
+pub use trend::{ModelDailyUsage, ModelTrend, ModelTrendPoint, RollingAveragePoint};
+pub use update::UpdateInfo;
+pub use webhook::{
+    WebhookDelivery, WebhookDeliveryStatus, WebhookEvent, WebhookFormat, WebhookPayload,
+    WebhookTarget, WebhookTargetInput,
+};
+pub use year_summary::{BusiestDay, MonthlyUsage, YearSummary};
@@ -0,0 +1,18 @@
+//! @file model_alias.rs
+//! @description 模型别名映射数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 模型别名，将中转站上报的非标准模型名映射为标准模型名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelAlias {
+    /// 别名（如 "claude-sonnet-4-5-latest"、"anthropic/claude-3-opus"）
+    pub alias: String,
+
+    /// 标准模型名（与价格表中的名称一致）
+    pub canonical: String,
+
+    /// 更新时间（ISO 8601 格式）
+    pub updated_at: String,
+}
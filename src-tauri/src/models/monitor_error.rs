@@ -0,0 +1,43 @@
+//! @file monitor_error.rs
+//! @description 监控错误数据模型，作为 monitor-error 事件负载发送给前端
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::BuildInfo;
+
+/// 错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorErrorCategory {
+    /// JSONL / settings.json 内容解析失败
+    Parse,
+
+    /// 数据库读写失败
+    Database,
+
+    /// 文件读取失败
+    Io,
+
+    /// 文件监控自身出错
+    Watcher,
+}
+
+/// 单条监控错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorError {
+    /// 错误类别
+    pub category: MonitorErrorCategory,
+
+    /// 相关文件路径
+    pub path: Option<String>,
+
+    /// 错误信息
+    pub message: String,
+
+    /// 发生时间（ISO 8601 格式）
+    pub timestamp: String,
+
+    /// 应用构建信息，便于问题反馈时定位版本
+    pub build: BuildInfo,
+}
@@ -0,0 +1,18 @@
+//! @file note.rs
+//! @description 日期备注数据模型，为历史图表中的用量波动提供说明
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 某一天的用户备注
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DateNote {
+    /// 日期（YYYY-MM-DD 格式，本地日期）
+    pub date: String,
+
+    /// 备注内容
+    pub note: String,
+
+    /// 最后修改时间（ISO 8601 格式）
+    pub updated_at: String,
+}
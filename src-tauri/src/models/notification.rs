@@ -0,0 +1,106 @@
+//! @file notification.rs
+//! @description 通知历史数据模型，记录应用发出的每条通知与告警
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 通知类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// 费用异常
+    CostAnomaly,
+
+    /// 5 小时区块用量预警
+    BlockWarning,
+
+    /// 告警规则触发
+    Alert,
+
+    /// 定时报告生成
+    Report,
+
+    /// 会话上下文接近上限
+    ContextWarning,
+
+    /// 异常大输入消息
+    Outlier,
+}
+
+impl NotificationKind {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::CostAnomaly => "cost_anomaly",
+            NotificationKind::BlockWarning => "block_warning",
+            NotificationKind::Alert => "alert",
+            NotificationKind::Report => "report",
+            NotificationKind::ContextWarning => "context_warning",
+            NotificationKind::Outlier => "outlier",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Alert 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "cost_anomaly" => NotificationKind::CostAnomaly,
+            "block_warning" => NotificationKind::BlockWarning,
+            "report" => NotificationKind::Report,
+            "context_warning" => NotificationKind::ContextWarning,
+            "outlier" => NotificationKind::Outlier,
+            _ => NotificationKind::Alert,
+        }
+    }
+}
+
+/// 实际送达的渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryChannel {
+    /// 系统通知（同时发送应用内事件）
+    System,
+
+    /// 仅应用内事件（未请求系统通知或系统通知发送失败）
+    InApp,
+}
+
+impl DeliveryChannel {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryChannel::System => "system",
+            DeliveryChannel::InApp => "in_app",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 InApp 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "system" => DeliveryChannel::System,
+            _ => DeliveryChannel::InApp,
+        }
+    }
+}
+
+/// 一条通知历史
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationRecord {
+    /// 记录 ID
+    pub id: i64,
+
+    pub kind: NotificationKind,
+
+    /// 通知标题
+    pub title: String,
+
+    /// 通知正文
+    pub body: String,
+
+    /// 随通知发送的事件数据
+    pub payload: serde_json::Value,
+
+    pub channel: DeliveryChannel,
+
+    /// 发送时间（本地时间，ISO 8601 格式）
+    pub sent_at: String,
+}
@@ -0,0 +1,83 @@
+//! @file official_usage.rs
+//! @description Anthropic 官方用量数据模型，以及与本地解析数据的偏差报告
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// Anthropic Admin API 返回的某一天的组织用量
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OfficialUsage {
+    /// 日期（YYYY-MM-DD 格式，UTC 日期，与 Admin API 的日桶一致）
+    pub date: String,
+
+    /// 未命中缓存的输入 Token 数量
+    pub input_tokens: i64,
+
+    pub output_tokens: i64,
+
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 数量（5 分钟与 1 小时缓存之和）
+    pub cache_creation_tokens: i64,
+
+    /// 官方费用（美元）
+    pub cost_usd: f64,
+
+    /// 拉取时间（ISO 8601 格式）
+    pub fetched_at: String,
+}
+
+impl OfficialUsage {
+    /// Token 总量
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+}
+
+/// 某一天官方用量与本地解析用量的对比
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageDriftPoint {
+    /// 日期（YYYY-MM-DD 格式，UTC 日期）
+    pub date: String,
+
+    pub official_tokens: i64,
+
+    pub local_tokens: i64,
+
+    pub official_cost_usd: f64,
+
+    pub local_cost_usd: f64,
+
+    /// 本地减官方的 Token 差值
+    pub token_drift: i64,
+
+    /// 本地减官方的费用差值（美元）
+    pub cost_drift_usd: f64,
+}
+
+/// 官方用量与本地解析用量的偏差报告
+///
+/// 只统计已拉取到官方数据的日期；本地数据包含所有供应商，
+/// 使用第三方代理或多个组织时偏差属于预期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageDriftReport {
+    pub start_date: String,
+
+    pub end_date: String,
+
+    pub points: Vec<UsageDriftPoint>,
+
+    pub official_tokens: i64,
+
+    pub local_tokens: i64,
+
+    pub official_cost_usd: f64,
+
+    pub local_cost_usd: f64,
+
+    /// Token 偏差比例：(本地 - 官方) / 官方，官方为 0 时为 None
+    pub token_drift_ratio: Option<f64>,
+
+    /// 费用偏差比例：(本地 - 官方) / 官方，官方为 0 时为 None
+    pub cost_drift_ratio: Option<f64>,
+}
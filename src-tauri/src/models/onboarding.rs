@@ -0,0 +1,72 @@
+//! @file onboarding.rs
+//! @description 首次运行引导数据模型，描述用户当前所处的引导步骤与各项检测结果
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 引导步骤，按检测顺序推进
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// 未找到 ~/.claude，可能尚未安装或运行过 Claude Code
+    ClaudeDirMissing,
+
+    /// 未找到任何会话 JSONL 文件
+    NoSessionFiles,
+
+    /// 未检测到 API Key 或订阅账号
+    NoProvider,
+
+    /// 已找到会话文件，等待首次扫描写入用量
+    Importing,
+
+    /// 已有用量数据，可以展示仪表盘
+    Ready,
+}
+
+impl OnboardingStep {
+    /// 根据检测结果确定当前步骤，排在前面的缺失项优先
+    pub fn from_checks(
+        claude_dir_found: bool,
+        session_files_found: bool,
+        provider_detected: bool,
+        has_usage: bool,
+    ) -> Self {
+        if has_usage {
+            OnboardingStep::Ready
+        } else if !claude_dir_found {
+            OnboardingStep::ClaudeDirMissing
+        } else if !session_files_found {
+            OnboardingStep::NoSessionFiles
+        } else if !provider_detected {
+            OnboardingStep::NoProvider
+        } else {
+            OnboardingStep::Importing
+        }
+    }
+}
+
+/// 首次运行引导状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingState {
+    /// 当前步骤
+    pub step: OnboardingStep,
+
+    /// Claude Code 数据目录
+    pub claude_dir: String,
+
+    /// 数据目录存在且非空（文件监控启动时会创建空目录）
+    pub claude_dir_found: bool,
+
+    /// projects 下是否存在会话 JSONL 文件
+    pub session_files_found: bool,
+
+    /// 是否已记录供应商，或可从 settings.json / OAuth 登录信息中识别
+    pub provider_detected: bool,
+
+    /// 是否已有消息用量记录
+    pub has_usage: bool,
+
+    /// 引导已完成（曾进入 Ready 或用户主动跳过），前端不再显示引导
+    pub completed: bool,
+}
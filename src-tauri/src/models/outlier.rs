@@ -0,0 +1,43 @@
+//! @file outlier.rs
+//! @description 异常大输入消息数据模型，用于发现意外粘贴大段上下文的请求
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::StoredMessage;
+
+/// 单条异常消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlierMessage {
+    /// 消息记录，session_id 可用于跳转到会话明细
+    #[serde(flatten)]
+    pub message: StoredMessage,
+
+    /// 会话标题，未解析到时为 None
+    pub session_title: Option<String>,
+
+    /// 新输入 Token（输入 + 缓存创建，不含随会话增长的缓存读取）
+    pub prompt_tokens: i64,
+
+    /// 新输入 Token 与中位数之比
+    pub ratio_to_median: f64,
+}
+
+/// 异常消息检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlierReport {
+    /// 开始日期（YYYY-MM-DD 格式，包含）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式，包含）
+    pub end_date: String,
+
+    /// 范围内消息新输入 Token 的中位数
+    pub median_prompt_tokens: f64,
+
+    /// 判定阈值（中位数 × 倍数，且不低于最小值）
+    pub threshold_tokens: f64,
+
+    /// 异常消息，按新输入 Token 降序
+    pub outliers: Vec<OutlierMessage>,
+}
@@ -0,0 +1,78 @@
+//! @file permissions.rs
+//! @description 权限与访问诊断数据模型，描述各项权限的检查结果与对应的系统设置入口
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 检查项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    /// 监控目录读取权限
+    WatchDirectory,
+
+    /// 系统通知权限
+    Notification,
+
+    /// macOS 完全磁盘访问权限
+    FullDiskAccess,
+}
+
+/// 检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionStatus {
+    /// 已授权
+    Granted,
+
+    /// 被拒绝
+    Denied,
+
+    /// 尚未请求，首次使用时系统会询问
+    NotDetermined,
+
+    /// 目标不存在
+    Missing,
+
+    /// 当前平台或配置下不需要
+    NotApplicable,
+}
+
+impl PermissionStatus {
+    /// 是否需要用户处理
+    pub fn needs_action(&self) -> bool {
+        matches!(
+            self,
+            PermissionStatus::Denied | PermissionStatus::NotDetermined | PermissionStatus::Missing
+        )
+    }
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionCheck {
+    pub kind: PermissionKind,
+
+    pub status: PermissionStatus,
+
+    /// 检查对象（如目录路径）
+    pub target: Option<String>,
+
+    /// 面向用户的说明与处理建议
+    pub message: String,
+
+    /// 系统设置深链接，前端可直接打开对应设置页
+    pub settings_url: Option<String>,
+}
+
+/// 权限诊断报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionReport {
+    pub checks: Vec<PermissionCheck>,
+
+    /// 是否存在需要用户处理的检查项
+    pub action_required: bool,
+
+    /// 检查时间（ISO 8601 格式）
+    pub checked_at: String,
+}
@@ -0,0 +1,197 @@
+//! @file plan.rs
+//! @description 订阅套餐相关数据模型，包含供应商套餐配置与订阅价值对比结果
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 套餐类型
+///
+/// Api 表示按量计费；Pro / Max 表示按月付费的 Claude 订阅
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanType {
+    /// 按量计费（默认）
+    Api,
+
+    /// Claude Pro 订阅
+    Pro,
+
+    /// Claude Max 订阅
+    Max,
+}
+
+impl PlanType {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanType::Api => "api",
+            PlanType::Pro => "pro",
+            PlanType::Max => "max",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Api 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "pro" => PlanType::Pro,
+            "max" => PlanType::Max,
+            _ => PlanType::Api,
+        }
+    }
+
+    /// 是否为按月付费的订阅套餐
+    pub fn is_subscription(&self) -> bool {
+        !matches!(self, PlanType::Api)
+    }
+}
+
+/// 供应商套餐配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderPlan {
+    /// 关联的供应商 ID
+    pub provider_id: i64,
+
+    /// 套餐类型
+    pub plan_type: PlanType,
+
+    /// 每月订阅费用（美元），按量计费时为 0
+    pub monthly_fee_usd: f64,
+
+    /// 最后更新时间（ISO 8601 格式）
+    pub updated_at: String,
+}
+
+/// 订阅价值对比结果
+///
+/// 将本月订阅费用与同等用量按 API 价格计算的虚拟费用进行对比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanValue {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 套餐类型
+    pub plan_type: PlanType,
+
+    /// 每月订阅费用（美元）
+    pub monthly_fee_usd: f64,
+
+    /// 统计周期开始日期（YYYY-MM-DD 格式）
+    pub period_start: String,
+
+    /// 统计周期结束日期（YYYY-MM-DD 格式）
+    pub period_end: String,
+
+    /// 本月记录的实际费用（美元）
+    pub recorded_cost_usd: f64,
+
+    /// 按 API 价格折算的虚拟费用（美元）
+    pub api_equivalent_cost_usd: f64,
+
+    /// 节省金额（美元），为负表示订阅不划算
+    /// 计算公式：api_equivalent_cost_usd - monthly_fee_usd
+    pub savings_usd: f64,
+
+    /// 价值倍数（API 等价费用 / 订阅费用），订阅费用为 0 时为 0
+    pub value_ratio: f64,
+}
+
+impl PlanValue {
+    /// 根据套餐与 API 等价费用计算订阅价值
+    ///
+    /// 业务逻辑说明：
+    /// 1. 节省金额 = API 等价费用 - 月费
+    /// 2. 价值倍数 = API 等价费用 / 月费（月费为 0 时记为 0）
+    pub fn new(
+        plan: &ProviderPlan,
+        period_start: String,
+        period_end: String,
+        recorded_cost_usd: f64,
+        api_equivalent_cost_usd: f64,
+    ) -> Self {
+        let value_ratio = if plan.monthly_fee_usd > 0.0 {
+            api_equivalent_cost_usd / plan.monthly_fee_usd
+        } else {
+            0.0
+        };
+
+        Self {
+            provider_id: plan.provider_id,
+            plan_type: plan.plan_type,
+            monthly_fee_usd: plan.monthly_fee_usd,
+            period_start,
+            period_end,
+            recorded_cost_usd,
+            api_equivalent_cost_usd,
+            savings_usd: api_equivalent_cost_usd - plan.monthly_fee_usd,
+            value_ratio,
+        }
+    }
+}
+
+/// 订阅每周额度使用情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanLimitStatus {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 套餐类型（已配置的套餐，或从 OAuth 登录信息识别）
+    pub plan_type: PlanType,
+
+    /// 当前周期开始时间（ISO 8601 格式）
+    pub window_start: String,
+
+    /// 下次重置时间（ISO 8601 格式）
+    pub reset_at: String,
+
+    /// 距下次重置的分钟数
+    pub reset_in_minutes: i64,
+
+    /// 本周期已用 token 总数
+    pub used_tokens: i64,
+
+    /// 本周期 API 等价费用（美元）
+    pub api_equivalent_cost_usd: f64,
+
+    /// 本周期消息数
+    pub message_count: i64,
+
+    /// 每周 token 上限，未配置时为 None
+    pub token_limit: Option<i64>,
+
+    /// 已用比例（used_tokens / token_limit），未配置上限时为 None
+    pub usage_ratio: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_type_round_trip() {
+        assert_eq!(PlanType::from_db(PlanType::Max.as_str()), PlanType::Max);
+        assert_eq!(PlanType::from_db("unknown"), PlanType::Api);
+        assert!(PlanType::Pro.is_subscription());
+        assert!(!PlanType::Api.is_subscription());
+    }
+
+    #[test]
+    fn test_plan_value_savings() {
+        let plan = ProviderPlan {
+            provider_id: 1,
+            plan_type: PlanType::Max,
+            monthly_fee_usd: 100.0,
+            updated_at: "2026-10-01T00:00:00Z".to_string(),
+        };
+
+        let value = PlanValue::new(
+            &plan,
+            "2026-10-01".to_string(),
+            "2026-10-17".to_string(),
+            0.0,
+            250.0,
+        );
+
+        assert_eq!(value.savings_usd, 150.0);
+        assert_eq!(value.value_ratio, 2.5);
+    }
+}
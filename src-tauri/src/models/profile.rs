@@ -0,0 +1,42 @@
+//! @file profile.rs
+//! @description 配置档案数据模型，每个档案（如工作/个人）使用独立的数据库与监控目录
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 默认档案名称，使用应用原有的数据库位置
+pub const DEFAULT_PROFILE_NAME: &str = "default";
+
+/// 档案名称最大长度
+pub const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// 配置档案
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// 档案名称，只允许字母、数字、- 与 _，同时用作数据库目录名
+    pub name: String,
+
+    /// 监控目录，为空时监控 ~/.claude
+    #[serde(default)]
+    pub watch_paths: Vec<String>,
+
+    /// 只读查看：拒绝一切修改操作，文件监控写入数据库副本
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// 档案展示信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    pub name: String,
+
+    pub watch_paths: Vec<String>,
+
+    pub read_only: bool,
+
+    /// 档案使用的数据库文件路径
+    pub db_path: String,
+
+    /// 是否为当前使用的档案
+    pub active: bool,
+}
@@ -6,6 +6,14 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use crate::models::settings::{CacheHitRateFormula, DEFAULT_API_KEY_PREFIX_LENGTH};
+
+/// "未归属"供应商的标识，删除供应商时可将其用量归并到该供应商
+pub const UNATTRIBUTED_PROVIDER_KEY: &str = "unattributed";
+
+/// "未归属"供应商的显示名称
+pub const UNATTRIBUTED_PROVIDER_NAME: &str = "Unattributed";
+
 /// 供应商信息
 ///
 /// 存储 Claude API 供应商的基本信息，用于多 API Key 管理和统计
@@ -18,6 +26,8 @@ pub struct Provider {
     pub api_key_hash: String,
 
     /// API Key 前 8 个字符，便于用户识别（如 "sk-ant-a"）
+    ///
+    /// 写入数据库时按隐私设置截短，见 [`crate::models::PrivacySettings`]
     pub api_key_prefix: String,
 
     /// 用户自定义的显示名称，方便区分不同的 API Key
@@ -34,6 +44,40 @@ pub struct Provider {
 
     /// 最后一次使用该 API Key 的时间（ISO 8601 格式）
     pub last_seen_at: String,
+
+    /// 认证方式
+    #[serde(default)]
+    pub kind: ProviderKind,
+}
+
+/// 供应商认证方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    /// 使用 API Key（ANTHROPIC_AUTH_TOKEN）认证
+    #[default]
+    ApiKey,
+
+    /// 使用 claude.ai 订阅账号（OAuth）登录，按订阅计费
+    Subscription,
+}
+
+impl ProviderKind {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::ApiKey => "api_key",
+            ProviderKind::Subscription => "subscription",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 ApiKey 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "subscription" => ProviderKind::Subscription,
+            _ => ProviderKind::ApiKey,
+        }
+    }
 }
 
 impl Provider {
@@ -62,7 +106,10 @@ impl Provider {
         let api_key_hash = format!("{:x}", hasher.finalize());
 
         // 提取前 8 个字符作为前缀
-        let api_key_prefix = api_key.chars().take(8).collect::<String>();
+        let api_key_prefix = api_key
+            .chars()
+            .take(DEFAULT_API_KEY_PREFIX_LENGTH)
+            .collect::<String>();
 
         Self {
             id: 0, // 数据库插入后会更新
@@ -73,6 +120,7 @@ impl Provider {
             is_active: true,
             first_seen_at: now.clone(),
             last_seen_at: now,
+            kind: ProviderKind::ApiKey,
         }
     }
 
@@ -110,8 +158,7 @@ pub struct ProviderStats {
     /// 今日费用（美元）
     pub today_cost_usd: f64,
 
-    /// 缓存命中率（0.0 - 1.0，表示百分比）
-    /// 计算公式：cache_read_tokens / (cache_read_tokens + input_tokens)
+    /// 缓存命中率（0.0 - 1.0，表示百分比），计算公式见 [`CacheHitRateFormula`]
     pub cache_hit_rate: f64,
 }
 
@@ -135,22 +182,41 @@ impl ProviderStats {
         }
     }
 
-    /// 计算并更新缓存命中率
-    ///
-    /// 业务逻辑说明：
-    /// 1. 检查是否有缓存读取或输入 Token
-    /// 2. 计算命中率：缓存读取 / (缓存读取 + 输入)
-    /// 3. 更新 cache_hit_rate 字段
-    pub fn update_cache_hit_rate(&mut self) {
-        let total_tokens = self.today_cache_read_tokens + self.today_input_tokens;
-        if total_tokens > 0 {
-            self.cache_hit_rate = self.today_cache_read_tokens as f64 / total_tokens as f64;
-        } else {
-            self.cache_hit_rate = 0.0;
-        }
+    /// 按指定公式计算并更新缓存命中率
+    pub fn update_cache_hit_rate(&mut self, formula: CacheHitRateFormula) {
+        self.cache_hit_rate = formula.rate(
+            self.today_cache_read_tokens,
+            self.today_input_tokens,
+            self.today_cache_creation_tokens,
+        );
     }
 }
 
+/// 供应商连通性检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderTestResult {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 实际请求的地址
+    pub endpoint: String,
+
+    /// 服务端返回 2xx 时为 true
+    pub ok: bool,
+
+    /// HTTP 状态码，网络层失败（DNS、超时、TLS 等）时为 None
+    pub status: Option<u16>,
+
+    /// 请求往返耗时（毫秒）
+    pub latency_ms: u64,
+
+    /// 失败原因
+    pub error: Option<String>,
+
+    /// 检测时间（ISO 8601 格式）
+    pub tested_at: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,7 +237,7 @@ mod tests {
 
         stats.today_cache_read_tokens = 300;
         stats.today_input_tokens = 700;
-        stats.update_cache_hit_rate();
+        stats.update_cache_hit_rate(CacheHitRateFormula::Strict);
 
         assert_eq!(stats.cache_hit_rate, 0.3);
     }
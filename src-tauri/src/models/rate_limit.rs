@@ -0,0 +1,63 @@
+//! @file rate_limit.rs
+//! @description 限流与过载事件数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 限流事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKind {
+    /// 触发速率限制（HTTP 429 / rate_limit_error）
+    RateLimited,
+
+    /// 服务过载（HTTP 529 / overloaded_error）
+    Overloaded,
+}
+
+impl RateLimitKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitKind::RateLimited => "rate_limited",
+            RateLimitKind::Overloaded => "overloaded",
+        }
+    }
+}
+
+/// JSONL 中解析出的单次限流事件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitEvent {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 事件类型
+    pub kind: RateLimitKind,
+
+    /// HTTP 状态码，日志中未给出时为 None
+    pub status_code: Option<i64>,
+
+    /// 错误信息
+    pub message: String,
+
+    /// 发生时间（ISO 8601 格式）
+    pub occurred_at: String,
+}
+
+/// 单个供应商的限流统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitStats {
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 供应商显示名称
+    pub display_name: Option<String>,
+
+    /// 速率限制次数
+    pub rate_limited_count: i64,
+
+    /// 服务过载次数
+    pub overloaded_count: i64,
+
+    /// 最近一次事件时间（ISO 8601 格式）
+    pub last_occurred_at: Option<String>,
+}
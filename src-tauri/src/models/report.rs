@@ -0,0 +1,158 @@
+//! @file report.rs
+//! @description 使用报告数据模型，包含报告汇总内容、报告类型与已生成报告文件信息
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AppInfo, DailyActivity, ModelUsage};
+
+/// 指定日期范围的使用报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// 开始日期（YYYY-MM-DD 格式，包含）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式，包含）
+    pub end_date: String,
+
+    /// 生成时间（ISO 8601 格式）
+    pub generated_at: String,
+
+    /// 输入 Token 总数
+    pub total_input_tokens: i64,
+
+    /// 输出 Token 总数
+    pub total_output_tokens: i64,
+
+    /// 缓存读取 Token 总数
+    pub total_cache_read_tokens: i64,
+
+    /// 缓存创建 Token 总数
+    pub total_cache_creation_tokens: i64,
+
+    /// 总费用（美元）
+    pub total_cost_usd: f64,
+
+    /// 总会话数
+    pub total_sessions: i64,
+
+    /// 总消息数
+    pub total_messages: i64,
+
+    /// 按模型汇总，按费用降序
+    pub models: Vec<ModelUsage>,
+
+    /// 每日使用量，按日期升序
+    pub daily: Vec<DailyActivity>,
+
+    /// 生成报告的应用信息
+    pub app_info: AppInfo,
+
+    /// 一句话用量摘要
+    pub summary_text: String,
+}
+
+impl UsageReport {
+    /// 计算总 Token 数
+    ///
+    /// # 返回
+    /// 输入 + 输出 Token 总数
+    pub fn total_tokens(&self) -> i64 {
+        self.total_input_tokens + self.total_output_tokens
+    }
+}
+
+/// 摘要文本语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SummaryLocale {
+    /// 简体中文
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+
+    /// 英文
+    #[serde(rename = "en")]
+    En,
+}
+
+impl SummaryLocale {
+    /// 从语言标签解析（如 en、en-US、zh-CN），不支持的语言使用中文
+    pub fn from_tag(tag: &str) -> Self {
+        if tag.to_ascii_lowercase().starts_with("en") {
+            SummaryLocale::En
+        } else {
+            SummaryLocale::ZhCn
+        }
+    }
+}
+
+/// 定时报告类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportKind {
+    /// 周报（周一至周日）
+    Weekly,
+
+    /// 月报（自然月）
+    Monthly,
+}
+
+impl ReportKind {
+    /// 转换为报告文件名前缀
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportKind::Weekly => "weekly",
+            ReportKind::Monthly => "monthly",
+        }
+    }
+
+    /// 从报告文件名前缀解析
+    pub fn from_prefix(value: &str) -> Option<Self> {
+        match value {
+            "weekly" => Some(ReportKind::Weekly),
+            "monthly" => Some(ReportKind::Monthly),
+            _ => None,
+        }
+    }
+}
+
+/// 已生成的报告文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedReport {
+    /// 报告文件路径
+    pub path: String,
+
+    /// 开始日期（YYYY-MM-DD 格式）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式）
+    pub end_date: String,
+
+    /// 生成时间（ISO 8601 格式）
+    pub generated_at: String,
+
+    /// 一句话用量摘要
+    pub summary_text: String,
+}
+
+/// 报告目录中的定时报告文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportFile {
+    /// 报告类型
+    pub kind: ReportKind,
+
+    /// 文件名
+    pub file_name: String,
+
+    /// 报告文件路径
+    pub path: String,
+
+    /// 开始日期（YYYY-MM-DD 格式）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式）
+    pub end_date: String,
+
+    /// 文件大小（字节）
+    pub size_bytes: u64,
+}
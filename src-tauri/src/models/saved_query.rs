@@ -0,0 +1,110 @@
+//! @file saved_query.rs
+//! @description 自定义查询与仪表板卡片数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 只读查询最多返回的行数，超出部分截断
+pub const MAX_QUERY_ROWS: usize = 1000;
+
+/// 只读查询的最长执行时间（秒），超时后中断查询并释放数据库连接
+pub const MAX_QUERY_SECONDS: u64 = 5;
+
+/// 自定义卡片的展示方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryVisualization {
+    /// 表格（默认）
+    #[default]
+    Table,
+
+    /// 单个数值，取首行首列
+    Number,
+
+    /// 折线图，首列为横轴
+    Line,
+
+    /// 柱状图，首列为横轴
+    Bar,
+}
+
+impl QueryVisualization {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryVisualization::Table => "table",
+            QueryVisualization::Number => "number",
+            QueryVisualization::Line => "line",
+            QueryVisualization::Bar => "bar",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Table 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "number" => QueryVisualization::Number,
+            "line" => QueryVisualization::Line,
+            "bar" => QueryVisualization::Bar,
+            _ => QueryVisualization::Table,
+        }
+    }
+}
+
+/// 已保存的自定义查询
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    /// 查询 ID
+    pub id: i64,
+
+    /// 名称（唯一），作为卡片标题
+    pub name: String,
+
+    /// 只读 SQL（单条 SELECT / WITH 语句）
+    pub sql: String,
+
+    /// 展示方式
+    pub visualization: QueryVisualization,
+
+    /// 创建时间（ISO 8601 格式）
+    pub created_at: String,
+
+    /// 更新时间（ISO 8601 格式）
+    pub updated_at: String,
+}
+
+/// 只读查询结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryResult {
+    /// 列名
+    pub columns: Vec<String>,
+
+    /// 行数据，与 columns 一一对应
+    pub rows: Vec<Vec<serde_json::Value>>,
+
+    /// 结果是否超过 MAX_QUERY_ROWS 被截断
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visualization_round_trip() {
+        for visualization in [
+            QueryVisualization::Table,
+            QueryVisualization::Number,
+            QueryVisualization::Line,
+            QueryVisualization::Bar,
+        ] {
+            assert_eq!(
+                QueryVisualization::from_db(visualization.as_str()),
+                visualization
+            );
+        }
+        assert_eq!(
+            QueryVisualization::from_db("pie"),
+            QueryVisualization::Table
+        );
+    }
+}
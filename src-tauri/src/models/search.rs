@@ -0,0 +1,163 @@
+//! @file search.rs
+//! @description 消息搜索过滤条件与分页结果数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 默认每页条数
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+
+/// 每页条数上限
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+/// 消息搜索过滤条件
+///
+/// 所有过滤字段均为可选，未设置时不参与过滤
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MessageSearchFilters {
+    /// 模型名称（精确匹配）
+    pub model: Option<String>,
+
+    /// 供应商 ID
+    pub provider_id: Option<i64>,
+
+    /// 项目路径（精确匹配）
+    pub project: Option<String>,
+
+    /// 开始日期（YYYY-MM-DD 格式，本地时间，包含）
+    pub start_date: Option<String>,
+
+    /// 结束日期（YYYY-MM-DD 格式，本地时间，包含）
+    pub end_date: Option<String>,
+
+    /// 最小 Token 数（输入 + 输出）
+    pub min_tokens: Option<i64>,
+
+    /// 页码（从 1 开始）
+    pub page: Option<i64>,
+
+    /// 每页条数
+    pub page_size: Option<i64>,
+}
+
+impl MessageSearchFilters {
+    /// 规范化后的页码，最小为 1
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    /// 规范化后的每页条数，限制在 1..=MAX_PAGE_SIZE
+    pub fn page_size(&self) -> i64 {
+        self.page_size
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// 当前页的偏移量
+    pub fn offset(&self) -> i64 {
+        (self.page() - 1) * self.page_size()
+    }
+}
+
+/// 核心统计命令的过滤条件
+///
+/// 所有字段均为可选，全部未设置时统计结果与全局汇总一致
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatsFilters {
+    /// 供应商 ID
+    pub provider_id: Option<i64>,
+
+    /// 模型名称（精确匹配）
+    pub model: Option<String>,
+
+    /// 项目路径（精确匹配）
+    pub project: Option<String>,
+}
+
+impl StatsFilters {
+    /// 是否未设置任何过滤条件
+    pub fn is_empty(&self) -> bool {
+        self.provider_id.is_none() && self.model.is_none() && self.project.is_none()
+    }
+}
+
+/// 搜索结果中的单条消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredMessage {
+    /// 记录 ID
+    pub id: i64,
+
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 消息 ID
+    pub message_id: String,
+
+    /// 使用模型名称
+    pub model: String,
+
+    /// 项目路径
+    pub project: Option<String>,
+
+    /// 输入 Token
+    pub input_tokens: i64,
+
+    /// 输出 Token
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token
+    pub cache_creation_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 创建时间（ISO 8601）
+    pub created_at: String,
+}
+
+/// 分页搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageSearchPage {
+    /// 当前页消息
+    pub items: Vec<StoredMessage>,
+
+    /// 满足条件的消息总数
+    pub total: i64,
+
+    /// 当前页码
+    pub page: i64,
+
+    /// 每页条数
+    pub page_size: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters_pagination_normalized() {
+        let filters = MessageSearchFilters {
+            page: Some(0),
+            page_size: Some(10_000),
+            ..Default::default()
+        };
+        assert_eq!(filters.page(), 1);
+        assert_eq!(filters.page_size(), MAX_PAGE_SIZE);
+        assert_eq!(filters.offset(), 0);
+
+        let filters = MessageSearchFilters {
+            page: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(filters.offset(), 2 * DEFAULT_PAGE_SIZE);
+    }
+}
@@ -0,0 +1,93 @@
+//! @file service_tier.rs
+//! @description API 服务层级数据模型，不同层级按不同倍率计费
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// Batch API 相对标准价格的费用倍率（五折）
+pub const BATCH_COST_MULTIPLIER: f64 = 0.5;
+
+/// Priority 层级相对标准价格的费用倍率
+pub const PRIORITY_COST_MULTIPLIER: f64 = 1.25;
+
+/// 服务层级，对应 usage 中的 service_tier 字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceTier {
+    /// 标准层级
+    #[default]
+    Standard,
+
+    /// 优先层级，按溢价计费
+    Priority,
+
+    /// Batch API，按折扣计费
+    Batch,
+}
+
+impl ServiceTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceTier::Standard => "standard",
+            ServiceTier::Priority => "priority",
+            ServiceTier::Batch => "batch",
+        }
+    }
+
+    /// 从数据库或日志中的字符串解析，未知值按标准层级处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "priority" => ServiceTier::Priority,
+            "batch" => ServiceTier::Batch,
+            _ => ServiceTier::Standard,
+        }
+    }
+
+    /// 相对标准价格的费用倍率
+    pub fn cost_multiplier(&self) -> f64 {
+        match self {
+            ServiceTier::Standard => 1.0,
+            ServiceTier::Priority => PRIORITY_COST_MULTIPLIER,
+            ServiceTier::Batch => BATCH_COST_MULTIPLIER,
+        }
+    }
+}
+
+/// 单个服务层级的用量汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceTierUsage {
+    /// 服务层级
+    pub service_tier: ServiceTier,
+
+    /// 输入 Token 总数
+    pub input_tokens: i64,
+
+    /// 输出 Token 总数
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token 总数
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 总数
+    pub cache_creation_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 消息数
+    pub message_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_tier_from_db() {
+        assert_eq!(ServiceTier::from_db("batch"), ServiceTier::Batch);
+        assert_eq!(ServiceTier::from_db("priority"), ServiceTier::Priority);
+        assert_eq!(ServiceTier::from_db("standard"), ServiceTier::Standard);
+        assert_eq!(ServiceTier::from_db("auto"), ServiceTier::Standard);
+        assert_eq!(ServiceTier::Batch.cost_multiplier(), 0.5);
+    }
+}
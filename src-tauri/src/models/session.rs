@@ -0,0 +1,195 @@
+//! @file session.rs
+//! @description 会话相关数据模型，包含会话汇总、会话明细、排行排序方式与会话标题来源
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::StoredMessage;
+
+/// 会话排行默认返回条数
+pub const DEFAULT_TOP_SESSIONS_LIMIT: i64 = 10;
+
+/// 会话排行排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionOrder {
+    /// 按费用降序
+    #[default]
+    Cost,
+
+    /// 按 Token 总数（输入 + 输出）降序
+    Tokens,
+}
+
+/// 会话标题来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionTitleSource {
+    /// Claude Code 生成的 summary 条目
+    Summary,
+
+    /// 会话中的首条用户输入
+    Prompt,
+}
+
+impl SessionTitleSource {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionTitleSource::Summary => "summary",
+            SessionTitleSource::Prompt => "prompt",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Prompt 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "summary" => SessionTitleSource::Summary,
+            _ => SessionTitleSource::Prompt,
+        }
+    }
+}
+
+/// 会话汇总
+///
+/// 单个供应商下单个会话在统计范围内的累计使用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 会话标题（summary 或首条用户输入），未解析到时为 None
+    pub title: Option<String>,
+
+    /// 项目路径（Claude Code 记录中的 cwd 或项目目录名）
+    pub project: Option<String>,
+
+    /// 会话中使用过的模型
+    pub models: Vec<String>,
+
+    /// 输入 Token 总数
+    pub input_tokens: i64,
+
+    /// 输出 Token 总数
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token 总数
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 总数
+    pub cache_creation_tokens: i64,
+
+    /// 累计费用（美元）
+    pub cost_usd: f64,
+
+    /// 消息数
+    pub message_count: i64,
+
+    /// 首条消息时间（ISO 8601 格式）
+    pub first_message_at: String,
+
+    /// 末条消息时间（ISO 8601 格式）
+    pub last_message_at: String,
+}
+
+impl SessionSummary {
+    /// 计算总 Token 数
+    ///
+    /// # 返回
+    /// 输入 + 输出 Token 总数
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// 会话上下文窗口占用
+///
+/// 每次请求都会携带完整对话历史，因此最新一条消息的输入与缓存 Token 之和即当前上下文大小
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionContextUsage {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 供应商 ID
+    pub provider_id: i64,
+
+    /// 最新消息使用的模型
+    pub model: String,
+
+    /// 当前上下文 Token 数（输入 + 缓存读取 + 缓存创建）
+    pub context_tokens: i64,
+
+    /// 模型上下文窗口大小（Token）
+    pub context_window_tokens: i64,
+
+    /// 占用比例（0.0 - 1.0，超出窗口时可能大于 1.0）
+    pub usage_ratio: f64,
+
+    /// 是否接近上下文上限
+    pub near_limit: bool,
+
+    /// 最新消息时间（ISO 8601 格式）
+    pub last_message_at: String,
+}
+
+/// 单条消息的缓存经济性
+///
+/// 对比按输入价格计费的假设费用，衡量该次请求从提示缓存中获得的收益
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheEconomics {
+    /// 缓存后的有效输入单价（美元 / 百万 Token，按输入、缓存读取与缓存创建 Token 加权）
+    pub effective_input_price_per_million: f64,
+
+    /// 缓存写入相对输入价格多付的费用（美元）
+    pub cache_write_premium_usd: f64,
+
+    /// 缓存读取相对输入价格节省的费用（美元）
+    pub cache_read_savings_usd: f64,
+
+    /// 净节省（读取节省 - 写入溢价，美元），为负表示缓存未回本
+    pub net_savings_usd: f64,
+}
+
+/// 会话明细中的单条消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMessageDetail {
+    #[serde(flatten)]
+    pub message: StoredMessage,
+
+    /// 缓存经济性，未知模型为 None
+    pub cache_economics: Option<CacheEconomics>,
+}
+
+/// 会话明细
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDetail {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 会话标题，未解析到时为 None
+    pub title: Option<String>,
+
+    /// 消息，按时间升序
+    pub messages: Vec<SessionMessageDetail>,
+
+    /// 会话累计缓存写入溢价（美元）
+    pub cache_write_premium_usd: f64,
+
+    /// 会话累计净节省（美元）
+    pub net_savings_usd: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_title_source_round_trip() {
+        for source in [SessionTitleSource::Summary, SessionTitleSource::Prompt] {
+            assert_eq!(SessionTitleSource::from_db(source.as_str()), source);
+        }
+    }
+}
@@ -0,0 +1,810 @@
+//! @file settings.rs
+//! @description 应用设置数据模型，各设置项以 JSON 形式存储在 app_settings 表中
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// 可持久化的设置项
+///
+/// 每个设置项对应 app_settings 表中的一行，KEY 为行主键；
+/// 缺失或无法解析时使用 Default 值
+pub trait AppSetting: Serialize + DeserializeOwned + Default {
+    /// 设置项存储键
+    const KEY: &'static str;
+}
+
+/// 定时报告设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportScheduleSettings {
+    /// 每周一生成上一周（周一至周日）的报告
+    pub weekly: bool,
+
+    /// 每月 1 日生成上一个自然月的报告
+    pub monthly: bool,
+
+    /// 生成报告后发送系统通知
+    pub notify: bool,
+}
+
+impl AppSetting for ReportScheduleSettings {
+    const KEY: &'static str = "report_schedule";
+}
+
+/// 本地 REST API 默认端口
+pub const DEFAULT_API_PORT: u16 = 17321;
+
+/// 本地 WebSocket 事件流默认端口
+pub const DEFAULT_EVENT_STREAM_PORT: u16 = 17322;
+
+/// 本地只读 REST API 设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiServerSettings {
+    /// 是否启用
+    pub enabled: bool,
+
+    /// 监听端口
+    pub port: u16,
+
+    /// 是否允许其他机器访问（监听 0.0.0.0），否则仅监听 127.0.0.1
+    pub allow_remote: bool,
+
+    /// Bearer Token，为空时启用服务会自动生成
+    pub token: String,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_API_PORT,
+            allow_remote: false,
+            token: String::new(),
+        }
+    }
+}
+
+impl AppSetting for ApiServerSettings {
+    const KEY: &'static str = "api_server";
+}
+
+/// 本地 WebSocket 事件流设置
+///
+/// 事件流仅监听 127.0.0.1，供同机的终端组件订阅
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventStreamSettings {
+    /// 是否启用
+    pub enabled: bool,
+
+    /// 监听端口
+    pub port: u16,
+
+    /// 订阅 Token，为空时启用服务会自动生成
+    pub token: String,
+}
+
+impl Default for EventStreamSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_EVENT_STREAM_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+impl AppSetting for EventStreamSettings {
+    const KEY: &'static str = "event_stream";
+}
+
+/// 悬浮窗默认快捷键
+pub const DEFAULT_OVERLAY_SHORTCUT: &str = "CommandOrControl+Shift+U";
+
+/// 迷你悬浮窗设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    /// 是否注册全局快捷键
+    pub enabled: bool,
+
+    /// 切换悬浮窗的全局快捷键（如 `CommandOrControl+Shift+U`）
+    pub shortcut: String,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shortcut: DEFAULT_OVERLAY_SHORTCUT.to_string(),
+        }
+    }
+}
+
+impl AppSetting for OverlaySettings {
+    const KEY: &'static str = "overlay";
+}
+
+/// 菜单栏模式设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MenuBarSettings {
+    /// 仅在菜单栏 / 托盘中运行：不显示 Dock 图标，主窗口从托盘图标弹出
+    pub menu_bar_only: bool,
+}
+
+impl AppSetting for MenuBarSettings {
+    const KEY: &'static str = "menu_bar";
+}
+
+/// 托盘图标显示的指标
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconMetric {
+    /// 应用默认图标
+    Static,
+
+    /// 今日费用，如 "$4.2"
+    #[default]
+    TodayCost,
+
+    /// 今日 Token 用量，如 "1.2M"
+    TodayTokens,
+
+    /// 今日消息数
+    TodayMessages,
+}
+
+/// 托盘图标设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrayIconSettings {
+    /// 托盘图标上绘制的指标
+    pub metric: TrayIconMetric,
+}
+
+impl AppSetting for TrayIconSettings {
+    const KEY: &'static str = "tray_icon";
+}
+
+/// Dock 角标显示内容
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DockBadgeContent {
+    /// 今日费用
+    #[default]
+    TodayCost,
+
+    /// 今日剩余预算，未配置每日预算时显示今日费用
+    RemainingBudget,
+}
+
+/// macOS Dock 角标设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DockBadgeSettings {
+    /// 是否在 Dock 图标上显示角标
+    pub enabled: bool,
+
+    /// 角标显示内容
+    pub content: DockBadgeContent,
+}
+
+impl AppSetting for DockBadgeSettings {
+    const KEY: &'static str = "dock_badge";
+}
+
+/// 更新渠道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// 正式版
+    #[default]
+    Stable,
+
+    /// 测试版，可提前获得日志格式变更的解析修复
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+}
+
+/// 自动更新设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateSettings {
+    /// 更新渠道
+    pub channel: UpdateChannel,
+
+    /// 启动时及每隔一段时间自动检查更新
+    pub auto_check: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            auto_check: true,
+        }
+    }
+}
+
+impl AppSetting for UpdateSettings {
+    const KEY: &'static str = "updater";
+}
+
+/// 消息去重策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupePolicy {
+    /// 全局去重：同一条消息已记录在任一供应商下时不再记录
+    ///
+    /// 避免切换供应商后重新扫描的 JSONL 行被记到新供应商名下导致总量翻倍
+    #[default]
+    Global,
+
+    /// 按供应商去重：同一条消息可分别记录在不同供应商下
+    PerProvider,
+}
+
+/// 消息去重设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupeSettings {
+    /// 去重策略
+    pub policy: DedupePolicy,
+}
+
+impl AppSetting for DedupeSettings {
+    const KEY: &'static str = "dedupe";
+}
+
+/// 缓存命中率计算公式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheHitRateFormula {
+    /// 缓存读取 / (缓存读取 + 输入)
+    #[default]
+    Strict,
+
+    /// 缓存读取 / (缓存读取 + 输入 + 缓存创建)，缓存创建也计入未命中
+    Inclusive,
+}
+
+impl CacheHitRateFormula {
+    /// 按公式计算缓存命中率（0.0 - 1.0），分母为 0 时返回 0.0
+    pub fn rate(
+        &self,
+        cache_read_tokens: i64,
+        input_tokens: i64,
+        cache_creation_tokens: i64,
+    ) -> f64 {
+        let total_tokens = match self {
+            CacheHitRateFormula::Strict => cache_read_tokens + input_tokens,
+            CacheHitRateFormula::Inclusive => {
+                cache_read_tokens + input_tokens + cache_creation_tokens
+            }
+        };
+        if total_tokens > 0 {
+            cache_read_tokens as f64 / total_tokens as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// 缓存命中率设置，统一作用于总览、今日、供应商与模型统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheHitRateSettings {
+    /// 计算公式
+    pub formula: CacheHitRateFormula,
+}
+
+impl AppSetting for CacheHitRateSettings {
+    const KEY: &'static str = "cache_hit_rate";
+}
+
+/// API Key 前缀默认保留的字符数，也是可保留的最大字符数
+pub const DEFAULT_API_KEY_PREFIX_LENGTH: usize = 8;
+
+/// 隐私设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacySettings {
+    /// 供应商 API Key 前缀保留的字符数，0 表示完全隐藏
+    ///
+    /// 只能缩短：原始 Key 不落库，已截短的前缀无法恢复
+    pub api_key_prefix_length: usize,
+}
+
+impl PrivacySettings {
+    /// 按设置截短 API Key 前缀
+    pub fn mask_api_key_prefix(&self, prefix: &str) -> String {
+        prefix
+            .chars()
+            .take(
+                self.api_key_prefix_length
+                    .min(DEFAULT_API_KEY_PREFIX_LENGTH),
+            )
+            .collect()
+    }
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            api_key_prefix_length: DEFAULT_API_KEY_PREFIX_LENGTH,
+        }
+    }
+}
+
+impl AppSetting for PrivacySettings {
+    const KEY: &'static str = "privacy";
+}
+
+/// 官方用量默认拉取间隔（分钟）
+pub const DEFAULT_ADMIN_API_INTERVAL_MINUTES: u32 = 360;
+
+/// 官方用量默认回溯天数
+pub const DEFAULT_ADMIN_API_LOOKBACK_DAYS: u32 = 7;
+
+/// Anthropic Admin API 官方用量拉取设置
+///
+/// Admin API Key 保存在系统钥匙串中，不属于本设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdminApiSettings {
+    /// 是否启用定时拉取
+    pub enabled: bool,
+
+    /// 拉取间隔（分钟）
+    pub interval_minutes: u32,
+
+    /// 每次拉取最近多少天（含今天）的数据，覆盖官方数据的延迟修正
+    pub lookback_days: u32,
+}
+
+impl Default for AdminApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: DEFAULT_ADMIN_API_INTERVAL_MINUTES,
+            lookback_days: DEFAULT_ADMIN_API_LOOKBACK_DAYS,
+        }
+    }
+}
+
+impl AppSetting for AdminApiSettings {
+    const KEY: &'static str = "admin_api";
+}
+
+/// 区块用量预警默认百分比
+pub const DEFAULT_BLOCK_WARNING_THRESHOLDS: [u32; 2] = [80, 95];
+
+/// 5 小时区块用量预警设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockWarningSettings {
+    /// 是否启用预警通知
+    pub enabled: bool,
+
+    /// 预警百分比，每个区块内每个百分比只通知一次
+    pub thresholds_percent: Vec<u32>,
+
+    /// 区块容量（Token 总数），未配置时从历史区块学习
+    pub capacity_tokens: Option<i64>,
+}
+
+impl Default for BlockWarningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            thresholds_percent: DEFAULT_BLOCK_WARNING_THRESHOLDS.to_vec(),
+            capacity_tokens: None,
+        }
+    }
+}
+
+impl AppSetting for BlockWarningSettings {
+    const KEY: &'static str = "block_warning";
+}
+
+/// 异常大输入消息默认的中位数倍数
+pub const DEFAULT_OUTLIER_MULTIPLE: f64 = 5.0;
+
+/// 异常大输入消息默认的最小输入 Token 数
+pub const DEFAULT_OUTLIER_MIN_PROMPT_TOKENS: i64 = 20_000;
+
+/// 异常大输入消息检测设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutlierSettings {
+    /// 新输入 Token 超过中位数的该倍数视为异常
+    pub multiple: f64,
+
+    /// 新输入 Token 低于该值的消息不视为异常，避免中位数很小时误报
+    pub min_prompt_tokens: i64,
+
+    /// 检测到新的异常消息时发送通知
+    pub notify: bool,
+}
+
+impl Default for OutlierSettings {
+    fn default() -> Self {
+        Self {
+            multiple: DEFAULT_OUTLIER_MULTIPLE,
+            min_prompt_tokens: DEFAULT_OUTLIER_MIN_PROMPT_TOKENS,
+            notify: false,
+        }
+    }
+}
+
+impl AppSetting for OutlierSettings {
+    const KEY: &'static str = "outliers";
+}
+
+/// 回收站条目默认保留天数
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// 回收站设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrashSettings {
+    /// 删除后保留的天数，超过后在启动时彻底清除
+    pub retention_days: u32,
+}
+
+impl Default for TrashSettings {
+    fn default() -> Self {
+        Self {
+            retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+        }
+    }
+}
+
+impl AppSetting for TrashSettings {
+    const KEY: &'static str = "trash";
+}
+
+/// 钥匙串设置，每个数据库（档案、演示数据库）各自保存
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeychainSettings {
+    /// 供应商凭证在钥匙串中的账户名前缀，首次使用时随机生成
+    pub namespace: Option<String>,
+
+    /// 是否已接管旧版本未区分数据库保存的凭证（只对默认档案进行）
+    pub legacy_adopted: bool,
+}
+
+impl AppSetting for KeychainSettings {
+    const KEY: &'static str = "keychain";
+}
+
+/// 应用锁默认的空闲自动锁定时间（分钟）
+pub const DEFAULT_APP_LOCK_IDLE_MINUTES: u32 = 5;
+
+/// 应用锁口令最短长度
+pub const MIN_APP_LOCK_PASSCODE_LEN: usize = 4;
+
+/// 应用锁设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppLockSettings {
+    /// 是否启用，启用后解锁前拒绝数据命令
+    pub enabled: bool,
+
+    /// 口令的 argon2 哈希（PHC 字符串），不保存明文
+    pub passcode_hash: String,
+
+    /// 无操作多少分钟后自动锁定
+    pub idle_timeout_minutes: u32,
+}
+
+impl Default for AppLockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            passcode_hash: String::new(),
+            idle_timeout_minutes: DEFAULT_APP_LOCK_IDLE_MINUTES,
+        }
+    }
+}
+
+impl AppSetting for AppLockSettings {
+    const KEY: &'static str = "app_lock";
+}
+
+/// 应用锁状态（不含口令哈希），供前端展示
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+
+    /// 当前是否已锁定
+    pub locked: bool,
+
+    pub idle_timeout_minutes: u32,
+}
+
+/// 每日预算默认的预警百分比
+pub const DEFAULT_BUDGET_WARNING_PERCENT: u32 = 80;
+
+/// 每日预算设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetSettings {
+    /// 每日预算（美元），未配置时不检查预算
+    pub daily_budget_usd: Option<f64>,
+
+    /// 今日费用达到预算的该百分比后进入预警状态
+    pub warning_percent: u32,
+}
+
+impl Default for BudgetSettings {
+    fn default() -> Self {
+        Self {
+            daily_budget_usd: None,
+            warning_percent: DEFAULT_BUDGET_WARNING_PERCENT,
+        }
+    }
+}
+
+impl AppSetting for BudgetSettings {
+    const KEY: &'static str = "budget";
+}
+
+/// 指标推送默认间隔（秒）
+pub const DEFAULT_METRICS_EXPORT_INTERVAL_SECONDS: u32 = 60;
+
+/// 指标推送默认 measurement 名称
+pub const DEFAULT_METRICS_MEASUREMENT: &str = "claude_usage";
+
+/// 推送失败时最多缓存的数据行数，超出后丢弃最旧的数据
+pub const DEFAULT_METRICS_MAX_BUFFERED_LINES: usize = 10_000;
+
+/// InfluxDB / VictoriaMetrics 行协议指标推送设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsExportSettings {
+    /// 是否启用推送
+    pub enabled: bool,
+
+    /// 写入地址，如 http://localhost:8086/api/v2/write?org=home&bucket=claude
+    /// 或 http://localhost:8428/write
+    pub url: String,
+
+    /// 完整的 Authorization 请求头，如 `Token xxx`、`Bearer xxx`，为空时不发送
+    pub authorization: String,
+
+    /// measurement 名称
+    pub measurement: String,
+
+    /// 推送间隔（秒），每次推送该时间段内的用量
+    pub interval_seconds: u32,
+
+    /// 推送失败时最多缓存的数据行数
+    pub max_buffered_lines: usize,
+}
+
+impl Default for MetricsExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            authorization: String::new(),
+            measurement: DEFAULT_METRICS_MEASUREMENT.to_string(),
+            interval_seconds: DEFAULT_METRICS_EXPORT_INTERVAL_SECONDS,
+            max_buffered_lines: DEFAULT_METRICS_MAX_BUFFERED_LINES,
+        }
+    }
+}
+
+impl AppSetting for MetricsExportSettings {
+    const KEY: &'static str = "metrics_export";
+}
+
+/// OTLP 指标推送默认间隔（秒）
+pub const DEFAULT_OTLP_EXPORT_INTERVAL_SECONDS: u32 = 60;
+
+/// OTLP 资源属性 service.name 的默认值
+pub const DEFAULT_OTLP_SERVICE_NAME: &str = "claude-token-monitor";
+
+/// OpenTelemetry（OTLP/HTTP JSON）指标推送设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtlpExportSettings {
+    /// 是否启用推送
+    pub enabled: bool,
+
+    /// 指标接收地址，如 http://localhost:4318/v1/metrics
+    pub endpoint: String,
+
+    /// 附加请求头，用于鉴权等
+    pub headers: BTreeMap<String, String>,
+
+    /// 资源属性 service.name
+    pub service_name: String,
+
+    /// 推送间隔（秒）
+    pub interval_seconds: u32,
+}
+
+impl Default for OtlpExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            headers: BTreeMap::new(),
+            service_name: DEFAULT_OTLP_SERVICE_NAME.to_string(),
+            interval_seconds: DEFAULT_OTLP_EXPORT_INTERVAL_SECONDS,
+        }
+    }
+}
+
+impl AppSetting for OtlpExportSettings {
+    const KEY: &'static str = "otlp_export";
+}
+
+/// 首次运行引导设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OnboardingSettings {
+    /// 引导已完成或已跳过
+    pub completed: bool,
+}
+
+impl AppSetting for OnboardingSettings {
+    const KEY: &'static str = "onboarding";
+}
+
+/// 订阅每周额度设置
+///
+/// 重置时间按本地时间计算；额度以 token 总数（输入、输出与缓存）计，未配置时只统计用量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanLimitSettings {
+    /// 每周重置的星期（0 = 周一 … 6 = 周日）
+    pub reset_weekday: u32,
+
+    /// 重置时刻（0 - 23 时）
+    pub reset_hour: u32,
+
+    /// Pro 套餐每周 token 上限
+    pub pro_weekly_token_limit: Option<i64>,
+
+    /// Max 套餐每周 token 上限
+    pub max_weekly_token_limit: Option<i64>,
+}
+
+impl AppSetting for PlanLimitSettings {
+    const KEY: &'static str = "plan_limits";
+}
+
+/// 云同步默认间隔（分钟）
+pub const DEFAULT_SYNC_INTERVAL_MINUTES: u32 = 60;
+
+/// 云同步远端对象默认路径
+pub const DEFAULT_SYNC_OBJECT_KEY: &str = "claude-token-monitor/snapshot.enc";
+
+/// 云同步存储类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncBackend {
+    #[default]
+    WebDav,
+    S3,
+}
+
+/// WebDAV 存储配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebDavConfig {
+    /// WebDAV 根地址，如 https://dav.example.com/remote.php/dav/files/me
+    pub url: String,
+
+    pub username: String,
+
+    /// 密码保存在系统钥匙串，同步时才填入，不写入数据库也不返回前端
+    #[serde(skip)]
+    pub password: String,
+}
+
+/// S3 兼容存储配置（AWS S3、MinIO、Cloudflare R2 等），使用路径风格访问
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    /// 服务地址，如 https://s3.us-east-1.amazonaws.com
+    pub endpoint: String,
+
+    /// 区域，如 us-east-1；R2 使用 auto
+    pub region: String,
+
+    pub bucket: String,
+
+    pub access_key_id: String,
+
+    /// 私有访问密钥保存在系统钥匙串，同步时才填入，不写入数据库也不返回前端
+    #[serde(skip)]
+    pub secret_access_key: String,
+}
+
+/// 云同步设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    /// 是否启用定时同步
+    pub enabled: bool,
+
+    /// 存储类型
+    pub backend: SyncBackend,
+
+    pub webdav: WebDavConfig,
+
+    pub s3: S3Config,
+
+    /// 远端快照对象路径
+    pub object_key: String,
+
+    /// 快照加密口令，各设备需保持一致；保存在系统钥匙串，同步时才填入，不写入数据库也不返回前端
+    #[serde(skip)]
+    pub passphrase: String,
+
+    /// 同步间隔（分钟）
+    pub interval_minutes: u32,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: SyncBackend::default(),
+            webdav: WebDavConfig::default(),
+            s3: S3Config::default(),
+            object_key: DEFAULT_SYNC_OBJECT_KEY.to_string(),
+            passphrase: String::new(),
+            interval_minutes: DEFAULT_SYNC_INTERVAL_MINUTES,
+        }
+    }
+}
+
+impl AppSetting for SyncSettings {
+    const KEY: &'static str = "sync";
+}
+
+/// 保存在系统钥匙串中的云同步密钥
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncSecret {
+    WebDavPassword,
+    S3SecretAccessKey,
+    Passphrase,
+}
+
+impl SyncSecret {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncSecret::WebDavPassword => "webdav_password",
+            SyncSecret::S3SecretAccessKey => "s3_secret_access_key",
+            SyncSecret::Passphrase => "passphrase",
+        }
+    }
+}
+
+/// 云同步密钥是否已保存，前端只获取该状态而不获取密钥本身
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncSecretStatus {
+    pub webdav_password: bool,
+    pub s3_secret_access_key: bool,
+    pub passphrase: bool,
+}
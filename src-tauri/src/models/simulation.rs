@@ -0,0 +1,60 @@
+//! @file simulation.rs
+//! @description 模型费用模拟数据模型，描述将历史用量换用其他模型计价后的费用差异
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 单个模型的模拟结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCostSimulation {
+    /// 实际使用的模型
+    pub model: String,
+
+    /// 模拟换用的模型，未映射时与 model 相同
+    pub target_model: String,
+
+    pub input_tokens: i64,
+
+    pub output_tokens: i64,
+
+    pub cache_read_tokens: i64,
+
+    pub cache_creation_tokens: i64,
+
+    pub message_count: i64,
+
+    /// 按实际模型计价的费用（美元）
+    pub original_cost_usd: f64,
+
+    /// 按目标模型计价的费用（美元）
+    pub simulated_cost_usd: f64,
+
+    /// 模拟费用 - 实际费用（美元），为负表示换用后更便宜
+    pub delta_usd: f64,
+}
+
+/// 费用模拟结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostSimulation {
+    /// 开始日期（YYYY-MM-DD 格式，包含）
+    pub start_date: String,
+
+    /// 结束日期（YYYY-MM-DD 格式，包含）
+    pub end_date: String,
+
+    pub original_cost_usd: f64,
+
+    pub simulated_cost_usd: f64,
+
+    /// 模拟费用 - 实际费用（美元）
+    pub delta_usd: f64,
+
+    /// 费用变化比例（相对实际费用），实际费用为 0 时为 None
+    pub delta_ratio: Option<f64>,
+
+    /// 按模型明细，按实际费用降序
+    pub models: Vec<ModelCostSimulation>,
+
+    /// 没有价格配置的模型，这些模型按记录的费用计入且不参与换算
+    pub unpriced_models: Vec<String>,
+}
@@ -0,0 +1,160 @@
+//! @file snapshot.rs
+//! @description 数据快照模型，用于多台设备之间导出、导入并合并历史数据
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::{DateNote, ProviderKind, ServiceTier};
+
+/// 数据快照
+///
+/// 各表数据以稳定标识互相引用：供应商使用 api_key_hash，标签使用名称，
+/// 不依赖各设备数据库中的自增 ID。daily_stats 等汇总表可由消息记录重建，不包含在快照中；
+/// 应用设置属于单台设备，同样不导出
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snapshot {
+    /// 快照格式版本
+    pub format_version: u32,
+
+    /// 导出时的数据库迁移版本
+    pub schema_version: i64,
+
+    /// 导出时间（ISO 8601 格式）
+    pub exported_at: String,
+
+    pub providers: Vec<SnapshotProvider>,
+    pub messages: Vec<SnapshotMessage>,
+    pub sessions: Vec<SnapshotSession>,
+    pub provider_plans: Vec<SnapshotProviderPlan>,
+    pub provider_switches: Vec<SnapshotProviderSwitch>,
+    pub rate_limit_events: Vec<SnapshotRateLimitEvent>,
+    pub api_errors: Vec<SnapshotApiError>,
+    pub tags: Vec<SnapshotTag>,
+    pub date_notes: Vec<DateNote>,
+}
+
+/// 快照中的供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotProvider {
+    pub api_key_hash: String,
+    pub api_key_prefix: String,
+    pub display_name: Option<String>,
+    pub base_url: Option<String>,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+    #[serde(default)]
+    pub kind: ProviderKind,
+}
+
+/// 快照中的消息用量，provider 为供应商的 api_key_hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotMessage {
+    pub provider: String,
+    pub session_id: String,
+    pub message_id: String,
+    pub model: String,
+    pub project: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cost_usd: f64,
+    pub duration_ms: Option<i64>,
+    pub created_at: String,
+    /// 旧版快照没有该字段，导入时取 cost_usd
+    #[serde(default)]
+    pub api_equivalent_cost_usd: Option<f64>,
+    /// 旧版快照没有该字段，导入时取 0
+    #[serde(default)]
+    pub thinking_tokens: i64,
+    /// 旧版快照没有该字段，导入时取 standard
+    #[serde(default)]
+    pub service_tier: ServiceTier,
+}
+
+/// 快照中的会话标题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotSession {
+    pub session_id: String,
+    pub title: String,
+    pub title_source: String,
+    pub updated_at: String,
+}
+
+/// 快照中的供应商套餐
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotProviderPlan {
+    pub provider: String,
+    pub plan_type: String,
+    pub monthly_fee_usd: f64,
+    pub updated_at: String,
+}
+
+/// 快照中的供应商切换记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotProviderSwitch {
+    pub provider: String,
+    pub switched_at: String,
+}
+
+/// 快照中的限流事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRateLimitEvent {
+    pub provider: String,
+    pub session_id: String,
+    pub kind: String,
+    pub status_code: Option<i64>,
+    pub message: String,
+    pub occurred_at: String,
+}
+
+/// 快照中的 API 错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotApiError {
+    pub provider: String,
+    pub session_id: String,
+    pub kind: String,
+    pub status_code: Option<i64>,
+    pub message: String,
+    pub is_retry: bool,
+    pub occurred_at: String,
+}
+
+/// 快照中的标签及其关联的会话与日期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotTag {
+    pub name: String,
+    pub created_at: String,
+    pub session_ids: Vec<String>,
+    pub dates: Vec<String>,
+}
+
+/// 快照导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotExport {
+    /// 快照文件路径
+    pub path: String,
+
+    /// 导出的消息数
+    pub message_count: usize,
+
+    /// 导出时间（ISO 8601 格式）
+    pub exported_at: String,
+}
+
+/// 快照导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotImportSummary {
+    /// 新增的供应商数
+    pub providers_added: usize,
+
+    /// 新增的消息数
+    pub messages_added: usize,
+
+    /// 本地已存在而跳过的消息数
+    pub messages_skipped: usize,
+
+    /// 新增或更新的其他记录数（会话标题、套餐、事件、标签、备注等）
+    pub other_rows_merged: usize,
+}
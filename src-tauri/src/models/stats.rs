@@ -5,6 +5,8 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
+use crate::models::CacheHitRateFormula;
+
 /// 模型使用统计
 ///
 /// 单个模型（如 claude-3-opus）的累计使用数据和费用统计
@@ -30,6 +32,9 @@ pub struct ModelUsage {
 
     /// 消息调用次数
     pub message_count: i64,
+
+    /// 思考 Token 总数（已包含在输出 Token 中）
+    pub thinking_tokens: i64,
 }
 
 impl ModelUsage {
@@ -49,25 +54,23 @@ impl ModelUsage {
             cache_creation_tokens: 0,
             cost_usd: 0.0,
             message_count: 0,
+            thinking_tokens: 0,
         }
     }
 
     /// 计算缓存命中率
     ///
-    /// 业务逻辑说明：
-    /// 1. 计算总 Token 数（缓存读取 + 输入）
-    /// 2. 如果总数为 0，返回 0.0
-    /// 3. 否则返回缓存读取占比
+    /// # 参数
+    /// * `formula` - 缓存命中率计算公式
     ///
     /// # 返回
     /// 缓存命中率（0.0 - 1.0）
-    pub fn cache_hit_rate(&self) -> f64 {
-        let total_tokens = self.cache_read_tokens + self.input_tokens;
-        if total_tokens > 0 {
-            self.cache_read_tokens as f64 / total_tokens as f64
-        } else {
-            0.0
-        }
+    pub fn cache_hit_rate(&self, formula: CacheHitRateFormula) -> f64 {
+        formula.rate(
+            self.cache_read_tokens,
+            self.input_tokens,
+            self.cache_creation_tokens,
+        )
     }
 }
 
@@ -88,17 +91,20 @@ pub struct StatsCache {
     /// 总缓存创建 Token 数
     pub total_cache_creation_tokens: i64,
 
-    /// 总费用（美元）
+    /// 总费用（美元），订阅供应商的用量不计入
     pub total_cost_usd: f64,
 
+    /// 按 API 价格计算的等价总费用（美元），包含订阅供应商的用量
+    #[serde(default)]
+    pub total_api_equivalent_cost_usd: f64,
+
     /// 总会话数
     pub total_sessions: i64,
 
     /// 总消息数
     pub total_messages: i64,
 
-    /// 全局缓存命中率（0.0 - 1.0）
-    /// 计算公式：total_cache_read_tokens / (total_cache_read_tokens + total_input_tokens)
+    /// 全局缓存命中率（0.0 - 1.0），计算公式见 [`CacheHitRateFormula`]
     pub cache_hit_rate: f64,
 
     /// 按模型分组的使用统计
@@ -119,19 +125,40 @@ pub struct TodayStats {
     pub session_count: i64,
     pub message_count: i64,
     pub cache_hit_rate: f64,
+
+    /// 按 API 价格计算的等价费用（美元），包含订阅供应商的用量
+    #[serde(default)]
+    pub api_equivalent_cost_usd: f64,
 }
 
 impl TodayStats {
-    pub fn update_cache_hit_rate(&mut self) {
-        let total_tokens = self.cache_read_tokens + self.input_tokens;
-        if total_tokens > 0 {
-            self.cache_hit_rate = self.cache_read_tokens as f64 / total_tokens as f64;
-        } else {
-            self.cache_hit_rate = 0.0;
-        }
+    pub fn update_cache_hit_rate(&mut self, formula: CacheHitRateFormula) {
+        self.cache_hit_rate = formula.rate(
+            self.cache_read_tokens,
+            self.input_tokens,
+            self.cache_creation_tokens,
+        );
     }
 }
 
+/// 小组件轮询用的精简统计
+///
+/// 由内存中的统计缓存提供，不含模型明细，适合托盘 / 小组件亚秒级轮询
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuickStats {
+    /// 今日费用（美元）
+    pub today_cost_usd: f64,
+
+    /// 今日 Token 总数（输入 + 输出 + 缓存读取 + 缓存创建）
+    pub today_tokens: i64,
+
+    /// 当前 5 小时区块剩余分钟数，没有活跃区块时为 None
+    pub block_remaining_minutes: Option<i64>,
+
+    /// 当前活跃供应商名称（未设置显示名称时为 API Key 前缀）
+    pub active_provider: Option<String>,
+}
+
 impl Default for StatsCache {
     /// 创建默认的统计缓存
     ///
@@ -149,6 +176,7 @@ impl Default for StatsCache {
             total_cache_read_tokens: 0,
             total_cache_creation_tokens: 0,
             total_cost_usd: 0.0,
+            total_api_equivalent_cost_usd: 0.0,
             total_sessions: 0,
             total_messages: 0,
             cache_hit_rate: 0.0,
@@ -162,17 +190,14 @@ impl StatsCache {
     /// 更新全局缓存命中率
     ///
     /// 业务逻辑说明：
-    /// 1. 计算总 Token 数（缓存读取 + 输入）
-    /// 2. 如果总数为 0，设置命中率为 0
-    /// 3. 否则计算缓存读取占比
-    /// 4. 更新 updated_at 时间戳
-    pub fn update_cache_hit_rate(&mut self) {
-        let total_tokens = self.total_cache_read_tokens + self.total_input_tokens;
-        if total_tokens > 0 {
-            self.cache_hit_rate = self.total_cache_read_tokens as f64 / total_tokens as f64;
-        } else {
-            self.cache_hit_rate = 0.0;
-        }
+    /// 1. 按指定公式计算缓存读取占比
+    /// 2. 更新 updated_at 时间戳
+    pub fn update_cache_hit_rate(&mut self, formula: CacheHitRateFormula) {
+        self.cache_hit_rate = formula.rate(
+            self.total_cache_read_tokens,
+            self.total_input_tokens,
+            self.total_cache_creation_tokens,
+        );
         self.updated_at = Utc::now().to_rfc3339();
     }
 
@@ -197,6 +222,7 @@ impl StatsCache {
             existing.cache_creation_tokens += model_usage.cache_creation_tokens;
             existing.cost_usd += model_usage.cost_usd;
             existing.message_count += model_usage.message_count;
+            existing.thinking_tokens += model_usage.thinking_tokens;
         } else {
             self.models.push(model_usage);
         }
@@ -235,6 +261,14 @@ pub struct DailyActivity {
 
     /// 当天消息数
     pub message_count: i64,
+
+    /// 用户为当天添加的备注
+    #[serde(default)]
+    pub note: Option<String>,
+
+    /// 当天按 API 价格计算的等价费用（美元），包含订阅供应商的用量
+    #[serde(default)]
+    pub api_equivalent_cost_usd: f64,
 }
 
 impl DailyActivity {
@@ -253,6 +287,8 @@ impl DailyActivity {
             cost_usd: 0.0,
             session_count: 0,
             message_count: 0,
+            note: None,
+            api_equivalent_cost_usd: 0.0,
         }
     }
 
@@ -265,6 +301,61 @@ impl DailyActivity {
     }
 }
 
+/// 单个时间窗口的消耗速率
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurnRateWindow {
+    /// 窗口天数（不含今天）
+    pub window_days: i64,
+
+    /// 窗口内 Token 总数（输入 + 输出）
+    pub total_tokens: i64,
+
+    /// 窗口内费用（美元）
+    pub total_cost_usd: f64,
+
+    /// 平均每小时 Token 数
+    pub tokens_per_hour: f64,
+
+    /// 平均每天费用（美元）
+    pub cost_per_day: f64,
+}
+
+/// 消耗速率与月末预测
+///
+/// 结合历史窗口平均值与今日实时速度，预测本月月末总花费
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurnRate {
+    /// 今日 Token 总数（输入 + 输出）
+    pub today_tokens: i64,
+
+    /// 今日费用（美元）
+    pub today_cost_usd: f64,
+
+    /// 今日已过去的小时数
+    pub today_hours_elapsed: f64,
+
+    /// 今日每小时 Token 数
+    pub today_tokens_per_hour: f64,
+
+    /// 按今日速度推算的全天费用（美元）
+    pub today_projected_cost_usd: f64,
+
+    /// 历史窗口速率（如最近 7 天、30 天）
+    pub windows: Vec<BurnRateWindow>,
+
+    /// 本月截至目前的费用（美元）
+    pub month_to_date_cost_usd: f64,
+
+    /// 本月剩余天数（不含今天）
+    pub days_remaining_in_month: i64,
+
+    /// 预测的月末总费用（美元）
+    pub projected_month_end_cost_usd: f64,
+
+    /// 计算时间（ISO 8601 格式）
+    pub generated_at: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,7 +366,11 @@ mod tests {
         usage.cache_read_tokens = 400;
         usage.input_tokens = 600;
 
-        assert_eq!(usage.cache_hit_rate(), 0.4);
+        assert_eq!(usage.cache_hit_rate(CacheHitRateFormula::Strict), 0.4);
+
+        usage.cache_creation_tokens = 1000;
+        assert_eq!(usage.cache_hit_rate(CacheHitRateFormula::Strict), 0.4);
+        assert_eq!(usage.cache_hit_rate(CacheHitRateFormula::Inclusive), 0.2);
     }
 
     #[test]
@@ -299,6 +394,7 @@ mod tests {
             cache_creation_tokens: 0,
             cost_usd: 0.5,
             message_count: 1,
+            thinking_tokens: 0,
         };
 
         cache.add_or_update_model(usage1);
@@ -312,6 +408,7 @@ mod tests {
             cache_creation_tokens: 0,
             cost_usd: 1.0,
             message_count: 1,
+            thinking_tokens: 0,
         };
 
         cache.add_or_update_model(usage2);
@@ -329,8 +426,31 @@ mod tests {
             cost_usd: 2.5,
             session_count: 5,
             message_count: 20,
+            note: None,
+            api_equivalent_cost_usd: 2.5,
         };
 
         assert_eq!(activity.total_tokens(), 1500);
     }
 }
+
+/// 实时 Token 速率
+///
+/// 基于最近一段时间内采集到的消息计算，会话正在输出时反映当前速度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveRate {
+    /// 每分钟输出 Token 数
+    pub output_tokens_per_minute: f64,
+
+    /// 每分钟输入 Token 数（含缓存读取与创建）
+    pub input_tokens_per_minute: f64,
+
+    /// 统计窗口内的消息数
+    pub message_count: usize,
+
+    /// 统计窗口长度（秒）
+    pub window_seconds: i64,
+
+    /// 计算时间（ISO 8601 格式）
+    pub updated_at: String,
+}
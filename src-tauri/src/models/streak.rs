@@ -0,0 +1,48 @@
+//! @file streak.rs
+//! @description 使用连续天数与累计 Token 里程碑数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 累计 Token 里程碑
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenMilestone {
+    /// 里程碑阈值（输入 + 输出 Token 累计数）
+    pub threshold_tokens: i64,
+
+    /// 达成日期（YYYY-MM-DD 格式），未达成时为 None
+    pub reached_on: Option<String>,
+}
+
+/// 使用连续天数统计
+///
+/// 用于仪表板的游戏化面板，展示连续活跃天数与累计里程碑
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageStreaks {
+    /// 当前连续活跃天数（今天尚未使用时，截至昨天的连续天数仍然有效）
+    pub current_streak_days: i64,
+
+    /// 历史最长连续活跃天数
+    pub longest_streak_days: i64,
+
+    /// 最长连续区间开始日期
+    pub longest_streak_start: Option<String>,
+
+    /// 最长连续区间结束日期
+    pub longest_streak_end: Option<String>,
+
+    /// 累计活跃天数
+    pub total_active_days: i64,
+
+    /// 最近一次活跃日期
+    pub last_active_date: Option<String>,
+
+    /// 累计 Token 总数（输入 + 输出）
+    pub total_tokens: i64,
+
+    /// 里程碑列表（按阈值升序）
+    pub milestones: Vec<TokenMilestone>,
+
+    /// 下一个未达成的里程碑阈值
+    pub next_milestone_tokens: Option<i64>,
+}
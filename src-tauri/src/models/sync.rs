@@ -0,0 +1,20 @@
+//! @file sync.rs
+//! @description 云同步结果数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::SnapshotImportSummary;
+
+/// 一次云同步的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResult {
+    /// 远端快照的合并结果，远端尚无快照时为 None
+    pub pulled: Option<SnapshotImportSummary>,
+
+    /// 推送到远端的快照中的消息数
+    pub pushed_messages: usize,
+
+    /// 同步完成时间（ISO 8601 格式）
+    pub synced_at: String,
+}
@@ -0,0 +1,43 @@
+//! @file tag.rs
+//! @description 会话与日期标签数据模型，用于按客户 / 实验等维度分摊费用
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 标签的关联对象
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum TagTarget {
+    /// 会话 ID
+    Session(String),
+
+    /// 日期（YYYY-MM-DD 格式，本地日期）
+    Date(String),
+}
+
+/// 单个标签的用量统计
+///
+/// 标签关联会话中的消息与关联日期当天的消息合并计算，同一条消息只计一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagStats {
+    /// 标签 ID
+    pub tag_id: i64,
+
+    /// 标签名称
+    pub name: String,
+
+    /// 关联的会话数
+    pub tagged_session_count: i64,
+
+    /// 关联的日期数
+    pub tagged_day_count: i64,
+
+    /// 消息数
+    pub message_count: i64,
+
+    /// Token 总数（输入 + 输出）
+    pub total_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+}
@@ -0,0 +1,43 @@
+//! @file tool_call.rs
+//! @description 工具调用数据模型，用于统计 Agent 会话中各工具的调用频率
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// JSONL 中解析出的单次工具调用（assistant 消息中的 tool_use 块）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// 会话 ID
+    pub session_id: String,
+
+    /// 所属 assistant 消息 ID
+    pub message_id: String,
+
+    /// tool_use 块 ID，用于重复扫描时去重
+    pub tool_use_id: String,
+
+    /// 工具名称（如 Bash、Edit、Read，MCP 工具为 mcp__server__tool）
+    pub tool_name: String,
+
+    /// 调用时间（ISO 8601 格式）
+    pub called_at: String,
+}
+
+/// 单个工具的调用统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallStats {
+    /// 工具名称
+    pub tool_name: String,
+
+    /// 调用次数
+    pub call_count: i64,
+
+    /// 调用次数占全部工具调用的比例
+    pub share: f64,
+
+    /// 使用过该工具的会话数
+    pub session_count: i64,
+
+    /// 最近一次调用时间（ISO 8601 格式）
+    pub last_called_at: String,
+}
@@ -0,0 +1,64 @@
+//! @file trash.rs
+//! @description 回收站数据模型，被删除的数据在保留期内可恢复
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 回收站条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletedItemKind {
+    /// 供应商及其消息、汇总、套餐与事件
+    Provider,
+
+    /// 清理重复消息时删除的消息及其所属供应商
+    DuplicateMessages,
+}
+
+impl DeletedItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletedItemKind::Provider => "provider",
+            DeletedItemKind::DuplicateMessages => "duplicate_messages",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "duplicate_messages" => DeletedItemKind::DuplicateMessages,
+            _ => DeletedItemKind::Provider,
+        }
+    }
+}
+
+/// 回收站条目（不含被删除数据本身）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeletedItem {
+    pub id: i64,
+
+    pub kind: DeletedItemKind,
+
+    /// 被删除对象原来的 ID（如供应商 ID），重复消息条目为 0
+    pub source_id: i64,
+
+    /// 展示名称
+    pub label: String,
+
+    /// 随之删除的消息条数
+    pub message_count: i64,
+
+    /// 删除时间（ISO 8601 格式）
+    pub deleted_at: String,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestoredItem {
+    pub item: DeletedItem,
+
+    /// 恢复后对象的 ID，供应商恢复后可能与原 ID 不同
+    pub restored_id: i64,
+
+    /// 恢复的消息条数（已存在的消息不会重复写入）
+    pub messages_restored: usize,
+}
@@ -0,0 +1,199 @@
+//! @file trend.rs
+//! @description 每日趋势数据模型（按模型趋势与滚动平均序列）
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// 单个模型单日使用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDailyUsage {
+    /// 日期（YYYY-MM-DD 格式）
+    pub date: String,
+
+    /// 模型名称
+    pub model: String,
+
+    /// 输入 Token 数
+    pub input_tokens: i64,
+
+    /// 输出 Token 数
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token 数
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 数
+    pub cache_creation_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 消息数
+    pub message_count: i64,
+}
+
+/// 趋势序列中的单日数据点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelTrendPoint {
+    /// 日期（YYYY-MM-DD 格式）
+    pub date: String,
+
+    /// 输入 Token 数
+    pub input_tokens: i64,
+
+    /// 输出 Token 数
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token 数
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 数
+    pub cache_creation_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 消息数
+    pub message_count: i64,
+}
+
+impl ModelTrendPoint {
+    /// 创建指定日期的空数据点
+    pub fn new(date: String) -> Self {
+        Self {
+            date,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd: 0.0,
+            message_count: 0,
+        }
+    }
+}
+
+/// 单个模型在日期范围内的每日趋势
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelTrend {
+    /// 模型名称
+    pub model: String,
+
+    /// 范围内 Token 总数（输入 + 输出）
+    pub total_tokens: i64,
+
+    /// 范围内总费用（美元）
+    pub total_cost_usd: f64,
+
+    /// 每日数据点，覆盖范围内每一天（无数据的日期为 0）
+    pub points: Vec<ModelTrendPoint>,
+}
+
+/// 滚动平均窗口天数上限
+pub const MAX_ROLLING_WINDOW_DAYS: u32 = 90;
+
+/// 滚动平均序列中的单日数据点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingAveragePoint {
+    /// 日期（YYYY-MM-DD 格式）
+    pub date: String,
+
+    /// 当日费用（美元）
+    pub cost_usd: f64,
+
+    /// 当日 Token 数（输入 + 输出）
+    pub total_tokens: i64,
+
+    /// 截至当日的窗口内日均费用（美元），无数据的日期按 0 计入窗口
+    pub avg_cost_usd: f64,
+
+    /// 截至当日的窗口内日均 Token 数
+    pub avg_total_tokens: f64,
+}
+
+/// 将按模型、日期聚合的数据整理为各模型的连续每日序列
+///
+/// 业务逻辑说明：
+/// 1. 为每个模型生成 start_date 到 end_date 的完整日期序列
+/// 2. 填入已有数据，缺失日期保持为 0，方便前端对齐绘图
+/// 3. 结果按范围内总费用降序排列
+pub fn build_model_trends(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    rows: Vec<ModelDailyUsage>,
+) -> Vec<ModelTrend> {
+    let dates: Vec<String> = (0..=(end_date - start_date).num_days())
+        .map(|offset| (start_date + Duration::days(offset)).to_string())
+        .collect();
+
+    let mut trends: Vec<ModelTrend> = Vec::new();
+    for row in rows {
+        let index = match trends.iter().position(|trend| trend.model == row.model) {
+            Some(index) => index,
+            None => {
+                trends.push(ModelTrend {
+                    model: row.model.clone(),
+                    total_tokens: 0,
+                    total_cost_usd: 0.0,
+                    points: dates.iter().cloned().map(ModelTrendPoint::new).collect(),
+                });
+                trends.len() - 1
+            }
+        };
+
+        let trend = &mut trends[index];
+        if let Some(point) = trend.points.iter_mut().find(|point| point.date == row.date) {
+            point.input_tokens += row.input_tokens;
+            point.output_tokens += row.output_tokens;
+            point.cache_read_tokens += row.cache_read_tokens;
+            point.cache_creation_tokens += row.cache_creation_tokens;
+            point.cost_usd += row.cost_usd;
+            point.message_count += row.message_count;
+
+            trend.total_tokens += row.input_tokens + row.output_tokens;
+            trend.total_cost_usd += row.cost_usd;
+        }
+    }
+
+    trends.sort_by(|a, b| b.total_cost_usd.total_cmp(&a.total_cost_usd));
+    trends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(date: &str, model: &str, cost_usd: f64) -> ModelDailyUsage {
+        ModelDailyUsage {
+            date: date.to_string(),
+            model: model.to_string(),
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd,
+            message_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_build_model_trends() {
+        let start = NaiveDate::from_ymd_opt(2026, 10, 1).expect("date");
+        let end = NaiveDate::from_ymd_opt(2026, 10, 3).expect("date");
+        let rows = vec![
+            row("2026-10-01", "claude-3-haiku", 0.1),
+            row("2026-10-02", "claude-3-opus", 2.0),
+            row("2026-10-03", "claude-3-opus", 3.0),
+        ];
+
+        let trends = build_model_trends(start, end, rows);
+
+        assert_eq!(trends.len(), 2);
+        assert_eq!(trends[0].model, "claude-3-opus");
+        assert_eq!(trends[0].points.len(), 3);
+        assert_eq!(trends[0].points[0].cost_usd, 0.0);
+        assert_eq!(trends[0].points[2].cost_usd, 3.0);
+        assert_eq!(trends[0].total_tokens, 300);
+        assert_eq!(trends[1].points[0].message_count, 1);
+    }
+}
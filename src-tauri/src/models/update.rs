@@ -0,0 +1,26 @@
+//! @file update.rs
+//! @description 应用更新信息数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+use crate::models::UpdateChannel;
+
+/// 可用更新信息，作为 update-available 事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    /// 新版本号
+    pub version: String,
+
+    /// 当前版本号
+    pub current_version: String,
+
+    /// 所属更新渠道
+    pub channel: UpdateChannel,
+
+    /// 更新说明
+    pub notes: Option<String>,
+
+    /// 发布时间（RFC 3339 格式）
+    pub published_at: Option<String>,
+}
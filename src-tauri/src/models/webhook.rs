@@ -0,0 +1,229 @@
+//! @file webhook.rs
+//! @description Webhook 告警数据模型，预算阈值与费用异常触发时向 Slack / Discord 等目标推送 JSON
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 保留的投递记录条数，超出后删除最旧的记录
+pub const MAX_WEBHOOK_DELIVERIES: usize = 500;
+
+/// Webhook 请求体格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// Slack Incoming Webhook：`{"text": ...}`
+    Slack,
+
+    /// Discord Webhook：`{"content": ...}`
+    Discord,
+
+    /// 通用 JSON：完整的告警数据
+    Generic,
+}
+
+impl WebhookFormat {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookFormat::Slack => "slack",
+            WebhookFormat::Discord => "discord",
+            WebhookFormat::Generic => "generic",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Generic 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "slack" => WebhookFormat::Slack,
+            "discord" => WebhookFormat::Discord,
+            _ => WebhookFormat::Generic,
+        }
+    }
+}
+
+/// 触发 Webhook 推送的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// 今日费用达到每日预算的预警百分比
+    BudgetWarning,
+
+    /// 今日费用达到或超出每日预算
+    BudgetExceeded,
+
+    /// 今日费用异常
+    CostAnomaly,
+
+    /// 手动发送的测试消息
+    Test,
+}
+
+impl WebhookEvent {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::BudgetWarning => "budget_warning",
+            WebhookEvent::BudgetExceeded => "budget_exceeded",
+            WebhookEvent::CostAnomaly => "cost_anomaly",
+            WebhookEvent::Test => "test",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Test 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "budget_warning" => WebhookEvent::BudgetWarning,
+            "budget_exceeded" => WebhookEvent::BudgetExceeded,
+            "cost_anomaly" => WebhookEvent::CostAnomaly,
+            _ => WebhookEvent::Test,
+        }
+    }
+}
+
+/// 新建或更新 Webhook 目标的参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookTargetInput {
+    /// 目标名称
+    pub name: String,
+
+    /// 接收地址（http / https）；更新时留空表示保留原地址
+    pub url: String,
+
+    pub format: WebhookFormat,
+
+    /// 是否启用，默认启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Webhook 目标
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookTarget {
+    /// 目标 ID
+    pub id: i64,
+
+    pub name: String,
+
+    /// 脱敏地址（只含协议与主机），完整地址保存在系统钥匙串
+    pub url: String,
+
+    pub format: WebhookFormat,
+
+    pub enabled: bool,
+
+    /// 创建时间（ISO 8601 格式）
+    pub created_at: String,
+
+    /// 更新时间（ISO 8601 格式）
+    pub updated_at: String,
+}
+
+/// 投递结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// 已送达（目标返回 2xx）
+    Delivered,
+
+    /// 重试后仍失败
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+
+    /// 从数据库存储值解析，未知值按 Failed 处理
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "delivered" => WebhookDeliveryStatus::Delivered,
+            _ => WebhookDeliveryStatus::Failed,
+        }
+    }
+}
+
+/// 一次 Webhook 投递记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+
+    pub target_id: i64,
+
+    /// 目标名称（投递时的名称）
+    pub target_name: String,
+
+    /// 触发投递的事件
+    pub event: WebhookEvent,
+
+    /// 消息标题
+    pub title: String,
+
+    pub status: WebhookDeliveryStatus,
+
+    /// 请求次数（含重试）
+    pub attempts: u32,
+
+    /// 最后一次失败的原因
+    pub error: Option<String>,
+
+    /// 投递完成时间（ISO 8601 格式）
+    pub delivered_at: String,
+}
+
+/// 通用格式的请求体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+
+    pub title: String,
+
+    pub body: String,
+
+    /// 告警详情（预算进度或费用异常记录）
+    pub data: serde_json::Value,
+
+    /// 发送时间（ISO 8601 格式）
+    pub sent_at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_enums_round_trip() {
+        for format in [
+            WebhookFormat::Slack,
+            WebhookFormat::Discord,
+            WebhookFormat::Generic,
+        ] {
+            assert_eq!(WebhookFormat::from_db(format.as_str()), format);
+        }
+        assert_eq!(WebhookFormat::from_db("teams"), WebhookFormat::Generic);
+        for event in [
+            WebhookEvent::BudgetWarning,
+            WebhookEvent::BudgetExceeded,
+            WebhookEvent::CostAnomaly,
+            WebhookEvent::Test,
+        ] {
+            assert_eq!(WebhookEvent::from_db(event.as_str()), event);
+        }
+        assert_eq!(
+            WebhookDeliveryStatus::from_db("delivered"),
+            WebhookDeliveryStatus::Delivered
+        );
+        assert_eq!(
+            WebhookDeliveryStatus::from_db("unknown"),
+            WebhookDeliveryStatus::Failed
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! @file year_summary.rs
+//! @description 年度回顾汇总数据模型
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::{Deserialize, Serialize};
+
+/// 年度中用量最高的一天
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusiestDay {
+    /// 日期（YYYY-MM-DD 格式）
+    pub date: String,
+
+    /// 当日 Token 数（输入 + 输出）
+    pub total_tokens: i64,
+
+    /// 当日费用（美元）
+    pub cost_usd: f64,
+
+    /// 当日消息数
+    pub message_count: i64,
+}
+
+/// 单月用量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyUsage {
+    /// 月份（YYYY-MM 格式）
+    pub month: String,
+
+    /// 输入 Token 数
+    pub input_tokens: i64,
+
+    /// 输出 Token 数
+    pub output_tokens: i64,
+
+    /// 费用（美元）
+    pub cost_usd: f64,
+
+    /// 消息数
+    pub message_count: i64,
+
+    /// 活跃天数
+    pub active_days: i64,
+}
+
+/// 年度回顾汇总，一次返回"年度报告"视图所需的全部数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YearSummary {
+    /// 年份
+    pub year: i32,
+
+    /// 输入 Token 总数
+    pub input_tokens: i64,
+
+    /// 输出 Token 总数
+    pub output_tokens: i64,
+
+    /// 缓存读取 Token 总数
+    pub cache_read_tokens: i64,
+
+    /// 缓存创建 Token 总数
+    pub cache_creation_tokens: i64,
+
+    /// 实际费用（美元）
+    pub cost_usd: f64,
+
+    /// API 等价费用（美元）
+    pub api_equivalent_cost_usd: f64,
+
+    /// 会话数
+    pub session_count: i64,
+
+    /// 消息数
+    pub message_count: i64,
+
+    /// 活跃天数
+    pub active_days: i64,
+
+    /// 用量最高的一天（按 Token 数），全年无用量时为 None
+    pub busiest_day: Option<BusiestDay>,
+
+    /// 消息数最多的模型
+    pub favorite_model: Option<String>,
+
+    /// 年内最长连续活跃天数
+    pub longest_streak_days: i64,
+
+    /// 最长连续区间开始日期
+    pub longest_streak_start: Option<String>,
+
+    /// 最长连续区间结束日期
+    pub longest_streak_end: Option<String>,
+
+    /// 缓存读取相比按输入价格计费节省的费用（美元）
+    pub cache_savings_usd: f64,
+
+    /// 1 - 12 月的逐月用量，无用量的月份为 0
+    pub months: Vec<MonthlyUsage>,
+}
@@ -0,0 +1,104 @@
+//! @file active_sessions.rs
+//! @description 活跃会话检测服务，找出最近仍有消息的会话并识别会话开始 / 结束
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::SessionSummary;
+
+/// 默认活跃判定时长：最近 5 分钟内有消息的会话视为活跃
+pub const DEFAULT_ACTIVE_MINUTES: i64 = 5;
+
+/// 两次检测之间活跃会话的变化
+#[derive(Debug, Clone, Default)]
+pub struct ActiveSessionChanges {
+    /// 新出现的活跃会话
+    pub started: Vec<SessionSummary>,
+
+    /// 不再活跃的会话（上一次检测时的状态）
+    pub ended: Vec<SessionSummary>,
+}
+
+/// 获取最近 minutes 分钟内有消息的会话
+pub fn get_active_sessions(
+    repository: &Repository,
+    now: DateTime<Utc>,
+    minutes: i64,
+) -> Result<Vec<SessionSummary>, RepositoryError> {
+    let since = now - Duration::minutes(minutes.max(1));
+    repository.get_active_sessions(&since.to_rfc3339())
+}
+
+/// 对比前后两次活跃会话列表
+///
+/// 会话以 (供应商 ID, 会话 ID) 标识
+pub fn diff_active_sessions(
+    previous: &[SessionSummary],
+    current: &[SessionSummary],
+) -> ActiveSessionChanges {
+    let key = |session: &SessionSummary| (session.provider_id, session.session_id.clone());
+    let previous_keys: HashSet<_> = previous.iter().map(key).collect();
+    let current_keys: HashSet<_> = current.iter().map(key).collect();
+
+    ActiveSessionChanges {
+        started: current
+            .iter()
+            .filter(|session| !previous_keys.contains(&key(session)))
+            .cloned()
+            .collect(),
+        ended: previous
+            .iter()
+            .filter(|session| !current_keys.contains(&key(session)))
+            .cloned()
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(session_id: &str) -> SessionSummary {
+        SessionSummary {
+            session_id: session_id.to_string(),
+            provider_id: 1,
+            title: None,
+            project: Some("/work/app".to_string()),
+            models: vec!["claude-3-opus".to_string()],
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd: 0.0,
+            message_count: 1,
+            first_message_at: "2026-10-17T10:00:00Z".to_string(),
+            last_message_at: "2026-10-17T10:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_active_sessions() {
+        let previous = vec![session("s1"), session("s2")];
+        let current = vec![session("s2"), session("s3")];
+
+        let changes = diff_active_sessions(&previous, &current);
+        let started: Vec<_> = changes
+            .started
+            .iter()
+            .map(|s| s.session_id.as_str())
+            .collect();
+        let ended: Vec<_> = changes
+            .ended
+            .iter()
+            .map(|s| s.session_id.as_str())
+            .collect();
+        assert_eq!(started, vec!["s3"]);
+        assert_eq!(ended, vec!["s1"]);
+
+        let unchanged = diff_active_sessions(&current, &current);
+        assert!(unchanged.started.is_empty() && unchanged.ended.is_empty());
+    }
+}
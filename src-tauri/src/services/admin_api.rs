@@ -0,0 +1,356 @@
+//! @file admin_api.rs
+//! @description Anthropic Admin API 官方用量拉取服务，并与本地解析数据对账生成偏差报告
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{OfficialUsage, UsageDriftReport};
+use crate::services::keychain::KeychainError;
+
+/// Anthropic API 地址
+const API_BASE_URL: &str = "https://api.anthropic.com";
+
+/// Admin API 要求的版本头
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// 按天分桶时单页最多返回的天数
+const DAILY_PAGE_LIMIT: u32 = 31;
+
+#[derive(Error, Debug)]
+pub enum AdminApiError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("Keychain error: {0}")]
+    Keychain(#[from] KeychainError),
+    #[error("未设置 Admin API Key")]
+    MissingApiKey,
+}
+
+/// Admin API 分页响应
+#[derive(Debug, Deserialize)]
+struct ReportPage<T> {
+    data: Vec<ReportBucket<T>>,
+    #[serde(default)]
+    has_more: bool,
+    next_page: Option<String>,
+}
+
+/// 单个时间桶，starting_at 为桶起始时间（RFC 3339，UTC）
+#[derive(Debug, Deserialize)]
+struct ReportBucket<T> {
+    starting_at: String,
+    results: Vec<T>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct UsageResult {
+    uncached_input_tokens: i64,
+    cache_creation: CacheCreation,
+    cache_read_input_tokens: i64,
+    output_tokens: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CacheCreation {
+    ephemeral_1h_input_tokens: i64,
+    ephemeral_5m_input_tokens: i64,
+}
+
+/// 费用结果，amount 为以最小货币单位（美分）表示的十进制字符串
+#[derive(Debug, Deserialize)]
+struct CostResult {
+    amount: String,
+    #[serde(default)]
+    currency: String,
+}
+
+/// Anthropic Admin API 客户端
+pub struct AdminApiClient {
+    api_key: String,
+}
+
+impl AdminApiClient {
+    pub fn new(api_key: String) -> Result<Self, AdminApiError> {
+        if api_key.trim().is_empty() {
+            return Err(AdminApiError::MissingApiKey);
+        }
+        Ok(Self { api_key })
+    }
+
+    /// 拉取日期范围内（UTC 日期，含首尾）每天的官方用量与费用
+    pub fn fetch_official_usage(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<OfficialUsage>, AdminApiError> {
+        let starting_at = format!("{}T00:00:00Z", start);
+        let ending_at = format!("{}T00:00:00Z", end + Duration::days(1));
+
+        let mut days = BTreeMap::new();
+        for page in self.fetch_pages(
+            "/v1/organizations/usage_report/messages",
+            &starting_at,
+            &ending_at,
+        )? {
+            merge_usage_page(&mut days, &page)?;
+        }
+        for page in self.fetch_pages("/v1/organizations/cost_report", &starting_at, &ending_at)? {
+            merge_cost_page(&mut days, &page)?;
+        }
+
+        let fetched_at = Utc::now().to_rfc3339();
+        Ok(days
+            .into_values()
+            .map(|day| OfficialUsage {
+                fetched_at: fetched_at.clone(),
+                ..day
+            })
+            .collect())
+    }
+
+    /// 按 next_page 依次拉取所有分页，返回原始响应内容
+    fn fetch_pages(
+        &self,
+        path: &str,
+        starting_at: &str,
+        ending_at: &str,
+    ) -> Result<Vec<String>, AdminApiError> {
+        let limit = DAILY_PAGE_LIMIT.to_string();
+        let mut pages = Vec::new();
+        let mut next_page: Option<String> = None;
+
+        loop {
+            let mut request = ureq::get(&format!("{}{}", API_BASE_URL, path))
+                .set("x-api-key", &self.api_key)
+                .set("anthropic-version", ANTHROPIC_VERSION)
+                .query("starting_at", starting_at)
+                .query("ending_at", ending_at)
+                .query("bucket_width", "1d")
+                .query("limit", &limit);
+            if let Some(page) = &next_page {
+                request = request.query("page", page);
+            }
+
+            let body = request
+                .call()
+                .map_err(|e| AdminApiError::Http(e.to_string()))?
+                .into_string()?;
+            let page: ReportPage<serde_json::Value> = serde_json::from_str(&body)?;
+            pages.push(body);
+
+            match page.next_page.filter(|_| page.has_more) {
+                Some(page) => next_page = Some(page),
+                None => return Ok(pages),
+            }
+        }
+    }
+}
+
+/// 将用量报告分页累加到对应日期
+fn merge_usage_page(
+    days: &mut BTreeMap<String, OfficialUsage>,
+    content: &str,
+) -> Result<(), AdminApiError> {
+    let page: ReportPage<UsageResult> = serde_json::from_str(content)?;
+    for bucket in page.data {
+        let day = day_entry(days, &bucket.starting_at);
+        for result in bucket.results {
+            day.input_tokens += result.uncached_input_tokens;
+            day.output_tokens += result.output_tokens;
+            day.cache_read_tokens += result.cache_read_input_tokens;
+            day.cache_creation_tokens += result.cache_creation.ephemeral_1h_input_tokens
+                + result.cache_creation.ephemeral_5m_input_tokens;
+        }
+    }
+    Ok(())
+}
+
+/// 将费用报告分页累加到对应日期，只统计美元费用
+fn merge_cost_page(
+    days: &mut BTreeMap<String, OfficialUsage>,
+    content: &str,
+) -> Result<(), AdminApiError> {
+    let page: ReportPage<CostResult> = serde_json::from_str(content)?;
+    for bucket in page.data {
+        let day = day_entry(days, &bucket.starting_at);
+        for result in bucket.results {
+            if !result.currency.is_empty() && result.currency != "USD" {
+                continue;
+            }
+            if let Ok(cents) = result.amount.parse::<f64>() {
+                day.cost_usd += cents / 100.0;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn day_entry<'a>(
+    days: &'a mut BTreeMap<String, OfficialUsage>,
+    starting_at: &str,
+) -> &'a mut OfficialUsage {
+    let date = starting_at.get(..10).unwrap_or(starting_at).to_string();
+    days.entry(date.clone()).or_insert_with(|| OfficialUsage {
+        date,
+        ..Default::default()
+    })
+}
+
+/// 拉取最近 lookback_days 天（含今天，UTC 日期）的官方用量并写入数据库
+///
+/// # 返回
+/// 写入的天数
+pub fn poll_official_usage(
+    repository: &Repository,
+    client: &AdminApiClient,
+    lookback_days: u32,
+) -> Result<usize, AdminApiError> {
+    let end = Utc::now().date_naive();
+    let start = end - Duration::days(i64::from(lookback_days.max(1)) - 1);
+
+    let usage = client.fetch_official_usage(start, end)?;
+    let written = repository.upsert_official_usage(&usage)?;
+    tracing::info!("官方用量拉取完成: {} 至 {}，共 {} 天", start, end, written);
+    Ok(written)
+}
+
+/// 生成日期范围内（UTC 日期）官方用量与本地解析用量的偏差报告
+pub fn build_drift_report(
+    repository: &Repository,
+    start_date: &str,
+    end_date: &str,
+) -> Result<UsageDriftReport, AdminApiError> {
+    let points = repository.get_usage_drift(start_date, end_date)?;
+
+    let official_tokens = points.iter().map(|point| point.official_tokens).sum();
+    let local_tokens = points.iter().map(|point| point.local_tokens).sum();
+    let official_cost_usd = points.iter().map(|point| point.official_cost_usd).sum();
+    let local_cost_usd = points.iter().map(|point| point.local_cost_usd).sum();
+
+    Ok(UsageDriftReport {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        token_drift_ratio: drift_ratio(local_tokens as f64, official_tokens as f64),
+        cost_drift_ratio: drift_ratio(local_cost_usd, official_cost_usd),
+        points,
+        official_tokens,
+        local_tokens,
+        official_cost_usd,
+        local_cost_usd,
+    })
+}
+
+fn drift_ratio(local: f64, official: f64) -> Option<f64> {
+    (official > 0.0).then(|| (local - official) / official)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    #[test]
+    fn test_merge_report_pages() {
+        let mut days = BTreeMap::new();
+        merge_usage_page(
+            &mut days,
+            r#"{
+                "data": [{
+                    "starting_at": "2026-10-01T00:00:00Z",
+                    "ending_at": "2026-10-02T00:00:00Z",
+                    "results": [
+                        {
+                            "uncached_input_tokens": 100,
+                            "cache_creation": {"ephemeral_1h_input_tokens": 10, "ephemeral_5m_input_tokens": 5},
+                            "cache_read_input_tokens": 200,
+                            "output_tokens": 50,
+                            "model": "claude-sonnet-4"
+                        },
+                        {"uncached_input_tokens": 1, "output_tokens": 2}
+                    ]
+                }],
+                "has_more": false,
+                "next_page": null
+            }"#,
+        )
+        .expect("usage");
+        merge_cost_page(
+            &mut days,
+            r#"{
+                "data": [
+                    {"starting_at": "2026-10-01T00:00:00Z", "results": [{"amount": "123.5", "currency": "USD"}]},
+                    {"starting_at": "2026-10-02T00:00:00Z", "results": [{"amount": "50", "currency": "USD"}]}
+                ],
+                "has_more": false
+            }"#,
+        )
+        .expect("cost");
+
+        let first = &days["2026-10-01"];
+        assert_eq!(first.input_tokens, 101);
+        assert_eq!(first.output_tokens, 52);
+        assert_eq!(first.cache_read_tokens, 200);
+        assert_eq!(first.cache_creation_tokens, 15);
+        assert!((first.cost_usd - 1.235).abs() < 1e-9);
+        assert_eq!(days["2026-10-02"].total_tokens(), 0);
+        assert!((days["2026-10-02"].cost_usd - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_build_drift_report() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            "2026-10-01T12:00:00Z".to_string(),
+            MessageUsage {
+                input_tokens: 90,
+                output_tokens: 10,
+                cost_usd: 0.9,
+                ..Default::default()
+            },
+        );
+        repo.insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        repo.upsert_official_usage(&[
+            OfficialUsage {
+                date: "2026-10-01".to_string(),
+                input_tokens: 100,
+                output_tokens: 25,
+                cost_usd: 1.0,
+                fetched_at: Utc::now().to_rfc3339(),
+                ..Default::default()
+            },
+            OfficialUsage {
+                date: "2026-10-02".to_string(),
+                fetched_at: Utc::now().to_rfc3339(),
+                ..Default::default()
+            },
+        ])
+        .expect("official");
+
+        let report = build_drift_report(&repo, "2026-10-01", "2026-10-31").expect("report");
+        assert_eq!(report.points.len(), 2);
+        assert_eq!(report.points[0].token_drift, -25);
+        assert!((report.points[0].cost_drift_usd + 0.1).abs() < 1e-9);
+        assert_eq!(report.points[1].local_tokens, 0);
+        assert_eq!(report.token_drift_ratio, Some(-0.2));
+        assert!((report.cost_drift_ratio.expect("ratio") + 0.1).abs() < 1e-9);
+    }
+}
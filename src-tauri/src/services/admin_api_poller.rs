@@ -0,0 +1,54 @@
+//! @file admin_api_poller.rs
+//! @description 官方用量拉取调度服务，按设置间隔调用 Admin API 并发送 official-usage-updated 事件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Repository;
+use crate::models::AdminApiSettings;
+use crate::services::admin_api::{poll_official_usage, AdminApiClient, AdminApiError};
+use crate::services::keychain;
+
+/// 调度检查间隔；实际拉取间隔由设置中的 interval_minutes 决定
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动官方用量拉取线程
+///
+/// 启用后立即拉取一次，之后按设置的间隔拉取；每次检查前重新读取设置
+pub fn start_admin_api_poller(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_polled: Option<Instant> = None;
+        loop {
+            let settings: Result<AdminApiSettings, _> = app.state::<Repository>().get_setting();
+            match settings {
+                Ok(settings) if settings.enabled => {
+                    let interval =
+                        Duration::from_secs(u64::from(settings.interval_minutes.max(1)) * 60);
+                    if last_polled.is_none_or(|at| at.elapsed() >= interval) {
+                        if let Err(e) = run_poll(&app, &settings) {
+                            tracing::error!("拉取官方用量失败: {}", e);
+                        }
+                        last_polled = Some(Instant::now());
+                    }
+                }
+                Ok(_) => last_polled = None,
+                Err(e) => tracing::error!("读取 Admin API 设置失败: {}", e),
+            }
+            std::thread::sleep(TICK_INTERVAL);
+        }
+    });
+}
+
+/// 使用钥匙串中的 Admin API Key 拉取一次官方用量并发送事件
+pub fn run_poll(app: &AppHandle, settings: &AdminApiSettings) -> Result<usize, AdminApiError> {
+    let api_key = keychain::load_admin_api_key()?.ok_or(AdminApiError::MissingApiKey)?;
+    let client = AdminApiClient::new(api_key)?;
+
+    let written = poll_official_usage(&app.state::<Repository>(), &client, settings.lookback_days)?;
+    if let Err(e) = app.emit("official-usage-updated", written) {
+        tracing::error!("发送 official-usage-updated 事件失败: {}", e);
+    }
+    Ok(written)
+}
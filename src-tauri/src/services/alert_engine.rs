@@ -0,0 +1,323 @@
+//! @file alert_engine.rs
+//! @description 告警规则引擎，每批数据写入后按规则的统计窗口计算指标并记录触发的告警
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{AlertMetric, AlertRule, AlertWindow, TriggeredAlert};
+use crate::services::plan_limits::to_local;
+
+/// 计算统计窗口的开始时间
+pub fn window_start(window: AlertWindow, now: DateTime<Local>) -> DateTime<Local> {
+    let today = now.date_naive();
+    match window {
+        AlertWindow::Hour => now - Duration::hours(1),
+        AlertWindow::Day => to_local(today.and_time(NaiveTime::MIN)),
+        AlertWindow::Week => to_local((today - Duration::days(6)).and_time(NaiveTime::MIN)),
+        AlertWindow::Month => to_local(today.with_day(1).unwrap_or(today).and_time(NaiveTime::MIN)),
+    }
+}
+
+/// 评估所有启用的告警规则
+///
+/// 业务逻辑说明：
+/// 1. 按规则的统计窗口汇总所有供应商的用量，取出规则指标与阈值比较，并记录本次越线的开始时间
+/// 2. 暂停中的规则不触发
+/// 3. 同一规则在一个窗口内只触发一次（窗口开始后已有触发记录则跳过）
+/// 4. 本次越线期间最近一次告警已确认时，进入新窗口也不再触发，指标回落后重新开始
+/// 5. 触发的告警写入历史表并返回，由调用方负责通知
+pub fn evaluate_alert_rules(
+    repository: &Repository,
+    now: DateTime<Local>,
+) -> Result<Vec<TriggeredAlert>, RepositoryError> {
+    let mut triggered = Vec::new();
+    for rule in repository.get_alert_rules()? {
+        if !rule.enabled {
+            continue;
+        }
+        let start = window_start(rule.window, now).to_rfc3339();
+        let value = rule
+            .metric
+            .value(&repository.get_usage_window_totals(&start)?);
+        if !rule.comparator.matches(value, rule.threshold) {
+            if rule.breached_since.is_some() {
+                repository.set_alert_rule_breached_since(rule.id, None)?;
+            }
+            continue;
+        }
+        let breached_since = match rule.breached_since.clone() {
+            Some(since) => since,
+            None => {
+                let since = now.to_rfc3339();
+                repository.set_alert_rule_breached_since(rule.id, Some(&since))?;
+                since
+            }
+        };
+        if is_snoozed(&rule, now) || repository.has_triggered_alert_since(rule.id, &start)? {
+            continue;
+        }
+        if repository.is_latest_alert_acknowledged(rule.id, &breached_since)? {
+            continue;
+        }
+
+        let message = alert_message(&rule, value);
+        triggered.push(repository.insert_triggered_alert(
+            &rule,
+            value,
+            &message,
+            &now.to_rfc3339(),
+        )?);
+    }
+    Ok(triggered)
+}
+
+/// 规则是否处于暂停期，无法解析的截止时间视为未暂停
+pub fn is_snoozed(rule: &AlertRule, now: DateTime<Local>) -> bool {
+    rule.snoozed_until
+        .as_deref()
+        .and_then(|until| DateTime::parse_from_rfc3339(until).ok())
+        .is_some_and(|until| until > now)
+}
+
+/// 生成告警通知文本
+pub fn alert_message(rule: &AlertRule, value: f64) -> String {
+    let window = match rule.window {
+        AlertWindow::Hour => "最近 1 小时",
+        AlertWindow::Day => "今日",
+        AlertWindow::Week => "最近 7 天",
+        AlertWindow::Month => "本月",
+    };
+    let metric = match rule.metric {
+        AlertMetric::CostUsd => "费用",
+        AlertMetric::TotalTokens => "Token 用量",
+        AlertMetric::MessageCount => "消息数",
+        AlertMetric::SessionCount => "会话数",
+    };
+    format!(
+        "{}：{}{} {} {} {}",
+        rule.name,
+        window,
+        metric,
+        format_metric(rule.metric, value),
+        rule.comparator.symbol(),
+        format_metric(rule.metric, rule.threshold)
+    )
+}
+
+fn format_metric(metric: AlertMetric, value: f64) -> String {
+    match metric {
+        AlertMetric::CostUsd => format!("${:.2}", value),
+        _ => format!("{:.0}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        AlertChannel, AlertComparator, AlertRuleInput, MessageRecord, MessageUsage,
+    };
+
+    fn rule_input(metric: AlertMetric, threshold: f64, window: AlertWindow) -> AlertRuleInput {
+        AlertRuleInput {
+            name: "日费用".to_string(),
+            metric,
+            comparator: AlertComparator::Gte,
+            threshold,
+            window,
+            channel: AlertChannel::Notification,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_window_start() {
+        let now = to_local(
+            chrono::NaiveDateTime::parse_from_str("2026-10-17 10:30", "%Y-%m-%d %H:%M")
+                .expect("time"),
+        );
+        let format = |time: DateTime<Local>| time.format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(
+            format(window_start(AlertWindow::Hour, now)),
+            "2026-10-17 09:30"
+        );
+        assert_eq!(
+            format(window_start(AlertWindow::Day, now)),
+            "2026-10-17 00:00"
+        );
+        assert_eq!(
+            format(window_start(AlertWindow::Week, now)),
+            "2026-10-11 00:00"
+        );
+        assert_eq!(
+            format(window_start(AlertWindow::Month, now)),
+            "2026-10-01 00:00"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_alert_rules_triggers_once_per_window() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+        let now = Local::now();
+        for (index, session) in ["s1", "s2"].iter().enumerate() {
+            let record = MessageRecord::new(
+                session.to_string(),
+                format!("m{}", index),
+                "claude-3-opus".to_string(),
+                now.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 3.0,
+                    ..Default::default()
+                },
+            );
+            repository
+                .insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let cost_rule = repository
+            .create_alert_rule(&rule_input(AlertMetric::CostUsd, 5.0, AlertWindow::Day))
+            .expect("rule");
+        repository
+            .create_alert_rule(&rule_input(
+                AlertMetric::SessionCount,
+                3.0,
+                AlertWindow::Day,
+            ))
+            .expect("rule");
+        let disabled = AlertRuleInput {
+            enabled: false,
+            ..rule_input(AlertMetric::TotalTokens, 1.0, AlertWindow::Hour)
+        };
+        repository.create_alert_rule(&disabled).expect("rule");
+
+        let triggered = evaluate_alert_rules(&repository, now).expect("evaluate");
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].rule_id, cost_rule.id);
+        assert_eq!(triggered[0].value, 6.0);
+        assert_eq!(triggered[0].message, "日费用：今日费用 $6.00 ≥ $5.00");
+
+        // 同一窗口内不重复触发
+        assert!(evaluate_alert_rules(&repository, now)
+            .expect("evaluate")
+            .is_empty());
+
+        // 删除规则后历史保留
+        assert!(repository.delete_alert_rule(cost_rule.id).expect("delete"));
+        let history = repository.get_triggered_alerts(10, false).expect("history");
+        assert_eq!(history, triggered);
+    }
+
+    #[test]
+    fn test_acknowledged_and_snoozed_rules_do_not_renotify() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+        let now = Local::now();
+        let record = MessageRecord::new(
+            "s1".to_string(),
+            "m1".to_string(),
+            "claude-3-opus".to_string(),
+            now.to_rfc3339(),
+            MessageUsage {
+                input_tokens: 100,
+                ..Default::default()
+            },
+        );
+        repository
+            .insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        let rule = repository
+            .create_alert_rule(&rule_input(
+                AlertMetric::MessageCount,
+                1.0,
+                AlertWindow::Hour,
+            ))
+            .expect("rule");
+        let triggered = evaluate_alert_rules(&repository, now).expect("evaluate");
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(
+            repository.get_triggered_alerts(10, true).expect("active"),
+            triggered
+        );
+
+        // 确认后仍持续越线：下一个窗口不再触发，但历史中仍可见
+        let acknowledged = repository
+            .acknowledge_alert(triggered[0].id)
+            .expect("ack")
+            .expect("exists");
+        assert!(acknowledged.acknowledged_at.is_some());
+        assert!(repository
+            .get_triggered_alerts(10, true)
+            .expect("active")
+            .is_empty());
+        let record = MessageRecord::new(
+            "s1".to_string(),
+            "m2".to_string(),
+            "claude-3-opus".to_string(),
+            (now + Duration::minutes(50)).to_rfc3339(),
+            MessageUsage {
+                input_tokens: 100,
+                ..Default::default()
+            },
+        );
+        repository
+            .insert_message_usage(provider.id, &record)
+            .expect("insert");
+        let later = now + Duration::minutes(70);
+        assert!(evaluate_alert_rules(&repository, later)
+            .expect("evaluate")
+            .is_empty());
+        assert_eq!(
+            repository
+                .get_triggered_alerts(10, false)
+                .expect("all")
+                .len(),
+            1
+        );
+
+        // 指标回落后重置越线状态；暂停期间再次越线不触发，暂停结束后触发
+        let much_later = now + Duration::hours(3);
+        assert!(evaluate_alert_rules(&repository, much_later)
+            .expect("evaluate")
+            .is_empty());
+        assert!(repository.get_alert_rules().expect("rules")[0]
+            .breached_since
+            .is_none());
+
+        let record = MessageRecord::new(
+            "s1".to_string(),
+            "m3".to_string(),
+            "claude-3-opus".to_string(),
+            much_later.to_rfc3339(),
+            MessageUsage {
+                input_tokens: 100,
+                ..Default::default()
+            },
+        );
+        repository
+            .insert_message_usage(provider.id, &record)
+            .expect("insert");
+        let snoozed = repository
+            .snooze_alert_rule(
+                rule.id,
+                Some(&(much_later + Duration::minutes(10)).to_rfc3339()),
+            )
+            .expect("snooze")
+            .expect("exists");
+        assert!(is_snoozed(&snoozed, much_later));
+        assert!(evaluate_alert_rules(&repository, much_later)
+            .expect("evaluate")
+            .is_empty());
+        let triggered = evaluate_alert_rules(&repository, much_later + Duration::minutes(20))
+            .expect("evaluate");
+        assert_eq!(triggered.len(), 1);
+    }
+}
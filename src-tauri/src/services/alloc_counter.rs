@@ -0,0 +1,83 @@
+//! @file alloc_counter.rs
+//! @description 计数分配器，包装系统分配器统计分配次数与字节数，供性能分析使用
+//!
+//! 注册为全局分配器会给每次分配增加原子操作，因此仅在启用 parser-benchmark 特性时编译
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 统计分配次数与分配字节数的全局分配器，开销为每次分配两次原子加法
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn record(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+}
+
+/// 某一时刻的累计分配计数（进程内所有线程）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocSnapshot {
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+impl AllocSnapshot {
+    pub fn now() -> Self {
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 自 self 以来新增的分配
+    pub fn elapsed(&self) -> AllocSnapshot {
+        let now = Self::now();
+        AllocSnapshot {
+            allocations: now.allocations.saturating_sub(self.allocations),
+            bytes: now.bytes.saturating_sub(self.bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_allocations() {
+        let start = AllocSnapshot::now();
+        let data = std::hint::black_box(vec![0u8; 4096]);
+        let elapsed = start.elapsed();
+        assert!(elapsed.allocations >= 1);
+        assert!(elapsed.bytes >= 4096);
+        drop(data);
+    }
+}
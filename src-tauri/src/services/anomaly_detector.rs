@@ -0,0 +1,206 @@
+//! @file anomaly_detector.rs
+//! @description 费用异常检测服务，将今日费用与近期基线（均值 + k·标准差）比较
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{Datelike, Duration, NaiveDate, Utc, Weekday};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{CostAnomaly, DailyActivity};
+
+/// 基线窗口天数（不含今天）
+pub const BASELINE_DAYS: i64 = 14;
+
+/// 标准差倍数 k
+pub const STDDEV_MULTIPLIER: f64 = 3.0;
+
+/// 触发异常的最低当日费用（美元），避免小额波动造成误报
+pub const MIN_ANOMALY_COST_USD: f64 = 1.0;
+
+/// 基线窗口内至少需要的活跃天数
+pub const MIN_ACTIVE_DAYS: usize = 3;
+
+/// 根据每日活动记录检测今日费用异常
+///
+/// 业务逻辑说明：
+/// 1. 取今天之前 BASELINE_DAYS 天作为基线，无记录的日期按 0 费用计
+/// 2. 活跃天数不足 MIN_ACTIVE_DAYS 时视为无基线，不做判断
+/// 3. 今日费用 > 均值 + k·标准差 且不低于 MIN_ANOMALY_COST_USD 时判定为异常
+/// 4. 倍数优先与基线内同星期几的日均费用比较，无同星期数据时使用整体均值
+pub fn detect_cost_anomaly(activities: &[DailyActivity], today: NaiveDate) -> Option<CostAnomaly> {
+    let cost_on = |date: NaiveDate| -> f64 {
+        let date = date.to_string();
+        activities
+            .iter()
+            .filter(|activity| activity.date == date)
+            .map(|activity| activity.cost_usd)
+            .sum()
+    };
+
+    let today_cost = cost_on(today);
+    if today_cost < MIN_ANOMALY_COST_USD {
+        return None;
+    }
+
+    let baseline: Vec<(NaiveDate, f64)> = (1..=BASELINE_DAYS)
+        .map(|offset| {
+            let date = today - Duration::days(offset);
+            (date, cost_on(date))
+        })
+        .collect();
+
+    let active_days = baseline.iter().filter(|(_, cost)| *cost > 0.0).count();
+    if active_days < MIN_ACTIVE_DAYS {
+        return None;
+    }
+
+    let mean = baseline.iter().map(|(_, cost)| cost).sum::<f64>() / baseline.len() as f64;
+    let variance = baseline
+        .iter()
+        .map(|(_, cost)| (cost - mean).powi(2))
+        .sum::<f64>()
+        / baseline.len() as f64;
+    let stddev = variance.sqrt();
+
+    if today_cost <= mean + STDDEV_MULTIPLIER * stddev {
+        return None;
+    }
+
+    let same_weekday: Vec<f64> = baseline
+        .iter()
+        .filter(|(date, _)| date.weekday() == today.weekday())
+        .map(|(_, cost)| *cost)
+        .collect();
+    let weekday_mean = if same_weekday.iter().any(|cost| *cost > 0.0) {
+        same_weekday.iter().sum::<f64>() / same_weekday.len() as f64
+    } else {
+        mean
+    };
+
+    Some(CostAnomaly {
+        id: 0,
+        date: today.to_string(),
+        cost_usd: today_cost,
+        baseline_mean_usd: mean,
+        baseline_stddev_usd: stddev,
+        weekday_mean_usd: weekday_mean,
+        ratio: today_cost / weekday_mean,
+        detected_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// 执行异常检测并持久化结果
+///
+/// # 返回
+/// 仅在今天首次检测到异常时返回 Some，用于避免重复通知
+pub fn run_anomaly_detection(
+    repository: &Repository,
+    today: NaiveDate,
+) -> Result<Option<CostAnomaly>, RepositoryError> {
+    let start_date = (today - Duration::days(BASELINE_DAYS)).to_string();
+    let activities = repository.get_daily_activities(&start_date, &today.to_string())?;
+
+    let Some(anomaly) = detect_cost_anomaly(&activities, today) else {
+        return Ok(None);
+    };
+
+    if repository.insert_cost_anomaly(&anomaly)? {
+        Ok(Some(anomaly))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 生成异常通知文案，如 "今日花费 $12.40，是你平常周二的 3.1 倍"
+pub fn anomaly_message(anomaly: &CostAnomaly) -> String {
+    let weekday = NaiveDate::parse_from_str(&anomaly.date, "%Y-%m-%d")
+        .map(|date| weekday_label(date.weekday()))
+        .unwrap_or("日子");
+
+    format!(
+        "今日花费 ${:.2}，是你平常{}的 {:.1} 倍",
+        anomaly.cost_usd, weekday, anomaly.ratio
+    )
+}
+
+fn weekday_label(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "周一",
+        Weekday::Tue => "周二",
+        Weekday::Wed => "周三",
+        Weekday::Thu => "周四",
+        Weekday::Fri => "周五",
+        Weekday::Sat => "周六",
+        Weekday::Sun => "周日",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(date: NaiveDate, cost_usd: f64) -> DailyActivity {
+        DailyActivity {
+            date: date.to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cost_usd,
+            session_count: 1,
+            message_count: 1,
+            note: None,
+            api_equivalent_cost_usd: cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_detect_cost_anomaly() {
+        // 2026-10-13 为周二
+        let today = NaiveDate::from_ymd_opt(2026, 10, 13).expect("date");
+        let mut activities: Vec<DailyActivity> = (1..=BASELINE_DAYS)
+            .map(|offset| activity(today - Duration::days(offset), 2.0))
+            .collect();
+        activities.push(activity(today, 6.0));
+
+        let anomaly = detect_cost_anomaly(&activities, today).expect("anomaly");
+
+        assert_eq!(anomaly.baseline_mean_usd, 2.0);
+        assert_eq!(anomaly.ratio, 3.0);
+        assert_eq!(
+            anomaly_message(&anomaly),
+            "今日花费 $6.00，是你平常周二的 3.0 倍"
+        );
+    }
+
+    #[test]
+    fn test_no_anomaly_without_baseline() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 13).expect("date");
+        let activities = vec![activity(today, 50.0)];
+
+        assert!(detect_cost_anomaly(&activities, today).is_none());
+    }
+
+    #[test]
+    fn test_insert_cost_anomaly_once_per_day() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let today = NaiveDate::from_ymd_opt(2026, 10, 13).expect("date");
+        let anomaly = CostAnomaly {
+            id: 0,
+            date: today.to_string(),
+            cost_usd: 6.0,
+            baseline_mean_usd: 2.0,
+            baseline_stddev_usd: 0.0,
+            weekday_mean_usd: 2.0,
+            ratio: 3.0,
+            detected_at: Utc::now().to_rfc3339(),
+        };
+
+        assert!(repository.insert_cost_anomaly(&anomaly).expect("insert"));
+        assert!(!repository.insert_cost_anomaly(&anomaly).expect("insert"));
+        assert_eq!(
+            repository
+                .get_cost_anomalies("2026-10-01", "2026-10-31")
+                .expect("anomalies")
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! @file api_server.rs
+//! @description 本地只读 REST API 服务，以 Bearer Token 鉴权对外提供与 Tauri Commands 相同的数据
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use chrono::NaiveDate;
+use rand::distr::{Alphanumeric, SampleString};
+use serde::Serialize;
+use serde_json::json;
+use thiserror::Error;
+use tiny_http::{Header, Response, Server};
+
+use crate::db::Repository;
+use crate::models::ApiServerSettings;
+
+/// 自动生成的 Token 长度
+const TOKEN_LENGTH: usize = 32;
+
+#[derive(Error, Debug)]
+pub enum ApiServerError {
+    #[error("Failed to bind API server: {0}")]
+    Bind(String),
+}
+
+/// API 响应（状态码 + JSON 正文）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiResponse {
+    /// HTTP 状态码
+    pub status: u16,
+
+    /// JSON 正文
+    pub body: String,
+}
+
+impl ApiResponse {
+    fn ok<T: Serialize>(data: &T) -> Self {
+        match serde_json::to_string(data) {
+            Ok(body) => Self { status: 200, body },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+/// 运行中的 REST API 服务
+pub struct ApiServer {
+    server: Arc<Server>,
+    thread: Option<JoinHandle<()>>,
+    addr: SocketAddr,
+}
+
+impl ApiServer {
+    /// 按设置启动服务，请求在独立线程中依次处理
+    pub fn start(
+        repository: Repository,
+        settings: &ApiServerSettings,
+    ) -> Result<Self, ApiServerError> {
+        let host = if settings.allow_remote {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let server = Server::http(SocketAddr::from((host, settings.port)))
+            .map_err(|e| ApiServerError::Bind(e.to_string()))?;
+        let addr = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| ApiServerError::Bind("non-IP listen address".to_string()))?;
+
+        let server = Arc::new(server);
+        let token = settings.token.clone();
+        let worker = server.clone();
+        let thread = std::thread::spawn(move || {
+            for request in worker.incoming_requests() {
+                let authorization = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv("Authorization"))
+                    .map(|header| header.value.as_str().to_string());
+                let response = route(
+                    &repository,
+                    request.method().as_str(),
+                    request.url(),
+                    authorization.as_deref(),
+                    &token,
+                );
+
+                let content_type =
+                    Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                        .expect("static header is valid");
+                let http_response = Response::from_string(response.body)
+                    .with_status_code(response.status)
+                    .with_header(content_type);
+                if let Err(e) = request.respond(http_response) {
+                    tracing::error!("REST API 响应发送失败: {}", e);
+                }
+            }
+        });
+
+        tracing::info!("REST API 已启动: http://{}", addr);
+        Ok(Self {
+            server,
+            thread: Some(thread),
+            addr,
+        })
+    }
+
+    /// 实际监听地址
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 停止服务并等待处理线程退出
+    pub fn stop(mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::info!("REST API 已停止: http://{}", self.addr);
+    }
+}
+
+/// 按设置重启服务：先停止已运行的实例，启用时再以新设置启动
+pub fn restart_api_server(
+    slot: &mut Option<ApiServer>,
+    repository: &Repository,
+    settings: &ApiServerSettings,
+) -> Result<(), ApiServerError> {
+    if let Some(server) = slot.take() {
+        server.stop();
+    }
+    if settings.enabled {
+        *slot = Some(ApiServer::start(repository.clone(), settings)?);
+    }
+    Ok(())
+}
+
+/// 生成随机 API Token
+pub fn generate_token() -> String {
+    Alphanumeric.sample_string(&mut rand::rng(), TOKEN_LENGTH)
+}
+
+/// 处理单个请求
+///
+/// 业务逻辑：
+/// 1. 校验 `Authorization: Bearer <token>`
+/// 2. 仅支持 GET，按路径分发到仓储查询
+///
+/// 支持的路径：
+/// - `/stats/today` 今日统计
+/// - `/stats/current` 全量统计
+/// - `/providers` 供应商列表
+/// - `/daily?start=YYYY-MM-DD&end=YYYY-MM-DD` 每日使用量
+pub fn route(
+    repository: &Repository,
+    method: &str,
+    url: &str,
+    authorization: Option<&str>,
+    token: &str,
+) -> ApiResponse {
+    if !is_authorized(authorization, token) {
+        return ApiResponse::error(401, "unauthorized");
+    }
+    if method != "GET" {
+        return ApiResponse::error(405, "method not allowed");
+    }
+
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    match path.trim_end_matches('/') {
+        "/stats/today" => respond(repository.get_today_stats()),
+        "/stats/current" => respond(repository.get_current_stats()),
+        "/providers" => respond(repository.get_all_providers(false)),
+        "/daily" => {
+            let start = query_param(query, "start");
+            let end = query_param(query, "end");
+            match (start, end) {
+                (Some(start), Some(end)) if is_valid_date(start) && is_valid_date(end) => {
+                    respond(repository.get_daily_activities(start, end))
+                }
+                _ => ApiResponse::error(400, "start and end must be YYYY-MM-DD dates"),
+            }
+        }
+        _ => ApiResponse::error(404, "not found"),
+    }
+}
+
+fn respond<T: Serialize, E: std::fmt::Display>(result: Result<T, E>) -> ApiResponse {
+    match result {
+        Ok(data) => ApiResponse::ok(&data),
+        Err(e) => ApiResponse::error(500, &e.to_string()),
+    }
+}
+
+/// 校验 Bearer Token，空 Token 一律拒绝
+pub(crate) fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    match authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+        Some(provided) => constant_time_eq(provided.trim().as_bytes(), token.as_bytes()),
+        None => false,
+    }
+}
+
+/// 常量时间比较，避免通过响应时间猜测 Token
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub(crate) fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn is_valid_date(value: &str) -> bool {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "test-token";
+
+    #[test]
+    fn test_route_requires_bearer_token() {
+        let repository = Repository::new_in_memory().expect("repo");
+
+        let response = route(&repository, "GET", "/providers", None, TOKEN);
+        assert_eq!(response.status, 401);
+        let response = route(
+            &repository,
+            "GET",
+            "/providers",
+            Some("Bearer wrong"),
+            TOKEN,
+        );
+        assert_eq!(response.status, 401);
+        let response = route(&repository, "GET", "/providers", Some("Bearer "), "");
+        assert_eq!(response.status, 401);
+    }
+
+    #[test]
+    fn test_route_endpoints() {
+        let repository = Repository::new_in_memory().expect("repo");
+        repository
+            .upsert_provider("sk-api", None)
+            .expect("provider");
+        let auth = Some("Bearer test-token");
+
+        let response = route(&repository, "GET", "/providers", auth, TOKEN);
+        assert_eq!(response.status, 200);
+        let providers: serde_json::Value = serde_json::from_str(&response.body).expect("json");
+        assert_eq!(providers.as_array().map(|list| list.len()), Some(1));
+
+        let response = route(&repository, "GET", "/stats/today", auth, TOKEN);
+        assert_eq!(response.status, 200);
+
+        let response = route(
+            &repository,
+            "GET",
+            "/daily?start=2026-10-01&end=2026-10-07",
+            auth,
+            TOKEN,
+        );
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "[]");
+
+        let response = route(&repository, "GET", "/daily?start=oops", auth, TOKEN);
+        assert_eq!(response.status, 400);
+        let response = route(&repository, "POST", "/providers", auth, TOKEN);
+        assert_eq!(response.status, 405);
+        let response = route(&repository, "GET", "/unknown", auth, TOKEN);
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token();
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert_ne!(token, generate_token());
+    }
+
+    #[test]
+    fn test_api_server_start_and_stop() {
+        use std::io::{Read, Write};
+
+        let repository = Repository::new_in_memory().expect("repo");
+        let settings = ApiServerSettings {
+            enabled: true,
+            port: 0,
+            allow_remote: false,
+            token: TOKEN.to_string(),
+        };
+        let server = ApiServer::start(repository, &settings).expect("start");
+
+        let mut stream = std::net::TcpStream::connect(server.local_addr()).expect("connect");
+        stream
+            .write_all(
+                b"GET /providers HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer test-token\r\nConnection: close\r\n\r\n",
+            )
+            .expect("write");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read");
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with("[]"));
+
+        server.stop();
+    }
+}
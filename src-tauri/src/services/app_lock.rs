@@ -0,0 +1,333 @@
+//! @file app_lock.rs
+//! @description 应用锁服务，启用后在口令解锁前拒绝数据命令，空闲超时自动锁定
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use tauri::{AppHandle, Emitter, Manager};
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::settings::MIN_APP_LOCK_PASSCODE_LEN;
+use crate::models::{AppLockSettings, AppLockStatus};
+
+/// 空闲检查间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 锁定期间仍允许调用的命令：解锁、查询锁状态与不涉及数据的界面操作
+const ALLOWED_WHILE_LOCKED: &[&str] = &[
+    "get_app_lock_status",
+    "get_read_only_mode",
+    "lock_app",
+    "report_activity",
+    "toggle_overlay_window",
+    "unlock_app",
+];
+
+#[derive(Error, Debug)]
+pub enum AppLockError {
+    #[error("口令哈希失败: {0}")]
+    Hash(String),
+    #[error("口令至少需要 {MIN_APP_LOCK_PASSCODE_LEN} 个字符")]
+    PasscodeTooShort,
+    #[error("需要提供口令")]
+    PasscodeRequired,
+    #[error("口令错误")]
+    WrongPasscode,
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// 应用锁运行状态
+///
+/// 启用时以锁定状态启动；内部加锁，可在命令处理与空闲检查线程间共享
+pub struct AppLock {
+    state: Mutex<LockState>,
+}
+
+#[derive(Debug)]
+struct LockState {
+    enabled: bool,
+    locked: bool,
+    idle_timeout: Duration,
+    last_activity: Instant,
+}
+
+impl AppLock {
+    pub fn new(settings: &AppLockSettings, now: Instant) -> Self {
+        Self {
+            state: Mutex::new(LockState {
+                enabled: settings.enabled,
+                locked: settings.enabled,
+                idle_timeout: idle_timeout(settings),
+                last_activity: now,
+            }),
+        }
+    }
+
+    /// 应用新设置
+    ///
+    /// 修改设置前已验证过口令，视为用户在场：保持解锁并重新计算空闲时间
+    pub fn configure(&self, settings: &AppLockSettings, now: Instant) {
+        self.update(|state| {
+            state.enabled = settings.enabled;
+            state.locked = false;
+            state.idle_timeout = idle_timeout(settings);
+            state.last_activity = now;
+        });
+    }
+
+    /// 空闲超时则锁定
+    ///
+    /// # 返回
+    /// 本次调用是否刚刚锁定（用于只通知一次）
+    pub fn check_idle(&self, now: Instant) -> bool {
+        self.update(|state| {
+            let idle = now.saturating_duration_since(state.last_activity);
+            if state.enabled && !state.locked && idle >= state.idle_timeout {
+                state.locked = true;
+                return true;
+            }
+            false
+        })
+    }
+
+    /// 当前是否锁定（先按空闲时间判断是否需要自动锁定）
+    pub fn is_locked(&self, now: Instant) -> bool {
+        self.check_idle(now);
+        self.update(|state| state.enabled && state.locked)
+    }
+
+    /// 记录一次用户操作；锁定期间的操作不会延长解锁时间
+    pub fn record_activity(&self, now: Instant) {
+        self.update(|state| {
+            if !state.locked {
+                state.last_activity = now;
+            }
+        });
+    }
+
+    /// 立即锁定；未启用应用锁时不生效
+    ///
+    /// # 返回
+    /// 是否处于锁定状态
+    pub fn lock(&self) -> bool {
+        self.update(|state| {
+            state.locked = state.enabled;
+            state.locked
+        })
+    }
+
+    fn unlock(&self, now: Instant) {
+        self.update(|state| {
+            state.locked = false;
+            state.last_activity = now;
+        });
+    }
+
+    /// 当前锁状态
+    pub fn status(&self, now: Instant) -> AppLockStatus {
+        let locked = self.is_locked(now);
+        self.update(|state| AppLockStatus {
+            enabled: state.enabled,
+            locked,
+            idle_timeout_minutes: (state.idle_timeout.as_secs() / 60) as u32,
+        })
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut LockState) -> T) -> T {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut state)
+    }
+}
+
+fn idle_timeout(settings: &AppLockSettings) -> Duration {
+    Duration::from_secs(u64::from(settings.idle_timeout_minutes.max(1)) * 60)
+}
+
+/// 锁定期间是否允许调用该命令
+pub fn is_allowed_while_locked(command: &str) -> bool {
+    ALLOWED_WHILE_LOCKED.contains(&command)
+}
+
+/// 以随机盐计算口令的 argon2 哈希，返回 PHC 字符串
+pub fn hash_passcode(passcode: &str) -> Result<String, AppLockError> {
+    let salt_bytes: [u8; 16] = rand::random();
+    let salt =
+        SaltString::encode_b64(&salt_bytes).map_err(|e| AppLockError::Hash(e.to_string()))?;
+    Argon2::default()
+        .hash_password(passcode.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppLockError::Hash(e.to_string()))
+}
+
+/// 校验口令；哈希格式无效时视为不匹配
+pub fn verify_passcode(passcode: &str, passcode_hash: &str) -> bool {
+    PasswordHash::new(passcode_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(passcode.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// 用口令解锁；未启用应用锁时直接返回当前状态
+pub fn unlock(
+    repository: &Repository,
+    lock: &AppLock,
+    passcode: &str,
+    now: Instant,
+) -> Result<AppLockStatus, AppLockError> {
+    let settings = repository.get_setting::<AppLockSettings>()?;
+    if settings.enabled {
+        if !verify_passcode(passcode, &settings.passcode_hash) {
+            tracing::warn!("应用解锁失败：口令错误");
+            return Err(AppLockError::WrongPasscode);
+        }
+        lock.unlock(now);
+    }
+    Ok(lock.status(now))
+}
+
+/// 修改应用锁设置
+///
+/// 业务逻辑说明：
+/// 1. 已启用应用锁时必须提供正确的当前口令，防止他人在空闲窗口内关闭或改口令
+/// 2. 提供新口令时重新计算哈希；启用时必须已有口令
+/// 3. 关闭应用锁时同时清除口令哈希
+pub fn set_app_lock(
+    repository: &Repository,
+    lock: &AppLock,
+    enabled: bool,
+    idle_timeout_minutes: u32,
+    passcode: Option<&str>,
+    current_passcode: Option<&str>,
+    now: Instant,
+) -> Result<AppLockStatus, AppLockError> {
+    let mut settings = repository.get_setting::<AppLockSettings>()?;
+    if settings.enabled {
+        let current = current_passcode.ok_or(AppLockError::PasscodeRequired)?;
+        if !verify_passcode(current, &settings.passcode_hash) {
+            return Err(AppLockError::WrongPasscode);
+        }
+    }
+    if let Some(passcode) = passcode {
+        if passcode.chars().count() < MIN_APP_LOCK_PASSCODE_LEN {
+            return Err(AppLockError::PasscodeTooShort);
+        }
+        settings.passcode_hash = hash_passcode(passcode)?;
+    }
+    if enabled && settings.passcode_hash.is_empty() {
+        return Err(AppLockError::PasscodeRequired);
+    }
+
+    settings.enabled = enabled;
+    settings.idle_timeout_minutes = idle_timeout_minutes.max(1);
+    if !enabled {
+        settings.passcode_hash.clear();
+    }
+    repository.set_setting(&settings)?;
+    lock.configure(&settings, now);
+    Ok(lock.status(now))
+}
+
+/// 启动空闲检查线程，自动锁定时发送 app-locked 事件
+pub fn start_auto_lock_checker(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+
+        if app.state::<AppLock>().check_idle(Instant::now()) {
+            tracing::info!("空闲超时，应用已锁定");
+            if let Err(e) = app.emit("app-locked", ()) {
+                tracing::error!("发送 app-locked 事件失败: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(enabled: bool) -> AppLockSettings {
+        AppLockSettings {
+            enabled,
+            passcode_hash: String::new(),
+            idle_timeout_minutes: 5,
+        }
+    }
+
+    #[test]
+    fn test_app_lock_idle_timeout() {
+        let start = Instant::now();
+        let lock = AppLock::new(&settings(true), start);
+        assert!(lock.is_locked(start));
+        lock.record_activity(start + Duration::from_secs(60));
+        lock.unlock(start);
+        assert!(!lock.is_locked(start + Duration::from_secs(60)));
+
+        lock.record_activity(start + Duration::from_secs(120));
+        assert!(!lock.check_idle(start + Duration::from_secs(400)));
+        assert!(lock.check_idle(start + Duration::from_secs(420)));
+        assert!(!lock.check_idle(start + Duration::from_secs(430)));
+        assert!(lock.is_locked(start + Duration::from_secs(430)));
+
+        let disabled = AppLock::new(&settings(false), start);
+        assert!(!disabled.lock());
+        assert!(!disabled.is_locked(start + Duration::from_secs(3600)));
+        assert!(is_allowed_while_locked("unlock_app"));
+        assert!(!is_allowed_while_locked("get_current_stats"));
+    }
+
+    #[test]
+    fn test_set_app_lock_and_unlock() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let now = Instant::now();
+        let lock = AppLock::new(&AppLockSettings::default(), now);
+
+        assert!(matches!(
+            set_app_lock(&repository, &lock, true, 5, None, None, now),
+            Err(AppLockError::PasscodeRequired)
+        ));
+        assert!(matches!(
+            set_app_lock(&repository, &lock, true, 5, Some("12"), None, now),
+            Err(AppLockError::PasscodeTooShort)
+        ));
+        let status =
+            set_app_lock(&repository, &lock, true, 5, Some("1234"), None, now).expect("enable");
+        assert!(status.enabled);
+        assert!(!status.locked);
+        let stored = repository
+            .get_setting::<AppLockSettings>()
+            .expect("settings");
+        assert!(stored.passcode_hash.starts_with("$argon2"));
+        assert!(!stored.passcode_hash.contains("1234"));
+
+        assert!(lock.lock());
+        assert!(matches!(
+            unlock(&repository, &lock, "0000", now),
+            Err(AppLockError::WrongPasscode)
+        ));
+        assert!(
+            !unlock(&repository, &lock, "1234", now)
+                .expect("unlock")
+                .locked
+        );
+
+        assert!(matches!(
+            set_app_lock(&repository, &lock, false, 5, None, Some("0000"), now),
+            Err(AppLockError::WrongPasscode)
+        ));
+        let status =
+            set_app_lock(&repository, &lock, false, 5, None, Some("1234"), now).expect("disable");
+        assert!(!status.enabled);
+        assert_eq!(
+            repository
+                .get_setting::<AppLockSettings>()
+                .expect("settings"),
+            AppLockSettings::default()
+        );
+    }
+}
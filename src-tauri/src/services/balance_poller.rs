@@ -0,0 +1,41 @@
+//! @file balance_poller.rs
+//! @description 中转站余额定时刷新服务，按各供应商的刷新间隔查询余额并发送 provider-balance-updated 事件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::Duration;
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Repository;
+use crate::services::provider_balance::{is_refresh_due, refresh_provider_balance};
+
+/// 调度检查间隔；实际刷新间隔由各供应商配置的 refresh_minutes 决定
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动余额刷新线程，每次检查前重新读取配置
+pub fn start_balance_poller(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let repository = app.state::<Repository>();
+        match repository.get_provider_balances() {
+            Ok(balances) => {
+                let now = Utc::now();
+                for balance in balances.iter().filter(|b| is_refresh_due(b, now)) {
+                    match refresh_provider_balance(&repository, balance.provider_id) {
+                        Ok(Some(updated)) => {
+                            if let Err(e) = app.emit("provider-balance-updated", updated) {
+                                tracing::error!("发送 provider-balance-updated 事件失败: {}", e);
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            tracing::error!("刷新供应商余额失败 [{}]: {}", balance.provider_id, e)
+                        }
+                    }
+                }
+            }
+            Err(e) => tracing::error!("读取余额查询配置失败: {}", e),
+        }
+        std::thread::sleep(TICK_INTERVAL);
+    });
+}
@@ -0,0 +1,240 @@
+//! @file block_warning.rs
+//! @description 5 小时区块用量预警服务，按历史区块上限或用户配置的容量判断当前区块用量百分比
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{BlockEntry, BlockWarning, BlockWarningSettings, UsageBlock};
+use crate::services::usage_block::{calculate_current_block, learn_block_ceiling};
+
+/// 学习区块上限时回看的天数
+pub const CEILING_LOOKBACK_DAYS: i64 = 30;
+
+/// 已通知的预警状态
+///
+/// 记录当前区块已通知过的最高百分比，同一区块内每个百分比只通知一次；内部加锁，可在多个线程间共享
+#[derive(Default)]
+pub struct BlockWarningState {
+    notified: Mutex<Option<(String, u32)>>,
+}
+
+impl BlockWarningState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 清除已通知记录（如切换档案后）
+    pub fn reset(&self) {
+        if let Ok(mut notified) = self.notified.lock() {
+            *notified = None;
+        }
+    }
+
+    /// 标记区块已达到某个百分比
+    ///
+    /// # 返回
+    /// 该百分比此前未通知过返回 true
+    fn mark(&self, block_start: &str, threshold: u32) -> bool {
+        let Ok(mut notified) = self.notified.lock() else {
+            return false;
+        };
+        if let Some((start, highest)) = notified.as_ref() {
+            if start == block_start && *highest >= threshold {
+                return false;
+            }
+        }
+        *notified = Some((block_start.to_string(), threshold));
+        true
+    }
+}
+
+/// 获取区块容量：优先使用配置值，否则从近 CEILING_LOOKBACK_DAYS 天的历史区块学习
+///
+/// # 返回
+/// (容量, 是否来自历史学习)，无配置且历史不足时返回 None
+pub fn get_block_capacity(
+    repository: &Repository,
+    settings: &BlockWarningSettings,
+    now: DateTime<Utc>,
+) -> Result<Option<(i64, bool)>, RepositoryError> {
+    let since = now - Duration::days(CEILING_LOOKBACK_DAYS);
+    let entries = repository.get_block_entries(&since.to_rfc3339())?;
+    Ok(block_capacity(&entries, settings, now))
+}
+
+fn block_capacity(
+    entries: &[BlockEntry],
+    settings: &BlockWarningSettings,
+    now: DateTime<Utc>,
+) -> Option<(i64, bool)> {
+    match settings.capacity_tokens.filter(|capacity| *capacity > 0) {
+        Some(capacity) => Some((capacity, false)),
+        None => learn_block_ceiling(entries, now).map(|ceiling| (ceiling, true)),
+    }
+}
+
+/// 检查当前区块是否需要预警
+///
+/// 业务逻辑说明：
+/// 1. 未启用、没有活跃区块或无法确定容量时不预警
+/// 2. 取当前用量已超过的最高预警百分比
+/// 3. 同一区块内已通知过相同或更高百分比时不再预警
+pub fn check_block_warning(
+    repository: &Repository,
+    settings: &BlockWarningSettings,
+    state: &BlockWarningState,
+    now: DateTime<Utc>,
+) -> Result<Option<BlockWarning>, RepositoryError> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let since = now - Duration::days(CEILING_LOOKBACK_DAYS);
+    let entries = repository.get_block_entries(&since.to_rfc3339())?;
+    let Some(block) = calculate_current_block(&entries, now) else {
+        return Ok(None);
+    };
+    let Some((capacity_tokens, capacity_learned)) = block_capacity(&entries, settings, now) else {
+        return Ok(None);
+    };
+
+    let Some(warning) = evaluate_block_warning(
+        block,
+        capacity_tokens,
+        capacity_learned,
+        &settings.thresholds_percent,
+    ) else {
+        return Ok(None);
+    };
+    if !state.mark(&warning.block.start_at, warning.threshold_percent) {
+        return Ok(None);
+    }
+    Ok(Some(warning))
+}
+
+/// 计算区块用量已超过的最高预警百分比，未超过任何百分比时返回 None
+pub fn evaluate_block_warning(
+    block: UsageBlock,
+    capacity_tokens: i64,
+    capacity_learned: bool,
+    thresholds_percent: &[u32],
+) -> Option<BlockWarning> {
+    if capacity_tokens <= 0 {
+        return None;
+    }
+    let usage_ratio = block.total_tokens as f64 / capacity_tokens as f64;
+    let threshold_percent = thresholds_percent
+        .iter()
+        .copied()
+        .filter(|threshold| *threshold > 0 && usage_ratio * 100.0 >= f64::from(*threshold))
+        .max()?;
+
+    Some(BlockWarning {
+        threshold_percent,
+        usage_ratio,
+        capacity_tokens,
+        capacity_learned,
+        block,
+    })
+}
+
+/// 生成预警通知文本
+pub fn block_warning_message(warning: &BlockWarning) -> String {
+    format!(
+        "当前 5 小时区块已使用约 {:.0}%（{} / {} tokens），距区块结束还有 {} 分钟",
+        warning.usage_ratio * 100.0,
+        warning.block.total_tokens,
+        warning.capacity_tokens,
+        warning.block.remaining_minutes
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(start_at: &str, total_tokens: i64) -> UsageBlock {
+        UsageBlock {
+            start_at: start_at.to_string(),
+            end_at: String::new(),
+            last_message_at: String::new(),
+            remaining_minutes: 90,
+            total_tokens,
+            cost_usd: 0.0,
+            message_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_block_warning() {
+        let thresholds = [80, 95];
+        assert!(evaluate_block_warning(block("a", 700), 1000, true, &thresholds).is_none());
+
+        let warning =
+            evaluate_block_warning(block("a", 850), 1000, true, &thresholds).expect("warning");
+        assert_eq!(warning.threshold_percent, 80);
+        assert_eq!(
+            block_warning_message(&warning),
+            "当前 5 小时区块已使用约 85%（850 / 1000 tokens），距区块结束还有 90 分钟"
+        );
+
+        let warning =
+            evaluate_block_warning(block("a", 1200), 1000, false, &thresholds).expect("warning");
+        assert_eq!(warning.threshold_percent, 95);
+        assert!(evaluate_block_warning(block("a", 1200), 0, false, &thresholds).is_none());
+    }
+
+    #[test]
+    fn test_warning_state_notifies_once_per_threshold() {
+        let state = BlockWarningState::new();
+        assert!(state.mark("block-1", 80));
+        assert!(!state.mark("block-1", 80));
+        assert!(state.mark("block-1", 95));
+        assert!(!state.mark("block-1", 80));
+        assert!(state.mark("block-2", 80));
+    }
+
+    #[test]
+    fn test_check_block_warning_with_configured_capacity() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+        let now = Utc::now();
+        let record = crate::models::MessageRecord::new(
+            "session-1".to_string(),
+            "m1".to_string(),
+            "claude-3-opus".to_string(),
+            now.to_rfc3339(),
+            crate::models::MessageUsage {
+                input_tokens: 900,
+                ..Default::default()
+            },
+        );
+        repository
+            .insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        let state = BlockWarningState::new();
+        let settings = BlockWarningSettings::default();
+        // 历史区块不足且未配置容量时不预警
+        assert!(check_block_warning(&repository, &settings, &state, now)
+            .expect("check")
+            .is_none());
+
+        let settings = BlockWarningSettings {
+            capacity_tokens: Some(1000),
+            ..Default::default()
+        };
+        let warning = check_block_warning(&repository, &settings, &state, now)
+            .expect("check")
+            .expect("warning");
+        assert_eq!(warning.threshold_percent, 80);
+        assert!(!warning.capacity_learned);
+        assert!(check_block_warning(&repository, &settings, &state, now)
+            .expect("check")
+            .is_none());
+    }
+}
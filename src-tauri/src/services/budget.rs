@@ -0,0 +1,219 @@
+//! @file budget.rs
+//! @description 每日预算进度计算与预算阈值检查，供任务栏进度条与 Dock 角标使用，今日费用首次达到预警线或超出预算时触发告警
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{BudgetSettings, DockBadgeContent};
+
+/// 预算消耗状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLevel {
+    /// 低于预警百分比
+    Normal,
+
+    /// 达到预警百分比，尚未超出预算
+    Warning,
+
+    /// 已达到或超出预算
+    Exceeded,
+}
+
+impl BudgetLevel {
+    /// 转换为数据库存储值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetLevel::Normal => "normal",
+            BudgetLevel::Warning => "warning",
+            BudgetLevel::Exceeded => "exceeded",
+        }
+    }
+}
+
+/// 今日预算消耗进度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BudgetProgress {
+    /// 每日预算（美元）
+    pub budget_usd: f64,
+
+    /// 今日费用（美元）
+    pub spent_usd: f64,
+
+    /// 剩余预算（美元），超支时为 0
+    pub remaining_usd: f64,
+
+    /// 消耗百分比（0 - 100，超支时为 100）
+    pub percent: u64,
+
+    pub level: BudgetLevel,
+}
+
+/// 计算今日预算消耗进度，未配置有效预算时返回 None
+pub fn budget_progress(settings: &BudgetSettings, spent_usd: f64) -> Option<BudgetProgress> {
+    let budget_usd = settings
+        .daily_budget_usd
+        .filter(|budget| budget.is_finite() && *budget > 0.0)?;
+    let spent_usd = spent_usd.max(0.0);
+    let ratio = spent_usd / budget_usd;
+    let level = if ratio >= 1.0 {
+        BudgetLevel::Exceeded
+    } else if ratio * 100.0 >= f64::from(settings.warning_percent) {
+        BudgetLevel::Warning
+    } else {
+        BudgetLevel::Normal
+    };
+
+    Some(BudgetProgress {
+        budget_usd,
+        spent_usd,
+        remaining_usd: (budget_usd - spent_usd).max(0.0),
+        percent: (ratio * 100.0).floor().min(100.0) as u64,
+        level,
+    })
+}
+
+/// 检查今日预算并记录首次达到的预警 / 超支级别
+///
+/// 业务逻辑说明：
+/// 1. 未配置预算或今日费用低于预警百分比时不触发
+/// 2. 同一天同一级别只触发一次，重复检查返回 None
+///
+/// # 返回
+/// 今日首次进入预警或超支级别时返回当前进度
+pub fn run_budget_check(
+    repository: &Repository,
+    today: NaiveDate,
+) -> Result<Option<BudgetProgress>, RepositoryError> {
+    let settings: BudgetSettings = repository.get_setting()?;
+    let date = today.to_string();
+    let spent_usd: f64 = repository
+        .get_daily_activities(&date, &date)?
+        .iter()
+        .map(|activity| activity.cost_usd)
+        .sum();
+
+    let Some(progress) = budget_progress(&settings, spent_usd) else {
+        return Ok(None);
+    };
+    if progress.level == BudgetLevel::Normal {
+        return Ok(None);
+    }
+
+    if repository.insert_budget_alert(
+        &date,
+        progress.level.as_str(),
+        progress.spent_usd,
+        progress.budget_usd,
+    )? {
+        Ok(Some(progress))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 生成预算告警的标题与正文
+pub fn budget_alert_message(progress: &BudgetProgress) -> (String, String) {
+    let title = match progress.level {
+        BudgetLevel::Exceeded => "今日费用超出预算",
+        _ => "今日费用接近预算",
+    };
+    let body = format!(
+        "今日花费 ${:.2}，已达每日预算 ${:.2} 的 {}%",
+        progress.spent_usd, progress.budget_usd, progress.percent
+    );
+    (title.to_string(), body)
+}
+
+/// 生成 Dock 角标文字，如 "$4.23"；剩余预算模式下未配置预算时显示今日费用
+pub fn dock_badge_label(
+    content: DockBadgeContent,
+    settings: &BudgetSettings,
+    spent_usd: f64,
+) -> String {
+    let amount = match content {
+        DockBadgeContent::TodayCost => spent_usd,
+        DockBadgeContent::RemainingBudget => budget_progress(settings, spent_usd)
+            .map(|progress| progress.remaining_usd)
+            .unwrap_or(spent_usd),
+    };
+    format!("${:.2}", amount.max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_progress() {
+        let settings = BudgetSettings {
+            daily_budget_usd: Some(10.0),
+            ..Default::default()
+        };
+
+        let progress = budget_progress(&settings, 4.25).expect("progress");
+        assert_eq!(progress.percent, 42);
+        assert_eq!(progress.level, BudgetLevel::Normal);
+        assert!((progress.remaining_usd - 5.75).abs() < 1e-9);
+
+        assert_eq!(
+            budget_progress(&settings, 8.0).expect("progress").level,
+            BudgetLevel::Warning
+        );
+        let exceeded = budget_progress(&settings, 12.0).expect("progress");
+        assert_eq!(exceeded.level, BudgetLevel::Exceeded);
+        assert_eq!(exceeded.percent, 100);
+        assert_eq!(exceeded.remaining_usd, 0.0);
+
+        assert!(budget_progress(&BudgetSettings::default(), 5.0).is_none());
+    }
+
+    #[test]
+    fn test_budget_alert_once_per_level() {
+        let repository = Repository::new_in_memory().expect("repo");
+        assert!(repository
+            .insert_budget_alert("2026-10-17", "warning", 8.0, 10.0)
+            .expect("insert"));
+        assert!(!repository
+            .insert_budget_alert("2026-10-17", "warning", 9.0, 10.0)
+            .expect("insert"));
+        assert!(repository
+            .insert_budget_alert("2026-10-17", "exceeded", 10.5, 10.0)
+            .expect("insert"));
+        assert!(repository
+            .insert_budget_alert("2026-10-18", "warning", 8.0, 10.0)
+            .expect("insert"));
+
+        // 未配置预算时不触发
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).expect("date");
+        assert!(run_budget_check(&repository, today)
+            .expect("check")
+            .is_none());
+    }
+
+    #[test]
+    fn test_dock_badge_label() {
+        let settings = BudgetSettings {
+            daily_budget_usd: Some(10.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            dock_badge_label(DockBadgeContent::TodayCost, &settings, 4.234),
+            "$4.23"
+        );
+        assert_eq!(
+            dock_badge_label(DockBadgeContent::RemainingBudget, &settings, 4.234),
+            "$5.77"
+        );
+        assert_eq!(
+            dock_badge_label(
+                DockBadgeContent::RemainingBudget,
+                &BudgetSettings::default(),
+                4.234
+            ),
+            "$4.23"
+        );
+    }
+}
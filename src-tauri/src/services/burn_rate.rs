@@ -0,0 +1,177 @@
+//! @file burn_rate.rs
+//! @description 消耗速率计算服务，基于每日统计与今日实时速度预测月末花费
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+
+use crate::models::{BurnRate, BurnRateWindow, DailyActivity};
+
+/// 参与计算的历史窗口天数
+pub const BURN_RATE_WINDOWS: [i64; 2] = [7, 30];
+
+/// 计算消耗速率所需的最早日期
+///
+/// 取最长窗口起点与本月 1 日中更早的一个，保证一次查询即可覆盖所有数据
+pub fn burn_rate_start_date(today: NaiveDate) -> NaiveDate {
+    let longest_window = BURN_RATE_WINDOWS.iter().copied().max().unwrap_or(0);
+    let window_start = today - Duration::days(longest_window);
+    let month_start = today.with_day(1).unwrap_or(today);
+    window_start.min(month_start)
+}
+
+/// 根据每日活动记录计算消耗速率
+///
+/// 业务逻辑说明：
+/// 1. 今日速度 = 今日用量 / 已过去小时数（不足 1 小时按 1 小时计，避免早间数值失真）
+/// 2. 历史窗口只统计今天之前的完整天数
+/// 3. 月末预测 = 本月此前费用 + 今日全天预测 + 剩余天数 × 日均费用
+///    日均费用优先取最短窗口，无历史数据时退化为今日全天预测
+pub fn calculate_burn_rate(activities: &[DailyActivity], now: NaiveDateTime) -> BurnRate {
+    let today = now.date();
+    let month_start = today.with_day(1).unwrap_or(today);
+
+    let dated: Vec<(NaiveDate, &DailyActivity)> = activities
+        .iter()
+        .filter_map(|activity| {
+            NaiveDate::parse_from_str(&activity.date, "%Y-%m-%d")
+                .ok()
+                .map(|date| (date, activity))
+        })
+        .collect();
+
+    let (today_tokens, today_cost_usd) = sum_activities(
+        dated
+            .iter()
+            .filter(|(date, _)| *date == today)
+            .map(|(_, activity)| *activity),
+    );
+
+    let today_hours_elapsed =
+        (now - today.and_hms_opt(0, 0, 0).unwrap_or(now)).num_seconds() as f64 / 3600.0;
+    let pace_hours = today_hours_elapsed.max(1.0);
+    let today_tokens_per_hour = today_tokens as f64 / pace_hours;
+    let today_projected_cost_usd = (today_cost_usd / pace_hours * 24.0).max(today_cost_usd);
+
+    let windows: Vec<BurnRateWindow> = BURN_RATE_WINDOWS
+        .iter()
+        .map(|&window_days| {
+            let window_start = today - Duration::days(window_days);
+            let (total_tokens, total_cost_usd) = sum_activities(
+                dated
+                    .iter()
+                    .filter(|(date, _)| *date >= window_start && *date < today)
+                    .map(|(_, activity)| *activity),
+            );
+
+            BurnRateWindow {
+                window_days,
+                total_tokens,
+                total_cost_usd,
+                tokens_per_hour: total_tokens as f64 / (window_days * 24) as f64,
+                cost_per_day: total_cost_usd / window_days as f64,
+            }
+        })
+        .collect();
+
+    let month_to_date_cost_usd: f64 = dated
+        .iter()
+        .filter(|(date, _)| *date >= month_start && *date <= today)
+        .map(|(_, activity)| activity.cost_usd)
+        .sum();
+
+    let days_remaining_in_month = days_in_month(today) - today.day() as i64;
+
+    let daily_rate = windows
+        .iter()
+        .find(|window| window.total_cost_usd > 0.0)
+        .map(|window| window.cost_per_day)
+        .unwrap_or(today_projected_cost_usd);
+
+    let projected_month_end_cost_usd = (month_to_date_cost_usd - today_cost_usd)
+        + today_projected_cost_usd
+        + days_remaining_in_month as f64 * daily_rate;
+
+    BurnRate {
+        today_tokens,
+        today_cost_usd,
+        today_hours_elapsed,
+        today_tokens_per_hour,
+        today_projected_cost_usd,
+        windows,
+        month_to_date_cost_usd,
+        days_remaining_in_month,
+        projected_month_end_cost_usd,
+        generated_at: Utc::now().to_rfc3339(),
+    }
+}
+
+/// 汇总 Token 总数（输入 + 输出）与费用
+fn sum_activities<'a>(activities: impl Iterator<Item = &'a DailyActivity>) -> (i64, f64) {
+    activities.fold((0, 0.0), |(tokens, cost), activity| {
+        (tokens + activity.total_tokens(), cost + activity.cost_usd)
+    })
+}
+
+/// 计算指定日期所在月份的天数
+fn days_in_month(date: NaiveDate) -> i64 {
+    let month_start = date.with_day(1).unwrap_or(date);
+    let next_month_start = if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1)
+    };
+
+    next_month_start
+        .map(|next| (next - month_start).num_days())
+        .unwrap_or(30)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(date: &str, tokens: i64, cost_usd: f64) -> DailyActivity {
+        DailyActivity {
+            date: date.to_string(),
+            input_tokens: tokens,
+            output_tokens: 0,
+            cost_usd,
+            session_count: 1,
+            message_count: 1,
+            note: None,
+            api_equivalent_cost_usd: cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_calculate_burn_rate_projection() {
+        let activities: Vec<DailyActivity> = (4..=9)
+            .map(|day| activity(&format!("2026-10-{:02}", day), 1_000, 2.0))
+            .chain(std::iter::once(activity("2026-10-10", 600, 3.0)))
+            .collect();
+        let now = NaiveDate::from_ymd_opt(2026, 10, 10)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .expect("now");
+
+        let rate = calculate_burn_rate(&activities, now);
+
+        assert_eq!(rate.today_tokens, 600);
+        assert_eq!(rate.today_tokens_per_hour, 50.0);
+        assert_eq!(rate.today_projected_cost_usd, 6.0);
+        assert_eq!(rate.windows[0].window_days, 7);
+        assert_eq!(rate.windows[0].total_cost_usd, 12.0);
+        assert_eq!(rate.month_to_date_cost_usd, 15.0);
+        assert_eq!(rate.days_remaining_in_month, 21);
+        // 12 (此前) + 6 (今日预测) + 21 × (12 / 7)
+        assert!((rate.projected_month_end_cost_usd - 54.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        let february = NaiveDate::from_ymd_opt(2028, 2, 10).expect("date");
+        let december = NaiveDate::from_ymd_opt(2026, 12, 31).expect("date");
+
+        assert_eq!(days_in_month(february), 29);
+        assert_eq!(days_in_month(december), 31);
+    }
+}
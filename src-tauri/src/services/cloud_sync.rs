@@ -0,0 +1,660 @@
+//! @file cloud_sync.rs
+//! @description 云同步服务，将加密后的数据快照推送到 WebDAV / S3 兼容存储并合并远端数据；密码、访问密钥与加密口令保存在系统钥匙串
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::io::Read;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::settings::AppSetting;
+use crate::models::{
+    S3Config, SyncBackend, SyncResult, SyncSecret, SyncSecretStatus, SyncSettings, WebDavConfig,
+};
+use crate::services::keychain::{self, KeychainError};
+use crate::services::snapshot::{build_snapshot, merge_snapshot, read_snapshot, SnapshotError};
+
+/// 加密快照文件头，用于识别格式版本
+const ENCRYPTED_MAGIC: &[u8] = b"CTMSYNC1";
+
+/// PBKDF2 盐长度
+const SALT_LEN: usize = 16;
+
+/// AES-GCM 随机数长度
+const NONCE_LEN: usize = 12;
+
+/// PBKDF2 迭代次数
+const PBKDF2_ROUNDS: u32 = 200_000;
+
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("Snapshot error: {0}")]
+    Snapshot(#[from] SnapshotError),
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("Keychain error: {0}")]
+    Keychain(#[from] KeychainError),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("同步配置不完整: {0}")]
+    Config(&'static str),
+    #[error("快照解密失败，请检查加密口令是否与其他设备一致")]
+    Decrypt,
+}
+
+/// 旧版本明文保存在云同步设置中的密钥，只用于迁移到钥匙串
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct LegacySyncSecrets {
+    webdav: LegacyWebDavSecret,
+    s3: LegacyS3Secret,
+    passphrase: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct LegacyWebDavSecret {
+    password: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct LegacyS3Secret {
+    secret_access_key: String,
+}
+
+impl AppSetting for LegacySyncSecrets {
+    const KEY: &'static str = SyncSettings::KEY;
+}
+
+/// 远端快照存储
+pub trait SyncRemote {
+    /// 下载远端快照，远端尚无快照时返回 None
+    fn download(&self) -> Result<Option<Vec<u8>>, SyncError>;
+
+    /// 上传快照，覆盖远端已有内容
+    fn upload(&self, data: &[u8]) -> Result<(), SyncError>;
+}
+
+/// 执行一次同步
+///
+/// 业务逻辑：
+/// 1. 下载并解密远端快照，按 message_id 去重合并到本地（与快照导入规则一致）
+/// 2. 导出合并后的本地快照，加密后覆盖远端
+///
+/// 每台设备推送前都会先合并远端数据，多台设备依次同步后数据趋于一致
+pub fn sync_once(
+    repository: &Repository,
+    remote: &dyn SyncRemote,
+    passphrase: &str,
+) -> Result<SyncResult, SyncError> {
+    if passphrase.is_empty() {
+        return Err(SyncError::Config("未设置加密口令"));
+    }
+
+    let pulled = match remote.download()? {
+        Some(data) => {
+            let snapshot = read_snapshot(&decrypt_snapshot(&data, passphrase)?)?;
+            Some(merge_snapshot(repository, &snapshot)?)
+        }
+        None => None,
+    };
+
+    let snapshot = build_snapshot(repository)?;
+    remote.upload(&encrypt_snapshot(
+        &serde_json::to_vec(&snapshot)?,
+        passphrase,
+    )?)?;
+
+    Ok(SyncResult {
+        pulled,
+        pushed_messages: snapshot.messages.len(),
+        synced_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// 读取云同步设置，并从钥匙串填入 WebDAV 密码、S3 私有访问密钥与加密口令
+pub fn load_sync_settings(repository: &Repository) -> Result<SyncSettings, SyncError> {
+    let namespace = migrate_legacy_secrets(repository)?;
+    let load =
+        |secret| keychain::load_sync_secret(&namespace, secret).map(Option::unwrap_or_default);
+
+    let mut settings: SyncSettings = repository.get_setting()?;
+    settings.webdav.password = load(SyncSecret::WebDavPassword)?;
+    settings.s3.secret_access_key = load(SyncSecret::S3SecretAccessKey)?;
+    settings.passphrase = load(SyncSecret::Passphrase)?;
+    Ok(settings)
+}
+
+/// 各云同步密钥是否已保存在钥匙串中
+pub fn sync_secret_status(repository: &Repository) -> Result<SyncSecretStatus, SyncError> {
+    let namespace = migrate_legacy_secrets(repository)?;
+    let is_set =
+        |secret| keychain::load_sync_secret(&namespace, secret).map(|value| value.is_some());
+
+    Ok(SyncSecretStatus {
+        webdav_password: is_set(SyncSecret::WebDavPassword)?,
+        s3_secret_access_key: is_set(SyncSecret::S3SecretAccessKey)?,
+        passphrase: is_set(SyncSecret::Passphrase)?,
+    })
+}
+
+/// 保存云同步密钥到钥匙串，value 为空时删除
+pub fn set_sync_secret(
+    repository: &Repository,
+    secret: SyncSecret,
+    value: Option<&str>,
+) -> Result<(), SyncError> {
+    let namespace = migrate_legacy_secrets(repository)?;
+    match value.filter(|value| !value.is_empty()) {
+        Some(value) => keychain::store_sync_secret(&namespace, secret, value)?,
+        None => {
+            keychain::delete_sync_secret(&namespace, secret)?;
+        }
+    }
+    Ok(())
+}
+
+/// 将旧版本明文保存在设置中的密钥迁移到钥匙串，并从设置中移除
+///
+/// 保存新设置前必须先调用，否则旧密钥会随设置被覆盖而丢失
+///
+/// # 返回
+/// 当前数据库的钥匙串命名空间
+pub fn migrate_legacy_secrets(repository: &Repository) -> Result<String, SyncError> {
+    let namespace = repository.keychain_namespace()?;
+    let legacy: LegacySyncSecrets = repository.get_setting()?;
+    let secrets = [
+        (SyncSecret::WebDavPassword, legacy.webdav.password),
+        (SyncSecret::S3SecretAccessKey, legacy.s3.secret_access_key),
+        (SyncSecret::Passphrase, legacy.passphrase),
+    ];
+    if secrets.iter().all(|(_, value)| value.is_empty()) {
+        return Ok(namespace);
+    }
+
+    for (secret, value) in &secrets {
+        if !value.is_empty() {
+            keychain::store_sync_secret(&namespace, *secret, value)?;
+        }
+    }
+    // 密钥字段不参与序列化，重新保存设置即去掉明文
+    let settings: SyncSettings = repository.get_setting()?;
+    repository.set_setting(&settings)?;
+    tracing::info!("已将云同步密钥迁移到钥匙串");
+    Ok(namespace)
+}
+
+/// 根据设置创建远端存储
+pub fn remote_from_settings(settings: &SyncSettings) -> Result<Box<dyn SyncRemote>, SyncError> {
+    if settings.object_key.trim_matches('/').is_empty() {
+        return Err(SyncError::Config("未设置远端对象路径"));
+    }
+    match settings.backend {
+        SyncBackend::WebDav => Ok(Box::new(WebDavRemote::new(
+            &settings.webdav,
+            &settings.object_key,
+        )?)),
+        SyncBackend::S3 => Ok(Box::new(S3Remote::new(&settings.s3, &settings.object_key)?)),
+    }
+}
+
+/// 使用口令加密快照
+///
+/// 格式：文件头 + 盐 + 随机数 + AES-256-GCM 密文；密钥由 PBKDF2-HMAC-SHA256 从口令派生
+pub fn encrypt_snapshot(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, SyncError> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let nonce: [u8; NONCE_LEN] = rand::random();
+
+    let cipher = cipher_for(passphrase, &salt);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| SyncError::Decrypt)?;
+
+    let mut data =
+        Vec::with_capacity(ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(ENCRYPTED_MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// 使用口令解密快照，口令错误或数据被篡改时返回 Decrypt 错误
+pub fn decrypt_snapshot(data: &[u8], passphrase: &str) -> Result<Vec<u8>, SyncError> {
+    let body = data
+        .strip_prefix(ENCRYPTED_MAGIC)
+        .filter(|body| body.len() > SALT_LEN + NONCE_LEN)
+        .ok_or(SyncError::Decrypt)?;
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    cipher_for(passphrase, salt)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| SyncError::Decrypt)
+}
+
+fn cipher_for(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    Aes256Gcm::new(&key.into())
+}
+
+/// WebDAV 远端存储
+pub struct WebDavRemote {
+    object_url: String,
+    collection_urls: Vec<String>,
+    authorization: String,
+}
+
+impl WebDavRemote {
+    pub fn new(config: &WebDavConfig, object_key: &str) -> Result<Self, SyncError> {
+        let base = config.url.trim_end_matches('/');
+        if base.is_empty() {
+            return Err(SyncError::Config("未设置 WebDAV 地址"));
+        }
+
+        let segments = encoded_segments(object_key);
+        let collection_urls = (1..segments.len())
+            .map(|depth| format!("{}/{}", base, segments[..depth].join("/")))
+            .collect();
+        let credentials = format!("{}:{}", config.username, config.password);
+
+        Ok(Self {
+            object_url: format!("{}/{}", base, segments.join("/")),
+            collection_urls,
+            authorization: format!("Basic {}", BASE64.encode(credentials)),
+        })
+    }
+}
+
+impl SyncRemote for WebDavRemote {
+    fn download(&self) -> Result<Option<Vec<u8>>, SyncError> {
+        let response = ureq::get(&self.object_url)
+            .set("Authorization", &self.authorization)
+            .call();
+        read_download(response)
+    }
+
+    fn upload(&self, data: &[u8]) -> Result<(), SyncError> {
+        // 逐级创建目录；目录已存在时服务器返回 405，忽略即可，真正的失败由 PUT 报告
+        for url in &self.collection_urls {
+            let _ = ureq::request("MKCOL", url)
+                .set("Authorization", &self.authorization)
+                .call();
+        }
+
+        ureq::put(&self.object_url)
+            .set("Authorization", &self.authorization)
+            .set("Content-Type", "application/octet-stream")
+            .send_bytes(data)
+            .map_err(|e| SyncError::Http(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// S3 兼容远端存储，请求使用 AWS Signature V4 签名
+pub struct S3Remote {
+    endpoint: String,
+    host: String,
+    canonical_uri: String,
+    config: S3Config,
+}
+
+impl S3Remote {
+    pub fn new(config: &S3Config, object_key: &str) -> Result<Self, SyncError> {
+        let endpoint = config.endpoint.trim_end_matches('/').to_string();
+        let host = endpoint
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(&endpoint)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        if host.is_empty() {
+            return Err(SyncError::Config("未设置 S3 服务地址"));
+        }
+        if config.bucket.is_empty() || config.region.is_empty() {
+            return Err(SyncError::Config("未设置 S3 存储桶或区域"));
+        }
+        if config.access_key_id.is_empty() || config.secret_access_key.is_empty() {
+            return Err(SyncError::Config("未设置 S3 访问密钥"));
+        }
+
+        let canonical_uri = format!(
+            "/{}/{}",
+            uri_encode(&config.bucket),
+            encoded_segments(object_key).join("/")
+        );
+
+        Ok(Self {
+            endpoint,
+            host,
+            canonical_uri,
+            config: config.clone(),
+        })
+    }
+
+    fn request(&self, method: &str, payload: &[u8]) -> ureq::Request {
+        let now = Utc::now();
+        let payload_hash = hex::encode(Sha256::digest(payload));
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = sign_s3_request(
+            &self.config,
+            method,
+            &self.host,
+            &self.canonical_uri,
+            &payload_hash,
+            now,
+        );
+
+        ureq::request(method, &format!("{}{}", self.endpoint, self.canonical_uri))
+            .set("Host", &self.host)
+            .set("x-amz-content-sha256", &payload_hash)
+            .set("x-amz-date", &amz_date)
+            .set("Authorization", &authorization)
+    }
+}
+
+impl SyncRemote for S3Remote {
+    fn download(&self) -> Result<Option<Vec<u8>>, SyncError> {
+        read_download(self.request("GET", &[]).call())
+    }
+
+    fn upload(&self, data: &[u8]) -> Result<(), SyncError> {
+        self.request("PUT", data)
+            .set("Content-Type", "application/octet-stream")
+            .send_bytes(data)
+            .map_err(|e| SyncError::Http(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// 生成 S3 请求的 Authorization 头（AWS Signature V4，签名 host、x-amz-content-sha256、x-amz-date）
+pub fn sign_s3_request(
+    config: &S3Config,
+    method: &str,
+    host: &str,
+    canonical_uri: &str,
+    payload_hash: &str,
+    now: DateTime<Utc>,
+) -> String {
+    const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+
+    let canonical_request = format!(
+        "{method}\n{canonical_uri}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{SIGNED_HEADERS}\n{payload_hash}"
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let signing_key = [date.as_str(), config.region.as_str(), "s3", "aws4_request"]
+        .iter()
+        .fold(
+            format!("AWS4{}", config.secret_access_key).into_bytes(),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        config.access_key_id, scope, SIGNED_HEADERS, signature
+    )
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// 读取下载响应，404 视为远端尚无快照
+fn read_download(
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<Option<Vec<u8>>, SyncError> {
+    match response {
+        Ok(response) => {
+            let mut data = Vec::new();
+            response
+                .into_reader()
+                .read_to_end(&mut data)
+                .map_err(|e| SyncError::Http(e.to_string()))?;
+            Ok(Some(data))
+        }
+        Err(ureq::Error::Status(404, _)) => Ok(None),
+        Err(e) => Err(SyncError::Http(e.to_string())),
+    }
+}
+
+/// 按 / 拆分对象路径并对每段做 URI 编码，忽略空段
+fn encoded_segments(object_key: &str) -> Vec<String> {
+    object_key
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(uri_encode)
+        .collect()
+}
+
+/// URI 编码，保留 RFC 3986 非保留字符
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    /// 内存中的远端存储
+    #[derive(Default)]
+    struct MemoryRemote {
+        data: RefCell<Option<Vec<u8>>>,
+    }
+
+    impl SyncRemote for MemoryRemote {
+        fn download(&self) -> Result<Option<Vec<u8>>, SyncError> {
+            Ok(self.data.borrow().clone())
+        }
+
+        fn upload(&self, data: &[u8]) -> Result<(), SyncError> {
+            *self.data.borrow_mut() = Some(data.to_vec());
+            Ok(())
+        }
+    }
+
+    fn repository_with(message_ids: &[&str]) -> Repository {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-shared", None).expect("provider");
+        for message_id in message_ids {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                Utc::now().to_rfc3339(),
+                MessageUsage::default(),
+            );
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+        repo
+    }
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let data = encrypt_snapshot(b"{\"format_version\":1}", "secret").expect("encrypt");
+        assert!(data.starts_with(ENCRYPTED_MAGIC));
+        assert_eq!(
+            decrypt_snapshot(&data, "secret").expect("decrypt"),
+            b"{\"format_version\":1}"
+        );
+        assert!(matches!(
+            decrypt_snapshot(&data, "wrong"),
+            Err(SyncError::Decrypt)
+        ));
+        assert!(matches!(
+            decrypt_snapshot(b"plain", "secret"),
+            Err(SyncError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn test_sync_once_merges_devices() {
+        let remote = MemoryRemote::default();
+        let desktop = repository_with(&["message-1", "message-2"]);
+        let laptop = repository_with(&["message-2", "message-3"]);
+
+        let result = sync_once(&desktop, &remote, "secret").expect("sync");
+        assert!(result.pulled.is_none());
+        assert_eq!(result.pushed_messages, 2);
+
+        let result = sync_once(&laptop, &remote, "secret").expect("sync");
+        let pulled = result.pulled.expect("pulled");
+        assert_eq!(pulled.messages_added, 1);
+        assert_eq!(pulled.messages_skipped, 1);
+        assert_eq!(result.pushed_messages, 3);
+
+        let result = sync_once(&desktop, &remote, "secret").expect("sync");
+        assert_eq!(result.pulled.expect("pulled").messages_added, 1);
+        assert_eq!(
+            desktop.get_current_stats().expect("stats").total_messages,
+            3
+        );
+
+        assert!(matches!(
+            sync_once(&desktop, &remote, "wrong"),
+            Err(SyncError::Decrypt)
+        ));
+        assert!(matches!(
+            sync_once(&desktop, &remote, ""),
+            Err(SyncError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_sign_s3_request() {
+        let config = S3Config {
+            endpoint: "https://s3.us-east-1.amazonaws.com".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: "backups".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+        };
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 8, 30, 0).unwrap();
+        let payload_hash = hex::encode(Sha256::digest(b""));
+
+        let authorization = sign_s3_request(
+            &config,
+            "GET",
+            "s3.us-east-1.amazonaws.com",
+            "/backups/claude-token-monitor/snapshot.enc",
+            &payload_hash,
+            now,
+        );
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20261017/us-east-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, \
+             Signature=8f1b345edc4e2492dcc524da7fe44f1031eca3826cb967fa5a15e890ffaf2775"
+        );
+    }
+
+    #[test]
+    fn test_sync_settings_keep_secrets_out_of_database() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let mut settings = SyncSettings {
+            passphrase: "secret".to_string(),
+            ..Default::default()
+        };
+        settings.webdav.password = "pw".to_string();
+        settings.s3.secret_access_key = "key".to_string();
+        repo.set_setting(&settings).expect("save");
+
+        let legacy: LegacySyncSecrets = repo.get_setting().expect("legacy");
+        assert!(legacy.webdav.password.is_empty());
+        assert!(legacy.s3.secret_access_key.is_empty());
+        assert!(legacy.passphrase.is_empty());
+
+        // 旧版本明文保存的密钥仍可读出以便迁移
+        let legacy: LegacySyncSecrets = serde_json::from_str(
+            r#"{"webdav":{"url":"https://dav.example.com","password":"pw"},"passphrase":"secret"}"#,
+        )
+        .expect("parse");
+        assert_eq!(legacy.webdav.password, "pw");
+        assert_eq!(legacy.passphrase, "secret");
+    }
+
+    #[test]
+    fn test_remote_urls() {
+        let remote = WebDavRemote::new(
+            &WebDavConfig {
+                url: "https://dav.example.com/files/".to_string(),
+                username: "me".to_string(),
+                password: "pw".to_string(),
+            },
+            "/ctm backups/snapshot.enc",
+        )
+        .expect("remote");
+        assert_eq!(
+            remote.object_url,
+            "https://dav.example.com/files/ctm%20backups/snapshot.enc"
+        );
+        assert_eq!(
+            remote.collection_urls,
+            vec!["https://dav.example.com/files/ctm%20backups"]
+        );
+        assert_eq!(remote.authorization, "Basic bWU6cHc=");
+
+        let remote = S3Remote::new(
+            &S3Config {
+                endpoint: "http://localhost:9000/".to_string(),
+                region: "us-east-1".to_string(),
+                bucket: "backups".to_string(),
+                access_key_id: "key".to_string(),
+                secret_access_key: "secret".to_string(),
+            },
+            "claude-token-monitor/snapshot.enc",
+        )
+        .expect("remote");
+        assert_eq!(remote.host, "localhost:9000");
+        assert_eq!(
+            remote.canonical_uri,
+            "/backups/claude-token-monitor/snapshot.enc"
+        );
+
+        assert!(matches!(
+            S3Remote::new(&S3Config::default(), "snapshot.enc"),
+            Err(SyncError::Config(_))
+        ));
+    }
+}
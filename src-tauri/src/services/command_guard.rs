@@ -0,0 +1,47 @@
+//! @file command_guard.rs
+//! @description 命令调用前置检查，只读模式拒绝修改类命令，应用锁定时拒绝数据命令
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::Instant;
+
+use tauri::ipc::Invoke;
+use tauri::{Manager, Runtime};
+
+use crate::services::app_lock::{is_allowed_while_locked, AppLock};
+use crate::services::read_only::{is_mutating_command, ReadOnlyMode};
+
+/// 包装命令处理器：被拒绝的命令直接返回错误，不进入处理器
+pub fn guard_invoke_handler<R, F>(handler: F) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke: Invoke<R>| {
+        let command = invoke.message.command();
+        let webview = invoke.message.webview_ref();
+        let rejection = if webview
+            .try_state::<AppLock>()
+            .is_some_and(|lock| lock.is_locked(Instant::now()))
+            && !is_allowed_while_locked(command)
+        {
+            Some("应用已锁定，请先解锁")
+        } else if webview
+            .try_state::<ReadOnlyMode>()
+            .is_some_and(|mode| mode.is_enabled())
+            && is_mutating_command(command)
+        {
+            Some("只读模式下不允许此操作")
+        } else {
+            None
+        };
+
+        match rejection {
+            Some(reason) => {
+                tracing::warn!("拒绝命令 {}: {}", command, reason);
+                invoke.resolver.reject(format!("{}: {}", reason, command));
+                true
+            }
+            None => handler(invoke),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! @file context_usage.rs
+//! @description 会话上下文窗口占用计算服务，会话接近模型上下文上限时给出提示
+//! @author Atlas.oi
+//! @date 2026-10-17
+use crate::db::{Repository, RepositoryError};
+use crate::models::{SessionContextUsage, StoredMessage};
+use crate::services::pricing::PricingService;
+
+/// 占用比例达到该值时视为接近上限
+pub const CONTEXT_WARNING_RATIO: f64 = 0.8;
+
+/// 获取会话当前的上下文窗口占用，会话不存在时返回 None
+pub fn get_session_context_usage(
+    repository: &Repository,
+    pricing: &PricingService,
+    session_id: &str,
+) -> Result<Option<SessionContextUsage>, RepositoryError> {
+    Ok(repository
+        .get_latest_session_message(session_id)?
+        .map(|message| calculate_context_usage(&message, pricing)))
+}
+
+/// 根据会话最新一条消息计算上下文占用
+pub fn calculate_context_usage(
+    message: &StoredMessage,
+    pricing: &PricingService,
+) -> SessionContextUsage {
+    let context_tokens =
+        message.input_tokens + message.cache_read_tokens + message.cache_creation_tokens;
+    let context_window_tokens = pricing.context_window(&message.model);
+    let usage_ratio = if context_window_tokens > 0 {
+        context_tokens as f64 / context_window_tokens as f64
+    } else {
+        0.0
+    };
+
+    SessionContextUsage {
+        session_id: message.session_id.clone(),
+        provider_id: message.provider_id,
+        model: message.model.clone(),
+        context_tokens,
+        context_window_tokens,
+        usage_ratio,
+        near_limit: usage_ratio >= CONTEXT_WARNING_RATIO,
+        last_message_at: message.created_at.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::pricing::DEFAULT_CONTEXT_WINDOW_TOKENS;
+
+    fn message(model: &str, input_tokens: i64, cache_read_tokens: i64) -> StoredMessage {
+        StoredMessage {
+            id: 1,
+            provider_id: 1,
+            session_id: "session-1".to_string(),
+            message_id: "message-1".to_string(),
+            model: model.to_string(),
+            project: None,
+            input_tokens,
+            output_tokens: 500,
+            cache_read_tokens,
+            cache_creation_tokens: 1_000,
+            cost_usd: 0.0,
+            created_at: "2026-10-17T10:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_calculate_context_usage() {
+        let pricing = PricingService::new();
+
+        let usage =
+            calculate_context_usage(&message("claude-3-opus-20240229", 9_000, 90_000), &pricing);
+        assert_eq!(usage.context_tokens, 100_000);
+        assert_eq!(usage.context_window_tokens, DEFAULT_CONTEXT_WINDOW_TOKENS);
+        assert_eq!(usage.usage_ratio, 0.5);
+        assert!(!usage.near_limit);
+
+        let usage = calculate_context_usage(&message("unknown-model", 9_000, 160_000), &pricing);
+        assert_eq!(usage.context_window_tokens, DEFAULT_CONTEXT_WINDOW_TOKENS);
+        assert!(usage.near_limit);
+
+        // Sonnet 4 的长上下文只影响计价，170k 仍接近 200k 上限
+        let usage = calculate_context_usage(
+            &message("claude-sonnet-4-20250514", 19_000, 150_000),
+            &pricing,
+        );
+        assert_eq!(usage.context_tokens, 170_000);
+        assert_eq!(usage.context_window_tokens, DEFAULT_CONTEXT_WINDOW_TOKENS);
+        assert!(usage.near_limit);
+    }
+
+    #[test]
+    fn test_get_session_context_usage_missing_session() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let usage = get_session_context_usage(&repository, &PricingService::new(), "missing")
+            .expect("query");
+        assert!(usage.is_none());
+    }
+}
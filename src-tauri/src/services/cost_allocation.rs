@@ -0,0 +1,115 @@
+//! @file cost_allocation.rs
+//! @description 费用分摊导出服务，将按项目或标签拆分的费用写为 CSV / JSON 文件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{AllocationGroupBy, CostAllocationExport, CostAllocationRow, ExportFormat};
+
+#[derive(Error, Debug)]
+pub enum AllocationError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// CSV 表头
+const CSV_HEADER: &str = "group,session_count,message_count,input_tokens,output_tokens,cache_read_tokens,cache_creation_tokens,cost_usd,cost_share";
+
+/// 导出日期范围内的费用分摊并写入 path
+pub fn export_cost_allocation(
+    repository: &Repository,
+    start_date: &str,
+    end_date: &str,
+    group_by: AllocationGroupBy,
+    format: ExportFormat,
+    path: &Path,
+) -> Result<CostAllocationExport, AllocationError> {
+    let rows = repository.get_cost_allocation(group_by, start_date, end_date)?;
+    let content = match format {
+        ExportFormat::Csv => render_csv(&rows),
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+
+    Ok(CostAllocationExport {
+        path: path.display().to_string(),
+        group_by,
+        format,
+        row_count: rows.len(),
+        total_cost_usd: repository.get_range_cost(start_date, end_date)?,
+    })
+}
+
+/// 将费用分摊渲染为 CSV，无项目信息的分组留空
+pub fn render_csv(rows: &[CostAllocationRow]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{:.6},{:.4}\n",
+            escape_csv(row.group.as_deref().unwrap_or_default()),
+            row.session_count,
+            row.message_count,
+            row.input_tokens,
+            row.output_tokens,
+            row.cache_read_tokens,
+            row.cache_creation_tokens,
+            row.cost_usd,
+            row.cost_share
+        ));
+    }
+    csv
+}
+
+/// 包含逗号、引号或换行的字段加引号，内部引号转义为两个引号
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(group: Option<&str>, cost_usd: f64) -> CostAllocationRow {
+        CostAllocationRow {
+            group: group.map(|group| group.to_string()),
+            session_count: 1,
+            message_count: 2,
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd,
+            cost_share: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let csv = render_csv(&[row(Some("client \"A\", inc"), 1.5), row(None, 1.5)]);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "\"client \"\"A\"\", inc\",1,2,100,50,0,0,1.500000,0.5000"
+        );
+        assert!(lines[2].starts_with(",1,2,"));
+    }
+}
@@ -0,0 +1,200 @@
+//! @file cost_simulation.rs
+//! @description 模型费用模拟服务，将历史用量按其他模型重新计价，评估换用模型的费用差异
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{CostSimulation, ModelCostSimulation};
+use crate::services::pricing::PricingService;
+
+/// 映射所有模型的通配键
+pub const WILDCARD_MODEL: &str = "*";
+
+/// 模拟日期范围内的用量换用其他模型后的费用
+///
+/// model_mapping 的键为实际模型（精确名称或前缀，如 claude-3-opus），值为目标模型；
+/// 键 `*` 匹配所有模型，例如 `{"*": "claude-3-haiku"}` 表示全部换用 Haiku。
+/// 多个键匹配时使用最长的键
+///
+/// 实际费用同样按当前价格重新计算，而不是使用记录的费用，
+/// 避免订阅供应商费用为 0 或历史价格变动影响对比
+pub fn simulate_cost(
+    repository: &Repository,
+    pricing: &PricingService,
+    start_date: &str,
+    end_date: &str,
+    model_mapping: &BTreeMap<String, String>,
+) -> Result<CostSimulation, RepositoryError> {
+    let mut usages: HashMap<String, ModelCostSimulation> = HashMap::new();
+    for row in repository.get_model_daily_usage(start_date, end_date)? {
+        let usage = usages
+            .entry(row.model.clone())
+            .or_insert_with(|| ModelCostSimulation {
+                target_model: map_model(&row.model, model_mapping).to_string(),
+                model: row.model.clone(),
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                message_count: 0,
+                original_cost_usd: 0.0,
+                simulated_cost_usd: 0.0,
+                delta_usd: 0.0,
+            });
+        usage.input_tokens += row.input_tokens;
+        usage.output_tokens += row.output_tokens;
+        usage.cache_read_tokens += row.cache_read_tokens;
+        usage.cache_creation_tokens += row.cache_creation_tokens;
+        usage.message_count += row.message_count;
+        usage.original_cost_usd += row.cost_usd;
+    }
+
+    let mut unpriced_models = BTreeSet::new();
+    let mut models: Vec<ModelCostSimulation> = usages
+        .into_values()
+        .map(|mut usage| {
+            let price = |model: &str| {
+                pricing.calculate_cost(
+                    model,
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.cache_read_tokens,
+                    usage.cache_creation_tokens,
+                )
+            };
+            if pricing.has_pricing(&usage.model) {
+                usage.original_cost_usd = price(&usage.model);
+            } else {
+                unpriced_models.insert(usage.model.clone());
+            }
+            usage.simulated_cost_usd = if pricing.has_pricing(&usage.target_model) {
+                price(&usage.target_model)
+            } else {
+                unpriced_models.insert(usage.target_model.clone());
+                usage.original_cost_usd
+            };
+            usage.delta_usd = usage.simulated_cost_usd - usage.original_cost_usd;
+            usage
+        })
+        .collect();
+    models.sort_by(|a, b| {
+        b.original_cost_usd
+            .partial_cmp(&a.original_cost_usd)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    let original_cost_usd: f64 = models.iter().map(|m| m.original_cost_usd).sum();
+    let simulated_cost_usd: f64 = models.iter().map(|m| m.simulated_cost_usd).sum();
+    let delta_usd = simulated_cost_usd - original_cost_usd;
+
+    Ok(CostSimulation {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        original_cost_usd,
+        simulated_cost_usd,
+        delta_usd,
+        delta_ratio: (original_cost_usd > 0.0).then(|| delta_usd / original_cost_usd),
+        models,
+        unpriced_models: unpriced_models.into_iter().collect(),
+    })
+}
+
+/// 按映射查找目标模型，无匹配时返回原模型
+fn map_model<'a>(model: &'a str, model_mapping: &'a BTreeMap<String, String>) -> &'a str {
+    model_mapping
+        .iter()
+        .filter(|(source, _)| {
+            source.as_str() == WILDCARD_MODEL || model.starts_with(source.as_str())
+        })
+        .max_by_key(|(source, _)| {
+            if source.as_str() == WILDCARD_MODEL {
+                0
+            } else {
+                source.len()
+            }
+        })
+        .map(|(_, target)| target.as_str())
+        .unwrap_or(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    #[test]
+    fn test_map_model() {
+        let mapping = BTreeMap::from([
+            ("*".to_string(), "claude-3-haiku".to_string()),
+            ("claude-3-opus".to_string(), "claude-3-sonnet".to_string()),
+        ]);
+        assert_eq!(
+            map_model("claude-3-opus-20240229", &mapping),
+            "claude-3-sonnet"
+        );
+        assert_eq!(map_model("claude-3-sonnet", &mapping), "claude-3-haiku");
+        assert_eq!(
+            map_model("claude-3-sonnet", &BTreeMap::new()),
+            "claude-3-sonnet"
+        );
+    }
+
+    #[test]
+    fn test_simulate_cost() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-simulate", None)
+            .expect("provider");
+        let created_at = chrono::Local::now();
+        for (index, model) in ["claude-3-opus-20240229", "custom-model"]
+            .iter()
+            .enumerate()
+        {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                format!("message-{}", index),
+                model.to_string(),
+                created_at.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 1_000_000,
+                    output_tokens: 100_000,
+                    cost_usd: 2.0,
+                    ..Default::default()
+                },
+            );
+            repository
+                .insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let today = created_at.date_naive().to_string();
+        let mapping = BTreeMap::from([("*".to_string(), "claude-3-haiku".to_string())]);
+        let simulation = simulate_cost(
+            &repository,
+            &PricingService::new(),
+            &today,
+            &today,
+            &mapping,
+        )
+        .expect("simulate");
+
+        assert_eq!(simulation.models.len(), 2);
+        let opus = &simulation.models[0];
+        assert_eq!(opus.model, "claude-3-opus-20240229");
+        assert_eq!(opus.target_model, "claude-3-haiku");
+        // Opus: 15 + 7.5 = 22.5；Haiku: 0.25 + 0.125 = 0.375
+        assert!((opus.original_cost_usd - 22.5).abs() < 1e-9);
+        assert!((opus.simulated_cost_usd - 0.375).abs() < 1e-9);
+
+        // 未配置价格的模型换用 Haiku 后按 Haiku 计价，原费用使用记录值
+        let custom = &simulation.models[1];
+        assert_eq!(custom.original_cost_usd, 2.0);
+        assert_eq!(simulation.unpriced_models, vec!["custom-model".to_string()]);
+
+        assert!((simulation.original_cost_usd - 24.5).abs() < 1e-9);
+        assert!((simulation.delta_usd - (0.75 - 24.5)).abs() < 1e-9);
+        assert!(simulation.delta_ratio.expect("ratio") < 0.0);
+    }
+}
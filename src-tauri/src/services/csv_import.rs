@@ -0,0 +1,400 @@
+//! @file csv_import.rs
+//! @description 通用 CSV 历史数据导入，按列映射将任意表格转换为消息记录写入数据库
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{CsvColumnMapping, CsvImportSummary, MessageRecord, MessageUsage};
+use crate::services::plan_limits::to_local;
+use crate::services::pricing::{pricing_date, PricingService};
+
+/// 未指定名称时导入数据归属的供应商名称
+pub const DEFAULT_IMPORT_PROVIDER_NAME: &str = "CSV Import";
+
+/// 导入结果中最多保留的错误条数
+const MAX_REPORTED_ERRORS: usize = 20;
+
+/// 未映射模型列时使用的模型名称
+const UNKNOWN_MODEL: &str = "unknown";
+
+#[derive(Error, Debug)]
+pub enum CsvImportError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("CSV 文件为空")]
+    Empty,
+    #[error("必须映射日期列")]
+    MissingDateMapping,
+    #[error("CSV 表头中找不到列: {0}")]
+    MissingColumn(String),
+}
+
+/// 映射后的列下标
+struct ColumnIndexes {
+    date: usize,
+    model: Option<usize>,
+    input_tokens: Option<usize>,
+    output_tokens: Option<usize>,
+    cache_read_tokens: Option<usize>,
+    cache_creation_tokens: Option<usize>,
+    total_tokens: Option<usize>,
+    cost_usd: Option<usize>,
+    session: Option<usize>,
+}
+
+/// 从文件导入 CSV
+///
+/// 数据归属名为 provider_name 的独立供应商（不会设为当前供应商）；
+/// 消息 ID 由行号与行内容生成，重复导入同一文件不会重复计数
+pub fn import_csv(
+    repository: &Repository,
+    path: &Path,
+    mapping: &CsvColumnMapping,
+    provider_name: Option<&str>,
+) -> Result<CsvImportSummary, CsvImportError> {
+    let content = std::fs::read_to_string(path)?;
+    import_csv_content(repository, &content, mapping, provider_name)
+}
+
+/// 导入 CSV 文本
+///
+/// 业务逻辑说明：
+/// 1. 按表头解析列映射，映射的列不存在时整体失败
+/// 2. 逐行转换为消息记录，无法解析的行计入 invalid_rows 并跳过
+/// 3. 未映射费用列或费用为空时按模型价格计算
+/// 4. 批量写入并重建每日汇总
+pub fn import_csv_content(
+    repository: &Repository,
+    content: &str,
+    mapping: &CsvColumnMapping,
+    provider_name: Option<&str>,
+) -> Result<CsvImportSummary, CsvImportError> {
+    let mut rows = parse_csv(content.trim_start_matches('\u{feff}')).into_iter();
+    let (_, header) = rows.next().ok_or(CsvImportError::Empty)?;
+    let columns = resolve_columns(&header, mapping)?;
+    let pricing = PricingService::new().with_aliases(&repository.get_model_aliases()?);
+
+    let mut summary = CsvImportSummary::default();
+    let mut records = Vec::new();
+    for (line, row) in rows {
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        summary.rows_read += 1;
+        match row_to_record(&row, &columns, &pricing, line) {
+            Ok(record) => records.push(record),
+            Err(message) => {
+                summary.invalid_rows += 1;
+                if summary.errors.len() < MAX_REPORTED_ERRORS {
+                    summary.errors.push(format!("第 {} 行: {}", line, message));
+                }
+            }
+        }
+    }
+
+    let name = provider_name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(DEFAULT_IMPORT_PROVIDER_NAME);
+    let provider =
+        repository.create_provider(&format!("csv-import:{}", name), Some(name.to_string()))?;
+    let (added, skipped) = repository.import_message_records(provider.id, &records)?;
+    summary.messages_added = added;
+    summary.messages_skipped = skipped;
+
+    tracing::info!(
+        "CSV 导入完成: 读取 {} 行，新增 {} 条，跳过 {} 条，无效 {} 行",
+        summary.rows_read,
+        summary.messages_added,
+        summary.messages_skipped,
+        summary.invalid_rows
+    );
+    Ok(summary)
+}
+
+fn resolve_columns(
+    header: &[String],
+    mapping: &CsvColumnMapping,
+) -> Result<ColumnIndexes, CsvImportError> {
+    let find = |name: &str| {
+        header
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| CsvImportError::MissingColumn(name.to_string()))
+    };
+    let find_optional = |name: &Option<String>| match name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => find(name).map(Some),
+        _ => Ok(None),
+    };
+
+    if mapping.date.trim().is_empty() {
+        return Err(CsvImportError::MissingDateMapping);
+    }
+    Ok(ColumnIndexes {
+        date: find(&mapping.date)?,
+        model: find_optional(&mapping.model)?,
+        input_tokens: find_optional(&mapping.input_tokens)?,
+        output_tokens: find_optional(&mapping.output_tokens)?,
+        cache_read_tokens: find_optional(&mapping.cache_read_tokens)?,
+        cache_creation_tokens: find_optional(&mapping.cache_creation_tokens)?,
+        total_tokens: find_optional(&mapping.total_tokens)?,
+        cost_usd: find_optional(&mapping.cost_usd)?,
+        session: find_optional(&mapping.session)?,
+    })
+}
+
+fn row_to_record(
+    row: &[String],
+    columns: &ColumnIndexes,
+    pricing: &PricingService,
+    line: usize,
+) -> Result<MessageRecord, String> {
+    let field = |index: Option<usize>| {
+        index
+            .and_then(|index| row.get(index))
+            .map(|value| value.trim())
+            .unwrap_or_default()
+    };
+    let tokens = |index: Option<usize>, name: &str| {
+        parse_number(field(index))
+            .map(|value| value.round() as i64)
+            .ok_or_else(|| format!("{} 不是有效数字: {}", name, field(index)))
+    };
+
+    let created_at = parse_timestamp(field(Some(columns.date)))
+        .ok_or_else(|| format!("无法解析日期: {}", field(Some(columns.date))))?;
+    let model = match field(columns.model) {
+        "" => UNKNOWN_MODEL.to_string(),
+        model => model.to_string(),
+    };
+
+    let mut usage = MessageUsage {
+        input_tokens: tokens(columns.input_tokens, "input_tokens")?,
+        output_tokens: tokens(columns.output_tokens, "output_tokens")?,
+        cache_read_tokens: tokens(columns.cache_read_tokens, "cache_read_tokens")?,
+        cache_creation_tokens: tokens(columns.cache_creation_tokens, "cache_creation_tokens")?,
+        thinking_tokens: 0,
+        cost_usd: 0.0,
+    };
+    if columns.input_tokens.is_none() && columns.output_tokens.is_none() {
+        usage.input_tokens = tokens(columns.total_tokens, "total_tokens")?;
+    }
+    if usage.input_tokens < 0
+        || usage.output_tokens < 0
+        || usage.cache_read_tokens < 0
+        || usage.cache_creation_tokens < 0
+    {
+        return Err("Token 数不能为负数".to_string());
+    }
+    usage.cost_usd = match field(columns.cost_usd) {
+        "" => pricing.calculate_cost_at(
+            &model,
+            pricing_date(&created_at),
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_read_tokens,
+            usage.cache_creation_tokens,
+        ),
+        value => parse_number(value).ok_or_else(|| format!("cost_usd 不是有效数字: {}", value))?,
+    };
+
+    let session_id = match field(columns.session) {
+        "" => format!("csv-import-{}", &created_at[..10]),
+        session => session.to_string(),
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(line.to_le_bytes());
+    hasher.update(row.join("\u{1f}").as_bytes());
+    let message_id = format!("csv-{}", &hex::encode(hasher.finalize())[..24]);
+
+    Ok(MessageRecord::new(
+        session_id, message_id, model, created_at, usage,
+    ))
+}
+
+/// 解析数字，允许千分位逗号、货币符号与空值（按 0 处理）
+fn parse_number(value: &str) -> Option<f64> {
+    let cleaned: String = value
+        .chars()
+        .filter(|ch| !matches!(ch, ',' | '$' | '_') && !ch.is_whitespace())
+        .collect();
+    if cleaned.is_empty() {
+        return Some(0.0);
+    }
+    cleaned
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+/// 解析时间为 RFC 3339；不带时区的时间按本地时间处理，只有日期时取当天正午
+fn parse_timestamp(value: &str) -> Option<String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.to_rfc3339());
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            ["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y"]
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .and_then(|date| date.and_hms_opt(12, 0, 0))
+        })?;
+    Some(to_local(naive).to_rfc3339())
+}
+
+/// 解析 RFC 4180 CSV，支持引号字段、字段内换行与转义引号
+///
+/// # 返回
+/// (起始行号, 字段) 列表，行号从 1 开始
+fn parse_csv(content: &str) -> Vec<(usize, Vec<String>)> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut row_start = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if ch == '\n' {
+                        line += 1;
+                    }
+                    field.push(ch);
+                }
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push((row_start, std::mem::take(&mut row)));
+                line += 1;
+                row_start = line;
+            }
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push((row_start, row));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> CsvColumnMapping {
+        CsvColumnMapping {
+            date: "Date".to_string(),
+            model: Some("Model".to_string()),
+            input_tokens: Some("Input".to_string()),
+            output_tokens: Some("Output".to_string()),
+            cost_usd: Some("Cost".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("a,b\r\n\"x, \"\"y\"\"\",\"multi\nline\"\n1,2");
+        assert_eq!(
+            rows,
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (2, vec!["x, \"y\"".to_string(), "multi\nline".to_string()]),
+                (4, vec!["1".to_string(), "2".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_values() {
+        assert_eq!(parse_number("$1,234.50"), Some(1234.5));
+        assert_eq!(parse_number(""), Some(0.0));
+        assert_eq!(parse_number("abc"), None);
+
+        assert_eq!(
+            parse_timestamp("2026-10-01T08:00:00Z").as_deref(),
+            Some("2026-10-01T08:00:00+00:00")
+        );
+        assert!(parse_timestamp("2026-10-01")
+            .expect("date")
+            .starts_with("2026-10-01T12:00:00"));
+        assert!(parse_timestamp("10/02/2026")
+            .expect("date")
+            .starts_with("2026-10-02"));
+        assert!(parse_timestamp("yesterday").is_none());
+    }
+
+    #[test]
+    fn test_import_csv_content() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let content = "Date,Model,Input,Output,Cost,Note\n\
+                       2026-10-01,claude-3-opus,\"1,000\",200,$1.50,first\n\
+                       2026-10-01,claude-3-opus,500,100,,second\n\
+                       2026-10-02,,100,0,0.1,\n\
+                       not-a-date,claude-3-opus,1,1,1,\n";
+
+        let summary = import_csv_content(&repository, content, &mapping(), None).expect("import");
+        assert_eq!(summary.rows_read, 4);
+        assert_eq!(summary.messages_added, 3);
+        assert_eq!(summary.invalid_rows, 1);
+        assert!(summary.errors[0].starts_with("第 5 行"));
+
+        let providers = repository.get_all_providers(false).expect("providers");
+        assert_eq!(providers.len(), 1);
+        assert!(!providers[0].is_active);
+        assert_eq!(
+            providers[0].display_name.as_deref(),
+            Some(DEFAULT_IMPORT_PROVIDER_NAME)
+        );
+
+        let days = repository
+            .get_daily_activities("2026-10-01", "2026-10-02")
+            .expect("daily");
+        assert_eq!(days.len(), 2);
+        let first = days
+            .iter()
+            .find(|day| day.date == "2026-10-01")
+            .expect("day");
+        assert_eq!(first.message_count, 2);
+        // 第二行未提供费用，按模型价格计算
+        assert!(first.cost_usd > 1.5);
+
+        let again = import_csv_content(&repository, content, &mapping(), None).expect("import");
+        assert_eq!(again.messages_added, 0);
+        assert_eq!(again.messages_skipped, 3);
+    }
+
+    #[test]
+    fn test_import_csv_missing_column() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let mut mapping = mapping();
+        mapping.cost_usd = Some("Price".to_string());
+        let result = import_csv_content(&repository, "Date,Model,Input,Output\n", &mapping, None);
+        assert!(matches!(result, Err(CsvImportError::MissingColumn(column)) if column == "Price"));
+    }
+}
@@ -0,0 +1,322 @@
+//! @file demo_data.rs
+//! @description 演示数据生成服务，向独立的演示数据库写入模拟的供应商、会话与消息
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{
+    DemoDataSummary, DemoIntensity, MessageRecord, MessageUsage, SessionTitleSource,
+};
+use crate::services::plan_limits::to_local;
+use crate::services::pricing::PricingService;
+
+/// 最多生成的天数
+pub const MAX_DEMO_DAYS: u32 = 365;
+
+/// 会话上下文上限（token），超过后视为已压缩
+const MAX_CONTEXT_TOKENS: i64 = 150_000;
+
+/// 演示供应商：(标识, 名称, 被选中的权重)
+const DEMO_PROVIDERS: [(&str, &str, f64); 2] = [
+    ("demo-sk-ant-primary", "Anthropic API", 0.75),
+    ("demo-sk-ant-team-proxy", "Team Proxy", 0.15),
+];
+
+/// 演示订阅账号，承担剩余的会话
+const DEMO_SUBSCRIPTION: (&str, &str) = ("demo-account", "Claude Max");
+
+/// 演示模型及权重
+const DEMO_MODELS: [(&str, f64); 3] = [
+    ("claude-3-sonnet-20240229", 0.6),
+    ("claude-3-opus-20240229", 0.2),
+    ("claude-3-haiku-20240307", 0.2),
+];
+
+const DEMO_PROJECTS: [&str; 5] = [
+    "/Users/demo/code/storefront",
+    "/Users/demo/code/orders-api",
+    "/Users/demo/code/data-pipeline",
+    "/Users/demo/code/infra",
+    "/Users/demo/code/mobile-app",
+];
+
+const DEMO_TITLES: [&str; 10] = [
+    "Fix flaky checkout integration test",
+    "Add pagination to orders endpoint",
+    "Refactor auth middleware",
+    "Investigate slow dashboard query",
+    "Write migration for user preferences",
+    "Upgrade build tooling",
+    "Add retry logic to webhook sender",
+    "Explain legacy billing module",
+    "Set up CI cache for dependencies",
+    "Draft release notes",
+];
+
+/// 删除已有的演示数据库并重新生成
+pub fn create_demo_database(
+    path: &Path,
+    days: u32,
+    intensity: DemoIntensity,
+) -> Result<DemoDataSummary, RepositoryError> {
+    for suffix in ["", "-wal", "-shm"] {
+        let file = path.with_file_name(format!(
+            "{}{}",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            suffix
+        ));
+        match std::fs::remove_file(&file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let repository = Repository::new(path)?;
+    let mut summary =
+        generate_demo_data(&repository, days, intensity, Local::now(), rand::random())?;
+    summary.path = path.display().to_string();
+    Ok(summary)
+}
+
+/// 生成截至 now 的 days 天演示数据
+///
+/// 业务逻辑说明：
+/// 1. 创建两个 API Key 供应商与一个订阅账号，API 主供应商设为当前供应商
+/// 2. 每天的会话数按强度随机，周末减半；会话在 9 - 22 点之间开始
+/// 3. 会话内上下文逐条增长，缓存读取随之增加，费用按模型价格计算
+/// 4. 相同 seed 生成相同的数据，便于截图对比
+pub fn generate_demo_data(
+    repository: &Repository,
+    days: u32,
+    intensity: DemoIntensity,
+    now: DateTime<Local>,
+    seed: u64,
+) -> Result<DemoDataSummary, RepositoryError> {
+    let days = days.clamp(1, MAX_DEMO_DAYS);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let pricing = PricingService::new();
+
+    let subscription =
+        repository.upsert_subscription_provider(DEMO_SUBSCRIPTION.0, DEMO_SUBSCRIPTION.1)?;
+    let mut providers = Vec::new();
+    for (index, (api_key, name, weight)) in DEMO_PROVIDERS.iter().enumerate() {
+        let provider = if index == 0 {
+            repository.upsert_provider_with_default_name(api_key, None, Some(name.to_string()))?
+        } else {
+            repository.create_provider(api_key, Some(name.to_string()))?
+        };
+        providers.push((provider.id, *weight));
+    }
+    let mut records: Vec<Vec<MessageRecord>> = vec![Vec::new(); providers.len() + 1];
+
+    let mut summary = DemoDataSummary {
+        days,
+        providers: providers.len() + 1,
+        ..Default::default()
+    };
+    let today = now.date_naive();
+    for offset in (0..days).rev() {
+        let date = today - Duration::days(i64::from(offset));
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let base = f64::from(intensity.sessions_per_weekday()) * if weekend { 0.5 } else { 1.0 };
+        let sessions = (base * rng.random_range(0.5..1.5)).round() as u32;
+
+        for _ in 0..sessions {
+            let start = session_start(&mut rng, date);
+            if start >= now {
+                continue;
+            }
+            let provider_slot = pick_provider(&mut rng, &providers);
+            let session = generate_session(&mut rng, &pricing, start, now);
+            if session.is_empty() {
+                continue;
+            }
+
+            repository.upsert_session_title(
+                &session[0].session_id,
+                DEMO_TITLES[rng.random_range(0..DEMO_TITLES.len())],
+                SessionTitleSource::Summary,
+            )?;
+            summary.sessions += 1;
+            summary.messages += session.len();
+            if provider_slot < providers.len() {
+                summary.total_cost_usd += session.iter().map(|m| m.usage.cost_usd).sum::<f64>();
+            }
+            records[provider_slot].extend(session);
+        }
+    }
+
+    for (slot, slot_records) in records.iter().enumerate() {
+        let provider_id = providers
+            .get(slot)
+            .map(|(id, _)| *id)
+            .unwrap_or(subscription.id);
+        repository.import_message_records(provider_id, slot_records)?;
+    }
+
+    tracing::info!(
+        "演示数据生成完成: {} 天，会话 {} 个，消息 {} 条",
+        summary.days,
+        summary.sessions,
+        summary.messages
+    );
+    Ok(summary)
+}
+
+fn session_start(rng: &mut StdRng, date: NaiveDate) -> DateTime<Local> {
+    let time = date
+        .and_hms_opt(rng.random_range(9..22), rng.random_range(0..60), 0)
+        .unwrap_or_default();
+    to_local(time)
+}
+
+/// 按权重选择供应商，返回 providers 中的下标；等于 providers.len() 时表示订阅账号
+fn pick_provider(rng: &mut StdRng, providers: &[(i64, f64)]) -> usize {
+    let mut roll: f64 = rng.random();
+    for (index, (_, weight)) in providers.iter().enumerate() {
+        if roll < *weight {
+            return index;
+        }
+        roll -= weight;
+    }
+    providers.len()
+}
+
+fn pick_model(rng: &mut StdRng) -> &'static str {
+    let mut roll: f64 = rng.random();
+    for (model, weight) in DEMO_MODELS {
+        if roll < weight {
+            return model;
+        }
+        roll -= weight;
+    }
+    DEMO_MODELS[0].0
+}
+
+/// 生成一个会话的消息，不生成晚于 now 的消息
+fn generate_session(
+    rng: &mut StdRng,
+    pricing: &PricingService,
+    start: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Vec<MessageRecord> {
+    let session_id = format!("demo-{:016x}", rng.random::<u64>());
+    let project = DEMO_PROJECTS[rng.random_range(0..DEMO_PROJECTS.len())];
+    let model = pick_model(rng);
+    let message_count = rng.random_range(5..=40);
+
+    let mut messages = Vec::with_capacity(message_count);
+    let mut created_at = start;
+    let mut context_tokens: i64 = 0;
+    for index in 0..message_count {
+        if created_at > now {
+            break;
+        }
+        let input_tokens = rng.random_range(200..3_000);
+        let output_tokens = rng.random_range(100..2_500);
+        // 首条消息与偶发的上下文变化会写入缓存
+        let cache_creation_tokens = if index == 0 || rng.random_bool(0.1) {
+            input_tokens * 4
+        } else {
+            0
+        };
+        let usage = MessageUsage {
+            input_tokens,
+            output_tokens,
+            cache_read_tokens: context_tokens,
+            cache_creation_tokens,
+            thinking_tokens: 0,
+            cost_usd: pricing.calculate_cost(
+                model,
+                input_tokens,
+                output_tokens,
+                context_tokens,
+                cache_creation_tokens,
+            ),
+        };
+
+        let mut record = MessageRecord::new(
+            session_id.clone(),
+            format!("msg_demo_{:016x}", rng.random::<u64>()),
+            model.to_string(),
+            created_at.to_rfc3339(),
+            usage,
+        );
+        record.project = Some(project.to_string());
+        record.duration_ms = Some(rng.random_range(2_000..45_000));
+        messages.push(record);
+
+        // 上下文接近上限时模拟自动压缩
+        context_tokens = (context_tokens + input_tokens + output_tokens + cache_creation_tokens)
+            .min(MAX_CONTEXT_TOKENS);
+        created_at += Duration::seconds(rng.random_range(15..120));
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_demo_data() {
+        let now = Local::now();
+        let repository = Repository::new_in_memory().expect("repo");
+        let summary =
+            generate_demo_data(&repository, 14, DemoIntensity::Normal, now, 42).expect("generate");
+
+        assert_eq!(summary.days, 14);
+        assert_eq!(summary.providers, 3);
+        assert!(summary.sessions > 0);
+        assert!(summary.messages >= summary.sessions);
+        assert!(summary.total_cost_usd > 0.0);
+
+        let providers = repository.get_all_providers(false).expect("providers");
+        assert_eq!(providers.len(), 3);
+        let active: Vec<_> = providers.iter().filter(|p| p.is_active).collect();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].display_name.as_deref(), Some("Anthropic API"));
+
+        let start = (now.date_naive() - Duration::days(13)).to_string();
+        let end = now.date_naive().to_string();
+        let days = repository
+            .get_daily_activities(&start, &end)
+            .expect("daily");
+        assert!(!days.is_empty() && days.len() <= 14);
+        let messages: i64 = days.iter().map(|day| day.message_count).sum();
+        assert_eq!(messages as usize, summary.messages);
+
+        let other = Repository::new_in_memory().expect("repo");
+        let again =
+            generate_demo_data(&other, 14, DemoIntensity::Normal, now, 42).expect("generate");
+        assert_eq!(again.messages, summary.messages);
+    }
+
+    #[test]
+    fn test_create_demo_database_replaces_existing() {
+        let dir = std::env::temp_dir().join(format!("demo-data-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("dir");
+        let path = dir.join("demo.db");
+
+        let first = create_demo_database(&path, 3, DemoIntensity::Light).expect("first");
+        let second = create_demo_database(&path, 3, DemoIntensity::Light).expect("second");
+        let repository = Repository::new(&path).expect("repo");
+        assert_eq!(
+            repository
+                .get_all_providers(false)
+                .expect("providers")
+                .len(),
+            3
+        );
+        assert_eq!(first.path, second.path);
+
+        drop(repository);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,189 @@
+//! @file distribution.rs
+//! @description 分布统计服务，按固定边界生成直方图并计算分位数（消息 Token 数、会话时长 / 长度 / 费用）
+//! @author Atlas.oi
+//! @date 2026-10-17
+use crate::models::{
+    Distribution, DistributionBucket, MessageDistribution, MessageTokenSample, SessionDistribution,
+    SessionSample,
+};
+
+/// 每条消息输入 Token 数的直方图边界
+pub const INPUT_TOKEN_BOUNDS: [f64; 6] = [0.0, 1_000.0, 10_000.0, 50_000.0, 100_000.0, 200_000.0];
+
+/// 每条消息输出 Token 数的直方图边界
+pub const OUTPUT_TOKEN_BOUNDS: [f64; 6] = [0.0, 100.0, 500.0, 1_000.0, 4_000.0, 16_000.0];
+
+/// 会话时长（秒）的直方图边界
+pub const SESSION_DURATION_BOUNDS: [f64; 7] = [0.0, 60.0, 300.0, 900.0, 1_800.0, 3_600.0, 7_200.0];
+
+/// 每个会话消息数的直方图边界
+pub const SESSION_MESSAGE_BOUNDS: [f64; 7] = [0.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0];
+
+/// 每个会话费用（美元）的直方图边界
+pub const SESSION_COST_BOUNDS: [f64; 7] = [0.0, 0.1, 0.5, 1.0, 5.0, 10.0, 25.0];
+
+/// 按边界生成直方图并计算分位数
+///
+/// 分桶区间为 [bounds[i], bounds[i + 1])，最后一个分桶无上限；
+/// 分位数使用最近秩法：排序后取第 ceil(p × n) 个样本
+pub fn build_distribution(mut values: Vec<f64>, bounds: &[f64]) -> Distribution {
+    let mut buckets: Vec<DistributionBucket> = bounds
+        .iter()
+        .enumerate()
+        .map(|(index, lower)| DistributionBucket {
+            lower: *lower,
+            upper: bounds.get(index + 1).copied(),
+            count: 0,
+        })
+        .collect();
+
+    for value in &values {
+        if let Some(bucket) = buckets
+            .iter_mut()
+            .rev()
+            .find(|bucket| *value >= bucket.lower)
+        {
+            bucket.count += 1;
+        }
+    }
+
+    if values.is_empty() {
+        return Distribution {
+            buckets,
+            ..Default::default()
+        };
+    }
+
+    values.sort_unstable_by(f64::total_cmp);
+    let percentile = |p: f64| {
+        let rank = ((values.len() as f64 * p).ceil() as usize).clamp(1, values.len());
+        values[rank - 1]
+    };
+
+    Distribution {
+        p50: percentile(0.50),
+        p90: percentile(0.90),
+        p99: percentile(0.99),
+        max: values[values.len() - 1],
+        buckets,
+    }
+}
+
+/// 计算每条消息输入 / 输出 Token 数的分布
+pub fn calculate_message_distribution(samples: &[MessageTokenSample]) -> MessageDistribution {
+    MessageDistribution {
+        message_count: samples.len() as i64,
+        input_tokens: build_distribution(
+            samples.iter().map(|s| s.input_tokens as f64).collect(),
+            &INPUT_TOKEN_BOUNDS,
+        ),
+        output_tokens: build_distribution(
+            samples.iter().map(|s| s.output_tokens as f64).collect(),
+            &OUTPUT_TOKEN_BOUNDS,
+        ),
+    }
+}
+
+/// 计算会话时长、消息数与费用的分布
+pub fn calculate_session_distribution(samples: &[SessionSample]) -> SessionDistribution {
+    let mut costs: Vec<f64> = samples.iter().map(|s| s.cost_usd).collect();
+    costs.sort_unstable_by(|a, b| b.total_cmp(a));
+
+    let total_cost: f64 = costs.iter().sum();
+    let top_count = costs.len().div_ceil(10);
+    let top_decile_cost_share = if total_cost > 0.0 {
+        costs[..top_count].iter().sum::<f64>() / total_cost
+    } else {
+        0.0
+    };
+
+    SessionDistribution {
+        session_count: samples.len() as i64,
+        duration_seconds: build_distribution(
+            samples.iter().map(|s| s.duration_seconds).collect(),
+            &SESSION_DURATION_BOUNDS,
+        ),
+        message_count: build_distribution(
+            samples.iter().map(|s| s.message_count as f64).collect(),
+            &SESSION_MESSAGE_BOUNDS,
+        ),
+        cost_usd: build_distribution(costs, &SESSION_COST_BOUNDS),
+        top_decile_cost_share,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_distribution() {
+        let values: Vec<f64> = (1..=100).map(|i| i as f64).collect();
+        let distribution = build_distribution(values, &[0.0, 10.0, 50.0]);
+
+        assert_eq!(distribution.p50, 50.0);
+        assert_eq!(distribution.p90, 90.0);
+        assert_eq!(distribution.p99, 99.0);
+        assert_eq!(distribution.max, 100.0);
+
+        let counts: Vec<i64> = distribution.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![9, 40, 51]);
+        assert_eq!(distribution.buckets[1].upper, Some(50.0));
+        assert_eq!(distribution.buckets[2].upper, None);
+    }
+
+    #[test]
+    fn test_calculate_message_distribution() {
+        let samples = vec![
+            MessageTokenSample {
+                input_tokens: 500,
+                output_tokens: 50,
+            },
+            MessageTokenSample {
+                input_tokens: 250_000,
+                output_tokens: 2_000,
+            },
+        ];
+
+        let distribution = calculate_message_distribution(&samples);
+        assert_eq!(distribution.message_count, 2);
+        assert_eq!(distribution.input_tokens.buckets[0].count, 1);
+        assert_eq!(distribution.input_tokens.buckets[5].count, 1);
+        assert_eq!(distribution.input_tokens.max, 250_000.0);
+        assert_eq!(distribution.output_tokens.buckets[3].count, 1);
+
+        let empty = calculate_message_distribution(&[]);
+        assert_eq!(empty.message_count, 0);
+        assert_eq!(empty.input_tokens.buckets.len(), INPUT_TOKEN_BOUNDS.len());
+        assert_eq!(empty.input_tokens.p99, 0.0);
+    }
+
+    #[test]
+    fn test_calculate_session_distribution() {
+        let mut samples: Vec<SessionSample> = (0..19)
+            .map(|_| SessionSample {
+                duration_seconds: 120.0,
+                message_count: 3,
+                cost_usd: 0.05,
+            })
+            .collect();
+        samples.push(SessionSample {
+            duration_seconds: 10_800.0,
+            message_count: 400,
+            cost_usd: 19.05,
+        });
+
+        let distribution = calculate_session_distribution(&samples);
+        assert_eq!(distribution.session_count, 20);
+        assert_eq!(distribution.duration_seconds.p50, 120.0);
+        assert_eq!(distribution.duration_seconds.max, 10_800.0);
+        assert_eq!(distribution.message_count.buckets[0].count, 19);
+        assert_eq!(distribution.message_count.buckets[6].count, 1);
+        // 前 10% 为 2 个会话：19.05 + 0.05，总费用 20.0
+        assert!((distribution.top_decile_cost_share - 0.955).abs() < 1e-9);
+
+        let empty = calculate_session_distribution(&[]);
+        assert_eq!(empty.session_count, 0);
+        assert_eq!(empty.top_decile_cost_share, 0.0);
+    }
+}
@@ -0,0 +1,59 @@
+//! @file dock_badge.rs
+//! @description macOS Dock 角标，显示今日费用或剩余预算
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::AppHandle;
+
+#[cfg(target_os = "macos")]
+use tauri::Manager;
+
+#[cfg(target_os = "macos")]
+use crate::db::Repository;
+#[cfg(target_os = "macos")]
+use crate::models::{BudgetSettings, DockBadgeSettings};
+#[cfg(target_os = "macos")]
+use crate::services::budget::dock_badge_label;
+#[cfg(target_os = "macos")]
+use crate::services::tray::MAIN_WINDOW_LABEL;
+
+/// 按 Dock 角标设置更新角标，关闭时清除角标
+#[cfg(target_os = "macos")]
+pub fn update_dock_badge(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+    let repository = app.state::<Repository>();
+    let settings: DockBadgeSettings = match repository.get_setting() {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("读取 Dock 角标设置失败: {}", e);
+            return;
+        }
+    };
+
+    let label = if settings.enabled {
+        let budget: BudgetSettings = match repository.get_setting() {
+            Ok(budget) => budget,
+            Err(e) => {
+                tracing::error!("读取预算设置失败: {}", e);
+                return;
+            }
+        };
+        match repository.get_today_stats() {
+            Ok(today) => Some(dock_badge_label(settings.content, &budget, today.cost_usd)),
+            Err(e) => {
+                tracing::error!("获取今日统计失败: {}", e);
+                return;
+            }
+        }
+    } else {
+        None
+    };
+    if let Err(e) = window.set_badge_label(label) {
+        tracing::error!("更新 Dock 角标失败: {}", e);
+    }
+}
+
+/// 非 macOS 平台没有 Dock 角标
+#[cfg(not(target_os = "macos"))]
+pub fn update_dock_badge(_app: &AppHandle) {}
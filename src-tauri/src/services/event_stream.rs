@@ -0,0 +1,306 @@
+//! @file event_stream.rs
+//! @description 本地 WebSocket 事件流服务，将应用事件实时推送给外部订阅者（tmux、polybar 等终端组件）
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use thiserror::Error;
+use tungstenite::handshake::server::{ErrorResponse, Request};
+use tungstenite::http::StatusCode;
+use tungstenite::{Message, WebSocket};
+
+use crate::models::EventStreamSettings;
+use crate::services::api_server::{is_authorized, query_param};
+
+/// 转发给订阅者的应用事件
+pub const FORWARDED_EVENTS: &[&str] = &[
+    "stats-updated",
+    "provider-switched",
+    "cost-anomaly",
+    "report-generated",
+    "update-available",
+    "monitor-error",
+    "live-rate",
+    "session-started",
+    "session-ended",
+    "context-warning",
+    "sync-completed",
+    "official-usage-updated",
+];
+
+/// 客户端连接轮询间隔，用于在读取控制帧与推送事件之间切换
+const CLIENT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Error, Debug)]
+pub enum EventStreamError {
+    #[error("Failed to bind event stream: {0}")]
+    Bind(#[from] std::io::Error),
+}
+
+/// 事件广播器
+///
+/// 克隆后共享同一订阅者列表；无订阅者时发布事件几乎没有开销
+#[derive(Clone, Default)]
+pub struct EventBroadcaster {
+    subscribers: Arc<Mutex<Vec<Sender<String>>>>,
+}
+
+impl EventBroadcaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 发布事件，消息格式为 `{"event": <名称>, "payload": <JSON>}`
+    ///
+    /// payload 无法解析为 JSON 时按字符串发送；已断开的订阅者会被移除
+    pub fn publish(&self, event: &str, payload: &str) {
+        let mut subscribers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(_) => return,
+        };
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let payload = serde_json::from_str::<Value>(payload)
+            .unwrap_or_else(|_| Value::String(payload.to_string()));
+        let message = json!({ "event": event, "payload": payload }).to_string();
+        subscribers.retain(|subscriber| subscriber.send(message.clone()).is_ok());
+    }
+
+    /// 当前订阅者数量
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers
+            .lock()
+            .map(|subscribers| subscribers.len())
+            .unwrap_or(0)
+    }
+
+    fn subscribe(&self) -> Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+}
+
+/// 运行中的 WebSocket 事件流服务
+pub struct EventStream {
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    addr: SocketAddr,
+}
+
+impl EventStream {
+    /// 按设置启动服务，每个订阅者使用独立线程
+    pub fn start(
+        broadcaster: EventBroadcaster,
+        settings: &EventStreamSettings,
+    ) -> Result<Self, EventStreamError> {
+        let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, settings.port)))?;
+        let addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let token = settings.token.clone();
+        let stop = shutdown.clone();
+        let thread = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                match stream {
+                    Ok(stream) => {
+                        let broadcaster = broadcaster.clone();
+                        let token = token.clone();
+                        let stop = stop.clone();
+                        std::thread::spawn(move || {
+                            handle_client(stream, &broadcaster, &token, &stop);
+                        });
+                    }
+                    Err(e) => tracing::error!("事件流连接失败: {}", e),
+                }
+            }
+        });
+
+        tracing::info!("WebSocket 事件流已启动: ws://{}", addr);
+        Ok(Self {
+            shutdown,
+            thread: Some(thread),
+            addr,
+        })
+    }
+
+    /// 实际监听地址
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 停止服务，已连接的订阅者会在下一次轮询时断开
+    pub fn stop(mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // 连接自身以唤醒阻塞在 accept 上的监听线程
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::info!("WebSocket 事件流已停止: ws://{}", self.addr);
+    }
+}
+
+/// 按设置重启服务：先停止已运行的实例，启用时再以新设置启动
+pub fn restart_event_stream(
+    slot: &mut Option<EventStream>,
+    broadcaster: &EventBroadcaster,
+    settings: &EventStreamSettings,
+) -> Result<(), EventStreamError> {
+    if let Some(stream) = slot.take() {
+        stream.stop();
+    }
+    if settings.enabled {
+        *slot = Some(EventStream::start(broadcaster.clone(), settings)?);
+    }
+    Ok(())
+}
+
+/// 处理单个订阅者连接
+///
+/// 握手时校验 `Authorization: Bearer <token>` 或 `?token=<token>`，
+/// 之后交替处理客户端控制帧与广播事件，直到连接关闭或服务停止
+// 握手回调的错误类型由 tungstenite 规定，无法缩小
+#[allow(clippy::result_large_err)]
+fn handle_client(
+    stream: TcpStream,
+    broadcaster: &EventBroadcaster,
+    token: &str,
+    stop: &AtomicBool,
+) {
+    let mut socket = match tungstenite::accept_hdr(stream, |request: &Request, response| {
+        if is_handshake_authorized(request, token) {
+            return Ok(response);
+        }
+        let mut rejection = ErrorResponse::new(Some("unauthorized".to_string()));
+        *rejection.status_mut() = StatusCode::UNAUTHORIZED;
+        Err(rejection)
+    }) {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!("事件流握手失败: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = socket
+        .get_ref()
+        .set_read_timeout(Some(CLIENT_POLL_INTERVAL))
+    {
+        tracing::error!("事件流连接设置失败: {}", e);
+        return;
+    }
+
+    let receiver = broadcaster.subscribe();
+    while !stop.load(Ordering::SeqCst) {
+        if !read_control_frames(&mut socket) {
+            return;
+        }
+        while let Ok(message) = receiver.try_recv() {
+            if socket.send(Message::text(message)).is_err() {
+                return;
+            }
+        }
+    }
+    let _ = socket.close(None);
+}
+
+/// 读取客户端发来的帧（ping / close 等），返回连接是否仍然可用
+fn read_control_frames(socket: &mut WebSocket<TcpStream>) -> bool {
+    match socket.read() {
+        Ok(Message::Close(_)) => false,
+        Ok(_) => true,
+        Err(ref e) if is_timeout(e) => {
+            // 读取超时表示暂无客户端帧，顺带冲刷自动回复的 pong
+            match socket.flush() {
+                Ok(()) => true,
+                Err(ref e) => is_timeout(e),
+            }
+        }
+        Err(_) => false,
+    }
+}
+
+fn is_timeout(error: &tungstenite::Error) -> bool {
+    matches!(error, tungstenite::Error::Io(e)
+        if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut)
+}
+
+/// 校验握手请求中的 Token
+fn is_handshake_authorized(request: &Request, token: &str) -> bool {
+    let header = request
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok());
+    let query_token = request
+        .uri()
+        .query()
+        .and_then(|query| query_param(query, "token"))
+        .map(|value| format!("Bearer {}", value));
+
+    is_authorized(header, token) || is_authorized(query_token.as_deref(), token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broadcaster_publish() {
+        let broadcaster = EventBroadcaster::new();
+        broadcaster.publish("stats-updated", "{}");
+
+        let receiver = broadcaster.subscribe();
+        broadcaster.publish("stats-updated", r#"{"total_cost_usd":1.5}"#);
+        let message: Value =
+            serde_json::from_str(&receiver.recv().expect("message")).expect("json");
+        assert_eq!(message["event"], "stats-updated");
+        assert_eq!(message["payload"]["total_cost_usd"], 1.5);
+
+        drop(receiver);
+        broadcaster.publish("stats-updated", "{}");
+        assert_eq!(broadcaster.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_event_stream_delivers_events() {
+        let broadcaster = EventBroadcaster::new();
+        let settings = EventStreamSettings {
+            enabled: true,
+            port: 0,
+            token: "stream-token".to_string(),
+        };
+        let server = EventStream::start(broadcaster.clone(), &settings).expect("start");
+        let url = format!("ws://{}/?token=stream-token", server.local_addr());
+
+        let rejected = tungstenite::connect(format!("ws://{}/", server.local_addr()));
+        assert!(rejected.is_err());
+
+        let (mut client, _) = tungstenite::connect(url).expect("connect");
+        while broadcaster.subscriber_count() == 0 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        broadcaster.publish("provider-switched", r#"{"id":1}"#);
+
+        let message = client.read().expect("message");
+        let message: Value = serde_json::from_str(message.to_text().expect("text")).expect("json");
+        assert_eq!(message["event"], "provider-switched");
+        assert_eq!(message["payload"]["id"], 1);
+
+        let _ = client.close(None);
+        server.stop();
+    }
+}
@@ -2,13 +2,35 @@
 //! @description 文件监控服务，监听 Claude CLI 数据目录变更
 //! @author Atlas.oi
 //! @date 2026-01-08
+use chrono::{Local, Utc};
 use notify::{Event, RecursiveMode, Watcher};
 use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Emitter, Manager};
 use thiserror::Error;
 
 use crate::db::Repository;
-use crate::services::parser::{parse_jsonl_line, parse_settings};
+use crate::models::{
+    AlertChannel, ApiErrorEvent, BlockWarningSettings, DedupePolicy, DedupeSettings,
+    MonitorErrorCategory, NotificationKind, OutlierSettings, Provider, ProviderKind, ToolCall,
+    WebhookEvent,
+};
+use crate::services::alert_engine::evaluate_alert_rules;
+use crate::services::anomaly_detector::{anomaly_message, run_anomaly_detection};
+use crate::services::block_warning::{self, block_warning_message, BlockWarningState};
+use crate::services::budget::{budget_alert_message, run_budget_check, BudgetLevel};
+use crate::services::health::WatcherHealth;
+use crate::services::ingest_worker::{parse_jsonl_content, IngestJob, IngestWorker, ParsedLine};
+use crate::services::live_stats::LiveStats;
+use crate::services::monitor_errors::MonitorErrorLog;
+use crate::services::notifier;
+use crate::services::oauth_account::detect_oauth_account;
+use crate::services::outliers::{self, outlier_message, OutlierState};
+use crate::services::parser::{parse_settings, ParserError};
+use crate::services::pricing::{pricing_date, PricingService};
+use crate::services::provider_tracker::ProviderTracker;
+use crate::services::shutdown::{spawn_tracked, IngestTracker};
+use crate::services::taskbar::update_taskbar_progress;
+use crate::services::webhook;
 
 #[derive(Error, Debug)]
 pub enum FileWatcherError {
@@ -21,75 +43,125 @@ pub enum FileWatcherError {
 }
 
 pub struct FileWatcher {
-    claude_dir: PathBuf,
+    watch_dirs: Vec<PathBuf>,
     watcher: notify::RecommendedWatcher,
     app: AppHandle,
+    /// 创建时的采集任务代次，切换档案后旧监控提交的任务被丢弃
+    generation: u64,
+}
+
+/// 默认监控目录：~/.claude
+pub fn default_claude_dir() -> Result<PathBuf, FileWatcherError> {
+    Ok(dirs::home_dir()
+        .ok_or(FileWatcherError::HomeDirNotFound)?
+        .join(".claude"))
 }
 
 impl FileWatcher {
-    /// 创建文件监控服务
+    /// 创建监控默认目录（~/.claude）的文件监控服务
     pub fn new(app: AppHandle) -> Result<Self, FileWatcherError> {
-        let claude_dir = dirs::home_dir()
-            .ok_or(FileWatcherError::HomeDirNotFound)?
-            .join(".claude");
+        Self::with_dirs(app, vec![default_claude_dir()?])
+    }
 
+    /// 创建监控指定目录的文件监控服务（如配置文件中的监控目录）
+    ///
+    /// 文件变更在监控回调中读取并解析，解析结果交给采集工作线程写库；
+    /// 工作线程队列满时回调阻塞，事件风暴下不会无限堆积
+    pub fn with_dirs(app: AppHandle, watch_dirs: Vec<PathBuf>) -> Result<Self, FileWatcherError> {
         let app_handle = app.clone();
+        let generation = app.state::<IngestWorker>().generation();
         let watcher = notify::recommended_watcher(move |event: Result<Event, _>| match event {
             Ok(event) => match event.kind {
                 notify::EventKind::Modify(_) | notify::EventKind::Create(_) => {
-                    println!("检测到文件变更: {:?}", event.paths);
-                    let paths = event.paths.clone();
-                    let _ = app_handle.emit("file-changed", paths.clone());
-                    let app_handle = app_handle.clone();
-                    std::thread::spawn(move || {
-                        if let Err(error) = handle_file_changes(&app_handle, &paths) {
-                            eprintln!("文件变更处理失败: {}", error);
-                        }
-                    });
+                    tracing::info!("检测到文件变更: {:?}", event.paths);
+                    let _ = app_handle.emit("file-changed", event.paths.clone());
+                    if !submit_paths(&app_handle, generation, &event.paths) {
+                        tracing::debug!("采集任务已失效，忽略文件变更");
+                    }
                 }
                 _ => {}
             },
             Err(e) => {
-                eprintln!("文件监控事件错误: {}", e);
+                let message = format!("文件监控事件错误: {}", e);
+                app_handle.state::<WatcherHealth>().mark_errored(&message);
+                record_error(&app_handle, MonitorErrorCategory::Watcher, None, message);
             }
         })?;
 
         Ok(Self {
-            claude_dir,
+            watch_dirs,
             watcher,
             app,
+            generation,
         })
     }
 
     /// 启动监控
     pub fn start(&mut self) -> Result<(), FileWatcherError> {
-        if !self.claude_dir.exists() {
-            std::fs::create_dir_all(&self.claude_dir)?;
+        let health = self.app.state::<WatcherHealth>();
+        for dir in &self.watch_dirs {
+            if !dir.exists() {
+                std::fs::create_dir_all(dir)?;
+            }
+            if let Err(e) = self.watcher.watch(dir, RecursiveMode::Recursive) {
+                health.mark_errored(&format!("文件监控启动失败 [{}]: {}", dir.display(), e));
+                return Err(e.into());
+            }
         }
+        health.mark_running(
+            self.watch_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect(),
+        );
 
-        self.watcher
-            .watch(&self.claude_dir, RecursiveMode::Recursive)?;
-
-        println!("文件监控已启动: {}", self.claude_dir.display());
+        tracing::info!("文件监控已启动: {:?}", self.watch_dirs);
+        // 启动扫描只负责读取与解析，写库同样交给采集工作线程
         let app = self.app.clone();
-        let claude_dir = self.claude_dir.clone();
-        std::thread::spawn(move || {
-            if let Err(error) = scan_existing_files(&app, &claude_dir) {
-                eprintln!("启动扫描失败: {}", error);
-            }
-        });
+        let watch_dirs = self.watch_dirs.clone();
+        let generation = self.generation;
+        std::thread::Builder::new()
+            .name("ingest-scan".to_string())
+            .spawn(move || {
+                for dir in &watch_dirs {
+                    match scan_existing_files(&app, generation, dir) {
+                        Ok(true) => {}
+                        Ok(false) => return,
+                        Err(error) => record_error(
+                            &app,
+                            MonitorErrorCategory::Watcher,
+                            None,
+                            format!("启动扫描失败 [{}]: {}", dir.display(), error),
+                        ),
+                    }
+                }
+            })?;
 
         Ok(())
     }
+
+    /// 停止监控；已排队的采集任务由工作线程继续处理，由退出流程等待其完成
+    pub fn stop(&mut self) {
+        for dir in &self.watch_dirs {
+            if let Err(e) = self.watcher.unwatch(dir) {
+                // 未启动（如演示模式）时 unwatch 会报错，无需处理
+                tracing::debug!("取消文件监控 [{}]: {}", dir.display(), e);
+            }
+        }
+        self.app.state::<WatcherHealth>().mark_paused();
+        tracing::info!("文件监控已停止");
+    }
 }
 
-fn scan_existing_files(app: &AppHandle, claude_dir: &Path) -> Result<(), FileWatcherError> {
+/// 扫描目录下已有的文件并提交采集；任务已失效时返回 false
+fn scan_existing_files(
+    app: &AppHandle,
+    generation: u64,
+    claude_dir: &Path,
+) -> Result<bool, FileWatcherError> {
     let mut paths = Vec::new();
     collect_relevant_files(claude_dir, &mut paths)?;
-    if paths.is_empty() {
-        return Ok(());
-    }
-    handle_file_changes(app, &paths)
+    Ok(submit_paths(app, generation, &paths))
 }
 
 fn collect_relevant_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), FileWatcherError> {
@@ -107,91 +179,171 @@ fn collect_relevant_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), Fi
     }
     Ok(())
 }
-/// 处理文件变更
-///
-/// 业务逻辑：
-/// 1. 解析 settings.json 更新供应商信息
-/// 2. 解析 JSONL 文件记录消息使用数据
-/// 3. 发送事件通知前端刷新
-fn handle_file_changes(app: &AppHandle, paths: &[PathBuf]) -> Result<(), FileWatcherError> {
-    let repository = app.state::<Repository>();
-    let mut updated_stats = false;
-    let mut skipped_lines = 0;
 
-    // 处理 settings.json 文件
-    let mut updated_provider = None;
-    for path in paths {
-        if is_settings_file(path) {
-            match std::fs::read_to_string(path) {
-                Ok(content) => match parse_settings(&content) {
-                    Ok(settings) => {
-                        match repository.upsert_provider(&settings.api_key, settings.base_url) {
-                            Ok(provider) => {
-                                println!("供应商信息已更新: {}", provider.api_key_prefix);
-                                updated_provider = Some(provider);
-                            }
-                            Err(e) => {
-                                eprintln!("供应商更新失败 [{}]: {}", path.display(), e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Settings 解析失败 [{}]: {}", path.display(), e);
-                    }
-                },
-                Err(e) => {
-                    eprintln!("文件读取失败 [{}]: {}", path.display(), e);
-                }
+/// 读取并解析变更的文件，逐个提交给采集工作线程
+///
+/// settings.json 先于 JSONL 提交，同一批变更中的记录归属到更新后的供应商
+///
+/// # 返回
+/// 任务已失效（如已切换档案）时返回 false，调用方应停止提交
+fn submit_paths(app: &AppHandle, generation: u64, paths: &[PathBuf]) -> bool {
+    let worker = app.state::<IngestWorker>();
+    let settings = paths.iter().filter(|path| is_settings_file(path));
+    let jsonl = paths.iter().filter(|path| is_jsonl_file(path));
+    for path in settings.chain(jsonl) {
+        if let Some(job) = read_ingest_job(app, path) {
+            if !worker.submit(generation, job) {
+                return false;
             }
         }
     }
+    true
+}
 
-    if let Some(provider) = updated_provider.clone() {
-        if let Err(e) = app.emit("provider-switched", provider) {
-            eprintln!("发送 provider-switched 事件失败: {}", e);
+/// 读取并解析单个文件；读取或解析失败时记录错误并返回 None
+fn read_ingest_job(app: &AppHandle, path: &Path) -> Option<IngestJob> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            let message = if is_settings_file(path) {
+                format!("文件读取失败: {}", e)
+            } else {
+                format!("JSONL 文件读取失败: {}", e)
+            };
+            record_error(app, MonitorErrorCategory::Io, Some(path), message);
+            return None;
         }
+    };
+
+    if is_settings_file(path) {
+        let settings = match parse_settings(&content) {
+            Ok(settings) => Some(settings),
+            // 没有 API Key 时 Claude Code 使用 claude.ai 账号登录
+            Err(ParserError::MissingApiKey) => None,
+            Err(e) => {
+                record_error(
+                    app,
+                    MonitorErrorCategory::Parse,
+                    Some(path),
+                    format!("Settings 解析失败: {}", e),
+                );
+                return None;
+            }
+        };
+        return Some(IngestJob::Settings {
+            path: path.to_path_buf(),
+            settings,
+        });
     }
 
-    let active_provider = if let Some(provider) = updated_provider {
-        Some(provider)
-    } else {
-        repository.get_active_provider().ok().flatten()
+    let parsed = parse_jsonl_content(path, &content);
+    // 同一文件的解析失败合并为一条错误，避免刷屏
+    if let Some(first_error) = &parsed.first_parse_error {
+        record_error(
+            app,
+            MonitorErrorCategory::Parse,
+            Some(path),
+            format!(
+                "{} 行解析失败，首个错误: {}",
+                parsed.parse_failures, first_error
+            ),
+        );
+    }
+    Some(IngestJob::Jsonl(parsed))
+}
+
+/// 采集工作线程处理一批任务
+///
+/// 退出或切换档案流程开始后（采集登记已关闭）直接丢弃本批；
+/// 登记成功后再核对代次，本批在切换档案前取出、切换完成后才登记时同样丢弃，避免写入新档案的数据库
+pub fn process_ingest_batch(app: &AppHandle, generation: u64, jobs: Vec<IngestJob>) {
+    let tracker = app.state::<IngestTracker>();
+    let Some(_guard) = tracker.begin() else {
+        tracing::debug!("应用正在退出，忽略 {} 个采集任务", jobs.len());
+        return;
     };
+    if generation != app.state::<IngestWorker>().generation() {
+        tracing::debug!("档案已切换，丢弃 {} 个已失效的采集任务", jobs.len());
+        return;
+    }
+    apply_ingest_jobs(app, jobs);
+}
 
-    // 处理 JSONL 文件
-    if let Some(provider) = active_provider {
-        for path in paths {
-            if is_jsonl_file(path) {
-                match std::fs::read_to_string(path) {
-                    Ok(content) => {
-                        for line in content.lines() {
-                            match parse_jsonl_line(line) {
-                                Ok(Some(record)) => {
-                                    match repository.insert_message_usage(provider.id, &record) {
-                                        Ok(_) => {
-                                            updated_stats = true;
-                                        }
-                                        Err(e) => {
-                                            // 重复记录是正常情况，不记录为错误
-                                            if !e.to_string().contains("duplicate") {
-                                                eprintln!("消息记录插入失败: {}", e);
-                                            }
-                                        }
-                                    }
-                                }
-                                Ok(None) => {
-                                    // 非消息行（如系统日志），正常跳过
-                                    skipped_lines += 1;
-                                }
-                                Err(e) => {
-                                    eprintln!("JSONL 行解析失败 [{}]: {}", path.display(), e);
-                                }
-                            }
+/// 写入一批采集任务并汇总
+///
+/// 业务逻辑：
+/// 1. 按提交顺序处理：settings.json 更新供应商信息，JSONL 记录消息使用数据
+/// 2. 整批写完后只汇总一次：发送统计更新并运行异常、预警、告警等检查
+fn apply_ingest_jobs(app: &AppHandle, jobs: Vec<IngestJob>) {
+    let repository = app.state::<Repository>();
+    let mut updated_stats = false;
+    let mut skipped_lines = 0;
+    let mut active_provider = None;
+    let mut ingest_context = None;
+
+    for job in jobs {
+        match job {
+            IngestJob::Settings { path, settings } => {
+                let updated = match settings {
+                    Some(settings) => match ProviderTracker::new(repository.inner().clone())
+                        .track_from_settings(&settings)
+                    {
+                        Ok(provider) => {
+                            tracing::info!("供应商信息已更新: {}", provider.api_key_prefix);
+                            Some(provider)
                         }
+                        Err(e) => {
+                            record_error(
+                                app,
+                                MonitorErrorCategory::Database,
+                                Some(&path),
+                                format!("供应商更新失败: {}", e),
+                            );
+                            None
+                        }
+                    },
+                    None => track_subscription_provider(app),
+                };
+                if let Some(provider) = updated {
+                    if let Err(e) = app.emit("provider-switched", &provider) {
+                        tracing::error!("发送 provider-switched 事件失败: {}", e);
                     }
-                    Err(e) => {
-                        eprintln!("JSONL 文件读取失败 [{}]: {}", path.display(), e);
-                    }
+                    active_provider = Some(provider);
+                }
+            }
+            IngestJob::Jsonl(file) => {
+                if active_provider.is_none() {
+                    // settings.json 不存在且尚未记录过供应商时，尝试识别订阅账号
+                    active_provider = repository
+                        .get_active_provider()
+                        .ok()
+                        .flatten()
+                        .or_else(|| track_subscription_provider(app));
+                }
+                let Some(provider) = &active_provider else {
+                    continue;
+                };
+                let (dedupe_policy, pricing) = ingest_context.get_or_insert_with(|| {
+                    (
+                        repository
+                            .get_setting::<DedupeSettings>()
+                            .unwrap_or_default()
+                            .policy,
+                        PricingService::new()
+                            .with_aliases(&repository.get_model_aliases().unwrap_or_default()),
+                    )
+                });
+                skipped_lines += file.skipped_lines;
+                for line in file.lines {
+                    updated_stats |= store_parsed_line(
+                        app,
+                        &repository,
+                        provider,
+                        pricing,
+                        *dedupe_policy,
+                        &file.path,
+                        line,
+                    );
                 }
             }
         }
@@ -199,23 +351,360 @@ fn handle_file_changes(app: &AppHandle, paths: &[PathBuf]) -> Result<(), FileWat
 
     // 记录跳过的行数（调试用）
     if skipped_lines > 0 {
-        println!("跳过 {} 行非消息数据", skipped_lines);
+        tracing::debug!("跳过 {} 行非消息数据", skipped_lines);
     }
 
     if updated_stats {
+        app.state::<WatcherHealth>().record_ingest();
         match repository.get_current_stats() {
             Ok(stats) => {
                 if let Err(e) = app.emit("stats-updated", stats) {
-                    eprintln!("发送 stats-updated 事件失败: {}", e);
+                    tracing::error!("发送 stats-updated 事件失败: {}", e);
                 }
             }
             Err(e) => {
-                eprintln!("获取统计数据失败: {}", e);
+                record_error(
+                    app,
+                    MonitorErrorCategory::Database,
+                    None,
+                    format!("获取统计数据失败: {}", e),
+                );
             }
         }
+
+        check_cost_anomaly(app, &repository);
+        check_budget(app, &repository);
+        check_block_warning(app, &repository);
+        check_alert_rules(app, &repository);
+        check_outliers(app, &repository);
+        update_taskbar_progress(app);
     }
+}
 
-    Ok(())
+/// 写入 JSONL 中的一行，返回是否新增了消息用量
+fn store_parsed_line(
+    app: &AppHandle,
+    repository: &Repository,
+    provider: &Provider,
+    pricing: &PricingService,
+    dedupe_policy: DedupePolicy,
+    path: &Path,
+    line: ParsedLine,
+) -> bool {
+    match line {
+        ParsedLine::ApiError(error) => store_api_error(repository, provider.id, &error),
+        ParsedLine::Compaction(event) => {
+            if let Err(e) = repository.insert_compaction_event(provider.id, &event) {
+                tracing::error!("压缩事件写入失败 [{}]: {}", event.session_id, e);
+            }
+        }
+        ParsedLine::Interrupt(event) => {
+            if let Err(e) = repository.insert_interrupt(provider.id, &event) {
+                tracing::error!("中断记录写入失败 [{}]: {}", event.session_id, e);
+            }
+        }
+        ParsedLine::Message {
+            mut record,
+            tool_calls,
+        } => {
+            store_tool_calls(repository, provider.id, &tool_calls);
+            record.model = pricing.resolve_model(&record.model).to_string();
+            // 订阅账号的记录通常不带费用，按 API 价格估算等价费用
+            if provider.kind == ProviderKind::Subscription && record.usage.cost_usd == 0.0 {
+                record.usage.cost_usd = pricing.calculate_tier_cost(
+                    &record.model,
+                    pricing_date(&record.created_at),
+                    record.service_tier,
+                    record.usage.input_tokens,
+                    record.usage.output_tokens,
+                    record.usage.cache_read_tokens,
+                    record.usage.cache_creation_tokens,
+                );
+            }
+            match repository.insert_message_usage_with_policy(provider.id, &record, dedupe_policy) {
+                Ok(_) => {
+                    app.state::<LiveStats>().record(&record, Utc::now());
+                    return true;
+                }
+                Err(e) => {
+                    // 重复记录由唯一索引静默忽略，这里只会是真正的写入失败
+                    record_error(
+                        app,
+                        MonitorErrorCategory::Database,
+                        Some(path),
+                        format!("消息记录插入失败: {}", e),
+                    );
+                }
+            }
+        }
+        ParsedLine::SessionTitle {
+            session_id,
+            title,
+            source,
+            tool_calls,
+        } => {
+            store_tool_calls(repository, provider.id, &tool_calls);
+            if let Err(e) = repository.upsert_session_title(&session_id, &title, source) {
+                tracing::error!("会话标题写入失败 [{}]: {}", session_id, e);
+            }
+        }
+        ParsedLine::ToolCalls(tool_calls) => store_tool_calls(repository, provider.id, &tool_calls),
+    }
+    false
+}
+
+/// 记录监控错误
+///
+/// 写入错误日志与最近错误缓冲区，更新 get_health 的最近错误，并发送 monitor-error 事件
+fn record_error(
+    app: &AppHandle,
+    category: MonitorErrorCategory,
+    path: Option<&Path>,
+    message: String,
+) {
+    let path = path.map(|path| path.display().to_string());
+    match &path {
+        Some(path) => tracing::error!("{} [{}]", message, path),
+        None => tracing::error!("{}", message),
+    }
+    app.state::<WatcherHealth>().record_error(&message);
+
+    let error = app.state::<MonitorErrorLog>().push(category, path, message);
+    if let Err(e) = app.emit("monitor-error", error) {
+        tracing::error!("发送 monitor-error 事件失败: {}", e);
+    }
+}
+
+/// 检测今日费用异常，首次检测到时通知前端并发送系统通知
+fn check_cost_anomaly(app: &AppHandle, repository: &Repository) {
+    match run_anomaly_detection(repository, Local::now().date_naive()) {
+        Ok(Some(anomaly)) => {
+            let message = anomaly_message(&anomaly);
+            tracing::info!("检测到费用异常: {}", message);
+
+            dispatch_webhook(
+                app,
+                repository,
+                WebhookEvent::CostAnomaly,
+                "费用异常提醒",
+                &message,
+                serde_json::to_value(&anomaly).unwrap_or_default(),
+            );
+            notifier::notify(
+                app,
+                NotificationKind::CostAnomaly,
+                "费用异常提醒",
+                &message,
+                &anomaly,
+                true,
+            );
+            if let Err(e) = app.emit("cost-anomaly", anomaly) {
+                tracing::error!("发送 cost-anomaly 事件失败: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("费用异常检测失败: {}", e);
+        }
+    }
+}
+
+/// 检查每日预算，今日费用首次达到预警线或超出预算时推送 Webhook
+fn check_budget(app: &AppHandle, repository: &Repository) {
+    match run_budget_check(repository, Local::now().date_naive()) {
+        Ok(Some(progress)) => {
+            let (title, body) = budget_alert_message(&progress);
+            tracing::info!("{}: {}", title, body);
+
+            let event = match progress.level {
+                BudgetLevel::Exceeded => WebhookEvent::BudgetExceeded,
+                _ => WebhookEvent::BudgetWarning,
+            };
+            dispatch_webhook(
+                app,
+                repository,
+                event,
+                &title,
+                &body,
+                serde_json::to_value(progress).unwrap_or_default(),
+            );
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("预算检查失败: {}", e);
+        }
+    }
+}
+
+/// 在登记过的后台任务中推送 Webhook，重试等待不阻塞采集，退出时等待推送完成
+fn dispatch_webhook(
+    app: &AppHandle,
+    repository: &Repository,
+    event: WebhookEvent,
+    title: &str,
+    body: &str,
+    data: serde_json::Value,
+) {
+    let repository = repository.clone();
+    let title = title.to_string();
+    let body = body.to_string();
+    spawn_tracked(app, move || {
+        webhook::dispatch(&repository, event, &title, &body, data)
+    });
+}
+
+/// 检查当前 5 小时区块用量，达到预警百分比时通知前端并发送系统通知
+fn check_block_warning(app: &AppHandle, repository: &Repository) {
+    let settings: BlockWarningSettings = match repository.get_setting() {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("读取区块预警设置失败: {}", e);
+            return;
+        }
+    };
+
+    match block_warning::check_block_warning(
+        repository,
+        &settings,
+        &app.state::<BlockWarningState>(),
+        Utc::now(),
+    ) {
+        Ok(Some(warning)) => {
+            let message = block_warning_message(&warning);
+            tracing::info!("区块用量预警: {}", message);
+
+            notifier::notify(
+                app,
+                NotificationKind::BlockWarning,
+                "区块用量提醒",
+                &message,
+                &warning,
+                true,
+            );
+            if let Err(e) = app.emit("block-warning", warning) {
+                tracing::error!("发送 block-warning 事件失败: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("区块用量预警检查失败: {}", e);
+        }
+    }
+}
+
+/// 评估告警规则，触发时通知前端，渠道为系统通知时同时发送系统通知
+fn check_alert_rules(app: &AppHandle, repository: &Repository) {
+    let alerts = match evaluate_alert_rules(repository, Local::now()) {
+        Ok(alerts) => alerts,
+        Err(e) => {
+            tracing::error!("告警规则评估失败: {}", e);
+            return;
+        }
+    };
+
+    for alert in alerts {
+        tracing::info!("触发告警: {}", alert.message);
+
+        notifier::notify(
+            app,
+            NotificationKind::Alert,
+            "用量告警",
+            &alert.message,
+            &alert,
+            alert.channel == AlertChannel::Notification,
+        );
+        if let Err(e) = app.emit("alert-triggered", alert) {
+            tracing::error!("发送 alert-triggered 事件失败: {}", e);
+        }
+    }
+}
+
+/// 检查新写入的消息中是否有异常大输入，开启通知时逐条提醒
+fn check_outliers(app: &AppHandle, repository: &Repository) {
+    let settings: OutlierSettings = match repository.get_setting() {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("读取异常输入检测设置失败: {}", e);
+            return;
+        }
+    };
+
+    let outliers = match outliers::check_new_outliers(
+        repository,
+        &settings,
+        &app.state::<OutlierState>(),
+        Local::now().date_naive(),
+    ) {
+        Ok(outliers) => outliers,
+        Err(e) => {
+            tracing::error!("异常输入检测失败: {}", e);
+            return;
+        }
+    };
+
+    for outlier in outliers {
+        let message = outlier_message(&outlier);
+        tracing::info!("异常大输入: {}", message);
+
+        notifier::notify(
+            app,
+            NotificationKind::Outlier,
+            "异常大输入提醒",
+            &message,
+            &outlier,
+            true,
+        );
+        if let Err(e) = app.emit("outlier-detected", outlier) {
+            tracing::error!("发送 outlier-detected 事件失败: {}", e);
+        }
+    }
+}
+
+/// 识别 claude.ai 订阅账号并记录为当前供应商，未登录时返回 None
+fn track_subscription_provider(app: &AppHandle) -> Option<Provider> {
+    let account = dirs::home_dir().and_then(|home| detect_oauth_account(&home))?;
+
+    match app
+        .state::<Repository>()
+        .upsert_subscription_provider(&account.account_id, &account.display_name())
+    {
+        Ok(provider) => {
+            tracing::info!("检测到订阅账号登录: {}", provider.api_key_prefix);
+            Some(provider)
+        }
+        Err(e) => {
+            record_error(
+                app,
+                MonitorErrorCategory::Database,
+                None,
+                format!("订阅账号记录失败: {}", e),
+            );
+            None
+        }
+    }
+}
+
+/// 写入 API 错误
+///
+/// 限流 / 过载错误同时写入 rate_limit_events，保持限流统计不变
+fn store_api_error(repository: &Repository, provider_id: i64, error: &ApiErrorEvent) {
+    if let Err(e) = repository.insert_api_error(provider_id, error) {
+        tracing::error!("API 错误写入失败 [{}]: {}", error.session_id, e);
+    }
+    if let Some(event) = error.rate_limit_event() {
+        if let Err(e) = repository.insert_rate_limit_event(provider_id, &event) {
+            tracing::error!("限流事件写入失败 [{}]: {}", event.session_id, e);
+        }
+    }
+}
+
+fn store_tool_calls(repository: &Repository, provider_id: i64, calls: &[ToolCall]) {
+    if calls.is_empty() {
+        return;
+    }
+    if let Err(e) = repository.insert_tool_calls(provider_id, calls) {
+        tracing::error!("工具调用写入失败 [{}]: {}", calls[0].session_id, e);
+    }
 }
 
 fn is_settings_file(path: &Path) -> bool {
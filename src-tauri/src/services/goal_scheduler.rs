@@ -0,0 +1,36 @@
+//! @file goal_scheduler.rs
+//! @description 目标每日评估调度服务，每天评估一次全部启用的目标并记录评估结果
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Repository;
+use crate::services::goals::evaluate_goals;
+
+/// 调度检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 启动目标评估调度线程
+///
+/// 启动时立即评估一次，之后每小时检查，跨过本地日期时再次评估；评估失败时下次检查重试
+pub fn start_goal_evaluator(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut evaluated_on: Option<NaiveDate> = None;
+        loop {
+            let now = Local::now();
+            if evaluated_on != Some(now.date_naive()) {
+                match evaluate_goals(&app.state::<Repository>(), now) {
+                    Ok(evaluations) => {
+                        tracing::info!("目标评估完成: {} 个目标", evaluations.len());
+                        evaluated_on = Some(now.date_naive());
+                    }
+                    Err(e) => tracing::error!("目标评估失败: {}", e),
+                }
+            }
+            std::thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
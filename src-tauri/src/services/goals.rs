@@ -0,0 +1,345 @@
+//! @file goals.rs
+//! @description 使用目标评估服务，按目标周期汇总用量并记录每个周期是否达成
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveTime};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{
+    CacheHitRateFormula, CacheHitRateSettings, Goal, GoalEvaluation, GoalMetric, GoalPeriod,
+    GoalProgress, GoalUsageTotals,
+};
+use crate::services::plan_limits::to_local;
+
+/// 目标进度中返回的已结束周期评估记录条数
+const GOAL_HISTORY_LIMIT: usize = 12;
+
+/// 包含 date 的周期，返回 [开始日期, 结束日期)
+pub fn period_bounds(period: GoalPeriod, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match period {
+        GoalPeriod::Day => (date, date + Duration::days(1)),
+        GoalPeriod::Week => {
+            let start = date - Duration::days(i64::from(date.weekday().num_days_from_monday()));
+            (start, start + Duration::days(7))
+        }
+        GoalPeriod::Month => {
+            let start = date.with_day(1).unwrap_or(date);
+            (start, start + Months::new(1))
+        }
+    }
+}
+
+/// 从用量汇总中取出目标指标的值，缓存命中率为百分比
+pub fn goal_value(
+    metric: GoalMetric,
+    totals: &GoalUsageTotals,
+    formula: CacheHitRateFormula,
+) -> f64 {
+    match metric {
+        GoalMetric::CacheHitRate => {
+            formula.rate(
+                totals.cache_read_tokens,
+                totals.input_tokens,
+                totals.cache_creation_tokens,
+            ) * 100.0
+        }
+        GoalMetric::CostUsd => totals.cost_usd,
+        GoalMetric::TotalTokens => {
+            (totals.input_tokens
+                + totals.output_tokens
+                + totals.cache_read_tokens
+                + totals.cache_creation_tokens) as f64
+        }
+        GoalMetric::InputTokens => totals.input_tokens as f64,
+        GoalMetric::OutputTokens => totals.output_tokens as f64,
+        GoalMetric::MessageCount => totals.message_count as f64,
+    }
+}
+
+/// 计算目标在 [start, end) 周期内的评估结果（不写入数据库）
+fn evaluate_period(
+    repository: &Repository,
+    goal: &Goal,
+    (start, end): (NaiveDate, NaiveDate),
+    formula: CacheHitRateFormula,
+    now: DateTime<Local>,
+) -> Result<GoalEvaluation, RepositoryError> {
+    let totals = repository.get_goal_usage_totals(
+        &to_local(start.and_time(NaiveTime::MIN)).to_rfc3339(),
+        &to_local(end.and_time(NaiveTime::MIN)).to_rfc3339(),
+    )?;
+    let value = goal_value(goal.metric, &totals, formula);
+    Ok(GoalEvaluation {
+        goal_id: goal.id,
+        period_start: start.to_string(),
+        value,
+        target: goal.target,
+        met: goal.direction.is_met(value, goal.target),
+        evaluated_at: now.to_rfc3339(),
+    })
+}
+
+/// 每日评估全部启用的目标
+///
+/// 业务逻辑说明：
+/// 1. 先重新评估上一个周期，补上上次评估之后写入的用量，得到该周期的最终结果；
+///    目标在当前周期内创建时没有上一个周期，跳过
+/// 2. 再评估当前周期，同一周期的记录每天覆盖
+/// 3. 返回当前周期的评估结果
+pub fn evaluate_goals(
+    repository: &Repository,
+    now: DateTime<Local>,
+) -> Result<Vec<GoalEvaluation>, RepositoryError> {
+    let formula = repository.get_setting::<CacheHitRateSettings>()?.formula;
+    let today = now.date_naive();
+    let mut evaluations = Vec::new();
+    for goal in repository.get_goals()? {
+        if !goal.enabled {
+            continue;
+        }
+        let current = period_bounds(goal.period, today);
+        let created = DateTime::parse_from_rfc3339(&goal.created_at)
+            .map(|created| created.with_timezone(&Local).date_naive())
+            .unwrap_or(today);
+        if created < current.0 {
+            let previous = period_bounds(goal.period, current.0 - Duration::days(1));
+            repository.upsert_goal_evaluation(&evaluate_period(
+                repository, &goal, previous, formula, now,
+            )?)?;
+        }
+        let evaluation = evaluate_period(repository, &goal, current, formula, now)?;
+        repository.upsert_goal_evaluation(&evaluation)?;
+        evaluations.push(evaluation);
+    }
+    Ok(evaluations)
+}
+
+/// 全部启用目标的当前进度
+///
+/// 当前周期的指标值实时计算，不依赖每日评估；历史只包含已结束周期的评估记录
+pub fn get_goal_progress(
+    repository: &Repository,
+    now: DateTime<Local>,
+) -> Result<Vec<GoalProgress>, RepositoryError> {
+    let formula = repository.get_setting::<CacheHitRateSettings>()?.formula;
+    let today = now.date_naive();
+    let mut progress = Vec::new();
+    for goal in repository.get_goals()? {
+        if !goal.enabled {
+            continue;
+        }
+        let bounds = period_bounds(goal.period, today);
+        let current = evaluate_period(repository, &goal, bounds, formula, now)?;
+        let history =
+            repository.get_goal_evaluations(goal.id, &current.period_start, GOAL_HISTORY_LIMIT)?;
+        let streak = history.iter().take_while(|e| e.met).count() as u32;
+        let percent = if goal.target > 0.0 {
+            current.value / goal.target * 100.0
+        } else if current.met {
+            100.0
+        } else {
+            0.0
+        };
+        progress.push(GoalProgress {
+            period_start: current.period_start,
+            period_end: bounds.1.to_string(),
+            value: current.value,
+            percent,
+            met: current.met,
+            streak,
+            history,
+            goal,
+        });
+    }
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GoalDirection, GoalInput, MessageRecord, MessageUsage};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date")
+    }
+
+    fn message(
+        id: &str,
+        created_at: DateTime<Local>,
+        input: i64,
+        output: i64,
+        cache_read: i64,
+    ) -> MessageRecord {
+        MessageRecord::new(
+            "session".to_string(),
+            id.to_string(),
+            "claude-sonnet-4-5".to_string(),
+            created_at.to_rfc3339(),
+            MessageUsage {
+                input_tokens: input,
+                output_tokens: output,
+                cache_read_tokens: cache_read,
+                cost_usd: 0.5,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        // 2026-10-17 为周六
+        let day = date("2026-10-17");
+        assert_eq!(
+            period_bounds(GoalPeriod::Day, day),
+            (day, date("2026-10-18"))
+        );
+        assert_eq!(
+            period_bounds(GoalPeriod::Week, day),
+            (date("2026-10-12"), date("2026-10-19"))
+        );
+        assert_eq!(
+            period_bounds(GoalPeriod::Week, date("2026-10-12")),
+            (date("2026-10-12"), date("2026-10-19"))
+        );
+        assert_eq!(
+            period_bounds(GoalPeriod::Month, date("2026-12-31")),
+            (date("2026-12-01"), date("2027-01-01"))
+        );
+    }
+
+    #[test]
+    fn test_goal_value() {
+        let totals = GoalUsageTotals {
+            cost_usd: 1.5,
+            input_tokens: 40,
+            output_tokens: 100,
+            cache_read_tokens: 60,
+            cache_creation_tokens: 20,
+            message_count: 3,
+        };
+        let strict = CacheHitRateFormula::Strict;
+        assert_eq!(goal_value(GoalMetric::CacheHitRate, &totals, strict), 60.0);
+        assert_eq!(
+            goal_value(
+                GoalMetric::CacheHitRate,
+                &totals,
+                CacheHitRateFormula::Inclusive
+            ),
+            50.0
+        );
+        assert_eq!(goal_value(GoalMetric::TotalTokens, &totals, strict), 220.0);
+        assert_eq!(goal_value(GoalMetric::OutputTokens, &totals, strict), 100.0);
+        assert_eq!(goal_value(GoalMetric::MessageCount, &totals, strict), 3.0);
+    }
+
+    #[test]
+    fn test_evaluate_goals_and_progress() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+        let now = Local::now();
+        let (week_start, week_end) = period_bounds(GoalPeriod::Week, now.date_naive());
+        let at = |day: NaiveDate| to_local(day.and_time(NaiveTime::MIN)) + Duration::hours(1);
+        for record in [
+            // 上一周：命中率 80%
+            message("m1", at(week_start - Duration::days(6)), 20, 500, 80),
+            // 本周：命中率 40%
+            message("m2", at(week_start), 60, 300, 40),
+            message("m3", at(week_start), 0, 200, 0),
+        ] {
+            repository
+                .insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let cache_goal = repository
+            .create_goal(&GoalInput {
+                name: "缓存命中率".to_string(),
+                metric: GoalMetric::CacheHitRate,
+                direction: GoalDirection::AtLeast,
+                target: 60.0,
+                period: GoalPeriod::Week,
+                enabled: true,
+            })
+            .expect("goal");
+        let output_goal = repository
+            .create_goal(&GoalInput {
+                name: "每周输出".to_string(),
+                metric: GoalMetric::OutputTokens,
+                direction: GoalDirection::AtMost,
+                target: 1000.0,
+                period: GoalPeriod::Week,
+                enabled: true,
+            })
+            .expect("goal");
+
+        // 目标在本周创建，首次评估只记录当前周期
+        let evaluations = evaluate_goals(&repository, now).expect("evaluate");
+        assert_eq!(evaluations.len(), 2);
+        assert_eq!(evaluations[0].period_start, week_start.to_string());
+        assert_eq!(evaluations[0].value, 40.0);
+        assert!(!evaluations[0].met);
+        assert_eq!(evaluations[1].value, 500.0);
+        assert!(evaluations[1].met);
+
+        // 下周评估时补记上一周期的最终结果
+        let next_week = now + Duration::days(7);
+        evaluate_goals(&repository, next_week).expect("evaluate");
+        let progress = get_goal_progress(&repository, next_week).expect("progress");
+        assert_eq!(progress.len(), 2);
+        let cache = &progress[0];
+        assert_eq!(cache.goal.id, cache_goal.id);
+        assert_eq!(cache.period_start, week_end.to_string());
+        assert_eq!(cache.period_end, (week_end + Duration::days(7)).to_string());
+        assert_eq!(cache.value, 0.0);
+        assert_eq!(cache.history.len(), 1);
+        assert_eq!(cache.history[0].period_start, week_start.to_string());
+        assert_eq!(cache.streak, 0);
+        let output = &progress[1];
+        assert_eq!(output.goal.id, output_goal.id);
+        assert!(output.met);
+        assert_eq!(output.percent, 0.0);
+        assert_eq!(output.streak, 1);
+
+        // 修改指标后旧评估记录清除；删除目标级联删除评估记录
+        let updated = repository
+            .update_goal(
+                output_goal.id,
+                &GoalInput {
+                    name: "每周 Token".to_string(),
+                    metric: GoalMetric::TotalTokens,
+                    direction: GoalDirection::AtMost,
+                    target: 2000.0,
+                    period: GoalPeriod::Week,
+                    enabled: true,
+                },
+            )
+            .expect("update")
+            .expect("goal");
+        assert_eq!(updated.metric, GoalMetric::TotalTokens);
+        assert!(repository
+            .get_goal_evaluations(output_goal.id, "9999-12-31", 10)
+            .expect("evaluations")
+            .is_empty());
+        assert!(repository.delete_goal(cache_goal.id).expect("delete"));
+        assert!(repository
+            .get_goal_evaluations(cache_goal.id, "9999-12-31", 10)
+            .expect("evaluations")
+            .is_empty());
+        assert!(repository
+            .update_goal(
+                cache_goal.id,
+                &GoalInput {
+                    name: "不存在".to_string(),
+                    metric: GoalMetric::CostUsd,
+                    direction: GoalDirection::AtMost,
+                    target: 1.0,
+                    period: GoalPeriod::Day,
+                    enabled: true,
+                }
+            )
+            .expect("update")
+            .is_none());
+    }
+}
@@ -0,0 +1,205 @@
+//! @file health.rs
+//! @description 运行状态诊断服务，汇总文件监控、数据库与最近错误信息，以及应用构建信息
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{AppInfo, BuildInfo, HealthReport, WatcherStatus};
+
+/// 文件监控运行状态
+///
+/// 由文件监控服务写入，get_health 读取；内部加锁，可在多个线程间共享
+#[derive(Default)]
+pub struct WatcherHealth {
+    state: Mutex<WatcherHealthState>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct WatcherHealthState {
+    status: WatcherStatus,
+    watched_paths: Vec<String>,
+    last_ingest_at: Option<String>,
+    last_error: Option<String>,
+    last_error_at: Option<String>,
+}
+
+impl WatcherHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 标记监控已启动
+    pub fn mark_running(&self, watched_paths: Vec<String>) {
+        self.update(|state| {
+            state.status = WatcherStatus::Running;
+            state.watched_paths = watched_paths;
+        });
+    }
+
+    /// 标记监控已停止
+    pub fn mark_paused(&self) {
+        self.update(|state| {
+            state.status = WatcherStatus::Paused;
+            state.watched_paths.clear();
+        });
+    }
+
+    /// 标记监控出错，同时记录错误信息
+    pub fn mark_errored(&self, error: &str) {
+        self.update(|state| state.status = WatcherStatus::Errored);
+        self.record_error(error);
+    }
+
+    /// 记录一次数据采集
+    pub fn record_ingest(&self) {
+        self.update(|state| state.last_ingest_at = Some(Utc::now().to_rfc3339()));
+    }
+
+    /// 记录最近一次错误，不改变监控状态
+    pub fn record_error(&self, error: &str) {
+        self.update(|state| {
+            state.last_error = Some(error.to_string());
+            state.last_error_at = Some(Utc::now().to_rfc3339());
+        });
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut WatcherHealthState)) {
+        if let Ok(mut state) = self.state.lock() {
+            apply(&mut state);
+        }
+    }
+
+    fn snapshot(&self) -> WatcherHealthState {
+        self.state
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// 生成运行状态诊断报告
+pub fn build_health_report(
+    repository: &Repository,
+    watcher: &WatcherHealth,
+) -> Result<HealthReport, RepositoryError> {
+    let state = watcher.snapshot();
+
+    Ok(HealthReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        watcher_status: state.status,
+        watched_paths: state.watched_paths,
+        db_path: repository.path().display().to_string(),
+        db_size_bytes: database_size(&repository.path()),
+        last_ingest_at: state.last_ingest_at,
+        last_error: state.last_error,
+        last_error_at: state.last_error_at,
+        schema_version: repository.schema_version()?,
+    })
+}
+
+/// 编译时启用的 Cargo features
+const ENABLED_FEATURES: &[(&str, bool)] = &[("custom-protocol", cfg!(feature = "custom-protocol"))];
+
+/// 获取构建信息
+///
+/// git 提交与构建时间由 build.rs 写入，未经 build.rs 编译时为 None
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_commit: option_env!("GIT_COMMIT_HASH")
+            .filter(|commit| !commit.is_empty())
+            .map(str::to_string),
+        build_date: option_env!("BUILD_TIMESTAMP")
+            .and_then(|timestamp| timestamp.parse::<i64>().ok())
+            .and_then(|timestamp| DateTime::<Utc>::from_timestamp(timestamp, 0))
+            .map(|date| date.to_rfc3339()),
+        features: ENABLED_FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        }
+        .to_string(),
+    }
+}
+
+/// 获取应用信息（构建信息与数据库 Schema 版本）
+pub fn get_app_info(repository: &Repository) -> Result<AppInfo, RepositoryError> {
+    Ok(AppInfo {
+        build: build_info(),
+        schema_version: repository.schema_version()?,
+    })
+}
+
+/// 数据库文件大小，包含尚未合并的 WAL 文件
+fn database_size(path: &Path) -> Option<u64> {
+    let main = std::fs::metadata(path).ok()?.len();
+    let mut wal_path = path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    let wal = std::fs::metadata(wal_path).map(|m| m.len()).unwrap_or(0);
+    Some(main + wal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_health_report() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let watcher = WatcherHealth::new();
+
+        let report = build_health_report(&repository, &watcher).expect("report");
+        assert_eq!(report.watcher_status, WatcherStatus::Paused);
+        assert_eq!(report.db_path, ":memory:");
+        assert_eq!(report.db_size_bytes, None);
+        assert!(report.schema_version > 0);
+
+        watcher.mark_running(vec!["/home/user/.claude".to_string()]);
+        watcher.record_ingest();
+        watcher.record_error("JSONL 行解析失败");
+        let report = build_health_report(&repository, &watcher).expect("report");
+        assert_eq!(report.watcher_status, WatcherStatus::Running);
+        assert_eq!(report.watched_paths, vec!["/home/user/.claude".to_string()]);
+        assert!(report.last_ingest_at.is_some());
+        assert_eq!(report.last_error.as_deref(), Some("JSONL 行解析失败"));
+
+        watcher.mark_errored("watch failed");
+        let report = build_health_report(&repository, &watcher).expect("report");
+        assert_eq!(report.watcher_status, WatcherStatus::Errored);
+        assert_eq!(report.last_error.as_deref(), Some("watch failed"));
+    }
+
+    #[test]
+    fn test_get_app_info() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let info = get_app_info(&repository).expect("info");
+        assert_eq!(info.build.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.schema_version, crate::db::migrations::latest_version());
+
+        let value = serde_json::to_value(&info).expect("serialize");
+        assert_eq!(value["version"], env!("CARGO_PKG_VERSION"));
+        assert!(value["features"].is_array());
+        assert!(value.get("build").is_none());
+    }
+
+    #[test]
+    fn test_database_size_on_disk() {
+        let path = std::env::temp_dir().join(format!("ctm-health-{}.db", std::process::id()));
+        let repository = Repository::new(&path).expect("repo");
+
+        let report = build_health_report(&repository, &WatcherHealth::new()).expect("report");
+        assert!(report.db_size_bytes.unwrap_or(0) > 0);
+
+        drop(repository);
+        std::fs::remove_file(&path).expect("cleanup");
+    }
+}
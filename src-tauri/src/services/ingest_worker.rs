@@ -0,0 +1,403 @@
+//! @file ingest_worker.rs
+//! @description 采集工作线程，文件监控解析出的记录经有界通道送入唯一的后台线程按序写库与汇总
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::Value;
+
+use crate::models::{
+    ApiErrorEvent, CompactionEvent, InterruptEvent, MessageRecord, SessionTitleSource, ToolCall,
+};
+use crate::services::parser::{
+    api_error_from_value, compaction_event_from_value, interrupt_from_value, message_from_value,
+    session_title_from_value, tool_calls_from_value, ParserError, Settings,
+};
+
+/// 通道容量（文件数），队列满时文件监控阻塞等待，限制事件风暴下的内存占用
+pub const INGEST_QUEUE_CAPACITY: usize = 32;
+
+/// 单批最多处理的任务数
+pub const MAX_BATCH_JOBS: usize = 64;
+
+/// 采集任务：一个已读取并解析的文件
+#[derive(Debug, Clone)]
+pub enum IngestJob {
+    /// settings.json；settings 为 None 表示未配置 API Key（使用 claude.ai 账号登录）
+    Settings {
+        path: PathBuf,
+        settings: Option<Settings>,
+    },
+
+    /// JSONL 文件的全部行
+    Jsonl(ParsedJsonl),
+}
+
+/// 解析后的 JSONL 文件
+#[derive(Debug, Clone, Default)]
+pub struct ParsedJsonl {
+    pub path: PathBuf,
+
+    /// 需要写库的行（按文件顺序），无内容的行不保留
+    pub lines: Vec<ParsedLine>,
+
+    /// 非消息且无标题的行数（调试用）
+    pub skipped_lines: usize,
+
+    /// 解析失败的行数
+    pub parse_failures: usize,
+
+    /// 首个解析错误
+    pub first_parse_error: Option<String>,
+}
+
+/// JSONL 中一行的解析结果
+#[derive(Debug, Clone)]
+pub enum ParsedLine {
+    ApiError(ApiErrorEvent),
+    Compaction(CompactionEvent),
+    Interrupt(InterruptEvent),
+
+    /// 消息行，附带该行的工具调用；模型别名与订阅费用估算在写库时处理
+    Message {
+        record: MessageRecord,
+        tool_calls: Vec<ToolCall>,
+    },
+
+    /// 会话标题，会话 ID 已按文件名补全
+    SessionTitle {
+        session_id: String,
+        title: String,
+        source: SessionTitleSource,
+        tool_calls: Vec<ToolCall>,
+    },
+
+    /// 只有工具调用的行（非消息行或消息解析失败）
+    ToolCalls(Vec<ToolCall>),
+}
+
+/// 逐行解析 JSONL 文件内容
+///
+/// 业务逻辑说明：
+/// 1. 每行只做一次 JSON 解码，解码失败的行计入解析失败
+/// 2. API 错误、压缩事件、中断记录独占一行，识别后不再按消息处理
+/// 3. 其余行先提取工具调用，再解析消息；非消息行尝试作为会话标题
+/// 4. 记录本身没有项目时按文件路径补全
+pub fn parse_jsonl_content(path: &Path, content: &str) -> ParsedJsonl {
+    let mut parsed = ParsedJsonl {
+        path: path.to_path_buf(),
+        ..Default::default()
+    };
+    for line in content.lines() {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                let e = ParserError::from(e);
+                tracing::warn!("JSONL 行解析失败 [{}]: {}", path.display(), e);
+                parsed.parse_failures += 1;
+                parsed.first_parse_error.get_or_insert(e.to_string());
+                continue;
+            }
+        };
+        if let Some(error) = api_error_from_value(&value) {
+            parsed.lines.push(ParsedLine::ApiError(error));
+            continue;
+        }
+        if let Some(event) = compaction_event_from_value(&value) {
+            parsed.lines.push(ParsedLine::Compaction(event));
+            continue;
+        }
+        if let Some(event) = interrupt_from_value(&value) {
+            parsed.lines.push(ParsedLine::Interrupt(event));
+            continue;
+        }
+        let tool_calls = tool_calls_from_value(&value);
+        if let Some(mut record) = message_from_value(&value) {
+            if record.project.is_none() {
+                record.project = project_from_path(path);
+            }
+            parsed
+                .lines
+                .push(ParsedLine::Message { record, tool_calls });
+            continue;
+        }
+        let title = session_title_from_value(&value).and_then(|entry| {
+            let session_id = entry.session_id.or_else(|| session_id_from_path(path))?;
+            Some((session_id, entry.title, entry.source))
+        });
+        if let Some((session_id, title, source)) = title {
+            parsed.lines.push(ParsedLine::SessionTitle {
+                session_id,
+                title,
+                source,
+                tool_calls,
+            });
+            continue;
+        }
+        parsed.skipped_lines += 1;
+        if !tool_calls.is_empty() {
+            parsed.lines.push(ParsedLine::ToolCalls(tool_calls));
+        }
+    }
+    parsed
+}
+
+/// 合并一批任务
+///
+/// 每次变更都会重新读取整个文件，同一文件较新的解析结果包含较早的内容，
+/// 因此只保留最新一份（位置取首次出现处）；settings.json 会切换供应商，
+/// 不跨越其前后合并，保证记录仍归属到变更发生时的供应商
+pub fn coalesce_jobs(jobs: Vec<IngestJob>) -> Vec<IngestJob> {
+    let mut batch: Vec<IngestJob> = Vec::with_capacity(jobs.len());
+    let mut segment_start = 0;
+    for job in jobs {
+        match job {
+            IngestJob::Jsonl(file) => {
+                let existing = batch[segment_start..].iter_mut().find(
+                    |job| matches!(job, IngestJob::Jsonl(existing) if existing.path == file.path),
+                );
+                match existing {
+                    Some(slot) => *slot = IngestJob::Jsonl(file),
+                    None => batch.push(IngestJob::Jsonl(file)),
+                }
+            }
+            settings @ IngestJob::Settings { .. } => {
+                batch.push(settings);
+                segment_start = batch.len();
+            }
+        }
+    }
+    batch
+}
+
+/// 排队中的任务，带提交时的代次
+struct QueuedJob {
+    generation: u64,
+    job: IngestJob,
+}
+
+/// 采集工作线程句柄
+///
+/// 全部写库与汇总都在同一个线程按提交顺序执行；通道有界，队列满时提交方阻塞。
+/// 切换档案时调用 discard_pending 使此前提交的任务失效，避免旧档案的数据写入新库
+pub struct IngestWorker {
+    sender: SyncSender<QueuedJob>,
+    generation: Arc<AtomicU64>,
+}
+
+impl IngestWorker {
+    /// 启动工作线程，handler 每次处理合并后的一批任务
+    ///
+    /// handler 收到本批任务的代次；取出任务到开始写库之间可能发生档案切换，
+    /// handler 开始写库前需再与 generation() 比对，不一致时放弃本批
+    pub fn spawn<F>(handler: F) -> std::io::Result<Self>
+    where
+        F: FnMut(u64, Vec<IngestJob>) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::sync_channel(INGEST_QUEUE_CAPACITY);
+        let generation = Arc::new(AtomicU64::new(0));
+        let current = generation.clone();
+        thread::Builder::new()
+            .name("ingest-worker".to_string())
+            .spawn(move || run_worker(receiver, current, handler))?;
+        Ok(Self { sender, generation })
+    }
+
+    /// 当前代次，提交任务时携带
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// 提交任务；代次已失效或工作线程已退出时返回 false，调用方应停止提交
+    pub fn submit(&self, generation: u64, job: IngestJob) -> bool {
+        if generation != self.generation() {
+            return false;
+        }
+        self.sender.send(QueuedJob { generation, job }).is_ok()
+    }
+
+    /// 使已提交但尚未处理的任务失效
+    pub fn discard_pending(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// 工作线程主循环：阻塞等待首个任务，再取出已排队的任务组成一批
+fn run_worker<F>(receiver: Receiver<QueuedJob>, generation: Arc<AtomicU64>, mut handler: F)
+where
+    F: FnMut(u64, Vec<IngestJob>),
+{
+    while let Ok(first) = receiver.recv() {
+        let mut queued = vec![first];
+        while queued.len() < MAX_BATCH_JOBS {
+            match receiver.try_recv() {
+                Ok(job) => queued.push(job),
+                Err(_) => break,
+            }
+        }
+
+        let current = generation.load(Ordering::SeqCst);
+        let total = queued.len();
+        let jobs: Vec<IngestJob> = queued
+            .into_iter()
+            .filter(|queued| queued.generation == current)
+            .map(|queued| queued.job)
+            .collect();
+        if jobs.len() < total {
+            tracing::debug!("丢弃 {} 个已失效的采集任务", total - jobs.len());
+        }
+        if jobs.is_empty() {
+            continue;
+        }
+
+        // 单批处理失败不能让工作线程退出，否则后续提交全部失败
+        if catch_unwind(AssertUnwindSafe(|| handler(current, coalesce_jobs(jobs)))).is_err() {
+            tracing::error!("采集任务处理异常，已跳过本批");
+        }
+    }
+    tracing::info!("采集工作线程已退出");
+}
+
+/// 从 JSONL 文件名推断会话 ID
+fn session_id_from_path(path: &Path) -> Option<String> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| stem.to_string())
+}
+
+/// 从 JSONL 路径推断项目目录名
+///
+/// Claude Code 的会话文件位于 ~/.claude/projects/<编码后的项目路径>/<session>.jsonl，
+/// 当记录本身没有 cwd 字段时以该目录名作为项目标识
+fn project_from_path(path: &Path) -> Option<String> {
+    let mut components = path.components().map(|c| c.as_os_str().to_string_lossy());
+    components.find(|component| component == "projects")?;
+    let project = components.next()?;
+    // 项目目录下必须还有文件名，避免把 projects 下的文件本身当作项目
+    components.next()?;
+    Some(project.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn jsonl(path: &str, lines: usize) -> IngestJob {
+        IngestJob::Jsonl(ParsedJsonl {
+            path: PathBuf::from(path),
+            skipped_lines: lines,
+            ..Default::default()
+        })
+    }
+
+    /// 任务摘要：(路径, 版本号)，版本号借用 skipped_lines 字段区分同一文件的多次读取
+    fn summary(jobs: &[IngestJob]) -> Vec<(String, usize)> {
+        jobs.iter()
+            .map(|job| match job {
+                IngestJob::Settings { path, .. } => (path.display().to_string(), 0),
+                IngestJob::Jsonl(file) => (file.path.display().to_string(), file.skipped_lines),
+            })
+            .collect()
+    }
+
+    fn settings(path: &str) -> IngestJob {
+        IngestJob::Settings {
+            path: PathBuf::from(path),
+            settings: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_jsonl_content() {
+        let path = Path::new("/home/u/.claude/projects/-work-app/sess_file.jsonl");
+        let content = [
+            r#"{"id":"msg_1","session_id":"sess_1","model":"claude-3","created_at":"2026-01-08T00:00:00Z","usage":{"input_tokens":10,"output_tokens":5,"cost_usd":0.01}}"#,
+            r#"{"type":"summary","summary":"Fix login","leafUuid":"uuid-1"}"#,
+            r#"{"type":"system"}"#,
+            "{not json",
+        ]
+        .join("\n");
+
+        let parsed = parse_jsonl_content(path, &content);
+        assert_eq!(parsed.lines.len(), 2);
+        match &parsed.lines[0] {
+            ParsedLine::Message { record, .. } => {
+                assert_eq!(record.message_id, "msg_1");
+                assert_eq!(record.project.as_deref(), Some("-work-app"));
+            }
+            other => panic!("unexpected line: {:?}", other),
+        }
+        match &parsed.lines[1] {
+            ParsedLine::SessionTitle {
+                session_id, title, ..
+            } => {
+                assert_eq!(session_id, "sess_file");
+                assert_eq!(title, "Fix login");
+            }
+            other => panic!("unexpected line: {:?}", other),
+        }
+        assert_eq!(parsed.skipped_lines, 1);
+        assert_eq!(parsed.parse_failures, 1);
+        assert!(parsed.first_parse_error.is_some());
+    }
+
+    #[test]
+    fn test_coalesce_jobs_keeps_latest_per_segment() {
+        let batch = coalesce_jobs(vec![
+            jsonl("a.jsonl", 1),
+            jsonl("b.jsonl", 1),
+            jsonl("a.jsonl", 2),
+            settings("settings.json"),
+            jsonl("a.jsonl", 3),
+            jsonl("a.jsonl", 4),
+        ]);
+        assert_eq!(
+            summary(&batch),
+            summary(&[
+                jsonl("a.jsonl", 2),
+                jsonl("b.jsonl", 1),
+                settings("settings.json"),
+                jsonl("a.jsonl", 4),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_worker_processes_in_order_and_discards_stale_jobs() {
+        let (done, batches) = mpsc::channel();
+        let worker = IngestWorker::spawn(move |generation, jobs| {
+            let _ = done.send((generation, jobs));
+        })
+        .expect("spawn");
+
+        let generation = worker.generation();
+        for index in 0..5 {
+            assert!(worker.submit(generation, jsonl(&format!("{}.jsonl", index), index)));
+        }
+        let mut received = Vec::new();
+        while received.len() < 5 {
+            let (batch_generation, jobs) =
+                batches.recv_timeout(Duration::from_secs(5)).expect("batch");
+            assert_eq!(batch_generation, generation);
+            received.extend(jobs);
+        }
+        let expected: Vec<IngestJob> = (0..5)
+            .map(|index| jsonl(&format!("{}.jsonl", index), index))
+            .collect();
+        assert_eq!(summary(&received), summary(&expected));
+
+        worker.discard_pending();
+        assert!(!worker.submit(generation, jsonl("stale.jsonl", 0)));
+        assert!(worker.submit(worker.generation(), jsonl("next.jsonl", 0)));
+        let (next_generation, next) = batches.recv_timeout(Duration::from_secs(5)).expect("batch");
+        assert_eq!(next_generation, worker.generation());
+        assert_ne!(next_generation, generation);
+        assert_eq!(summary(&next), summary(&[jsonl("next.jsonl", 0)]));
+    }
+}
@@ -0,0 +1,182 @@
+//! @file insights.rs
+//! @description 用量洞察服务，根据消息级统计发现可优化的使用模式并给出建议
+//! @author Atlas.oi
+//! @date 2026-10-17
+use crate::db::{Repository, RepositoryError};
+use crate::models::{Insight, InsightKind, ModelOutputProfile};
+use crate::services::pricing::{ModelFamily, PricingService};
+
+/// 输出 Token 低于该值的消息视为短输出
+pub const SHORT_OUTPUT_TOKENS: i64 = 500;
+
+/// 短输出消息占比达到该值时给出换用建议
+const MIN_SHORT_OUTPUT_SHARE: f64 = 0.3;
+
+/// 模型消息数低于该值时样本不足，不给出建议
+const MIN_MESSAGES: i64 = 20;
+
+/// 生成日期范围内的用量洞察，按预计节省金额降序
+pub fn get_insights(
+    repository: &Repository,
+    pricing: &PricingService,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<Insight>, RepositoryError> {
+    let profiles =
+        repository.get_model_output_profiles(start_date, end_date, SHORT_OUTPUT_TOKENS)?;
+    let mut insights: Vec<Insight> = profiles
+        .iter()
+        .filter_map(|profile| downgrade_insight(profile, pricing))
+        .collect();
+    insights.sort_by(|a, b| {
+        b.potential_savings_usd
+            .unwrap_or(0.0)
+            .partial_cmp(&a.potential_savings_usd.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+    Ok(insights)
+}
+
+/// 较贵模型的短输出消息占比较高时，建议换用低一档的模型
+///
+/// 建议模型由模型名中的系列替换得到（如 claude-3-opus-20240229 → claude-3-sonnet-20240229），
+/// 预计节省按短输出消息的 Token 分别以两个模型计价得到
+pub fn downgrade_insight(
+    profile: &ModelOutputProfile,
+    pricing: &PricingService,
+) -> Option<Insight> {
+    if profile.message_count < MIN_MESSAGES || profile.short_output_count == 0 {
+        return None;
+    }
+    let share = profile.short_output_count as f64 / profile.message_count as f64;
+    if share < MIN_SHORT_OUTPUT_SHARE {
+        return None;
+    }
+
+    let model = pricing.resolve_model(&profile.model);
+    let family = ModelFamily::detect(model)?;
+    let cheaper = family.cheaper()?;
+    let suggested_model = model.replace(family.id(), cheaper.id());
+
+    let cost = |model: &str| {
+        pricing.calculate_cost(
+            model,
+            profile.short_input_tokens,
+            profile.short_output_tokens,
+            profile.short_cache_read_tokens,
+            profile.short_cache_creation_tokens,
+        )
+    };
+    let potential_savings_usd = (pricing.has_pricing(model)
+        && pricing.has_pricing(&suggested_model))
+    .then(|| cost(model) - cost(&suggested_model));
+
+    let alternatives = match cheaper.cheaper() {
+        Some(cheapest) => format!("{} 或 {}", cheaper.name(), cheapest.name()),
+        None => cheaper.name().to_string(),
+    };
+    let mut message = format!(
+        "{:.0}% 的 {} 消息输出少于 {} Token，可考虑改用 {}",
+        share * 100.0,
+        family.name(),
+        SHORT_OUTPUT_TOKENS,
+        alternatives
+    );
+    if let Some(savings) = potential_savings_usd.filter(|savings| *savings > 0.0) {
+        message.push_str(&format!("，预计节省 ${:.2}", savings));
+    }
+
+    Some(Insight {
+        kind: InsightKind::ModelDowngrade,
+        message,
+        model: profile.model.clone(),
+        suggested_model: Some(suggested_model),
+        affected_messages: profile.short_output_count,
+        share,
+        potential_savings_usd,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    fn profile(model: &str, message_count: i64, short_output_count: i64) -> ModelOutputProfile {
+        ModelOutputProfile {
+            model: model.to_string(),
+            message_count,
+            short_output_count,
+            short_input_tokens: 1_000_000,
+            short_output_tokens: 0,
+            short_cache_read_tokens: 0,
+            short_cache_creation_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn test_downgrade_insight() {
+        let pricing = PricingService::new();
+
+        let insight = downgrade_insight(&profile("claude-3-opus-20240229", 50, 19), &pricing)
+            .expect("insight");
+        assert_eq!(insight.kind, InsightKind::ModelDowngrade);
+        assert_eq!(
+            insight.suggested_model.as_deref(),
+            Some("claude-3-sonnet-20240229")
+        );
+        assert!((insight.potential_savings_usd.expect("savings") - 12.0).abs() < 1e-9);
+        assert_eq!(
+            insight.message,
+            "38% 的 Opus 消息输出少于 500 Token，可考虑改用 Sonnet 或 Haiku，预计节省 $12.00"
+        );
+
+        // 占比不足、样本不足或已是最便宜的系列时不给出建议
+        assert!(downgrade_insight(&profile("claude-3-opus", 50, 10), &pricing).is_none());
+        assert!(downgrade_insight(&profile("claude-3-opus", 10, 10), &pricing).is_none());
+        assert!(downgrade_insight(&profile("claude-3-haiku", 50, 50), &pricing).is_none());
+
+        // 建议模型没有价格配置时不估算节省
+        let insight =
+            downgrade_insight(&profile("claude-opus-4-1", 50, 50), &pricing).expect("insight");
+        assert_eq!(
+            insight.suggested_model.as_deref(),
+            Some("claude-sonnet-4-1")
+        );
+        assert!(insight.potential_savings_usd.is_none());
+    }
+
+    #[test]
+    fn test_get_insights() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-insights", None)
+            .expect("provider");
+        let created_at = chrono::Local::now();
+        for index in 0..40 {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                format!("message-{}", index),
+                "claude-3-opus".to_string(),
+                created_at.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 10_000,
+                    output_tokens: if index < 20 { 100 } else { 2_000 },
+                    ..Default::default()
+                },
+            );
+            repository
+                .insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let today = created_at.date_naive().to_string();
+        let insights =
+            get_insights(&repository, &PricingService::new(), &today, &today).expect("insights");
+        assert_eq!(insights.len(), 1);
+        assert_eq!(insights[0].affected_messages, 20);
+        assert!((insights[0].share - 0.5).abs() < 1e-9);
+        assert!(insights[0].potential_savings_usd.expect("savings") > 0.0);
+    }
+}
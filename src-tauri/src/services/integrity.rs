@@ -0,0 +1,227 @@
+//! @file integrity.rs
+//! @description 数据一致性检查服务，发现增量汇总 daily_stats 与原始消息记录之间的偏差，以及重复写入的消息
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{
+    DailyStatsDiscrepancy, DailyStatsTotals, DuplicateAuditReport, DuplicateGroup, IntegrityReport,
+};
+
+/// 检查 daily_stats 与 message_usage 重新汇总结果是否一致
+pub fn verify_data_integrity(repository: &Repository) -> Result<IntegrityReport, RepositoryError> {
+    let stored = repository.get_stored_daily_totals()?;
+    let recomputed = repository.recompute_daily_totals()?;
+    Ok(build_integrity_report(&stored, &recomputed))
+}
+
+/// 根据 message_usage 重建每日汇总，并返回重建后的检查结果
+pub fn rebuild_daily_stats(repository: &Repository) -> Result<IntegrityReport, RepositoryError> {
+    let rebuilt = repository.rebuild_daily_stats()?;
+    tracing::info!("已重建每日汇总: {} 条", rebuilt);
+    verify_data_integrity(repository)
+}
+
+/// 审计 message_usage 中重复写入的消息
+///
+/// 早期版本按 (供应商, 消息, 日期) 去重，同一消息可能在多个供应商下或跨天被重复记录
+pub fn audit_duplicates(repository: &Repository) -> Result<DuplicateAuditReport, RepositoryError> {
+    let groups = repository.get_duplicate_messages()?;
+    Ok(build_duplicate_report(groups))
+}
+
+/// 删除重复消息（每个 message_id 保留最早写入的一条），并返回清理后的审计结果
+///
+/// 被删除的消息移入回收站，保留期内可恢复
+pub fn cleanup_duplicates(
+    repository: &Repository,
+) -> Result<DuplicateAuditReport, RepositoryError> {
+    if let Some(item) = repository.delete_duplicate_messages()? {
+        tracing::info!("已将重复消息移入回收站: {} 条", item.message_count);
+    }
+    audit_duplicates(repository)
+}
+
+/// 汇总重复记录组，生成审计报告
+pub fn build_duplicate_report(groups: Vec<DuplicateGroup>) -> DuplicateAuditReport {
+    DuplicateAuditReport {
+        group_count: groups.len(),
+        duplicate_rows: groups.iter().map(|g| g.row_count - 1).sum(),
+        duplicate_tokens: groups.iter().map(|g| g.duplicate_tokens).sum(),
+        duplicate_cost_usd: groups.iter().map(|g| g.duplicate_cost_usd).sum(),
+        groups,
+        checked_at: Utc::now().to_rfc3339(),
+    }
+}
+
+/// 对比存储值与重新汇总值，生成检查报告
+///
+/// 任一侧缺失或数值不一致的 (供应商, 日期) 组合都视为不一致
+pub fn build_integrity_report(
+    stored: &BTreeMap<(i64, String), DailyStatsTotals>,
+    recomputed: &BTreeMap<(i64, String), DailyStatsTotals>,
+) -> IntegrityReport {
+    let keys: BTreeSet<_> = stored.keys().chain(recomputed.keys()).collect();
+
+    let discrepancies: Vec<_> = keys
+        .iter()
+        .filter_map(|key| {
+            let stored = stored.get(*key);
+            let recomputed = recomputed.get(*key);
+            match (stored, recomputed) {
+                (Some(stored), Some(recomputed)) if stored.matches(recomputed) => None,
+                _ => Some(DailyStatsDiscrepancy {
+                    provider_id: key.0,
+                    date: key.1.clone(),
+                    stored: stored.cloned(),
+                    recomputed: recomputed.cloned(),
+                }),
+            }
+        })
+        .collect();
+
+    IntegrityReport {
+        checked_days: keys.len(),
+        is_consistent: discrepancies.is_empty(),
+        discrepancies,
+        checked_at: Utc::now().to_rfc3339(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals(input_tokens: i64, cost_usd: f64) -> DailyStatsTotals {
+        DailyStatsTotals {
+            input_tokens,
+            cost_usd,
+            session_count: 1,
+            message_count: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_integrity_report() {
+        let day = |date: &str| (1, date.to_string());
+        let stored = BTreeMap::from([
+            (day("2026-10-15"), totals(100, 0.3)),
+            (day("2026-10-16"), totals(150, 1.0)),
+            (day("2026-10-17"), totals(10, 0.1)),
+        ]);
+        let recomputed = BTreeMap::from([
+            (day("2026-10-14"), totals(50, 0.2)),
+            (day("2026-10-15"), totals(100, 0.1 + 0.2)),
+            (day("2026-10-16"), totals(200, 1.0)),
+        ]);
+
+        let report = build_integrity_report(&stored, &recomputed);
+        assert_eq!(report.checked_days, 4);
+        assert!(!report.is_consistent);
+
+        let dates: Vec<_> = report
+            .discrepancies
+            .iter()
+            .map(|d| d.date.as_str())
+            .collect();
+        assert_eq!(dates, vec!["2026-10-14", "2026-10-16", "2026-10-17"]);
+        assert!(report.discrepancies[0].stored.is_none());
+        assert!(report.discrepancies[2].recomputed.is_none());
+
+        let report = build_integrity_report(&recomputed, &recomputed);
+        assert!(report.is_consistent);
+    }
+
+    #[test]
+    fn test_audit_and_cleanup_duplicates() {
+        use crate::models::{
+            DedupePolicy, DeletedItemKind, DuplicateKind, MessageRecord, MessageUsage,
+        };
+
+        let repository = Repository::new_in_memory().expect("repo");
+        let first = repository
+            .upsert_provider("sk-first", None)
+            .expect("provider");
+        let second = repository
+            .create_provider("sk-second", None)
+            .expect("provider");
+        let record = |message_id: &str, created_at: &str| {
+            MessageRecord::new(
+                "s1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                created_at.to_string(),
+                MessageUsage {
+                    input_tokens: 100,
+                    output_tokens: 10,
+                    cost_usd: 0.5,
+                    ..Default::default()
+                },
+            )
+        };
+        // 按早期版本的供应商内去重写入：m1 跨供应商重复，m2 同一供应商跨天重复，m3 不重复
+        for (provider_id, message_id, created_at) in [
+            (first.id, "m1", "2026-10-15T10:00:00+00:00"),
+            (second.id, "m1", "2026-10-15T10:00:00+00:00"),
+            (first.id, "m2", "2026-10-15T23:59:00+00:00"),
+            (first.id, "m2", "2026-10-16T00:01:00+00:00"),
+            (first.id, "m2", "2026-10-17T00:01:00+00:00"),
+            (first.id, "m3", "2026-10-16T08:00:00+00:00"),
+        ] {
+            repository
+                .insert_message_usage_with_policy(
+                    provider_id,
+                    &record(message_id, created_at),
+                    DedupePolicy::PerProvider,
+                )
+                .expect("insert");
+        }
+
+        let report = audit_duplicates(&repository).expect("audit");
+        assert_eq!(report.group_count, 2);
+        assert_eq!(report.duplicate_rows, 3);
+        assert_eq!(report.duplicate_tokens, 330);
+        assert!((report.duplicate_cost_usd - 1.5).abs() < 1e-9);
+
+        let m2 = &report.groups[0];
+        assert_eq!(m2.message_id, "m2");
+        assert_eq!(m2.kind, DuplicateKind::RepeatedSessionMessage);
+        assert_eq!(m2.row_count, 3);
+        assert_eq!(m2.first_created_at, "2026-10-15T23:59:00+00:00");
+        let m1 = &report.groups[1];
+        assert_eq!(m1.kind, DuplicateKind::CrossProvider);
+        assert_eq!(m1.provider_ids.len(), 2);
+
+        let report = cleanup_duplicates(&repository).expect("cleanup");
+        assert_eq!(report.group_count, 0);
+        assert_eq!(report.duplicate_rows, 0);
+        assert!(
+            verify_data_integrity(&repository)
+                .expect("verify")
+                .is_consistent
+        );
+
+        // 被删除的重复消息进入回收站，恢复后回到清理前的状态
+        let items = repository.get_deleted_items().expect("trash");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, DeletedItemKind::DuplicateMessages);
+        assert_eq!(items[0].message_count, 3);
+        let restored = repository
+            .restore_deleted(items[0].id)
+            .expect("restore")
+            .expect("item");
+        assert_eq!(restored.messages_restored, 3);
+        assert_eq!(restored.restored_id, 0);
+        let report = audit_duplicates(&repository).expect("audit");
+        assert_eq!(report.duplicate_rows, 3);
+        assert!(
+            verify_data_integrity(&repository)
+                .expect("verify")
+                .is_consistent
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! @file json_rpc.rs
+//! @description 行分隔 JSON-RPC 2.0 的 stdio 循环与响应封装，供 MCP 服务与 RPC 服务共用
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::io::{BufRead, Write};
+
+use serde_json::{json, Value};
+
+/// 方法分发结果，失败时为（错误码, 错误信息）
+pub type DispatchResult = Result<Value, (i64, String)>;
+
+/// 运行 stdio 服务，逐行读取 JSON-RPC 请求并写回响应，直到标准输入关闭
+///
+/// 标准输出只用于协议消息，日志写入标准错误
+pub fn run_stdio<F>(mut dispatch: F) -> std::io::Result<()>
+where
+    F: FnMut(&str, Value) -> DispatchResult,
+{
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_message(&line, &mut dispatch) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理单条 JSON-RPC 消息，按方法名与参数调用 dispatch
+///
+/// # 返回
+/// 需要回复时返回响应 JSON；通知消息（无 id）返回 None
+pub fn handle_message<F>(line: &str, dispatch: F) -> Option<String>
+where
+    F: FnOnce(&str, Value) -> DispatchResult,
+{
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(error_response(Value::Null, -32700, &e.to_string())),
+    };
+
+    let id = request.get("id").cloned();
+    let method = request.get("method").and_then(|v| v.as_str()).unwrap_or("");
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    // 通知消息不需要回复
+    let id = id?;
+
+    Some(match dispatch(method, params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+/// 未知方法对应的错误
+pub fn method_not_found(method: &str) -> (i64, String) {
+    (-32601, format!("Method not found: {}", method))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn echo(method: &str, params: Value) -> DispatchResult {
+        match method {
+            "echo" => Ok(params),
+            _ => Err(method_not_found(method)),
+        }
+    }
+
+    #[test]
+    fn test_handle_message() {
+        let response = handle_message(
+            r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":[1]}"#,
+            echo,
+        )
+        .expect("response");
+        let response: Value = serde_json::from_str(&response).expect("json");
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"], json!([1]));
+
+        let response = handle_message(r#"{"jsonrpc":"2.0","id":"a","method":"nope"}"#, echo)
+            .expect("response");
+        let response: Value = serde_json::from_str(&response).expect("json");
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["error"]["code"], -32601);
+
+        let response = handle_message("not json", echo).expect("response");
+        let response: Value = serde_json::from_str(&response).expect("json");
+        assert!(response["id"].is_null());
+        assert_eq!(response["error"]["code"], -32700);
+
+        // 通知消息不返回响应
+        assert!(handle_message(r#"{"jsonrpc":"2.0","method":"echo"}"#, echo).is_none());
+    }
+}
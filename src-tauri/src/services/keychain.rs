@@ -0,0 +1,205 @@
+//! @file keychain.rs
+//! @description 系统钥匙串服务，保存手动添加供应商的完整 API Key（SQLite 中只保存哈希与前缀）、余额查询凭证、云同步密钥、Webhook 地址与 Admin API Key；
+//! 除 Admin API Key 外均按数据库的命名空间区分，不同档案与演示数据库互不影响
+//! @author Atlas.oi
+//! @date 2026-10-17
+use keyring::Entry;
+use thiserror::Error;
+
+use crate::models::SyncSecret;
+
+/// 钥匙串中的服务名
+const KEYCHAIN_SERVICE: &str = "claude-token-monitor";
+
+/// Anthropic Admin API Key 的钥匙串账户名
+const ADMIN_API_KEY_USER: &str = "anthropic-admin";
+
+#[derive(Error, Debug)]
+pub enum KeychainError {
+    #[error("Keychain error: {0}")]
+    Keyring(#[from] keyring::Error),
+}
+
+/// 保存供应商的完整 API Key，已存在时覆盖
+///
+/// namespace 为供应商所在数据库的钥匙串命名空间（Repository::keychain_namespace）
+pub fn store_api_key(
+    namespace: &str,
+    provider_id: i64,
+    api_key: &str,
+) -> Result<(), KeychainError> {
+    provider_entry(namespace, provider_id)?.set_password(api_key)?;
+    Ok(())
+}
+
+/// 读取供应商的完整 API Key，钥匙串中没有时返回 None
+pub fn load_api_key(namespace: &str, provider_id: i64) -> Result<Option<String>, KeychainError> {
+    read_password(&provider_entry(namespace, provider_id)?)
+}
+
+/// 删除供应商的 API Key
+///
+/// # 返回
+/// 钥匙串中存在并被删除返回 true
+pub fn delete_api_key(namespace: &str, provider_id: i64) -> Result<bool, KeychainError> {
+    remove_credential(&provider_entry(namespace, provider_id)?)
+}
+
+/// 保存供应商余额查询凭证（中转站的访问令牌），已存在时覆盖
+pub fn store_quota_credential(
+    namespace: &str,
+    provider_id: i64,
+    credential: &str,
+) -> Result<(), KeychainError> {
+    quota_entry(namespace, provider_id)?.set_password(credential)?;
+    Ok(())
+}
+
+/// 读取供应商余额查询凭证，未保存时返回 None
+pub fn load_quota_credential(
+    namespace: &str,
+    provider_id: i64,
+) -> Result<Option<String>, KeychainError> {
+    read_password(&quota_entry(namespace, provider_id)?)
+}
+
+/// 删除供应商余额查询凭证，存在并被删除时返回 true
+pub fn delete_quota_credential(namespace: &str, provider_id: i64) -> Result<bool, KeychainError> {
+    remove_credential(&quota_entry(namespace, provider_id)?)
+}
+
+/// 保存云同步密钥（WebDAV 密码、S3 私有访问密钥或快照加密口令），已存在时覆盖
+pub fn store_sync_secret(
+    namespace: &str,
+    secret: SyncSecret,
+    value: &str,
+) -> Result<(), KeychainError> {
+    sync_entry(namespace, secret)?.set_password(value)?;
+    Ok(())
+}
+
+/// 读取云同步密钥，未保存时返回 None
+pub fn load_sync_secret(
+    namespace: &str,
+    secret: SyncSecret,
+) -> Result<Option<String>, KeychainError> {
+    read_password(&sync_entry(namespace, secret)?)
+}
+
+/// 删除云同步密钥，存在并被删除时返回 true
+pub fn delete_sync_secret(namespace: &str, secret: SyncSecret) -> Result<bool, KeychainError> {
+    remove_credential(&sync_entry(namespace, secret)?)
+}
+
+/// 保存 Webhook 目标的完整地址（含访问令牌），已存在时覆盖
+pub fn store_webhook_url(namespace: &str, target_id: i64, url: &str) -> Result<(), KeychainError> {
+    webhook_entry(namespace, target_id)?.set_password(url)?;
+    Ok(())
+}
+
+/// 读取 Webhook 目标的完整地址，未保存时返回 None
+pub fn load_webhook_url(namespace: &str, target_id: i64) -> Result<Option<String>, KeychainError> {
+    read_password(&webhook_entry(namespace, target_id)?)
+}
+
+/// 删除 Webhook 目标的完整地址，存在并被删除时返回 true
+pub fn delete_webhook_url(namespace: &str, target_id: i64) -> Result<bool, KeychainError> {
+    remove_credential(&webhook_entry(namespace, target_id)?)
+}
+
+/// 保存 Anthropic Admin API Key，已存在时覆盖
+pub fn store_admin_api_key(api_key: &str) -> Result<(), KeychainError> {
+    Entry::new(KEYCHAIN_SERVICE, ADMIN_API_KEY_USER)?.set_password(api_key)?;
+    Ok(())
+}
+
+/// 读取 Anthropic Admin API Key，未保存时返回 None
+pub fn load_admin_api_key() -> Result<Option<String>, KeychainError> {
+    read_password(&Entry::new(KEYCHAIN_SERVICE, ADMIN_API_KEY_USER)?)
+}
+
+/// 删除 Anthropic Admin API Key，存在并被删除时返回 true
+pub fn delete_admin_api_key() -> Result<bool, KeychainError> {
+    remove_credential(&Entry::new(KEYCHAIN_SERVICE, ADMIN_API_KEY_USER)?)
+}
+
+/// 接管旧版本未区分数据库保存的供应商凭证，移入指定命名空间
+///
+/// 旧版本只有一个数据库，只应对默认档案的数据库调用
+///
+/// # 返回
+/// 迁移的凭证条数
+pub fn adopt_legacy_credentials(
+    namespace: &str,
+    provider_ids: &[i64],
+) -> Result<usize, KeychainError> {
+    let mut adopted = 0;
+    for &provider_id in provider_ids {
+        for (legacy_user, entry) in [
+            (
+                format!("provider-{}", provider_id),
+                provider_entry(namespace, provider_id)?,
+            ),
+            (
+                format!("provider-quota-{}", provider_id),
+                quota_entry(namespace, provider_id)?,
+            ),
+        ] {
+            let legacy = Entry::new(KEYCHAIN_SERVICE, &legacy_user)?;
+            if let Some(secret) = read_password(&legacy)? {
+                entry.set_password(&secret)?;
+                remove_credential(&legacy)?;
+                adopted += 1;
+            }
+        }
+    }
+    Ok(adopted)
+}
+
+/// 供应商对应的钥匙串条目，以数据库命名空间与供应商 ID 区分
+fn provider_entry(namespace: &str, provider_id: i64) -> Result<Entry, keyring::Error> {
+    Entry::new(
+        KEYCHAIN_SERVICE,
+        &format!("{}-provider-{}", namespace, provider_id),
+    )
+}
+
+/// 供应商余额查询凭证的钥匙串条目
+fn quota_entry(namespace: &str, provider_id: i64) -> Result<Entry, keyring::Error> {
+    Entry::new(
+        KEYCHAIN_SERVICE,
+        &format!("{}-provider-quota-{}", namespace, provider_id),
+    )
+}
+
+/// 云同步密钥的钥匙串条目
+fn sync_entry(namespace: &str, secret: SyncSecret) -> Result<Entry, keyring::Error> {
+    Entry::new(
+        KEYCHAIN_SERVICE,
+        &format!("{}-sync-{}", namespace, secret.as_str()),
+    )
+}
+
+/// Webhook 目标地址的钥匙串条目
+fn webhook_entry(namespace: &str, target_id: i64) -> Result<Entry, keyring::Error> {
+    Entry::new(
+        KEYCHAIN_SERVICE,
+        &format!("{}-webhook-{}", namespace, target_id),
+    )
+}
+
+fn read_password(entry: &Entry) -> Result<Option<String>, KeychainError> {
+    match entry.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn remove_credential(entry: &Entry) -> Result<bool, KeychainError> {
+    match entry.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,74 @@
+//! @file latency.rs
+//! @description 请求耗时统计服务，按供应商与模型计算平均值和 P95
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::BTreeMap;
+
+use crate::models::{LatencySample, LatencyStats};
+
+/// 按供应商与模型汇总耗时样本
+///
+/// P95 使用最近秩法：排序后取第 ceil(0.95 × n) 个样本
+pub fn calculate_latency_stats(samples: &[LatencySample]) -> Vec<LatencyStats> {
+    let mut groups: BTreeMap<(i64, &str), Vec<i64>> = BTreeMap::new();
+    for sample in samples {
+        groups
+            .entry((sample.provider_id, sample.model.as_str()))
+            .or_default()
+            .push(sample.duration_ms);
+    }
+
+    groups
+        .into_iter()
+        .map(|((provider_id, model), mut durations)| {
+            durations.sort_unstable();
+            let count = durations.len();
+            let total: i64 = durations.iter().sum();
+            let p95_rank = ((count as f64 * 0.95).ceil() as usize).clamp(1, count);
+
+            LatencyStats {
+                provider_id,
+                model: model.to_string(),
+                request_count: count as i64,
+                avg_ms: total as f64 / count as f64,
+                p95_ms: durations[p95_rank - 1],
+                max_ms: durations[count - 1],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(provider_id: i64, model: &str, duration_ms: i64) -> LatencySample {
+        LatencySample {
+            provider_id,
+            model: model.to_string(),
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn test_calculate_latency_stats() {
+        let mut samples: Vec<_> = (1..=20)
+            .map(|i| sample(1, "claude-3-opus", i * 100))
+            .collect();
+        samples.push(sample(2, "claude-3-opus", 800));
+
+        let stats = calculate_latency_stats(&samples);
+        assert_eq!(stats.len(), 2);
+
+        assert_eq!(stats[0].provider_id, 1);
+        assert_eq!(stats[0].request_count, 20);
+        assert_eq!(stats[0].avg_ms, 1050.0);
+        assert_eq!(stats[0].p95_ms, 1900);
+        assert_eq!(stats[0].max_ms, 2000);
+
+        assert_eq!(stats[1].provider_id, 2);
+        assert_eq!(stats[1].p95_ms, 800);
+
+        assert!(calculate_latency_stats(&[]).is_empty());
+    }
+}
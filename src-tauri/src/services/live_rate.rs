@@ -0,0 +1,34 @@
+//! @file live_rate.rs
+//! @description 实时速率推送服务，定期向前端发送 live-rate 事件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::Duration;
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::services::live_stats::LiveStats;
+
+/// 推送间隔
+const EMIT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 启动实时速率推送线程
+///
+/// 窗口内有消息时每隔 EMIT_INTERVAL 推送一次；速率归零时再推送一次后停止，直到有新消息
+pub fn start_live_rate_emitter(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut was_active = false;
+        loop {
+            std::thread::sleep(EMIT_INTERVAL);
+
+            let rate = app.state::<LiveStats>().rate(Utc::now());
+            let is_active = rate.message_count > 0;
+            if is_active || was_active {
+                if let Err(e) = app.emit("live-rate", rate) {
+                    tracing::error!("发送 live-rate 事件失败: {}", e);
+                }
+            }
+            was_active = is_active;
+        }
+    });
+}
@@ -0,0 +1,160 @@
+//! @file live_stats.rs
+//! @description 实时 Token 速率统计，在内存中维护最近采集消息的滑动窗口
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::{LiveRate, MessageRecord};
+
+/// 滑动窗口长度（秒）
+pub const LIVE_WINDOW_SECONDS: i64 = 120;
+
+/// 实时速率统计
+///
+/// 由文件监控服务写入，get_live_rate 与 live-rate 事件读取；内部加锁，可在多个线程间共享
+#[derive(Default)]
+pub struct LiveStats {
+    samples: Mutex<VecDeque<LiveSample>>,
+}
+
+#[derive(Debug, Clone)]
+struct LiveSample {
+    message_id: String,
+    created_at: DateTime<Utc>,
+    input_tokens: i64,
+    output_tokens: i64,
+}
+
+impl LiveStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 清空全部样本（如切换档案后）
+    pub fn reset(&self) {
+        if let Ok(mut samples) = self.samples.lock() {
+            samples.clear();
+        }
+    }
+
+    /// 记录一条新采集的消息
+    ///
+    /// 文件变更时会重新读取整个 JSONL 文件，因此按消息 ID 去重；
+    /// 创建时间不在窗口内（历史消息或时间无法解析）的消息直接忽略
+    pub fn record(&self, record: &MessageRecord, now: DateTime<Utc>) {
+        let created_at = match DateTime::parse_from_rfc3339(&record.created_at) {
+            Ok(created_at) => created_at.with_timezone(&Utc),
+            Err(_) => return,
+        };
+        if created_at < window_start(now) || created_at > now {
+            return;
+        }
+
+        if let Ok(mut samples) = self.samples.lock() {
+            prune(&mut samples, now);
+            if samples
+                .iter()
+                .any(|sample| sample.message_id == record.message_id)
+            {
+                return;
+            }
+            samples.push_back(LiveSample {
+                message_id: record.message_id.clone(),
+                created_at,
+                input_tokens: record.usage.input_tokens
+                    + record.usage.cache_read_tokens
+                    + record.usage.cache_creation_tokens,
+                output_tokens: record.usage.output_tokens,
+            });
+        }
+    }
+
+    /// 计算当前窗口内的每分钟 Token 速率
+    pub fn rate(&self, now: DateTime<Utc>) -> LiveRate {
+        let (input_tokens, output_tokens, message_count) = self
+            .samples
+            .lock()
+            .map(|mut samples| {
+                prune(&mut samples, now);
+                samples
+                    .iter()
+                    .fold((0, 0, 0), |(input, output, count), sample| {
+                        (
+                            input + sample.input_tokens,
+                            output + sample.output_tokens,
+                            count + 1,
+                        )
+                    })
+            })
+            .unwrap_or_default();
+
+        let minutes = LIVE_WINDOW_SECONDS as f64 / 60.0;
+        LiveRate {
+            output_tokens_per_minute: output_tokens as f64 / minutes,
+            input_tokens_per_minute: input_tokens as f64 / minutes,
+            message_count,
+            window_seconds: LIVE_WINDOW_SECONDS,
+            updated_at: now.to_rfc3339(),
+        }
+    }
+}
+
+fn window_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    now - Duration::seconds(LIVE_WINDOW_SECONDS)
+}
+
+/// 移除窗口外的样本
+fn prune(samples: &mut VecDeque<LiveSample>, now: DateTime<Utc>) {
+    let start = window_start(now);
+    samples.retain(|sample| sample.created_at >= start);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MessageUsage;
+
+    fn record(message_id: &str, created_at: DateTime<Utc>, output_tokens: i64) -> MessageRecord {
+        MessageRecord::new(
+            "session-1".to_string(),
+            message_id.to_string(),
+            "claude-3-opus".to_string(),
+            created_at.to_rfc3339(),
+            MessageUsage {
+                input_tokens: 10,
+                output_tokens,
+                cache_read_tokens: 90,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 0.0,
+            },
+        )
+    }
+
+    #[test]
+    fn test_live_rate_sliding_window() {
+        let stats = LiveStats::new();
+        let now = Utc::now();
+
+        stats.record(&record("m1", now - Duration::seconds(30), 600), now);
+        // 重复读取同一条消息不重复计数
+        stats.record(&record("m1", now - Duration::seconds(30), 600), now);
+        stats.record(&record("m2", now - Duration::seconds(90), 400), now);
+        // 历史消息不计入
+        stats.record(&record("m3", now - Duration::hours(1), 9999), now);
+
+        let rate = stats.rate(now);
+        assert_eq!(rate.message_count, 2);
+        assert_eq!(rate.output_tokens_per_minute, 500.0);
+        assert_eq!(rate.input_tokens_per_minute, 100.0);
+
+        let later = stats.rate(now + Duration::seconds(60));
+        assert_eq!(later.message_count, 1);
+        assert_eq!(later.output_tokens_per_minute, 300.0);
+
+        assert_eq!(stats.rate(now + Duration::minutes(10)).message_count, 0);
+    }
+}
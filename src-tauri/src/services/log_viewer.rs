@@ -0,0 +1,176 @@
+//! @file log_viewer.rs
+//! @description 日志文件读取服务，从滚动日志中提取最近的日志记录供界面查看
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::models::{LogEntry, LogLevel};
+
+/// 日志文件名前缀，滚动后的文件名形如 `claude-token-monitor.2026-10-17.log`
+pub const LOG_FILE_PREFIX: &str = "claude-token-monitor";
+
+/// 日志文件扩展名
+pub const LOG_FILE_SUFFIX: &str = "log";
+
+/// 读取最近的日志记录
+///
+/// 业务逻辑说明：
+/// 1. 按文件名（含日期）从新到旧遍历日志文件
+/// 2. 每个文件从最后一行向前读取，跳过无法解析或级别低于 min_level 的行
+/// 3. 收集满 limit 条后停止；目录不存在时返回空列表
+///
+/// # 返回
+/// 按时间从新到旧排列的日志记录
+pub fn read_recent_logs(
+    dir: &Path,
+    min_level: LogLevel,
+    limit: usize,
+) -> std::io::Result<Vec<LogEntry>> {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut files: Vec<_> = read_dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| {
+                    name.starts_with(LOG_FILE_PREFIX)
+                        && name.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+                })
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+
+    let mut entries = Vec::new();
+    for path in files.iter().rev() {
+        let content = fs::read_to_string(path)?;
+        for line in content.lines().rev() {
+            if entries.len() >= limit {
+                return Ok(entries);
+            }
+            if let Some(entry) = parse_log_line(line) {
+                if entry.level.is_at_least(min_level) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 解析一行 JSON 格式日志
+///
+/// message 以外的结构化字段以 `key=value` 形式追加到消息末尾
+pub fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let level = LogLevel::parse(value.get("level")?.as_str()?)?;
+    let fields = value.get("fields").and_then(|v| v.as_object());
+
+    let mut message = fields
+        .and_then(|fields| fields.get("message"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    if let Some(fields) = fields {
+        for (key, field) in fields.iter().filter(|(key, _)| *key != "message") {
+            let field = match field {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            message.push_str(&format!(" {}={}", key, field));
+        }
+    }
+
+    Some(LogEntry {
+        timestamp: value
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        level,
+        target: value
+            .get("target")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        message: message.trim_start().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(timestamp: &str, level: &str, message: &str) -> String {
+        format!(
+            r#"{{"timestamp":"{}","level":"{}","fields":{{"message":"{}"}},"target":"claude_token_monitor_lib::services::file_watcher"}}"#,
+            timestamp, level, message
+        )
+    }
+
+    #[test]
+    fn test_parse_log_line() {
+        let entry = parse_log_line(
+            r#"{"timestamp":"2026-10-17T10:00:00Z","level":"WARN","fields":{"message":"JSONL 行解析失败","line":3},"target":"parser"}"#,
+        )
+        .expect("entry");
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.message, "JSONL 行解析失败 line=3");
+        assert_eq!(entry.target, "parser");
+
+        assert!(parse_log_line("not json").is_none());
+    }
+
+    #[test]
+    fn test_read_recent_logs() {
+        let dir = std::env::temp_dir().join(format!("ctm-logs-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("dir");
+        fs::write(
+            dir.join("claude-token-monitor.2026-10-16.log"),
+            [
+                line("2026-10-16T09:00:00Z", "ERROR", "old error"),
+                line("2026-10-16T10:00:00Z", "INFO", "old info"),
+            ]
+            .join("\n"),
+        )
+        .expect("write");
+        fs::write(
+            dir.join("claude-token-monitor.2026-10-17.log"),
+            [
+                line("2026-10-17T09:00:00Z", "DEBUG", "debug"),
+                line("2026-10-17T10:00:00Z", "WARN", "new warn"),
+            ]
+            .join("\n"),
+        )
+        .expect("write");
+        fs::write(
+            dir.join("other.log"),
+            line("2026-10-17T11:00:00Z", "ERROR", "x"),
+        )
+        .expect("write");
+
+        let entries = read_recent_logs(&dir, LogLevel::Warn, 10).expect("logs");
+        let messages: Vec<_> = entries.iter().map(|entry| entry.message.as_str()).collect();
+        assert_eq!(messages, vec!["new warn", "old error"]);
+
+        let entries = read_recent_logs(&dir, LogLevel::Trace, 2).expect("logs");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].message, "debug");
+
+        fs::remove_dir_all(&dir).expect("cleanup");
+        assert!(read_recent_logs(&dir, LogLevel::Info, 10)
+            .expect("missing dir")
+            .is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+//! @file logging.rs
+//! @description 日志初始化，控制台输出便于开发调试，JSON 格式写入按天滚动的日志文件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+use crate::services::log_viewer::{LOG_FILE_PREFIX, LOG_FILE_SUFFIX};
+
+/// 保留的日志文件数量（按天滚动，即保留最近 7 天）
+const MAX_LOG_FILES: usize = 7;
+
+/// 未设置 RUST_LOG 时的默认过滤规则
+const DEFAULT_FILTER: &str = "info,claude_token_monitor_lib=debug";
+
+/// 日志目录（应用数据目录下的 logs）
+pub fn logs_dir(app: &AppHandle) -> Result<PathBuf, tauri::Error> {
+    Ok(app.path().app_data_dir()?.join("logs"))
+}
+
+/// 初始化全局日志
+///
+/// 只能调用一次；日志文件创建失败时返回错误，调用方可继续运行
+pub fn init_logging(dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let file_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)?;
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_ansi(false)
+                .with_writer(file_appender),
+        )
+        .try_init()?;
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! @file mcp_server.rs
+//! @description MCP（Model Context Protocol）stdio 服务，向 Claude 提供使用量查询工具
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::Local;
+use serde_json::{json, Value};
+
+use crate::db::Repository;
+use crate::models::{SessionOrder, DEFAULT_TOP_SESSIONS_LIMIT};
+use crate::services::burn_rate::{burn_rate_start_date, calculate_burn_rate};
+use crate::services::json_rpc::{self, method_not_found, DispatchResult};
+use crate::services::plan_value::calculate_plan_value;
+use crate::services::pricing::PricingService;
+
+/// 未指定客户端版本时使用的 MCP 协议版本
+const DEFAULT_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// 运行 stdio 服务，直到标准输入关闭
+pub fn run_stdio(repository: &Repository) -> std::io::Result<()> {
+    json_rpc::run_stdio(|method, params| dispatch(repository, method, params))
+}
+
+/// 处理单条 JSON-RPC 消息
+///
+/// # 返回
+/// 需要回复时返回响应 JSON；通知消息（无 id）返回 None
+pub fn handle_message(repository: &Repository, line: &str) -> Option<String> {
+    json_rpc::handle_message(line, |method, params| dispatch(repository, method, params))
+}
+
+fn dispatch(repository: &Repository, method: &str, params: Value) -> DispatchResult {
+    match method {
+        "initialize" => Ok(initialize_result(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => Ok(call_tool(repository, &params)),
+        _ => Err(method_not_found(method)),
+    }
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let protocol_version = params
+        .get("protocolVersion")
+        .and_then(|v| v.as_str())
+        .unwrap_or(DEFAULT_PROTOCOL_VERSION);
+
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": "claude-token-monitor",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "get_today_stats",
+            "description": "Token usage and cost recorded today across all providers.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "get_budget_status",
+            "description": "Month-to-date spend, burn rate, projected month-end cost, and subscription plan value.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "get_daily_activities",
+            "description": "Daily token usage and cost for a date range.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "start_date": { "type": "string", "description": "YYYY-MM-DD" },
+                    "end_date": { "type": "string", "description": "YYYY-MM-DD" },
+                },
+                "required": ["start_date", "end_date"],
+            },
+        },
+        {
+            "name": "get_top_sessions",
+            "description": "Most expensive or most token-heavy sessions in a date range.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "start_date": { "type": "string", "description": "YYYY-MM-DD" },
+                    "end_date": { "type": "string", "description": "YYYY-MM-DD" },
+                    "limit": { "type": "integer", "minimum": 1 },
+                    "order_by": { "type": "string", "enum": ["cost", "tokens"] },
+                },
+                "required": ["start_date", "end_date"],
+            },
+        },
+    ])
+}
+
+/// 执行工具调用，工具错误以 isError 结果返回而非协议错误
+fn call_tool(repository: &Repository, params: &Value) -> Value {
+    let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+
+    let output = match name {
+        "get_today_stats" => to_value(repository.get_today_stats()),
+        "get_budget_status" => budget_status(repository),
+        "get_daily_activities" => match date_range(&arguments) {
+            Ok((start, end)) => to_value(repository.get_daily_activities(&start, &end)),
+            Err(e) => Err(e),
+        },
+        "get_top_sessions" => match date_range(&arguments) {
+            Ok((start, end)) => {
+                let limit = arguments
+                    .get("limit")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(DEFAULT_TOP_SESSIONS_LIMIT);
+                let order_by = match arguments.get("order_by").and_then(|v| v.as_str()) {
+                    Some("tokens") => SessionOrder::Tokens,
+                    _ => SessionOrder::Cost,
+                };
+                to_value(repository.get_top_sessions(&start, &end, limit, order_by))
+            }
+            Err(e) => Err(e),
+        },
+        _ => Err(format!("Unknown tool: {}", name)),
+    };
+
+    match output {
+        Ok(value) => json!({
+            "content": [{ "type": "text", "text": value.to_string() }],
+            "structuredContent": value,
+            "isError": false,
+        }),
+        Err(message) => json!({
+            "content": [{ "type": "text", "text": message }],
+            "isError": true,
+        }),
+    }
+}
+
+/// 本月消耗速率与订阅套餐价值
+fn budget_status(repository: &Repository) -> Result<Value, String> {
+    let now = Local::now().naive_local();
+    let today = now.date();
+    let start_date = burn_rate_start_date(today).to_string();
+    let activities = repository
+        .get_daily_activities(&start_date, &today.to_string())
+        .map_err(|e| e.to_string())?;
+    let burn_rate = calculate_burn_rate(&activities, now);
+
+    let pricing = PricingService::new();
+    let plans = repository
+        .get_provider_plans()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .filter(|plan| plan.plan_type.is_subscription())
+        .map(|plan| calculate_plan_value(repository, &pricing, plan, today))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(json!({ "burn_rate": burn_rate, "plans": plans }))
+}
+
+fn date_range(arguments: &Value) -> Result<(String, String), String> {
+    let start = arguments.get("start_date").and_then(|v| v.as_str());
+    let end = arguments.get("end_date").and_then(|v| v.as_str());
+    match (start, end) {
+        (Some(start), Some(end)) => Ok((start.to_string(), end.to_string())),
+        _ => Err("start_date and end_date are required (YYYY-MM-DD)".to_string()),
+    }
+}
+
+fn to_value<T: serde::Serialize, E: std::fmt::Display>(
+    result: Result<T, E>,
+) -> Result<Value, String> {
+    result
+        .map_err(|e| e.to_string())
+        .and_then(|data| serde_json::to_value(data).map_err(|e| e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(repository: &Repository, message: Value) -> Value {
+        let response = handle_message(repository, &message.to_string()).expect("response");
+        serde_json::from_str(&response).expect("json")
+    }
+
+    #[test]
+    fn test_initialize_and_list_tools() {
+        let repository = Repository::new_in_memory().expect("repo");
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}),
+        );
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(
+            response["result"]["serverInfo"]["name"],
+            "claude-token-monitor"
+        );
+
+        let notification = json!({"jsonrpc":"2.0","method":"notifications/initialized"});
+        assert!(handle_message(&repository, &notification.to_string()).is_none());
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":2,"method":"tools/list"}),
+        );
+        let tools = response["result"]["tools"].as_array().expect("tools");
+        assert!(tools.iter().any(|tool| tool["name"] == "get_today_stats"));
+        assert!(tools.iter().any(|tool| tool["name"] == "get_budget_status"));
+    }
+
+    #[test]
+    fn test_call_tools() {
+        let repository = Repository::new_in_memory().expect("repo");
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"get_today_stats"}}),
+        );
+        assert_eq!(response["result"]["isError"], false);
+        assert!(response["result"]["structuredContent"].is_object());
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"get_budget_status"}}),
+        );
+        assert_eq!(response["result"]["isError"], false);
+        assert!(response["result"]["structuredContent"]["burn_rate"].is_object());
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"get_daily_activities","arguments":{}}}),
+        );
+        assert_eq!(response["result"]["isError"], true);
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":6,"method":"unknown"}),
+        );
+        assert_eq!(response["error"]["code"], -32601);
+    }
+}
@@ -0,0 +1,89 @@
+//! @file menu_bar.rs
+//! @description 菜单栏模式窗口定位计算，将主窗口以弹出面板形式锚定到托盘图标
+//! @author Atlas.oi
+//! @date 2026-10-17
+
+/// 弹出窗口与托盘图标之间的间距（物理像素）
+const POPOVER_GAP: f64 = 4.0;
+
+/// 屏幕矩形区域（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// 计算弹出窗口左上角位置
+///
+/// 业务逻辑说明：
+/// 1. 窗口水平居中对齐托盘图标
+/// 2. 托盘位于工作区上半部分（macOS 菜单栏）时显示在图标下方，
+///    否则（Windows 任务栏等位于底部）显示在图标上方
+/// 3. 结果限制在工作区内，避免窗口超出屏幕边缘
+pub fn popover_position(
+    tray: ScreenRect,
+    window_width: f64,
+    window_height: f64,
+    work_area: ScreenRect,
+) -> (f64, f64) {
+    let x = tray.x + tray.width / 2.0 - window_width / 2.0;
+
+    let tray_center_y = tray.y + tray.height / 2.0;
+    let y = if tray_center_y < work_area.y + work_area.height / 2.0 {
+        tray.y + tray.height + POPOVER_GAP
+    } else {
+        tray.y - window_height - POPOVER_GAP
+    };
+
+    (
+        clamp_axis(x, window_width, work_area.x, work_area.width),
+        clamp_axis(y, window_height, work_area.y, work_area.height),
+    )
+}
+
+/// 将单个坐标限制在区域内；窗口大于区域时贴齐区域起点
+fn clamp_axis(value: f64, length: f64, area_start: f64, area_length: f64) -> f64 {
+    let max = (area_start + area_length - length).max(area_start);
+    value.clamp(area_start, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: ScreenRect = ScreenRect {
+        x: 0.0,
+        y: 50.0,
+        width: 2000.0,
+        height: 1000.0,
+    };
+
+    #[test]
+    fn test_popover_below_menu_bar_tray() {
+        let tray = ScreenRect {
+            x: 1500.0,
+            y: 0.0,
+            width: 40.0,
+            height: 44.0,
+        };
+
+        assert_eq!(popover_position(tray, 400.0, 600.0, SCREEN), (1320.0, 50.0));
+    }
+
+    #[test]
+    fn test_popover_above_taskbar_tray_and_clamped() {
+        let tray = ScreenRect {
+            x: 1980.0,
+            y: 1050.0,
+            width: 20.0,
+            height: 40.0,
+        };
+
+        assert_eq!(
+            popover_position(tray, 400.0, 600.0, SCREEN),
+            (1600.0, 446.0)
+        );
+    }
+}
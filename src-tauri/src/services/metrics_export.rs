@@ -0,0 +1,274 @@
+//! @file metrics_export.rs
+//! @description InfluxDB / VictoriaMetrics 行协议指标推送，负责生成数据行、失败缓存与重试
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{IntervalUsage, MetricsExportSettings};
+
+/// 单次请求最多发送的数据行数
+const MAX_LINES_PER_REQUEST: usize = 5_000;
+
+/// 同一请求失败后的重试等待时间，重试仍失败的数据留在缓存中等下一周期
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(1), Duration::from_secs(3)];
+
+/// 待推送数据行缓存，推送失败时保留，超过上限丢弃最旧的行
+#[derive(Debug, Default)]
+pub struct MetricsBuffer {
+    lines: VecDeque<String>,
+}
+
+impl MetricsBuffer {
+    /// 加入新数据行
+    ///
+    /// # 返回
+    /// 因超过上限被丢弃的行数
+    pub fn push(&mut self, lines: Vec<String>, max_lines: usize) -> usize {
+        self.lines.extend(lines);
+        let overflow = self.lines.len().saturating_sub(max_lines.max(1));
+        self.lines.drain(..overflow);
+        overflow
+    }
+
+    /// 按批发送缓存中的数据行，某批失败即停止，未发送的行保留到下次
+    ///
+    /// # 返回
+    /// 成功发送的行数
+    pub fn flush<F>(&mut self, mut send: F) -> Result<usize, String>
+    where
+        F: FnMut(&str) -> Result<(), String>,
+    {
+        let mut sent = 0;
+        while !self.lines.is_empty() {
+            let count = self.lines.len().min(MAX_LINES_PER_REQUEST);
+            let body = self
+                .lines
+                .iter()
+                .take(count)
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("\n");
+            send(&body)?;
+            self.lines.drain(..count);
+            sent += count;
+        }
+        Ok(sent)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+/// 查询 [start, end) 时间段的用量并生成行协议数据行，时间戳取 end
+pub fn collect_lines(
+    repository: &Repository,
+    measurement: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<String>, RepositoryError> {
+    let timestamp_ns = end.timestamp_nanos_opt().unwrap_or_default();
+    Ok(repository
+        .get_interval_usage(&start.to_rfc3339(), &end.to_rfc3339())?
+        .iter()
+        .map(|usage| format_line(measurement, usage, timestamp_ns))
+        .collect())
+}
+
+/// 生成一条行协议数据：供应商与模型为 tag，用量为 field
+pub fn format_line(measurement: &str, usage: &IntervalUsage, timestamp_ns: i64) -> String {
+    format!(
+        "{},provider={},model={} input_tokens={}i,output_tokens={}i,cache_read_tokens={}i,cache_creation_tokens={}i,cost_usd={},messages={}i {}",
+        escape_measurement(measurement),
+        escape_tag(&usage.provider),
+        escape_tag(&usage.model),
+        usage.input_tokens,
+        usage.output_tokens,
+        usage.cache_read_tokens,
+        usage.cache_creation_tokens,
+        usage.cost_usd,
+        usage.message_count,
+        timestamp_ns
+    )
+}
+
+/// 发送一批数据行
+pub fn send_lines(settings: &MetricsExportSettings, body: &str) -> Result<(), String> {
+    post_with_retry(
+        || {
+            let request =
+                ureq::post(&settings.url).set("Content-Type", "text/plain; charset=utf-8");
+            if settings.authorization.is_empty() {
+                request
+            } else {
+                request.set("Authorization", &settings.authorization)
+            }
+        },
+        body,
+    )
+}
+
+/// 发送请求，网络错误、429 与 5xx 按 RETRY_DELAYS 重试，其余错误直接返回
+pub(crate) fn post_with_retry<F>(build_request: F, body: &str) -> Result<(), String>
+where
+    F: Fn() -> ureq::Request,
+{
+    let mut delays = RETRY_DELAYS.iter();
+    loop {
+        let error = match build_request().send_string(body) {
+            Ok(_) => return Ok(()),
+            Err(ureq::Error::Status(status, response)) => {
+                let message = format!(
+                    "HTTP {}: {}",
+                    status,
+                    response.into_string().unwrap_or_default()
+                );
+                if status != 429 && status < 500 {
+                    return Err(message);
+                }
+                message
+            }
+            Err(e) => e.to_string(),
+        };
+
+        match delays.next() {
+            Some(delay) => {
+                tracing::warn!("推送指标失败，{:?} 后重试: {}", delay, error);
+                std::thread::sleep(*delay);
+            }
+            None => return Err(error),
+        }
+    }
+}
+
+/// measurement 中的逗号与空格需要转义
+fn escape_measurement(value: &str) -> String {
+    escape(value, &[',', ' '])
+}
+
+/// tag 中的逗号、等号与空格需要转义；空值不合法，替换为 unknown
+fn escape_tag(value: &str) -> String {
+    if value.is_empty() {
+        return "unknown".to_string();
+    }
+    escape(value, &[',', '=', ' '])
+}
+
+fn escape(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\n' | '\r' => escaped.push(' '),
+            _ => {
+                if special.contains(&ch) || ch == '\\' {
+                    escaped.push('\\');
+                }
+                escaped.push(ch);
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    fn usage(provider: &str, model: &str) -> IntervalUsage {
+        IntervalUsage {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            input_tokens: 100,
+            output_tokens: 20,
+            cache_read_tokens: 300,
+            cache_creation_tokens: 0,
+            cost_usd: 0.25,
+            message_count: 2,
+        }
+    }
+
+    #[test]
+    fn test_format_line() {
+        let line = format_line(
+            "claude usage",
+            &usage("My Key,1", "claude=opus"),
+            1_760_000_000_000_000_000,
+        );
+        assert_eq!(
+            line,
+            "claude\\ usage,provider=My\\ Key\\,1,model=claude\\=opus input_tokens=100i,output_tokens=20i,cache_read_tokens=300i,cache_creation_tokens=0i,cost_usd=0.25,messages=2i 1760000000000000000"
+        );
+        assert!(format_line("m", &usage("", "x"), 0).starts_with("m,provider=unknown,"));
+    }
+
+    #[test]
+    fn test_buffer_keeps_failed_lines() {
+        let mut buffer = MetricsBuffer::default();
+        assert_eq!(buffer.push(vec!["a".into(), "b".into()], 3), 0);
+        assert_eq!(buffer.push(vec!["c".into(), "d".into()], 3), 1);
+        assert_eq!(buffer.len(), 3);
+
+        let result = buffer.flush(|_| Err("offline".to_string()));
+        assert!(result.is_err());
+        assert_eq!(buffer.len(), 3);
+
+        let mut bodies = Vec::new();
+        let sent = buffer
+            .flush(|body| {
+                bodies.push(body.to_string());
+                Ok(())
+            })
+            .expect("flush");
+        assert_eq!(sent, 3);
+        assert_eq!(bodies, vec!["b\nc\nd".to_string()]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_collect_lines() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+        let end = Utc::now();
+        let start = end - chrono::Duration::minutes(1);
+        for (index, created_at) in [end - chrono::Duration::seconds(30), end]
+            .iter()
+            .enumerate()
+        {
+            let record = MessageRecord::new(
+                "s1".to_string(),
+                format!("m{}", index),
+                "claude-3-opus".to_string(),
+                created_at.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cost_usd: 1.5,
+                    ..Default::default()
+                },
+            );
+            repository
+                .insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let lines = collect_lines(&repository, "claude_usage", start, end).expect("lines");
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("claude_usage,provider="));
+        assert!(lines[0].contains("input_tokens=100i"));
+        assert!(lines[0].contains("messages=1i"));
+    }
+}
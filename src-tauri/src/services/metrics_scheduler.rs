@@ -0,0 +1,131 @@
+//! @file metrics_scheduler.rs
+//! @description 指标推送调度服务，按设置间隔将用量以行协议推送到 InfluxDB / VictoriaMetrics，或以 OTLP 推送到 OpenTelemetry Collector
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Repository;
+use crate::models::{CacheHitRateSettings, MetricsExportSettings, OtlpExportSettings};
+use crate::services::metrics_export::{collect_lines, send_lines, MetricsBuffer};
+use crate::services::otlp_export::{collect_metrics, send_metrics};
+
+/// 调度检查间隔；实际推送间隔由设置中的 interval_seconds 决定
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 周期结束时间相对当前时间的延后量，给文件监控留出写入新消息的时间
+const INGEST_DELAY_SECONDS: i64 = 30;
+
+/// 启动指标推送线程
+///
+/// 业务逻辑说明：
+/// 1. 每个周期查询 [上次推送截止时间, 当前时间 - 写入延迟) 内的用量，生成行协议数据加入缓存
+/// 2. 发送缓存中的所有数据，失败的数据保留到下个周期重试，超过上限丢弃最旧的数据
+/// 3. 关闭推送时清空缓存与推送进度；每次检查前重新读取设置
+pub fn start_metrics_exporter(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut buffer = MetricsBuffer::default();
+        let mut exported_until: Option<DateTime<Utc>> = None;
+        loop {
+            let settings: Result<MetricsExportSettings, _> =
+                app.state::<Repository>().get_setting();
+            match settings {
+                Ok(settings) if settings.enabled && !settings.url.is_empty() => {
+                    let interval =
+                        chrono::Duration::seconds(i64::from(settings.interval_seconds.max(1)));
+                    let end = Utc::now() - chrono::Duration::seconds(INGEST_DELAY_SECONDS);
+                    let start = exported_until.unwrap_or(end - interval);
+                    if end - start >= interval {
+                        export_interval(&app, &settings, &mut buffer, start, end);
+                        exported_until = Some(end);
+                    }
+                }
+                Ok(_) => {
+                    buffer.clear();
+                    exported_until = None;
+                }
+                Err(e) => tracing::error!("读取指标推送设置失败: {}", e),
+            }
+            std::thread::sleep(TICK_INTERVAL);
+        }
+    });
+}
+
+fn export_interval(
+    app: &AppHandle,
+    settings: &MetricsExportSettings,
+    buffer: &mut MetricsBuffer,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    match collect_lines(
+        &app.state::<Repository>(),
+        &settings.measurement,
+        start,
+        end,
+    ) {
+        Ok(lines) => {
+            let dropped = buffer.push(lines, settings.max_buffered_lines);
+            if dropped > 0 {
+                tracing::warn!("指标缓存已满，丢弃最旧的 {} 行", dropped);
+            }
+        }
+        Err(e) => tracing::error!("查询指标数据失败: {}", e),
+    }
+
+    if buffer.is_empty() {
+        return;
+    }
+    match buffer.flush(|body| send_lines(settings, body)) {
+        Ok(sent) => tracing::debug!("推送指标 {} 行", sent),
+        Err(e) => tracing::error!("推送指标失败，缓存 {} 行待重试: {}", buffer.len(), e),
+    }
+}
+
+/// 启动 OTLP 指标推送线程
+///
+/// 计数器从启用推送时开始累计；关闭推送后重新启用会开始新的累计周期
+pub fn start_otlp_exporter(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut started_at: Option<DateTime<Utc>> = None;
+        let mut last_pushed: Option<Instant> = None;
+        loop {
+            let settings: Result<OtlpExportSettings, _> = app.state::<Repository>().get_setting();
+            match settings {
+                Ok(settings) if settings.enabled && !settings.endpoint.is_empty() => {
+                    let start = *started_at.get_or_insert_with(Utc::now);
+                    let interval = Duration::from_secs(u64::from(settings.interval_seconds.max(1)));
+                    if last_pushed.is_none_or(|at| at.elapsed() >= interval) {
+                        if let Err(e) = push_otlp_metrics(&app, &settings, start) {
+                            tracing::error!("推送 OTLP 指标失败: {}", e);
+                        }
+                        last_pushed = Some(Instant::now());
+                    }
+                }
+                Ok(_) => {
+                    started_at = None;
+                    last_pushed = None;
+                }
+                Err(e) => tracing::error!("读取 OTLP 推送设置失败: {}", e),
+            }
+            std::thread::sleep(TICK_INTERVAL);
+        }
+    });
+}
+
+fn push_otlp_metrics(
+    app: &AppHandle,
+    settings: &OtlpExportSettings,
+    start: DateTime<Utc>,
+) -> Result<(), String> {
+    let repository = app.state::<Repository>();
+    let formula = repository
+        .get_setting::<CacheHitRateSettings>()
+        .map_err(|e| e.to_string())?
+        .formula;
+    let body = collect_metrics(&repository, settings, formula, start, Utc::now())
+        .map_err(|e| e.to_string())?;
+    send_metrics(settings, &body)
+}
@@ -2,7 +2,83 @@
 //! @description 核心服务模块入口
 //! @author Atlas.oi
 //! @date 2026-01-08
+pub mod active_sessions;
+pub mod admin_api;
+pub mod admin_api_poller;
+pub mod alert_engine;
+#[cfg(feature = "parser-benchmark")]
+pub mod alloc_counter;
+pub mod anomaly_detector;
+pub mod api_server;
+pub mod app_lock;
+pub mod balance_poller;
+pub mod block_warning;
+pub mod budget;
+pub mod burn_rate;
+pub mod cloud_sync;
+pub mod command_guard;
+pub mod context_usage;
+pub mod cost_allocation;
+pub mod cost_simulation;
+pub mod csv_import;
+pub mod demo_data;
+pub mod distribution;
+pub mod dock_badge;
+pub mod event_stream;
 pub mod file_watcher;
+pub mod goal_scheduler;
+pub mod goals;
+pub mod health;
+pub mod ingest_worker;
+pub mod insights;
+pub mod integrity;
+pub mod json_rpc;
+pub mod keychain;
+pub mod latency;
+pub mod live_rate;
+pub mod live_stats;
+pub mod log_viewer;
+pub mod logging;
+pub mod mcp_server;
+pub mod menu_bar;
+pub mod metrics_export;
+pub mod metrics_scheduler;
+pub mod monitor_errors;
+pub mod notifier;
+pub mod oauth_account;
+pub mod onboarding;
+pub mod otlp_export;
+pub mod outliers;
+pub mod overlay;
+pub mod parquet_export;
 pub mod parser;
+#[cfg(feature = "parser-benchmark")]
+pub mod parser_benchmark;
+pub mod permissions;
+pub mod plan_limits;
+pub mod plan_value;
 pub mod pricing;
+pub mod profile;
+pub mod provider_balance;
+pub mod provider_test;
 pub mod provider_tracker;
+pub mod read_only;
+pub mod report;
+pub mod report_scheduler;
+pub mod rpc_server;
+pub mod session_detail;
+pub mod session_tracker;
+pub mod shutdown;
+pub mod snapshot;
+pub mod statusline;
+pub mod streaks;
+pub mod summary_text;
+pub mod sync_scheduler;
+pub mod taskbar;
+pub mod trash;
+pub mod tray;
+pub mod tray_icon;
+pub mod updater;
+pub mod usage_block;
+pub mod webhook;
+pub mod year_summary;
@@ -0,0 +1,97 @@
+//! @file monitor_errors.rs
+//! @description 最近监控错误环形缓冲区，供 get_recent_errors 查询
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+use crate::models::{MonitorError, MonitorErrorCategory};
+use crate::services::health::build_info;
+
+/// 缓冲区默认容量
+pub const DEFAULT_ERROR_CAPACITY: usize = 200;
+
+/// 最近监控错误缓冲区
+///
+/// 超出容量时丢弃最早的错误；内部加锁，可在多个线程间共享
+pub struct MonitorErrorLog {
+    entries: Mutex<VecDeque<MonitorError>>,
+    capacity: usize,
+}
+
+impl Default for MonitorErrorLog {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_ERROR_CAPACITY)
+    }
+}
+
+impl MonitorErrorLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// 记录一条错误并返回记录内容（用于发送事件）
+    pub fn push(
+        &self,
+        category: MonitorErrorCategory,
+        path: Option<String>,
+        message: impl Into<String>,
+    ) -> MonitorError {
+        let error = MonitorError {
+            category,
+            path,
+            message: message.into(),
+            timestamp: Utc::now().to_rfc3339(),
+            build: build_info(),
+        };
+
+        if let Ok(mut entries) = self.entries.lock() {
+            while entries.len() >= self.capacity {
+                entries.pop_front();
+            }
+            entries.push_back(error.clone());
+        }
+        error
+    }
+
+    /// 最近的错误（从新到旧），最多 limit 条
+    pub fn recent(&self, limit: usize) -> Vec<MonitorError> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_keeps_latest() {
+        let log = MonitorErrorLog::with_capacity(2);
+        log.push(MonitorErrorCategory::Io, None, "first");
+        log.push(
+            MonitorErrorCategory::Parse,
+            Some("/tmp/a.jsonl".to_string()),
+            "second",
+        );
+        let latest = log.push(MonitorErrorCategory::Database, None, "third");
+        assert_eq!(latest.category, MonitorErrorCategory::Database);
+
+        let recent = log.recent(10);
+        let messages: Vec<_> = recent.iter().map(|error| error.message.as_str()).collect();
+        assert_eq!(messages, vec!["third", "second"]);
+        assert_eq!(recent[1].path.as_deref(), Some("/tmp/a.jsonl"));
+        assert_eq!(log.recent(1).len(), 1);
+    }
+}
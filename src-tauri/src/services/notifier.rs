@@ -0,0 +1,47 @@
+//! @file notifier.rs
+//! @description 通知发送服务，统一发送系统通知并将每条通知写入通知历史
+//! @author Atlas.oi
+//! @date 2026-10-17
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::db::Repository;
+use crate::models::{DeliveryChannel, NotificationKind};
+
+/// 发送通知并记录到通知历史
+///
+/// 业务逻辑说明：
+/// 1. system 为 true 时发送系统通知，发送失败时按仅应用内送达记录
+/// 2. 应用内事件由调用方自行发送，payload 为随事件发送的数据
+/// 3. 写入历史失败只记录日志，不影响通知本身
+pub fn notify<T: Serialize>(
+    app: &AppHandle,
+    kind: NotificationKind,
+    title: &str,
+    body: &str,
+    payload: &T,
+    system: bool,
+) {
+    let mut channel = DeliveryChannel::InApp;
+    if system {
+        match app.notification().builder().title(title).body(body).show() {
+            Ok(()) => channel = DeliveryChannel::System,
+            Err(e) => tracing::error!("发送{}通知失败: {}", title, e),
+        }
+    }
+
+    let payload = match serde_json::to_value(payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::error!("序列化通知数据失败: {}", e);
+            serde_json::Value::Null
+        }
+    };
+    if let Err(e) = app
+        .state::<Repository>()
+        .insert_notification(kind, title, body, &payload, channel)
+    {
+        tracing::error!("记录通知历史失败: {}", e);
+    }
+}
@@ -0,0 +1,125 @@
+//! @file oauth_account.rs
+//! @description claude.ai 订阅账号（OAuth 登录）识别服务
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::Path;
+
+use serde_json::Value;
+
+/// 凭据文件中没有账号信息时使用的账号标识
+const UNKNOWN_ACCOUNT_ID: &str = "default";
+
+/// 通过 claude.ai OAuth 登录的订阅账号
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthAccount {
+    /// 账号 UUID
+    pub account_id: String,
+
+    /// 账号邮箱
+    pub email: Option<String>,
+
+    /// 订阅类型（如 pro、max）
+    pub subscription_type: Option<String>,
+}
+
+impl OAuthAccount {
+    /// 订阅供应商的默认显示名称
+    pub fn display_name(&self) -> String {
+        match &self.subscription_type {
+            Some(subscription_type) => format!("Subscription ({})", subscription_type),
+            None => "Subscription".to_string(),
+        }
+    }
+}
+
+/// 检测 Claude Code 是否通过 claude.ai OAuth 登录
+///
+/// 账号信息读取自 ~/.claude.json 的 oauthAccount，订阅类型读取自
+/// ~/.claude/.credentials.json 的 claudeAiOauth（macOS 上凭据保存在钥匙串中，此文件可能不存在）
+pub fn detect_oauth_account(home_dir: &Path) -> Option<OAuthAccount> {
+    let account = std::fs::read_to_string(home_dir.join(".claude.json"))
+        .ok()
+        .and_then(|content| parse_oauth_account(&content));
+    let credentials = std::fs::read_to_string(home_dir.join(".claude").join(".credentials.json"))
+        .ok()
+        .and_then(|content| parse_oauth_credentials(&content));
+
+    match (account, credentials) {
+        (Some(account), subscription_type) => Some(OAuthAccount {
+            subscription_type: subscription_type.flatten(),
+            ..account
+        }),
+        (None, Some(subscription_type)) => Some(OAuthAccount {
+            account_id: UNKNOWN_ACCOUNT_ID.to_string(),
+            email: None,
+            subscription_type,
+        }),
+        (None, None) => None,
+    }
+}
+
+/// 解析 .claude.json 中的 oauthAccount
+pub fn parse_oauth_account(content: &str) -> Option<OAuthAccount> {
+    let value: Value = serde_json::from_str(content).ok()?;
+    let account = value.get("oauthAccount")?;
+
+    Some(OAuthAccount {
+        account_id: account.get("accountUuid")?.as_str()?.to_string(),
+        email: account
+            .get("emailAddress")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string()),
+        subscription_type: None,
+    })
+}
+
+/// 解析 .credentials.json 中的 claudeAiOauth
+///
+/// # 返回
+/// 存在 OAuth 凭据时返回 Some，内层为订阅类型
+pub fn parse_oauth_credentials(content: &str) -> Option<Option<String>> {
+    let value: Value = serde_json::from_str(content).ok()?;
+    let oauth = value.get("claudeAiOauth")?;
+    oauth.get("accessToken")?;
+
+    Some(
+        oauth
+            .get("subscriptionType")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_oauth_account() {
+        let home = std::env::temp_dir().join(format!("ctm-oauth-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".claude")).expect("home");
+        assert_eq!(detect_oauth_account(&home), None);
+
+        std::fs::write(
+            home.join(".claude").join(".credentials.json"),
+            r#"{"claudeAiOauth":{"accessToken":"token","refreshToken":"refresh","subscriptionType":"max"}}"#,
+        )
+        .expect("credentials");
+        assert_eq!(
+            detect_oauth_account(&home).map(|account| account.account_id),
+            Some(UNKNOWN_ACCOUNT_ID.to_string())
+        );
+
+        std::fs::write(
+            home.join(".claude.json"),
+            r#"{"numStartups":3,"oauthAccount":{"accountUuid":"uuid-1","emailAddress":"me@example.com"}}"#,
+        )
+        .expect("claude.json");
+        let account = detect_oauth_account(&home).expect("account");
+        assert_eq!(account.account_id, "uuid-1");
+        assert_eq!(account.email.as_deref(), Some("me@example.com"));
+        assert_eq!(account.display_name(), "Subscription (max)");
+
+        std::fs::remove_dir_all(&home).expect("cleanup");
+    }
+}
@@ -0,0 +1,157 @@
+//! @file onboarding.rs
+//! @description 首次运行引导服务，检测 Claude Code 数据目录、会话文件、供应商与用量数据，确定引导步骤
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::Path;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{OnboardingSettings, OnboardingState, OnboardingStep};
+use crate::services::oauth_account::detect_oauth_account;
+use crate::services::parser::parse_settings;
+
+/// 查找会话文件时的最大目录深度（projects/<项目>/<会话>.jsonl 以及子代理目录）
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// 检测首次运行引导状态
+///
+/// 业务逻辑说明：
+/// 1. 依次检测数据目录、会话文件、供应商与用量数据，确定当前步骤
+/// 2. 首次进入 Ready 时记录引导已完成，之后即使数据被清空也不再显示引导
+pub fn get_onboarding_state(
+    repository: &Repository,
+    home_dir: &Path,
+) -> Result<OnboardingState, RepositoryError> {
+    let claude_dir = home_dir.join(".claude");
+    let claude_dir_found = std::fs::read_dir(&claude_dir)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    let session_files_found = has_jsonl_file(&claude_dir.join("projects"), MAX_SCAN_DEPTH);
+    let provider_detected = !repository.get_all_providers(false)?.is_empty()
+        || has_settings_api_key(&claude_dir)
+        || detect_oauth_account(home_dir).is_some();
+    let has_usage = repository.has_message_usage()?;
+
+    let step = OnboardingStep::from_checks(
+        claude_dir_found,
+        session_files_found,
+        provider_detected,
+        has_usage,
+    );
+
+    let mut settings: OnboardingSettings = repository.get_setting()?;
+    if step == OnboardingStep::Ready && !settings.completed {
+        settings.completed = true;
+        repository.set_setting(&settings)?;
+        tracing::info!("首次运行引导完成");
+    }
+
+    Ok(OnboardingState {
+        step,
+        claude_dir: claude_dir.display().to_string(),
+        claude_dir_found,
+        session_files_found,
+        provider_detected,
+        has_usage,
+        completed: settings.completed,
+    })
+}
+
+/// 标记引导已完成（用户跳过引导时调用）
+pub fn complete_onboarding(repository: &Repository) -> Result<(), RepositoryError> {
+    repository.set_setting(&OnboardingSettings { completed: true })
+}
+
+/// 目录下（含子目录）是否存在 JSONL 文件，找到第一个即返回
+fn has_jsonl_file(dir: &Path, depth: usize) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl"))
+        {
+            return true;
+        }
+    }
+    depth > 1 && subdirs.iter().any(|dir| has_jsonl_file(dir, depth - 1))
+}
+
+fn has_settings_api_key(claude_dir: &Path) -> bool {
+    std::fs::read_to_string(claude_dir.join("settings.json"))
+        .ok()
+        .is_some_and(|content| parse_settings(&content).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    #[test]
+    fn test_onboarding_steps() {
+        let home = std::env::temp_dir().join(format!("ctm-onboarding-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(home.join(".claude")).expect("claude dir");
+        let repository = Repository::new_in_memory().expect("repo");
+
+        let state = get_onboarding_state(&repository, &home).expect("state");
+        assert_eq!(state.step, OnboardingStep::ClaudeDirMissing);
+
+        let project = home
+            .join(".claude")
+            .join("projects")
+            .join("-Users-demo-app");
+        std::fs::create_dir_all(&project).expect("project");
+        let state = get_onboarding_state(&repository, &home).expect("state");
+        assert_eq!(state.step, OnboardingStep::NoSessionFiles);
+        assert!(state.claude_dir_found);
+
+        std::fs::write(project.join("session-1.jsonl"), "").expect("jsonl");
+        let state = get_onboarding_state(&repository, &home).expect("state");
+        assert_eq!(state.step, OnboardingStep::NoProvider);
+
+        std::fs::write(
+            home.join(".claude").join("settings.json"),
+            r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"sk-test"}}"#,
+        )
+        .expect("settings");
+        let state = get_onboarding_state(&repository, &home).expect("state");
+        assert_eq!(state.step, OnboardingStep::Importing);
+        assert!(!state.completed);
+
+        let provider = repository
+            .upsert_provider("sk-test", None)
+            .expect("provider");
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus".to_string(),
+            chrono::Utc::now().to_rfc3339(),
+            MessageUsage::default(),
+        );
+        repository
+            .insert_message_usage(provider.id, &record)
+            .expect("insert");
+        let state = get_onboarding_state(&repository, &home).expect("state");
+        assert_eq!(state.step, OnboardingStep::Ready);
+        assert!(state.completed);
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_complete_onboarding() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let home = std::env::temp_dir().join(format!("ctm-onboarding-skip-{}", std::process::id()));
+        complete_onboarding(&repository).expect("complete");
+        let state = get_onboarding_state(&repository, &home).expect("state");
+        assert_eq!(state.step, OnboardingStep::ClaudeDirMissing);
+        assert!(state.completed);
+    }
+}
@@ -0,0 +1,230 @@
+//! @file otlp_export.rs
+//! @description OpenTelemetry 指标推送，将用量以 OTLP/HTTP JSON 格式发送到 Collector
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{CacheHitRateFormula, IntervalUsage, OtlpExportSettings};
+use crate::services::metrics_export::post_with_retry;
+
+/// 累计计数（Sum）的聚合时间性：CUMULATIVE
+const AGGREGATION_TEMPORALITY_CUMULATIVE: u8 = 2;
+
+/// 查询自 start 起的累计用量并生成 OTLP 指标请求体
+///
+/// 计数器采用累计时间性，推送失败不会丢失数据，下一次推送自然补上
+pub fn collect_metrics(
+    repository: &Repository,
+    settings: &OtlpExportSettings,
+    formula: CacheHitRateFormula,
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<Value, RepositoryError> {
+    let usages = repository.get_interval_usage(&start.to_rfc3339(), &now.to_rfc3339())?;
+    Ok(build_metrics_request(
+        &usages,
+        formula,
+        &settings.service_name,
+        start.timestamp_nanos_opt().unwrap_or_default(),
+        now.timestamp_nanos_opt().unwrap_or_default(),
+    ))
+}
+
+/// 生成 ExportMetricsServiceRequest
+///
+/// 指标：
+/// - `claude.tokens` 累计 token 数，按 type（input / output / cache_read / cache_creation）区分
+/// - `claude.cost` 累计费用（美元）
+/// - `claude.messages` 累计消息数
+/// - `claude.cache_hit_rate` 缓存命中率（0 - 1）
+///
+/// 所有数据点都带 provider 与 model 属性
+pub fn build_metrics_request(
+    usages: &[IntervalUsage],
+    formula: CacheHitRateFormula,
+    service_name: &str,
+    start_ns: i64,
+    now_ns: i64,
+) -> Value {
+    let mut token_points = Vec::new();
+    let mut cost_points = Vec::new();
+    let mut message_points = Vec::new();
+    let mut hit_rate_points = Vec::new();
+
+    for usage in usages {
+        let attributes = vec![
+            string_attribute("provider", &usage.provider),
+            string_attribute("model", &usage.model),
+        ];
+        for (kind, value) in [
+            ("input", usage.input_tokens),
+            ("output", usage.output_tokens),
+            ("cache_read", usage.cache_read_tokens),
+            ("cache_creation", usage.cache_creation_tokens),
+        ] {
+            let mut attributes = attributes.clone();
+            attributes.push(string_attribute("type", kind));
+            token_points.push(int_point(attributes, value, start_ns, now_ns));
+        }
+        cost_points.push(double_point(
+            attributes.clone(),
+            usage.cost_usd,
+            Some(start_ns),
+            now_ns,
+        ));
+        message_points.push(int_point(
+            attributes.clone(),
+            usage.message_count,
+            start_ns,
+            now_ns,
+        ));
+        hit_rate_points.push(double_point(
+            attributes,
+            formula.rate(
+                usage.cache_read_tokens,
+                usage.input_tokens,
+                usage.cache_creation_tokens,
+            ),
+            None,
+            now_ns,
+        ));
+    }
+
+    json!({
+        "resourceMetrics": [{
+            "resource": {
+                "attributes": [string_attribute("service.name", service_name)],
+            },
+            "scopeMetrics": [{
+                "scope": { "name": "claude-token-monitor", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": [
+                    sum_metric("claude.tokens", "Tokens used", "{token}", token_points),
+                    sum_metric("claude.cost", "Cost in USD", "USD", cost_points),
+                    sum_metric("claude.messages", "Messages recorded", "{message}", message_points),
+                    {
+                        "name": "claude.cache_hit_rate",
+                        "description": "Cache hit rate",
+                        "unit": "1",
+                        "gauge": { "dataPoints": hit_rate_points },
+                    },
+                ],
+            }],
+        }],
+    })
+}
+
+/// 发送指标请求
+pub fn send_metrics(settings: &OtlpExportSettings, body: &Value) -> Result<(), String> {
+    post_with_retry(
+        || {
+            settings.headers.iter().fold(
+                ureq::post(&settings.endpoint).set("Content-Type", "application/json"),
+                |request, (name, value)| request.set(name, value),
+            )
+        },
+        &body.to_string(),
+    )
+}
+
+fn sum_metric(name: &str, description: &str, unit: &str, data_points: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "unit": unit,
+        "sum": {
+            "dataPoints": data_points,
+            "aggregationTemporality": AGGREGATION_TEMPORALITY_CUMULATIVE,
+            "isMonotonic": true,
+        },
+    })
+}
+
+/// 整数数据点；OTLP JSON 中 64 位整数与时间戳以字符串表示
+fn int_point(attributes: Vec<Value>, value: i64, start_ns: i64, now_ns: i64) -> Value {
+    json!({
+        "attributes": attributes,
+        "startTimeUnixNano": start_ns.to_string(),
+        "timeUnixNano": now_ns.to_string(),
+        "asInt": value.to_string(),
+    })
+}
+
+fn double_point(attributes: Vec<Value>, value: f64, start_ns: Option<i64>, now_ns: i64) -> Value {
+    let mut point = json!({
+        "attributes": attributes,
+        "timeUnixNano": now_ns.to_string(),
+        "asDouble": value,
+    });
+    if let Some(start_ns) = start_ns {
+        point["startTimeUnixNano"] = json!(start_ns.to_string());
+    }
+    point
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_metrics_request() {
+        let usage = IntervalUsage {
+            provider: "Claude".to_string(),
+            model: "claude-3-opus".to_string(),
+            input_tokens: 100,
+            output_tokens: 20,
+            cache_read_tokens: 300,
+            cache_creation_tokens: 0,
+            cost_usd: 0.25,
+            message_count: 2,
+        };
+        let request =
+            build_metrics_request(&[usage], CacheHitRateFormula::Strict, "svc", 1_000, 2_000);
+
+        let resource = &request["resourceMetrics"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "svc"
+        );
+        let metrics = resource["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .expect("metrics");
+        assert_eq!(metrics.len(), 4);
+
+        let tokens = &metrics[0]["sum"];
+        assert_eq!(tokens["aggregationTemporality"], 2);
+        assert_eq!(tokens["isMonotonic"], true);
+        let points = tokens["dataPoints"].as_array().expect("points");
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0]["asInt"], "100");
+        assert_eq!(points[0]["startTimeUnixNano"], "1000");
+        assert_eq!(points[0]["attributes"][2]["value"]["stringValue"], "input");
+
+        assert_eq!(metrics[1]["sum"]["dataPoints"][0]["asDouble"], 0.25);
+        assert_eq!(metrics[2]["sum"]["dataPoints"][0]["asInt"], "2");
+        let hit_rate = &metrics[3]["gauge"]["dataPoints"][0];
+        assert_eq!(hit_rate["asDouble"], 0.75);
+        assert!(hit_rate.get("startTimeUnixNano").is_none());
+    }
+
+    #[test]
+    fn test_collect_metrics_empty() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let now = Utc::now();
+        let request = collect_metrics(
+            &repository,
+            &OtlpExportSettings::default(),
+            CacheHitRateFormula::Strict,
+            now - chrono::Duration::hours(1),
+            now,
+        )
+        .expect("metrics");
+        let metrics = &request["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+        assert_eq!(metrics[0]["sum"]["dataPoints"], json!([]));
+    }
+}
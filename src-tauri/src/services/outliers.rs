@@ -0,0 +1,272 @@
+//! @file outliers.rs
+//! @description 异常大输入消息检测服务，发现新输入 Token 远超中位数的请求（如意外粘贴的大段上下文）
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{OutlierMessage, OutlierReport, OutlierSettings, StoredMessage};
+
+/// 新消息检测时计算中位数回看的天数
+pub const BASELINE_DAYS: i64 = 30;
+
+/// 新输入 Token：输入 + 缓存创建
+///
+/// 缓存读取随会话上下文自然增长，不计入
+pub fn prompt_tokens(message: &StoredMessage) -> i64 {
+    message.input_tokens + message.cache_creation_tokens
+}
+
+/// 获取日期范围内的异常消息，中位数按范围内全部消息计算
+pub fn get_outliers(
+    repository: &Repository,
+    settings: &OutlierSettings,
+    start_date: &str,
+    end_date: &str,
+) -> Result<OutlierReport, RepositoryError> {
+    let messages = repository.get_messages_in_range(start_date, end_date)?;
+    let median = median(messages.iter().map(prompt_tokens).collect()).unwrap_or(0.0);
+    let threshold = threshold(median, settings);
+    let outliers = attach_titles(repository, find_outliers(messages, median, threshold))?;
+
+    Ok(OutlierReport {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        median_prompt_tokens: median,
+        threshold_tokens: threshold,
+        outliers,
+    })
+}
+
+/// 判定阈值：中位数 × 倍数，且不低于最小新输入 Token
+pub fn threshold(median: f64, settings: &OutlierSettings) -> f64 {
+    (median * settings.multiple).max(settings.min_prompt_tokens as f64)
+}
+
+/// 找出超过阈值的消息，按新输入 Token 降序
+pub fn find_outliers(
+    messages: Vec<StoredMessage>,
+    median: f64,
+    threshold: f64,
+) -> Vec<OutlierMessage> {
+    let mut outliers: Vec<OutlierMessage> = messages
+        .into_iter()
+        .filter_map(|message| {
+            let prompt_tokens = prompt_tokens(&message);
+            (prompt_tokens as f64 > threshold).then(|| OutlierMessage {
+                ratio_to_median: if median > 0.0 {
+                    prompt_tokens as f64 / median
+                } else {
+                    0.0
+                },
+                prompt_tokens,
+                session_title: None,
+                message,
+            })
+        })
+        .collect();
+    outliers.sort_by_key(|outlier| std::cmp::Reverse(outlier.prompt_tokens));
+    outliers
+}
+
+/// 中位数，空列表返回 None
+pub fn median(mut values: Vec<i64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) as f64 / 2.0
+    } else {
+        values[mid] as f64
+    })
+}
+
+/// 新消息异常检测状态
+///
+/// 记录已检查到的最大消息记录 ID，每条消息只检查一次；内部加锁，可在多个线程间共享
+#[derive(Default)]
+pub struct OutlierState {
+    last_checked_id: Mutex<Option<i64>>,
+}
+
+impl OutlierState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 清除检查进度（如切换档案后记录 ID 不再可比），下次检查重新从当前最大 ID 开始
+    pub fn reset(&self) {
+        if let Ok(mut last_checked_id) = self.last_checked_id.lock() {
+            *last_checked_id = None;
+        }
+    }
+}
+
+/// 检查上次检查后新写入的消息是否异常
+///
+/// 业务逻辑说明：
+/// 1. 未开启通知时不检查
+/// 2. 首次检查只记录当前最大记录 ID，不对历史消息通知
+/// 3. 中位数按截至 today 的近 BASELINE_DAYS 天消息计算
+pub fn check_new_outliers(
+    repository: &Repository,
+    settings: &OutlierSettings,
+    state: &OutlierState,
+    today: NaiveDate,
+) -> Result<Vec<OutlierMessage>, RepositoryError> {
+    if !settings.notify {
+        return Ok(Vec::new());
+    }
+    let Ok(mut last_checked_id) = state.last_checked_id.lock() else {
+        return Ok(Vec::new());
+    };
+    let Some(after_id) = *last_checked_id else {
+        *last_checked_id = Some(repository.get_max_message_id()?);
+        return Ok(Vec::new());
+    };
+
+    let messages = repository.get_messages_after_id(after_id)?;
+    let Some(last) = messages.last() else {
+        return Ok(Vec::new());
+    };
+    *last_checked_id = Some(last.id);
+
+    let start = today - Duration::days(BASELINE_DAYS - 1);
+    let baseline = repository.get_messages_in_range(&start.to_string(), &today.to_string())?;
+    let median = median(baseline.iter().map(prompt_tokens).collect()).unwrap_or(0.0);
+    attach_titles(
+        repository,
+        find_outliers(messages, median, threshold(median, settings)),
+    )
+}
+
+/// 通知文本
+pub fn outlier_message(outlier: &OutlierMessage) -> String {
+    let session = outlier
+        .session_title
+        .as_deref()
+        .unwrap_or(&outlier.message.session_id);
+    format!(
+        "会话「{}」中的一条消息输入 {} Token，约为平时的 {:.0} 倍",
+        session, outlier.prompt_tokens, outlier.ratio_to_median
+    )
+}
+
+fn attach_titles(
+    repository: &Repository,
+    mut outliers: Vec<OutlierMessage>,
+) -> Result<Vec<OutlierMessage>, RepositoryError> {
+    let mut titles: HashMap<String, Option<String>> = HashMap::new();
+    for outlier in &mut outliers {
+        let session_id = &outlier.message.session_id;
+        if !titles.contains_key(session_id) {
+            let title = repository
+                .get_session_title(session_id)?
+                .map(|(title, _)| title);
+            titles.insert(session_id.clone(), title);
+        }
+        outlier.session_title = titles[session_id].clone();
+    }
+    Ok(outliers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage, SessionTitleSource};
+
+    fn insert(repository: &Repository, provider_id: i64, index: usize, input_tokens: i64) {
+        let record = MessageRecord::new(
+            format!("session-{}", index % 2),
+            format!("message-{}", index),
+            "claude-3-sonnet".to_string(),
+            chrono::Local::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens,
+                cache_read_tokens: 500_000,
+                ..Default::default()
+            },
+        );
+        repository
+            .insert_message_usage(provider_id, &record)
+            .expect("insert");
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![3, 1, 2]), Some(2.0));
+        assert_eq!(median(vec![4, 1, 3, 2]), Some(2.5));
+    }
+
+    #[test]
+    fn test_get_outliers() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-outliers", None)
+            .expect("provider");
+        for (index, input_tokens) in [8_000, 10_000, 12_000, 150_000, 40_000].iter().enumerate() {
+            insert(&repository, provider.id, index, *input_tokens);
+        }
+        repository
+            .upsert_session_title("session-1", "Paste logs", SessionTitleSource::Summary)
+            .expect("title");
+
+        let today = chrono::Local::now().date_naive().to_string();
+        let report = get_outliers(&repository, &OutlierSettings::default(), &today, &today)
+            .expect("outliers");
+        assert_eq!(report.median_prompt_tokens, 12_000.0);
+        assert_eq!(report.threshold_tokens, 60_000.0);
+        assert_eq!(report.outliers.len(), 1);
+        let outlier = &report.outliers[0];
+        assert_eq!(outlier.message.message_id, "message-3");
+        assert_eq!(outlier.session_title.as_deref(), Some("Paste logs"));
+        assert!((outlier.ratio_to_median - 12.5).abs() < 1e-9);
+
+        let settings = OutlierSettings {
+            multiple: 3.0,
+            ..Default::default()
+        };
+        let report = get_outliers(&repository, &settings, &today, &today).expect("outliers");
+        assert_eq!(report.outliers.len(), 2);
+    }
+
+    #[test]
+    fn test_check_new_outliers() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-outliers", None)
+            .expect("provider");
+        let settings = OutlierSettings {
+            notify: true,
+            ..Default::default()
+        };
+        let state = OutlierState::new();
+        let today = chrono::Local::now().date_naive();
+        for index in 0..5 {
+            insert(&repository, provider.id, index, 10_000);
+        }
+        insert(&repository, provider.id, 5, 200_000);
+
+        // 首次检查不对已有消息通知
+        assert!(check_new_outliers(&repository, &settings, &state, today)
+            .expect("check")
+            .is_empty());
+
+        insert(&repository, provider.id, 6, 300_000);
+        insert(&repository, provider.id, 7, 9_000);
+        let outliers = check_new_outliers(&repository, &settings, &state, today).expect("check");
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].message.message_id, "message-6");
+        assert!(outlier_message(&outliers[0]).contains("300000 Token"));
+
+        assert!(check_new_outliers(&repository, &settings, &state, today)
+            .expect("check")
+            .is_empty());
+    }
+}
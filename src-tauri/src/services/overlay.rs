@@ -0,0 +1,69 @@
+//! @file overlay.rs
+//! @description 迷你悬浮窗服务，通过全局快捷键切换置顶小窗口
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::models::OverlaySettings;
+
+/// 悬浮窗窗口标识，需与 capabilities 中的 windows 保持一致
+pub const OVERLAY_WINDOW_LABEL: &str = "overlay";
+
+/// 悬浮窗前端路由
+const OVERLAY_URL: &str = "index.html#/overlay";
+
+/// 悬浮窗尺寸（逻辑像素）
+const OVERLAY_WIDTH: f64 = 260.0;
+const OVERLAY_HEIGHT: f64 = 112.0;
+
+/// 切换悬浮窗显示状态
+///
+/// 首次调用时创建窗口，之后在显示与隐藏之间切换，避免重复加载前端
+pub fn toggle_overlay(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(OVERLAY_WINDOW_LABEL) {
+        if window.is_visible()? {
+            window.hide()?;
+        } else {
+            window.show()?;
+            window.set_focus()?;
+        }
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(
+        app,
+        OVERLAY_WINDOW_LABEL,
+        WebviewUrl::App(OVERLAY_URL.into()),
+    )
+    .title("Claude Token Monitor")
+    .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .build()?;
+    Ok(())
+}
+
+/// 按设置注册悬浮窗全局快捷键
+///
+/// 悬浮窗是唯一的全局快捷键，注册前会先清除已注册的快捷键
+pub fn register_overlay_shortcut(
+    app: &AppHandle,
+    settings: &OverlaySettings,
+) -> Result<(), tauri_plugin_global_shortcut::Error> {
+    let global_shortcut = app.global_shortcut();
+    global_shortcut.unregister_all()?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    global_shortcut.on_shortcut(settings.shortcut.as_str(), |app, _shortcut, event| {
+        if event.state() == ShortcutState::Pressed {
+            if let Err(e) = toggle_overlay(app) {
+                tracing::error!("切换悬浮窗失败: {}", e);
+            }
+        }
+    })
+}
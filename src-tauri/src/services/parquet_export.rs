@@ -0,0 +1,186 @@
+//! @file parquet_export.rs
+//! @description 消息级用量 Parquet 导出服务，便于在 DuckDB / pandas 中直接分析
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{ParquetExport, StoredMessage};
+
+#[derive(Error, Debug)]
+pub enum ParquetExportError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+}
+
+/// 导出文件的列定义，created_at 写为 UTC 微秒时间戳
+pub fn message_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("provider_id", DataType::Int64, false),
+        Field::new("session_id", DataType::Utf8, false),
+        Field::new("message_id", DataType::Utf8, false),
+        Field::new("model", DataType::Utf8, false),
+        Field::new("project", DataType::Utf8, true),
+        Field::new("input_tokens", DataType::Int64, false),
+        Field::new("output_tokens", DataType::Int64, false),
+        Field::new("cache_read_tokens", DataType::Int64, false),
+        Field::new("cache_creation_tokens", DataType::Int64, false),
+        Field::new("cost_usd", DataType::Float64, false),
+        Field::new(
+            "created_at",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            true,
+        ),
+    ])
+}
+
+/// 将消息转换为单个 RecordBatch，无法解析的 created_at 写为 null
+pub fn build_record_batch(messages: &[StoredMessage]) -> Result<RecordBatch, ArrowError> {
+    let created_at: Vec<Option<i64>> = messages
+        .iter()
+        .map(|message| {
+            chrono::DateTime::parse_from_rfc3339(&message.created_at)
+                .ok()
+                .map(|parsed| parsed.timestamp_micros())
+        })
+        .collect();
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from_iter_values(messages.iter().map(|m| m.id))),
+        Arc::new(Int64Array::from_iter_values(
+            messages.iter().map(|m| m.provider_id),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            messages.iter().map(|m| m.session_id.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            messages.iter().map(|m| m.message_id.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            messages.iter().map(|m| m.model.as_str()),
+        )),
+        Arc::new(StringArray::from(
+            messages
+                .iter()
+                .map(|m| m.project.as_deref())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            messages.iter().map(|m| m.input_tokens),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            messages.iter().map(|m| m.output_tokens),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            messages.iter().map(|m| m.cache_read_tokens),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            messages.iter().map(|m| m.cache_creation_tokens),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            messages.iter().map(|m| m.cost_usd),
+        )),
+        Arc::new(TimestampMicrosecondArray::from(created_at).with_timezone("UTC")),
+    ];
+
+    RecordBatch::try_new(Arc::new(message_schema()), columns)
+}
+
+/// 导出日期范围内的消息级用量为 Parquet 文件（Snappy 压缩）
+pub fn export_parquet(
+    repository: &Repository,
+    start_date: &str,
+    end_date: &str,
+    path: &Path,
+) -> Result<ParquetExport, ParquetExportError> {
+    let messages = repository.get_messages_in_range(start_date, end_date)?;
+    let batch = build_record_batch(&messages)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(ParquetExport {
+        path: path.display().to_string(),
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        row_count: messages.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn test_export_parquet_round_trip() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let provider = repo.upsert_provider("sk-test", None).expect("provider");
+        let now = chrono::Local::now();
+
+        for (message_id, project) in [("m1", Some("/work/a")), ("m2", None)] {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                message_id.to_string(),
+                "claude-3-opus".to_string(),
+                now.to_rfc3339(),
+                MessageUsage {
+                    input_tokens: 100,
+                    output_tokens: 10,
+                    cost_usd: 1.0,
+                    ..Default::default()
+                },
+            )
+            .with_project(project.map(|p| p.to_string()));
+            repo.insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let date = now.date_naive().to_string();
+        let path = std::env::temp_dir()
+            .join(format!("ctm-parquet-{}", std::process::id()))
+            .join("usage.parquet");
+        let export = export_parquet(&repo, &date, &date, &path).expect("export");
+        assert_eq!(export.row_count, 2);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).expect("open"))
+            .expect("reader")
+            .build()
+            .expect("build");
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.expect("batch")).collect();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+        assert_eq!(batches[0].num_columns(), message_schema().fields().len());
+        assert_eq!(batches[0].column(5).null_count(), 1);
+        assert_eq!(batches[0].column(11).null_count(), 0);
+
+        let _ = std::fs::remove_dir_all(path.parent().expect("parent"));
+    }
+}
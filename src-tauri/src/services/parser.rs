@@ -7,7 +7,19 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
 
-use crate::models::{MessageRecord, MessageUsage};
+use crate::models::{
+    ApiErrorEvent, ApiErrorKind, CompactionEvent, CompactionTrigger, InterruptEvent, InterruptKind,
+    MessageRecord, MessageUsage, RateLimitEvent, ServiceTier, SessionTitleSource, ToolCall,
+};
+
+/// 估算思考 Token 时每个 Token 对应的平均字符数
+const THINKING_CHARS_PER_TOKEN: usize = 4;
+
+/// 用户中断时 Claude Code 写入的提示文本前缀
+const INTERRUPT_MARKER: &str = "[Request interrupted by user";
+
+/// 由首条用户输入生成的标题最大字符数
+const MAX_PROMPT_TITLE_CHARS: usize = 80;
 
 #[derive(Error, Debug)]
 pub enum ParserError {
@@ -23,6 +35,19 @@ pub struct Settings {
     pub base_url: Option<String>,
 }
 
+/// JSONL 中解析出的会话标题候选
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionTitleEntry {
+    /// 会话 ID，summary 条目不携带时为 None，由调用方根据文件名补全
+    pub session_id: Option<String>,
+
+    /// 标题文本
+    pub title: String,
+
+    /// 标题来源
+    pub source: SessionTitleSource,
+}
+
 /// 解析 settings.json 内容
 pub fn parse_settings(content: &str) -> Result<Settings, ParserError> {
     let value: Value = serde_json::from_str(content)?;
@@ -60,21 +85,25 @@ pub fn parse_settings(content: &str) -> Result<Settings, ParserError> {
 /// 解析单行 JSONL 消息记录
 pub fn parse_jsonl_line(line: &str) -> Result<Option<MessageRecord>, ParserError> {
     let value: Value = serde_json::from_str(line)?;
+    Ok(message_from_value(&value))
+}
 
-    let model = extract_string(&value, &["model", "message.model"]);
-    let message_id = extract_string(&value, &["id", "message.id"]);
+/// 同 [`parse_jsonl_line`]，用于已解析为 JSON 的行
+pub fn message_from_value(value: &Value) -> Option<MessageRecord> {
+    let model = extract_string(value, &["model", "message.model"]);
+    let message_id = extract_string(value, &["id", "message.id"]);
 
     if model.is_none() || message_id.is_none() {
-        return Ok(None);
+        return None;
     }
 
     let session_id = extract_string(
-        &value,
+        value,
         &["session_id", "sessionId", "conversation_id", "chat_id"],
     )
     .unwrap_or_else(|| "unknown".to_string());
 
-    let created_at = extract_string(&value, &["created_at", "timestamp", "message.created_at"])
+    let created_at = extract_string(value, &["created_at", "timestamp", "message.created_at"])
         .unwrap_or_else(|| Utc::now().to_rfc3339());
 
     let usage_value = value
@@ -98,16 +127,356 @@ pub fn parse_jsonl_line(line: &str) -> Result<Option<MessageRecord>, ParserError
             &usage_value,
             &["cache_creation_tokens", "cache_creation_input_tokens"],
         ),
+        thinking_tokens: 0,
         cost_usd: extract_f64(&usage_value, &["cost_usd", "total_cost_usd"]),
     };
+    let usage = MessageUsage {
+        thinking_tokens: thinking_tokens(value, &usage_value).min(usage.output_tokens),
+        ..usage
+    };
+
+    let project = extract_string(value, &["cwd", "project"]);
+    let duration_ms =
+        extract_optional_i64(value, &["durationMs", "duration_ms", "message.duration_ms"])
+            .filter(|duration| *duration >= 0);
+    let service_tier = extract_string(&usage_value, &["service_tier"])
+        .or_else(|| extract_string(value, &["service_tier", "message.service_tier"]))
+        .map(|tier| ServiceTier::from_db(&tier))
+        .unwrap_or_default();
+
+    Some(
+        MessageRecord::new(
+            session_id,
+            message_id.unwrap_or_else(|| "unknown".to_string()),
+            model.unwrap_or_else(|| "unknown".to_string()),
+            created_at,
+            usage,
+        )
+        .with_project(project)
+        .with_duration_ms(duration_ms)
+        .with_service_tier(service_tier),
+    )
+}
+
+/// 提取思考 Token，结果不超过输出 Token
+///
+/// 业务逻辑：
+/// 1. usage 中带思考 / 推理 Token 字段时直接使用（兼容 OpenAI 风格的 reasoning_tokens）
+/// 2. 否则按 message.content 中 thinking 块的文本长度估算（约 4 字符 1 Token）
+/// 3. 都没有时为 0
+fn thinking_tokens(value: &Value, usage_value: &Value) -> i64 {
+    if let Some(tokens) = extract_optional_i64(
+        usage_value,
+        &[
+            "thinking_tokens",
+            "reasoning_tokens",
+            "output_tokens_details.thinking_tokens",
+            "output_tokens_details.reasoning_tokens",
+            "completion_tokens_details.reasoning_tokens",
+        ],
+    ) {
+        return tokens.max(0);
+    }
+
+    let Some(Value::Array(blocks)) = get_by_path(value, "message.content") else {
+        return 0;
+    };
+    let chars: usize = blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(|v| v.as_str()) == Some("thinking"))
+        .filter_map(|block| block.get("thinking").and_then(|v| v.as_str()))
+        .map(|text| text.chars().count())
+        .sum();
+    chars.div_ceil(THINKING_CHARS_PER_TOKEN) as i64
+}
+
+/// 解析单行 JSONL 中的 API 错误（状态码错误、过载、超时等）
+///
+/// 业务逻辑：
+/// 1. 带 error 对象的条目（如重试中的 system 条目）从 error 中读取类型、状态码与信息
+/// 2. `isApiErrorMessage = true` 的条目从错误文本（如 "API Error: 529 {...}"）中识别
+/// 3. 条目带 retryAttempt / retryInMs 字段时视为重试过程中的错误
+/// 4. 其余行返回 None
+pub fn parse_api_error(line: &str) -> Result<Option<ApiErrorEvent>, ParserError> {
+    let value: Value = serde_json::from_str(line)?;
+    Ok(api_error_from_value(&value))
+}
+
+/// 同 [`parse_api_error`]，用于已解析为 JSON 的行
+pub fn api_error_from_value(value: &Value) -> Option<ApiErrorEvent> {
+    let (error_type, status_code, message) = if let Some(error) = value.get("error") {
+        let error_type = extract_string(error, &["type", "error.type"]);
+        let status_code = extract_optional_i64(error, &["status", "status_code"])
+            .or_else(|| extract_optional_i64(value, &["status", "status_code"]));
+        let message = extract_string(error, &["message", "error.message"])
+            .or_else(|| error.as_str().map(|text| text.to_string()))
+            .unwrap_or_default();
+        (error_type, status_code, message)
+    } else if value.get("isApiErrorMessage").and_then(|v| v.as_bool()) == Some(true) {
+        let message = match get_by_path(value, "message.content") {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Array(blocks)) => blocks
+                .iter()
+                .find_map(|block| block.get("text").and_then(|v| v.as_str()))
+                .unwrap_or_default()
+                .to_string(),
+            _ => String::new(),
+        };
+        (None, status_code_from_text(&message), message)
+    } else {
+        return None;
+    };
+
+    let is_retry = ["retryAttempt", "retryInMs", "retry_attempt"]
+        .iter()
+        .any(|key| value.get(key).is_some());
+
+    Some(ApiErrorEvent {
+        session_id: extract_string(value, &["session_id", "sessionId"])
+            .unwrap_or_else(|| "unknown".to_string()),
+        kind: classify_api_error(error_type.as_deref(), status_code, &message),
+        status_code,
+        message: normalize_title(&message),
+        is_retry,
+        occurred_at: extract_string(value, &["timestamp", "created_at"])
+            .unwrap_or_else(|| Utc::now().to_rfc3339()),
+    })
+}
+
+/// 解析单行 JSONL 中的限流 / 过载错误，其他 API 错误返回 None
+pub fn parse_rate_limit_event(line: &str) -> Result<Option<RateLimitEvent>, ParserError> {
+    Ok(parse_api_error(line)?.and_then(|error| error.rate_limit_event()))
+}
+
+/// 解析单行 JSONL 中的上下文压缩事件
+///
+/// 会话被压缩时 Claude Code 写入 `type = system, subtype = compact_boundary` 条目，
+/// compactMetadata 中带有触发方式与压缩前的 Token 数；其余行返回 None
+pub fn parse_compaction_event(line: &str) -> Result<Option<CompactionEvent>, ParserError> {
+    let value: Value = serde_json::from_str(line)?;
+    Ok(compaction_event_from_value(&value))
+}
+
+/// 同 [`parse_compaction_event`]，用于已解析为 JSON 的行
+pub fn compaction_event_from_value(value: &Value) -> Option<CompactionEvent> {
+    if value.get("type").and_then(|v| v.as_str()) != Some("system")
+        || value.get("subtype").and_then(|v| v.as_str()) != Some("compact_boundary")
+    {
+        return None;
+    }
+
+    let metadata = value
+        .get("compactMetadata")
+        .or_else(|| value.get("compact_metadata"))
+        .cloned()
+        .unwrap_or(Value::Null);
+
+    Some(CompactionEvent {
+        session_id: extract_string(value, &["session_id", "sessionId"])
+            .unwrap_or_else(|| "unknown".to_string()),
+        trigger: extract_string(&metadata, &["trigger"])
+            .map(|trigger| CompactionTrigger::from_db(&trigger))
+            .unwrap_or(CompactionTrigger::Auto),
+        pre_tokens: extract_optional_i64(&metadata, &["preTokens", "pre_tokens"]),
+        occurred_at: extract_string(value, &["timestamp", "created_at"])
+            .unwrap_or_else(|| Utc::now().to_rfc3339()),
+    })
+}
+
+/// 解析单行 JSONL 中 assistant 消息的工具调用
+///
+/// 取 message.content 中全部 `type = tool_use` 块，缺少块 ID 或工具名称的块跳过；
+/// 非 assistant 行或不含工具调用时返回空列表
+pub fn parse_tool_calls(line: &str) -> Result<Vec<ToolCall>, ParserError> {
+    let value: Value = serde_json::from_str(line)?;
+    Ok(tool_calls_from_value(&value))
+}
+
+/// 同 [`parse_tool_calls`]，用于已解析为 JSON 的行
+pub fn tool_calls_from_value(value: &Value) -> Vec<ToolCall> {
+    if value.get("type").and_then(|v| v.as_str()) != Some("assistant") {
+        return Vec::new();
+    }
+    let Some(Value::Array(blocks)) = get_by_path(value, "message.content") else {
+        return Vec::new();
+    };
+
+    let session_id = extract_string(value, &["session_id", "sessionId"])
+        .unwrap_or_else(|| "unknown".to_string());
+    let message_id = extract_string(value, &["message.id", "uuid"]).unwrap_or_default();
+    let called_at = extract_string(value, &["timestamp", "created_at"])
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+
+    blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(|v| v.as_str()) == Some("tool_use"))
+        .filter_map(|block| {
+            Some(ToolCall {
+                session_id: session_id.clone(),
+                message_id: message_id.clone(),
+                tool_use_id: extract_string(block, &["id"])?,
+                tool_name: extract_string(block, &["name"])?,
+                called_at: called_at.clone(),
+            })
+        })
+        .collect()
+}
+
+/// 解析单行 JSONL 中的用户中断
+///
+/// 用户中断生成或拒绝工具调用时，Claude Code 写入一条文本为
+/// "[Request interrupted by user]" 或 "[Request interrupted by user for tool use]" 的 user 条目；
+/// 其余行返回 None
+pub fn parse_interrupt(line: &str) -> Result<Option<InterruptEvent>, ParserError> {
+    let value: Value = serde_json::from_str(line)?;
+    Ok(interrupt_from_value(&value))
+}
+
+/// 同 [`parse_interrupt`]，用于已解析为 JSON 的行
+pub fn interrupt_from_value(value: &Value) -> Option<InterruptEvent> {
+    if value.get("type").and_then(|v| v.as_str()) != Some("user") {
+        return None;
+    }
+
+    let text = match get_by_path(value, "message.content") {
+        Some(Value::String(text)) => Some(text.as_str()),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(|v| v.as_str()) == Some("text"))
+            .find_map(|block| block.get("text").and_then(|v| v.as_str())),
+        _ => None,
+    };
+    let text = text
+        .map(str::trim)
+        .filter(|text| text.starts_with(INTERRUPT_MARKER))?;
+
+    Some(InterruptEvent {
+        session_id: extract_string(value, &["session_id", "sessionId"])
+            .unwrap_or_else(|| "unknown".to_string()),
+        kind: if text.contains("for tool use") {
+            InterruptKind::ToolUse
+        } else {
+            InterruptKind::User
+        },
+        occurred_at: extract_string(value, &["timestamp", "created_at"])
+            .unwrap_or_else(|| Utc::now().to_rfc3339()),
+    })
+}
+
+/// 根据错误类型、状态码与错误文本判断错误类别
+///
+/// 错误类型与文本中的关键字优先于状态码，避免中转服务返回非标准状态码时误判
+fn classify_api_error(
+    error_type: Option<&str>,
+    status_code: Option<i64>,
+    message: &str,
+) -> ApiErrorKind {
+    let text = format!("{} {}", error_type.unwrap_or_default(), message).to_lowercase();
+    if text.contains("rate_limit") || text.contains("rate limit") {
+        return ApiErrorKind::RateLimited;
+    }
+    if text.contains("overloaded") {
+        return ApiErrorKind::Overloaded;
+    }
+    if text.contains("timeout") || text.contains("timed out") {
+        return ApiErrorKind::Timeout;
+    }
+
+    match status_code {
+        Some(429) => ApiErrorKind::RateLimited,
+        Some(529) => ApiErrorKind::Overloaded,
+        Some(408) | Some(504) => ApiErrorKind::Timeout,
+        Some(code) if (500..600).contains(&code) => ApiErrorKind::Server,
+        Some(code) if (400..500).contains(&code) => ApiErrorKind::Client,
+        _ => ApiErrorKind::Other,
+    }
+}
+
+/// 从 "API Error: 429 ..." 形式的文本中提取状态码
+fn status_code_from_text(text: &str) -> Option<i64> {
+    let rest = text.split("API Error:").nth(1)?.trim_start();
+    let code: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    code.parse().ok()
+}
+
+/// 解析单行 JSONL 中的会话标题候选
+///
+/// 业务逻辑：
+/// 1. `type = summary` 条目取 summary 字段作为标题
+/// 2. `type = user` 条目取首段文本作为标题候选，跳过元信息、斜杠命令与工具结果
+/// 3. 其余行返回 None
+pub fn parse_session_title(line: &str) -> Result<Option<SessionTitleEntry>, ParserError> {
+    let value: Value = serde_json::from_str(line)?;
+    Ok(session_title_from_value(&value))
+}
+
+/// 同 [`parse_session_title`]，用于已解析为 JSON 的行
+pub fn session_title_from_value(value: &Value) -> Option<SessionTitleEntry> {
+    let session_id = extract_string(value, &["session_id", "sessionId"]);
+
+    match value.get("type").and_then(|v| v.as_str()) {
+        Some("summary") => extract_string(value, &["summary"])
+            .map(|summary| normalize_title(&summary))
+            .filter(|title| !title.is_empty())
+            .map(|title| SessionTitleEntry {
+                session_id,
+                title,
+                source: SessionTitleSource::Summary,
+            }),
+        Some("user") => {
+            // 元信息与压缩后自动生成的摘要都不是用户输入
+            if ["isMeta", "isCompactSummary"]
+                .iter()
+                .any(|key| value.get(key).and_then(|v| v.as_bool()) == Some(true))
+            {
+                return None;
+            }
+
+            let text = match get_by_path(value, "message.content") {
+                Some(Value::String(text)) => Some(text.clone()),
+                // 数组形式只取 text 块，tool_result 等块不视为用户输入
+                Some(Value::Array(blocks)) => blocks
+                    .iter()
+                    .filter(|block| block.get("type").and_then(|v| v.as_str()) == Some("text"))
+                    .find_map(|block| block.get("text").and_then(|v| v.as_str()))
+                    .map(|text| text.to_string()),
+                _ => None,
+            };
 
-    Ok(Some(MessageRecord::new(
-        session_id,
-        message_id.unwrap_or_else(|| "unknown".to_string()),
-        model.unwrap_or_else(|| "unknown".to_string()),
-        created_at,
-        usage,
-    )))
+            text.filter(|text| !is_command_prompt(text))
+                .map(|text| truncate_title(&normalize_title(&text)))
+                .filter(|title| !title.is_empty())
+                .map(|title| SessionTitleEntry {
+                    session_id,
+                    title,
+                    source: SessionTitleSource::Prompt,
+                })
+        }
+        _ => None,
+    }
+}
+
+/// 判断是否为斜杠命令或命令输出等非自然语言输入
+fn is_command_prompt(text: &str) -> bool {
+    let text = text.trim_start();
+    text.starts_with('/')
+        || text.starts_with("<command-")
+        || text.starts_with("<local-command-")
+        || text.starts_with("Caveat:")
+}
+
+/// 合并连续空白字符为单个空格
+fn normalize_title(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 按字符截断标题，超出部分以省略号表示
+fn truncate_title(title: &str) -> String {
+    if title.chars().count() <= MAX_PROMPT_TITLE_CHARS {
+        return title.to_string();
+    }
+    let truncated: String = title.chars().take(MAX_PROMPT_TITLE_CHARS).collect();
+    format!("{}…", truncated.trim_end())
 }
 
 fn extract_string(value: &Value, paths: &[&str]) -> Option<String> {
@@ -135,6 +504,12 @@ fn extract_i64(value: &Value, paths: &[&str]) -> i64 {
     0
 }
 
+fn extract_optional_i64(value: &Value, paths: &[&str]) -> Option<i64> {
+    paths
+        .iter()
+        .find_map(|path| get_by_path(value, path).and_then(|v| v.as_i64()))
+}
+
 fn extract_f64(value: &Value, paths: &[&str]) -> f64 {
     for path in paths {
         if let Some(v) = get_by_path(value, path) {
@@ -179,5 +554,204 @@ mod tests {
         assert_eq!(record.message_id, "msg_1");
         assert_eq!(record.model, "claude-3");
         assert_eq!(record.usage.input_tokens, 10);
+        assert_eq!(record.project, None);
+        assert_eq!(record.duration_ms, None);
+    }
+
+    #[test]
+    fn test_parse_jsonl_line_thinking_tokens() {
+        let line = r#"{"type":"assistant","sessionId":"sess_1","message":{"id":"msg_1","model":"claude-opus-4","content":[{"type":"thinking","thinking":"abcdefghij","signature":"sig"},{"type":"text","text":"Done."}],"usage":{"input_tokens":10,"output_tokens":50}}}"#;
+        let record = parse_jsonl_line(line).expect("parse line").expect("record");
+        assert_eq!(record.usage.thinking_tokens, 3);
+
+        let line = r#"{"id":"msg_2","session_id":"sess_1","model":"o3","usage":{"prompt_tokens":10,"completion_tokens":80,"completion_tokens_details":{"reasoning_tokens":64}}}"#;
+        let record = parse_jsonl_line(line).expect("parse line").expect("record");
+        assert_eq!(record.usage.thinking_tokens, 64);
+
+        // 估算值不超过输出 Token
+        let line = r#"{"type":"assistant","message":{"id":"msg_3","model":"claude-opus-4","content":[{"type":"thinking","thinking":"a long chain of thought"}],"usage":{"output_tokens":2}}}"#;
+        let record = parse_jsonl_line(line).expect("parse line").expect("record");
+        assert_eq!(record.usage.thinking_tokens, 2);
+    }
+
+    #[test]
+    fn test_parse_jsonl_line_service_tier() {
+        let line = r#"{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4","usage":{"input_tokens":10,"output_tokens":5,"service_tier":"batch"}}}"#;
+        let record = parse_jsonl_line(line).expect("parse line").expect("record");
+        assert_eq!(record.service_tier, ServiceTier::Batch);
+
+        let line = r#"{"id":"msg_2","model":"claude-3","usage":{"input_tokens":10}}"#;
+        let record = parse_jsonl_line(line).expect("parse line").expect("record");
+        assert_eq!(record.service_tier, ServiceTier::Standard);
+    }
+
+    #[test]
+    fn test_parse_jsonl_line_with_duration() {
+        let line = r#"{"sessionId":"sess_1","durationMs":1830,"message":{"id":"msg_1","model":"claude-3","usage":{"input_tokens":10,"output_tokens":5}}}"#;
+        let record = parse_jsonl_line(line).expect("parse line").expect("record");
+
+        assert_eq!(record.duration_ms, Some(1830));
+    }
+
+    #[test]
+    fn test_parse_jsonl_line_with_cwd() {
+        let line = r#"{"sessionId":"sess_1","cwd":"/Users/atlas/work/app","message":{"id":"msg_1","model":"claude-3","usage":{"input_tokens":10,"output_tokens":5}}}"#;
+        let record = parse_jsonl_line(line).expect("parse line").expect("record");
+
+        assert_eq!(record.project, Some("/Users/atlas/work/app".to_string()));
+    }
+
+    #[test]
+    fn test_parse_session_title_summary() {
+        let line = r#"{"type":"summary","summary":"Fix  login\nredirect","leafUuid":"uuid-1"}"#;
+        let entry = parse_session_title(line)
+            .expect("parse line")
+            .expect("title");
+
+        assert_eq!(entry.session_id, None);
+        assert_eq!(entry.title, "Fix login redirect");
+        assert_eq!(entry.source, SessionTitleSource::Summary);
+    }
+
+    #[test]
+    fn test_parse_session_title_first_prompt() {
+        let line = r#"{"type":"user","sessionId":"sess_1","message":{"role":"user","content":[{"type":"text","text":"Add a dark mode toggle"}]}}"#;
+        let entry = parse_session_title(line)
+            .expect("parse line")
+            .expect("title");
+
+        assert_eq!(entry.session_id, Some("sess_1".to_string()));
+        assert_eq!(entry.title, "Add a dark mode toggle");
+        assert_eq!(entry.source, SessionTitleSource::Prompt);
+
+        let long_prompt = "a".repeat(200);
+        let line = format!(
+            r#"{{"type":"user","sessionId":"sess_1","message":{{"role":"user","content":"{}"}}}}"#,
+            long_prompt
+        );
+        let entry = parse_session_title(&line)
+            .expect("parse line")
+            .expect("title");
+        assert_eq!(entry.title.chars().count(), MAX_PROMPT_TITLE_CHARS + 1);
+    }
+
+    #[test]
+    fn test_parse_session_title_skips_non_prompts() {
+        for line in [
+            r#"{"type":"user","sessionId":"sess_1","message":{"role":"user","content":[{"type":"tool_result","content":"ok"}]}}"#,
+            r#"{"type":"user","sessionId":"sess_1","isMeta":true,"message":{"role":"user","content":"Caveat: meta"}}"#,
+            r#"{"type":"user","sessionId":"sess_1","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"assistant","sessionId":"sess_1","message":{"role":"assistant","content":"hi"}}"#,
+        ] {
+            assert_eq!(parse_session_title(line).expect("parse line"), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_api_error() {
+        let line = r#"{"type":"assistant","sessionId":"sess_1","isApiErrorMessage":true,"message":{"content":"API Error: 401 invalid x-api-key"}}"#;
+        let error = parse_api_error(line).expect("parse line").expect("error");
+        assert_eq!(error.kind, ApiErrorKind::Client);
+        assert_eq!(error.status_code, Some(401));
+        assert!(!error.is_retry);
+
+        let line = r#"{"type":"system","sessionId":"sess_1","level":"error","retryAttempt":2,"retryInMs":4000,"error":{"message":"Request timed out."}}"#;
+        let error = parse_api_error(line).expect("parse line").expect("error");
+        assert_eq!(error.kind, ApiErrorKind::Timeout);
+        assert_eq!(error.status_code, None);
+        assert!(error.is_retry);
+
+        let line = r#"{"type":"system","sessionId":"sess_1","error":{"status":502,"message":"Bad Gateway"}}"#;
+        let error = parse_api_error(line).expect("parse line").expect("error");
+        assert_eq!(error.kind, ApiErrorKind::Server);
+
+        let line = r#"{"id":"msg_1","session_id":"sess_1","model":"claude-3","usage":{"input_tokens":10}}"#;
+        assert_eq!(parse_api_error(line).expect("parse line"), None);
+    }
+
+    #[test]
+    fn test_parse_compaction_event() {
+        let line = r#"{"type":"system","subtype":"compact_boundary","content":"Conversation compacted","sessionId":"sess_1","timestamp":"2026-10-17T08:00:00.000Z","compactMetadata":{"trigger":"auto","preTokens":155000}}"#;
+        let event = parse_compaction_event(line)
+            .expect("parse line")
+            .expect("event");
+        assert_eq!(event.session_id, "sess_1");
+        assert_eq!(event.trigger, CompactionTrigger::Auto);
+        assert_eq!(event.pre_tokens, Some(155_000));
+        assert_eq!(event.occurred_at, "2026-10-17T08:00:00.000Z");
+
+        let line = r#"{"type":"system","subtype":"compact_boundary","sessionId":"sess_1","compactMetadata":{"trigger":"manual"}}"#;
+        let event = parse_compaction_event(line)
+            .expect("parse line")
+            .expect("event");
+        assert_eq!(event.trigger, CompactionTrigger::Manual);
+        assert_eq!(event.pre_tokens, None);
+
+        let line = r#"{"type":"system","sessionId":"sess_1","error":{"status":502,"message":"Bad Gateway"}}"#;
+        assert_eq!(parse_compaction_event(line).expect("parse line"), None);
+
+        let line = r#"{"type":"user","isCompactSummary":true,"message":{"content":"This session is being continued from a previous conversation."}}"#;
+        assert_eq!(parse_session_title(line).expect("parse line"), None);
+    }
+
+    #[test]
+    fn test_parse_tool_calls() {
+        let line = r#"{"type":"assistant","sessionId":"sess_1","timestamp":"2026-10-17T08:00:00.000Z","message":{"id":"msg_1","model":"claude-sonnet-4","content":[{"type":"text","text":"Let me check."},{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}},{"type":"tool_use","id":"toolu_2","name":"Read","input":{}}],"usage":{"input_tokens":10,"output_tokens":5}}}"#;
+        let calls = parse_tool_calls(line).expect("parse line");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].tool_name, "Bash");
+        assert_eq!(calls[0].tool_use_id, "toolu_1");
+        assert_eq!(calls[0].message_id, "msg_1");
+        assert_eq!(calls[1].tool_name, "Read");
+        assert_eq!(calls[1].called_at, "2026-10-17T08:00:00.000Z");
+
+        let line = r#"{"type":"user","sessionId":"sess_1","message":{"content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"ok"}]}}"#;
+        assert!(parse_tool_calls(line).expect("parse line").is_empty());
+    }
+
+    #[test]
+    fn test_parse_interrupt() {
+        let line = r#"{"type":"user","sessionId":"sess_1","timestamp":"2026-10-17T08:00:00.000Z","message":{"role":"user","content":[{"type":"text","text":"[Request interrupted by user]"}]}}"#;
+        let event = parse_interrupt(line).expect("parse line").expect("event");
+        assert_eq!(event.session_id, "sess_1");
+        assert_eq!(event.kind, InterruptKind::User);
+        assert_eq!(event.occurred_at, "2026-10-17T08:00:00.000Z");
+
+        let line = r#"{"type":"user","sessionId":"sess_1","message":{"role":"user","content":"[Request interrupted by user for tool use]"}}"#;
+        let event = parse_interrupt(line).expect("parse line").expect("event");
+        assert_eq!(event.kind, InterruptKind::ToolUse);
+
+        let line = r#"{"type":"user","sessionId":"sess_1","message":{"role":"user","content":"Why was the request interrupted by user?"}}"#;
+        assert_eq!(parse_interrupt(line).expect("parse line"), None);
+    }
+
+    #[test]
+    fn test_parse_rate_limit_event() {
+        let line = r#"{"type":"assistant","sessionId":"sess_1","timestamp":"2026-10-17T10:00:00Z","isApiErrorMessage":true,"message":{"id":"msg_1","model":"<synthetic>","content":[{"type":"text","text":"API Error: 529 {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}"}]}}"#;
+        let event = parse_rate_limit_event(line)
+            .expect("parse line")
+            .expect("event");
+        assert_eq!(event.kind, crate::models::RateLimitKind::Overloaded);
+        assert_eq!(event.status_code, Some(529));
+        assert_eq!(event.session_id, "sess_1");
+        assert_eq!(event.occurred_at, "2026-10-17T10:00:00Z");
+
+        let line = r#"{"type":"system","sessionId":"sess_1","timestamp":"2026-10-17T10:01:00Z","level":"error","error":{"status":429,"error":{"type":"rate_limit_error","message":"Number of requests has exceeded your rate limit"}}}"#;
+        let event = parse_rate_limit_event(line)
+            .expect("parse line")
+            .expect("event");
+        assert_eq!(event.kind, crate::models::RateLimitKind::RateLimited);
+        assert_eq!(event.status_code, Some(429));
+        assert_eq!(
+            event.message,
+            "Number of requests has exceeded your rate limit"
+        );
+
+        for line in [
+            r#"{"type":"assistant","sessionId":"sess_1","isApiErrorMessage":true,"message":{"content":"API Error: 401 invalid x-api-key"}}"#,
+            r#"{"id":"msg_1","session_id":"sess_1","model":"claude-3","usage":{"input_tokens":10}}"#,
+        ] {
+            assert_eq!(parse_rate_limit_event(line).expect("parse line"), None);
+        }
     }
 }
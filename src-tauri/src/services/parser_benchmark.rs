@@ -0,0 +1,234 @@
+//! @file parser_benchmark.rs
+//! @description 解析器基准测试，对 JSONL 语料逐阶段计时并统计分配，用于验证解析器改动的性能
+//!
+//! 仅在启用 parser-benchmark 特性时编译，依赖 alloc_counter 的计数分配器
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::models::{ParserBenchmarkReport, StageTiming};
+use crate::services::alloc_counter::AllocSnapshot;
+use crate::services::ingest_worker::parse_jsonl_content;
+use crate::services::parser::{
+    api_error_from_value, compaction_event_from_value, interrupt_from_value, message_from_value,
+    session_title_from_value, tool_calls_from_value,
+};
+
+/// 阶段名称：读取文件并按行切分
+const STAGE_READ: &str = "read";
+
+/// 阶段名称：文件监控入库实际使用的整文件解析
+const STAGE_INGEST: &str = "ingest";
+
+/// 对 path（JSONL 文件或包含 JSONL 文件的目录）运行基准测试
+///
+/// 业务逻辑说明：
+/// 1. 读取全部 JSONL 文件（目录按递归查找），记为 read 阶段
+/// 2. 对每个文件运行入库使用的 parse_jsonl_content，记为 ingest 阶段
+/// 3. 再对全部行单独运行 JSON 解码，并对解码结果依次运行各提取函数，便于定位耗时，每个阶段单独计时与统计分配
+/// 4. 每秒行数与数据量按 ingest 阶段的耗时计算
+pub fn benchmark_parser(path: &Path) -> std::io::Result<ParserBenchmarkReport> {
+    let mut files = Vec::new();
+    if path.is_dir() {
+        collect_jsonl_files(path, &mut files)?;
+    } else {
+        files.push(path.to_path_buf());
+    }
+    files.sort();
+
+    let alloc_start = AllocSnapshot::now();
+    let started = Instant::now();
+    let mut contents = Vec::with_capacity(files.len());
+    for file in &files {
+        contents.push(std::fs::read_to_string(file)?);
+    }
+    let lines: Vec<&str> = contents
+        .iter()
+        .flat_map(|content| content.lines())
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let mut stages = vec![stage_timing(
+        STAGE_READ,
+        started.elapsed(),
+        alloc_start.elapsed(),
+        lines.len(),
+    )];
+    let bytes: u64 = contents.iter().map(|content| content.len() as u64).sum();
+
+    let alloc_start = AllocSnapshot::now();
+    let started = Instant::now();
+    for (file, content) in files.iter().zip(&contents) {
+        black_box(parse_jsonl_content(file, content));
+    }
+    let ingest = stage_timing(
+        STAGE_INGEST,
+        started.elapsed(),
+        alloc_start.elapsed(),
+        lines.len(),
+    );
+    let ingest_secs = ingest.total_ms / 1000.0;
+    stages.push(ingest);
+
+    let mut invalid_lines = 0;
+    let mut values = Vec::with_capacity(lines.len());
+    stages.push(run_stage(
+        "json",
+        &lines,
+        |line| match serde_json::from_str::<Value>(line) {
+            Ok(value) => values.push(value),
+            Err(_) => invalid_lines += 1,
+        },
+    ));
+    // 以下阶段只统计从已解码 JSON 中提取数据的开销
+    let mut messages = 0;
+    stages.push(run_stage("message", &values, |value| {
+        if let Some(record) = message_from_value(value) {
+            messages += 1;
+            black_box(record);
+        }
+    }));
+    stages.push(run_stage("api_error", &values, |value| {
+        black_box(api_error_from_value(value));
+    }));
+    stages.push(run_stage("session_title", &values, |value| {
+        black_box(session_title_from_value(value));
+    }));
+    stages.push(run_stage("compaction", &values, |value| {
+        black_box(compaction_event_from_value(value));
+    }));
+    stages.push(run_stage("tool_call", &values, |value| {
+        black_box(tool_calls_from_value(value));
+    }));
+    stages.push(run_stage("interrupt", &values, |value| {
+        black_box(interrupt_from_value(value));
+    }));
+
+    let report = ParserBenchmarkReport {
+        path: path.display().to_string(),
+        files: files.len(),
+        bytes,
+        lines: lines.len(),
+        messages,
+        invalid_lines,
+        total_ms: stages.iter().map(|stage| stage.total_ms).sum(),
+        lines_per_sec: if ingest_secs > 0.0 {
+            lines.len() as f64 / ingest_secs
+        } else {
+            0.0
+        },
+        mb_per_sec: if ingest_secs > 0.0 {
+            bytes as f64 / 1_000_000.0 / ingest_secs
+        } else {
+            0.0
+        },
+        allocations: stages.iter().map(|stage| stage.allocations).sum(),
+        allocated_bytes: stages.iter().map(|stage| stage.allocated_bytes).sum(),
+        stages,
+    };
+
+    tracing::info!(
+        "解析器基准测试完成: {} 行，{:.0} 行/秒，分配 {} 次",
+        report.lines,
+        report.lines_per_sec,
+        report.allocations
+    );
+    Ok(report)
+}
+
+fn run_stage<T, F>(name: &str, items: &[T], mut parse: F) -> StageTiming
+where
+    F: FnMut(&T),
+{
+    let alloc_start = AllocSnapshot::now();
+    let started = Instant::now();
+    for item in items {
+        parse(item);
+    }
+    stage_timing(name, started.elapsed(), alloc_start.elapsed(), items.len())
+}
+
+fn stage_timing(name: &str, elapsed: Duration, allocs: AllocSnapshot, lines: usize) -> StageTiming {
+    let total_ms = elapsed.as_secs_f64() * 1000.0;
+    StageTiming {
+        name: name.to_string(),
+        total_ms,
+        avg_us_per_line: if lines > 0 {
+            total_ms * 1000.0 / lines as f64
+        } else {
+            0.0
+        },
+        allocations: allocs.allocations,
+        allocated_bytes: allocs.bytes,
+    }
+}
+
+fn collect_jsonl_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_jsonl_files(&path, files)?;
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl"))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmark_parser() {
+        let dir = std::env::temp_dir().join(format!("parser-bench-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("project")).expect("dir");
+        std::fs::write(
+            dir.join("project/session.jsonl"),
+            concat!(
+                r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-10-01T10:00:00Z","message":{"id":"m1","model":"claude-3-opus","usage":{"input_tokens":10,"output_tokens":5}}}"#,
+                "\n",
+                r#"{"type":"user","sessionId":"s1","message":{"role":"user","content":"hello"}}"#,
+                "\n\nnot json\n"
+            ),
+        )
+        .expect("write");
+        std::fs::write(dir.join("notes.txt"), "ignored").expect("write");
+
+        let report = benchmark_parser(&dir).expect("report");
+        assert_eq!(report.files, 1);
+        assert_eq!(report.lines, 3);
+        assert_eq!(report.messages, 1);
+        assert_eq!(report.invalid_lines, 1);
+        let names: Vec<_> = report
+            .stages
+            .iter()
+            .map(|stage| stage.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "read",
+                "ingest",
+                "json",
+                "message",
+                "api_error",
+                "session_title",
+                "compaction",
+                "tool_call",
+                "interrupt"
+            ]
+        );
+        assert!(report.stages[1].allocations > 0);
+        assert!(report.stages[2].allocations > 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,223 @@
+//! @file permissions.rs
+//! @description 权限与访问诊断服务，检查监控目录读取权限、通知权限与 macOS 完全磁盘访问
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+
+use crate::models::{PermissionCheck, PermissionKind, PermissionReport, PermissionStatus};
+
+/// macOS 完全磁盘访问设置页
+const MACOS_FULL_DISK_ACCESS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.security?Privacy_AllFiles";
+
+/// macOS 通知设置页
+const MACOS_NOTIFICATIONS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.notifications";
+
+/// Windows 通知设置页
+const WINDOWS_NOTIFICATIONS_URL: &str = "ms-settings:notifications";
+
+/// macOS 受隐私保护的用户目录（相对主目录），其中的文件需要授权才能读取
+const MACOS_PROTECTED_DIRS: [&str; 4] = [
+    "Desktop",
+    "Documents",
+    "Downloads",
+    "Library/Mobile Documents",
+];
+
+/// 汇总各项检查结果
+pub fn build_permission_report(checks: Vec<PermissionCheck>) -> PermissionReport {
+    PermissionReport {
+        action_required: checks.iter().any(|check| check.status.needs_action()),
+        checks,
+        checked_at: Utc::now().to_rfc3339(),
+    }
+}
+
+/// 检查全部监控目录与完全磁盘访问
+pub fn check_directories(watch_dirs: &[PathBuf], home_dir: &Path) -> Vec<PermissionCheck> {
+    let mut checks: Vec<_> = watch_dirs
+        .iter()
+        .map(|dir| check_directory_access(dir))
+        .collect();
+    checks.push(check_full_disk_access(
+        &checks,
+        watch_dirs,
+        home_dir,
+        cfg!(target_os = "macos"),
+    ));
+    checks
+}
+
+/// 检查目录是否可读
+pub fn check_directory_access(dir: &Path) -> PermissionCheck {
+    let (status, message) = match std::fs::read_dir(dir) {
+        Ok(_) => (PermissionStatus::Granted, "目录可读取".to_string()),
+        Err(e) if e.kind() == ErrorKind::NotFound => (
+            PermissionStatus::Missing,
+            "目录不存在，请确认已安装并运行过 Claude Code".to_string(),
+        ),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => (
+            PermissionStatus::Denied,
+            "没有读取权限，请检查目录权限或在系统设置中为应用授权".to_string(),
+        ),
+        Err(e) => (PermissionStatus::Denied, format!("无法读取目录: {}", e)),
+    };
+
+    PermissionCheck {
+        kind: PermissionKind::WatchDirectory,
+        status,
+        target: Some(dir.display().to_string()),
+        message,
+        settings_url: (status == PermissionStatus::Denied && cfg!(target_os = "macos"))
+            .then(|| MACOS_FULL_DISK_ACCESS_URL.to_string()),
+    }
+}
+
+/// 判断是否需要 macOS 完全磁盘访问
+///
+/// 监控目录位于受保护目录（桌面、文稿、下载、iCloud）内时需要授权；
+/// 此时目录读取被拒绝即视为未授权，其他情况不需要
+pub fn check_full_disk_access(
+    directory_checks: &[PermissionCheck],
+    watch_dirs: &[PathBuf],
+    home_dir: &Path,
+    is_macos: bool,
+) -> PermissionCheck {
+    let protected: Vec<&PathBuf> = watch_dirs
+        .iter()
+        .filter(|dir| {
+            MACOS_PROTECTED_DIRS
+                .iter()
+                .any(|protected| dir.starts_with(home_dir.join(protected)))
+        })
+        .collect();
+    let denied = directory_checks
+        .iter()
+        .any(|check| check.status == PermissionStatus::Denied);
+
+    let (status, message) = if !is_macos {
+        (PermissionStatus::NotApplicable, "仅 macOS 需要".to_string())
+    } else if denied {
+        (
+            PermissionStatus::Denied,
+            "监控目录读取被拒绝，请在“隐私与安全性 > 完全磁盘访问权限”中允许本应用".to_string(),
+        )
+    } else if protected.is_empty() {
+        (
+            PermissionStatus::NotApplicable,
+            "监控目录不在受保护位置，不需要完全磁盘访问权限".to_string(),
+        )
+    } else {
+        (
+            PermissionStatus::Granted,
+            "已可读取受保护位置中的监控目录".to_string(),
+        )
+    };
+
+    PermissionCheck {
+        kind: PermissionKind::FullDiskAccess,
+        status,
+        target: protected.first().map(|dir| dir.display().to_string()),
+        message,
+        settings_url: is_macos.then(|| MACOS_FULL_DISK_ACCESS_URL.to_string()),
+    }
+}
+
+/// 根据通知插件返回的权限状态生成检查结果
+pub fn notification_check(status: PermissionStatus) -> PermissionCheck {
+    let message = match status {
+        PermissionStatus::Granted => "已允许系统通知",
+        PermissionStatus::Denied => "系统通知已被关闭，请在系统设置中允许本应用发送通知",
+        PermissionStatus::NotDetermined => "尚未授权系统通知，首次发送通知时系统会询问",
+        PermissionStatus::Missing | PermissionStatus::NotApplicable => "当前平台不需要通知授权",
+    };
+    let settings_url = if cfg!(target_os = "macos") {
+        Some(MACOS_NOTIFICATIONS_URL)
+    } else if cfg!(target_os = "windows") {
+        Some(WINDOWS_NOTIFICATIONS_URL)
+    } else {
+        None
+    };
+
+    PermissionCheck {
+        kind: PermissionKind::Notification,
+        status,
+        target: None,
+        message: message.to_string(),
+        settings_url: settings_url.map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_directory_access() {
+        let dir = std::env::temp_dir().join(format!("ctm-permissions-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("dir");
+
+        let check = check_directory_access(&dir);
+        assert_eq!(check.status, PermissionStatus::Granted);
+        assert!(check.settings_url.is_none());
+
+        let missing = check_directory_access(&dir.join("missing"));
+        assert_eq!(missing.status, PermissionStatus::Missing);
+        assert!(missing.status.needs_action());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_check_full_disk_access() {
+        let home = PathBuf::from("/Users/demo");
+        let protected = vec![home.join("Documents/claude")];
+        let granted = |dir: &PathBuf| PermissionCheck {
+            kind: PermissionKind::WatchDirectory,
+            status: PermissionStatus::Granted,
+            target: Some(dir.display().to_string()),
+            message: String::new(),
+            settings_url: None,
+        };
+
+        let check = check_full_disk_access(&[], &protected, &home, false);
+        assert_eq!(check.status, PermissionStatus::NotApplicable);
+        assert!(check.settings_url.is_none());
+
+        let unprotected = vec![home.join(".claude")];
+        let check = check_full_disk_access(&[granted(&unprotected[0])], &unprotected, &home, true);
+        assert_eq!(check.status, PermissionStatus::NotApplicable);
+
+        let check = check_full_disk_access(&[granted(&protected[0])], &protected, &home, true);
+        assert_eq!(check.status, PermissionStatus::Granted);
+        assert_eq!(
+            check.target.as_deref(),
+            Some("/Users/demo/Documents/claude")
+        );
+
+        let mut denied = granted(&protected[0]);
+        denied.status = PermissionStatus::Denied;
+        let check = check_full_disk_access(&[denied], &protected, &home, true);
+        assert_eq!(check.status, PermissionStatus::Denied);
+        assert_eq!(
+            check.settings_url.as_deref(),
+            Some(MACOS_FULL_DISK_ACCESS_URL)
+        );
+    }
+
+    #[test]
+    fn test_build_permission_report() {
+        let report = build_permission_report(vec![notification_check(PermissionStatus::Granted)]);
+        assert!(!report.action_required);
+
+        let report = build_permission_report(vec![
+            notification_check(PermissionStatus::Granted),
+            notification_check(PermissionStatus::Denied),
+        ]);
+        assert!(report.action_required);
+    }
+}
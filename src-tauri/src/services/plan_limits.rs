@@ -0,0 +1,193 @@
+//! @file plan_limits.rs
+//! @description 订阅每周额度追踪服务，计算当前周期用量与距下次重置的时间
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{PlanLimitSettings, PlanLimitStatus, PlanType, ProviderKind, ProviderPlan};
+
+/// 计算 now 所在的每周周期 [开始, 下次重置)
+///
+/// 周期从最近一次（含当前时刻）的重置时间开始，持续 7 天；非法的星期与小时按周一 0 时处理
+pub fn weekly_window(
+    now: NaiveDateTime,
+    reset_weekday: u32,
+    reset_hour: u32,
+) -> (NaiveDateTime, NaiveDateTime) {
+    let weekday = u8::try_from(reset_weekday)
+        .ok()
+        .and_then(|day| Weekday::try_from(day).ok())
+        .unwrap_or(Weekday::Mon);
+    let reset_time = NaiveTime::from_hms_opt(reset_hour, 0, 0).unwrap_or(NaiveTime::MIN);
+
+    let days_back = (now.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    let mut start = (now.date() - Duration::days(i64::from(days_back))).and_time(reset_time);
+    if start > now {
+        start -= Duration::days(7);
+    }
+    (start, start + Duration::days(7))
+}
+
+/// 计算订阅供应商的每周额度使用情况
+///
+/// 业务逻辑说明：
+/// 1. 套餐优先取用户配置；未配置的订阅账号使用 detected_plan（OAuth 登录信息中的订阅类型）
+/// 2. 只统计订阅套餐（Pro / Max），按量计费的供应商不返回
+/// 3. 未指定 provider_id 时返回所有订阅供应商
+pub fn get_plan_limit_statuses(
+    repository: &Repository,
+    settings: &PlanLimitSettings,
+    detected_plan: Option<PlanType>,
+    provider_id: Option<i64>,
+    now: DateTime<Local>,
+) -> Result<Vec<PlanLimitStatus>, RepositoryError> {
+    let plans = repository.get_provider_plans()?;
+    let (start, reset_at) = weekly_window(
+        now.naive_local(),
+        settings.reset_weekday,
+        settings.reset_hour,
+    );
+    let start = to_local(start);
+    let reset_at = to_local(reset_at);
+
+    let mut statuses = Vec::new();
+    for provider in repository.get_all_providers(false)? {
+        if provider_id.is_some_and(|id| id != provider.id) {
+            continue;
+        }
+        let plan_type = match plans.iter().find(|plan| plan.provider_id == provider.id) {
+            Some(ProviderPlan { plan_type, .. }) => Some(*plan_type),
+            None if provider.kind == ProviderKind::Subscription => detected_plan,
+            None => None,
+        };
+        let Some(plan_type) = plan_type.filter(PlanType::is_subscription) else {
+            continue;
+        };
+
+        let (used_tokens, api_equivalent_cost_usd, message_count) = repository
+            .get_provider_window_usage(provider.id, &start.to_rfc3339(), &reset_at.to_rfc3339())?;
+        let token_limit = match plan_type {
+            PlanType::Pro => settings.pro_weekly_token_limit,
+            PlanType::Max => settings.max_weekly_token_limit,
+            PlanType::Api => None,
+        }
+        .filter(|limit| *limit > 0);
+
+        statuses.push(PlanLimitStatus {
+            provider_id: provider.id,
+            plan_type,
+            window_start: start.to_rfc3339(),
+            reset_at: reset_at.to_rfc3339(),
+            reset_in_minutes: (reset_at - now).num_minutes().max(0),
+            used_tokens,
+            api_equivalent_cost_usd,
+            message_count,
+            token_limit,
+            usage_ratio: token_limit.map(|limit| used_tokens as f64 / limit as f64),
+        });
+    }
+
+    Ok(statuses)
+}
+
+/// 本地时间转换为带时区的时间；夏令时切换导致不存在的时刻向后顺延一小时
+pub(crate) fn to_local(time: NaiveDateTime) -> DateTime<Local> {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .or_else(|| {
+            Local
+                .from_local_datetime(&(time + Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| Local.from_utc_datetime(&time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    fn naive(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").expect("time")
+    }
+
+    #[test]
+    fn test_weekly_window() {
+        // 2026-10-17 为周六
+        let (start, end) = weekly_window(naive("2026-10-17 10:00"), 0, 9);
+        assert_eq!(start, naive("2026-10-12 09:00"));
+        assert_eq!(end, naive("2026-10-19 09:00"));
+
+        // 重置当天、重置时刻之前属于上一周期
+        let (start, _) = weekly_window(naive("2026-10-17 08:00"), 5, 9);
+        assert_eq!(start, naive("2026-10-10 09:00"));
+        let (start, _) = weekly_window(naive("2026-10-17 09:00"), 5, 9);
+        assert_eq!(start, naive("2026-10-17 09:00"));
+
+        // 非法配置按周一 0 时处理
+        let (start, _) = weekly_window(naive("2026-10-17 10:00"), 9, 30);
+        assert_eq!(start, naive("2026-10-12 00:00"));
+    }
+
+    #[test]
+    fn test_plan_limit_statuses() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let api = repository
+            .upsert_provider("sk-api", None)
+            .expect("provider");
+        let subscription = repository
+            .upsert_subscription_provider("uuid-1", "Subscription (max)")
+            .expect("subscription");
+        let now = Local::now();
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "m1".to_string(),
+            "claude-3-opus".to_string(),
+            now.to_rfc3339(),
+            MessageUsage {
+                input_tokens: 600,
+                output_tokens: 400,
+                cost_usd: 2.0,
+                ..Default::default()
+            },
+        );
+        repository
+            .insert_message_usage(subscription.id, &record)
+            .expect("insert");
+
+        let settings = PlanLimitSettings {
+            max_weekly_token_limit: Some(4000),
+            ..Default::default()
+        };
+        let statuses =
+            get_plan_limit_statuses(&repository, &settings, Some(PlanType::Max), None, now)
+                .expect("statuses");
+        assert_eq!(statuses.len(), 1);
+        let status = &statuses[0];
+        assert_eq!(status.provider_id, subscription.id);
+        assert_eq!(status.plan_type, PlanType::Max);
+        assert_eq!(status.used_tokens, 1000);
+        assert_eq!(status.message_count, 1);
+        assert!((status.api_equivalent_cost_usd - 2.0).abs() < 1e-9);
+        assert_eq!(status.usage_ratio, Some(0.25));
+        assert!(status.reset_in_minutes <= 7 * 24 * 60);
+
+        // 未识别到订阅类型时不返回；手动配置的套餐优先
+        assert!(
+            get_plan_limit_statuses(&repository, &settings, None, None, now)
+                .expect("statuses")
+                .is_empty()
+        );
+        repository
+            .set_provider_plan(api.id, PlanType::Pro, 20.0)
+            .expect("plan");
+        let statuses = get_plan_limit_statuses(&repository, &settings, None, Some(api.id), now)
+            .expect("statuses");
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].plan_type, PlanType::Pro);
+        assert_eq!(statuses[0].token_limit, None);
+    }
+}
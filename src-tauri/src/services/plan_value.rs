@@ -0,0 +1,107 @@
+//! @file plan_value.rs
+//! @description 订阅价值计算服务，对比订阅月费与同等用量的 API 价格
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{Datelike, NaiveDate};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{ModelUsage, PlanValue, ProviderPlan};
+use crate::services::pricing::PricingService;
+
+/// 计算单个模型用量的 API 等价费用
+///
+/// 有价格配置的模型按 API 价格重新计算；未知模型回退为已记录的费用
+pub fn api_equivalent_cost(pricing: &PricingService, usage: &ModelUsage) -> f64 {
+    if !pricing.has_pricing(&usage.model) {
+        return usage.cost_usd;
+    }
+
+    pricing.calculate_cost(
+        &usage.model,
+        usage.input_tokens,
+        usage.output_tokens,
+        usage.cache_read_tokens,
+        usage.cache_creation_tokens,
+    )
+}
+
+/// 计算指定套餐在 `today` 所在自然月的订阅价值
+///
+/// 业务逻辑说明：
+/// 1. 统计周期为本月 1 日至今天（本地日期）
+/// 2. 查询该供应商周期内按模型分组的用量
+/// 3. 汇总实际记录费用与 API 等价费用，生成对比结果
+pub fn calculate_plan_value(
+    repository: &Repository,
+    pricing: &PricingService,
+    plan: &ProviderPlan,
+    today: NaiveDate,
+) -> Result<PlanValue, RepositoryError> {
+    let period_start = today.with_day(1).unwrap_or(today).to_string();
+    let period_end = today.to_string();
+
+    let usages =
+        repository.get_provider_model_usage(plan.provider_id, &period_start, &period_end)?;
+
+    let recorded_cost_usd = usages.iter().map(|usage| usage.cost_usd).sum();
+    let api_equivalent_cost_usd = usages
+        .iter()
+        .map(|usage| api_equivalent_cost(pricing, usage))
+        .sum();
+
+    Ok(PlanValue::new(
+        plan,
+        period_start,
+        period_end,
+        recorded_cost_usd,
+        api_equivalent_cost_usd,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage, PlanType};
+    use chrono::Local;
+
+    #[test]
+    fn test_calculate_plan_value() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-plan", None)
+            .expect("provider");
+        let plan = repository
+            .set_provider_plan(provider.id, PlanType::Pro, 20.0)
+            .expect("plan");
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-3-opus-20240229".to_string(),
+            Local::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 1_000_000,
+                output_tokens: 1_000_000,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 0.0,
+            },
+        );
+        repository
+            .insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        let value = calculate_plan_value(
+            &repository,
+            &PricingService::new(),
+            &plan,
+            Local::now().date_naive(),
+        )
+        .expect("plan value");
+
+        assert_eq!(value.recorded_cost_usd, 0.0);
+        assert_eq!(value.api_equivalent_cost_usd, 90.0);
+        assert_eq!(value.savings_usd, 70.0);
+    }
+}
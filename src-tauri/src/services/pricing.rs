@@ -4,17 +4,140 @@
 //! @date 2026-01-08
 use std::collections::HashMap;
 
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::models::{CacheEconomics, ModelAlias, ServiceTier};
+
+/// 内置价格表版本，价格表变更时同步更新，记录在费用重算审计中
+pub const PRICING_VERSION: &str = "2026-10-18";
+
+/// 未配置模型的默认上下文窗口大小（Token）
+pub const DEFAULT_CONTEXT_WINDOW_TOKENS: i64 = 200_000;
+
+/// 模型系列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelFamily {
+    Opus,
+    Sonnet,
+    Haiku,
+}
+
+impl ModelFamily {
+    /// 从模型名称识别系列，无法识别时返回 None
+    pub fn detect(model: &str) -> Option<Self> {
+        let lower = model.to_ascii_lowercase();
+        [ModelFamily::Opus, ModelFamily::Sonnet, ModelFamily::Haiku]
+            .into_iter()
+            .find(|family| lower.contains(family.id()))
+    }
+
+    /// 模型名称中的系列标识
+    pub fn id(&self) -> &'static str {
+        match self {
+            ModelFamily::Opus => "opus",
+            ModelFamily::Sonnet => "sonnet",
+            ModelFamily::Haiku => "haiku",
+        }
+    }
+
+    /// 展示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModelFamily::Opus => "Opus",
+            ModelFamily::Sonnet => "Sonnet",
+            ModelFamily::Haiku => "Haiku",
+        }
+    }
+
+    /// 低一档的系列，Haiku 返回 None
+    pub fn cheaper(&self) -> Option<Self> {
+        match self {
+            ModelFamily::Opus => Some(ModelFamily::Sonnet),
+            ModelFamily::Sonnet => Some(ModelFamily::Haiku),
+            ModelFamily::Haiku => None,
+        }
+    }
+}
+
+/// 长上下文计费阈值（Token），提示 Token 超过该值的请求按长上下文价格计费
+pub const LONG_CONTEXT_THRESHOLD_TOKENS: i64 = 200_000;
+
+/// Sonnet 4 长上下文计价的生效日期
+const SONNET_4_LONG_CONTEXT_SINCE: (i32, u32, u32) = (2025, 8, 12);
+
+/// 每百万 Token 单价（美元）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenRates {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    pub cache_read_per_million: f64,
+    pub cache_creation_per_million: f64,
+}
+
+/// 阈值档位价格：提示 Token（输入 + 缓存读取 + 缓存创建）超过阈值时，整条请求按该档单价计费
+#[derive(Debug, Clone)]
+pub struct PricingTier {
+    pub threshold_tokens: i64,
+    pub rates: TokenRates,
+}
+
 #[derive(Debug, Clone)]
 pub struct ModelPricing {
     pub input_per_million: f64,
     pub output_per_million: f64,
     pub cache_read_per_million: f64,
     pub cache_creation_per_million: f64,
+    /// 上下文窗口大小（Token）
+    pub context_window_tokens: i64,
+    /// 阈值档位，按 threshold_tokens 升序排列
+    pub tiers: Vec<PricingTier>,
+    /// 生效日期（含），None 表示不限起始
+    pub effective_from: Option<NaiveDate>,
+    /// 失效日期（不含），None 表示当前仍有效
+    pub effective_until: Option<NaiveDate>,
+}
+
+impl ModelPricing {
+    /// 基础单价（未超过任何档位阈值时使用）
+    pub fn base_rates(&self) -> TokenRates {
+        TokenRates {
+            input_per_million: self.input_per_million,
+            output_per_million: self.output_per_million,
+            cache_read_per_million: self.cache_read_per_million,
+            cache_creation_per_million: self.cache_creation_per_million,
+        }
+    }
+
+    /// 该价格在 date 当天是否有效
+    pub fn is_effective_on(&self, date: NaiveDate) -> bool {
+        self.effective_from.is_none_or(|from| from <= date)
+            && self.effective_until.is_none_or(|until| date < until)
+    }
+
+    /// 按提示 Token 数选出生效单价：取阈值被超过的最高档位，均未超过时使用基础单价
+    pub fn rates_for(&self, prompt_tokens: i64) -> TokenRates {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| prompt_tokens > tier.threshold_tokens)
+            .map(|tier| tier.rates)
+            .unwrap_or_else(|| self.base_rates())
+    }
+}
+
+/// 消息时间戳对应的计价日期（UTC），无法解析时使用当天
+pub fn pricing_date(created_at: &str) -> NaiveDate {
+    DateTime::parse_from_rfc3339(created_at)
+        .map(|timestamp| timestamp.with_timezone(&Utc).date_naive())
+        .unwrap_or_else(|_| Utc::now().date_naive())
 }
 
 #[derive(Debug, Clone)]
 pub struct PricingService {
-    pricing: HashMap<String, ModelPricing>,
+    /// 模型名 → 价格版本，按 effective_from 升序排列，最后一项为当前价格
+    pricing: HashMap<String, Vec<ModelPricing>>,
+    /// 模型别名 → 标准模型名
+    aliases: HashMap<String, String>,
 }
 
 impl Default for PricingService {
@@ -30,37 +153,112 @@ impl PricingService {
         // 价格为示例值，可在后续版本中由配置或远程获取
         pricing.insert(
             "claude-3-opus".to_string(),
-            ModelPricing {
+            vec![ModelPricing {
                 input_per_million: 15.0,
                 output_per_million: 75.0,
                 cache_read_per_million: 1.5,
                 cache_creation_per_million: 0.0,
-            },
+                context_window_tokens: DEFAULT_CONTEXT_WINDOW_TOKENS,
+                tiers: Vec::new(),
+                effective_from: None,
+                effective_until: None,
+            }],
         );
 
         pricing.insert(
             "claude-3-sonnet".to_string(),
-            ModelPricing {
+            vec![ModelPricing {
                 input_per_million: 3.0,
                 output_per_million: 15.0,
                 cache_read_per_million: 0.3,
                 cache_creation_per_million: 0.0,
-            },
+                context_window_tokens: DEFAULT_CONTEXT_WINDOW_TOKENS,
+                tiers: Vec::new(),
+                effective_from: None,
+                effective_until: None,
+            }],
+        );
+
+        // Sonnet 4 自 2025-08-12 起提供长上下文计价，提示超过 200k Token 的请求输入价翻倍、输出价 1.5 倍；
+        // 此前不存在长上下文档位。上下文窗口仍按 200k 计算，会话接近 200k 时给出提示
+        let (year, month, day) = SONNET_4_LONG_CONTEXT_SINCE;
+        let long_context_since = NaiveDate::from_ymd_opt(year, month, day);
+        let sonnet_4 = ModelPricing {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+            cache_read_per_million: 0.3,
+            cache_creation_per_million: 3.75,
+            context_window_tokens: DEFAULT_CONTEXT_WINDOW_TOKENS,
+            tiers: Vec::new(),
+            effective_from: None,
+            effective_until: long_context_since,
+        };
+        pricing.insert(
+            "claude-sonnet-4".to_string(),
+            vec![
+                sonnet_4.clone(),
+                ModelPricing {
+                    tiers: vec![PricingTier {
+                        threshold_tokens: LONG_CONTEXT_THRESHOLD_TOKENS,
+                        rates: TokenRates {
+                            input_per_million: 6.0,
+                            output_per_million: 22.5,
+                            cache_read_per_million: 0.6,
+                            cache_creation_per_million: 7.5,
+                        },
+                    }],
+                    effective_from: long_context_since,
+                    effective_until: None,
+                    ..sonnet_4
+                },
+            ],
         );
 
         pricing.insert(
             "claude-3-haiku".to_string(),
-            ModelPricing {
+            vec![ModelPricing {
                 input_per_million: 0.25,
                 output_per_million: 1.25,
                 cache_read_per_million: 0.025,
                 cache_creation_per_million: 0.0,
-            },
+                context_window_tokens: DEFAULT_CONTEXT_WINDOW_TOKENS,
+                tiers: Vec::new(),
+                effective_from: None,
+                effective_until: None,
+            }],
         );
 
-        Self { pricing }
+        Self {
+            pricing,
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// 加载模型别名，之后的价格查找与模型名解析都会先做别名映射
+    pub fn with_aliases(mut self, aliases: &[ModelAlias]) -> Self {
+        self.aliases = aliases
+            .iter()
+            .map(|alias| (alias.alias.clone(), alias.canonical.clone()))
+            .collect();
+        self
     }
 
+    /// 设置模型的全部价格版本（替换原有配置），按生效日期排序；用于配置历史价格
+    pub fn with_pricing_versions(mut self, model: &str, mut versions: Vec<ModelPricing>) -> Self {
+        versions.sort_by_key(|version| version.effective_from);
+        self.pricing.insert(model.to_string(), versions);
+        self
+    }
+
+    /// 将别名解析为标准模型名，无对应别名时原样返回
+    pub fn resolve_model<'a>(&'a self, model: &'a str) -> &'a str {
+        self.aliases
+            .get(model)
+            .map(|canonical| canonical.as_str())
+            .unwrap_or(model)
+    }
+
+    /// 按当前价格计算费用，提示 Token 超过模型档位阈值时整条请求按对应档位计费
     pub fn calculate_cost(
         &self,
         model: &str,
@@ -69,19 +267,172 @@ impl PricingService {
         cache_read_tokens: i64,
         cache_creation_tokens: i64,
     ) -> f64 {
-        let Some(pricing) = self.pricing.get(model) else {
+        self.find_pricing(model).map_or(0.0, |pricing| {
+            token_cost(
+                pricing,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_creation_tokens,
+            )
+        })
+    }
+
+    /// 按 date 当天有效的价格计算费用，用于按消息时间戳计价与重算历史费用
+    pub fn calculate_cost_at(
+        &self,
+        model: &str,
+        date: NaiveDate,
+        input_tokens: i64,
+        output_tokens: i64,
+        cache_read_tokens: i64,
+        cache_creation_tokens: i64,
+    ) -> f64 {
+        self.find_pricing_at(model, date).map_or(0.0, |pricing| {
+            token_cost(
+                pricing,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_creation_tokens,
+            )
+        })
+    }
+
+    /// 按服务层级计算 date 当天的费用：标准价格乘以层级倍率（Batch 五折、Priority 溢价）
+    #[allow(clippy::too_many_arguments)]
+    pub fn calculate_tier_cost(
+        &self,
+        model: &str,
+        date: NaiveDate,
+        service_tier: ServiceTier,
+        input_tokens: i64,
+        output_tokens: i64,
+        cache_read_tokens: i64,
+        cache_creation_tokens: i64,
+    ) -> f64 {
+        self.calculate_cost_at(
+            model,
+            date,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_creation_tokens,
+        ) * service_tier.cost_multiplier()
+    }
+
+    /// 按 date 当天有效的价格，计算缓存读取相比按输入价格计费节省的费用（美元），未知模型返回 0
+    pub fn cache_savings(&self, model: &str, date: NaiveDate, cache_read_tokens: i64) -> f64 {
+        let Some(pricing) = self.find_pricing_at(model, date) else {
             return 0.0;
         };
 
-        let input_cost = input_tokens as f64 / 1_000_000.0 * pricing.input_per_million;
-        let output_cost = output_tokens as f64 / 1_000_000.0 * pricing.output_per_million;
-        let cache_read_cost =
-            cache_read_tokens as f64 / 1_000_000.0 * pricing.cache_read_per_million;
-        let cache_creation_cost =
-            cache_creation_tokens as f64 / 1_000_000.0 * pricing.cache_creation_per_million;
+        cache_read_tokens as f64 / 1_000_000.0
+            * (pricing.input_per_million - pricing.cache_read_per_million)
+    }
 
-        input_cost + output_cost + cache_read_cost + cache_creation_cost
+    /// 按 date 当天有效的价格计算单条消息的缓存经济性，未知模型返回 None
+    ///
+    /// 以"所有提示 Token 按输入价格计费"为基准：
+    /// 写入溢价 = 缓存创建 Token × (缓存创建价 - 输入价)，
+    /// 读取节省 = 缓存读取 Token × (输入价 - 缓存读取价)
+    pub fn cache_economics(
+        &self,
+        model: &str,
+        date: NaiveDate,
+        input_tokens: i64,
+        cache_read_tokens: i64,
+        cache_creation_tokens: i64,
+    ) -> Option<CacheEconomics> {
+        let pricing = self.find_pricing_at(model, date)?;
+
+        let prompt_tokens = input_tokens + cache_read_tokens + cache_creation_tokens;
+        let rates = pricing.rates_for(prompt_tokens);
+        let prompt_cost = (input_tokens as f64 * rates.input_per_million
+            + cache_read_tokens as f64 * rates.cache_read_per_million
+            + cache_creation_tokens as f64 * rates.cache_creation_per_million)
+            / 1_000_000.0;
+        let effective_input_price_per_million = if prompt_tokens > 0 {
+            prompt_cost / prompt_tokens as f64 * 1_000_000.0
+        } else {
+            rates.input_per_million
+        };
+
+        let cache_write_premium_usd = cache_creation_tokens as f64 / 1_000_000.0
+            * (rates.cache_creation_per_million - rates.input_per_million);
+        let cache_read_savings_usd = cache_read_tokens as f64 / 1_000_000.0
+            * (rates.input_per_million - rates.cache_read_per_million);
+
+        Some(CacheEconomics {
+            effective_input_price_per_million,
+            cache_write_premium_usd,
+            cache_read_savings_usd,
+            net_savings_usd: cache_read_savings_usd - cache_write_premium_usd,
+        })
     }
+
+    /// 查找模型当前价格
+    pub fn find_pricing(&self, model: &str) -> Option<&ModelPricing> {
+        self.find_versions(model)?.last()
+    }
+
+    /// 查找 date 当天有效的模型价格，没有覆盖该日期的版本时使用当前价格
+    pub fn find_pricing_at(&self, model: &str, date: NaiveDate) -> Option<&ModelPricing> {
+        let versions = self.find_versions(model)?;
+        versions
+            .iter()
+            .find(|pricing| pricing.is_effective_on(date))
+            .or_else(|| versions.last())
+    }
+
+    /// 查找模型的全部价格版本
+    ///
+    /// 业务逻辑说明：
+    /// 1. 先按模型别名映射为标准模型名
+    /// 2. 优先精确匹配模型名称
+    /// 3. 否则按最长前缀匹配（如 "claude-3-opus-20240229" 匹配 "claude-3-opus"）
+    fn find_versions(&self, model: &str) -> Option<&Vec<ModelPricing>> {
+        let model = self.resolve_model(model);
+        if let Some(versions) = self.pricing.get(model) {
+            return Some(versions);
+        }
+
+        self.pricing
+            .iter()
+            .filter(|(name, _)| model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, versions)| versions)
+    }
+
+    /// 模型上下文窗口大小，未配置的模型使用 DEFAULT_CONTEXT_WINDOW_TOKENS
+    pub fn context_window(&self, model: &str) -> i64 {
+        self.find_pricing(model)
+            .map(|pricing| pricing.context_window_tokens)
+            .unwrap_or(DEFAULT_CONTEXT_WINDOW_TOKENS)
+    }
+
+    /// 是否存在该模型的价格配置
+    pub fn has_pricing(&self, model: &str) -> bool {
+        self.find_pricing(model).is_some()
+    }
+}
+
+/// 按给定价格计算费用，提示 Token 超过档位阈值时整条请求按对应档位计费
+fn token_cost(
+    pricing: &ModelPricing,
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_read_tokens: i64,
+    cache_creation_tokens: i64,
+) -> f64 {
+    let rates = pricing.rates_for(input_tokens + cache_read_tokens + cache_creation_tokens);
+    let input_cost = input_tokens as f64 / 1_000_000.0 * rates.input_per_million;
+    let output_cost = output_tokens as f64 / 1_000_000.0 * rates.output_per_million;
+    let cache_read_cost = cache_read_tokens as f64 / 1_000_000.0 * rates.cache_read_per_million;
+    let cache_creation_cost =
+        cache_creation_tokens as f64 / 1_000_000.0 * rates.cache_creation_per_million;
+
+    input_cost + output_cost + cache_read_cost + cache_creation_cost
 }
 
 #[cfg(test)]
@@ -95,4 +446,158 @@ mod tests {
 
         assert_eq!(cost, 15.0);
     }
+
+    #[test]
+    fn test_calculate_tier_cost() {
+        let service = PricingService::new();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let batch = service.calculate_tier_cost(
+            "claude-3-opus",
+            date,
+            ServiceTier::Batch,
+            1_000_000,
+            0,
+            0,
+            0,
+        );
+        let priority = service.calculate_tier_cost(
+            "claude-3-opus",
+            date,
+            ServiceTier::Priority,
+            1_000_000,
+            0,
+            0,
+            0,
+        );
+
+        assert_eq!(batch, 7.5);
+        assert_eq!(priority, 18.75);
+    }
+
+    #[test]
+    fn test_calculate_cost_with_long_context_tier() {
+        let service = PricingService::new();
+        let model = "claude-sonnet-4-20250514";
+
+        // 恰好 200k 仍按基础价格：200k × 3 + 1M × 15
+        let standard = service.calculate_cost(model, 200_000, 1_000_000, 0, 0);
+        assert!((standard - 15.6).abs() < 1e-9);
+
+        // 缓存 Token 计入阈值，超过后整条请求按长上下文价格：100k × 6 + 1M × 22.5 + 150k × 0.6
+        let long = service.calculate_cost(model, 100_000, 1_000_000, 150_000, 0);
+        assert!((long - 23.19).abs() < 1e-9);
+        assert_eq!(service.context_window(model), DEFAULT_CONTEXT_WINDOW_TOKENS);
+
+        // 长上下文计价开放之前的消息仍按基础价格计费
+        let before = NaiveDate::from_ymd_opt(2025, 8, 11).unwrap();
+        let since = NaiveDate::from_ymd_opt(2025, 8, 12).unwrap();
+        let cost_on = |date| service.calculate_cost_at(model, date, 100_000, 1_000_000, 150_000, 0);
+        assert!((cost_on(before) - 15.345).abs() < 1e-9);
+        assert!((cost_on(since) - 23.19).abs() < 1e-9);
+
+        // 无档位的模型不受影响
+        assert_eq!(
+            service.calculate_cost("claude-3-opus", 1_000_000, 0, 0, 0),
+            15.0
+        );
+    }
+
+    #[test]
+    fn test_calculate_cost_at_effective_date() {
+        let changed_on = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let current = PricingService::new()
+            .find_pricing("claude-3-haiku")
+            .cloned()
+            .expect("pricing");
+        let service = PricingService::new().with_pricing_versions(
+            "claude-3-haiku",
+            vec![
+                ModelPricing {
+                    effective_from: Some(changed_on),
+                    ..current.clone()
+                },
+                ModelPricing {
+                    input_per_million: 0.5,
+                    effective_until: Some(changed_on),
+                    ..current
+                },
+            ],
+        );
+        let cost_on = |date: NaiveDate| {
+            service.calculate_cost_at("claude-3-haiku-20240307", date, 1_000_000, 0, 0, 0)
+        };
+
+        assert_eq!(cost_on(NaiveDate::from_ymd_opt(2026, 5, 31).unwrap()), 0.5);
+        assert_eq!(cost_on(changed_on), 0.25);
+        assert_eq!(
+            service.calculate_cost("claude-3-haiku", 1_000_000, 0, 0, 0),
+            0.25
+        );
+        assert_eq!(
+            pricing_date("2026-05-31T23:30:00-02:00"),
+            NaiveDate::from_ymd_opt(2026, 6, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_calculate_cost_with_dated_model_name() {
+        let service = PricingService::new();
+        let cost = service.calculate_cost("claude-3-haiku-20240307", 0, 1_000_000, 0, 0);
+
+        assert_eq!(cost, 1.25);
+        assert!(!service.has_pricing("gpt-4"));
+    }
+
+    #[test]
+    fn test_calculate_cost_with_alias() {
+        let service = PricingService::new().with_aliases(&[ModelAlias {
+            alias: "relay/opus-latest".to_string(),
+            canonical: "claude-3-opus".to_string(),
+            updated_at: String::new(),
+        }]);
+
+        assert_eq!(service.resolve_model("relay/opus-latest"), "claude-3-opus");
+        assert_eq!(service.resolve_model("claude-3-haiku"), "claude-3-haiku");
+        assert_eq!(
+            service.calculate_cost("relay/opus-latest", 1_000_000, 0, 0, 0),
+            15.0
+        );
+    }
+
+    #[test]
+    fn test_cache_economics() {
+        let service = PricingService::new();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let economics = service
+            .cache_economics(
+                "claude-3-opus-20240229",
+                date,
+                1_000_000,
+                2_000_000,
+                1_000_000,
+            )
+            .expect("economics");
+
+        // (15 + 2 × 1.5 + 0) / 4 = 4.5
+        assert!((economics.effective_input_price_per_million - 4.5).abs() < 1e-9);
+        assert!((economics.cache_write_premium_usd + 15.0).abs() < 1e-9);
+        assert!((economics.cache_read_savings_usd - 27.0).abs() < 1e-9);
+        assert!((economics.net_savings_usd - 42.0).abs() < 1e-9);
+
+        let empty = service
+            .cache_economics("claude-3-haiku", date, 0, 0, 0)
+            .expect("economics");
+        assert_eq!(empty.effective_input_price_per_million, 0.25);
+        assert!(service.cache_economics("gpt-4", date, 100, 0, 0).is_none());
+
+        // 长上下文计价开放之前，超过 200k 的提示仍按基础缓存价格计算节省
+        let sonnet = |date| {
+            service
+                .cache_economics("claude-sonnet-4", date, 0, 1_000_000, 0)
+                .expect("economics")
+                .cache_read_savings_usd
+        };
+        assert!((sonnet(NaiveDate::from_ymd_opt(2025, 8, 1).unwrap()) - 2.7).abs() < 1e-9);
+        assert!((sonnet(date) - 5.4).abs() < 1e-9);
+    }
 }
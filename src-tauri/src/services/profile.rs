@@ -0,0 +1,148 @@
+//! @file profile.rs
+//! @description 配置档案切换服务，切换时重新打开数据库、重置内存状态并按档案的目录重新监控
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::profile::{profile_db_path, ProfileError, ProfilesConfig};
+use crate::db::{self, Repository};
+use crate::models::{AppLockSettings, Profile, ProfileInfo, DEFAULT_PROFILE_NAME};
+use crate::services::app_lock::AppLock;
+use crate::services::block_warning::BlockWarningState;
+use crate::services::file_watcher::{default_claude_dir, FileWatcher, FileWatcherError};
+use crate::services::ingest_worker::IngestWorker;
+use crate::services::live_stats::LiveStats;
+use crate::services::outliers::OutlierState;
+use crate::services::shutdown::{IngestTracker, INGEST_DRAIN_TIMEOUT};
+
+/// 当前使用的档案名称
+pub struct ActiveProfile {
+    name: Mutex<String>,
+}
+
+impl ActiveProfile {
+    pub fn new(name: String) -> Self {
+        Self {
+            name: Mutex::new(name),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name
+            .lock()
+            .map(|name| name.clone())
+            .unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    fn set(&self, name: &str) {
+        if let Ok(mut current) = self.name.lock() {
+            *current = name.to_string();
+        }
+    }
+}
+
+/// 档案的监控目录，未配置时监控 ~/.claude
+pub fn watch_dirs(profile: &Profile) -> Result<Vec<PathBuf>, FileWatcherError> {
+    if profile.watch_paths.is_empty() {
+        return Ok(vec![default_claude_dir()?]);
+    }
+    Ok(profile.watch_paths.iter().map(PathBuf::from).collect())
+}
+
+/// 切换到指定档案
+///
+/// 业务逻辑说明：
+/// 1. 只读档案需使用数据库副本，只能在启动时选择
+/// 2. 停止文件监控，拒绝新的采集任务并等待进行中的写入完成；切换成功后丢弃原档案排队中的任务
+/// 3. 共享连接切换到档案的数据库，所有后台服务随之使用新库；打开失败时恢复原监控
+/// 4. 按新库重新加载应用锁设置，清空与原数据库相关的内存状态（实时速率、区块预警、异常检测进度）
+/// 5. 按档案的监控目录重新启动文件监控，保存当前档案并通知前端刷新
+pub fn switch_profile(
+    app: &AppHandle,
+    app_data_dir: &Path,
+    name: &str,
+) -> Result<ProfileInfo, ProfileError> {
+    if db::is_demo_mode() {
+        return Err(ProfileError::DemoMode);
+    }
+    let mut config = ProfilesConfig::load(app_data_dir);
+    let profile = config
+        .get(name)
+        .ok_or_else(|| ProfileError::NotFound(name.to_string()))?;
+    if profile.read_only {
+        return Err(ProfileError::ReadOnlyProfile(name.to_string()));
+    }
+    let dirs = watch_dirs(&profile).map_err(|e| ProfileError::Watcher(e.to_string()))?;
+    let active = app.state::<ActiveProfile>();
+    let db_path = profile_db_path(app_data_dir, name);
+    let info = ProfileInfo {
+        name: profile.name.clone(),
+        watch_paths: profile.watch_paths.clone(),
+        read_only: profile.read_only,
+        db_path: db_path.display().to_string(),
+        active: true,
+    };
+    if active.name() == name {
+        return Ok(info);
+    }
+
+    let watcher_state = app.state::<Mutex<FileWatcher>>();
+    let mut watcher = watcher_state
+        .lock()
+        .map_err(|e| ProfileError::Watcher(e.to_string()))?;
+    watcher.stop();
+    let tracker = app.state::<IngestTracker>();
+    tracker.close();
+    if !tracker.wait_idle(INGEST_DRAIN_TIMEOUT) {
+        tracing::warn!("等待采集任务超时，仍有 {} 个任务未完成", tracker.active());
+    }
+
+    let repository = app.state::<Repository>();
+    if let Err(e) = repository.reopen(&db_path) {
+        tracker.reopen();
+        if let Err(e) = watcher.start() {
+            tracing::error!("恢复文件监控失败: {}", e);
+        }
+        return Err(e.into());
+    }
+    // 应用锁设置随数据库切换，发起切换的用户视为在场，保持解锁
+    match repository.get_setting::<AppLockSettings>() {
+        Ok(settings) => app.state::<AppLock>().configure(&settings, Instant::now()),
+        Err(e) => tracing::error!("读取应用锁设置失败: {}", e),
+    }
+    app.state::<LiveStats>().reset();
+    app.state::<BlockWarningState>().reset();
+    app.state::<OutlierState>().reset();
+
+    // 原档案尚在队列中的采集任务作废，不能写入新库
+    app.state::<IngestWorker>().discard_pending();
+    tracker.reopen();
+    let mut next = FileWatcher::with_dirs(app.clone(), dirs)
+        .map_err(|e| ProfileError::Watcher(e.to_string()))?;
+    let started = next.start();
+    *watcher = next;
+    drop(watcher);
+
+    active.set(name);
+    config.active = (name != DEFAULT_PROFILE_NAME).then(|| name.to_string());
+    config.save(app_data_dir)?;
+    tracing::info!("已切换档案: {} ({})", name, db_path.display());
+
+    if let Err(e) = app.emit("profile-switched", &info) {
+        tracing::error!("发送 profile-switched 事件失败: {}", e);
+    }
+    match repository.get_current_stats() {
+        Ok(stats) => {
+            if let Err(e) = app.emit("stats-updated", stats) {
+                tracing::error!("发送 stats-updated 事件失败: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("获取统计数据失败: {}", e),
+    }
+    started.map_err(|e| ProfileError::Watcher(e.to_string()))?;
+    Ok(info)
+}
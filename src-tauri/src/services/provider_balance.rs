@@ -0,0 +1,232 @@
+//! @file provider_balance.rs
+//! @description 中转站余额查询服务，请求供应商配置的余额接口并解析常见中转站的响应格式
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{BalanceAmounts, ProviderBalance};
+use crate::services::keychain::{self, KeychainError};
+
+/// one-api / new-api 中 1 美元对应的额度单位
+const ONE_API_QUOTA_PER_USD: f64 = 500_000.0;
+
+/// 单次查询的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum ProviderBalanceError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("Keychain error: {0}")]
+    Keychain(#[from] KeychainError),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("无法识别的余额响应格式")]
+    UnrecognizedResponse,
+}
+
+/// 立即查询一次供应商余额并保存结果
+///
+/// 网络或解析失败会记录到余额结果的 error 字段，不作为错误返回
+///
+/// # 返回
+/// 最新的余额记录，供应商未配置余额查询时返回 None
+pub fn refresh_provider_balance(
+    repository: &Repository,
+    provider_id: i64,
+) -> Result<Option<ProviderBalance>, ProviderBalanceError> {
+    let Some(config) = repository.get_provider_balance(provider_id)? else {
+        return Ok(None);
+    };
+    let namespace = repository.keychain_namespace()?;
+    let credential = keychain::load_quota_credential(&namespace, provider_id)?;
+
+    let result = fetch_balance(&config.quota_url, credential.as_deref()).map_err(|e| e.to_string());
+    if let Err(e) = &result {
+        tracing::warn!("查询供应商余额失败 [{}]: {}", provider_id, e);
+    }
+    Ok(repository.record_provider_balance(provider_id, &result)?)
+}
+
+/// 是否已到刷新时间：从未查询过，或距上次查询超过刷新间隔
+pub fn is_refresh_due(balance: &ProviderBalance, now: DateTime<Utc>) -> bool {
+    let Some(fetched_at) = balance
+        .fetched_at
+        .as_deref()
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+    else {
+        return true;
+    };
+    let interval = chrono::Duration::minutes(i64::from(balance.refresh_minutes.max(1)));
+    now.signed_duration_since(fetched_at) >= interval
+}
+
+/// 请求余额接口，凭证以 Bearer Token 方式携带
+fn fetch_balance(
+    quota_url: &str,
+    credential: Option<&str>,
+) -> Result<BalanceAmounts, ProviderBalanceError> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    let mut request = agent.get(quota_url);
+    if let Some(credential) = credential {
+        request = request.set("authorization", &format!("Bearer {}", credential));
+    }
+
+    let body = request
+        .call()
+        .map_err(|e| ProviderBalanceError::Http(e.to_string()))?
+        .into_string()?;
+    parse_balance(&body)
+}
+
+/// 解析余额响应，支持以下格式（字段可位于顶层或 data 对象内）：
+///
+/// - one-api / new-api 用户信息：`quota`（剩余）、`used_quota`（已用），单位为额度，按 500000 / 美元换算
+/// - OpenAI 兼容账单：`hard_limit_usd`（总额度）
+/// - 余额字段：`balance` / `remaining_balance` / `total_available`（剩余）、`total_granted`（总额）、`total_used`（已用）
+fn parse_balance(body: &str) -> Result<BalanceAmounts, ProviderBalanceError> {
+    let value: Value = serde_json::from_str(body)?;
+    let data = value
+        .get("data")
+        .filter(|data| data.is_object())
+        .unwrap_or(&value);
+
+    if let Some(quota) = number_field(data, &["quota"]) {
+        let remaining = quota / ONE_API_QUOTA_PER_USD;
+        let used = number_field(data, &["used_quota"]).map(|used| used / ONE_API_QUOTA_PER_USD);
+        return Ok(BalanceAmounts {
+            remaining_usd: Some(remaining),
+            used_usd: used,
+            total_usd: used.map(|used| remaining + used),
+        });
+    }
+
+    let amounts = BalanceAmounts {
+        remaining_usd: number_field(data, &["balance", "remaining_balance", "total_available"]),
+        used_usd: number_field(data, &["total_used", "used"]),
+        total_usd: number_field(data, &["total_granted", "hard_limit_usd"]),
+    };
+    let amounts = BalanceAmounts {
+        remaining_usd: amounts.remaining_usd.or_else(|| {
+            amounts
+                .total_usd
+                .zip(amounts.used_usd)
+                .map(|(total, used)| total - used)
+        }),
+        ..amounts
+    };
+    if amounts == BalanceAmounts::default() {
+        return Err(ProviderBalanceError::UnrecognizedResponse);
+    }
+    Ok(amounts)
+}
+
+/// 按顺序读取第一个存在的数值字段，兼容以字符串返回的数字
+fn number_field(data: &Value, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|key| match data.get(*key)? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_one_api_user_self() {
+        let amounts = parse_balance(
+            r#"{"success":true,"data":{"username":"me","quota":5000000,"used_quota":2500000}}"#,
+        )
+        .expect("parse");
+        assert_eq!(amounts.remaining_usd, Some(10.0));
+        assert_eq!(amounts.used_usd, Some(5.0));
+        assert_eq!(amounts.total_usd, Some(15.0));
+    }
+
+    #[test]
+    fn test_parse_balance_fields() {
+        let amounts = parse_balance(r#"{"balance":"12.5"}"#).expect("parse");
+        assert_eq!(amounts.remaining_usd, Some(12.5));
+        assert_eq!(amounts.total_usd, None);
+
+        let amounts = parse_balance(r#"{"total_granted":20,"total_used":7.5}"#).expect("parse");
+        assert_eq!(amounts.remaining_usd, Some(12.5));
+
+        let amounts = parse_balance(r#"{"object":"billing_subscription","hard_limit_usd":100}"#)
+            .expect("parse");
+        assert_eq!(amounts.total_usd, Some(100.0));
+        assert_eq!(amounts.remaining_usd, None);
+
+        assert!(matches!(
+            parse_balance(r#"{"data":{"username":"me"}}"#),
+            Err(ProviderBalanceError::UnrecognizedResponse)
+        ));
+    }
+
+    #[test]
+    fn test_record_and_refresh_due() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-relay", None)
+            .expect("provider");
+        let balance = repository
+            .set_provider_quota(provider.id, "https://relay.example.com/api/user/self", 15)
+            .expect("quota");
+        assert!(is_refresh_due(&balance, Utc::now()));
+
+        let balance = repository
+            .record_provider_balance(
+                provider.id,
+                &Ok(BalanceAmounts {
+                    remaining_usd: Some(3.0),
+                    ..Default::default()
+                }),
+            )
+            .expect("record")
+            .expect("configured");
+        assert_eq!(balance.remaining_usd, Some(3.0));
+        assert!(!is_refresh_due(&balance, Utc::now()));
+        assert!(is_refresh_due(
+            &balance,
+            Utc::now() + chrono::Duration::minutes(15)
+        ));
+
+        // 失败时保留上一次的额度
+        let balance = repository
+            .record_provider_balance(provider.id, &Err("timeout".to_string()))
+            .expect("record")
+            .expect("configured");
+        assert_eq!(balance.remaining_usd, Some(3.0));
+        assert_eq!(balance.error.as_deref(), Some("timeout"));
+
+        // 修改查询地址后清空旧结果
+        let balance = repository
+            .set_provider_quota(provider.id, "https://relay.example.com/v1/balance", 30)
+            .expect("quota");
+        assert_eq!(balance.remaining_usd, None);
+        assert!(balance.fetched_at.is_none());
+        assert_eq!(
+            repository.get_provider_balances().expect("list"),
+            vec![balance]
+        );
+
+        assert!(repository
+            .delete_provider_quota(provider.id)
+            .expect("delete"));
+        assert!(repository
+            .get_provider_balance(provider.id)
+            .expect("get")
+            .is_none());
+    }
+}
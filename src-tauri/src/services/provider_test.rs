@@ -0,0 +1,140 @@
+//! @file provider_test.rs
+//! @description 供应商连通性检测服务，使用钥匙串中的 API Key 请求模型列表并记录延迟与状态
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{Provider, ProviderKind, ProviderTestResult};
+use crate::services::keychain::{self, KeychainError};
+
+/// 未配置 base_url 时使用的官方 API 地址
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// 请求要求的 API 版本头
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// 单次检测的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum ProviderTestError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("Keychain error: {0}")]
+    Keychain(#[from] KeychainError),
+    #[error("供应商不存在: {0}")]
+    ProviderNotFound(i64),
+    #[error("订阅账号没有 API Key，无法检测")]
+    Subscription,
+    #[error("钥匙串中未保存该供应商的 API Key")]
+    MissingApiKey,
+}
+
+/// 检测指定供应商是否可用
+///
+/// 请求 `GET {base_url}/v1/models`，不消耗 token；网络失败或非 2xx 状态不作为错误返回，
+/// 而是记录在结果的 ok/status/error 中
+pub fn test_provider(
+    repository: &Repository,
+    provider_id: i64,
+) -> Result<ProviderTestResult, ProviderTestError> {
+    let provider = repository
+        .get_provider(provider_id)?
+        .ok_or(ProviderTestError::ProviderNotFound(provider_id))?;
+    if provider.kind == ProviderKind::Subscription {
+        return Err(ProviderTestError::Subscription);
+    }
+    let namespace = repository.keychain_namespace()?;
+    let api_key =
+        keychain::load_api_key(&namespace, provider_id)?.ok_or(ProviderTestError::MissingApiKey)?;
+
+    Ok(send_test_request(&provider, &api_key))
+}
+
+fn send_test_request(provider: &Provider, api_key: &str) -> ProviderTestResult {
+    let endpoint = models_endpoint(provider.base_url.as_deref());
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+    // 官方 API 使用 x-api-key，多数中转站使用 Bearer Token，两者同时携带
+    let started = Instant::now();
+    let response = agent
+        .get(&endpoint)
+        .set("x-api-key", api_key)
+        .set("authorization", &format!("Bearer {}", api_key))
+        .set("anthropic-version", ANTHROPIC_VERSION)
+        .call();
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (status, error) = match response {
+        Ok(response) => (Some(response.status()), None),
+        Err(ureq::Error::Status(code, response)) => {
+            let text = response.status_text().to_string();
+            (Some(code), Some(format!("HTTP {} {}", code, text)))
+        }
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    ProviderTestResult {
+        provider_id: provider.id,
+        endpoint,
+        ok: status.is_some_and(|code| (200..300).contains(&code)),
+        status,
+        latency_ms,
+        error,
+        tested_at: Utc::now().to_rfc3339(),
+    }
+}
+
+/// 根据 base_url 拼接模型列表地址，兼容末尾已带 `/v1` 的写法
+fn models_endpoint(base_url: Option<&str>) -> String {
+    let base = base_url
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_BASE_URL)
+        .trim_end_matches('/');
+    let base = base.strip_suffix("/v1").unwrap_or(base);
+    format!("{}/v1/models", base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_models_endpoint() {
+        assert_eq!(models_endpoint(None), "https://api.anthropic.com/v1/models");
+        assert_eq!(
+            models_endpoint(Some("https://relay.example.com/")),
+            "https://relay.example.com/v1/models"
+        );
+        assert_eq!(
+            models_endpoint(Some("https://relay.example.com/api/v1")),
+            "https://relay.example.com/api/v1/models"
+        );
+        assert_eq!(
+            models_endpoint(Some("  ")),
+            "https://api.anthropic.com/v1/models"
+        );
+    }
+
+    #[test]
+    fn test_provider_rejects_missing_and_subscription() {
+        let repository = Repository::new_in_memory().expect("repo");
+        assert!(matches!(
+            test_provider(&repository, 42),
+            Err(ProviderTestError::ProviderNotFound(42))
+        ));
+
+        let subscription = repository
+            .upsert_subscription_provider("uuid-1", "Subscription")
+            .expect("subscription");
+        assert!(matches!(
+            test_provider(&repository, subscription.id),
+            Err(ProviderTestError::Subscription)
+        ));
+    }
+}
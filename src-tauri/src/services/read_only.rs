@@ -0,0 +1,209 @@
+//! @file read_only.rs
+//! @description 只读查看模式，拒绝一切修改类命令，文件监控写入数据库副本，适合在共享屏幕上展示
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::{Path, PathBuf};
+
+use crate::db::location::copy_database;
+use crate::db::{Repository, RepositoryError, DB_FILE_NAME};
+use crate::models::Profile;
+
+/// 启动参数：以只读模式启动当前档案
+pub const READ_ONLY_ARG: &str = "--read-only";
+
+/// 只读模式数据库副本目录，位于应用数据目录
+pub const READ_ONLY_DIR: &str = "read-only";
+
+/// 只读模式下允许调用的命令：查询、导出与不写入数据库的界面操作
+///
+/// 未列出的命令一律视为修改类命令，新增命令默认在只读模式下被拒绝
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "audit_duplicates",
+    "benchmark_parser",
+    "check_for_updates",
+    "check_permissions",
+    "compare_providers",
+    "export_cost_allocation",
+    "export_parquet",
+    "export_snapshot",
+    "get_active_sessions",
+    "get_admin_api_settings",
+    "get_alert_rules",
+    "get_api_error_stats",
+    "get_api_server_settings",
+    "get_app_info",
+    "get_app_lock_status",
+    "get_block_capacity",
+    "get_block_warning_settings",
+    "get_budget_progress",
+    "get_budget_settings",
+    "get_burn_rate",
+    "get_cache_hit_rate_settings",
+    "get_compaction_stats",
+    "get_cost_anomalies",
+    "get_current_stats",
+    "get_daily_activities",
+    "get_daily_summary_text",
+    "get_database_location",
+    "get_date_notes",
+    "get_dedupe_settings",
+    "get_deleted_items",
+    "get_dock_badge_settings",
+    "get_event_stream_settings",
+    "get_goal_progress",
+    "get_goals",
+    "get_health",
+    "get_insights",
+    "get_interrupt_stats",
+    "get_latency_stats",
+    "get_live_rate",
+    "get_menu_bar_settings",
+    "get_message_distribution",
+    "get_metrics_export_settings",
+    "get_model_aliases",
+    "get_model_trends",
+    "get_notification_history",
+    "get_official_usage",
+    "get_onboarding_state",
+    "get_otlp_export_settings",
+    "get_outlier_settings",
+    "get_outliers",
+    "get_overlay_settings",
+    "get_plan_limit_settings",
+    "get_plan_limit_status",
+    "get_plan_value",
+    "get_privacy_settings",
+    "get_profiles",
+    "get_provider_balance",
+    "get_provider_balances",
+    "get_provider_plans",
+    "get_providers",
+    "get_quick_stats",
+    "get_range_stats",
+    "get_rate_limit_stats",
+    "get_read_only_mode",
+    "get_recalculation_history",
+    "get_recent_errors",
+    "get_recent_logs",
+    "get_report_schedule",
+    "get_rolling_average",
+    "get_saved_queries",
+    "get_schema_version",
+    "get_service_tier_usage",
+    "get_session_context_usage",
+    "get_session_detail",
+    "get_session_distribution",
+    "get_stats_by_tag",
+    "get_streaks",
+    "get_sync_secret_status",
+    "get_sync_settings",
+    "get_today_provider_stats",
+    "get_today_stats",
+    "get_tool_call_stats",
+    "get_top_sessions",
+    "get_trash_settings",
+    "get_tray_icon_settings",
+    "get_triggered_alerts",
+    "get_update_settings",
+    "get_usage_block",
+    "get_usage_drift_report",
+    "get_usage_heatmap",
+    "get_webhook_deliveries",
+    "get_webhook_targets",
+    "get_year_summary",
+    "greet",
+    "has_admin_api_key",
+    "has_provider_api_key",
+    "list_reports",
+    "lock_app",
+    "report_activity",
+    "run_readonly_query",
+    "run_saved_query",
+    "search_messages",
+    "simulate_cost",
+    "test_provider",
+    "toggle_overlay_window",
+    "unlock_app",
+    "verify_data_integrity",
+];
+
+/// 只读模式状态，启动时确定，运行期间不变
+pub struct ReadOnlyMode {
+    enabled: bool,
+}
+
+impl ReadOnlyMode {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// 是否以只读模式启动：档案设置为只读，或带有 `--read-only` 启动参数
+pub fn read_only_requested(profile: &Profile, args: &[String]) -> bool {
+    profile.read_only || args.iter().any(|arg| arg == READ_ONLY_ARG)
+}
+
+/// 命令是否会修改数据或设置
+pub fn is_mutating_command(command: &str) -> bool {
+    !READ_ONLY_COMMANDS.contains(&command)
+}
+
+/// 为只读模式准备数据库副本，返回副本路径
+///
+/// 每次启动都从档案数据库重新复制，上次只读会话中采集的数据不会保留；
+/// 复制前确保档案数据库已有钥匙串命名空间，副本沿用同一命名空间读取凭证
+pub fn prepare_read_only_copy(
+    app_data_dir: &Path,
+    profile_name: &str,
+    source: &Path,
+) -> Result<PathBuf, RepositoryError> {
+    let copy_path = app_data_dir
+        .join(READ_ONLY_DIR)
+        .join(profile_name)
+        .join(DB_FILE_NAME);
+    if source.exists() {
+        Repository::new(source)?.keychain_namespace()?;
+    }
+    copy_database(source, &copy_path)?;
+    Ok(copy_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_mutating_command() {
+        for command in [
+            "set_budget_settings",
+            "delete_provider",
+            "import_snapshot",
+            "move_database",
+            "switch_profile",
+            "sync_now",
+            "recalculate_costs",
+            "poll_official_usage_now",
+            "test_webhook_target",
+            "generate_demo_data",
+            "set_sync_secret",
+            "unknown_command",
+        ] {
+            assert!(is_mutating_command(command), "{}", command);
+        }
+        for command in [
+            "get_current_stats",
+            "search_messages",
+            "export_snapshot",
+            "run_readonly_query",
+            "toggle_overlay_window",
+            "get_sync_secret_status",
+            "test_provider",
+        ] {
+            assert!(!is_mutating_command(command), "{}", command);
+        }
+    }
+}
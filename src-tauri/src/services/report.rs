@@ -0,0 +1,522 @@
+//! @file report.rs
+//! @description HTML 使用报告生成服务，汇总指定日期范围的用量并渲染为自包含 HTML 文件，
+//!              并负责定时周报 / 月报的周期计算与报告目录管理
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{
+    AppInfo, GeneratedReport, ModelUsage, ReportFile, ReportKind, ReportScheduleSettings,
+    SummaryLocale, UsageReport,
+};
+use crate::services::health::get_app_info;
+use crate::services::summary_text::range_summary_text;
+
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// 报告 HTML 模板
+///
+/// `{{name}}` 占位符由 render_html_report 替换；图表数据以 JSON 内联，
+/// 由页面内脚本绘制 SVG 柱状图，不依赖任何外部资源
+const REPORT_TEMPLATE: &str = r##"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Claude Token 使用报告 {{start_date}} ~ {{end_date}}</title>
+<style>
+  body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 0; padding: 32px; background: #f8fafc; color: #0f172a; }
+  h1 { font-size: 24px; margin: 0 0 4px; }
+  h2 { font-size: 18px; margin: 32px 0 12px; }
+  .meta { color: #64748b; font-size: 13px; }
+  .cards { display: grid; grid-template-columns: repeat(auto-fit, minmax(180px, 1fr)); gap: 12px; margin-top: 24px; }
+  .card { background: #fff; border: 1px solid #e2e8f0; border-radius: 8px; padding: 16px; }
+  .card .label { color: #64748b; font-size: 12px; }
+  .card .value { font-size: 22px; font-weight: 600; margin-top: 4px; }
+  table { width: 100%; border-collapse: collapse; background: #fff; border: 1px solid #e2e8f0; }
+  th, td { padding: 8px 12px; border-bottom: 1px solid #e2e8f0; text-align: right; font-size: 13px; }
+  th:first-child, td:first-child { text-align: left; }
+  th { background: #f1f5f9; font-weight: 600; }
+  #chart { background: #fff; border: 1px solid #e2e8f0; border-radius: 8px; padding: 16px; }
+  #chart svg { width: 100%; height: 220px; }
+  .empty { color: #94a3b8; font-size: 13px; }
+  .summary { font-size: 15px; margin: 16px 0 0; }
+</style>
+</head>
+<body>
+<h1>Claude Token 使用报告</h1>
+<div class="meta">统计周期：{{start_date}} ~ {{end_date}} · 生成时间：{{generated_at}} · {{app_info}}</div>
+<p class="summary">{{summary_text}}</p>
+
+<div class="cards">
+  <div class="card"><div class="label">总费用</div><div class="value">${{total_cost}}</div></div>
+  <div class="card"><div class="label">总 Token（输入 + 输出）</div><div class="value">{{total_tokens}}</div></div>
+  <div class="card"><div class="label">输入 / 输出 Token</div><div class="value">{{input_tokens}} / {{output_tokens}}</div></div>
+  <div class="card"><div class="label">缓存读取 / 创建 Token</div><div class="value">{{cache_read_tokens}} / {{cache_creation_tokens}}</div></div>
+  <div class="card"><div class="label">会话数</div><div class="value">{{sessions}}</div></div>
+  <div class="card"><div class="label">消息数</div><div class="value">{{messages}}</div></div>
+</div>
+
+<h2>每日费用</h2>
+<div id="chart"></div>
+
+<h2>模型明细</h2>
+<table>
+  <thead>
+    <tr><th>模型</th><th>输入 Token</th><th>输出 Token</th><th>缓存读取</th><th>缓存创建</th><th>消息数</th><th>费用（USD）</th></tr>
+  </thead>
+  <tbody>
+{{model_rows}}
+  </tbody>
+</table>
+
+<script>
+const DAILY = {{daily_json}};
+(function () {
+  const chart = document.getElementById("chart");
+  if (DAILY.length === 0) {
+    chart.innerHTML = '<div class="empty">该周期内暂无数据</div>';
+    return;
+  }
+  const width = 800, height = 220, padding = 24;
+  const max = Math.max(...DAILY.map((d) => d.cost_usd), 0.000001);
+  const barWidth = (width - padding * 2) / DAILY.length;
+  const ns = "http://www.w3.org/2000/svg";
+  const svg = document.createElementNS(ns, "svg");
+  svg.setAttribute("viewBox", "0 0 " + width + " " + height);
+  svg.setAttribute("preserveAspectRatio", "none");
+  DAILY.forEach((d, i) => {
+    const barHeight = (d.cost_usd / max) * (height - padding * 2);
+    const rect = document.createElementNS(ns, "rect");
+    rect.setAttribute("x", padding + i * barWidth + barWidth * 0.1);
+    rect.setAttribute("y", height - padding - barHeight);
+    rect.setAttribute("width", barWidth * 0.8);
+    rect.setAttribute("height", barHeight);
+    rect.setAttribute("fill", "#6366f1");
+    const title = document.createElementNS(ns, "title");
+    title.textContent = d.date + "  $" + d.cost_usd.toFixed(2) + "  " + (d.input_tokens + d.output_tokens) + " tokens";
+    rect.appendChild(title);
+    svg.appendChild(rect);
+  });
+  chart.appendChild(svg);
+})();
+</script>
+</body>
+</html>
+"##;
+
+/// 汇总指定日期范围的使用报告
+///
+/// 业务逻辑说明：
+/// 1. 查询范围内每日使用量，累加得到总计
+/// 2. 查询范围内按日期与模型的用量，按模型合并后按费用降序排列
+pub fn build_usage_report(
+    repository: &Repository,
+    start_date: &str,
+    end_date: &str,
+) -> Result<UsageReport, RepositoryError> {
+    let daily = repository.get_daily_activities(start_date, end_date)?;
+    let model_rows = repository.get_model_daily_usage(start_date, end_date)?;
+
+    let mut models: HashMap<String, ModelUsage> = HashMap::new();
+    for row in model_rows {
+        let usage = models
+            .entry(row.model.clone())
+            .or_insert_with(|| ModelUsage::new(row.model.clone()));
+        usage.input_tokens += row.input_tokens;
+        usage.output_tokens += row.output_tokens;
+        usage.cache_read_tokens += row.cache_read_tokens;
+        usage.cache_creation_tokens += row.cache_creation_tokens;
+        usage.cost_usd += row.cost_usd;
+        usage.message_count += row.message_count;
+    }
+    let mut models: Vec<ModelUsage> = models.into_values().collect();
+    models.sort_by(|a, b| {
+        b.cost_usd
+            .partial_cmp(&a.cost_usd)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    Ok(UsageReport {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        generated_at: Local::now().to_rfc3339(),
+        total_input_tokens: daily.iter().map(|day| day.input_tokens).sum(),
+        total_output_tokens: daily.iter().map(|day| day.output_tokens).sum(),
+        total_cache_read_tokens: models.iter().map(|model| model.cache_read_tokens).sum(),
+        total_cache_creation_tokens: models.iter().map(|model| model.cache_creation_tokens).sum(),
+        total_cost_usd: daily.iter().map(|day| day.cost_usd).sum(),
+        total_sessions: daily.iter().map(|day| day.session_count).sum(),
+        total_messages: daily.iter().map(|day| day.message_count).sum(),
+        models,
+        daily,
+        app_info: get_app_info(repository)?,
+        summary_text: range_summary_text(repository, start_date, end_date, SummaryLocale::ZhCn)?,
+    })
+}
+
+/// 将使用报告渲染为自包含 HTML
+pub fn render_html_report(report: &UsageReport) -> Result<String, ReportError> {
+    let model_rows = if report.models.is_empty() {
+        "    <tr><td colspan=\"7\" class=\"empty\">该周期内暂无数据</td></tr>".to_string()
+    } else {
+        report
+            .models
+            .iter()
+            .map(|model| {
+                format!(
+                    "    <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>${:.2}</td></tr>",
+                    escape_html(&model.model),
+                    format_number(model.input_tokens),
+                    format_number(model.output_tokens),
+                    format_number(model.cache_read_tokens),
+                    format_number(model.cache_creation_tokens),
+                    format_number(model.message_count),
+                    model.cost_usd
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    // 内联到 <script> 中，需避免数据里的 "</" 提前结束脚本标签
+    let daily_json = serde_json::to_string(&report.daily)?.replace("</", "<\\/");
+
+    Ok(REPORT_TEMPLATE
+        .replace("{{start_date}}", &escape_html(&report.start_date))
+        .replace("{{end_date}}", &escape_html(&report.end_date))
+        .replace("{{generated_at}}", &escape_html(&report.generated_at))
+        .replace("{{summary_text}}", &escape_html(&report.summary_text))
+        .replace(
+            "{{app_info}}",
+            &escape_html(&format_app_info(&report.app_info)),
+        )
+        .replace("{{total_cost}}", &format!("{:.2}", report.total_cost_usd))
+        .replace("{{total_tokens}}", &format_number(report.total_tokens()))
+        .replace(
+            "{{input_tokens}}",
+            &format_number(report.total_input_tokens),
+        )
+        .replace(
+            "{{output_tokens}}",
+            &format_number(report.total_output_tokens),
+        )
+        .replace(
+            "{{cache_read_tokens}}",
+            &format_number(report.total_cache_read_tokens),
+        )
+        .replace(
+            "{{cache_creation_tokens}}",
+            &format_number(report.total_cache_creation_tokens),
+        )
+        .replace("{{sessions}}", &format_number(report.total_sessions))
+        .replace("{{messages}}", &format_number(report.total_messages))
+        .replace("{{model_rows}}", &model_rows)
+        .replace("{{daily_json}}", &daily_json))
+}
+
+/// 报告页脚中的版本信息，例如 `Claude Token Monitor v1.0.0 (a1b2c3d4e5f6) · Schema 27`
+fn format_app_info(info: &AppInfo) -> String {
+    let mut text = format!("Claude Token Monitor v{}", info.build.version);
+    if let Some(commit) = &info.build.git_commit {
+        text.push_str(&format!(" ({})", commit));
+    }
+    text.push_str(&format!(" · Schema {}", info.schema_version));
+    text
+}
+
+/// 生成报告并写入指定路径
+pub fn generate_report(
+    repository: &Repository,
+    start_date: &str,
+    end_date: &str,
+    path: &Path,
+) -> Result<GeneratedReport, ReportError> {
+    let report = build_usage_report(repository, start_date, end_date)?;
+    let html = render_html_report(&report)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, html)?;
+
+    Ok(GeneratedReport {
+        path: path.display().to_string(),
+        start_date: report.start_date,
+        end_date: report.end_date,
+        generated_at: report.generated_at,
+        summary_text: report.summary_text,
+    })
+}
+
+/// 返回 `today` 时已结束、按设置应存在的定时报告周期
+///
+/// 周报为上一个完整周（周一至周日），月报为上一个自然月。
+/// 应用在周一或 1 日未运行时，之后首次检查仍会补生成最近一期
+pub fn due_report_periods(
+    settings: &ReportScheduleSettings,
+    today: NaiveDate,
+) -> Vec<(ReportKind, NaiveDate, NaiveDate)> {
+    let mut periods = Vec::new();
+
+    if settings.weekly {
+        let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        periods.push((
+            ReportKind::Weekly,
+            this_monday - Duration::days(7),
+            this_monday - Duration::days(1),
+        ));
+    }
+
+    if settings.monthly {
+        let last_month_end = today.with_day(1).unwrap_or(today) - Duration::days(1);
+        periods.push((
+            ReportKind::Monthly,
+            last_month_end.with_day(1).unwrap_or(last_month_end),
+            last_month_end,
+        ));
+    }
+
+    periods
+}
+
+/// 定时报告文件名，格式为 `<类型>_<开始日期>_<结束日期>.html`
+pub fn report_file_name(kind: ReportKind, start_date: NaiveDate, end_date: NaiveDate) -> String {
+    format!("{}_{}_{}.html", kind.as_str(), start_date, end_date)
+}
+
+/// 为报告目录中尚不存在的到期周期生成报告
+///
+/// # 返回
+/// 本次新生成的报告（已存在的周期不会重复生成）
+pub fn generate_due_reports(
+    repository: &Repository,
+    reports_dir: &Path,
+    settings: &ReportScheduleSettings,
+    today: NaiveDate,
+) -> Result<Vec<GeneratedReport>, ReportError> {
+    let mut generated = Vec::new();
+
+    for (kind, start_date, end_date) in due_report_periods(settings, today) {
+        let path = reports_dir.join(report_file_name(kind, start_date, end_date));
+        if path.exists() {
+            continue;
+        }
+        generated.push(generate_report(
+            repository,
+            &start_date.to_string(),
+            &end_date.to_string(),
+            &path,
+        )?);
+    }
+
+    Ok(generated)
+}
+
+/// 列出报告目录中的定时报告，按结束日期降序
+///
+/// 文件名不符合定时报告格式的文件会被忽略；目录不存在时返回空列表
+pub fn list_reports(reports_dir: &Path) -> Result<Vec<ReportFile>, ReportError> {
+    if !reports_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut reports = Vec::new();
+    for entry in std::fs::read_dir(reports_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let (kind, start_date, end_date) = match parse_report_file_name(&file_name) {
+            Some(parsed) => parsed,
+            None => continue,
+        };
+
+        reports.push(ReportFile {
+            kind,
+            file_name,
+            path: path.display().to_string(),
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            size_bytes: entry.metadata()?.len(),
+        });
+    }
+
+    reports.sort_by(|a, b| {
+        b.end_date
+            .cmp(&a.end_date)
+            .then_with(|| a.file_name.cmp(&b.file_name))
+    });
+    Ok(reports)
+}
+
+/// 解析定时报告文件名
+fn parse_report_file_name(file_name: &str) -> Option<(ReportKind, NaiveDate, NaiveDate)> {
+    let stem = file_name.strip_suffix(".html")?;
+    let mut parts = stem.split('_');
+    let kind = ReportKind::from_prefix(parts.next()?)?;
+    let start_date = NaiveDate::parse_from_str(parts.next()?, "%Y-%m-%d").ok()?;
+    let end_date = NaiveDate::parse_from_str(parts.next()?, "%Y-%m-%d").ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((kind, start_date, end_date))
+}
+
+/// 转义 HTML 特殊字符
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 千分位格式化整数
+fn format_number(value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut groups: Vec<&str> = digits
+        .as_bytes()
+        .rchunks(3)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+    groups.reverse();
+    let grouped = groups.join(",");
+    if value < 0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0), "0");
+        assert_eq!(format_number(999), "999");
+        assert_eq!(format_number(1234567), "1,234,567");
+        assert_eq!(format_number(-1000), "-1,000");
+    }
+
+    #[test]
+    fn test_render_html_report() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-report", None)
+            .expect("provider");
+
+        let record = MessageRecord::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            "claude-<script>".to_string(),
+            Local::now().to_rfc3339(),
+            MessageUsage {
+                input_tokens: 1200,
+                output_tokens: 300,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                thinking_tokens: 0,
+                cost_usd: 1.5,
+            },
+        );
+        repository
+            .insert_message_usage(provider.id, &record)
+            .expect("insert");
+
+        let today = Local::now().date_naive().to_string();
+        let report = build_usage_report(&repository, &today, &today).expect("report");
+        assert_eq!(report.total_tokens(), 1500);
+        assert_eq!(report.models.len(), 1);
+
+        let html = render_html_report(&report).expect("render");
+        assert!(html.contains("1,500"));
+        assert!(html.contains("$1.50"));
+        assert!(html.contains("claude-&lt;script&gt;"));
+        assert!(html.contains("共花费 $1.50，1 个会话"));
+        assert!(html.contains(&format!(
+            "Claude Token Monitor v{}",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(!html.contains("{{"));
+    }
+
+    #[test]
+    fn test_due_report_periods() {
+        let settings = ReportScheduleSettings {
+            weekly: true,
+            monthly: true,
+            notify: false,
+        };
+        // 2026-10-14 为周三
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).expect("date");
+        let periods = due_report_periods(&settings, today);
+
+        assert_eq!(
+            periods,
+            vec![
+                (
+                    ReportKind::Weekly,
+                    NaiveDate::from_ymd_opt(2026, 10, 5).expect("date"),
+                    NaiveDate::from_ymd_opt(2026, 10, 11).expect("date"),
+                ),
+                (
+                    ReportKind::Monthly,
+                    NaiveDate::from_ymd_opt(2026, 9, 1).expect("date"),
+                    NaiveDate::from_ymd_opt(2026, 9, 30).expect("date"),
+                ),
+            ]
+        );
+        assert!(due_report_periods(&ReportScheduleSettings::default(), today).is_empty());
+    }
+
+    #[test]
+    fn test_generate_due_reports_and_list() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let reports_dir = std::env::temp_dir().join(format!(
+            "claude-token-monitor-reports-{}",
+            std::process::id()
+        ));
+        let settings = ReportScheduleSettings {
+            weekly: true,
+            monthly: false,
+            notify: false,
+        };
+        let today = NaiveDate::from_ymd_opt(2026, 10, 12).expect("date");
+        std::fs::create_dir_all(&reports_dir).expect("reports dir");
+        std::fs::write(reports_dir.join("notes.html"), "ignored").expect("other file");
+
+        let generated =
+            generate_due_reports(&repository, &reports_dir, &settings, today).expect("generate");
+        assert_eq!(generated.len(), 1);
+        let again =
+            generate_due_reports(&repository, &reports_dir, &settings, today).expect("generate");
+        assert!(again.is_empty());
+
+        let reports = list_reports(&reports_dir).expect("list");
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, ReportKind::Weekly);
+        assert_eq!(reports[0].file_name, "weekly_2026-10-05_2026-10-11.html");
+
+        std::fs::remove_dir_all(&reports_dir).expect("cleanup");
+    }
+}
@@ -0,0 +1,84 @@
+//! @file report_scheduler.rs
+//! @description 定时报告调度服务，按设置在后台生成周报 / 月报
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::Local;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Repository;
+use crate::models::{NotificationKind, ReportScheduleSettings};
+use crate::services::notifier;
+use crate::services::report::generate_due_reports;
+
+/// 调度检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 定时报告存放目录（应用数据目录下的 reports）
+pub fn reports_dir(app: &AppHandle) -> Result<PathBuf, tauri::Error> {
+    Ok(app.path().app_data_dir()?.join("reports"))
+}
+
+/// 启动定时报告调度线程
+///
+/// 启动时立即检查一次，之后每小时检查；到期且尚未生成的报告会被补生成
+pub fn start_report_scheduler(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        run_scheduled_reports(&app);
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+/// 执行一次定时报告检查
+fn run_scheduled_reports(app: &AppHandle) {
+    let repository = app.state::<Repository>();
+    let settings: ReportScheduleSettings = match repository.get_setting() {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("读取定时报告设置失败: {}", e);
+            return;
+        }
+    };
+    if !settings.weekly && !settings.monthly {
+        return;
+    }
+
+    let dir = match reports_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            tracing::error!("获取报告目录失败: {}", e);
+            return;
+        }
+    };
+
+    let generated =
+        match generate_due_reports(&repository, &dir, &settings, Local::now().date_naive()) {
+            Ok(generated) => generated,
+            Err(e) => {
+                tracing::error!("定时报告生成失败: {}", e);
+                return;
+            }
+        };
+
+    for report in generated {
+        tracing::info!("定时报告已生成: {}", report.path);
+        let body = format!(
+            "{} ~ {} 使用报告已生成：{}",
+            report.start_date, report.end_date, report.summary_text
+        );
+
+        notifier::notify(
+            app,
+            NotificationKind::Report,
+            "使用报告",
+            &body,
+            &report,
+            settings.notify,
+        );
+        if let Err(e) = app.emit("report-generated", report) {
+            tracing::error!("发送 report-generated 事件失败: {}", e);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! @file rpc_server.rs
+//! @description 行分隔 JSON-RPC stdio 服务，向编辑器与脚本直接暴露仓储查询
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::db::Repository;
+use crate::models::{SessionOrder, DEFAULT_TOP_SESSIONS_LIMIT};
+use crate::services::json_rpc::{self, method_not_found, DispatchResult};
+
+/// 告警记录默认返回条数
+const DEFAULT_TRIGGERED_ALERTS_LIMIT: usize = 50;
+
+/// 可调用的方法及说明，供 `rpc.discover` 返回
+const METHODS: &[(&str, &str)] = &[
+    ("rpc.discover", "List available methods."),
+    ("ping", "Health check, returns an empty object."),
+    ("get_today_stats", "Token usage and cost recorded today."),
+    ("get_current_stats", "All-time aggregated statistics."),
+    (
+        "get_providers",
+        "Providers, optionally only active ones. Params: active_only?",
+    ),
+    (
+        "get_daily_activities",
+        "Daily usage. Params: start_date, end_date",
+    ),
+    (
+        "get_model_daily_usage",
+        "Daily usage per model. Params: start_date, end_date",
+    ),
+    (
+        "get_usage_heatmap",
+        "Usage by weekday and hour. Params: start_date, end_date",
+    ),
+    (
+        "get_range_cost",
+        "Total cost in a date range. Params: start_date, end_date",
+    ),
+    (
+        "get_cost_anomalies",
+        "Detected cost anomalies. Params: start_date, end_date",
+    ),
+    (
+        "get_top_sessions",
+        "Top sessions. Params: start_date, end_date, limit?, order_by?",
+    ),
+    (
+        "get_active_sessions",
+        "Sessions active since a timestamp. Params: since",
+    ),
+    (
+        "get_stats_by_tag",
+        "Usage grouped by tag. Params: start_date?, end_date?",
+    ),
+    ("get_alert_rules", "Configured alert rules."),
+    (
+        "get_triggered_alerts",
+        "Triggered alerts. Params: limit?, active_only?",
+    ),
+    (
+        "get_notification_history",
+        "Sent notifications. Params: start_date, end_date",
+    ),
+];
+
+/// 运行 stdio 服务，直到标准输入关闭
+pub fn run_stdio(repository: &Repository) -> std::io::Result<()> {
+    json_rpc::run_stdio(|method, params| dispatch(repository, method, params))
+}
+
+/// 处理单条 JSON-RPC 消息
+///
+/// # 返回
+/// 需要回复时返回响应 JSON；通知消息（无 id）返回 None
+pub fn handle_message(repository: &Repository, line: &str) -> Option<String> {
+    json_rpc::handle_message(line, |method, params| dispatch(repository, method, params))
+}
+
+#[derive(Deserialize)]
+struct DateRangeParams {
+    start_date: String,
+    end_date: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct OptionalDateRangeParams {
+    start_date: Option<String>,
+    end_date: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ProvidersParams {
+    active_only: bool,
+}
+
+#[derive(Deserialize)]
+struct TopSessionsParams {
+    start_date: String,
+    end_date: String,
+    #[serde(default)]
+    limit: Option<i64>,
+    #[serde(default)]
+    order_by: SessionOrder,
+}
+
+#[derive(Deserialize)]
+struct ActiveSessionsParams {
+    since: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TriggeredAlertsParams {
+    limit: Option<usize>,
+    active_only: bool,
+}
+
+/// 按方法名分发到仓储查询
+///
+/// 参数错误返回 -32602，查询失败返回 -32000
+fn dispatch(repository: &Repository, method: &str, params: Value) -> DispatchResult {
+    match method {
+        "rpc.discover" => Ok(json!({
+            "methods": METHODS
+                .iter()
+                .map(|(name, description)| json!({ "name": name, "description": description }))
+                .collect::<Vec<_>>(),
+        })),
+        "ping" => Ok(json!({})),
+        "get_today_stats" => to_value(repository.get_today_stats()),
+        "get_current_stats" => to_value(repository.get_current_stats()),
+        "get_providers" => {
+            let p: ProvidersParams = parse_optional_params(params)?;
+            to_value(repository.get_all_providers(p.active_only))
+        }
+        "get_daily_activities" => {
+            let p = date_range(params)?;
+            to_value(repository.get_daily_activities(&p.start_date, &p.end_date))
+        }
+        "get_model_daily_usage" => {
+            let p = date_range(params)?;
+            to_value(repository.get_model_daily_usage(&p.start_date, &p.end_date))
+        }
+        "get_usage_heatmap" => {
+            let p = date_range(params)?;
+            to_value(repository.get_usage_heatmap(&p.start_date, &p.end_date))
+        }
+        "get_range_cost" => {
+            let p = date_range(params)?;
+            to_value(repository.get_range_cost(&p.start_date, &p.end_date))
+        }
+        "get_cost_anomalies" => {
+            let p = date_range(params)?;
+            to_value(repository.get_cost_anomalies(&p.start_date, &p.end_date))
+        }
+        "get_top_sessions" => {
+            let p: TopSessionsParams = parse_params(params)?;
+            validate_date(&p.start_date)?;
+            validate_date(&p.end_date)?;
+            let limit = p.limit.unwrap_or(DEFAULT_TOP_SESSIONS_LIMIT).max(1);
+            to_value(repository.get_top_sessions(&p.start_date, &p.end_date, limit, p.order_by))
+        }
+        "get_active_sessions" => {
+            let p: ActiveSessionsParams = parse_params(params)?;
+            to_value(repository.get_active_sessions(&p.since))
+        }
+        "get_stats_by_tag" => {
+            let p: OptionalDateRangeParams = parse_optional_params(params)?;
+            for date in [&p.start_date, &p.end_date].into_iter().flatten() {
+                validate_date(date)?;
+            }
+            to_value(repository.get_stats_by_tag(p.start_date.as_deref(), p.end_date.as_deref()))
+        }
+        "get_alert_rules" => to_value(repository.get_alert_rules()),
+        "get_triggered_alerts" => {
+            let p: TriggeredAlertsParams = parse_optional_params(params)?;
+            to_value(repository.get_triggered_alerts(
+                p.limit.unwrap_or(DEFAULT_TRIGGERED_ALERTS_LIMIT),
+                p.active_only,
+            ))
+        }
+        "get_notification_history" => {
+            let p = date_range(params)?;
+            to_value(repository.get_notification_history(&p.start_date, &p.end_date))
+        }
+        _ => Err(method_not_found(method)),
+    }
+}
+
+/// 解析按名称传递的参数
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    if !params.is_object() {
+        return Err((-32602, "params must be an object".to_string()));
+    }
+    serde_json::from_value(params).map_err(|e| (-32602, format!("Invalid params: {}", e)))
+}
+
+/// 解析全部字段可选的参数，允许省略 params
+fn parse_optional_params<T: DeserializeOwned + Default>(params: Value) -> Result<T, (i64, String)> {
+    if params.is_null() {
+        return Ok(T::default());
+    }
+    parse_params(params)
+}
+
+fn date_range(params: Value) -> Result<DateRangeParams, (i64, String)> {
+    let range: DateRangeParams = parse_params(params)?;
+    validate_date(&range.start_date)?;
+    validate_date(&range.end_date)?;
+    Ok(range)
+}
+
+fn validate_date(value: &str) -> Result<(), (i64, String)> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| {
+            (
+                -32602,
+                format!("Invalid date (expected YYYY-MM-DD): {}", value),
+            )
+        })
+}
+
+fn to_value<T: serde::Serialize, E: std::fmt::Display>(result: Result<T, E>) -> DispatchResult {
+    result
+        .map_err(|e| e.to_string())
+        .and_then(|data| serde_json::to_value(data).map_err(|e| e.to_string()))
+        .map_err(|message| (-32000, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(repository: &Repository, message: Value) -> Value {
+        let response = handle_message(repository, &message.to_string()).expect("response");
+        serde_json::from_str(&response).expect("json")
+    }
+
+    #[test]
+    fn test_discover_and_queries() {
+        let repository = Repository::new_in_memory().expect("repo");
+        repository
+            .upsert_provider("sk-api", None)
+            .expect("provider");
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":1,"method":"rpc.discover"}),
+        );
+        let methods = response["result"]["methods"].as_array().expect("methods");
+        assert_eq!(methods.len(), METHODS.len());
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":2,"method":"get_providers"}),
+        );
+        assert_eq!(
+            response["result"].as_array().map(|list| list.len()),
+            Some(1)
+        );
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":3,"method":"get_daily_activities","params":{"start_date":"2026-10-01","end_date":"2026-10-07"}}),
+        );
+        assert_eq!(response["result"], json!([]));
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":4,"method":"get_top_sessions","params":{"start_date":"2026-10-01","end_date":"2026-10-07","order_by":"tokens"}}),
+        );
+        assert!(response["result"].is_array());
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":5,"method":"get_today_stats"}),
+        );
+        assert!(response["result"].is_object());
+
+        let notification = json!({"jsonrpc":"2.0","method":"get_today_stats"});
+        assert!(handle_message(&repository, &notification.to_string()).is_none());
+    }
+
+    #[test]
+    fn test_errors() {
+        let repository = Repository::new_in_memory().expect("repo");
+
+        let response: Value =
+            serde_json::from_str(&handle_message(&repository, "{oops").expect("response"))
+                .expect("json");
+        assert_eq!(response["error"]["code"], -32700);
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":1,"method":"unknown"}),
+        );
+        assert_eq!(response["error"]["code"], -32601);
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":2,"method":"get_daily_activities"}),
+        );
+        assert_eq!(response["error"]["code"], -32602);
+
+        let response = request(
+            &repository,
+            json!({"jsonrpc":"2.0","id":3,"method":"get_range_cost","params":{"start_date":"2026-13-01","end_date":"2026-10-07"}}),
+        );
+        assert_eq!(response["error"]["code"], -32602);
+    }
+}
@@ -0,0 +1,111 @@
+//! @file session_detail.rs
+//! @description 会话明细服务，返回会话的逐条消息及每条消息的缓存经济性
+//! @author Atlas.oi
+//! @date 2026-10-17
+use crate::db::{Repository, RepositoryError};
+use crate::models::{SessionDetail, SessionMessageDetail};
+use crate::services::pricing::{pricing_date, PricingService};
+
+/// 获取会话明细，会话不存在时返回 None
+///
+/// 每条消息按 PricingService 计算有效输入单价、缓存写入溢价与净节省，
+/// 便于找出从缓存中获益或未回本的请求
+pub fn get_session_detail(
+    repository: &Repository,
+    pricing: &PricingService,
+    session_id: &str,
+) -> Result<Option<SessionDetail>, RepositoryError> {
+    let messages = repository.get_session_messages(session_id)?;
+    if messages.is_empty() {
+        return Ok(None);
+    }
+    let title = repository
+        .get_session_title(session_id)?
+        .map(|(title, _)| title);
+
+    let messages: Vec<SessionMessageDetail> = messages
+        .into_iter()
+        .map(|message| SessionMessageDetail {
+            cache_economics: pricing.cache_economics(
+                &message.model,
+                pricing_date(&message.created_at),
+                message.input_tokens,
+                message.cache_read_tokens,
+                message.cache_creation_tokens,
+            ),
+            message,
+        })
+        .collect();
+
+    let economics = messages.iter().filter_map(|m| m.cache_economics.as_ref());
+    let cache_write_premium_usd = economics.clone().map(|e| e.cache_write_premium_usd).sum();
+    let net_savings_usd = economics.map(|e| e.net_savings_usd).sum();
+
+    Ok(Some(SessionDetail {
+        session_id: session_id.to_string(),
+        title,
+        messages,
+        cache_write_premium_usd,
+        net_savings_usd,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage, SessionTitleSource};
+
+    #[test]
+    fn test_get_session_detail() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-detail", None)
+            .expect("provider");
+        let pricing = PricingService::new();
+
+        for (index, (model, cache_read_tokens)) in [
+            ("claude-3-opus", 1_000_000),
+            ("unknown-model", 0),
+            ("claude-3-opus", 500_000),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let record = MessageRecord::new(
+                "session-1".to_string(),
+                format!("message-{}", index),
+                model.to_string(),
+                format!("2026-10-17T10:0{}:00+00:00", index),
+                MessageUsage {
+                    input_tokens: 1_000,
+                    cache_read_tokens: *cache_read_tokens,
+                    ..Default::default()
+                },
+            );
+            repository
+                .insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+        repository
+            .upsert_session_title("session-1", "Refactor", SessionTitleSource::Summary)
+            .expect("title");
+
+        let detail = get_session_detail(&repository, &pricing, "session-1")
+            .expect("detail")
+            .expect("session");
+        assert_eq!(detail.title.as_deref(), Some("Refactor"));
+        assert_eq!(detail.messages.len(), 3);
+        assert_eq!(detail.messages[0].message.message_id, "message-0");
+        assert!(detail.messages[1].cache_economics.is_none());
+        // 1.5M 缓存读取 × (15 - 1.5) / 1M
+        assert!((detail.net_savings_usd - 20.25).abs() < 1e-9);
+
+        let value = serde_json::to_value(&detail.messages[0]).expect("serialize");
+        assert_eq!(value["session_id"], "session-1");
+        assert!(value["cache_economics"]["net_savings_usd"].is_number());
+
+        assert!(get_session_detail(&repository, &pricing, "missing")
+            .expect("detail")
+            .is_none());
+    }
+}
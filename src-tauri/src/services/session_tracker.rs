@@ -0,0 +1,86 @@
+//! @file session_tracker.rs
+//! @description 活跃会话跟踪服务，定期检测并发送 session-started / session-ended / context-warning 事件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Repository;
+use crate::models::NotificationKind;
+use crate::services::active_sessions::{
+    diff_active_sessions, get_active_sessions, DEFAULT_ACTIVE_MINUTES,
+};
+use crate::services::context_usage::get_session_context_usage;
+use crate::services::notifier;
+use crate::services::pricing::PricingService;
+
+/// 检测间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 启动活跃会话跟踪线程
+///
+/// 启动时已在进行中的会话同样会发送 session-started；
+/// 活跃会话的上下文占用接近上限时发送一次 context-warning
+pub fn start_session_tracker(app: AppHandle) {
+    std::thread::spawn(move || {
+        let pricing = PricingService::new();
+        let mut previous = Vec::new();
+        let mut warned = HashSet::new();
+        loop {
+            let repository = app.state::<Repository>();
+            match get_active_sessions(&repository, Utc::now(), DEFAULT_ACTIVE_MINUTES) {
+                Ok(current) => {
+                    let changes = diff_active_sessions(&previous, &current);
+                    for session in changes.started {
+                        tracing::debug!("会话开始: {}", session.session_id);
+                        if let Err(e) = app.emit("session-started", session) {
+                            tracing::error!("发送 session-started 事件失败: {}", e);
+                        }
+                    }
+                    for session in changes.ended {
+                        tracing::debug!("会话结束: {}", session.session_id);
+                        warned.remove(&session.session_id);
+                        if let Err(e) = app.emit("session-ended", session) {
+                            tracing::error!("发送 session-ended 事件失败: {}", e);
+                        }
+                    }
+
+                    for session in &current {
+                        if warned.contains(&session.session_id) {
+                            continue;
+                        }
+                        match get_session_context_usage(&repository, &pricing, &session.session_id)
+                        {
+                            Ok(Some(usage)) if usage.near_limit => {
+                                warned.insert(session.session_id.clone());
+                                notifier::notify(
+                                    &app,
+                                    NotificationKind::ContextWarning,
+                                    "上下文占用提醒",
+                                    &format!(
+                                        "会话 {} 的上下文已占用约 {:.0}%",
+                                        usage.session_id,
+                                        usage.usage_ratio * 100.0
+                                    ),
+                                    &usage,
+                                    false,
+                                );
+                                if let Err(e) = app.emit("context-warning", usage) {
+                                    tracing::error!("发送 context-warning 事件失败: {}", e);
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => tracing::error!("上下文占用计算失败: {}", e),
+                        }
+                    }
+                    previous = current;
+                }
+                Err(e) => tracing::error!("活跃会话检测失败: {}", e),
+            }
+            std::thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
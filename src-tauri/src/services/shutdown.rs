@@ -0,0 +1,180 @@
+//! @file shutdown.rs
+//! @description 应用退出流程，停止文件监控、等待采集工作线程写完当前批次并在关闭前检查点数据库
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::db::Repository;
+use crate::services::file_watcher::FileWatcher;
+
+/// 退出时等待进行中采集任务的最长时间，超时后不再等待直接关闭
+pub const INGEST_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 进行中的采集任务计数
+///
+/// 采集工作线程处理每批任务前通过 begin 登记，结束时（守卫释放）注销；
+/// 退出流程先调用 close 拒绝新任务，再通过 wait_idle 等待已有任务完成
+#[derive(Default)]
+pub struct IngestTracker {
+    active: Mutex<usize>,
+    idle: Condvar,
+    closed: AtomicBool,
+}
+
+impl IngestTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一个采集任务；退出流程开始后返回 None，调用方应放弃本次采集
+    pub fn begin(&self) -> Option<IngestGuard<'_>> {
+        if self.closed.load(Ordering::SeqCst) {
+            return None;
+        }
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        // 加锁后再检查一次，避免与 close + wait_idle 交错时漏等
+        if self.closed.load(Ordering::SeqCst) {
+            return None;
+        }
+        *active += 1;
+        Some(IngestGuard { tracker: self })
+    }
+
+    /// 拒绝后续新的采集任务
+    pub fn close(&self) {
+        let _active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    /// 重新接受采集任务（如切换档案完成后）
+    pub fn reopen(&self) {
+        self.closed.store(false, Ordering::SeqCst);
+    }
+
+    /// 当前进行中的采集任务数
+    pub fn active(&self) -> usize {
+        *self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 等待全部采集任务结束；超时返回 false
+    pub fn wait_idle(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        while *active > 0 {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            active = self
+                .idle
+                .wait_timeout(active, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        true
+    }
+
+    fn finish(&self) {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        *active = active.saturating_sub(1);
+        if *active == 0 {
+            self.idle.notify_all();
+        }
+    }
+}
+
+/// 采集任务守卫，释放时注销任务（包括线程 panic 展开的情况）
+pub struct IngestGuard<'a> {
+    tracker: &'a IngestTracker,
+}
+
+impl Drop for IngestGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.finish();
+    }
+}
+
+/// 在阻塞线程池中执行后台任务（如 Webhook 推送），并登记到 IngestTracker
+///
+/// 退出流程会与采集任务一起等待它完成；退出流程已开始时放弃执行
+pub fn spawn_tracked<F>(app: &AppHandle, task: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let tracker = app.state::<IngestTracker>();
+        let Some(_guard) = tracker.begin() else {
+            tracing::warn!("应用正在退出，放弃后台任务");
+            return;
+        };
+        task();
+    });
+}
+
+/// 应用退出时调用
+///
+/// 业务逻辑说明：
+/// 1. 停止文件监控，不再产生新的文件变更事件
+/// 2. 拒绝新的采集任务，并最多等待 INGEST_DRAIN_TIMEOUT 让进行中的写入完成
+/// 3. 检查点数据库，确保退出后数据库文件完整
+pub fn shutdown(app: &AppHandle) {
+    tracing::info!("应用退出：开始关闭");
+    if let Some(watcher) = app.try_state::<Mutex<FileWatcher>>() {
+        match watcher.lock() {
+            Ok(mut watcher) => watcher.stop(),
+            Err(e) => tracing::error!("停止文件监控失败: {}", e),
+        }
+    }
+
+    if let Some(tracker) = app.try_state::<IngestTracker>() {
+        tracker.close();
+        if !tracker.wait_idle(INGEST_DRAIN_TIMEOUT) {
+            tracing::warn!("等待采集任务超时，仍有 {} 个任务未完成", tracker.active());
+        }
+    }
+
+    if let Some(repository) = app.try_state::<Repository>() {
+        if let Err(e) = repository.close() {
+            tracing::error!("数据库关闭失败: {}", e);
+        }
+    }
+    tracing::info!("应用退出：关闭完成");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_ingest_tracker_waits_for_active_tasks() {
+        let tracker = Arc::new(IngestTracker::new());
+        let guard = tracker.begin().expect("guard");
+        assert_eq!(tracker.active(), 1);
+        assert!(!tracker.wait_idle(Duration::from_millis(10)));
+
+        let worker = {
+            let tracker = tracker.clone();
+            let (started_tx, started_rx) = std::sync::mpsc::channel();
+            let handle = std::thread::spawn(move || {
+                let _guard = tracker.begin().expect("guard");
+                started_tx.send(()).expect("send");
+                std::thread::sleep(Duration::from_millis(50));
+            });
+            started_rx.recv().expect("recv");
+            handle
+        };
+        drop(guard);
+
+        tracker.close();
+        assert!(tracker.begin().is_none());
+        assert!(tracker.wait_idle(Duration::from_secs(5)));
+        assert_eq!(tracker.active(), 0);
+        worker.join().expect("join");
+    }
+}
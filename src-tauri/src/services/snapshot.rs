@@ -0,0 +1,189 @@
+//! @file snapshot.rs
+//! @description 数据快照导出 / 导入服务，用于合并多台设备上的使用历史
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::path::Path;
+
+use chrono::Utc;
+use thiserror::Error;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{DedupeSettings, Snapshot, SnapshotExport, SnapshotImportSummary};
+
+/// 当前快照格式版本
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] RepositoryError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("不支持的快照格式版本: {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// 导出当前数据库的快照
+pub fn build_snapshot(repository: &Repository) -> Result<Snapshot, SnapshotError> {
+    Ok(Snapshot {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        ..repository.export_snapshot()?
+    })
+}
+
+/// 导出数据快照并写入 path
+pub fn export_snapshot(
+    repository: &Repository,
+    path: &Path,
+) -> Result<SnapshotExport, SnapshotError> {
+    let snapshot = build_snapshot(repository)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec(&snapshot)?)?;
+
+    Ok(SnapshotExport {
+        path: path.display().to_string(),
+        message_count: snapshot.messages.len(),
+        exported_at: snapshot.exported_at,
+    })
+}
+
+/// 读取 path 中的快照并合并到本地数据库
+pub fn import_snapshot(
+    repository: &Repository,
+    path: &Path,
+) -> Result<SnapshotImportSummary, SnapshotError> {
+    let snapshot = read_snapshot(&std::fs::read(path)?)?;
+    merge_snapshot(repository, &snapshot)
+}
+
+/// 将快照合并到本地数据库，消息去重沿用当前的去重策略设置
+pub fn merge_snapshot(
+    repository: &Repository,
+    snapshot: &Snapshot,
+) -> Result<SnapshotImportSummary, SnapshotError> {
+    let dedupe: DedupeSettings = repository.get_setting()?;
+
+    let summary = repository.import_snapshot(snapshot, dedupe.policy)?;
+    tracing::info!(
+        "快照导入完成: 新增供应商 {} 个，新增消息 {} 条，跳过 {} 条",
+        summary.providers_added,
+        summary.messages_added,
+        summary.messages_skipped
+    );
+    Ok(summary)
+}
+
+/// 解析快照内容并校验格式版本
+pub fn read_snapshot(content: &[u8]) -> Result<Snapshot, SnapshotError> {
+    let snapshot: Snapshot = serde_json::from_slice(content)?;
+    if snapshot.format_version == 0 || snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(snapshot.format_version));
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DateNote, MessageRecord, MessageUsage, TagTarget};
+
+    fn record(message_id: &str, created_at: &str) -> MessageRecord {
+        MessageRecord::new(
+            "session-1".to_string(),
+            message_id.to_string(),
+            "claude-3-opus".to_string(),
+            created_at.to_string(),
+            MessageUsage {
+                input_tokens: 100,
+                output_tokens: 10,
+                cost_usd: 1.0,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_merges_history() {
+        let now = Utc::now().to_rfc3339();
+        let desktop = Repository::new_in_memory().expect("repo");
+        let provider = desktop
+            .upsert_provider("sk-shared", None)
+            .expect("provider");
+        desktop
+            .insert_message_usage(provider.id, &record("message-1", &now))
+            .expect("insert");
+        desktop
+            .insert_message_usage(provider.id, &record("message-2", &now))
+            .expect("insert");
+        desktop
+            .add_tag("client-A", &TagTarget::Session("session-1".to_string()))
+            .expect("tag");
+        desktop
+            .set_date_note("2026-10-01", "desktop")
+            .expect("note");
+
+        let laptop = Repository::new_in_memory().expect("repo");
+        let provider = laptop.upsert_provider("sk-shared", None).expect("provider");
+        laptop
+            .insert_message_usage(provider.id, &record("message-2", &now))
+            .expect("insert");
+        laptop
+            .insert_message_usage(provider.id, &record("message-3", &now))
+            .expect("insert");
+
+        let snapshot = Snapshot {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            ..desktop.export_snapshot().expect("export")
+        };
+        let content = serde_json::to_vec(&snapshot).expect("serialize");
+        let snapshot = read_snapshot(&content).expect("read");
+        assert_eq!(snapshot.messages.len(), 2);
+
+        let summary = laptop
+            .import_snapshot(&snapshot, Default::default())
+            .expect("import");
+        assert_eq!(summary.providers_added, 0);
+        assert_eq!(summary.messages_added, 1);
+        assert_eq!(summary.messages_skipped, 1);
+
+        let stats = laptop.get_current_stats().expect("stats");
+        assert_eq!(stats.total_messages, 3);
+        assert!(
+            crate::services::integrity::verify_data_integrity(&laptop)
+                .expect("integrity")
+                .is_consistent
+        );
+        assert_eq!(
+            laptop
+                .get_date_notes("2026-10-01", "2026-10-01")
+                .expect("notes"),
+            vec![DateNote {
+                date: "2026-10-01".to_string(),
+                note: "desktop".to_string(),
+                updated_at: snapshot.date_notes[0].updated_at.clone(),
+            }]
+        );
+        assert_eq!(laptop.get_stats_by_tag(None, None).expect("tags").len(), 1);
+
+        let summary = laptop
+            .import_snapshot(&snapshot, Default::default())
+            .expect("import");
+        assert_eq!(summary.messages_added, 0);
+        assert_eq!(summary.other_rows_merged, 0);
+    }
+
+    #[test]
+    fn test_read_snapshot_rejects_unknown_version() {
+        let content = br#"{"format_version": 99}"#;
+        assert!(matches!(
+            read_snapshot(content),
+            Err(SnapshotError::UnsupportedVersion(99))
+        ));
+    }
+}
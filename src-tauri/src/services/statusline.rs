@@ -0,0 +1,90 @@
+//! @file statusline.rs
+//! @description Claude Code statusLine 输出，生成单行使用量摘要
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::Utc;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{TodayStats, UsageBlock};
+use crate::services::usage_block::get_current_block;
+
+/// 读取数据库并生成状态行
+pub fn build_statusline(repository: &Repository) -> Result<String, RepositoryError> {
+    let today = repository.get_today_stats()?;
+    let block = get_current_block(repository, Utc::now())?;
+    Ok(format_statusline(&today, block.as_ref()))
+}
+
+/// 格式化状态行：今日费用 | 区块剩余时间 | 缓存命中率
+///
+/// Claude Code 只显示标准输出的第一行，因此结果不包含换行
+pub fn format_statusline(today: &TodayStats, block: Option<&UsageBlock>) -> String {
+    let block = match block {
+        Some(block) => format!("区块剩余 {}", format_remaining(block.remaining_minutes)),
+        None => "无活跃区块".to_string(),
+    };
+
+    format!(
+        "今日 ${:.2} | {} | 缓存命中 {:.0}%",
+        today.cost_usd,
+        block,
+        today.cache_hit_rate * 100.0
+    )
+}
+
+fn format_remaining(minutes: i64) -> String {
+    let minutes = minutes.max(0);
+    if minutes >= 60 {
+        format!("{}h{:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today_stats(cost_usd: f64, cache_hit_rate: f64) -> TodayStats {
+        TodayStats {
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd,
+            session_count: 0,
+            message_count: 0,
+            cache_hit_rate,
+            api_equivalent_cost_usd: cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_format_statusline() {
+        let block = UsageBlock {
+            start_at: "2026-10-17T09:00:00+00:00".to_string(),
+            end_at: "2026-10-17T14:00:00+00:00".to_string(),
+            last_message_at: "2026-10-17T10:05:00Z".to_string(),
+            remaining_minutes: 133,
+            total_tokens: 100,
+            cost_usd: 1.0,
+            message_count: 1,
+        };
+
+        assert_eq!(
+            format_statusline(&today_stats(3.456, 0.871), Some(&block)),
+            "今日 $3.46 | 区块剩余 2h13m | 缓存命中 87%"
+        );
+        assert_eq!(
+            format_statusline(&today_stats(0.0, 0.0), None),
+            "今日 $0.00 | 无活跃区块 | 缓存命中 0%"
+        );
+    }
+
+    #[test]
+    fn test_build_statusline_empty_db() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let line = build_statusline(&repository).expect("statusline");
+        assert!(line.contains("无活跃区块"));
+    }
+}
@@ -0,0 +1,151 @@
+//! @file streaks.rs
+//! @description 连续活跃天数与累计 Token 里程碑计算服务
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{Duration, NaiveDate};
+
+use crate::models::{DailyActivity, TokenMilestone, UsageStreaks};
+
+/// 累计 Token 里程碑阈值
+pub const TOKEN_MILESTONES: [i64; 5] = [
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+];
+
+/// 根据每日活动记录计算连续天数与里程碑
+///
+/// 业务逻辑说明：
+/// 1. 有消息记录的日期视为活跃日（输入需已按日期聚合），按日期升序遍历
+/// 2. 相邻活跃日相差 1 天则延续连续区间，否则重新计数
+/// 3. 最后一个区间截止到今天或昨天时作为当前连续天数
+/// 4. 累加每日 Token，记录首次跨过各里程碑阈值的日期
+pub fn calculate_streaks(activities: &[DailyActivity], today: NaiveDate) -> UsageStreaks {
+    let mut active: Vec<(NaiveDate, i64)> = activities
+        .iter()
+        .filter(|activity| activity.message_count > 0)
+        .filter_map(|activity| {
+            NaiveDate::parse_from_str(&activity.date, "%Y-%m-%d")
+                .ok()
+                .map(|date| (date, activity.total_tokens()))
+        })
+        .collect();
+    active.sort_by_key(|(date, _)| *date);
+
+    let mut longest: Option<(NaiveDate, NaiveDate, i64)> = None;
+    let mut run_start: Option<NaiveDate> = None;
+    let mut run_length = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    let mut total_tokens = 0;
+    let mut milestones: Vec<TokenMilestone> = TOKEN_MILESTONES
+        .iter()
+        .map(|&threshold_tokens| TokenMilestone {
+            threshold_tokens,
+            reached_on: None,
+        })
+        .collect();
+
+    for (date, tokens) in &active {
+        match previous {
+            Some(prev) if *date - prev == Duration::days(1) => run_length += 1,
+            _ => {
+                run_start = Some(*date);
+                run_length = 1;
+            }
+        }
+        previous = Some(*date);
+
+        let longest_length = longest.map(|(_, _, length)| length).unwrap_or(0);
+        if run_length > longest_length {
+            if let Some(start) = run_start {
+                longest = Some((start, *date, run_length));
+            }
+        }
+
+        total_tokens += tokens;
+        for milestone in milestones.iter_mut() {
+            if milestone.reached_on.is_none() && total_tokens >= milestone.threshold_tokens {
+                milestone.reached_on = Some(date.to_string());
+            }
+        }
+    }
+
+    let current_streak_days = match previous {
+        Some(last) if today - last <= Duration::days(1) => run_length,
+        _ => 0,
+    };
+
+    let next_milestone_tokens = milestones
+        .iter()
+        .find(|milestone| milestone.reached_on.is_none())
+        .map(|milestone| milestone.threshold_tokens);
+
+    UsageStreaks {
+        current_streak_days,
+        longest_streak_days: longest.map(|(_, _, length)| length).unwrap_or(0),
+        longest_streak_start: longest.map(|(start, _, _)| start.to_string()),
+        longest_streak_end: longest.map(|(_, end, _)| end.to_string()),
+        total_active_days: active.len() as i64,
+        last_active_date: previous.map(|date| date.to_string()),
+        total_tokens,
+        milestones,
+        next_milestone_tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(date: &str, tokens: i64) -> DailyActivity {
+        DailyActivity {
+            date: date.to_string(),
+            input_tokens: tokens,
+            output_tokens: 0,
+            cost_usd: 0.0,
+            session_count: 1,
+            message_count: 1,
+            note: None,
+            api_equivalent_cost_usd: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_calculate_streaks() {
+        let activities = vec![
+            activity("2026-10-01", 400_000),
+            activity("2026-10-02", 400_000),
+            activity("2026-10-03", 400_000),
+            activity("2026-10-05", 100_000),
+            activity("2026-10-06", 100_000),
+        ];
+        let today = NaiveDate::from_ymd_opt(2026, 10, 7).expect("date");
+
+        let streaks = calculate_streaks(&activities, today);
+
+        assert_eq!(streaks.current_streak_days, 2);
+        assert_eq!(streaks.longest_streak_days, 3);
+        assert_eq!(streaks.longest_streak_start, Some("2026-10-01".to_string()));
+        assert_eq!(streaks.total_active_days, 5);
+        assert_eq!(streaks.total_tokens, 1_400_000);
+        assert_eq!(
+            streaks.milestones[0].reached_on,
+            Some("2026-10-03".to_string())
+        );
+        assert_eq!(streaks.next_milestone_tokens, Some(10_000_000));
+    }
+
+    #[test]
+    fn test_current_streak_broken() {
+        let activities = vec![activity("2026-10-01", 10)];
+        let today = NaiveDate::from_ymd_opt(2026, 10, 7).expect("date");
+
+        let streaks = calculate_streaks(&activities, today);
+
+        assert_eq!(streaks.current_streak_days, 0);
+        assert_eq!(streaks.longest_streak_days, 1);
+    }
+}
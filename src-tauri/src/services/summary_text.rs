@@ -0,0 +1,220 @@
+//! @file summary_text.rs
+//! @description 自然语言用量摘要服务，将指定日期范围的用量渲染为一句话描述，供通知与报告使用
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::collections::HashMap;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{CacheHitRateSettings, SummaryLocale};
+use crate::services::pricing::ModelFamily;
+
+/// 摘要所需的用量数据
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryFigures {
+    pub cost_usd: f64,
+
+    pub session_count: i64,
+
+    pub message_count: i64,
+
+    /// 缓存命中率（0 - 1）
+    pub cache_hit_rate: f64,
+
+    /// 消息数最多的模型系列（如 Sonnet）
+    pub top_model: Option<String>,
+}
+
+/// 生成指定日期的用量摘要
+pub fn daily_summary_text(
+    repository: &Repository,
+    date: &str,
+    locale: SummaryLocale,
+) -> Result<String, RepositoryError> {
+    range_summary_text(repository, date, date, locale)
+}
+
+/// 生成日期范围（包含两端）的用量摘要
+pub fn range_summary_text(
+    repository: &Repository,
+    start_date: &str,
+    end_date: &str,
+    locale: SummaryLocale,
+) -> Result<String, RepositoryError> {
+    let figures = collect_summary_figures(repository, start_date, end_date)?;
+    Ok(render_summary(figures.as_ref(), locale))
+}
+
+/// 汇总日期范围内的用量，没有消息时返回 None
+pub fn collect_summary_figures(
+    repository: &Repository,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Option<SummaryFigures>, RepositoryError> {
+    let daily = repository.get_daily_activities(start_date, end_date)?;
+    let message_count: i64 = daily.iter().map(|day| day.message_count).sum();
+    if message_count == 0 {
+        return Ok(None);
+    }
+
+    let formula = repository.get_setting::<CacheHitRateSettings>()?.formula;
+    let models = repository.get_model_daily_usage(start_date, end_date)?;
+    let (mut cache_read, mut input, mut cache_creation) = (0, 0, 0);
+    let mut families: HashMap<String, i64> = HashMap::new();
+    for row in &models {
+        cache_read += row.cache_read_tokens;
+        input += row.input_tokens;
+        cache_creation += row.cache_creation_tokens;
+        let family = ModelFamily::detect(&row.model)
+            .map(|family| family.name().to_string())
+            .unwrap_or_else(|| row.model.clone());
+        *families.entry(family).or_default() += row.message_count;
+    }
+    let top_model = families
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .map(|(family, _)| family);
+
+    Ok(Some(SummaryFigures {
+        cost_usd: daily.iter().map(|day| day.cost_usd).sum(),
+        session_count: daily.iter().map(|day| day.session_count).sum(),
+        message_count,
+        cache_hit_rate: formula.rate(cache_read, input, cache_creation),
+        top_model,
+    }))
+}
+
+/// 渲染摘要文本
+///
+/// 例：`You spent $3.40 across 5 sessions, 72% cache hit rate, mostly Sonnet.`
+pub fn render_summary(figures: Option<&SummaryFigures>, locale: SummaryLocale) -> String {
+    let Some(figures) = figures else {
+        return match locale {
+            SummaryLocale::ZhCn => "没有使用记录。".to_string(),
+            SummaryLocale::En => "No usage recorded.".to_string(),
+        };
+    };
+
+    let hit_rate = (figures.cache_hit_rate * 100.0).round();
+    match locale {
+        SummaryLocale::ZhCn => {
+            let mut text = format!(
+                "共花费 ${:.2}，{} 个会话，缓存命中率 {}%",
+                figures.cost_usd, figures.session_count, hit_rate
+            );
+            if let Some(model) = &figures.top_model {
+                text.push_str(&format!("，主要使用 {}", model));
+            }
+            text.push('。');
+            text
+        }
+        SummaryLocale::En => {
+            let mut text = format!(
+                "You spent ${:.2} across {} {}, {}% cache hit rate",
+                figures.cost_usd,
+                figures.session_count,
+                if figures.session_count == 1 {
+                    "session"
+                } else {
+                    "sessions"
+                },
+                hit_rate
+            );
+            if let Some(model) = &figures.top_model {
+                text.push_str(&format!(", mostly {}", model));
+            }
+            text.push('.');
+            text
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRecord, MessageUsage};
+
+    fn figures(session_count: i64, top_model: Option<&str>) -> SummaryFigures {
+        SummaryFigures {
+            cost_usd: 3.4,
+            session_count,
+            message_count: 20,
+            cache_hit_rate: 0.7249,
+            top_model: top_model.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_render_summary() {
+        assert_eq!(
+            render_summary(Some(&figures(5, Some("Sonnet"))), SummaryLocale::En),
+            "You spent $3.40 across 5 sessions, 72% cache hit rate, mostly Sonnet."
+        );
+        assert_eq!(
+            render_summary(Some(&figures(1, None)), SummaryLocale::En),
+            "You spent $3.40 across 1 session, 72% cache hit rate."
+        );
+        assert_eq!(
+            render_summary(Some(&figures(5, Some("Opus"))), SummaryLocale::ZhCn),
+            "共花费 $3.40，5 个会话，缓存命中率 72%，主要使用 Opus。"
+        );
+        assert_eq!(
+            render_summary(None, SummaryLocale::En),
+            "No usage recorded."
+        );
+        assert_eq!(SummaryLocale::from_tag("en-US"), SummaryLocale::En);
+        assert_eq!(SummaryLocale::from_tag("fr"), SummaryLocale::ZhCn);
+    }
+
+    #[test]
+    fn test_daily_summary_text() {
+        let repository = Repository::new_in_memory().expect("repo");
+        let provider = repository
+            .upsert_provider("sk-summary", None)
+            .expect("provider");
+        let created_at = "2026-10-16T10:00:00+08:00";
+        for (index, model) in [
+            "claude-3-5-sonnet-20241022",
+            "claude-3-5-sonnet-20241022",
+            "claude-3-opus-20240229",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let record = MessageRecord::new(
+                format!("s{}", index % 2),
+                format!("m{}", index),
+                model.to_string(),
+                created_at.to_string(),
+                MessageUsage {
+                    input_tokens: 100,
+                    cache_read_tokens: 300,
+                    cost_usd: 1.0,
+                    ..Default::default()
+                },
+            );
+            repository
+                .insert_message_usage(provider.id, &record)
+                .expect("insert");
+        }
+
+        let date = chrono::DateTime::parse_from_rfc3339(created_at)
+            .expect("date")
+            .with_timezone(&chrono::Local)
+            .date_naive()
+            .to_string();
+        let figures = collect_summary_figures(&repository, &date, &date)
+            .expect("figures")
+            .expect("usage");
+        assert_eq!(figures.message_count, 3);
+        assert_eq!(figures.session_count, 2);
+        assert_eq!(figures.top_model.as_deref(), Some("Sonnet"));
+
+        let text = daily_summary_text(&repository, &date, SummaryLocale::En).expect("text");
+        assert!(text.starts_with("You spent $3.00 across 2 sessions"));
+        assert!(text.ends_with("mostly Sonnet."));
+        assert_eq!(
+            daily_summary_text(&repository, "2020-01-01", SummaryLocale::ZhCn).expect("text"),
+            "没有使用记录。"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! @file sync_scheduler.rs
+//! @description 云同步调度服务，按设置间隔在后台同步并发送 sync-completed 事件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Repository;
+use crate::models::{SyncResult, SyncSettings};
+use crate::services::cloud_sync::{load_sync_settings, remote_from_settings, sync_once, SyncError};
+
+/// 调度检查间隔；实际同步间隔由设置中的 interval_minutes 决定
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动云同步调度线程
+///
+/// 启用同步后立即同步一次，之后按设置的间隔同步；每次检查前重新读取设置
+pub fn start_sync_scheduler(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_synced: Option<Instant> = None;
+        loop {
+            let settings: Result<SyncSettings, _> = app.state::<Repository>().get_setting();
+            match settings {
+                Ok(settings) if settings.enabled => {
+                    let interval =
+                        Duration::from_secs(u64::from(settings.interval_minutes.max(1)) * 60);
+                    if last_synced.is_none_or(|at| at.elapsed() >= interval) {
+                        if let Err(e) = run_sync(&app) {
+                            tracing::error!("云同步失败: {}", e);
+                        }
+                        last_synced = Some(Instant::now());
+                    }
+                }
+                Ok(_) => last_synced = None,
+                Err(e) => tracing::error!("读取云同步设置失败: {}", e),
+            }
+            std::thread::sleep(TICK_INTERVAL);
+        }
+    });
+}
+
+/// 按当前设置执行一次同步并发送事件；合并了远端新消息时同时通知前端刷新统计
+///
+/// 密码、访问密钥与加密口令在此时才从钥匙串读取
+pub fn run_sync(app: &AppHandle) -> Result<SyncResult, SyncError> {
+    let repository = app.state::<Repository>();
+    let settings = load_sync_settings(&repository)?;
+    let remote = remote_from_settings(&settings)?;
+    let result = sync_once(&repository, remote.as_ref(), &settings.passphrase)?;
+    tracing::info!("云同步完成: 推送消息 {} 条", result.pushed_messages);
+
+    if result
+        .pulled
+        .as_ref()
+        .is_some_and(|summary| summary.messages_added > 0)
+    {
+        match repository.get_current_stats() {
+            Ok(stats) => {
+                if let Err(e) = app.emit("stats-updated", stats) {
+                    tracing::error!("发送 stats-updated 事件失败: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("获取统计数据失败: {}", e),
+        }
+    }
+    if let Err(e) = app.emit("sync-completed", &result) {
+        tracing::error!("发送 sync-completed 事件失败: {}", e);
+    }
+    Ok(result)
+}
@@ -0,0 +1,66 @@
+//! @file taskbar.rs
+//! @description Windows 任务栏进度条，按今日预算消耗显示进度（绿 → 黄 → 红）
+//! @author Atlas.oi
+//! @date 2026-10-17
+use tauri::AppHandle;
+
+#[cfg(target_os = "windows")]
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+#[cfg(target_os = "windows")]
+use tauri::Manager;
+
+#[cfg(target_os = "windows")]
+use crate::db::Repository;
+#[cfg(target_os = "windows")]
+use crate::models::BudgetSettings;
+#[cfg(target_os = "windows")]
+use crate::services::budget::{budget_progress, BudgetLevel};
+#[cfg(target_os = "windows")]
+use crate::services::tray::MAIN_WINDOW_LABEL;
+
+/// 按今日费用与每日预算更新主窗口的任务栏进度条
+///
+/// 正常为绿色，达到预警百分比为黄色，超出预算为红色；未配置预算时清除进度条
+#[cfg(target_os = "windows")]
+pub fn update_taskbar_progress(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+    let repository = app.state::<Repository>();
+    let settings: BudgetSettings = match repository.get_setting() {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("读取预算设置失败: {}", e);
+            return;
+        }
+    };
+    let today = match repository.get_today_stats() {
+        Ok(today) => today,
+        Err(e) => {
+            tracing::error!("获取今日统计失败: {}", e);
+            return;
+        }
+    };
+
+    let state = match budget_progress(&settings, today.cost_usd) {
+        Some(progress) => ProgressBarState {
+            status: Some(match progress.level {
+                BudgetLevel::Normal => ProgressBarStatus::Normal,
+                BudgetLevel::Warning => ProgressBarStatus::Paused,
+                BudgetLevel::Exceeded => ProgressBarStatus::Error,
+            }),
+            progress: Some(progress.percent),
+        },
+        None => ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+        },
+    };
+    if let Err(e) = window.set_progress_bar(state) {
+        tracing::error!("更新任务栏进度失败: {}", e);
+    }
+}
+
+/// 非 Windows 平台没有任务栏进度条
+#[cfg(not(target_os = "windows"))]
+pub fn update_taskbar_progress(_app: &AppHandle) {}
@@ -0,0 +1,118 @@
+//! @file trash.rs
+//! @description 回收站服务，清除过期条目并维护被删除供应商的钥匙串凭证
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{DateTime, Duration, Utc};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{DeletedItem, DeletedItemKind, KeychainSettings, TrashSettings};
+use crate::services::keychain;
+
+/// 彻底清除超过保留期的回收站条目
+///
+/// 被删除供应商的钥匙串凭证保留到条目清除为止，以便恢复后继续使用
+pub fn purge_expired_items(
+    repository: &Repository,
+    now: DateTime<Utc>,
+) -> Result<Vec<DeletedItem>, RepositoryError> {
+    let settings: TrashSettings = repository.get_setting()?;
+    let cutoff = now - Duration::days(i64::from(settings.retention_days));
+    let purged = repository.purge_deleted_items(&cutoff.to_rfc3339())?;
+
+    for item in &purged {
+        if item.kind == DeletedItemKind::Provider {
+            delete_provider_credentials(repository, item.source_id);
+        }
+    }
+    if !purged.is_empty() {
+        tracing::info!("已清除 {} 个过期的回收站条目", purged.len());
+    }
+    Ok(purged)
+}
+
+/// 供应商恢复后 ID 变化时，将钥匙串中的 API Key 与余额查询凭证迁移到新 ID
+pub fn move_provider_credentials(repository: &Repository, from: i64, to: i64) {
+    if from == to {
+        return;
+    }
+    let Some(namespace) = keychain_namespace(repository) else {
+        return;
+    };
+
+    match keychain::load_api_key(&namespace, from) {
+        Ok(Some(api_key)) => {
+            if let Err(e) = keychain::store_api_key(&namespace, to, &api_key) {
+                tracing::error!("迁移钥匙串中的 API Key 失败 [{} → {}]: {}", from, to, e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("读取钥匙串中的 API Key 失败 [{}]: {}", from, e),
+    }
+    match keychain::load_quota_credential(&namespace, from) {
+        Ok(Some(credential)) => {
+            if let Err(e) = keychain::store_quota_credential(&namespace, to, &credential) {
+                tracing::error!("迁移钥匙串中的余额查询凭证失败 [{} → {}]: {}", from, to, e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("读取钥匙串中的余额查询凭证失败 [{}]: {}", from, e),
+    }
+    delete_provider_credentials(repository, from);
+}
+
+/// 删除钥匙串中保存的 API Key 与余额查询凭证，只删除当前数据库命名空间下的条目
+pub fn delete_provider_credentials(repository: &Repository, provider_id: i64) {
+    let Some(namespace) = keychain_namespace(repository) else {
+        return;
+    };
+    if let Err(e) = keychain::delete_api_key(&namespace, provider_id) {
+        tracing::error!("删除钥匙串中的 API Key 失败 [{}]: {}", provider_id, e);
+    }
+    if let Err(e) = keychain::delete_quota_credential(&namespace, provider_id) {
+        tracing::error!("删除钥匙串中的余额查询凭证失败 [{}]: {}", provider_id, e);
+    }
+}
+
+/// 接管旧版本未区分数据库保存的钥匙串凭证（含回收站中的供应商），每个数据库只进行一次
+///
+/// 旧版本只有一个数据库，只应对默认档案的数据库调用；钥匙串不可用时下次启动重试
+pub fn adopt_legacy_credentials(repository: &Repository) -> Result<(), RepositoryError> {
+    let settings: KeychainSettings = repository.get_setting()?;
+    if settings.legacy_adopted {
+        return Ok(());
+    }
+
+    let namespace = repository.keychain_namespace()?;
+    let mut provider_ids: Vec<i64> = repository
+        .get_all_providers(false)?
+        .iter()
+        .map(|provider| provider.id)
+        .collect();
+    provider_ids.extend(
+        repository
+            .get_deleted_items()?
+            .iter()
+            .filter(|item| item.kind == DeletedItemKind::Provider)
+            .map(|item| item.source_id),
+    );
+    match keychain::adopt_legacy_credentials(&namespace, &provider_ids) {
+        Ok(0) => {}
+        Ok(adopted) => tracing::info!("已迁移 {} 条旧版本钥匙串凭证", adopted),
+        Err(e) => {
+            tracing::error!("迁移旧版本钥匙串凭证失败: {}", e);
+            return Ok(());
+        }
+    }
+
+    let mut settings: KeychainSettings = repository.get_setting()?;
+    settings.legacy_adopted = true;
+    repository.set_setting(&settings)
+}
+
+/// 读取当前数据库的钥匙串命名空间，失败时记录错误
+fn keychain_namespace(repository: &Repository) -> Option<String> {
+    repository
+        .keychain_namespace()
+        .inspect_err(|e| tracing::error!("读取钥匙串命名空间失败: {}", e))
+        .ok()
+}
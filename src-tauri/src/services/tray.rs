@@ -0,0 +1,227 @@
+//! @file tray.rs
+//! @description 系统托盘 / 菜单栏图标与菜单栏模式（无 Dock 图标）切换
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, PhysicalPosition, Rect, Window, WindowEvent};
+
+use crate::db::Repository;
+use crate::models::{TrayIconMetric, TrayIconSettings};
+use crate::services::menu_bar::{popover_position, ScreenRect};
+use crate::services::tray_icon::{render_label, tray_label};
+
+/// 主窗口标识
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// 托盘图标标识
+const TRAY_ID: &str = "main";
+
+const MENU_SHOW: &str = "show";
+const MENU_QUIT: &str = "quit";
+
+/// 菜单栏模式运行时状态，供托盘与窗口事件回调读取
+#[derive(Default)]
+pub struct MenuBarMode(AtomicBool);
+
+impl MenuBarMode {
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// 创建托盘图标
+///
+/// 左键点击：菜单栏模式下在图标旁弹出 / 收起主窗口，普通模式下显示主窗口；
+/// 右键点击：显示菜单
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+    let show = MenuItem::with_id(app, MENU_SHOW, "显示主窗口", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, "退出", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Claude Token Monitor")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            MENU_SHOW => show_main_window(app),
+            MENU_QUIT => app.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                rect,
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                handle_tray_click(tray.app_handle(), rect);
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// 按托盘图标设置重新绘制托盘图标
+///
+/// 显示指标时将今日数值绘制为图标，并在提示文字中显示完整数值；
+/// 设置为 Static 时恢复应用默认图标
+pub fn refresh_tray_icon(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let repository = app.state::<Repository>();
+    let settings: TrayIconSettings = match repository.get_setting() {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("读取托盘图标设置失败: {}", e);
+            return;
+        }
+    };
+    let today = match repository.get_today_stats() {
+        Ok(today) => today,
+        Err(e) => {
+            tracing::error!("获取今日统计失败: {}", e);
+            return;
+        }
+    };
+
+    let result = match tray_label(settings.metric, &today) {
+        Some(label) => {
+            let bitmap = render_label(&label);
+            let tooltip = match settings.metric {
+                TrayIconMetric::TodayCost => format!("今日费用 ${:.2}", today.cost_usd),
+                _ => format!("今日 {}", label),
+            };
+            tray.set_icon(Some(Image::new_owned(
+                bitmap.rgba,
+                bitmap.width,
+                bitmap.height,
+            )))
+            .and_then(|_| tray.set_tooltip(Some(tooltip)))
+        }
+        None => tray
+            .set_icon(app.default_window_icon().cloned())
+            .and_then(|_| tray.set_tooltip(Some("Claude Token Monitor"))),
+    };
+    if let Err(e) = result {
+        tracing::error!("更新托盘图标失败: {}", e);
+    }
+}
+
+/// 切换菜单栏模式
+///
+/// 业务逻辑：
+/// 1. macOS 切换激活策略：Accessory 不显示 Dock 图标，Regular 恢复
+/// 2. 主窗口同步隐藏 / 恢复任务栏图标（Windows / Linux）
+/// 3. 开启时收起主窗口，等待从托盘弹出；关闭时恢复显示
+pub fn apply_menu_bar_mode(app: &AppHandle, enabled: bool) -> tauri::Result<()> {
+    app.state::<MenuBarMode>()
+        .0
+        .store(enabled, Ordering::SeqCst);
+
+    #[cfg(target_os = "macos")]
+    app.set_activation_policy(if enabled {
+        tauri::ActivationPolicy::Accessory
+    } else {
+        tauri::ActivationPolicy::Regular
+    })?;
+
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        window.set_skip_taskbar(enabled)?;
+        if enabled {
+            window.hide()?;
+        } else {
+            window.show()?;
+            window.set_focus()?;
+        }
+    }
+    Ok(())
+}
+
+/// 菜单栏模式下的主窗口行为：关闭时隐藏而非退出，失去焦点时自动收起
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW_LABEL || !window.state::<MenuBarMode>().is_enabled() {
+        return;
+    }
+
+    match event {
+        WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+        WindowEvent::Focused(false) => {
+            let _ = window.hide();
+        }
+        _ => {}
+    }
+}
+
+fn handle_tray_click(app: &AppHandle, tray_rect: Rect) {
+    if !app.state::<MenuBarMode>().is_enabled() {
+        show_main_window(app);
+        return;
+    }
+
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+        return;
+    }
+
+    if let Err(e) = anchor_to_tray(&window, tray_rect) {
+        tracing::error!("主窗口定位失败: {}", e);
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// 将主窗口定位到托盘图标旁
+fn anchor_to_tray(window: &tauri::WebviewWindow, tray_rect: Rect) -> tauri::Result<()> {
+    let scale_factor = window.scale_factor()?;
+    let tray_position = tray_rect.position.to_physical::<f64>(scale_factor);
+    let tray_size = tray_rect.size.to_physical::<f64>(scale_factor);
+    let tray = ScreenRect {
+        x: tray_position.x,
+        y: tray_position.y,
+        width: tray_size.width,
+        height: tray_size.height,
+    };
+
+    let Some(monitor) = window.monitor_from_point(tray.x, tray.y)? else {
+        return Ok(());
+    };
+    let area = monitor.work_area();
+    let work_area = ScreenRect {
+        x: area.position.x as f64,
+        y: area.position.y as f64,
+        width: area.size.width as f64,
+        height: area.size.height as f64,
+    };
+
+    let window_size = window.outer_size()?;
+    let (x, y) = popover_position(
+        tray,
+        window_size.width as f64,
+        window_size.height as f64,
+        work_area,
+    );
+    window.set_position(PhysicalPosition::new(x, y))
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
@@ -0,0 +1,241 @@
+//! @file tray_icon.rs
+//! @description 动态托盘图标绘制，将今日指标以内置点阵字体渲染为 RGBA 图像
+//! @author Atlas.oi
+//! @date 2026-10-17
+use crate::models::{TodayStats, TrayIconMetric};
+
+/// 点阵字体字形宽度（像素）
+const GLYPH_WIDTH: u32 = 5;
+
+/// 点阵字体字形高度（像素）
+const GLYPH_HEIGHT: u32 = 7;
+
+/// 字形放大倍数
+const SCALE: u32 = 3;
+
+/// 字形之间的间距（放大后像素）
+const GLYPH_SPACING: u32 = 3;
+
+/// 文字与背景边缘的间距（放大后像素）
+const PADDING: u32 = 5;
+
+/// 背景色：深灰，浅色与深色菜单栏下都能看清
+const BACKGROUND: [u8; 4] = [32, 32, 36, 255];
+
+/// 文字颜色
+const FOREGROUND: [u8; 4] = [255, 255, 255, 255];
+
+/// 渲染结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrayBitmap {
+    /// RGBA 像素，按行排列
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 生成托盘图标上显示的文字，Static 返回 None
+pub fn tray_label(metric: TrayIconMetric, today: &TodayStats) -> Option<String> {
+    match metric {
+        TrayIconMetric::Static => None,
+        TrayIconMetric::TodayCost => Some(format_cost(today.cost_usd)),
+        TrayIconMetric::TodayTokens => Some(format_count(
+            today.input_tokens
+                + today.output_tokens
+                + today.cache_read_tokens
+                + today.cache_creation_tokens,
+        )),
+        TrayIconMetric::TodayMessages => Some(format_count(today.message_count)),
+    }
+}
+
+/// 费用文字：不足 10 美元保留一位小数，不足 1000 美元取整，再大按 k 缩写
+fn format_cost(cost_usd: f64) -> String {
+    let cost = cost_usd.max(0.0);
+    if cost < 10.0 {
+        format!("${:.1}", cost)
+    } else if cost < 1000.0 {
+        format!("${:.0}", cost)
+    } else {
+        format!("${}", format_count(cost as i64))
+    }
+}
+
+/// 数量文字：按 k / M 缩写，保留不超过三位有效数字
+fn format_count(value: i64) -> String {
+    let value = value.max(0);
+    let (scaled, suffix) = match value {
+        0..=999 => return value.to_string(),
+        1_000..=999_999 => (value as f64 / 1_000.0, "k"),
+        _ => (value as f64 / 1_000_000.0, "M"),
+    };
+    if scaled < 10.0 {
+        format!("{:.1}{}", (scaled * 10.0).floor() / 10.0, suffix)
+    } else {
+        format!("{:.0}{}", scaled.floor(), suffix)
+    }
+}
+
+/// 将文字渲染为圆角背景上的白色点阵字，不支持的字符跳过
+pub fn render_label(label: &str) -> TrayBitmap {
+    let glyphs: Vec<&[u8; 7]> = label.chars().filter_map(glyph).collect();
+    let count = glyphs.len() as u32;
+    let text_width = count * GLYPH_WIDTH * SCALE + count.saturating_sub(1) * GLYPH_SPACING;
+    let width = text_width + PADDING * 2;
+    let height = GLYPH_HEIGHT * SCALE + PADDING * 2;
+
+    let mut rgba = vec![0u8; (width * height * 4) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            if !is_outside_corner(x, y, width, height) {
+                put_pixel(&mut rgba, width, x, y, BACKGROUND);
+            }
+        }
+    }
+
+    for (index, rows) in glyphs.iter().enumerate() {
+        let origin_x = PADDING + index as u32 * (GLYPH_WIDTH * SCALE + GLYPH_SPACING);
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+                for dy in 0..SCALE {
+                    for dx in 0..SCALE {
+                        put_pixel(
+                            &mut rgba,
+                            width,
+                            origin_x + column * SCALE + dx,
+                            PADDING + row as u32 * SCALE + dy,
+                            FOREGROUND,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    TrayBitmap {
+        rgba,
+        width,
+        height,
+    }
+}
+
+fn put_pixel(rgba: &mut [u8], width: u32, x: u32, y: u32, color: [u8; 4]) {
+    let offset = ((y * width + x) * 4) as usize;
+    rgba[offset..offset + 4].copy_from_slice(&color);
+}
+
+/// 是否位于圆角之外（圆角半径等于 PADDING）
+fn is_outside_corner(x: u32, y: u32, width: u32, height: u32) -> bool {
+    let radius = PADDING as i64;
+    let (x, y) = (x as i64, y as i64);
+    let corner_x = if x < radius {
+        radius - x
+    } else if x >= width as i64 - radius {
+        x - (width as i64 - radius - 1)
+    } else {
+        return false;
+    };
+    let corner_y = if y < radius {
+        radius - y
+    } else if y >= height as i64 - radius {
+        y - (height as i64 - radius - 1)
+    } else {
+        return false;
+    };
+    corner_x * corner_x + corner_y * corner_y > radius * radius
+}
+
+/// 5 x 7 点阵字形，每行低 5 位从左到右
+fn glyph(ch: char) -> Option<&'static [u8; 7]> {
+    let rows: &'static [u8; 7] = match ch {
+        '0' => &[0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => &[0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => &[0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => &[0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => &[0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => &[0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => &[0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => &[0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => &[0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => &[0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        '$' => &[0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04],
+        '.' => &[0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        'k' => &[0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12],
+        'M' => &[0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today(cost_usd: f64, input_tokens: i64, message_count: i64) -> TodayStats {
+        TodayStats {
+            input_tokens,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd,
+            session_count: 0,
+            message_count,
+            cache_hit_rate: 0.0,
+            api_equivalent_cost_usd: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_tray_label() {
+        let stats = today(4.23, 1_250_000, 87);
+        assert_eq!(
+            tray_label(TrayIconMetric::TodayCost, &stats).as_deref(),
+            Some("$4.2")
+        );
+        assert_eq!(
+            tray_label(TrayIconMetric::TodayTokens, &stats).as_deref(),
+            Some("1.2M")
+        );
+        assert_eq!(
+            tray_label(TrayIconMetric::TodayMessages, &stats).as_deref(),
+            Some("87")
+        );
+        assert_eq!(tray_label(TrayIconMetric::Static, &stats), None);
+
+        assert_eq!(format_cost(123.6), "$124");
+        assert_eq!(format_cost(1520.0), "$1.5k");
+        assert_eq!(format_count(45_900), "45k");
+        assert_eq!(format_count(999_999), "999k");
+    }
+
+    #[test]
+    fn test_render_label() {
+        let bitmap = render_label("$4.2");
+        assert_eq!(bitmap.height, GLYPH_HEIGHT * SCALE + PADDING * 2);
+        assert_eq!(
+            bitmap.width,
+            4 * GLYPH_WIDTH * SCALE + 3 * GLYPH_SPACING + PADDING * 2
+        );
+        assert_eq!(
+            bitmap.rgba.len(),
+            (bitmap.width * bitmap.height * 4) as usize
+        );
+
+        let pixel = |x: u32, y: u32| {
+            let offset = ((y * bitmap.width + x) * 4) as usize;
+            [
+                bitmap.rgba[offset],
+                bitmap.rgba[offset + 1],
+                bitmap.rgba[offset + 2],
+                bitmap.rgba[offset + 3],
+            ]
+        };
+        // 圆角外透明，背景区域为背景色，"$" 顶部中间一列为文字色
+        assert_eq!(pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(PADDING, 1), BACKGROUND);
+        assert_eq!(pixel(PADDING + 2 * SCALE, PADDING), FOREGROUND);
+    }
+}
@@ -0,0 +1,116 @@
+//! @file updater.rs
+//! @description 应用自动更新服务，按更新渠道检查新版本并发送 update-available 事件
+//! @author Atlas.oi
+//! @date 2026-10-17
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use thiserror::Error;
+
+use crate::db::Repository;
+use crate::models::{UpdateChannel, UpdateInfo, UpdateSettings};
+
+/// 正式版更新清单地址
+const STABLE_ENDPOINT: &str =
+    "https://github.com/kissesu/claude-token-monitor/releases/latest/download/latest.json";
+
+/// 测试版更新清单地址（固定 beta 标签的 Release）
+const BETA_ENDPOINT: &str =
+    "https://github.com/kissesu/claude-token-monitor/releases/download/beta/latest.json";
+
+/// 更新签名公钥，构建时通过环境变量注入；未设置时使用 tauri.conf.json 中的配置
+const UPDATER_PUBKEY: Option<&str> = option_env!("TAURI_UPDATER_PUBKEY");
+
+/// 自动检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Error, Debug)]
+pub enum UpdaterError {
+    #[error("Updater error: {0}")]
+    Updater(#[from] tauri_plugin_updater::Error),
+    #[error("Invalid update endpoint: {0}")]
+    Endpoint(String),
+    #[error("No pending update to install")]
+    NoUpdate,
+}
+
+/// 更新渠道对应的更新清单地址
+pub fn channel_endpoint(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+/// 检查指定渠道是否有新版本，有新版本时发送 update-available 事件
+pub async fn check_for_updates(
+    app: &AppHandle,
+    channel: UpdateChannel,
+) -> Result<Option<UpdateInfo>, UpdaterError> {
+    let update = match fetch_update(app, channel).await? {
+        Some(update) => update,
+        None => return Ok(None),
+    };
+
+    let info = UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel,
+        notes: update.body.clone(),
+        published_at: update.date.map(|date| date.to_string()),
+    };
+    if let Err(e) = app.emit("update-available", &info) {
+        tracing::error!("发送 update-available 事件失败: {}", e);
+    }
+    Ok(Some(info))
+}
+
+/// 下载并安装指定渠道的最新版本，完成后重启应用
+pub async fn install_update(app: &AppHandle, channel: UpdateChannel) -> Result<(), UpdaterError> {
+    let update = fetch_update(app, channel)
+        .await?
+        .ok_or(UpdaterError::NoUpdate)?;
+
+    tracing::info!(
+        "开始安装更新: {} -> {}",
+        update.current_version,
+        update.version
+    );
+    update.download_and_install(|_, _| {}, || {}).await?;
+    app.restart()
+}
+
+/// 启动后台更新检查任务
+///
+/// 启动时立即检查一次，之后按间隔检查；每次检查前重新读取设置，关闭自动检查后跳过
+pub fn start_update_checker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = app.state::<Repository>().get_setting::<UpdateSettings>();
+            match settings {
+                Ok(settings) if settings.auto_check => {
+                    if let Err(e) = check_for_updates(&app, settings.channel).await {
+                        tracing::error!("检查更新失败: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("读取更新设置失败: {}", e),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+async fn fetch_update(
+    app: &AppHandle,
+    channel: UpdateChannel,
+) -> Result<Option<Update>, UpdaterError> {
+    let endpoint =
+        Url::parse(channel_endpoint(channel)).map_err(|e| UpdaterError::Endpoint(e.to_string()))?;
+    let mut builder = app.updater_builder().endpoints(vec![endpoint])?;
+    if let Some(pubkey) = UPDATER_PUBKEY {
+        builder = builder.pubkey(pubkey);
+    }
+    Ok(builder.build()?.check().await?)
+}
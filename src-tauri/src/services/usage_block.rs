@@ -0,0 +1,217 @@
+//! @file usage_block.rs
+//! @description 5 小时使用区块计算服务，识别当前活跃区块与剩余时间
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{BlockEntry, UsageBlock};
+
+/// 区块时长（小时）
+pub const BLOCK_DURATION_HOURS: i64 = 5;
+
+/// 学习区块用量上限至少需要的已结束区块数
+pub const MIN_CEILING_BLOCKS: usize = 3;
+
+/// 查询当前区块时回看的小时数
+///
+/// 连续使用时区块首尾相接，回看窗口需覆盖多个区块才能对齐当前区块的起点
+const LOOKBACK_HOURS: i64 = 24;
+
+/// 获取当前活跃区块，没有活跃区块时返回 None
+pub fn get_current_block(
+    repository: &Repository,
+    now: DateTime<Utc>,
+) -> Result<Option<UsageBlock>, RepositoryError> {
+    let since = now - Duration::hours(LOOKBACK_HOURS);
+    let entries = repository.get_block_entries(&since.to_rfc3339())?;
+    Ok(calculate_current_block(&entries, now))
+}
+
+/// 与 get_current_block 相同，消息用量来自总体统计缓存，供小组件高频轮询
+pub fn get_cached_current_block(
+    repository: &Repository,
+    now: DateTime<Utc>,
+) -> Result<Option<UsageBlock>, RepositoryError> {
+    let since = now - Duration::hours(LOOKBACK_HOURS);
+    let entries = repository.get_cached_block_entries(since)?;
+    Ok(calculate_current_block(&entries, now))
+}
+
+/// 根据消息用量计算当前活跃区块
+///
+/// 业务逻辑说明：
+/// 1. 区块从首条消息时间向下取整到整点开始，持续 5 小时
+/// 2. 消息超出当前区块结束时间，或距上一条消息超过 5 小时，则开启新区块
+/// 3. 最后一个区块尚未结束且最近 5 小时内有消息时视为活跃
+///
+/// entries 需按时间升序排列，无法解析时间的记录会被跳过
+pub fn calculate_current_block(entries: &[BlockEntry], now: DateTime<Utc>) -> Option<UsageBlock> {
+    let block = group_blocks(entries).pop()?;
+    if !block.is_active(now) {
+        return None;
+    }
+
+    let end = block.end();
+    Some(UsageBlock {
+        start_at: block.start.to_rfc3339(),
+        end_at: end.to_rfc3339(),
+        last_message_at: block.last_entry.created_at.clone(),
+        remaining_minutes: (end - now).num_minutes(),
+        total_tokens: block.total_tokens,
+        cost_usd: block.cost_usd,
+        message_count: block.message_count,
+    })
+}
+
+/// 从历史区块中学习单个区块的用量上限
+///
+/// 取已结束区块中的最大 Token 总数；已结束区块少于 MIN_CEILING_BLOCKS 个时返回 None
+pub fn learn_block_ceiling(entries: &[BlockEntry], now: DateTime<Utc>) -> Option<i64> {
+    let finished: Vec<i64> = group_blocks(entries)
+        .iter()
+        .filter(|block| !block.is_active(now))
+        .map(|block| block.total_tokens)
+        .collect();
+    if finished.len() < MIN_CEILING_BLOCKS {
+        return None;
+    }
+    finished.into_iter().max().filter(|ceiling| *ceiling > 0)
+}
+
+/// 分组后的单个区块
+struct BlockTotals<'a> {
+    start: DateTime<Utc>,
+    last: DateTime<Utc>,
+    last_entry: &'a BlockEntry,
+    total_tokens: i64,
+    cost_usd: f64,
+    message_count: i64,
+}
+
+impl BlockTotals<'_> {
+    fn end(&self) -> DateTime<Utc> {
+        self.start + Duration::hours(BLOCK_DURATION_HOURS)
+    }
+
+    /// 区块尚未结束且最近 5 小时内有消息
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.end() && now - self.last < Duration::hours(BLOCK_DURATION_HOURS)
+    }
+}
+
+/// 按区块规则将消息分组，返回按时间升序排列的区块
+fn group_blocks(entries: &[BlockEntry]) -> Vec<BlockTotals<'_>> {
+    let block_duration = Duration::hours(BLOCK_DURATION_HOURS);
+    let mut blocks: Vec<BlockTotals<'_>> = Vec::new();
+
+    for entry in entries {
+        let created_at = match DateTime::parse_from_rfc3339(&entry.created_at) {
+            Ok(time) => time.with_timezone(&Utc),
+            Err(_) => continue,
+        };
+
+        let current = blocks.last_mut().filter(|block| {
+            created_at < block.start + block_duration && created_at - block.last < block_duration
+        });
+        match current {
+            Some(block) => {
+                block.last = created_at;
+                block.last_entry = entry;
+                block.total_tokens += entry.total_tokens;
+                block.cost_usd += entry.cost_usd;
+                block.message_count += 1;
+            }
+            None => blocks.push(BlockTotals {
+                start: created_at
+                    .duration_trunc(Duration::hours(1))
+                    .unwrap_or(created_at),
+                last: created_at,
+                last_entry: entry,
+                total_tokens: entry.total_tokens,
+                cost_usd: entry.cost_usd,
+                message_count: 1,
+            }),
+        }
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(created_at: &str, cost_usd: f64) -> BlockEntry {
+        BlockEntry {
+            created_at: created_at.to_string(),
+            total_tokens: 100,
+            cost_usd,
+        }
+    }
+
+    fn time(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("time")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_current_block_starts_on_hour() {
+        let entries = vec![
+            entry("2026-10-17T01:00:00Z", 5.0),
+            entry("2026-10-17T09:20:00Z", 1.0),
+            entry("2026-10-17T10:05:00Z", 2.0),
+        ];
+
+        let block =
+            calculate_current_block(&entries, time("2026-10-17T11:30:00Z")).expect("active block");
+        assert_eq!(block.start_at, "2026-10-17T09:00:00+00:00");
+        assert_eq!(block.end_at, "2026-10-17T14:00:00+00:00");
+        assert_eq!(block.remaining_minutes, 150);
+        assert_eq!(block.message_count, 2);
+        assert_eq!(block.total_tokens, 200);
+        assert_eq!(block.cost_usd, 3.0);
+    }
+
+    #[test]
+    fn test_continuous_usage_rolls_into_next_block() {
+        let entries = vec![
+            entry("2026-10-17T09:10:00Z", 1.0),
+            entry("2026-10-17T13:50:00Z", 1.0),
+            entry("2026-10-17T14:10:00Z", 4.0),
+        ];
+
+        let block =
+            calculate_current_block(&entries, time("2026-10-17T15:00:00Z")).expect("active block");
+        assert_eq!(block.start_at, "2026-10-17T14:00:00+00:00");
+        assert_eq!(block.cost_usd, 4.0);
+    }
+
+    #[test]
+    fn test_learn_block_ceiling() {
+        let mut entries = vec![
+            entry("2026-10-15T09:10:00Z", 1.0),
+            entry("2026-10-16T09:10:00Z", 1.0),
+            entry("2026-10-16T10:10:00Z", 1.0),
+        ];
+        let now = time("2026-10-17T10:30:00Z");
+        assert_eq!(learn_block_ceiling(&entries, now), None);
+
+        entries.push(entry("2026-10-17T08:10:00Z", 1.0));
+        entries.push(entry("2026-10-17T10:10:00Z", 1.0));
+        assert_eq!(learn_block_ceiling(&entries, now), None);
+
+        // 当前活跃区块不参与学习
+        let now = time("2026-10-17T20:00:00Z");
+        assert_eq!(learn_block_ceiling(&entries, now), Some(200));
+    }
+
+    #[test]
+    fn test_no_active_block() {
+        assert!(calculate_current_block(&[], time("2026-10-17T15:00:00Z")).is_none());
+
+        let entries = vec![entry("2026-10-17T09:10:00Z", 1.0)];
+        assert!(calculate_current_block(&entries, time("2026-10-17T14:00:00Z")).is_none());
+    }
+}
@@ -0,0 +1,243 @@
+//! @file year_summary.rs
+//! @description 年度回顾汇总服务
+//! @author Atlas.oi
+//! @date 2026-10-17
+use chrono::NaiveDate;
+
+use crate::db::{Repository, RepositoryError};
+use crate::models::{
+    BusiestDay, DailyActivity, ModelDailyUsage, MonthlyUsage, StatsCache, StatsFilters, YearSummary,
+};
+use crate::services::pricing::PricingService;
+use crate::services::streaks::calculate_streaks;
+
+/// 计算指定年份的年度回顾
+///
+/// 业务逻辑说明：
+/// 1. 统计周期为 1 月 1 日至 12 月 31 日（本地日期）
+/// 2. 总量与模型用量取自日期范围汇总，逐日活动用于最忙一天、连续天数与逐月序列
+/// 3. 缓存节省按各模型逐日用量与当天有效的价格计算，未知模型不计入
+pub fn build_year_summary(
+    repository: &Repository,
+    pricing: &PricingService,
+    year: i32,
+) -> Result<YearSummary, RepositoryError> {
+    let (Some(start), Some(end)) = (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Ok(summarize_year(
+            year,
+            &StatsCache::default(),
+            &[],
+            &[],
+            pricing,
+        ));
+    };
+
+    let stats = repository.get_range_stats(
+        &start.to_string(),
+        &end.to_string(),
+        &StatsFilters::default(),
+    )?;
+    let activities = repository.get_daily_activities(&start.to_string(), &end.to_string())?;
+    let model_days = repository.get_model_daily_usage(&start.to_string(), &end.to_string())?;
+
+    Ok(summarize_year(
+        year,
+        &stats,
+        &activities,
+        &model_days,
+        pricing,
+    ))
+}
+
+/// 根据年度汇总、逐日活动与模型逐日用量生成年度回顾
+pub fn summarize_year(
+    year: i32,
+    stats: &StatsCache,
+    activities: &[DailyActivity],
+    model_days: &[ModelDailyUsage],
+    pricing: &PricingService,
+) -> YearSummary {
+    let busiest_day = activities
+        .iter()
+        .filter(|activity| activity.message_count > 0)
+        .max_by_key(|activity| activity.total_tokens())
+        .map(|activity| BusiestDay {
+            date: activity.date.clone(),
+            total_tokens: activity.total_tokens(),
+            cost_usd: activity.cost_usd,
+            message_count: activity.message_count,
+        });
+
+    let favorite_model = stats
+        .models
+        .iter()
+        .filter(|usage| usage.message_count > 0)
+        .max_by_key(|usage| usage.message_count)
+        .map(|usage| usage.model.clone());
+
+    let cache_savings_usd = model_days
+        .iter()
+        .filter_map(|usage| {
+            let date = NaiveDate::parse_from_str(&usage.date, "%Y-%m-%d").ok()?;
+            Some(pricing.cache_savings(&usage.model, date, usage.cache_read_tokens))
+        })
+        .sum();
+
+    // 连续天数只使用最长区间，today 取年末即可
+    let streaks = calculate_streaks(
+        activities,
+        NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or_default(),
+    );
+
+    let mut months: Vec<MonthlyUsage> = (1..=12)
+        .map(|month| MonthlyUsage {
+            month: format!("{:04}-{:02}", year, month),
+            input_tokens: 0,
+            output_tokens: 0,
+            cost_usd: 0.0,
+            message_count: 0,
+            active_days: 0,
+        })
+        .collect();
+    for activity in activities {
+        let Some(month) = months
+            .iter_mut()
+            .find(|month| activity.date.starts_with(&month.month))
+        else {
+            continue;
+        };
+        month.input_tokens += activity.input_tokens;
+        month.output_tokens += activity.output_tokens;
+        month.cost_usd += activity.cost_usd;
+        month.message_count += activity.message_count;
+        if activity.message_count > 0 {
+            month.active_days += 1;
+        }
+    }
+
+    YearSummary {
+        year,
+        input_tokens: stats.total_input_tokens,
+        output_tokens: stats.total_output_tokens,
+        cache_read_tokens: stats.total_cache_read_tokens,
+        cache_creation_tokens: stats.total_cache_creation_tokens,
+        cost_usd: stats.total_cost_usd,
+        api_equivalent_cost_usd: stats.total_api_equivalent_cost_usd,
+        session_count: stats.total_sessions,
+        message_count: stats.total_messages,
+        active_days: streaks.total_active_days,
+        busiest_day,
+        favorite_model,
+        longest_streak_days: streaks.longest_streak_days,
+        longest_streak_start: streaks.longest_streak_start,
+        longest_streak_end: streaks.longest_streak_end,
+        cache_savings_usd,
+        months,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ModelUsage;
+
+    fn activity(date: &str, input_tokens: i64, cost_usd: f64) -> DailyActivity {
+        DailyActivity {
+            date: date.to_string(),
+            input_tokens,
+            output_tokens: 0,
+            cost_usd,
+            session_count: 1,
+            message_count: 1,
+            note: None,
+            api_equivalent_cost_usd: cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_summarize_year() {
+        let stats = StatsCache {
+            total_messages: 4,
+            models: vec![
+                ModelUsage {
+                    model: "claude-3-opus".to_string(),
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    cache_read_tokens: 1_000_000,
+                    cache_creation_tokens: 0,
+                    cost_usd: 1.5,
+                    message_count: 1,
+                    thinking_tokens: 0,
+                },
+                ModelUsage {
+                    model: "claude-3-sonnet".to_string(),
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    cache_read_tokens: 0,
+                    cache_creation_tokens: 0,
+                    cost_usd: 0.0,
+                    message_count: 3,
+                    thinking_tokens: 0,
+                },
+            ],
+            ..Default::default()
+        };
+        let activities = vec![
+            activity("2026-01-30", 100, 1.0),
+            activity("2026-01-31", 500, 2.0),
+            activity("2026-02-01", 200, 3.0),
+            activity("2026-03-10", 50, 0.5),
+        ];
+
+        let model_days = vec![ModelDailyUsage {
+            date: "2026-03-10".to_string(),
+            model: "claude-3-opus".to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 1_000_000,
+            cache_creation_tokens: 0,
+            cost_usd: 1.5,
+            message_count: 1,
+        }];
+
+        let summary = summarize_year(
+            2026,
+            &stats,
+            &activities,
+            &model_days,
+            &PricingService::new(),
+        );
+
+        assert_eq!(summary.favorite_model.as_deref(), Some("claude-3-sonnet"));
+        assert_eq!(
+            summary.busiest_day.as_ref().map(|day| day.date.as_str()),
+            Some("2026-01-31")
+        );
+        assert_eq!(summary.longest_streak_days, 3);
+        assert_eq!(summary.longest_streak_start.as_deref(), Some("2026-01-30"));
+        assert_eq!(summary.active_days, 4);
+        // opus 输入 15 美元 / 百万，缓存读取 1.5 美元 / 百万
+        assert!((summary.cache_savings_usd - 13.5).abs() < 1e-9);
+
+        assert_eq!(summary.months.len(), 12);
+        assert_eq!(summary.months[0].month, "2026-01");
+        assert_eq!(summary.months[0].input_tokens, 600);
+        assert_eq!(summary.months[0].active_days, 2);
+        assert!((summary.months[1].cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(summary.months[11].message_count, 0);
+    }
+
+    #[test]
+    fn test_build_year_summary_empty() {
+        let repo = Repository::new_in_memory().expect("repo");
+        let summary = build_year_summary(&repo, &PricingService::new(), 2026).expect("summary");
+
+        assert_eq!(summary.message_count, 0);
+        assert!(summary.busiest_day.is_none());
+        assert!(summary.favorite_model.is_none());
+        assert_eq!(summary.months.len(), 12);
+    }
+}
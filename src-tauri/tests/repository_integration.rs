@@ -19,6 +19,7 @@ fn test_repository_end_to_end_flow() {
             output_tokens: 10,
             cache_read_tokens: 0,
             cache_creation_tokens: 0,
+            thinking_tokens: 0,
             cost_usd: 0.2,
         },
     );
@@ -5,13 +5,16 @@ import { Dashboard } from './pages/Dashboard';
 import { Providers } from './pages/Providers';
 import { Settings } from './pages/Settings';
 import { Logs } from './pages/Logs';
+import { Overlay } from './pages/Overlay';
 import { useAppStore } from './store';
 import { useResponsiveScale } from './hooks/useResponsiveScale';
 import { useTauriEvents } from './hooks/useTauriEvents';
+import { useAppLockActivity } from './hooks/useAppLockActivity';
 import './index.css';
 
 function AppContent() {
   useResponsiveScale(1280, 16);
+  useAppLockActivity();
   const { fetchStats } = useAppStore();
 
   useEffect(() => {
@@ -43,7 +46,11 @@ function AppContent() {
 function App() {
   return (
     <Router>
-      <AppContent />
+      <Routes>
+        {/* 迷你悬浮窗独立渲染，不加载主布局 */}
+        <Route path="/overlay" element={<Overlay />} />
+        <Route path="/*" element={<AppContent />} />
+      </Routes>
     </Router>
   );
 }
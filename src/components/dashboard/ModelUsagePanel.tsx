@@ -30,6 +30,11 @@ export function ModelUsagePanel() {
       header: '输出 (Output)',
       render: (m: ModelUsage) => formatCompactNumber(m.output_tokens),
     },
+    {
+      header: '其中思考',
+      render: (m: ModelUsage) => formatCompactNumber(m.thinking_tokens ?? 0),
+      className: 'text-secondary',
+    },
     {
       header: '缓存读取',
       render: (m: ModelUsage) => formatCompactNumber(m.cache_read_tokens),
@@ -0,0 +1,37 @@
+/**
+ * @file useAppLockActivity.ts
+ * @description 向后端上报用户操作，应用锁据此计算空闲时间
+ * @author Atlas.oi
+ * @date 2026-10-17
+ */
+
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+
+/** 两次上报的最小间隔，避免频繁调用 */
+const REPORT_INTERVAL_MS = 30_000;
+
+const ACTIVITY_EVENTS = ['pointerdown', 'keydown', 'wheel'] as const;
+
+/**
+ * 监听键盘、鼠标操作并节流上报
+ * 自动刷新等非用户触发的命令不计入活动，避免应用锁永不超时
+ */
+export function useAppLockActivity() {
+  useEffect(() => {
+    let lastReport = 0;
+    const report = () => {
+      const now = Date.now();
+      if (now - lastReport < REPORT_INTERVAL_MS) return;
+      lastReport = now;
+      invoke('report_activity').catch((error) => {
+        console.warn('上报用户操作失败:', error);
+      });
+    };
+
+    ACTIVITY_EVENTS.forEach((name) => window.addEventListener(name, report, { passive: true }));
+    return () => {
+      ACTIVITY_EVENTS.forEach((name) => window.removeEventListener(name, report));
+    };
+  }, []);
+}
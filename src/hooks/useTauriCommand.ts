@@ -12,11 +12,14 @@ import type {
   DeleteProviderArgs,
   GetDailyActivitiesArgs,
   GetProvidersArgs,
+  GetStatsArgs,
   Provider,
+  ProviderBalance,
   ProviderStats,
   StatsCache,
   TodayStats,
-  UpdateProviderNameArgs
+  UpdateProviderNameArgs,
+  UsageBlock
 } from '@/types/tauri';
 
 export async function invokeCommand<T>(
@@ -54,12 +57,19 @@ function assertDailyActivitiesArgs(args: GetDailyActivitiesArgs) {
 }
 
 export const tauriCommands = {
-  getCurrentStats: () => invokeCommand<StatsCache>('get_current_stats'),
+  getCurrentStats: (args?: GetStatsArgs) =>
+    invokeCommand<StatsCache>('get_current_stats', { ...args }),
 
   getTodayProviderStats: () =>
     invokeCommand<ProviderStats[]>('get_today_provider_stats'),
 
-  getTodayStats: () => invokeCommand<TodayStats>('get_today_stats'),
+  getProviderBalances: () =>
+    invokeCommand<ProviderBalance[]>('get_provider_balances'),
+
+  getTodayStats: (args?: GetStatsArgs) =>
+    invokeCommand<TodayStats>('get_today_stats', { ...args }),
+
+  getUsageBlock: () => invokeCommand<UsageBlock | null>('get_usage_block'),
 
   getDailyActivities: (args: GetDailyActivitiesArgs) => {
     assertDailyActivitiesArgs(args);
@@ -7,12 +7,14 @@
 
 import { useEffect } from 'react';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
-import type { Provider, StatsCache } from '@/types/tauri';
+import type { Provider, ProviderBalance, StatsCache, TriggeredAlert } from '@/types/tauri';
 
 export interface TauriEventHandlers {
   onStatsUpdated?: (payload: StatsCache) => void;
   onProviderSwitched?: (payload: Provider) => void;
   onFileChanged?: (paths: string[]) => void;
+  onProviderBalanceUpdated?: (payload: ProviderBalance) => void;
+  onAlertTriggered?: (payload: TriggeredAlert) => void;
 }
 
 /**
@@ -51,6 +53,16 @@ export function useTauriEvents(handlers: TauriEventHandlers) {
           handlers.onFileChanged?.(event.payload);
         });
         if (!isCleanedUp) unlisteners.push(unlistenFile);
+
+        const unlistenBalance = await listen<ProviderBalance>('provider-balance-updated', (event) => {
+          handlers.onProviderBalanceUpdated?.(event.payload);
+        });
+        if (!isCleanedUp) unlisteners.push(unlistenBalance);
+
+        const unlistenAlert = await listen<TriggeredAlert>('alert-triggered', (event) => {
+          handlers.onAlertTriggered?.(event.payload);
+        });
+        if (!isCleanedUp) unlisteners.push(unlistenAlert);
       } catch (error) {
         // Tauri 事件监听器设置失败，记录错误但不阻断应用
         console.error('Tauri 事件监听器设置失败:', error);
@@ -0,0 +1,67 @@
+/**
+ * @file Overlay.tsx
+ * @description 迷你悬浮窗页面，展示今日费用与当前 5 小时区块剩余时间
+ * @author Atlas.oi
+ * @date 2026-10-17
+ */
+
+import { useCallback, useEffect, useMemo, useState } from 'react';
+import { tauriCommands } from '../hooks/useTauriCommand';
+import { useTauriEvents } from '../hooks/useTauriEvents';
+import type { TodayStats, UsageBlock } from '../types/tauri';
+
+/** 区块剩余时间刷新间隔（毫秒） */
+const REFRESH_INTERVAL_MS = 60_000;
+
+function formatRemaining(minutes: number): string {
+  const safeMinutes = Math.max(0, minutes);
+  const hours = Math.floor(safeMinutes / 60);
+  return hours > 0 ? `${hours}h${String(safeMinutes % 60).padStart(2, '0')}m` : `${safeMinutes}m`;
+}
+
+export function Overlay() {
+  const [todayStats, setTodayStats] = useState<TodayStats | null>(null);
+  const [block, setBlock] = useState<UsageBlock | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      const [today, currentBlock] = await Promise.all([
+        tauriCommands.getTodayStats(),
+        tauriCommands.getUsageBlock()
+      ]);
+      setTodayStats(today);
+      setBlock(currentBlock);
+    } catch (error) {
+      console.error('悬浮窗数据刷新失败:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const timer = window.setInterval(refresh, REFRESH_INTERVAL_MS);
+    return () => window.clearInterval(timer);
+  }, [refresh]);
+
+  const handlers = useMemo(() => ({ onStatsUpdated: () => refresh() }), [refresh]);
+  useTauriEvents(handlers);
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="h-screen w-screen bento-card px-4 py-3 flex flex-col justify-between select-none cursor-move"
+    >
+      <div data-tauri-drag-region className="flex justify-between items-baseline">
+        <span className="font-mono text-[10px] uppercase tracking-wider text-secondary">今日费用</span>
+        <span className="text-2xl font-display font-medium text-primary">
+          ${(todayStats?.cost_usd ?? 0).toFixed(2)}
+        </span>
+      </div>
+      <div data-tauri-drag-region className="flex justify-between items-baseline">
+        <span className="font-mono text-[10px] uppercase tracking-wider text-secondary">区块剩余</span>
+        <span className="text-lg font-mono text-neonPrimary">
+          {block ? formatRemaining(block.remaining_minutes) : '无活跃区块'}
+        </span>
+      </div>
+    </div>
+  );
+}
@@ -5,7 +5,7 @@
  * @date 2026-01-10
  */
 
-import { useEffect, useState } from 'react';
+import { useEffect, useMemo, useState } from 'react';
 import { Check, MoreHorizontal, Key, Calendar, DollarSign, Zap, Database, Edit2, Save, X, Plus, Trash2, Copy, Info } from 'lucide-react';
 import { cn, formatCompactNumber } from '../lib/utils';
 import { useAppStore } from '../store';
@@ -13,6 +13,9 @@ import { formatDistanceToNow } from 'date-fns';
 import { zhCN } from 'date-fns/locale';
 import { ErrorMessage } from '../components/common/Feedback';
 import { Dialog, ConfirmDialog, AlertDialog } from '../components/common/Dialog';
+import { tauriCommands } from '../hooks/useTauriCommand';
+import { useTauriEvents } from '../hooks/useTauriEvents';
+import type { ProviderBalance } from '../types/tauri';
 
 export function Providers() {
   const { providerStats, activeProviderId, updateProviderName, addProvider, deleteProvider, error, setError } = useAppStore();
@@ -33,6 +36,21 @@ export function Providers() {
   const [alertState, setAlertState] = useState<{ open: boolean; title: string; message: string }>({ open: false, title: '', message: '' });
   const [copySuccess, setCopySuccess] = useState(false);
   const [detailProviderId, setDetailProviderId] = useState<number | null>(null);
+  const [balances, setBalances] = useState<Record<number, ProviderBalance>>({});
+
+  // 中转站余额：首次加载全部，之后随后台定时刷新事件更新
+  useEffect(() => {
+    tauriCommands
+      .getProviderBalances()
+      .then((list) => setBalances(Object.fromEntries(list.map((b) => [b.provider_id, b]))))
+      .catch((e) => console.error('获取供应商余额失败:', e));
+  }, []);
+
+  const balanceHandlers = useMemo(() => ({
+    onProviderBalanceUpdated: (balance: ProviderBalance) =>
+      setBalances((prev) => ({ ...prev, [balance.provider_id]: balance })),
+  }), []);
+  useTauriEvents(balanceHandlers);
 
   useEffect(() => {
     if (menuOpenId === null) return;
@@ -179,6 +197,7 @@ export function Providers() {
           const isEditing = editingId === stat.provider.id;
           const isActive = stat.provider.id === activeProviderId;
           const isMenuOpen = menuOpenId === stat.provider.id;
+          const remainingUsd = balances[stat.provider.id]?.remaining_usd;
 
           return (
             <div 
@@ -331,6 +350,15 @@ export function Providers() {
                   </span>
                 </div>
               </div>
+
+              {remainingUsd != null && (
+                <div className="flex items-center justify-between text-[10px] text-secondary">
+                  <span>剩余额度</span>
+                  <span className="font-mono text-sm text-primary">
+                    ${remainingUsd.toFixed(2)}
+                  </span>
+                </div>
+              )}
             </div>
           );
         })}
@@ -13,6 +13,8 @@ export interface ModelUsage {
   cache_creation_tokens: number;
   cost_usd: number;
   message_count: number;
+  /** 思考 Token，已包含在 output_tokens 中 */
+  thinking_tokens: number;
 }
 
 export interface StatsCache {
@@ -21,6 +23,7 @@ export interface StatsCache {
   total_cache_read_tokens: number;
   total_cache_creation_tokens: number;
   total_cost_usd: number;
+  total_api_equivalent_cost_usd: number;
   total_sessions: number;
   total_messages: number;
   cache_hit_rate: number;
@@ -37,6 +40,7 @@ export interface TodayStats {
   session_count: number;
   message_count: number;
   cache_hit_rate: number;
+  api_equivalent_cost_usd: number;
 }
 
 export interface DailyActivity {
@@ -46,6 +50,8 @@ export interface DailyActivity {
   cost_usd: number;
   session_count: number;
   message_count: number;
+  note?: string | null;
+  api_equivalent_cost_usd: number;
 }
 
 export interface Provider {
@@ -57,6 +63,7 @@ export interface Provider {
   is_active: boolean;
   first_seen_at: string;
   last_seen_at: string;
+  kind: 'api_key' | 'subscription';
 }
 
 export interface ProviderStats {
@@ -69,13 +76,111 @@ export interface ProviderStats {
   cache_hit_rate: number;
 }
 
+/**
+ * 中转站余额（美元），由 set_provider_quota 配置的余额接口定时刷新
+ */
+export interface ProviderBalance {
+  provider_id: number;
+  quota_url: string;
+  refresh_minutes: number;
+  remaining_usd?: number | null;
+  used_usd?: number | null;
+  total_usd?: number | null;
+  fetched_at?: string | null;
+  error?: string | null;
+  updated_at: string;
+}
+
+/**
+ * 5 小时使用区块
+ */
+export interface UsageBlock {
+  start_at: string;
+  end_at: string;
+  last_message_at: string;
+  remaining_minutes: number;
+  total_tokens: number;
+  cost_usd: number;
+  message_count: number;
+}
+
+/**
+ * 告警规则
+ */
+export type AlertMetric = 'cost_usd' | 'total_tokens' | 'message_count' | 'session_count';
+export type AlertComparator = 'gt' | 'gte' | 'lt' | 'lte';
+export type AlertWindow = 'hour' | 'day' | 'week' | 'month';
+export type AlertChannel = 'notification' | 'in_app';
+
+export interface AlertRule {
+  id: number;
+  name: string;
+  metric: AlertMetric;
+  comparator: AlertComparator;
+  threshold: number;
+  window: AlertWindow;
+  channel: AlertChannel;
+  enabled: boolean;
+  snoozed_until?: string | null;
+  breached_since?: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+/**
+ * 已触发的告警记录
+ */
+export interface TriggeredAlert {
+  id: number;
+  rule_id: number;
+  rule_name: string;
+  metric: AlertMetric;
+  value: number;
+  threshold: number;
+  channel: AlertChannel;
+  message: string;
+  triggered_at: string;
+  acknowledged_at?: string | null;
+}
+
+/**
+ * 通知历史
+ */
+export type NotificationKind =
+  | 'cost_anomaly'
+  | 'block_warning'
+  | 'alert'
+  | 'report'
+  | 'context_warning';
+
+export interface NotificationRecord {
+  id: number;
+  kind: NotificationKind;
+  title: string;
+  body: string;
+  payload: unknown;
+  channel: 'system' | 'in_app';
+  sent_at: string;
+}
+
 /**
  * Tauri 命令参数类型
  * 注意：参数名使用 camelCase，Rust 端通过 #[tauri::command(rename_all = "camelCase")] 处理转换
  */
+export interface StatsFilters {
+  providerId?: number | null;
+  model?: string | null;
+  project?: string | null;
+}
+
+export interface GetStatsArgs {
+  filters?: StatsFilters;
+}
+
 export interface GetDailyActivitiesArgs {
   startDate: string;
   endDate: string;
+  filters?: StatsFilters;
 }
 
 export interface AddProviderArgs {